@@ -4,6 +4,7 @@ import fs from 'node:fs/promises';
 import path from 'node:path';
 import { createStageTracker, recordProjectTelemetry } from './lib/pipeline_telemetry.mjs';
 import {
+  RESULT_SCHEMA_VERSION,
   validateAssetSuggestions,
   validateTemplatePlacements,
   validateTemplatePlan,
@@ -1039,6 +1040,7 @@ async function resolveExternalMediaForAssets(
         media: {
           status: 'cached',
           localPath: existing.localPath,
+          sourceUrl: existing.sourceUrl || '',
           attribution: existing.attribution || null,
           license: existing.license || '',
           providerAssetId: existing.providerAssetId || '',
@@ -1111,6 +1113,7 @@ async function resolveExternalMediaForAssets(
 
       cacheIndex[key] = {
         localPath,
+        sourceUrl: providerAsset.downloadUrl,
         providerAssetId: providerAsset.providerAssetId,
         license: providerAsset.license,
         attribution,
@@ -1122,6 +1125,7 @@ async function resolveExternalMediaForAssets(
         media: {
           status: 'downloaded',
           localPath,
+          sourceUrl: providerAsset.downloadUrl,
           attribution,
           license: providerAsset.license,
           providerAssetId: providerAsset.providerAssetId,
@@ -1513,6 +1517,7 @@ async function main() {
     process.stdout.write(
       `${JSON.stringify(
         {
+          schemaVersion: RESULT_SCHEMA_VERSION,
           ok: true,
           projectId,
           templatePlanPath,
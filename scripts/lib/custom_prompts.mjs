@@ -24,6 +24,21 @@ const PROMPTS_PATH = path.join(ROOT_DIR, 'desktop', 'data', 'custom_prompts.json
 
 let _cached = null;
 
+/**
+ * Per-project prompts the desktop app passes from project.config.json as
+ * `--prompt-overrides <json>`. They win over the app-wide file.
+ */
+function promptOverridesFromArgs() {
+    const idx = process.argv.indexOf('--prompt-overrides');
+    if (idx === -1) return {};
+    try {
+        const parsed = JSON.parse(process.argv[idx + 1] ?? '{}');
+        return parsed && typeof parsed === 'object' ? parsed : {};
+    } catch {
+        return {};
+    }
+}
+
 /**
  * Load all custom prompts from disk.
  * Returns { [stageKey]: string } map.
@@ -33,10 +48,10 @@ export async function loadCustomPrompts() {
     try {
         const raw = await fs.readFile(PROMPTS_PATH, 'utf8');
         const data = JSON.parse(raw);
-        _cached = data.prompts || {};
+        _cached = { ...(data.prompts || {}), ...promptOverridesFromArgs() };
         return _cached;
     } catch {
-        _cached = {};
+        _cached = promptOverridesFromArgs();
         return _cached;
     }
 }
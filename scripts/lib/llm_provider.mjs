@@ -264,6 +264,25 @@ async function runCodex(model, prompt, timeoutMs) {
     throw lastError;
 }
 
+/**
+ * `--llm-params <json>` from project.config.json: { temperature, maxTokens }.
+ * Unset values keep the defaults below.
+ */
+function readLLMParams() {
+    const idx = process.argv.indexOf('--llm-params');
+    if (idx === -1) return {};
+    try {
+        const parsed = JSON.parse(process.argv[idx + 1] ?? '{}');
+        return parsed && typeof parsed === 'object' ? parsed : {};
+    } catch {
+        return {};
+    }
+}
+
+const LLM_PARAMS = readLLMParams();
+const TEMPERATURE = LLM_PARAMS.temperature ?? 0.3;
+const MAX_TOKENS = LLM_PARAMS.maxTokens ?? 4096;
+
 async function runOllama(model, prompt, timeoutMs) {
     const controller = new AbortController();
     const timeout = setTimeout(() => controller.abort(), timeoutMs);
@@ -278,7 +297,8 @@ async function runOllama(model, prompt, timeoutMs) {
                 stream: false,
                 options: {
                     num_ctx: 8192, // Increased context window
-                    temperature: 0.3
+                    temperature: TEMPERATURE,
+                    ...(LLM_PARAMS.maxTokens ? { num_predict: MAX_TOKENS } : {}),
                 }
             }),
             signal: controller.signal,
@@ -323,8 +343,8 @@ async function runOpenAI(model, prompt, timeoutMs) {
             body: JSON.stringify({
                 model,
                 messages: [{ role: 'user', content: prompt }],
-                temperature: 0.3,
-                max_tokens: 4096,
+                temperature: TEMPERATURE,
+                max_tokens: MAX_TOKENS,
             }),
             signal: controller.signal,
         });
@@ -355,7 +375,7 @@ async function runGoogle(model, prompt, timeoutMs) {
             headers: { 'Content-Type': 'application/json' },
             body: JSON.stringify({
                 contents: [{ parts: [{ text: prompt }] }],
-                generationConfig: { temperature: 0.3, maxOutputTokens: 4096 },
+                generationConfig: { temperature: TEMPERATURE, maxOutputTokens: MAX_TOKENS },
             }),
             signal: controller.signal,
         });
@@ -389,7 +409,8 @@ async function runAnthropic(model, prompt, timeoutMs) {
             },
             body: JSON.stringify({
                 model,
-                max_tokens: 4096,
+                max_tokens: MAX_TOKENS,
+                ...(LLM_PARAMS.temperature !== undefined ? { temperature: TEMPERATURE } : {}),
                 messages: [{ role: 'user', content: prompt }],
             }),
             signal: controller.signal,
@@ -424,8 +445,8 @@ async function runSarvam(model, prompt, timeoutMs) {
             body: JSON.stringify({
                 model: model || 'sarvam-m',
                 messages: [{ role: 'user', content: prompt }],
-                max_tokens: 4096,
-                temperature: 0.3,
+                max_tokens: MAX_TOKENS,
+                temperature: TEMPERATURE,
             }),
             signal: controller.signal,
         });
@@ -51,6 +51,92 @@ export async function detectHWAccel() {
     return _hwAccelCache;
 }
 
+// ── Encoder Selection ────────────────────────────────────────────────────────
+
+/**
+ * Per-encoder quality args. Values are tuned so each quality level lands
+ * near the libx264 CRF of the same name.
+ */
+const HW_ENCODER_ARGS = {
+    h264_videotoolbox: (q) => ['-q:v', String({ fast: 65, balanced: 55, high: 45, lossless: 35 }[q] ?? 55)],
+    hevc_videotoolbox: (q) => ['-q:v', String({ fast: 65, balanced: 55, high: 45, lossless: 35 }[q] ?? 55), '-tag:v', 'hvc1'],
+    h264_nvenc: (q) => ['-preset', 'p4', '-rc', 'vbr', '-cq', String({ fast: 28, balanced: 23, high: 19, lossless: 15 }[q] ?? 23)],
+    hevc_nvenc: (q) => ['-preset', 'p4', '-rc', 'vbr', '-cq', String({ fast: 30, balanced: 25, high: 21, lossless: 17 }[q] ?? 25), '-tag:v', 'hvc1'],
+    h264_qsv: (q) => ['-preset', 'medium', '-global_quality', String({ fast: 28, balanced: 23, high: 19, lossless: 15 }[q] ?? 23)],
+    hevc_qsv: (q) => ['-preset', 'medium', '-global_quality', String({ fast: 30, balanced: 25, high: 21, lossless: 17 }[q] ?? 25), '-tag:v', 'hvc1'],
+    h264_amf: (q) => {
+        const qp = String({ fast: 28, balanced: 23, high: 19, lossless: 15 }[q] ?? 23);
+        return ['-quality', 'balanced', '-rc', 'cqp', '-qp_i', qp, '-qp_p', qp];
+    },
+    hevc_amf: (q) => {
+        const qp = String({ fast: 30, balanced: 25, high: 21, lossless: 17 }[q] ?? 25);
+        return ['-quality', 'balanced', '-rc', 'cqp', '-qp_i', qp, '-qp_p', qp, '-tag:v', 'hvc1'];
+    },
+};
+
+/** Options (with a value) that the video encoder builders emit. */
+const ENCODER_OPTION_KEYS = new Set([
+    '-c:v', '-preset', '-crf', '-q:v', '-tag:v', '-rc', '-cq', '-global_quality', '-quality', '-qp_i', '-qp_p',
+]);
+
+// 'auto' keeps the VideoToolbox-on-Apple-Silicon default.
+let _preferredEncoder = 'auto';
+// Quality of the last encode, reused when a hardware encode is retried.
+let _lastQuality = 'balanced';
+
+/**
+ * Pins the video encoder for this process: 'auto', 'libx264', or one of the
+ * hardware encoders above. Unknown names fall back to 'auto'.
+ */
+export function setPreferredVideoEncoder(name) {
+    const value = String(name || 'auto').trim();
+    _preferredEncoder = value === 'libx264' || HW_ENCODER_ARGS[value] ? value : 'auto';
+    return _preferredEncoder;
+}
+
+export function preferredVideoEncoder() {
+    return _preferredEncoder;
+}
+
+export function isHardwareEncoder(name) {
+    return Boolean(HW_ENCODER_ARGS[name]);
+}
+
+function softwareEncodeArgs(quality, pixFmt) {
+    const presetMap = { fast: 'fast', balanced: 'medium', high: 'slow', lossless: 'veryslow' };
+    const crfMap = { fast: 28, balanced: 23, high: 18, lossless: 14 };
+    return [
+        '-c:v', 'libx264',
+        '-preset', presetMap[quality] ?? 'medium',
+        '-crf', String(crfMap[quality] ?? 23),
+        '-pix_fmt', pixFmt,
+    ];
+}
+
+/**
+ * If `args` encode with a hardware encoder, returns the same command with
+ * the encoder options swapped for libx264; otherwise null.
+ */
+export function softwareFallbackArgs(args, { quality = _lastQuality } = {}) {
+    const codecIndex = args.indexOf('-c:v');
+    if (codecIndex === -1 || !isHardwareEncoder(args[codecIndex + 1])) return null;
+    const stripped = [];
+    let insertAt = -1;
+    for (let i = 0; i < args.length; i += 1) {
+        if (ENCODER_OPTION_KEYS.has(args[i])) {
+            if (insertAt === -1) insertAt = stripped.length;
+            i += 1;
+            continue;
+        }
+        stripped.push(args[i]);
+    }
+    const hasPixFmt = stripped.includes('-pix_fmt');
+    const software = softwareEncodeArgs(quality, 'yuv420p');
+    const replacement = hasPixFmt ? software.slice(0, -2) : software;
+    stripped.splice(insertAt, 0, ...replacement);
+    return stripped;
+}
+
 // ── ffmpeg Argument Builders ─────────────────────────────────────────────────
 
 /**
@@ -71,29 +157,23 @@ export async function hwDecodeArgs() {
  * Falls back to libx264 if VideoToolbox unavailable.
  */
 export async function hwEncodeVideoArgs({ quality = 'balanced', pixFmt = 'yuv420p' } = {}) {
+    _lastQuality = quality;
+    if (_preferredEncoder === 'libx264') {
+        return softwareEncodeArgs(quality, pixFmt);
+    }
+    if (HW_ENCODER_ARGS[_preferredEncoder]) {
+        return ['-c:v', _preferredEncoder, ...HW_ENCODER_ARGS[_preferredEncoder](quality), '-pix_fmt', pixFmt];
+    }
+
     const hw = await detectHWAccel();
 
     if (hw.videotoolbox) {
         // VideoToolbox quality: lower = better (0-100, ~50 is visually lossless)
-        const qMap = { fast: 65, balanced: 55, high: 45, lossless: 35 };
-        const q = qMap[quality] ?? 55;
-        return [
-            '-c:v', 'hevc_videotoolbox',
-            '-q:v', String(q),
-            '-tag:v', 'hvc1',        // Apple-compatible HEVC tag
-            '-pix_fmt', pixFmt,
-        ];
+        return ['-c:v', 'hevc_videotoolbox', ...HW_ENCODER_ARGS.hevc_videotoolbox(quality), '-pix_fmt', pixFmt];
     }
 
     // CPU fallback
-    const presetMap = { fast: 'fast', balanced: 'medium', high: 'slow', lossless: 'veryslow' };
-    const crfMap = { fast: 28, balanced: 23, high: 18, lossless: 14 };
-    return [
-        '-c:v', 'libx264',
-        '-preset', presetMap[quality] ?? 'medium',
-        '-crf', String(crfMap[quality] ?? 23),
-        '-pix_fmt', pixFmt,
-    ];
+    return softwareEncodeArgs(quality, pixFmt);
 }
 
 /**
@@ -1,5 +1,10 @@
 import { z } from 'zod';
 
+// The `schemaVersion` of the start-editing and edit-now results. The desktop
+// app (src-tauri/src/pipeline_result.rs) rejects versions newer than it knows,
+// so bump both together when a result field changes shape.
+export const RESULT_SCHEMA_VERSION = 1;
+
 const microsecondSchema = z.number().int().nonnegative();
 const confidenceSchema = z.number().min(0).max(1);
 
@@ -4,6 +4,7 @@ import fs from 'node:fs/promises';
 import path from 'node:path';
 import { execFile as execFileCb } from 'node:child_process';
 import { promisify } from 'node:util';
+import { createHash } from 'node:crypto';
 import { fileURLToPath } from 'node:url';
 import { hwDecodeArgs, hwEncodeVideoArgs, hwEncodeAudioArgs } from './lib/metal_accel.mjs';
 
@@ -63,7 +64,9 @@ async function probeMedia(inputPath) {
   const streams = payload.streams ?? [];
   const format = payload.format ?? {};
 
-  const video = streams.find((stream) => stream.codec_type === 'video') ?? null;
+  // Cover art in music files shows up as a one-frame video stream.
+  const video =
+    streams.find((stream) => stream.codec_type === 'video' && !stream.disposition?.attached_pic) ?? null;
   const audio = streams.find((stream) => stream.codec_type === 'audio') ?? null;
 
   return {
@@ -89,6 +92,32 @@ async function probeMedia(inputPath) {
   };
 }
 
+const IMAGE_EXTENSIONS = new Set(['.png', '.jpg', '.jpeg', '.webp', '.gif', '.bmp', '.tif', '.tiff']);
+const IMAGE_FORMATS = /^(image2|gif|webp_pipe|png_pipe|jpeg_pipe|bmp_pipe|tiff_pipe)$/;
+
+/** `video`, `audio` or `image`, from the probed streams and container. */
+function classifyMedia(inputPath, media) {
+  if (IMAGE_FORMATS.test(media.formatName) || IMAGE_EXTENSIONS.has(path.extname(inputPath).toLowerCase())) {
+    return media.video ? 'image' : 'unknown';
+  }
+  if (media.video) return 'video';
+  if (media.audio) return 'audio';
+  return 'unknown';
+}
+
+async function maybeGenerateThumbnail(inputPath, outputPath) {
+  try {
+    await run(
+      'ffmpeg',
+      ['-y', '-i', inputPath, '-vf', "scale='min(640,iw)':-2", '-frames:v', '1', outputPath],
+      2 * 60 * 1000,
+    );
+    return { ok: true, path: outputPath };
+  } catch (error) {
+    return { ok: false, path: '', error: String(error?.message ?? error) };
+  }
+}
+
 async function maybeGenerateProxy(inputPath, outputPath) {
   try {
     const decArgs = await hwDecodeArgs();
@@ -143,6 +172,9 @@ async function main() {
   const projectId = readArg('--project-id', 'default-project');
   const generateProxy = readArg('--generate-proxy', 'true') !== 'false';
   const generateWaveform = readArg('--generate-waveform', 'true') !== 'false';
+  // Set when the app stored the file in its media pool and passed that copy.
+  const contentHash = readArg('--content-hash');
+  const originalPath = readArg('--original-path');
 
   if (!input) {
     throw new Error('Missing required argument: --input <file>');
@@ -157,10 +189,19 @@ async function main() {
   const ffmpegExists = await commandExists('ffmpeg');
 
   const mediaMeta = await probeMedia(absInput);
+  const kind = classifyMedia(absInput, mediaMeta);
+  if (kind === 'unknown') {
+    throw new Error(`${path.basename(absInput)} has no audio, video or image stream to import.`);
+  }
   const projectDir = readArg('--project-dir') || path.resolve('desktop', 'data', projectId);
   const mediaDir = path.join(projectDir, 'media');
   await fs.mkdir(mediaDir, { recursive: true });
 
+  if (kind !== 'video') {
+    await ingestAsset({ kind, absInput, mediaMeta, projectId, mediaDir, ffmpegExists, generateWaveform, contentHash, originalPath });
+    return;
+  }
+
   // ── Run Input Quality Gate ───────────────────────────────────────────────
   let qualityReport = null;
   if (ffmpegExists) {
@@ -189,14 +230,17 @@ async function main() {
       ? await maybeGenerateProxy(absInput, proxyPath)
       : { ok: false, path: '', error: ffmpegExists ? 'Proxy generation disabled.' : 'ffmpeg not available.' };
 
-  const waveformResult =
-    ffmpegExists && generateWaveform
+  const waveformResult = !mediaMeta.audio
+    ? { ok: false, path: '', error: 'No audio stream.' }
+    : ffmpegExists && generateWaveform
       ? await maybeGenerateWaveform(absInput, waveformPath)
       : { ok: false, path: '', error: ffmpegExists ? 'Waveform generation disabled.' : 'ffmpeg not available.' };
 
   const payload = {
     projectId,
+    kind,
     sourcePath: absInput,
+    ...(contentHash ? { contentHash, originalPath: originalPath || absInput } : {}),
     ffmpegAvailable: ffmpegExists,
     ingestedAt: new Date().toISOString(),
     media: mediaMeta,
@@ -220,6 +264,56 @@ async function main() {
   );
 }
 
+/**
+ * Music beds, voiceover and stills are assets next to the project's main
+ * video, so they're listed in media/assets.json instead of replacing
+ * media/metadata.json. Audio gets a waveform, images a thumbnail; neither
+ * gets a proxy.
+ */
+async function ingestAsset({ kind, absInput, mediaMeta, projectId, mediaDir, ffmpegExists, generateWaveform, contentHash, originalPath }) {
+  const assetId = contentHash || createHash('sha256').update(absInput).digest('hex').slice(0, 16);
+  const assetsDir = path.join(mediaDir, 'assets');
+  await fs.mkdir(assetsDir, { recursive: true });
+  const unavailable = (reason) => ({ ok: false, path: '', error: reason });
+
+  let waveform = unavailable(`Not generated for ${kind} assets.`);
+  let thumbnail = unavailable(`Not generated for ${kind} assets.`);
+  if (!ffmpegExists) {
+    waveform = thumbnail = unavailable('ffmpeg not available.');
+  } else if (kind === 'audio') {
+    waveform = generateWaveform
+      ? await maybeGenerateWaveform(absInput, path.join(assetsDir, `${assetId}.waveform.png`))
+      : unavailable('Waveform generation disabled.');
+  } else {
+    thumbnail = await maybeGenerateThumbnail(absInput, path.join(assetsDir, `${assetId}.thumb.jpg`));
+  }
+
+  const asset = {
+    assetId,
+    kind,
+    name: path.basename(originalPath || absInput),
+    sourcePath: absInput,
+    ...(contentHash ? { contentHash, originalPath: originalPath || absInput } : {}),
+    ingestedAt: new Date().toISOString(),
+    media: mediaMeta,
+    waveform,
+    thumbnail,
+  };
+
+  const metadataPath = path.join(mediaDir, 'assets.json');
+  let index = { assets: [] };
+  try {
+    index = JSON.parse(await fs.readFile(metadataPath, 'utf8'));
+    if (!Array.isArray(index.assets)) index.assets = [];
+  } catch {
+    // First asset of the project.
+  }
+  index.assets = [...index.assets.filter((entry) => entry.assetId !== assetId), asset];
+  await fs.writeFile(metadataPath, `${JSON.stringify(index, null, 2)}\n`, 'utf8');
+
+  process.stdout.write(`${JSON.stringify({ ok: true, projectId, metadataPath, ...asset }, null, 2)}\n`);
+}
+
 main().catch((error) => {
   process.stderr.write(`${String(error?.message ?? error)}\n`);
   process.exit(1);
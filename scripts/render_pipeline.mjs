@@ -4,9 +4,17 @@ import fs from 'node:fs/promises';
 import os from 'node:os';
 import path from 'node:path';
 import { execFile as execFileCb } from 'node:child_process';
+import { createHash, randomUUID } from 'node:crypto';
 import { promisify } from 'node:util';
 import { createStageTracker, recordProjectTelemetry } from './lib/pipeline_telemetry.mjs';
-import { hwDecodeArgs, hwEncodeVideoArgs, hwEncodeAudioArgs } from './lib/metal_accel.mjs';
+import {
+  hwDecodeArgs,
+  hwEncodeVideoArgs,
+  hwEncodeAudioArgs,
+  preferredVideoEncoder,
+  setPreferredVideoEncoder,
+  softwareFallbackArgs,
+} from './lib/metal_accel.mjs';
 
 const execFile = promisify(execFileCb);
 
@@ -16,7 +24,7 @@ function readArg(flag, fallback = '') {
   return process.argv[idx + 1] ?? fallback;
 }
 
-async function run(command, args = [], timeout = 20 * 60 * 1000) {
+async function runOnce(command, args, timeout) {
   const { stdout, stderr } = await execFile(command, args, {
     timeout,
     maxBuffer: 1024 * 1024 * 12,
@@ -27,6 +35,42 @@ async function run(command, args = [], timeout = 20 * 60 * 1000) {
   };
 }
 
+// Output options from the project's project.config.json, added before the
+// output path of every ffmpeg call.
+const FFMPEG_OUTPUT_ARGS = (() => {
+  try {
+    const parsed = JSON.parse(readArg('--ffmpeg-output-args', '[]'));
+    return Array.isArray(parsed) ? parsed.map(String) : [];
+  } catch {
+    return [];
+  }
+})();
+
+function withOutputArgs(args) {
+  if (FFMPEG_OUTPUT_ARGS.length === 0 || args.length === 0) return args;
+  return [...args.slice(0, -1), ...FFMPEG_OUTPUT_ARGS, args[args.length - 1]];
+}
+
+// Hardware encodes that failed and were redone with libx264.
+const encoderFallbacks = [];
+
+async function run(command, args = [], timeout = 20 * 60 * 1000) {
+  if (command === 'ffmpeg') args = withOutputArgs(args);
+  try {
+    return await runOnce(command, args, timeout);
+  } catch (error) {
+    const fallbackArgs = command === 'ffmpeg' ? softwareFallbackArgs(args) : null;
+    if (!fallbackArgs) throw error;
+    const encoder = args[args.indexOf('-c:v') + 1];
+    const reason = String(error?.stderr || error?.message || error).trim().split('\n').pop();
+    encoderFallbacks.push({ encoder, error: reason.slice(0, 300) });
+    process.stderr.write(`[Render] ${encoder} encode failed, retrying with libx264: ${reason}\n`);
+    // Later stages go straight to software instead of failing again.
+    setPreferredVideoEncoder('libx264');
+    return runOnce(command, fallbackArgs, timeout);
+  }
+}
+
 async function commandExists(command) {
   try {
     const out = await run('which', [command], 8000);
@@ -67,7 +111,10 @@ async function appendRenderHistory(projectDir, record) {
   const current = await readJsonIfExists(historyPath, []);
   const list = Array.isArray(current) ? current : [];
   list.unshift(record);
-  await writeJson(historyPath, list.slice(0, 200));
+  // Pinned renders are managed from the desktop app and never aged out.
+  let unpinnedKept = 0;
+  const kept = list.filter((entry) => entry?.pinned || unpinnedKept++ < 200);
+  await writeJson(historyPath, kept);
   return historyPath;
 }
 
@@ -153,19 +200,25 @@ function collectSourceClips(timeline) {
   const clips = Array.isArray(timeline?.clips) ? timeline.clips : [];
   const sourceClips = clips
     .filter((clip) => clip && clip.clipType === 'source_clip')
-    .map((clip, index) => ({
-      id: String(clip.clipId || `source-${index + 1}`),
-      sourceRef: String(clip.sourceRef || ''),
-      sourceStartUs: Number(clip.sourceStartUs || 0),
-      sourceEndUs: Number(clip.sourceEndUs || 0),
-      startUs: Number(clip.startUs || 0),
-      endUs: Number(clip.endUs || 0),
-    }))
+    .flatMap((clip, index) =>
+      splitBySpeed({
+        id: String(clip.clipId || `source-${index + 1}`),
+        sourceRef: String(clip.sourceRef || ''),
+        sourceStartUs: Number(clip.sourceStartUs || 0),
+        sourceEndUs: Number(clip.sourceEndUs || 0),
+        startUs: Number(clip.startUs || 0),
+        endUs: Number(clip.endUs || 0),
+        crop: normalizeCrop(clip?.transform?.crop),
+        censors: normalizeCensors(clip?.effects?.censor),
+        lut: clip?.effects?.lut ? String(clip.effects.lut) : null,
+        audioPath: clip?.effects?.audioEnhance?.enabled ? String(clip.effects.audioEnhance.audioPath || '') || null : null,
+      }, clip),
+    )
     .filter((clip) => clip.sourceEndUs > clip.sourceStartUs)
     .sort((a, b) => a.startUs - b.startUs);
 
   if (sourceClips.length > 0) {
-    return mergeAdjacentClips(sourceClips);
+    return mergeAdjacentClips(sourceClips, undefined, normalizeCensors(timeline?.meta?.censoredCuts));
   }
 
   const durationUs = Number(timeline?.durationUs || 0);
@@ -181,16 +234,162 @@ function collectSourceClips(timeline) {
       sourceEndUs: durationUs,
       startUs: 0,
       endUs: durationUs,
+      crop: null,
+      speed: 1,
     },
   ];
 }
 
+/**
+ * Splits a clip at its speed keyframes. Keyframes hold their speed until the
+ * next one ({ sourceOffsetUs, speed }); before the first, `clip.speed` applies.
+ */
+function splitBySpeed(base, clip) {
+  const baseSpeed = Number(clip.speed) > 0 ? Number(clip.speed) : 1;
+  const keyframes = (Array.isArray(clip.speedKeyframes) ? clip.speedKeyframes : [])
+    .map((keyframe) => ({
+      offsetUs: Number(keyframe?.sourceOffsetUs || 0),
+      speed: Number(keyframe?.speed),
+    }))
+    .filter((keyframe) => keyframe.speed > 0 && keyframe.offsetUs > 0)
+    .filter((keyframe) => base.sourceStartUs + keyframe.offsetUs < base.sourceEndUs)
+    .sort((a, b) => a.offsetUs - b.offsetUs);
+  if (keyframes.length === 0) {
+    return [{ ...base, speed: baseSpeed }];
+  }
+
+  const bounds = [{ offsetUs: 0, speed: baseSpeed }, ...keyframes];
+  const parts = [];
+  let timelineUs = base.startUs;
+  for (let index = 0; index < bounds.length; index += 1) {
+    const sourceStartUs = base.sourceStartUs + bounds[index].offsetUs;
+    const sourceEndUs = index + 1 < bounds.length
+      ? base.sourceStartUs + bounds[index + 1].offsetUs
+      : base.sourceEndUs;
+    const lengthUs = Math.round((sourceEndUs - sourceStartUs) / bounds[index].speed);
+    parts.push({
+      ...base,
+      id: `${base.id}-speed-${index + 1}`,
+      sourceStartUs,
+      sourceEndUs,
+      startUs: timelineUs,
+      endUs: timelineUs + lengthUs,
+      speed: bounds[index].speed,
+    });
+    timelineUs += lengthUs;
+  }
+  return parts;
+}
+
+/** setpts/atempo filters for a playback rate; atempo only takes 0.5-2 per stage. */
+/**
+ * Mute/bleep ranges (source time) from a clip's `effects.censor`.
+ */
+function normalizeCensors(raw) {
+  return (Array.isArray(raw) ? raw : [])
+    .map((entry) => ({
+      sourceStartUs: Number(entry?.sourceStartUs || 0),
+      sourceEndUs: Number(entry?.sourceEndUs || 0),
+      mode: entry?.mode === 'bleep' ? 'bleep' : 'mute',
+    }))
+    .filter((entry) => entry.sourceEndUs > entry.sourceStartUs);
+}
+
+/**
+ * aeval filter silencing, or replacing with a 1kHz tone, each censored range
+ * of a segment whose audio starts at `audioStartUs`. Runs before atempo so
+ * times stay in source seconds.
+ */
+function censorFilter(censors, audioStartUs) {
+  if (!Array.isArray(censors) || censors.length === 0) {
+    return null;
+  }
+  const expression = censors.reduceRight((otherwise, censor) => {
+    const start = Math.max(0, (censor.sourceStartUs - audioStartUs) / 1_000_000).toFixed(3);
+    const end = Math.max(0, (censor.sourceEndUs - audioStartUs) / 1_000_000).toFixed(3);
+    const replacement = censor.mode === 'bleep' ? '0.25*sin(2*PI*1000*t)' : '0';
+    return `if(between(t,${start},${end}),${replacement},${otherwise})`;
+  }, 'val(ch)');
+  return `aeval=exprs='${expression}':c=same`;
+}
+
+function speedFilters(speed) {
+  if (!(speed > 0) || Math.abs(speed - 1) < 1e-6) {
+    return { video: null, audio: null };
+  }
+  const stages = [];
+  let remaining = speed;
+  while (remaining > 2) {
+    stages.push(2);
+    remaining /= 2;
+  }
+  while (remaining < 0.5) {
+    stages.push(0.5);
+    remaining /= 0.5;
+  }
+  stages.push(remaining);
+  return {
+    video: `setpts=(PTS-STARTPTS)/${speed.toFixed(6)}`,
+    audio: stages.map((stage) => `atempo=${stage.toFixed(6)}`).join(','),
+  };
+}
+
+/**
+ * Crop rectangle from a clip transform, as fractions of the source frame
+ * ({ x, y, width, height } in 0..1). Written by the desktop app's
+ * export profiles for reframed (e.g. 9:16) renders.
+ */
+function normalizeCrop(crop) {
+  if (!crop || typeof crop !== 'object') return null;
+  const clamp01 = (value) => Math.min(1, Math.max(0, Number(value)));
+  const width = clamp01(crop.width);
+  const height = clamp01(crop.height);
+  if (!(width > 0) || !(height > 0)) return null;
+  return {
+    x: Math.min(clamp01(crop.x || 0), 1 - width),
+    y: Math.min(clamp01(crop.y || 0), 1 - height),
+    width,
+    height,
+  };
+}
+
+function parseFrameSize(value) {
+  const match = /^(\d+)x(\d+)$/.exec(String(value || '').trim());
+  if (!match) return null;
+  const width = Number(match[1]);
+  const height = Number(match[2]);
+  return width > 0 && height > 0 ? { width, height } : null;
+}
+
+/** Per-segment video filter for crop + output frame size, or null. */
+function segmentVideoFilter(crop, frameSize, lutPath = null) {
+  const filters = [];
+  if (lutPath) {
+    // Color first, on the camera's full frame
+    filters.push(`lut3d=file=${escapeSubtitlePath(lutPath)}`);
+  }
+  if (crop) {
+    filters.push(
+      `crop=trunc(iw*${crop.width.toFixed(6)}/2)*2:trunc(ih*${crop.height.toFixed(6)}/2)*2:trunc(iw*${crop.x.toFixed(6)}):trunc(ih*${crop.y.toFixed(6)})`,
+    );
+  }
+  if (frameSize) {
+    const { width, height } = frameSize;
+    filters.push(
+      `scale=${width}:${height}:force_original_aspect_ratio=decrease`,
+      `pad=${width}:${height}:(ow-iw)/2:(oh-ih)/2`,
+      'setsar=1',
+    );
+  }
+  return filters.length > 0 ? filters.join(',') : null;
+}
+
 /**
  * Merge adjacent source clips from the same sourceRef into larger segments
  * to dramatically reduce the number of ffmpeg invocations.
  * Clips within MERGE_GAP_US of each other are combined.
  */
-function mergeAdjacentClips(sortedClips, mergeGapUs = 2_000_000) {
+function mergeAdjacentClips(sortedClips, mergeGapUs = 2_000_000, keepCuts = []) {
   if (sortedClips.length <= 1) return sortedClips;
 
   const merged = [];
@@ -199,12 +398,21 @@ function mergeAdjacentClips(sortedClips, mergeGapUs = 2_000_000) {
   for (let i = 1; i < sortedClips.length; i++) {
     const next = sortedClips[i];
     const sameSource = current.sourceRef === next.sourceRef || !next.sourceRef || !current.sourceRef;
+    const sameCrop = JSON.stringify(current.crop ?? null) === JSON.stringify(next.crop ?? null);
+    const sameSpeed = (current.speed ?? 1) === (next.speed ?? 1);
+    const sameAudio = (current.audioPath ?? null) === (next.audioPath ?? null);
+    const sameLut = (current.lut ?? null) === (next.lut ?? null);
     const gap = next.sourceStartUs - current.sourceEndUs;
+    // Censored words cut out of the gap must stay out
+    const gapCensored = gap > 0 && keepCuts.some(
+      (cut) => cut.sourceStartUs < next.sourceStartUs && cut.sourceEndUs > current.sourceEndUs,
+    );
 
-    if (sameSource && gap <= mergeGapUs) {
+    if (sameSource && sameCrop && sameSpeed && sameAudio && sameLut && gap <= mergeGapUs && !gapCensored) {
       // Extend current segment to include next clip
       current.sourceEndUs = Math.max(current.sourceEndUs, next.sourceEndUs);
       current.endUs = Math.max(current.endUs, next.endUs);
+      current.censors = [...(current.censors || []), ...(next.censors || [])];
       current.id = `merged-${merged.length + 1}`;
     } else {
       merged.push(current);
@@ -241,12 +449,241 @@ function collectOverlayClips(timeline) {
     .sort((a, b) => a.startUs - b.startUs);
 }
 
+function collectTitleClips(timeline) {
+  const clips = Array.isArray(timeline?.clips) ? timeline.clips : [];
+  return clips
+    .filter((clip) => clip && clip.clipType === 'title' && clip?.meta?.title?.text)
+    .map((clip, index) => ({
+      id: String(clip.clipId || `title-${index + 1}`),
+      startUs: Number(clip.startUs || 0),
+      endUs: Number(clip.endUs || 0),
+      title: clip.meta.title,
+    }))
+    .filter((clip) => clip.endUs > clip.startUs)
+    .sort((a, b) => a.startUs - b.startUs);
+}
+
+// Audio-only clips on audio tracks (voiceover takes), mixed over the edit.
+function collectAudioClips(timeline) {
+  const clips = Array.isArray(timeline?.clips) ? timeline.clips : [];
+  return clips
+    .filter((clip) => clip && clip.clipType === 'audio_clip')
+    .map((clip, index) => ({
+      id: String(clip.clipId || `audio-${index + 1}`),
+      sourceRef: String(clip.sourceRef || ''),
+      startUs: Number(clip.startUs || 0),
+      endUs: Number(clip.endUs || 0),
+      sourceStartUs: Number(clip.sourceStartUs || 0),
+      gainDb: Number(clip?.effects?.gainDb || 0),
+    }))
+    .filter((clip) => clip.endUs > clip.startUs)
+    .sort((a, b) => a.startUs - b.startUs);
+}
+
+// Mixes audio clips into the render's soundtrack at their timeline offsets;
+// the video stream is copied. A render without audio gets a silent bed.
+async function mixAudioClips({ inputPath, audioClips, outputPath }) {
+  const warnings = [];
+  const inputs = [];
+  for (const clip of audioClips) {
+    const filePath = await resolveOverlaySourcePath(clip);
+    if (!filePath) {
+      warnings.push(`Audio clip ${clip.id} skipped: file not found (${clip.sourceRef})`);
+      continue;
+    }
+    inputs.push({ clip, filePath });
+  }
+  if (inputs.length === 0) {
+    return { appliedCount: 0, warnings };
+  }
+
+  const probe = await probeVideo(inputPath);
+  const base = probe.hasAudio ? '[0:a]' : '[bed]';
+  const filters = probe.hasAudio
+    ? []
+    : [`anullsrc=r=48000:cl=stereo,atrim=duration=${probe.durationSec.toFixed(3)}[bed]`];
+  const labels = [];
+  inputs.forEach(({ clip }, index) => {
+    const delayMs = Math.round(clip.startUs / 1000);
+    filters.push(
+      `[${index + 1}:a]atrim=start=${usToSec(clip.sourceStartUs)}:duration=${usToSec(clip.endUs - clip.startUs)},`
+      + `asetpts=PTS-STARTPTS,volume=${clip.gainDb.toFixed(2)}dB,adelay=${delayMs}:all=1[vo${index}]`,
+    );
+    labels.push(`[vo${index}]`);
+  });
+  filters.push(
+    `${base}${labels.join('')}amix=inputs=${labels.length + 1}:duration=first:dropout_transition=0:normalize=0[mix]`,
+  );
+  await run('ffmpeg', [
+    '-y', '-loglevel', 'error',
+    '-i', inputPath,
+    ...inputs.flatMap(({ filePath }) => ['-i', filePath]),
+    '-filter_complex', filters.join(';'),
+    '-map', '0:v', '-map', '[mix]',
+    '-c:v', 'copy',
+    '-c:a', 'aac', '-b:a', '192k',
+    '-movflags', '+faststart',
+    outputPath,
+  ]);
+  return { appliedCount: inputs.length, warnings };
+}
+
+function escapeFilterValue(value) {
+  return String(value).replace(/\\/g, '/').replace(/[:,'[\]]/g, (c) => `\\${c}`);
+}
+
+// "#RRGGBB[AA]" -> drawtext "0xRRGGBB@alpha".
+function drawtextColor(hex, fallback = 'white') {
+  const match = /^#?([0-9a-f]{6})([0-9a-f]{2})?$/i.exec(String(hex || '').trim());
+  if (!match) return fallback;
+  const alpha = match[2] ? (parseInt(match[2], 16) / 255).toFixed(3) : '1';
+  return `0x${match[1]}@${alpha}`;
+}
+
+function titleDrawtextFilter(clip, textFile) {
+  const { title } = clip;
+  const start = Number(usToSec(clip.startUs));
+  const end = Number(usToSec(clip.endUs));
+  const fade = Math.min(0.4, (end - start) / 3).toFixed(3);
+  const size = Math.max(8, Number(title.fontSize || 72));
+  const positions = {
+    top: ['(w-text_w)/2', 'h*0.08'],
+    center: ['(w-text_w)/2', '(h-text_h)/2'],
+    bottom: ['(w-text_w)/2', 'h*0.88-text_h'],
+    lowerThird: ['w*0.06', 'h*0.72'],
+  };
+  let [x, y] = positions[title.position] || positions.center;
+  const animation = String(title.animation || 'none');
+  let alpha = '1';
+  if (animation === 'fade' || animation === 'slideUp') {
+    alpha = `if(lt(t,${start}+${fade}),(t-${start})/${fade},if(gt(t,${end}-${fade}),(${end}-t)/${fade},1))`;
+  }
+  if (animation === 'slideUp') {
+    y = `${y}+h*0.05*max(0,1-(t-${start})/0.5)`;
+  }
+  const options = [
+    `textfile=${escapeFilterValue(path.resolve(textFile))}`,
+    'expansion=none',
+    `font=${escapeFilterValue(title.fontFamily || 'Arial')}`,
+    `fontsize='${size}*min(w,h)/1080'`,
+    `fontcolor=${drawtextColor(title.color)}`,
+    `x='${x}'`,
+    `y='${y}'`,
+    `alpha='${alpha}'`,
+    `enable='between(t,${start},${end})'`,
+  ];
+  if (title.backgroundColor) {
+    options.push('box=1', `boxcolor=${drawtextColor(title.backgroundColor, 'black@0.5')}`, `boxborderw='${Math.round(size * 0.3)}'`);
+  }
+  return `drawtext=${options.join(':')}`;
+}
+
+// Burns every title clip in one drawtext pass.
+async function renderTitles({ inputPath, titleClips, outputPath, tempDir, profile }) {
+  const filters = [];
+  for (const clip of titleClips) {
+    const textFile = path.join(tempDir, `title-${filters.length}.txt`);
+    await fs.writeFile(textFile, String(clip.title.text), 'utf8');
+    filters.push(titleDrawtextFilter(clip, textFile));
+  }
+  const vEnc = await hwEncodeVideoArgs({ quality: profile.quality || 'balanced' });
+  await run('ffmpeg', [
+    '-y', '-loglevel', 'error',
+    '-i', inputPath,
+    '-vf', filters.join(','),
+    '-map', '0:v', '-map', '0:a?',
+    ...vEnc,
+    '-c:a', 'copy',
+    '-movflags', '+faststart',
+    outputPath,
+  ]);
+  return filters.length;
+}
+
+async function probeVideo(filePath) {
+  const { stdout } = await execFile('ffprobe', [
+    '-v', 'quiet', '-show_entries', 'stream=codec_type,width,height,r_frame_rate:format=duration',
+    '-of', 'json', filePath,
+  ], { timeout: 10000 });
+  const probe = JSON.parse(stdout.toString());
+  const streams = Array.isArray(probe.streams) ? probe.streams : [];
+  const video = streams.find(stream => stream.codec_type === 'video');
+  return {
+    width: Number(video?.width) || 0,
+    height: Number(video?.height) || 0,
+    fps: video?.r_frame_rate && video.r_frame_rate !== '0/0' ? video.r_frame_rate : '30',
+    hasAudio: streams.some(stream => stream.codec_type === 'audio'),
+    durationSec: Number(probe.format?.duration) || 0,
+  };
+}
+
+// Concatenates intro/outro stingers around the render, scaled and padded to
+// its frame. Stingers without audio get silence.
+async function appendStingers({ inputPath, intro, outro, outputPath, profile }) {
+  const parts = [intro, inputPath, outro].filter(Boolean);
+  const probes = await Promise.all(parts.map(probeVideo));
+  const main = probes[parts.indexOf(inputPath)];
+  if (!main.width || !main.height) {
+    throw new Error('could not read the render frame size');
+  }
+  const filters = [];
+  const labels = [];
+  probes.forEach((probe, index) => {
+    filters.push(
+      `[${index}:v]scale=${main.width}:${main.height}:force_original_aspect_ratio=decrease,` +
+        `pad=${main.width}:${main.height}:(ow-iw)/2:(oh-ih)/2,setsar=1,fps=${main.fps},format=yuv420p[v${index}]`,
+    );
+    filters.push(
+      probe.hasAudio
+        ? `[${index}:a]aresample=48000,aformat=channel_layouts=stereo[a${index}]`
+        : `aevalsrc=0:c=stereo:s=48000:d=${probe.durationSec.toFixed(3)}[a${index}]`,
+    );
+    labels.push(`[v${index}][a${index}]`);
+  });
+  filters.push(`${labels.join('')}concat=n=${parts.length}:v=1:a=1[outv][outa]`);
+  const vEnc = await hwEncodeVideoArgs({ quality: profile.quality || 'balanced' });
+  const aEnc = await hwEncodeAudioArgs({ bitrate: '160k' });
+  await run('ffmpeg', [
+    '-y', '-loglevel', 'error',
+    ...parts.flatMap(part => ['-i', part]),
+    '-filter_complex', filters.join(';'),
+    '-map', '[outv]', '-map', '[outa]',
+    ...vEnc,
+    ...aEnc,
+    '-movflags', '+faststart',
+    outputPath,
+  ]);
+  const stingerSec = probes
+    .filter((_, index) => parts[index] !== inputPath)
+    .reduce((total, probe) => total + probe.durationSec, 0);
+  return { count: parts.length - 1, durationUs: Math.round(stingerSec * 1_000_000) };
+}
+
 function isProbablePath(input) {
   if (!input) return false;
   return input.startsWith('/') || input.startsWith('./') || input.startsWith('../') || input.startsWith('file://');
 }
 
+// Path aliases from the app settings: `${NAME}/clip.mp4` resolves under roots.NAME.
+const MEDIA_ROOTS = (() => {
+  try {
+    const roots = JSON.parse(process.env.LAPAAS_MEDIA_ROOTS || '{}');
+    return roots && typeof roots === 'object' ? roots : {};
+  } catch {
+    return {};
+  }
+})();
+
+function expandMediaRoot(value) {
+  const match = /^\$\{([A-Za-z0-9_]+)\}[\\/]?(.*)$/s.exec(String(value || ''));
+  if (!match || typeof MEDIA_ROOTS[match[1]] !== 'string') {
+    return value;
+  }
+  return match[2] ? path.join(MEDIA_ROOTS[match[1]], match[2]) : MEDIA_ROOTS[match[1]];
+}
+
 function decodeFileUrl(value) {
+  value = expandMediaRoot(value);
   if (!value.startsWith('file://')) {
     return value;
   }
@@ -292,14 +729,16 @@ function isAudioPath(filePath) {
 async function resolveDefaultSourcePath(projectDir) {
   const transcriptPath = path.join(projectDir, 'transcript.json');
   const transcript = await readJsonIfExists(transcriptPath);
-  if (transcript?.source?.path && (await exists(transcript.source.path))) {
-    return path.resolve(transcript.source.path);
+  const transcriptSource = expandMediaRoot(transcript?.source?.path);
+  if (transcriptSource && (await exists(transcriptSource))) {
+    return path.resolve(transcriptSource);
   }
 
   const ingestPath = path.join(projectDir, 'media', 'metadata.json');
   const ingest = await readJsonIfExists(ingestPath);
-  if (ingest?.sourcePath && (await exists(ingest.sourcePath))) {
-    return path.resolve(ingest.sourcePath);
+  const ingestSource = expandMediaRoot(ingest?.sourcePath);
+  if (ingestSource && (await exists(ingestSource))) {
+    return path.resolve(ingestSource);
   }
 
   // Fallback 1: scan state.json for media item paths
@@ -384,6 +823,12 @@ function concatPathLine(filePath) {
   return `file '${filePath.replace(/'/g, "'\\''")}'`;
 }
 
+// Styled .ass files go through libass directly so their styles are kept.
+function subtitleBurnFilter(filePath) {
+  const filter = path.extname(filePath).toLowerCase() === '.ass' ? 'ass' : 'subtitles';
+  return `${filter}=filename=${escapeSubtitlePath(filePath)}`;
+}
+
 function escapeSubtitlePath(filePath) {
   return path
     .resolve(filePath)
@@ -394,7 +839,7 @@ function escapeSubtitlePath(filePath) {
     .replace(/\]/g, '\\]');
 }
 
-async function renderSegment({ sourcePath, startUs, endUs, outputPath, profile, seamFadeMs = 50, paddingMs = 0, audioLeadMs = 0, audioLagMs = 0 }) {
+async function renderSegment({ sourcePath, startUs, endUs, outputPath, profile, seamFadeMs = 50, paddingMs = 0, audioLeadMs = 0, audioLagMs = 0, videoFilter = null, frameSize = null, speed = 1, censors = [], audioPath = null }) {
   // Detect audio-only by extension first, then probe for video stream as fallback
   let isAudio = isAudioPath(sourcePath);
   if (!isAudio) {
@@ -429,17 +874,25 @@ async function renderSegment({ sourcePath, startUs, endUs, outputPath, profile,
   const audioEndUs = adjEndUs + audioLagUs;
   const hasJLCut = audioLeadMs > 0 || audioLagMs > 0;
 
+  // Playback rate: setpts for video, atempo for audio (applied before fades)
+  const rate = speedFilters(speed);
+  const segmentVideoFilters = [rate.video, videoFilter].filter(Boolean).join(',') || null;
+
   // Build audio fade filter for smooth seam transitions
   const fadeSec = Math.max(0.02, seamFadeMs / 1000);
-  const audioDurationSec = (audioEndUs - audioStartUs) / 1_000_000;
+  const audioDurationSec = (audioEndUs - audioStartUs) / 1_000_000 / (speed > 0 ? speed : 1);
   const fadeOutStart = Math.max(0, audioDurationSec - fadeSec);
-  const afadeFilter = `afade=t=in:st=0:d=${fadeSec},afade=t=out:st=${fadeOutStart.toFixed(3)}:d=${fadeSec}`;
+  const afadeFilter = [
+    censorFilter(censors, audioStartUs),
+    rate.audio,
+    `afade=t=in:st=0:d=${fadeSec},afade=t=out:st=${fadeOutStart.toFixed(3)}:d=${fadeSec}`,
+  ].filter(Boolean).join(',');
 
   if (isAudio) {
     await run('ffmpeg', [
       '-y', '-loglevel', 'error',
-      '-f', 'lavfi', '-i', 'color=c=black:s=1920x1080:r=30',
-      '-ss', usToSec(audioStartUs), '-to', usToSec(audioEndUs), '-i', sourcePath,
+      '-f', 'lavfi', '-i', `color=c=black:s=${frameSize ? `${frameSize.width}x${frameSize.height}` : '1920x1080'}:r=30`,
+      '-ss', usToSec(audioStartUs), '-to', usToSec(audioEndUs), '-i', audioPath || sourcePath,
       '-map', '0:v', '-map', '1:a',
       '-af', afadeFilter,
       '-shortest',
@@ -455,13 +908,14 @@ async function renderSegment({ sourcePath, startUs, endUs, outputPath, profile,
     const aStartSec = usToSec(audioStartUs);
     const aEndSec = usToSec(audioEndUs);
     const filterComplex = [
-      `[0:v]trim=start=${vStartSec}:end=${vEndSec},setpts=PTS-STARTPTS[v]`,
-      `[0:a]atrim=start=${aStartSec}:end=${aEndSec},asetpts=PTS-STARTPTS,${afadeFilter}[a]`,
+      `[0:v]trim=start=${vStartSec}:end=${vEndSec},setpts=PTS-STARTPTS${segmentVideoFilters ? `,${segmentVideoFilters}` : ''}[v]`,
+      `[${audioPath ? 1 : 0}:a]atrim=start=${aStartSec}:end=${aEndSec},asetpts=PTS-STARTPTS,${afadeFilter}[a]`,
     ].join(';');
     await run('ffmpeg', [
       '-y', '-loglevel', 'error',
       ...decArgs,
       '-i', sourcePath,
+      ...(audioPath ? ['-i', audioPath] : []),
       '-filter_complex', filterComplex,
       '-map', '[v]', '-map', '[a]',
       '-shortest',
@@ -477,8 +931,10 @@ async function renderSegment({ sourcePath, startUs, endUs, outputPath, profile,
       '-ss', usToSec(adjStartUs),
       '-to', usToSec(adjEndUs),
       '-i', sourcePath,
+      ...(audioPath ? ['-ss', usToSec(adjStartUs), '-to', usToSec(adjEndUs), '-i', audioPath] : []),
       '-map', '0:v:0',
-      '-map', '0:a?',
+      '-map', audioPath ? '1:a:0' : '0:a?',
+      ...(segmentVideoFilters ? ['-vf', segmentVideoFilters] : []),
       '-af', afadeFilter,
       ...vEnc,
       ...aEnc,
@@ -489,16 +945,19 @@ async function renderSegment({ sourcePath, startUs, endUs, outputPath, profile,
 }
 
 /**
- * Render a low-res preview for a single chunk (480p, fast preset).
- * Used by chunk QC scoring to visually validate edit plans.
+ * Render a low-res preview for a single chunk (854x480, fast preset).
+ * Used by chunk QC scoring to visually validate edit plans, and by the
+ * desktop app's timeline preview, which concatenates chunks without
+ * re-encoding — so every chunk gets the same frame, rate and audio layout.
  * @param {object} opts
  * @param {string} opts.sourcePath - Path to source video/audio
  * @param {number} opts.startUs - Chunk start in microseconds
  * @param {number} opts.endUs - Chunk end in microseconds
  * @param {string} opts.outputPath - Where to write the preview MP4
  * @param {number} [opts.seamFadeMs=50] - Audio fade duration
+ * @param {number} [opts.speed=1] - Playback rate of the chunk
  */
-async function renderChunkPreview({ sourcePath, startUs, endUs, outputPath, seamFadeMs = 50 }) {
+async function renderChunkPreview({ sourcePath, startUs, endUs, outputPath, seamFadeMs = 50, speed = 1 }) {
   let isAudio = isAudioPath(sourcePath);
   if (!isAudio) {
     try {
@@ -510,9 +969,17 @@ async function renderChunkPreview({ sourcePath, startUs, endUs, outputPath, seam
     } catch { /* fall through */ }
   }
   const fadeSec = Math.max(0.02, seamFadeMs / 1000);
-  const segDurationSec = (endUs - startUs) / 1_000_000;
+  const rate = speedFilters(speed);
+  const segDurationSec = (endUs - startUs) / 1_000_000 / (rate.video ? speed : 1);
   const fadeOutStart = Math.max(0, segDurationSec - fadeSec);
-  const afadeFilter = `afade=t=in:st=0:d=${fadeSec},afade=t=out:st=${fadeOutStart.toFixed(3)}:d=${fadeSec}`;
+  const afadeFilter = [
+    rate.audio,
+    `afade=t=in:st=0:d=${fadeSec},afade=t=out:st=${fadeOutStart.toFixed(3)}:d=${fadeSec}`,
+  ].filter(Boolean).join(',');
+  const videoFilter = [
+    rate.video,
+    'scale=854:480:force_original_aspect_ratio=decrease,pad=854:480:(ow-iw)/2:(oh-ih)/2,setsar=1',
+  ].filter(Boolean).join(',');
 
   if (isAudio) {
     await run('ffmpeg', [
@@ -522,8 +989,8 @@ async function renderChunkPreview({ sourcePath, startUs, endUs, outputPath, seam
       '-map', '0:v', '-map', '1:a',
       '-af', afadeFilter,
       '-shortest',
-      '-c:v', 'libx264', '-preset', 'ultrafast', '-crf', '30',
-      '-c:a', 'aac', '-b:a', '96k',
+      '-c:v', 'libx264', '-preset', 'ultrafast', '-crf', '30', '-pix_fmt', 'yuv420p',
+      '-c:a', 'aac', '-b:a', '96k', '-ar', '48000', '-ac', '2',
       '-movflags', '+faststart',
       outputPath,
     ]);
@@ -532,10 +999,10 @@ async function renderChunkPreview({ sourcePath, startUs, endUs, outputPath, seam
       '-y', '-loglevel', 'error',
       '-ss', usToSec(startUs), '-to', usToSec(endUs),
       '-i', sourcePath,
-      '-vf', 'scale=-2:480',
+      '-vf', videoFilter,
       '-af', afadeFilter,
-      '-c:v', 'libx264', '-preset', 'ultrafast', '-crf', '30',
-      '-c:a', 'aac', '-b:a', '96k',
+      '-c:v', 'libx264', '-preset', 'ultrafast', '-crf', '30', '-pix_fmt', 'yuv420p',
+      '-c:a', 'aac', '-b:a', '96k', '-ar', '48000', '-ac', '2',
       '-r', '24',
       '-movflags', '+faststart',
       outputPath,
@@ -543,6 +1010,32 @@ async function renderChunkPreview({ sourcePath, startUs, endUs, outputPath, seam
   }
 }
 
+// Bump when renderSegment's output changes so cached segments are re-encoded.
+const SEGMENT_CACHE_VERSION = 1;
+
+async function fileStamp(filePath) {
+  if (!filePath) return null;
+  const stat = await fs.stat(filePath).catch(() => null);
+  return stat ? [filePath, stat.size, Math.round(stat.mtimeMs)] : [filePath];
+}
+
+/**
+ * Cache key of a segment: every renderSegment input except the output path,
+ * the files it reads (by size and mtime) and the video encoder.
+ */
+async function segmentCacheKey(inputs, lutPath) {
+  const { outputPath, ...rest } = inputs;
+  const payload = {
+    version: SEGMENT_CACHE_VERSION,
+    ...rest,
+    source: await fileStamp(inputs.sourcePath),
+    audio: await fileStamp(inputs.audioPath),
+    lut: await fileStamp(lutPath),
+    encoder: preferredVideoEncoder(),
+  };
+  return createHash('sha256').update(JSON.stringify(payload)).digest('hex').slice(0, 32);
+}
+
 async function concatSegments(listPath, outputPath, profile) {
   try {
     await run('ffmpeg', [
@@ -776,10 +1269,17 @@ async function main() {
   const outputName = readArg('--output-name');
   const quality = safeQuality(readArg('--quality', 'balanced'));
   const burnSubtitles = readArg('--burn-subtitles', 'false') === 'true';
+  const projectLut = readArg('--lut', ''); // .cube applied to source clips without their own LUT
+  const loudnorm = readArg('--loudnorm', 'true') !== 'false'; // false when the caller normalizes two-pass itself
   const captionsVariants = readArg('--captions-variants', 'false') === 'true'; // Export both captioned + uncaptioned
   const watermarkPath = readArg('--watermark', ''); // Path to watermark image (PNG with transparency)
   const watermarkPos = readArg('--watermark-position', 'bottom-right'); // top-left, top-right, bottom-left, bottom-right
   const watermarkOpacity = parseFloat(readArg('--watermark-opacity', '0.6'));
+  const segmentCacheDir = readArg('--segment-cache', ''); // Clip segment cache managed by the desktop app
+  const introPath = readArg('--intro', ''); // Branding stinger played before the edit
+  const outroPath = readArg('--outro', ''); // Branding stinger played after the edit
+  const frameSize = parseFrameSize(readArg('--frame-size', '')); // e.g. "1080x1920" for reframed profiles
+  const requestedEncoder = setPreferredVideoEncoder(readArg('--encoder', 'auto')); // auto, libx264 or a hardware encoder
   const exportFormats = readArg('--formats', '').split(',').map(f => f.trim()).filter(Boolean); // e.g. "vertical,shorts"
   const maxRetries = safeInteger(
     readArg('--max-retries', process.env.LAPAAS_RENDER_MAX_RETRIES ?? '1'),
@@ -804,11 +1304,14 @@ async function main() {
   }
 
   const projectDir = readArg('--project-dir') || path.resolve('desktop', 'data', projectId);
-  const timelinePath = path.join(projectDir, 'timeline.json');
+  const timelinePath = readArg('--timeline-path') || path.join(projectDir, 'timeline.json');
   const jobPath = path.join(projectDir, 'render-job.json');
   const renderDir = path.join(projectDir, 'renders');
   const tempDir = path.join(renderDir, `tmp-${Date.now()}`);
-  const subtitlesPath = path.join(projectDir, 'subtitles', 'subtitles.srt');
+  // Styled ASS written by the desktop app; falls back to the plain SRT.
+  const subtitlesPath =
+    readArg('--subtitles-ass') || path.join(projectDir, 'subtitles', 'subtitles.srt');
+  const subtitlesExt = path.extname(subtitlesPath);
   const tracker = createStageTracker();
   const warnings = [];
   const retryEvents = [];
@@ -874,6 +1377,7 @@ async function main() {
 
     const { timeline, sourceClips, profile, defaultSourcePath } = setup;
     const segmentPaths = [];
+    const segmentCache = segmentCacheDir ? { hits: [], stored: [] } : null;
 
     // Load seam quality report for per-cut fade/padding recommendations
     const seamReportPath = path.join(projectDir, 'seam_quality_report.json');
@@ -911,26 +1415,60 @@ async function main() {
         const audioLagMs = seamRec.audioLagMs || 0;
 
         const segmentPath = path.join(tempDir, `segment-${String(index + 1).padStart(3, '0')}.mp4`);
+        // Enhanced audio replaces the source's track, same timing
+        const audioPath = clip.audioPath && (await exists(clip.audioPath)) ? clip.audioPath : null;
+        if (clip.audioPath && !audioPath) {
+          warnings.push(`Enhanced audio ${clip.audioPath} is missing; used the original audio for ${clip.id}.`);
+        }
+        const wantedLut = clip.lut || projectLut || null;
+        const lutPath = wantedLut && (await exists(wantedLut)) ? wantedLut : null;
+        if (wantedLut && !lutPath) {
+          warnings.push(`LUT ${wantedLut} is missing; rendered ${clip.id} without it.`);
+        }
+        const segmentInputs = {
+          sourcePath: clipSourcePath,
+          startUs: clip.sourceStartUs,
+          endUs: clip.sourceEndUs,
+          outputPath: segmentPath,
+          profile,
+          seamFadeMs,
+          paddingMs,
+          audioLeadMs,
+          audioLagMs,
+          videoFilter: segmentVideoFilter(clip.crop, frameSize, lutPath),
+          frameSize,
+          speed: clip.speed,
+          censors: clip.censors,
+          audioPath,
+        };
+        const cacheKey = segmentCache ? await segmentCacheKey(segmentInputs, lutPath) : null;
+        const cachedPath = cacheKey ? path.join(segmentCacheDir, `${cacheKey}.mp4`) : null;
+        if (cachedPath && (await exists(cachedPath))) {
+          segmentCache.hits.push(cacheKey);
+          segmentPaths.push(cachedPath);
+          continue;
+        }
         const retryResult = await withRetries(
           `segment:${clip.id}`,
           maxRetries,
           retryDelayMs,
-          () =>
-            renderSegment({
-              sourcePath: clipSourcePath,
-              startUs: clip.sourceStartUs,
-              endUs: clip.sourceEndUs,
-              outputPath: segmentPath,
-              profile,
-              seamFadeMs,
-              paddingMs,
-              audioLeadMs,
-              audioLagMs,
-            }),
+          () => renderSegment(segmentInputs),
           onRetry,
         );
         stageAttempts[`segment:${clip.id}`] = retryResult.attempts;
         segmentPaths.push(segmentPath);
+        if (cachedPath) {
+          // Copied under a temporary name so a half-written file is never a hit.
+          const partialPath = `${cachedPath}.part`;
+          try {
+            await fs.copyFile(segmentPath, partialPath);
+            await fs.rename(partialPath, cachedPath);
+            segmentCache.stored.push(cacheKey);
+          } catch (e) {
+            await fs.rm(partialPath, { force: true }).catch(() => { });
+            warnings.push(`Could not cache segment for ${clip.id}: ${e.message}`);
+          }
+        }
       }
     });
 
@@ -990,8 +1528,54 @@ async function main() {
       process.stderr.write(`[Render] Overlay warnings:\n${overlayResult.warnings.map(w => `  - ${w}`).join('\n')}\n`);
     }
 
+    // ── Title Clips ───────────────────────────────────────────────────────────
+    let titledPath = compositedPath;
+    const titleClips = collectTitleClips(timeline);
+    if (titleClips.length > 0) {
+      await tracker.run('titles', async () => {
+        const titledTemp = path.join(tempDir, 'titled.mp4');
+        try {
+          const applied = await renderTitles({
+            inputPath: compositedPath,
+            titleClips,
+            outputPath: titledTemp,
+            tempDir,
+            profile,
+          });
+          titledPath = titledTemp;
+          process.stderr.write(`[Render] Applied ${applied} title clips\n`);
+        } catch (e) {
+          warnings.push(`Title rendering failed (likely ffmpeg built without drawtext): ${e.message.split('\n')[0]}`);
+        }
+      });
+    }
+
+    // ── Audio Clips ───────────────────────────────────────────────────────────
+    let mixedPath = titledPath;
+    const audioClips = collectAudioClips(timeline);
+    if (audioClips.length > 0) {
+      await tracker.run('audio-mix', async () => {
+        const mixedTemp = path.join(tempDir, 'mixed.mp4');
+        try {
+          const mixResult = await mixAudioClips({
+            inputPath: titledPath,
+            audioClips,
+            outputPath: mixedTemp,
+          });
+          warnings.push(...mixResult.warnings);
+          if (mixResult.appliedCount > 0) {
+            mixedPath = mixedTemp;
+            process.stderr.write(`[Render] Mixed ${mixResult.appliedCount} audio clips\n`);
+          }
+        } catch (e) {
+          warnings.push(`Audio clip mix failed: ${e.message.split('\n')[0]}`);
+        }
+      });
+    }
+
     // ── Watermark / Branding Overlay ──────────────────────────────────────────
-    let watermarkedPath = compositedPath;
+    let watermarkedPath = mixedPath;
+    let watermarkApplied = false;
     if (watermarkPath && (await exists(watermarkPath))) {
       await tracker.run('watermark', async () => {
         const wmTemp = path.join(tempDir, 'watermarked.mp4');
@@ -1007,7 +1591,7 @@ async function main() {
         try {
           await run('ffmpeg', [
             '-y', '-loglevel', 'error',
-            '-i', compositedPath,
+            '-i', mixedPath,
             '-i', watermarkPath,
             '-filter_complex', `[1:v]format=rgba,colorchannelmixer=aa=${opacityVal.toFixed(2)}[wm];[0:v][wm]overlay=${overlay}[out]`,
             '-map', '[out]', '-map', '0:a?',
@@ -1017,6 +1601,7 @@ async function main() {
             wmTemp,
           ]);
           watermarkedPath = wmTemp;
+          watermarkApplied = true;
           console.error(`[Render] Watermark applied: ${watermarkPos}, opacity ${opacityVal}`);
         } catch (e) {
           warnings.push(`Watermark overlay failed: ${e.message}`);
@@ -1032,9 +1617,8 @@ async function main() {
     await tracker.run('subtitle-finalize', async () => {
       if (burnSubtitles && (await exists(subtitlesPath))) {
         const subtitleTempDir = await fs.mkdtemp(path.join(os.tmpdir(), 'lapaas-subtitles-'));
-        const subtitleTempPath = path.join(subtitleTempDir, 'subtitles.srt');
+        const subtitleTempPath = path.join(subtitleTempDir, `subtitles${subtitlesExt}`);
         await fs.copyFile(subtitlesPath, subtitleTempPath);
-        const escapedSubtitlePath = escapeSubtitlePath(subtitleTempPath);
         try {
           const subtitleBurnVEnc = await hwEncodeVideoArgs({ quality: profile.quality || 'balanced' });
           const retryResult = await withRetries(
@@ -1049,7 +1633,7 @@ async function main() {
                 '-i',
                 preSubtitlePath,
                 '-vf',
-                `subtitles=filename=${escapedSubtitlePath}`,
+                subtitleBurnFilter(subtitleTempPath),
                 ...subtitleBurnVEnc,
                 '-c:a',
                 'copy',
@@ -1069,15 +1653,49 @@ async function main() {
         }
       } else {
         if (burnSubtitles) {
-          warnings.push('Subtitle burn-in requested, but subtitles.srt was not found.');
+          warnings.push(`Subtitle burn-in requested, but ${path.basename(subtitlesPath)} was not found.`);
         }
         await fs.copyFile(preSubtitlePath, finalOutputPath);
       }
     });
 
+    // ── Intro / Outro Stingers ────────────────────────────────────────────────
+    let stingers = 0;
+    let stingerDurationUs = 0;
+    const stingerPaths = [];
+    for (const stinger of [introPath, outroPath]) {
+      if (!stinger) continue;
+      if (await exists(stinger)) {
+        stingerPaths.push(stinger);
+      } else {
+        warnings.push(`Branding stinger not found: ${stinger}`);
+      }
+    }
+    if (stingerPaths.length > 0) {
+      await tracker.run('stingers', async () => {
+        const stingerTemp = path.join(tempDir, 'stingers.mp4');
+        try {
+          ({ count: stingers, durationUs: stingerDurationUs } = await appendStingers({
+            inputPath: finalOutputPath,
+            intro: stingerPaths.includes(introPath) ? introPath : null,
+            outro: stingerPaths.includes(outroPath) ? outroPath : null,
+            outputPath: stingerTemp,
+            profile,
+          }));
+          await fs.rename(stingerTemp, finalOutputPath);
+          console.error(`[Render] Added ${stingers} branding stinger(s)`);
+        } catch (e) {
+          warnings.push(`Intro/outro stingers failed: ${e.message.split('\n')[0]}`);
+        }
+      });
+    }
+
     // ── Audio Loudness Normalization (EBU R128) ──────────────────────────────
     let loudnormApplied = false;
     await tracker.run('loudnorm', async () => {
+      if (!loudnorm) {
+        return;
+      }
       try {
         const loudnormTemp = path.join(tempDir, 'loudnorm.mp4');
         const vEnc = await hwEncodeVideoArgs({ quality: profile.quality || 'balanced' });
@@ -1098,6 +1716,21 @@ async function main() {
       }
     });
 
+    // Recorded in the history so the desktop app can estimate later renders.
+    let output = null;
+    try {
+      const probe = await probeVideo(finalOutputPath);
+      const { size } = await fs.stat(finalOutputPath);
+      output = {
+        durationUs: Math.round(probe.durationSec * 1_000_000),
+        width: probe.width,
+        height: probe.height,
+        bytes: size,
+      };
+    } catch (e) {
+      warnings.push(`Output probe failed (non-critical): ${e.message}`);
+    }
+
     const totalClipCount = Array.isArray(timeline.clips) ? timeline.clips.length : 0;
     const overlayClipCount = collectOverlayClips(timeline).length;
     const ignoredClipCount = Math.max(0, totalClipCount - sourceClips.length - overlayResult.appliedCount);
@@ -1117,15 +1750,29 @@ async function main() {
       },
     });
 
+    for (const fallback of encoderFallbacks) {
+      warnings.push(`${fallback.encoder} encode failed, used libx264 instead: ${fallback.error}`);
+    }
     const result = {
       ok: true,
+      renderId: `render-${randomUUID()}`,
       projectId,
       outputPath: finalOutputPath,
+      output,
       timelinePath,
       quality,
+      encoder: {
+        requested: requestedEncoder,
+        used: preferredVideoEncoder(),
+        fallbacks: encoderFallbacks,
+      },
       burnSubtitlesRequested: burnSubtitles,
       subtitlesBurned,
       loudnormApplied,
+      watermarkApplied,
+      stingers,
+      stingerDurationUs,
+      segmentCache,
       sourceClipCount: sourceClips.length,
       overlayClipCount,
       overlayAppliedCount: overlayResult.appliedCount,
@@ -1233,14 +1880,13 @@ async function main() {
           // Main file has no captions — create a with-captions variant
           const captionedPath = finalOutputPath.replace(/\.mp4$/, '-captioned.mp4');
           const subtitleTempDir2 = await fs.mkdtemp(path.join(os.tmpdir(), 'lapaas-capvar-'));
-          const subtitleTempPath2 = path.join(subtitleTempDir2, 'subtitles.srt');
+          const subtitleTempPath2 = path.join(subtitleTempDir2, `subtitles${subtitlesExt}`);
           await fs.copyFile(subtitlesPath, subtitleTempPath2);
-          const escapedPath2 = escapeSubtitlePath(subtitleTempPath2);
           const capVEnc = await hwEncodeVideoArgs({ quality: profile.quality || 'balanced' });
           await run('ffmpeg', [
             '-y', '-loglevel', 'error',
             '-i', finalOutputPath,
-            '-vf', `subtitles=filename=${escapedPath2}`,
+            '-vf', subtitleBurnFilter(subtitleTempPath2),
             ...capVEnc,
             '-c:a', 'copy',
             '-movflags', '+faststart',
@@ -1307,19 +1953,21 @@ async function main() {
 }
 
 // ── Preview-chunk sub-command ────────────────────────────────────────────────
-// Usage: node render_pipeline.mjs --preview-chunk --source <path> --start-us <n> --end-us <n> --output <path>
+// Usage: node render_pipeline.mjs --preview-chunk --source <path> --start-us <n> --end-us <n> --output <path> [--speed <rate>]
 if (process.argv.includes('--preview-chunk')) {
   const src = readArg('--source');
   const startUs = Number(readArg('--start-us', '0'));
   const endUs = Number(readArg('--end-us', '0'));
   const output = readArg('--output');
+  const speed = Number(readArg('--speed', '1'));
   if (!src || !output || endUs <= startUs) {
     process.stderr.write('Usage: --preview-chunk --source <path> --start-us <n> --end-us <n> --output <path>\n');
     process.exit(1);
   }
-  renderChunkPreview({ sourcePath: src, startUs, endUs, outputPath: output })
+  renderChunkPreview({ sourcePath: src, startUs, endUs, outputPath: output, speed })
     .then(() => {
-      process.stdout.write(JSON.stringify({ ok: true, output, durationUs: endUs - startUs }));
+      const durationUs = Math.round((endUs - startUs) / (speed > 0 ? speed : 1));
+      process.stdout.write(JSON.stringify({ ok: true, output, durationUs }));
     })
     .catch(err => {
       process.stderr.write(`Preview render failed: ${err.message}\n`);
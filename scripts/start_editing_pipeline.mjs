@@ -10,6 +10,7 @@ import { runLLMPrompt, extractJsonFromLLMOutput, detectBestLLM } from './lib/llm
 import { getCustomPrompt } from './lib/custom_prompts.mjs';
 import { audioExtractArgs, hwDecodeArgs, parallelMap, detectHWAccel, isMlxWhisperAvailable, transcribeWithMlxWhisper } from './lib/metal_accel.mjs';
 import {
+  RESULT_SCHEMA_VERSION,
   validateCanonicalTranscript,
   validateCutPlan,
   validateCutRanges,
@@ -1145,6 +1146,33 @@ function buildSyntheticCutPlan(durationUs, transcriptPayload, { silenceRanges =
   };
 }
 
+const CHECKPOINT_STAGES = ['ingest', 'transcript', 'plan'];
+
+function parseCheckpointKeys(raw) {
+  try {
+    const parsed = JSON.parse(raw || '{}');
+    return parsed && typeof parsed === 'object' ? parsed : {};
+  } catch {
+    return {};
+  }
+}
+
+// Checkpoints let the desktop app resume a failed run from the last finished
+// stage. The app decides which ones are still valid by comparing keys.
+async function writeCheckpoint(projectDir, stage, key, data) {
+  await writeJson(path.join(projectDir, 'checkpoints', `${stage}.json`), {
+    stage,
+    key: String(key || ''),
+    completedAt: new Date().toISOString(),
+    data,
+  });
+}
+
+async function readCheckpoint(projectDir, stage) {
+  const raw = await fs.readFile(path.join(projectDir, 'checkpoints', `${stage}.json`), 'utf8');
+  return JSON.parse(raw).data;
+}
+
 async function writeJson(filePath, payload) {
   await fs.mkdir(path.dirname(filePath), { recursive: true });
   await fs.writeFile(filePath, `${JSON.stringify(payload, null, 2)}\n`, 'utf8');
@@ -1207,6 +1235,11 @@ async function main() {
   const transcriptionModel = readArg('--transcription-model', '').trim();
   // Accept pre-known duration (from ingest metadata) to skip ffprobe on large files
   const knownDurationSec = Number(readArg('--duration-sec', '0')) || 0;
+  const checkpointKeys = parseCheckpointKeys(readArg('--checkpoint-keys', '{}'));
+  const resumeFrom = CHECKPOINT_STAGES.includes(readArg('--resume-from', ''))
+    ? readArg('--resume-from', '')
+    : '';
+  const resumeIndex = CHECKPOINT_STAGES.indexOf(resumeFrom);
   // Auto-detect best LLM: Codex CLI → OpenAI → Google → Anthropic → Ollama
   const autoLLM = await detectBestLLM();
   const cutPlannerModel =
@@ -1254,6 +1287,7 @@ async function main() {
   let cutPlanPayload = null;
   let removeRanges = [];
   let silenceRanges = [];
+  let silenceAnalyzed = false;
   let cutAnalysis = {
     silenceRangeCount: 0,
     fillerWordCount: 0,
@@ -1280,20 +1314,38 @@ async function main() {
       startedAt,
     });
 
-    // Use pre-known duration from ingest metadata if available — skips ffprobe on large files
-    durationUs = knownDurationSec > 0
-      ? Math.round(knownDurationSec * 1_000_000)
-      : await tracker.run('duration-probe', () => getDurationUs(inputPath));
+    if (resumeFrom) {
+      console.error(`[Pipeline] Resuming after the ${resumeFrom} stage`);
+      const ingest = await readCheckpoint(projectDir, 'ingest');
+      durationUs = ingest.durationUs;
+      silenceRanges = ingest.silenceRanges || [];
+      silenceAnalyzed = Boolean(ingest.silenceAnalyzed);
+    } else {
+      // Use pre-known duration from ingest metadata if available — skips ffprobe on large files
+      durationUs = knownDurationSec > 0
+        ? Math.round(knownDurationSec * 1_000_000)
+        : await tracker.run('duration-probe', () => getDurationUs(inputPath));
+    }
     // Skip CPU-heavy silence detection for all API-based transcription providers.
     // AI cut planning (Codex) will derive cuts from the transcript instead.
     if (adapter.kind === 'api') {
       console.error(`[Pipeline] API transcription (${adapter.runtime}) — skipping local silence detection`);
       silenceRanges = [];
-    } else {
+    } else if (!silenceAnalyzed) {
       silenceRanges = await tracker.run('silence-analysis', () => detectSilenceRanges(inputPath, durationUs));
+      silenceAnalyzed = true;
+    }
+    if (!resumeFrom) {
+      await writeCheckpoint(projectDir, 'ingest', checkpointKeys.ingest, {
+        durationUs,
+        silenceRanges,
+        silenceAnalyzed,
+      });
     }
 
-    transcriptPayload = await tracker.run('transcription-synthesis', async () => {
+    transcriptPayload = resumeIndex >= CHECKPOINT_STAGES.indexOf('transcript')
+      ? await readCheckpoint(projectDir, 'transcript')
+      : await tracker.run('transcription-synthesis', async () => {
       let transcript;
 
       if (adapter.runtime === 'sarvam') {
@@ -1405,19 +1457,25 @@ async function main() {
       return validateCanonicalTranscript(canonical);
     });
 
-    // Speaker diarization (non-blocking — enriches transcript with speaker labels)
-    try {
-      const diarizeScript = path.join(path.dirname(new URL(import.meta.url).pathname), 'lib', 'speaker_diarization.mjs');
-      console.error('[Pipeline] Running speaker diarization...');
-      await execFile('node', [diarizeScript, '--project-id', projectId, '--project-dir', projectDir, '--input', inputPath, '--max-speakers', '4'], {
-        timeout: 5 * 60 * 1000, maxBuffer: 1024 * 1024 * 4,
-      });
-      console.error('[Pipeline] Speaker diarization complete');
-    } catch (e) {
-      console.error(`[Pipeline] Speaker diarization failed (non-blocking): ${e.message}`);
+    if (resumeIndex < CHECKPOINT_STAGES.indexOf('transcript')) {
+      // Speaker diarization (non-blocking — enriches transcript with speaker labels)
+      try {
+        const diarizeScript = path.join(path.dirname(new URL(import.meta.url).pathname), 'lib', 'speaker_diarization.mjs');
+        console.error('[Pipeline] Running speaker diarization...');
+        await execFile('node', [diarizeScript, '--project-id', projectId, '--project-dir', projectDir, '--input', inputPath, '--max-speakers', '4'], {
+          timeout: 5 * 60 * 1000, maxBuffer: 1024 * 1024 * 4,
+        });
+        console.error('[Pipeline] Speaker diarization complete');
+      } catch (e) {
+        console.error(`[Pipeline] Speaker diarization failed (non-blocking): ${e.message}`);
+      }
+      await writeCheckpoint(projectDir, 'transcript', checkpointKeys.transcript, transcriptPayload);
     }
 
-    removeRanges = await tracker.run('cut-planning', async () => {
+    const planCheckpoint = resumeIndex >= CHECKPOINT_STAGES.indexOf('plan')
+      ? await readCheckpoint(projectDir, 'plan')
+      : null;
+    removeRanges = planCheckpoint ? planCheckpoint.removeRanges : await tracker.run('cut-planning', async () => {
       // Load topic boundaries from semantic chunks (if available) to protect topic transitions
       let topicBoundaries = [];
       try {
@@ -1456,27 +1514,37 @@ async function main() {
       return validateCutRanges(planned.removeRanges, durationUs);
     });
 
-    cutPlanPayload = {
-      planId: `cp-${Date.now()}`,
-      projectId,
-      createdAt: new Date().toISOString(),
-      mode,
-      fallbackPolicy,
-      sourceRef,
-      planner: {
-        model: cutPlannerModel,
-        strategy: 'heuristic-cut-planner-v1',
-      },
-      analysis: cutAnalysis,
-      removeRanges,
-      rationale: removeRanges.map((range) => ({
-        startUs: range.startUs,
-        endUs: range.endUs,
-        reason: range.reason,
-        confidence: range.confidence,
-      })),
-    };
-    cutPlanPayload = validateCutPlan(cutPlanPayload, durationUs);
+    if (planCheckpoint) {
+      cutAnalysis = planCheckpoint.analysis;
+      cutPlanPayload = planCheckpoint.cutPlan;
+    } else {
+      cutPlanPayload = {
+        planId: `cp-${Date.now()}`,
+        projectId,
+        createdAt: new Date().toISOString(),
+        mode,
+        fallbackPolicy,
+        sourceRef,
+        planner: {
+          model: cutPlannerModel,
+          strategy: 'heuristic-cut-planner-v1',
+        },
+        analysis: cutAnalysis,
+        removeRanges,
+        rationale: removeRanges.map((range) => ({
+          startUs: range.startUs,
+          endUs: range.endUs,
+          reason: range.reason,
+          confidence: range.confidence,
+        })),
+      };
+      cutPlanPayload = validateCutPlan(cutPlanPayload, durationUs);
+      await writeCheckpoint(projectDir, 'plan', checkpointKeys.plan, {
+        removeRanges,
+        analysis: cutAnalysis,
+        cutPlan: cutPlanPayload,
+      });
+    }
 
     await tracker.run('artifact-write', async () => {
       await writeJson(transcriptPath, transcriptPayload);
@@ -1523,6 +1591,7 @@ async function main() {
     process.stdout.write(
       `${JSON.stringify(
         {
+          schemaVersion: RESULT_SCHEMA_VERSION,
           ok: true,
           projectId,
           mode,
@@ -1539,6 +1608,7 @@ async function main() {
           },
           stageDurationsMs,
           telemetryPath: telemetry.summaryPath,
+          resumedFrom: resumeFrom || null,
           transcription: {
             adapter: {
               kind: adapter.kind,
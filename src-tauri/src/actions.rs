@@ -0,0 +1,519 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::timeline::{self, FrameSnapPolicy, TimeRange};
+use crate::timeline_merge::{self, subtract_ranges};
+use crate::timeline_stats::content_end_us;
+use crate::{
+    clip_flags, markers, now_iso, read_timeline, speed, write_timeline, Timeline, TimelineClip,
+};
+
+/// Clip types that can be cut in two; markers, captions and titles stay
+/// whole.
+const SPLITTABLE_CLIP_TYPES: &[&str] = &["source_clip", "audio_clip"];
+const DEFAULT_MARKER_TEXT: &str = "Marker";
+
+type ActionFn = fn(&mut Timeline, Value) -> Result<Vec<String>, String>;
+
+/// An editor operation the UI, keyboard macros and the control API all run
+/// through `run_action`, so a shortcut and a Stream Deck button behave alike.
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionSpec {
+    pub id: &'static str,
+    pub description: &'static str,
+    #[serde(skip)]
+    run: ActionFn,
+}
+
+const ACTIONS: &[ActionSpec] = &[
+    ActionSpec {
+        id: "split_at_playhead",
+        description: "Cuts the clips under the playhead in two.",
+        run: split_at_playhead,
+    },
+    ActionSpec {
+        id: "ripple_delete",
+        description: "Removes clips and closes the gap they leave on their tracks.",
+        run: ripple_delete,
+    },
+    ActionSpec {
+        id: "add_marker",
+        description: "Drops a marker at the playhead.",
+        run: add_marker,
+    },
+    ActionSpec {
+        id: "nudge_clip",
+        description: "Moves a clip by microseconds or frames without overlapping its neighbours.",
+        run: nudge_clip,
+    },
+    ActionSpec {
+        id: "sequence",
+        description: "Runs a list of actions as a single edit.",
+        run: sequence,
+    },
+];
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunActionRequest {
+    project_id: String,
+    action_id: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionResult {
+    pub action_id: String,
+    /// Clips and markers the action created, moved or removed.
+    pub clip_ids: Vec<String>,
+    pub timeline: Timeline,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SplitParams {
+    at_us: u64,
+    /// Only these clips; every splittable clip under the playhead when omitted.
+    clip_ids: Option<Vec<String>>,
+    force: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RippleDeleteParams {
+    clip_ids: Vec<String>,
+    /// Delete, and ripple past clips, even when they are locked.
+    force: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddMarkerParams {
+    at_us: u64,
+    text: Option<String>,
+    kind: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NudgeParams {
+    clip_id: String,
+    /// Exactly one of `delta_us` and `delta_frames`; negative moves left.
+    delta_us: Option<i64>,
+    delta_frames: Option<i64>,
+    force: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ActionStep {
+    action_id: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SequenceParams {
+    steps: Vec<ActionStep>,
+}
+
+fn parse<T: DeserializeOwned>(action_id: &str, params: Value) -> Result<T, String> {
+    serde_json::from_value(params)
+        .map_err(|error| format!("Invalid params for {action_id}: {error}"))
+}
+
+fn locked_tracks(timeline: &Timeline) -> Vec<String> {
+    timeline
+        .tracks
+        .iter()
+        .filter(|track| track.locked)
+        .map(|track| track.id.clone())
+        .collect()
+}
+
+/// Checks a clip the action edits directly.
+fn ensure_editable(
+    clip: &TimelineClip,
+    locked_tracks: &[String],
+    force: bool,
+) -> Result<(), String> {
+    if locked_tracks.contains(&clip.track_id) {
+        return Err(format!("Track {} is locked.", clip.track_id));
+    }
+    clip_flags::ensure_unlocked(clip, force)
+}
+
+/// Runs one registered action against `timeline` in memory and returns the
+/// ids it touched. Nothing is saved, so a failing step leaves no trace.
+pub fn apply(
+    timeline: &mut Timeline,
+    action_id: &str,
+    params: Value,
+) -> Result<Vec<String>, String> {
+    let action = ACTIONS
+        .iter()
+        .find(|action| action.id == action_id)
+        .ok_or_else(|| format!("Unknown action: {action_id}"))?;
+    (action.run)(timeline, params)
+}
+
+/// An id for the right half of a split `clip_id` that no clip uses yet.
+fn split_id(timeline: &Timeline, clip_id: &str) -> String {
+    (1..)
+        .map(|n| format!("{clip_id}-split-{n}"))
+        .find(|id| !timeline.clips.iter().any(|clip| &clip.clip_id == id))
+        .unwrap_or_default()
+}
+
+fn split_at_playhead(timeline: &mut Timeline, params: Value) -> Result<Vec<String>, String> {
+    let params = parse::<SplitParams>("split_at_playhead", params)?;
+    let at_us = timeline::snap_to_frame(params.at_us, timeline.fps, FrameSnapPolicy::Nearest);
+    let force = params.force.unwrap_or(false);
+    let locked_tracks = locked_tracks(timeline);
+    if let Some(missing) = params
+        .clip_ids
+        .iter()
+        .flatten()
+        .find(|id| !timeline.clips.iter().any(|clip| &clip.clip_id == *id))
+    {
+        return Err(format!("Clip not found: {missing}"));
+    }
+
+    let targets = timeline
+        .clips
+        .iter()
+        .enumerate()
+        .filter(|(_, clip)| match &params.clip_ids {
+            Some(ids) => ids.contains(&clip.clip_id),
+            None => {
+                SPLITTABLE_CLIP_TYPES.contains(&clip.clip_type.as_str())
+                    && !locked_tracks.contains(&clip.track_id)
+            }
+        })
+        .filter(|(_, clip)| clip.start_us < at_us && at_us < clip.end_us)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    if targets.is_empty() {
+        return Err(format!("No clip to split at {at_us}us."));
+    }
+
+    let mut created = Vec::new();
+    for index in targets.into_iter().rev() {
+        let clip = &timeline.clips[index];
+        if !SPLITTABLE_CLIP_TYPES.contains(&clip.clip_type.as_str()) {
+            return Err(format!("Clip {} cannot be split.", clip.clip_id));
+        }
+        ensure_editable(clip, &locked_tracks, force)?;
+        let source_us =
+            clip.source_start_us + speed::timeline_to_source_offset(clip, at_us - clip.start_us);
+        let (left, mut right) = speed::split_at_source(clip, source_us)
+            .ok_or_else(|| format!("Clip {} is too short to split at {at_us}us.", clip.clip_id))?;
+        right.clip_id = split_id(timeline, &clip.clip_id);
+        created.push(right.clip_id.clone());
+        timeline.clips[index] = left;
+        timeline.clips.insert(index + 1, right);
+    }
+    created.reverse();
+    Ok(created)
+}
+
+/// How much of `gaps` lies before `time_us`.
+fn removed_before(gaps: &[TimeRange], time_us: u64) -> u64 {
+    gaps.iter()
+        .map(|gap| time_us.min(gap.end_us).saturating_sub(gap.start_us))
+        .sum()
+}
+
+/// Deletes the clips, then closes the stretches their tracks are left empty
+/// for by pulling everything after them, on every track, to the left. Refused
+/// when a clip on another track runs into such a stretch, since closing it
+/// would push later clips on that track over it.
+fn ripple_delete(timeline: &mut Timeline, params: Value) -> Result<Vec<String>, String> {
+    let params = parse::<RippleDeleteParams>("ripple_delete", params)?;
+    if params.clip_ids.is_empty() {
+        return Err("Select at least one clip to delete.".to_string());
+    }
+    let force = params.force.unwrap_or(false);
+    let locked_tracks = locked_tracks(timeline);
+    let mut removed = Vec::new();
+    for id in &params.clip_ids {
+        let clip = timeline
+            .clips
+            .iter()
+            .find(|clip| &clip.clip_id == id)
+            .ok_or_else(|| format!("Clip not found: {id}"))?;
+        ensure_editable(clip, &locked_tracks, force)?;
+        removed.push(clip.clone());
+    }
+    let kept = |clip: &&TimelineClip| !params.clip_ids.contains(&clip.clip_id);
+
+    let mut covered = timeline
+        .clips
+        .iter()
+        .filter(kept)
+        .filter(|clip| removed.iter().any(|gone| gone.track_id == clip.track_id))
+        .map(|clip| (clip.start_us, clip.end_us))
+        .collect::<Vec<_>>();
+    covered.sort_unstable();
+    let gaps = timeline::normalize_ranges(
+        removed
+            .iter()
+            .flat_map(|clip| subtract_ranges(clip.start_us, clip.end_us, &covered))
+            .map(|(start_us, end_us)| TimeRange { start_us, end_us })
+            .collect(),
+        u64::MAX,
+    );
+
+    if let Some(clip) = timeline.clips.iter().filter(kept).find(|clip| {
+        clip.clip_type != markers::MARKER_CLIP_TYPE
+            && gaps
+                .iter()
+                .any(|gap| clip.start_us < gap.end_us && gap.start_us < clip.end_us)
+    }) {
+        return Err(format!(
+            "Clip {} on {} overlaps the deleted range; delete or move it first.",
+            clip.clip_id, clip.track_id
+        ));
+    }
+
+    let moved = |clip: &&TimelineClip| removed_before(&gaps, clip.start_us) > 0;
+    if let Some(clip) = timeline
+        .clips
+        .iter()
+        .filter(kept)
+        .find(|clip| moved(clip) && locked_tracks.contains(&clip.track_id))
+    {
+        return Err(format!(
+            "Rippling would move clip {} on locked track {}.",
+            clip.clip_id, clip.track_id
+        ));
+    }
+    if let Some(clip) = timeline
+        .clips
+        .iter()
+        .filter(kept)
+        .find(|clip| moved(clip) && clip.locked && !force)
+    {
+        return Err(format!(
+            "Rippling would move locked clip {}; force it to move anyway.",
+            clip.clip_id
+        ));
+    }
+    timeline
+        .clips
+        .retain(|clip| !params.clip_ids.contains(&clip.clip_id));
+    for clip in &mut timeline.clips {
+        let length = clip.end_us - clip.start_us;
+        clip.start_us -= removed_before(&gaps, clip.start_us);
+        // Chapters are respanned below; everything else keeps its length.
+        clip.end_us = if clip.clip_type == markers::MARKER_CLIP_TYPE {
+            clip.end_us - removed_before(&gaps, clip.end_us)
+        } else {
+            clip.start_us + length
+        };
+    }
+    timeline.duration_us = content_end_us(timeline);
+    markers::respan_chapters(timeline);
+    Ok(params.clip_ids)
+}
+
+fn add_marker(timeline: &mut Timeline, params: Value) -> Result<Vec<String>, String> {
+    let params = parse::<AddMarkerParams>("add_marker", params)?;
+    let id = markers::put_marker(
+        timeline,
+        None,
+        params.kind.as_deref(),
+        params.at_us,
+        params.text.as_deref().unwrap_or(DEFAULT_MARKER_TEXT),
+    )?;
+    Ok(vec![id])
+}
+
+fn nudge_clip(timeline: &mut Timeline, params: Value) -> Result<Vec<String>, String> {
+    let params = parse::<NudgeParams>("nudge_clip", params)?;
+    let delta_us = match (params.delta_us, params.delta_frames) {
+        (Some(delta_us), None) => delta_us,
+        (None, Some(frames)) => frames.saturating_mul(1_000_000) / i64::from(timeline.fps.max(1)),
+        _ => return Err("Give either deltaUs or deltaFrames.".to_string()),
+    };
+    let locked_tracks = locked_tracks(timeline);
+    let index = timeline
+        .clips
+        .iter()
+        .position(|clip| clip.clip_id == params.clip_id)
+        .ok_or_else(|| format!("Clip not found: {}", params.clip_id))?;
+    let clip = &timeline.clips[index];
+    ensure_editable(clip, &locked_tracks, params.force.unwrap_or(false))?;
+    let start_us = clip
+        .start_us
+        .checked_add_signed(delta_us)
+        .ok_or_else(|| format!("Clip {} would start before the timeline.", clip.clip_id))?;
+    let end_us = start_us + (clip.end_us - clip.start_us);
+    if let Some(other) = timeline.clips.iter().find(|other| {
+        other.clip_id != clip.clip_id
+            && other.track_id == clip.track_id
+            && other.start_us < end_us
+            && start_us < other.end_us
+    }) {
+        return Err(format!(
+            "Clip {} would overlap {} on {}.",
+            clip.clip_id, other.clip_id, clip.track_id
+        ));
+    }
+
+    let clip = &mut timeline.clips[index];
+    clip.start_us = start_us;
+    clip.end_us = end_us;
+    timeline.duration_us = content_end_us(timeline);
+    Ok(vec![params.clip_id])
+}
+
+/// Runs the steps in order on the same timeline; the first failure abandons
+/// the whole sequence.
+fn sequence(timeline: &mut Timeline, params: Value) -> Result<Vec<String>, String> {
+    let params = parse::<SequenceParams>("sequence", params)?;
+    if params.steps.is_empty() {
+        return Err("A sequence needs at least one step.".to_string());
+    }
+    let mut clip_ids = Vec::new();
+    for (index, step) in params.steps.into_iter().enumerate() {
+        let ids = apply(timeline, &step.action_id, step.params)
+            .map_err(|error| format!("Step {} ({}) failed: {error}", index + 1, step.action_id))?;
+        for id in ids {
+            if !clip_ids.contains(&id) {
+                clip_ids.push(id);
+            }
+        }
+    }
+    Ok(clip_ids)
+}
+
+/// Applies an action to a copy of `stored` and flags what it changed as
+/// user edits, like a save from the editor, so a pipeline re-run keeps them.
+fn apply_as_user_edit(
+    stored: &Timeline,
+    action_id: &str,
+    params: Value,
+) -> Result<(Timeline, Vec<String>), String> {
+    let mut timeline = stored.clone();
+    let clip_ids = apply(&mut timeline, action_id, params)?;
+    timeline_merge::mark_user_edits(Some(stored), &mut timeline);
+    Ok((timeline, clip_ids))
+}
+
+fn run_action_blocking(request: RunActionRequest) -> Result<ActionResult, String> {
+    let _save = timeline_merge::lock_saves();
+    let stored = read_timeline(&request.project_id)?;
+    let (mut timeline, clip_ids) = apply_as_user_edit(&stored, &request.action_id, request.params)?;
+    timeline.version = timeline.version.saturating_add(1);
+    timeline.updated_at = now_iso();
+    write_timeline(&timeline)?;
+    Ok(ActionResult {
+        action_id: request.action_id,
+        clip_ids,
+        timeline,
+    })
+}
+
+/// The actions `run_action` accepts, for shortcut and macro editors.
+#[tauri::command]
+pub fn list_actions() -> Vec<ActionSpec> {
+    ACTIONS.to_vec()
+}
+
+/// Runs a registered action (or a `sequence` of them) and saves the result
+/// as one timeline version.
+#[tauri::command]
+pub async fn run_action(request: RunActionRequest) -> Result<ActionResult, String> {
+    tauri::async_runtime::spawn_blocking(move || run_action_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{self, timeline, track};
+    use serde_json::json;
+
+    fn clip(id: &str, track_id: &str, clip_type: &str, start_us: u64, end_us: u64) -> TimelineClip {
+        let mut clip = test_fixtures::clip(id, track_id, start_us, end_us);
+        clip.clip_type = clip_type.to_string();
+        clip
+    }
+
+    #[test]
+    fn sequence_splits_ripples_and_nudges() {
+        let mut timeline = timeline(
+            30,
+            vec![track("video", "video", 0), track("captions", "caption", 1)],
+            vec![
+                clip("v1", "video", "source_clip", 0, 2_000_000),
+                clip("v2", "video", "source_clip", 2_000_000, 4_000_000),
+                clip("c1", "captions", "caption", 2_500_000, 3_000_000),
+            ],
+        );
+        let steps = json!({ "steps": [
+            { "actionId": "split_at_playhead", "params": { "atUs": 1_000_000 } },
+            { "actionId": "ripple_delete", "params": { "clipIds": ["v1-split-1"] } },
+            { "actionId": "nudge_clip", "params": { "clipId": "c1", "deltaFrames": 3 } },
+        ]});
+        let ids = apply(&mut timeline, "sequence", steps).unwrap();
+        assert_eq!(ids, ["v1-split-1", "c1"]);
+        let spans = timeline
+            .clips
+            .iter()
+            .map(|clip| (clip.clip_id.as_str(), clip.start_us, clip.end_us))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            [
+                ("v1", 0, 1_000_000),
+                ("v2", 1_000_000, 3_000_000),
+                ("c1", 1_600_000, 2_100_000)
+            ]
+        );
+        assert_eq!(timeline.duration_us, 3_000_000);
+
+        // c1 sits over the stretch deleting v2 would close.
+        let crossing = json!({ "clipIds": ["v2"] });
+        let error = apply(&mut timeline, "ripple_delete", crossing).unwrap_err();
+        assert!(error.contains("c1"), "{error}");
+        assert_eq!(timeline.clips.len(), 3);
+
+        let overlap = json!({ "clipId": "v2", "deltaUs": -1 });
+        assert!(apply(&mut timeline, "nudge_clip", overlap).is_err());
+        assert!(apply(&mut timeline, "explode", Value::Null).is_err());
+    }
+
+    #[test]
+    fn action_edits_survive_a_pipeline_rerun() {
+        let generated = timeline(
+            30,
+            vec![track("video", "video", 0)],
+            vec![
+                clip("v1", "video", "source_clip", 0, 2_000_000),
+                clip("v2", "video", "source_clip", 2_000_000, 4_000_000),
+            ],
+        );
+        let split = json!({ "atUs": 1_000_000 });
+        let (edited, _) = apply_as_user_edit(&generated, "split_at_playhead", split).unwrap();
+        assert!(edited.clips[..2].iter().all(timeline_merge::is_user_edited));
+        assert!(!timeline_merge::is_user_edited(&edited.clips[2]));
+
+        let mut rerun = generated.clone();
+        let report = timeline_merge::preserve_user_edits(&edited, &mut rerun);
+        assert_eq!(report.preserved, ["v1", "v1-split-1"]);
+        let kept = rerun
+            .clips
+            .iter()
+            .find(|clip| clip.clip_id == "v1")
+            .unwrap();
+        assert_eq!((kept.source_start_us, kept.source_end_us), (0, 1_000_000));
+    }
+}
@@ -0,0 +1,425 @@
+use std::collections::BTreeSet;
+use std::process::Command;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::ffmpeg;
+use crate::hardware::{powershell, probe};
+
+/// The id that selects the system default on every backend.
+pub const DEFAULT_DEVICE_ID: &str = "default";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AudioDirection {
+    Input,
+    Output,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioDevice {
+    /// What `start_voiceover_recording` takes as `device` for inputs.
+    pub id: String,
+    pub name: String,
+    pub direction: AudioDirection,
+    pub is_default: bool,
+    /// Rates the device reports, ascending; empty when the platform doesn't
+    /// say.
+    pub sample_rates: Vec<u32>,
+}
+
+impl AudioDevice {
+    fn system_default(direction: AudioDirection) -> Self {
+        AudioDevice {
+            id: DEFAULT_DEVICE_ID.to_string(),
+            name: match direction {
+                AudioDirection::Input => "System default input",
+                AudioDirection::Output => "System default output",
+            }
+            .to_string(),
+            direction,
+            is_default: true,
+            sample_rates: Vec::new(),
+        }
+    }
+}
+
+/// ffmpeg's device listings exit non-zero by design and print to stderr.
+fn ffmpeg_listing(args: &[&str]) -> String {
+    Command::new(ffmpeg::ffmpeg_binary())
+        .arg("-hide_banner")
+        .args(args)
+        .output()
+        .map(|output| {
+            format!(
+                "{}\n{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Devices from `system_profiler SPAudioDataType -json`. A device with both
+/// inputs and outputs is listed once per direction. AVFoundation accepts
+/// the device name as its id.
+fn parse_system_profiler(raw: &str) -> Vec<AudioDevice> {
+    let parsed = serde_json::from_str::<Value>(raw).unwrap_or(Value::Null);
+    let items = parsed
+        .get("SPAudioDataType")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|group| group.get("_items")?.as_array())
+        .flatten();
+    let mut devices = Vec::new();
+    for item in items {
+        let Some(name) = item.get("_name").and_then(Value::as_str) else {
+            continue;
+        };
+        let sample_rates = item
+            .get("coreaudio_device_srate")
+            .and_then(Value::as_f64)
+            .map(|rate| vec![rate.round() as u32])
+            .unwrap_or_default();
+        for (direction, channels, default_key) in [
+            (
+                AudioDirection::Input,
+                "coreaudio_device_input",
+                "coreaudio_default_audio_input_device",
+            ),
+            (
+                AudioDirection::Output,
+                "coreaudio_device_output",
+                "coreaudio_default_audio_output_device",
+            ),
+        ] {
+            if item.get(channels).and_then(Value::as_u64).unwrap_or(0) == 0 {
+                continue;
+            }
+            devices.push(AudioDevice {
+                id: name.to_string(),
+                name: name.to_string(),
+                direction,
+                is_default: item.get(default_key).and_then(Value::as_str) == Some("spaudio_yes"),
+                sample_rates: sample_rates.clone(),
+            });
+        }
+    }
+    devices
+}
+
+/// Rate of a pulse sample spec like `s16le 2ch 44100Hz`.
+fn pulse_rate(spec: &str) -> Option<u32> {
+    spec.split_whitespace()
+        .find_map(|part| part.strip_suffix("Hz")?.parse().ok())
+}
+
+/// Devices from `pactl list short sources|sinks`; monitors of outputs are
+/// left out of the inputs.
+fn parse_pactl_short(
+    listing: &str,
+    direction: AudioDirection,
+    default_name: Option<&str>,
+) -> Vec<AudioDevice> {
+    listing
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split('\t');
+            let id = columns.nth(1)?.trim();
+            let rate = columns.nth(1).and_then(pulse_rate);
+            (!id.is_empty() && !id.ends_with(".monitor")).then(|| AudioDevice {
+                id: id.to_string(),
+                name: id.to_string(),
+                direction,
+                is_default: Some(id) == default_name,
+                sample_rates: rate.into_iter().collect(),
+            })
+        })
+        .collect()
+}
+
+/// Devices from `ffmpeg -sources pulse` or `-sinks pulse`: `* id
+/// [description]`, `*` marking the default.
+fn parse_ffmpeg_pulse(listing: &str, direction: AudioDirection) -> Vec<AudioDevice> {
+    listing
+        .lines()
+        .filter(|line| line.starts_with("  ") || line.starts_with("* "))
+        .filter_map(|line| {
+            let is_default = line.starts_with('*');
+            let line = line.trim_start_matches(['*', ' ']);
+            let (id, rest) = line.split_once(' ').unwrap_or((line, ""));
+            let name = rest.trim().trim_start_matches('[').trim_end_matches(']');
+            (!id.is_empty() && !id.ends_with(".monitor")).then(|| AudioDevice {
+                id: id.to_string(),
+                name: if name.is_empty() { id } else { name }.to_string(),
+                direction,
+                is_default,
+                sample_rates: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// Input names from `ffmpeg -list_devices true -f dshow -i dummy`: quoted
+/// names tagged `(audio)`, or listed under `DirectShow audio devices` by
+/// older builds.
+fn parse_dshow(listing: &str) -> Vec<String> {
+    let mut in_audio_section = false;
+    let mut names = Vec::new();
+    for line in listing.lines() {
+        if line.contains("DirectShow audio devices") {
+            in_audio_section = true;
+            continue;
+        }
+        if line.contains("DirectShow video devices") {
+            in_audio_section = false;
+            continue;
+        }
+        if line.contains("Alternative name") {
+            continue;
+        }
+        let line = line.trim_end();
+        let Some(name) = line.split('"').nth(1) else {
+            continue;
+        };
+        if line.ends_with("(audio)") || (in_audio_section && !line.ends_with("(video)")) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Every `rate=` value in a dshow `-list_options` listing.
+fn parse_dshow_rates(listing: &str) -> Vec<u32> {
+    listing
+        .split("rate=")
+        .skip(1)
+        .filter_map(|rest| {
+            rest.trim_start()
+                .split(|c: char| !c.is_ascii_digit())
+                .next()?
+                .parse()
+                .ok()
+        })
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// `Get-PnpDevice` audio endpoints; render endpoints' instance ids carry
+/// `{0.0.0.` and capture ones `{0.0.1.`.
+fn windows_output_names() -> Vec<String> {
+    powershell(
+        "Get-PnpDevice -Class AudioEndpoint -Status OK | \
+         ForEach-Object { $_.InstanceId + '|' + $_.FriendlyName }",
+    )
+    .unwrap_or_default()
+    .lines()
+    .filter_map(|line| {
+        let (instance, name) = line.split_once('|')?;
+        instance
+            .contains("{0.0.0.")
+            .then(|| name.trim().to_string())
+    })
+    .filter(|name| !name.is_empty())
+    .collect()
+}
+
+fn macos_devices() -> Vec<AudioDevice> {
+    probe("system_profiler", &["SPAudioDataType", "-json"])
+        .map(|raw| parse_system_profiler(&raw))
+        .unwrap_or_default()
+}
+
+fn linux_devices() -> Vec<AudioDevice> {
+    let info = probe("pactl", &["info"]).unwrap_or_default();
+    let default_of = |key: &str| {
+        info.lines()
+            .find_map(|line| line.strip_prefix(key))
+            .map(str::trim)
+            .map(str::to_string)
+    };
+    let mut devices = Vec::new();
+    for (kind, direction, default_key, ffmpeg_flag) in [
+        (
+            "sources",
+            AudioDirection::Input,
+            "Default Source:",
+            "-sources",
+        ),
+        ("sinks", AudioDirection::Output, "Default Sink:", "-sinks"),
+    ] {
+        match probe("pactl", &["list", "short", kind]) {
+            Some(listing) => devices.extend(parse_pactl_short(
+                &listing,
+                direction,
+                default_of(default_key).as_deref(),
+            )),
+            None => devices.extend(parse_ffmpeg_pulse(
+                &ffmpeg_listing(&[ffmpeg_flag, "pulse"]),
+                direction,
+            )),
+        }
+    }
+    devices
+}
+
+fn windows_devices() -> Vec<AudioDevice> {
+    let inputs = parse_dshow(&ffmpeg_listing(&[
+        "-list_devices",
+        "true",
+        "-f",
+        "dshow",
+        "-i",
+        "dummy",
+    ]));
+    let mut devices = inputs
+        .into_iter()
+        .enumerate()
+        .map(|(index, name)| {
+            let options = ffmpeg_listing(&[
+                "-list_options",
+                "true",
+                "-f",
+                "dshow",
+                "-i",
+                &format!("audio={name}"),
+            ]);
+            AudioDevice {
+                id: name.clone(),
+                name,
+                direction: AudioDirection::Input,
+                // DirectShow lists the default capture device first.
+                is_default: index == 0,
+                sample_rates: parse_dshow_rates(&options),
+            }
+        })
+        .collect::<Vec<_>>();
+    // Outputs are only named; playback goes to the system default.
+    devices.push(AudioDevice::system_default(AudioDirection::Output));
+    devices.extend(windows_output_names().into_iter().map(|name| AudioDevice {
+        id: name.clone(),
+        name,
+        direction: AudioDirection::Output,
+        is_default: false,
+        sample_rates: Vec::new(),
+    }));
+    devices
+}
+
+/// Inputs and outputs, each direction led by its default. A direction the
+/// platform lists nothing for gets a `default` entry.
+pub fn audio_devices() -> Vec<AudioDevice> {
+    let mut devices = if cfg!(target_os = "macos") {
+        macos_devices()
+    } else if cfg!(windows) {
+        windows_devices()
+    } else {
+        linux_devices()
+    };
+    for direction in [AudioDirection::Input, AudioDirection::Output] {
+        if !devices.iter().any(|device| device.direction == direction) {
+            devices.push(AudioDevice::system_default(direction));
+        }
+    }
+    devices.sort_by_key(|device| {
+        (
+            device.direction == AudioDirection::Output,
+            !device.is_default,
+        )
+    });
+    devices
+}
+
+/// The input used when a recording doesn't name one.
+pub fn default_input() -> Option<AudioDevice> {
+    audio_devices()
+        .into_iter()
+        .find(|device| device.direction == AudioDirection::Input)
+}
+
+/// `-f <format> -i <device>` for an input on this platform's capture
+/// backend: AVFoundation, DirectShow or PulseAudio.
+pub fn capture_input(device: Option<&str>) -> Result<Vec<String>, String> {
+    let device = device.map(str::trim).filter(|device| !device.is_empty());
+    let (format, input) = if cfg!(target_os = "macos") {
+        let name = device.unwrap_or(DEFAULT_DEVICE_ID);
+        ("avfoundation", format!(":{name}"))
+    } else if cfg!(windows) {
+        // DirectShow has no default alias; use the first capture device.
+        let name = match device.filter(|device| *device != DEFAULT_DEVICE_ID) {
+            Some(device) => device.to_string(),
+            None => default_input()
+                .map(|device| device.id)
+                .filter(|id| id != DEFAULT_DEVICE_ID)
+                .ok_or_else(|| "No audio input device found.".to_string())?,
+        };
+        ("dshow", format!("audio={name}"))
+    } else {
+        ("pulse", device.unwrap_or(DEFAULT_DEVICE_ID).to_string())
+    };
+    Ok(vec![
+        "-f".to_string(),
+        format.to_string(),
+        "-i".to_string(),
+        input,
+    ])
+}
+
+/// Audio inputs and outputs with default flags and sample rates, for the
+/// voiceover recorder and the preview monitoring output.
+#[tauri::command]
+pub async fn list_audio_devices() -> Result<Vec<AudioDevice>, String> {
+    tauri::async_runtime::spawn_blocking(audio_devices)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_platform_device_listings() {
+        let profiler = r#"{"SPAudioDataType":[{"_name":"devices","_items":[
+            {"_name":"MacBook Pro Microphone","coreaudio_device_input":1,
+             "coreaudio_device_srate":48000,
+             "coreaudio_default_audio_input_device":"spaudio_yes"},
+            {"_name":"USB Audio CODEC","coreaudio_device_input":2,
+             "coreaudio_device_output":2,"coreaudio_device_srate":44100,
+             "coreaudio_default_audio_output_device":"spaudio_yes"}]}]}"#;
+        let devices = parse_system_profiler(profiler);
+        assert_eq!(devices.len(), 3);
+        assert!(devices[0].is_default);
+        assert_eq!(devices[2].direction, AudioDirection::Output);
+        assert!(devices[2].is_default && !devices[1].is_default);
+        assert_eq!(devices[2].sample_rates, vec![44100]);
+
+        let sources = "0\talsa_output.pci.analog-stereo.monitor\tmodule-alsa-card.c\t\
+                       s16le 2ch 44100Hz\tSUSPENDED\n\
+                       1\talsa_input.pci.analog-stereo\tmodule-alsa-card.c\t\
+                       s16le 2ch 48000Hz\tRUNNING";
+        let devices = parse_pactl_short(
+            sources,
+            AudioDirection::Input,
+            Some("alsa_input.pci.analog-stereo"),
+        );
+        assert_eq!(devices.len(), 1);
+        assert!(devices[0].is_default);
+        assert_eq!(devices[0].sample_rates, vec![48000]);
+
+        let dshow = "\
+[dshow @ 0x1] \"Integrated Camera\" (video)
+[dshow @ 0x1]   Alternative name \"@device_pnp_camera\"
+[dshow @ 0x1] \"Microphone (Realtek Audio)\" (audio)
+[dshow @ 0x1]   Alternative name \"@device_cm_mic\"
+";
+        assert_eq!(parse_dshow(dshow), vec!["Microphone (Realtek Audio)"]);
+        let options = "[dshow @ 0x1]   min ch=1 bits=8 rate= 11025 max ch=2 bits=16 rate= 44100\n\
+                       [dshow @ 0x1]   ch= 2, bits=16, rate= 48000";
+        assert_eq!(parse_dshow_rates(options), vec![11025, 44100, 48000]);
+    }
+}
@@ -0,0 +1,484 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::ffmpeg::ffmpeg_binary;
+use crate::media::resolve_source_path;
+use crate::settings::{self, AudioEnhanceSettings};
+use crate::{
+    clip_flags, now_iso, path_safety, read_timeline, shutdown, timeline_merge, write_timeline,
+    Timeline,
+};
+
+/// `afftdn` noise reduction, in dB.
+const STRENGTH_DB_RANGE: std::ops::RangeInclusive<u32> = 1..=40;
+const DEFAULT_STRENGTH_DB: u32 = 12;
+const PREVIEW_US: u64 = 10_000_000;
+const MAX_EXTERNAL_TIMEOUT_SECS: u64 = 4 * 3600;
+const ENHANCED_DIR: &str = "audio_enhanced";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioEnhanceMethod {
+    /// ffmpeg's FFT denoiser; needs nothing extra.
+    Afftdn,
+    /// ffmpeg `arnndn` with the model file from settings.
+    Rnnoise,
+    /// The enhancer command configured in settings.
+    External,
+}
+
+impl AudioEnhanceMethod {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Afftdn => "afftdn",
+            Self::Rnnoise => "rnnoise",
+            Self::External => "external",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnhanceAudioRequest {
+    project_id: String,
+    /// Source reference to enhance; defaults to the project's ingested media.
+    asset_id: Option<String>,
+    method: Option<AudioEnhanceMethod>,
+    /// Noise reduction for `afftdn`, in dB.
+    strength_db: Option<u32>,
+    /// Where the before/after previews start, in source time.
+    preview_start_us: Option<u64>,
+    /// Switch enhancement on for every clip of the asset. Defaults to true.
+    apply_to_clips: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetClipAudioEnhanceRequest {
+    project_id: String,
+    clip_id: String,
+    enabled: bool,
+    /// Change the clip even when it is locked.
+    force: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListEnhancedAudioRequest {
+    project_id: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EnhanceOptions {
+    pub method: AudioEnhanceMethod,
+    pub strength_db: u32,
+    pub preview_start_us: u64,
+}
+
+impl Default for EnhanceOptions {
+    fn default() -> Self {
+        Self {
+            method: AudioEnhanceMethod::Afftdn,
+            strength_db: DEFAULT_STRENGTH_DB,
+            preview_start_us: 0,
+        }
+    }
+}
+
+/// An asset's enhanced audio track, kept in `audio_enhanced/manifest.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnhancedAudio {
+    pub source_ref: String,
+    pub source_path: String,
+    pub method: AudioEnhanceMethod,
+    /// Same timing as the source, so clips read it at their source times.
+    pub audio_path: String,
+    pub preview_before_path: String,
+    pub preview_after_path: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnhanceAudioResult {
+    pub enhanced: EnhancedAudio,
+    /// Source clips switched to the enhanced audio.
+    pub enabled_clips: usize,
+}
+
+fn enhanced_dir(project_id: &str) -> Result<PathBuf, String> {
+    Ok(path_safety::project_dir(project_id)?.join(ENHANCED_DIR))
+}
+
+fn manifest_path(project_id: &str) -> Result<PathBuf, String> {
+    Ok(enhanced_dir(project_id)?.join("manifest.json"))
+}
+
+fn read_manifest(project_id: &str) -> Result<BTreeMap<String, EnhancedAudio>, String> {
+    let path = manifest_path(project_id)?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let raw = fs::read_to_string(&path)
+        .map_err(|error| format!("Failed reading enhanced audio manifest: {error}"))?;
+    serde_json::from_str(&raw)
+        .map_err(|error| format!("Invalid enhanced audio manifest JSON: {error}"))
+}
+
+fn write_manifest(
+    project_id: &str,
+    manifest: &BTreeMap<String, EnhancedAudio>,
+) -> Result<(), String> {
+    let serialized = serde_json::to_string_pretty(manifest)
+        .map_err(|error| format!("Enhanced audio manifest serialize error: {error}"))?;
+    fs::write(manifest_path(project_id)?, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing enhanced audio manifest: {error}"))
+}
+
+/// File stem for an asset; source refs can be long paths.
+fn asset_stem(source_ref: &str) -> String {
+    Sha256::digest(source_ref.as_bytes())
+        .iter()
+        .take(6)
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn ffmpeg_filter(
+    method: AudioEnhanceMethod,
+    strength_db: u32,
+    settings: &AudioEnhanceSettings,
+) -> Result<String, String> {
+    match method {
+        AudioEnhanceMethod::Afftdn => Ok(format!("highpass=f=80,afftdn=nr={strength_db}:nf=-40")),
+        AudioEnhanceMethod::Rnnoise => {
+            let model = settings
+                .rnnoise_model
+                .as_deref()
+                .map(str::trim)
+                .filter(|model| !model.is_empty())
+                .ok_or_else(|| {
+                    "RNNoise needs a model file; set audioEnhance.rnnoiseModel in settings."
+                        .to_string()
+                })?;
+            if !Path::new(model).is_file() {
+                return Err(format!("RNNoise model not found: {model}"));
+            }
+            // The filter graph parser treats `:` and `\` in the path specially.
+            let escaped = model.replace('\\', "/").replace(':', "\\:");
+            Ok(format!("arnndn=m='{escaped}'"))
+        }
+        AudioEnhanceMethod::External => Err("External enhancers do not use ffmpeg.".to_string()),
+    }
+}
+
+fn run_ffmpeg(args: &[&str], stage: &str) -> Result<(), String> {
+    let output = Command::new(ffmpeg_binary())
+        .args(args)
+        .output()
+        .map_err(|error| format!("Failed to execute ffmpeg: {error}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "ffmpeg {stage} failed: {}",
+            stderr.trim().chars().take(300).collect::<String>()
+        ));
+    }
+    Ok(())
+}
+
+fn run_external(
+    settings: &AudioEnhanceSettings,
+    input: &Path,
+    output: &Path,
+) -> Result<(), String> {
+    let program = settings
+        .external_command
+        .as_deref()
+        .map(str::trim)
+        .filter(|program| !program.is_empty())
+        .ok_or_else(|| {
+            "No external enhancer configured; set audioEnhance.externalCommand in settings."
+                .to_string()
+        })?;
+    let args = settings
+        .external_args
+        .iter()
+        .map(|arg| {
+            arg.replace("{input}", &input.to_string_lossy())
+                .replace("{output}", &output.to_string_lossy())
+        })
+        .collect::<Vec<_>>();
+    let mut command = Command::new(program);
+    command.args(&args);
+    let timeout = Duration::from_secs(
+        settings
+            .external_timeout_secs
+            .clamp(1, MAX_EXTERNAL_TIMEOUT_SECS),
+    );
+    let result = shutdown::run_tracked_with_timeout(&mut command, Vec::new(), timeout)
+        .map_err(|error| format!("Failed to run audio enhancer `{program}`: {error}"))?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(format!(
+            "Audio enhancer `{program}` exited with {}: {}",
+            result.status,
+            stderr.trim().chars().take(300).collect::<String>()
+        ));
+    }
+    if !output.is_file() {
+        return Err(format!(
+            "Audio enhancer `{program}` did not write {}; pass {{output}} in its args.",
+            output.display()
+        ));
+    }
+    Ok(())
+}
+
+fn write_preview(input: &Path, start_us: u64, output: &Path) -> Result<(), String> {
+    let start = format!("{:.3}", start_us as f64 / 1_000_000.0);
+    let length = format!("{:.3}", PREVIEW_US as f64 / 1_000_000.0);
+    run_ffmpeg(
+        &[
+            "-y",
+            "-hide_banner",
+            "-loglevel",
+            "error",
+            "-ss",
+            &start,
+            "-t",
+            &length,
+            "-i",
+            &input.to_string_lossy(),
+            "-vn",
+            "-c:a",
+            "aac",
+            "-b:a",
+            "160k",
+            &output.to_string_lossy(),
+        ],
+        "preview",
+    )
+}
+
+/// Writes a cleaned-up copy of an asset's audio plus short before/after
+/// previews, and records it in the manifest. Re-running replaces the
+/// asset's previous result.
+pub fn enhance_asset(
+    project_id: &str,
+    source_ref: &str,
+    source_path: &Path,
+    options: EnhanceOptions,
+) -> Result<EnhancedAudio, String> {
+    if !STRENGTH_DB_RANGE.contains(&options.strength_db) {
+        return Err(format!(
+            "Strength must be {}-{} dB, got {}.",
+            STRENGTH_DB_RANGE.start(),
+            STRENGTH_DB_RANGE.end(),
+            options.strength_db
+        ));
+    }
+    let settings = settings::read_app_settings()?.audio_enhance;
+    let dir = enhanced_dir(project_id)?;
+    fs::create_dir_all(&dir)
+        .map_err(|error| format!("Failed creating enhanced audio dir: {error}"))?;
+    let stem = format!("{}-{}", asset_stem(source_ref), options.method.name());
+
+    let audio_path = match options.method {
+        AudioEnhanceMethod::External => {
+            let output = dir.join(format!("{stem}.wav"));
+            run_external(&settings, source_path, &output)?;
+            output
+        }
+        method => {
+            let filter = ffmpeg_filter(method, options.strength_db, &settings)?;
+            let output = dir.join(format!("{stem}.flac"));
+            run_ffmpeg(
+                &[
+                    "-y",
+                    "-hide_banner",
+                    "-loglevel",
+                    "error",
+                    "-i",
+                    &source_path.to_string_lossy(),
+                    "-vn",
+                    "-af",
+                    &filter,
+                    "-c:a",
+                    "flac",
+                    &output.to_string_lossy(),
+                ],
+                "audio enhancement",
+            )?;
+            output
+        }
+    };
+
+    let before = dir.join(format!("{stem}-before.m4a"));
+    let after = dir.join(format!("{stem}-after.m4a"));
+    write_preview(source_path, options.preview_start_us, &before)?;
+    write_preview(&audio_path, options.preview_start_us, &after)?;
+
+    let enhanced = EnhancedAudio {
+        source_ref: source_ref.to_string(),
+        source_path: source_path.to_string_lossy().to_string(),
+        method: options.method,
+        audio_path: audio_path.to_string_lossy().to_string(),
+        preview_before_path: before.to_string_lossy().to_string(),
+        preview_after_path: after.to_string_lossy().to_string(),
+        created_at: now_iso(),
+    };
+    let mut manifest = read_manifest(project_id)?;
+    manifest.insert(source_ref.to_string(), enhanced.clone());
+    write_manifest(project_id, &manifest)?;
+    Ok(enhanced)
+}
+
+fn enhance_effect(enhanced: &EnhancedAudio, enabled: bool) -> Value {
+    json!({
+        "enabled": enabled,
+        "method": enhanced.method,
+        "audioPath": enhanced.audio_path,
+    })
+}
+
+/// Switches every source clip of `enhanced`'s asset to its enhanced audio,
+/// skipping clips on locked tracks. Returns the clips switched.
+pub fn enable_for_clips(timeline: &mut Timeline, enhanced: &EnhancedAudio) -> usize {
+    let mut enabled = 0;
+    for clip in &mut timeline.clips {
+        if clip.clip_type != "source_clip" || clip.source_ref != enhanced.source_ref || clip.locked
+        {
+            continue;
+        }
+        if timeline
+            .tracks
+            .iter()
+            .any(|track| track.id == clip.track_id && track.locked)
+        {
+            continue;
+        }
+        if !clip.effects.is_object() {
+            clip.effects = json!({});
+        }
+        if let Some(effects) = clip.effects.as_object_mut() {
+            effects.insert("audioEnhance".to_string(), enhance_effect(enhanced, true));
+            enabled += 1;
+        }
+    }
+    enabled
+}
+
+/// Enables enhancement on the project's timeline for an asset just
+/// enhanced. A project without a timeline yet has nothing to switch.
+pub fn enable_on_timeline(project_id: &str, enhanced: &EnhancedAudio) -> Result<usize, String> {
+    let _save = timeline_merge::lock_saves();
+    let Ok(mut timeline) = read_timeline(project_id) else {
+        return Ok(0);
+    };
+    let enabled = enable_for_clips(&mut timeline, enhanced);
+    if enabled > 0 {
+        timeline.version = timeline.version.saturating_add(1);
+        timeline.updated_at = now_iso();
+        write_timeline(&timeline)?;
+    }
+    Ok(enabled)
+}
+
+fn enhance_audio_blocking(request: EnhanceAudioRequest) -> Result<EnhanceAudioResult, String> {
+    let source_ref = request
+        .asset_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|asset_id| !asset_id.is_empty())
+        .unwrap_or("source-video")
+        .to_string();
+    let source_path = resolve_source_path(&request.project_id, Some(&source_ref))?;
+    let options = EnhanceOptions {
+        method: request.method.unwrap_or(AudioEnhanceMethod::Afftdn),
+        strength_db: request.strength_db.unwrap_or(DEFAULT_STRENGTH_DB),
+        preview_start_us: request.preview_start_us.unwrap_or(0),
+    };
+    let enhanced = enhance_asset(&request.project_id, &source_ref, &source_path, options)?;
+    let enabled_clips = if request.apply_to_clips.unwrap_or(true) {
+        enable_on_timeline(&request.project_id, &enhanced)?
+    } else {
+        0
+    };
+    Ok(EnhanceAudioResult {
+        enhanced,
+        enabled_clips,
+    })
+}
+
+fn set_clip_audio_enhance_blocking(request: SetClipAudioEnhanceRequest) -> Result<Value, String> {
+    let manifest = read_manifest(&request.project_id)?;
+    let _save = timeline_merge::lock_saves();
+    let mut timeline = read_timeline(&request.project_id)?;
+    let clip = timeline
+        .clips
+        .iter_mut()
+        .find(|clip| clip.clip_id == request.clip_id && clip.clip_type == "source_clip")
+        .ok_or_else(|| format!("Source clip not found: {}", request.clip_id))?;
+    if let Some(track) = timeline
+        .tracks
+        .iter()
+        .find(|track| track.id == clip.track_id && track.locked)
+    {
+        return Err(format!("Track {} is locked.", track.id));
+    }
+    clip_flags::ensure_unlocked(clip, request.force.unwrap_or(false))?;
+    let enhanced = manifest.get(&clip.source_ref).ok_or_else(|| {
+        format!(
+            "No enhanced audio for {}; run enhance_audio on it first.",
+            clip.source_ref
+        )
+    })?;
+    if !clip.effects.is_object() {
+        clip.effects = json!({});
+    }
+    let effect = enhance_effect(enhanced, request.enabled);
+    if let Some(effects) = clip.effects.as_object_mut() {
+        effects.insert("audioEnhance".to_string(), effect.clone());
+    }
+    timeline.version = timeline.version.saturating_add(1);
+    timeline.updated_at = now_iso();
+    write_timeline(&timeline)?;
+    Ok(effect)
+}
+
+/// Cleans up an asset's audio with ffmpeg or the configured enhancer and
+/// writes before/after previews. Clips of the asset render with the
+/// enhanced audio unless `applyToClips` is false.
+#[tauri::command]
+pub async fn enhance_audio(request: EnhanceAudioRequest) -> Result<EnhanceAudioResult, String> {
+    tauri::async_runtime::spawn_blocking(move || enhance_audio_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// Turns enhanced audio on or off for one clip.
+#[tauri::command]
+pub async fn set_clip_audio_enhance(request: SetClipAudioEnhanceRequest) -> Result<Value, String> {
+    tauri::async_runtime::spawn_blocking(move || set_clip_audio_enhance_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// Enhanced assets of a project, by source reference.
+#[tauri::command]
+pub async fn list_enhanced_audio(
+    request: ListEnhancedAudioRequest,
+) -> Result<BTreeMap<String, EnhancedAudio>, String> {
+    tauri::async_runtime::spawn_blocking(move || read_manifest(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,176 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+use crate::render_history::timeline_content_hash;
+use crate::{
+    now_iso, read_timeline, shutdown, speed, timeline_file_path, timeline_merge, timeline_stats,
+    write_timeline, Timeline,
+};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutosaveTimelineRequest {
+    timeline: Timeline,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryRequest {
+    project_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryStatus {
+    /// An autosave exists that is newer than, and differs from, the saved
+    /// timeline. The UI should offer `restore_autosave` / `discard_autosave`.
+    pub recoverable: bool,
+    pub has_autosave: bool,
+    /// Unix seconds, like the timeline's `updatedAt`.
+    pub autosaved_at: Option<String>,
+    pub committed_at: Option<String>,
+    pub committed_version: Option<u32>,
+    pub autosave_clip_count: Option<usize>,
+}
+
+fn autosave_path(project_id: &str) -> Result<PathBuf, String> {
+    Ok(timeline_file_path(project_id)?.with_file_name("timeline.autosave.json"))
+}
+
+fn modified_secs(path: &Path) -> Option<u64> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+}
+
+fn read_autosave(project_id: &str) -> Result<Option<Timeline>, String> {
+    let file_path = autosave_path(project_id)?;
+    if !file_path.exists() {
+        return Ok(None);
+    }
+    let raw = fs::read_to_string(&file_path)
+        .map_err(|error| format!("Failed reading autosave: {error}"))?;
+    serde_json::from_str::<Timeline>(&raw)
+        .map(Some)
+        .map_err(|error| format!("Invalid autosave JSON: {error}"))
+}
+
+/// Drops the autosave once its changes are saved or discarded.
+pub fn clear_autosave(project_id: &str) -> Result<(), String> {
+    let file_path = autosave_path(project_id)?;
+    if file_path.exists() {
+        fs::remove_file(&file_path)
+            .map_err(|error| format!("Failed removing autosave: {error}"))?;
+    }
+    Ok(())
+}
+
+fn autosave_blocking(request: AutosaveTimelineRequest) -> Result<String, String> {
+    let timeline = request.timeline;
+    if timeline.project_id.trim().is_empty() {
+        return Err("Timeline has no project id.".to_string());
+    }
+    let file_path = autosave_path(&timeline.project_id)?;
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating timeline dir: {error}"))?;
+    }
+    let serialized = serde_json::to_string_pretty(&timeline)
+        .map_err(|error| format!("Autosave serialize error: {error}"))?;
+    let _write = shutdown::begin_write();
+    // Write then rename so a crash mid-write can't leave a torn autosave.
+    let temp_path = file_path.with_extension("json.tmp");
+    fs::write(&temp_path, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing autosave: {error}"))?;
+    fs::rename(&temp_path, &file_path)
+        .map_err(|error| format!("Failed writing autosave: {error}"))?;
+    Ok(now_iso())
+}
+
+fn check_recovery_blocking(project_id: &str) -> Result<RecoveryStatus, String> {
+    let committed = read_timeline(project_id).ok();
+    let autosave = match read_autosave(project_id) {
+        Ok(autosave) => autosave,
+        Err(error) => {
+            tracing::warn!("Ignoring unreadable autosave for {project_id}: {error}");
+            None
+        }
+    };
+    let autosaved_secs = modified_secs(&autosave_path(project_id)?);
+    let committed_secs = modified_secs(&timeline_file_path(project_id)?);
+
+    let recoverable = match (&autosave, &committed) {
+        (Some(_), None) => true,
+        (Some(autosave), Some(committed)) => {
+            autosaved_secs >= committed_secs
+                && timeline_content_hash(autosave) != timeline_content_hash(committed)
+        }
+        (None, _) => false,
+    };
+
+    Ok(RecoveryStatus {
+        recoverable,
+        has_autosave: autosave.is_some(),
+        autosaved_at: autosave
+            .as_ref()
+            .and(autosaved_secs)
+            .map(|secs| secs.to_string()),
+        committed_at: committed
+            .as_ref()
+            .map(|timeline| timeline.updated_at.clone()),
+        committed_version: committed.as_ref().map(|timeline| timeline.version),
+        autosave_clip_count: autosave.as_ref().map(|timeline| timeline.clips.len()),
+    })
+}
+
+/// Promotes the autosave to the saved timeline, as `save_timeline` would.
+fn restore_autosave_blocking(project_id: &str) -> Result<Timeline, String> {
+    let mut timeline =
+        read_autosave(project_id)?.ok_or_else(|| "No autosave to restore.".to_string())?;
+    speed::apply_clip_speeds(&mut timeline)?;
+    timeline.duration_us = timeline_stats::content_end_us(&timeline);
+    let _save = timeline_merge::lock_saves();
+    let committed_version = read_timeline(project_id)
+        .map(|committed| committed.version)
+        .unwrap_or(0);
+    timeline.version = timeline.version.max(committed_version).saturating_add(1);
+    timeline.updated_at = now_iso();
+    write_timeline(&timeline)?;
+    clear_autosave(project_id)?;
+    Ok(timeline)
+}
+
+/// Stores the editor's working copy without touching the saved timeline or
+/// its version. Returns the autosave time.
+#[tauri::command]
+pub async fn autosave_timeline(request: AutosaveTimelineRequest) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || autosave_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn check_recovery(request: RecoveryRequest) -> Result<RecoveryStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || check_recovery_blocking(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn restore_autosave(request: RecoveryRequest) -> Result<Timeline, String> {
+    tauri::async_runtime::spawn_blocking(move || restore_autosave_blocking(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn discard_autosave(request: RecoveryRequest) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || clear_autosave(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
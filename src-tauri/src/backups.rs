@@ -0,0 +1,317 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    autosave, now_iso, path_safety, read_projects, write_projects, write_timeline, Project,
+    Timeline,
+};
+
+/// Project files captured in a backup, relative to the project data dir.
+/// The timeline is handled separately so its version keeps moving forward.
+const BACKED_UP_FILES: &[&str] = &[
+    "media/metadata.json",
+    "transcript.json",
+    "subtitles/style.json",
+    "credits.json",
+    "external_assets.json",
+];
+const TIMELINE_FILE: &str = "timeline.json";
+const PROJECT_FILE: &str = "project.json";
+const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupReason {
+    Manual,
+    /// Taken automatically before `restore_backup` overwrote the project.
+    PreRestore,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub id: String,
+    pub project_id: String,
+    pub created_at: String,
+    pub reason: BackupReason,
+    #[serde(default)]
+    pub label: String,
+    pub timeline_version: Option<u32>,
+    /// Relative paths stored in the backup, including the timeline and the
+    /// project record.
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBackupRequest {
+    project_id: String,
+    label: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListBackupsRequest {
+    project_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreBackupRequest {
+    project_id: String,
+    backup_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreBackupResult {
+    pub restored: BackupManifest,
+    /// Snapshot of the state that was replaced, for undoing the restore.
+    pub safety_backup: BackupManifest,
+    pub timeline: Option<Timeline>,
+}
+
+fn project_dir(project_id: &str) -> Result<PathBuf, String> {
+    path_safety::project_dir(project_id)
+}
+
+fn backups_dir(project_id: &str) -> Result<PathBuf, String> {
+    Ok(project_dir(project_id)?.join("backups"))
+}
+
+fn next_backup_id(project_id: &str) -> Result<String, String> {
+    let micros = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros();
+    let dir = backups_dir(project_id)?;
+    let mut suffix = 0;
+    loop {
+        let candidate = if suffix == 0 {
+            format!("backup-{micros}")
+        } else {
+            format!("backup-{micros}-{suffix}")
+        };
+        if !dir.join(&candidate).exists() {
+            return Ok(candidate);
+        }
+        suffix += 1;
+    }
+}
+
+fn find_project(project_id: &str) -> Result<Project, String> {
+    read_projects()?
+        .into_iter()
+        .find(|project| project.id == project_id)
+        .ok_or_else(|| "Project not found.".to_string())
+}
+
+fn copy_file(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating backup dir: {error}"))?;
+    }
+    fs::copy(from, to)
+        .map(|_| ())
+        .map_err(|error| format!("Failed copying {}: {error}", from.display()))
+}
+
+fn write_json<T: Serialize>(file_path: &Path, value: &T, label: &str) -> Result<(), String> {
+    let serialized = serde_json::to_string_pretty(value)
+        .map_err(|error| format!("{label} serialize error: {error}"))?;
+    fs::write(file_path, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing {label}: {error}"))
+}
+
+fn read_timeline_file(file_path: &Path) -> Result<Timeline, String> {
+    let raw = fs::read_to_string(file_path)
+        .map_err(|error| format!("Failed reading backup timeline: {error}"))?;
+    serde_json::from_str::<Timeline>(&raw)
+        .map_err(|error| format!("Invalid backup timeline JSON: {error}"))
+}
+
+pub fn create_backup_for(
+    project_id: &str,
+    reason: BackupReason,
+    label: &str,
+) -> Result<BackupManifest, String> {
+    let project = find_project(project_id)?;
+    let source_dir = project_dir(project_id)?;
+    let id = next_backup_id(project_id)?;
+    let backup_dir = backups_dir(project_id)?.join(&id);
+    fs::create_dir_all(&backup_dir)
+        .map_err(|error| format!("Failed creating backup dir: {error}"))?;
+
+    let mut files = vec![PROJECT_FILE.to_string()];
+    write_json(&backup_dir.join(PROJECT_FILE), &project, "backup project")?;
+
+    let timeline_path = source_dir.join(TIMELINE_FILE);
+    let mut timeline_version = None;
+    if timeline_path.is_file() {
+        // A timeline that doesn't parse is still copied; restoring it fails
+        // before anything is overwritten.
+        timeline_version = read_timeline_file(&timeline_path)
+            .ok()
+            .map(|timeline| timeline.version);
+        copy_file(&timeline_path, &backup_dir.join(TIMELINE_FILE))?;
+        files.push(TIMELINE_FILE.to_string());
+    }
+    for relative in BACKED_UP_FILES {
+        let source = source_dir.join(relative);
+        if source.is_file() {
+            copy_file(&source, &backup_dir.join(relative))?;
+            files.push(relative.to_string());
+        }
+    }
+
+    let manifest = BackupManifest {
+        id,
+        project_id: project_id.to_string(),
+        created_at: now_iso(),
+        reason,
+        label: label.trim().to_string(),
+        timeline_version,
+        files,
+    };
+    write_json(
+        &backup_dir.join(MANIFEST_FILE),
+        &manifest,
+        "backup manifest",
+    )?;
+    Ok(manifest)
+}
+
+fn read_manifest(backup_dir: &Path) -> Result<BackupManifest, String> {
+    let raw = fs::read_to_string(backup_dir.join(MANIFEST_FILE))
+        .map_err(|error| format!("Failed reading backup manifest: {error}"))?;
+    serde_json::from_str::<BackupManifest>(&raw)
+        .map_err(|error| format!("Invalid backup manifest JSON: {error}"))
+}
+
+/// Newest first. Backups with an unreadable manifest are skipped.
+pub fn list_backups_for(project_id: &str) -> Result<Vec<BackupManifest>, String> {
+    let dir = backups_dir(project_id)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        fs::read_dir(&dir).map_err(|error| format!("Failed reading backups dir: {error}"))?;
+    let mut backups = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| match read_manifest(&entry.path()) {
+            Ok(manifest) => Some(manifest),
+            Err(error) => {
+                tracing::warn!("Skipping backup {}: {error}", entry.path().display());
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(backups)
+}
+
+fn restore_backup_blocking(request: RestoreBackupRequest) -> Result<RestoreBackupResult, String> {
+    path_safety::validate_file_name(&request.backup_id)?;
+    let backup_dir = backups_dir(&request.project_id)?.join(&request.backup_id);
+    if !backup_dir.is_dir() {
+        return Err(format!("Backup not found: {}", request.backup_id));
+    }
+    let restored = read_manifest(&backup_dir)?;
+    // Read everything before touching the project so a bad backup fails early.
+    let backup_project = serde_json::from_str::<Project>(
+        &fs::read_to_string(backup_dir.join(PROJECT_FILE))
+            .map_err(|error| format!("Failed reading backup project: {error}"))?,
+    )
+    .map_err(|error| format!("Invalid backup project JSON: {error}"))?;
+    let backup_timeline = if restored.files.iter().any(|file| file == TIMELINE_FILE) {
+        Some(read_timeline_file(&backup_dir.join(TIMELINE_FILE))?)
+    } else {
+        None
+    };
+
+    let safety_backup = create_backup_for(
+        &request.project_id,
+        BackupReason::PreRestore,
+        &format!("Before restoring {}", restored.id),
+    )?;
+
+    let project_dir = project_dir(&request.project_id)?;
+    for relative in BACKED_UP_FILES {
+        let target = project_dir.join(relative);
+        if restored.files.iter().any(|file| file == relative) {
+            copy_file(&backup_dir.join(relative), &target)?;
+        } else if target.is_file() {
+            fs::remove_file(&target)
+                .map_err(|error| format!("Failed removing {relative}: {error}"))?;
+        }
+    }
+
+    let timeline = match backup_timeline {
+        Some(mut timeline) => {
+            let current_version = safety_backup.timeline_version.unwrap_or(0);
+            timeline.project_id = request.project_id.clone();
+            timeline.version = timeline.version.max(current_version).saturating_add(1);
+            timeline.updated_at = now_iso();
+            write_timeline(&timeline)?;
+            Some(timeline)
+        }
+        None => {
+            let target = project_dir.join(TIMELINE_FILE);
+            if target.is_file() {
+                fs::remove_file(&target)
+                    .map_err(|error| format!("Failed removing timeline: {error}"))?;
+            }
+            None
+        }
+    };
+    if let Err(error) = autosave::clear_autosave(&request.project_id) {
+        tracing::warn!("Failed clearing autosave: {error}");
+    }
+
+    let mut projects = read_projects()?;
+    if let Some(project) = projects
+        .iter_mut()
+        .find(|project| project.id == request.project_id)
+    {
+        project.settings = backup_project.settings;
+        project.updated_at = now_iso();
+    }
+    write_projects(&projects)?;
+
+    Ok(RestoreBackupResult {
+        restored,
+        safety_backup,
+        timeline,
+    })
+}
+
+#[tauri::command]
+pub async fn create_backup(request: CreateBackupRequest) -> Result<BackupManifest, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        create_backup_for(
+            &request.project_id,
+            BackupReason::Manual,
+            request.label.as_deref().unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn list_backups(request: ListBackupsRequest) -> Result<Vec<BackupManifest>, String> {
+    tauri::async_runtime::spawn_blocking(move || list_backups_for(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn restore_backup(request: RestoreBackupRequest) -> Result<RestoreBackupResult, String> {
+    tauri::async_runtime::spawn_blocking(move || restore_backup_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
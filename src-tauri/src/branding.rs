@@ -0,0 +1,203 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::media::{IMAGE_EXTENSIONS, VIDEO_EXTENSIONS};
+use crate::path_safety;
+
+const BRANDING_FILE: &str = "branding.json";
+const OPACITY_RANGE: std::ops::RangeInclusive<f64> = 0.1..=1.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WatermarkPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl WatermarkPosition {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::TopLeft => "top-left",
+            Self::TopRight => "top-right",
+            Self::BottomLeft => "bottom-left",
+            Self::BottomRight => "bottom-right",
+        }
+    }
+}
+
+/// A project's logo watermark and intro/outro stingers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Branding {
+    /// Image overlaid on the whole render; PNG with transparency works best.
+    pub logo: Option<PathBuf>,
+    pub position: WatermarkPosition,
+    pub opacity: f64,
+    /// Clip played before the edit, scaled to the render's frame.
+    pub intro: Option<PathBuf>,
+    /// Clip played after the edit.
+    pub outro: Option<PathBuf>,
+}
+
+impl Default for Branding {
+    fn default() -> Self {
+        Self {
+            logo: None,
+            position: WatermarkPosition::default(),
+            opacity: 0.6,
+            intro: None,
+            outro: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBrandingRequest {
+    project_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetBrandingRequest {
+    project_id: String,
+    branding: Branding,
+}
+
+fn branding_path(project_id: &str) -> Result<PathBuf, String> {
+    Ok(path_safety::project_dir(project_id)?.join(BRANDING_FILE))
+}
+
+pub fn read_branding(project_id: &str) -> Result<Branding, String> {
+    let path = branding_path(project_id)?;
+    if !path.exists() {
+        return Ok(Branding::default());
+    }
+    let raw = fs::read_to_string(&path)
+        .map_err(|error| format!("Failed reading branding file: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("Invalid branding JSON: {error}"))
+}
+
+fn check_file(field: &str, path: &Path, extensions: &[&str]) -> Result<(), String> {
+    if !path.is_file() {
+        return Err(format!("Branding {field} not found: {}", path.display()));
+    }
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    if !extensions.contains(&extension.as_str()) {
+        return Err(format!(
+            "Branding {field} must be one of {}, got {}.",
+            extensions.join(", "),
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_branding(branding: &Branding) -> Result<(), String> {
+    if !OPACITY_RANGE.contains(&branding.opacity) {
+        return Err(format!(
+            "Watermark opacity must be {}-{}, got {}.",
+            OPACITY_RANGE.start(),
+            OPACITY_RANGE.end(),
+            branding.opacity
+        ));
+    }
+    if let Some(logo) = &branding.logo {
+        check_file("logo", logo, IMAGE_EXTENSIONS)?;
+    }
+    if let Some(intro) = &branding.intro {
+        check_file("intro", intro, VIDEO_EXTENSIONS)?;
+    }
+    if let Some(outro) = &branding.outro {
+        check_file("outro", outro, VIDEO_EXTENSIONS)?;
+    }
+    Ok(())
+}
+
+/// Render script arguments for the project's branding. The watermark is
+/// left out when `watermark` is false, e.g. for a client delivery.
+pub fn render_args(project_id: &str, watermark: bool) -> Result<Vec<String>, String> {
+    let branding = read_branding(project_id)?;
+    let mut args = Vec::new();
+    if let Some(logo) = branding.logo.filter(|_| watermark) {
+        args.extend([
+            "--watermark".to_string(),
+            logo.to_string_lossy().to_string(),
+            "--watermark-position".to_string(),
+            branding.position.as_str().to_string(),
+            "--watermark-opacity".to_string(),
+            branding.opacity.to_string(),
+        ]);
+    }
+    if let Some(intro) = branding.intro {
+        args.extend(["--intro".to_string(), intro.to_string_lossy().to_string()]);
+    }
+    if let Some(outro) = branding.outro {
+        args.extend(["--outro".to_string(), outro.to_string_lossy().to_string()]);
+    }
+    Ok(args)
+}
+
+fn set_branding_blocking(request: SetBrandingRequest) -> Result<Branding, String> {
+    validate_branding(&request.branding)?;
+    let path = branding_path(&request.project_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating project dir: {error}"))?;
+    }
+    let serialized = serde_json::to_string_pretty(&request.branding)
+        .map_err(|error| format!("Branding serialize error: {error}"))?;
+    fs::write(&path, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing branding file: {error}"))?;
+    Ok(request.branding)
+}
+
+#[tauri::command]
+pub async fn get_branding(request: GetBrandingRequest) -> Result<Branding, String> {
+    tauri::async_runtime::spawn_blocking(move || read_branding(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// Replaces the project's branding; every render includes it unless the
+/// render request turns the watermark off.
+#[tauri::command]
+pub async fn set_branding(request: SetBrandingRequest) -> Result<Branding, String> {
+    tauri::async_runtime::spawn_blocking(move || set_branding_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn branding_defaults_and_validation() {
+        let branding: Branding = serde_json::from_str(r#"{"position":"top-left"}"#).unwrap();
+        assert_eq!(branding.position, WatermarkPosition::TopLeft);
+        assert_eq!(branding.opacity, 0.6);
+        assert!(validate_branding(&branding).is_ok());
+        let faint = Branding {
+            opacity: 0.05,
+            ..Branding::default()
+        };
+        assert!(validate_branding(&faint).unwrap_err().contains("opacity"));
+        let missing = Branding {
+            logo: Some(PathBuf::from("/nonexistent/logo.png")),
+            ..Branding::default()
+        };
+        assert!(validate_branding(&missing)
+            .unwrap_err()
+            .contains("not found"));
+    }
+}
@@ -0,0 +1,427 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::subtitles::{timeline_words, CueWord, CUE_GAP_US};
+use crate::transcript::{read_transcript, Transcript};
+use crate::{
+    now_iso, read_timeline, timeline_merge, write_timeline, Timeline, TimelineClip, TimelineTrack,
+};
+
+pub const CAPTION_CLIP_TYPE: &str = "caption_clip";
+const LINE_CHARS_RANGE: std::ops::RangeInclusive<u32> = 10..=120;
+const DURATION_RANGE_US: std::ops::RangeInclusive<u64> = 500_000..=20_000_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptionMode {
+    /// Whole caption at once.
+    #[default]
+    Subtitle,
+    /// Each word highlighted as it is spoken.
+    Karaoke,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateCaptionsRequest {
+    project_id: String,
+    max_chars_per_line: Option<u32>,
+    max_duration_us: Option<u64>,
+    mode: Option<CaptionMode>,
+    /// Drop the captions track's clips first (the default); otherwise new
+    /// captions are only added where the track is free.
+    replace: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedCaptions {
+    pub track_id: String,
+    pub added: usize,
+    pub removed: usize,
+    /// Captions left out in append mode because the track was taken.
+    pub skipped: usize,
+    pub timeline: Timeline,
+}
+
+/// One caption before it becomes a clip.
+#[derive(Debug, Clone)]
+pub struct Caption {
+    pub words: Vec<CueWord>,
+    /// Word index each line starts at.
+    pub line_starts: Vec<usize>,
+}
+
+impl Caption {
+    fn start_us(&self) -> u64 {
+        self.words.first().map_or(0, |word| word.start_us)
+    }
+
+    fn end_us(&self) -> u64 {
+        self.words.last().map_or(0, |word| word.end_us)
+    }
+
+    /// Captions break on speaker changes, so the first word's speaker is
+    /// everyone's.
+    fn speaker(&self) -> Option<&str> {
+        self.words.first().and_then(|word| word.speaker.as_deref())
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let mut bounds = self.line_starts.clone();
+        bounds.push(self.words.len());
+        bounds
+            .windows(2)
+            .map(|range| {
+                self.words[range[0]..range[1]]
+                    .iter()
+                    .map(|word| word.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+}
+
+fn chars(words: &[CueWord]) -> usize {
+    words
+        .iter()
+        .map(|word| word.text.chars().count())
+        .sum::<usize>()
+        + words.len().saturating_sub(1)
+}
+
+fn ends_sentence(text: &str) -> bool {
+    text.trim_end_matches(['"', '\'', ')', '”', '’'])
+        .ends_with(['.', '?', '!', '…'])
+}
+
+fn ends_clause(text: &str) -> bool {
+    text.ends_with([',', ';', ':', '—'])
+}
+
+/// Where to start each line of `words`, or None if they don't fit in two
+/// lines of `max_chars`. Two lines are balanced, leaning towards a break
+/// after a clause.
+fn line_breaks(words: &[CueWord], max_chars: usize) -> Option<Vec<usize>> {
+    if chars(words) <= max_chars {
+        return Some(vec![0]);
+    }
+    (1..words.len())
+        .filter(|&split| chars(&words[..split]) <= max_chars && chars(&words[split..]) <= max_chars)
+        .min_by_key(|&split| {
+            let imbalance = chars(&words[..split]).abs_diff(chars(&words[split..]));
+            let clause_bonus = if ends_clause(&words[split - 1].text) {
+                max_chars / 4
+            } else {
+                0
+            };
+            imbalance.saturating_sub(clause_bonus)
+        })
+        .map(|split| vec![0, split])
+}
+
+/// Groups timeline words into captions of at most two lines of `max_chars`
+/// and `max_duration_us`. A caption ends at a sentence end, a transcript
+/// segment end, a pause or a change of speaker.
+pub fn chunk_captions(
+    segments: Vec<Vec<CueWord>>,
+    max_chars: usize,
+    max_duration_us: u64,
+) -> Vec<Caption> {
+    let mut captions = Vec::new();
+    let mut current: Vec<CueWord> = Vec::new();
+    let flush = |captions: &mut Vec<Caption>, current: &mut Vec<CueWord>| {
+        if current.is_empty() {
+            return;
+        }
+        if let Some(line_starts) = line_breaks(current, max_chars) {
+            captions.push(Caption {
+                words: std::mem::take(current),
+                line_starts,
+            });
+        }
+        current.clear();
+    };
+
+    for words in segments {
+        for word in words {
+            if let (Some(first), Some(previous)) = (current.first(), current.last()) {
+                let pause = word.start_us.saturating_sub(previous.end_us) > CUE_GAP_US;
+                // Reordered clips can put a later word before an earlier one.
+                let jumped = word.start_us < previous.start_us;
+                let new_speaker = word.speaker != previous.speaker;
+                let too_long = word.end_us.saturating_sub(first.start_us) > max_duration_us;
+                let mut candidate = current.clone();
+                candidate.push(word.clone());
+                let too_wide = line_breaks(&candidate, max_chars).is_none();
+                if pause || jumped || new_speaker || too_long || too_wide {
+                    flush(&mut captions, &mut current);
+                }
+            }
+            let sentence_end = ends_sentence(&word.text);
+            current.push(word);
+            // A word too wide for a line on its own still gets a caption.
+            if line_breaks(&current, max_chars).is_none() {
+                let word = current.pop().into_iter().collect::<Vec<_>>();
+                flush(&mut captions, &mut current);
+                captions.push(Caption {
+                    words: word,
+                    line_starts: vec![0],
+                });
+            } else if sentence_end {
+                flush(&mut captions, &mut current);
+            }
+        }
+        flush(&mut captions, &mut current);
+    }
+
+    // Keep captions from overlapping the next one.
+    for index in 1..captions.len() {
+        let next_start = captions[index].start_us();
+        if let Some(last) = captions[index - 1].words.last_mut() {
+            if last.end_us > next_start {
+                last.end_us = next_start.max(last.start_us);
+            }
+        }
+    }
+    captions
+}
+
+fn caption_clip(
+    caption: &Caption,
+    transcript: &Transcript,
+    track_id: &str,
+    mode: CaptionMode,
+    index: usize,
+) -> TimelineClip {
+    let (start_us, end_us) = (caption.start_us(), caption.end_us());
+    let speaker = caption.speaker();
+    TimelineClip {
+        clip_id: format!("caption-{start_us}-{index}"),
+        track_id: track_id.to_string(),
+        clip_type: CAPTION_CLIP_TYPE.to_string(),
+        start_us,
+        end_us,
+        source_start_us: start_us,
+        source_end_us: end_us,
+        speed: 1.0,
+        speed_keyframes: Vec::new(),
+        locked: false,
+        protected_from_ai: false,
+        source_ref: String::new(),
+        effects: json!({}),
+        transform: json!({}),
+        meta: json!({
+            "text": caption.lines().join("\n"),
+            "words": caption
+                .words
+                .iter()
+                .map(|word| json!({
+                    "text": word.text,
+                    "startUs": word.start_us,
+                    "endUs": word.end_us,
+                }))
+                .collect::<Vec<_>>(),
+            "lineStarts": caption.line_starts,
+            "speaker": speaker,
+            "speakerLabel": speaker.map(|speaker| transcript.speaker_label(speaker)),
+            "mode": mode,
+            "generated": true,
+            "style": { "karaoke": mode == CaptionMode::Karaoke },
+        }),
+    }
+}
+
+/// Refreshes `speakerLabel` on caption clips after speakers were renamed.
+/// Returns how many clips changed.
+pub fn relabel_speakers(timeline: &mut Timeline, transcript: &Transcript) -> usize {
+    let mut changed = 0;
+    for clip in &mut timeline.clips {
+        if clip.clip_type != CAPTION_CLIP_TYPE {
+            continue;
+        }
+        let Some(speaker) = clip.meta.get("speaker").and_then(Value::as_str) else {
+            continue;
+        };
+        let label = json!(transcript.speaker_label(speaker));
+        if clip.meta.get("speakerLabel") != Some(&label) {
+            clip.meta["speakerLabel"] = label;
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// The first caption track, added if the timeline has none.
+fn captions_track(timeline: &mut Timeline) -> Result<String, String> {
+    if let Some(track) = timeline.tracks.iter().find(|track| track.kind == "caption") {
+        if track.locked {
+            return Err(format!("Track {} is locked.", track.id));
+        }
+        return Ok(track.id.clone());
+    }
+    let track = TimelineTrack {
+        id: "track-captions".to_string(),
+        name: "Captions".to_string(),
+        kind: "caption".to_string(),
+        order: timeline.tracks.len() as u32,
+        locked: false,
+    };
+    let id = track.id.clone();
+    timeline.tracks.push(track);
+    Ok(id)
+}
+
+fn generate_captions_blocking(
+    request: GenerateCaptionsRequest,
+) -> Result<GeneratedCaptions, String> {
+    let max_chars = request.max_chars_per_line.unwrap_or(42);
+    if !LINE_CHARS_RANGE.contains(&max_chars) {
+        return Err(format!(
+            "Max characters per line must be {}-{}, got {max_chars}.",
+            LINE_CHARS_RANGE.start(),
+            LINE_CHARS_RANGE.end()
+        ));
+    }
+    let max_duration_us = request.max_duration_us.unwrap_or(6_000_000);
+    if !DURATION_RANGE_US.contains(&max_duration_us) {
+        return Err(format!(
+            "Max caption duration must be {}-{}us, got {max_duration_us}.",
+            DURATION_RANGE_US.start(),
+            DURATION_RANGE_US.end()
+        ));
+    }
+    let mode = request.mode.unwrap_or_default();
+    let transcript = read_transcript(&request.project_id)?;
+
+    let _save = timeline_merge::lock_saves();
+    let mut timeline = read_timeline(&request.project_id)?;
+    let track_id = captions_track(&mut timeline)?;
+    let captions = chunk_captions(
+        timeline_words(&transcript, &timeline),
+        max_chars as usize,
+        max_duration_us,
+    );
+
+    let before = timeline.clips.len();
+    if request.replace.unwrap_or(true) {
+        timeline.clips.retain(|clip| clip.track_id != track_id);
+    }
+    let removed = before - timeline.clips.len();
+    let taken = timeline
+        .clips
+        .iter()
+        .filter(|clip| clip.track_id == track_id)
+        .map(|clip| (clip.start_us, clip.end_us))
+        .collect::<Vec<_>>();
+    let mut added = 0;
+    let mut skipped = 0;
+    for (index, caption) in captions.iter().enumerate() {
+        let overlaps = taken
+            .iter()
+            .any(|&(start_us, end_us)| caption.start_us() < end_us && caption.end_us() > start_us);
+        if overlaps {
+            skipped += 1;
+            continue;
+        }
+        timeline.clips.push(caption_clip(
+            caption,
+            &transcript,
+            &track_id,
+            mode,
+            index + 1,
+        ));
+        added += 1;
+    }
+
+    timeline.version = timeline.version.saturating_add(1);
+    timeline.updated_at = now_iso();
+    write_timeline(&timeline)?;
+    Ok(GeneratedCaptions {
+        track_id,
+        added,
+        removed,
+        skipped,
+        timeline,
+    })
+}
+
+/// Fills the captions track from the stored transcript, one clip per
+/// caption, following the timeline's cuts.
+#[tauri::command]
+pub async fn generate_captions(
+    request: GenerateCaptionsRequest,
+) -> Result<GeneratedCaptions, String> {
+    tauri::async_runtime::spawn_blocking(move || generate_captions_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str, start_us: u64) -> Vec<CueWord> {
+        text.split_whitespace()
+            .enumerate()
+            .map(|(index, text)| CueWord {
+                start_us: start_us + index as u64 * 300_000,
+                end_us: start_us + index as u64 * 300_000 + 250_000,
+                text: text.to_string(),
+                speaker: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn captions_break_at_sentences_and_balance_lines() {
+        let captions = chunk_captions(
+            vec![words(
+                "Hi there. This is a longer sentence, which needs two lines to fit on screen.",
+                0,
+            )],
+            30,
+            6_000_000,
+        );
+        let lines = captions
+            .iter()
+            .map(|caption| caption.lines())
+            .collect::<Vec<_>>();
+        assert_eq!(lines[0], ["Hi there."]);
+        // Balanced, and broken after the comma.
+        assert_eq!(
+            lines[1],
+            ["This is a longer sentence,", "which needs two lines to fit"]
+        );
+        assert!(captions
+            .iter()
+            .flat_map(Caption::lines)
+            .all(|line| line.chars().count() <= 30));
+        assert!(captions
+            .iter()
+            .all(|caption| caption.end_us() - caption.start_us() <= 6_000_000));
+    }
+
+    #[test]
+    fn captions_split_on_pauses_duration_and_speakers() {
+        let mut segment = words("one two three", 0);
+        segment.extend(words("four five", 5_000_000));
+        let captions = chunk_captions(vec![segment], 42, 700_000);
+        let texts = captions
+            .iter()
+            .map(|caption| caption.lines().join(" "))
+            .collect::<Vec<_>>();
+        assert_eq!(texts, ["one two", "three", "four five"]);
+
+        let mut segment = words("so what do you think", 0);
+        segment[3].speaker = Some("SPEAKER_2".to_string());
+        segment[4].speaker = Some("SPEAKER_2".to_string());
+        let captions = chunk_captions(vec![segment], 42, 6_000_000);
+        let speakers = captions
+            .iter()
+            .map(|caption| caption.speaker())
+            .collect::<Vec<_>>();
+        assert_eq!(speakers, [None, Some("SPEAKER_2")]);
+    }
+}
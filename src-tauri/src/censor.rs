@@ -0,0 +1,408 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::cuts::{self, CutDecision, ProposedCutSet};
+use crate::project_status::ProjectStatus;
+use crate::timeline::{normalize_ranges, source_to_timeline_us, TimeRange};
+use crate::transcript::{normalize_word, read_transcript, Transcript, TranscriptWord};
+use crate::{
+    now_iso, read_timeline, timeline_merge, update_project_status, write_timeline, Timeline,
+    TimelineClip,
+};
+
+/// Matched against normalized words; a trailing `*` matches any ending.
+const BUILTIN_WORDS: &[&str] = &[
+    "fuck*",
+    "motherfuck*",
+    "shit*",
+    "bullshit*",
+    "bitch*",
+    "asshole*",
+    "bastard*",
+    "cunt*",
+    "dick",
+    "dickhead*",
+    "piss",
+    "pissed",
+    "wank*",
+];
+/// Reason recorded on cuts made by `apply_censor`.
+pub const CENSOR_CUT_REASON: &str = "censor";
+const PADDING_MS_RANGE: std::ops::RangeInclusive<u32> = 0..=1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CensorMode {
+    Mute,
+    Bleep,
+    Cut,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectProfanityRequest {
+    project_id: String,
+    /// Extra words to flag; `*` at the end matches any ending.
+    word_list: Option<Vec<String>>,
+    /// Include the built-in list. Defaults to on without a word list.
+    builtin: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyCensorRequest {
+    project_id: String,
+    /// Source time ranges, as returned by `detect_profanity`.
+    ranges: Vec<TimeRange>,
+    mode: CensorMode,
+    /// Extra source time covered on each side of every range.
+    padding_ms: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfanityHit {
+    pub word_id: String,
+    pub text: String,
+    /// The list entry that matched.
+    pub term: String,
+    pub source_start_us: u64,
+    pub source_end_us: u64,
+    /// `None` when the word was already cut from the timeline.
+    pub timeline_start_us: Option<u64>,
+    pub timeline_end_us: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfanityReport {
+    pub hits: Vec<ProfanityHit>,
+    pub counts: BTreeMap<String, usize>,
+    /// Merged source ranges of the hits, ready for `apply_censor`.
+    pub ranges: Vec<TimeRange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CensorResult {
+    pub mode: CensorMode,
+    pub ranges: Vec<TimeRange>,
+    /// Source clips that gained a mute or bleep.
+    pub censored_clips: usize,
+    pub timeline: Timeline,
+}
+
+fn matches_term(term: &str, word: &str) -> bool {
+    match term.strip_suffix('*') {
+        Some(prefix) => !prefix.is_empty() && word.starts_with(prefix),
+        None => word == term,
+    }
+}
+
+/// The detection terms, normalized like transcript words.
+fn detection_terms(word_list: &[String], builtin: bool) -> Vec<String> {
+    let mut terms = word_list
+        .iter()
+        .map(|entry| {
+            let entry = entry.trim();
+            let wildcard = if entry.ends_with('*') { "*" } else { "" };
+            format!("{}{wildcard}", normalize_word(entry))
+        })
+        .filter(|term| !term.is_empty() && term != "*")
+        .collect::<Vec<_>>();
+    if builtin {
+        terms.extend(BUILTIN_WORDS.iter().map(|term| term.to_string()));
+    }
+    terms.sort_unstable();
+    terms.dedup();
+    terms
+}
+
+/// Every transcript word matching one of `terms`, with the term it matched.
+pub fn find_flagged_words<'a>(
+    transcript: &'a Transcript,
+    terms: &[String],
+) -> Vec<(&'a TranscriptWord, String)> {
+    transcript
+        .words()
+        .filter_map(|word| {
+            let normalized = if word.normalized.is_empty() {
+                normalize_word(&word.text)
+            } else {
+                word.normalized.clone()
+            };
+            terms
+                .iter()
+                .find(|term| matches_term(term, &normalized))
+                .map(|term| (word, term.clone()))
+        })
+        .collect()
+}
+
+/// The source a clip cuts from; multicam switches keep the master's timing.
+fn clip_master(clip: &TimelineClip) -> &str {
+    clip.meta
+        .pointer("/multicam/masterSourceRef")
+        .and_then(Value::as_str)
+        .unwrap_or(&clip.source_ref)
+}
+
+fn clip_censors(clip: &TimelineClip) -> Vec<(TimeRange, CensorMode)> {
+    clip.effects
+        .get("censor")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let range = TimeRange {
+                start_us: entry.get("sourceStartUs")?.as_u64()?,
+                end_us: entry.get("sourceEndUs")?.as_u64()?,
+            };
+            let mode = serde_json::from_value(entry.get("mode")?.clone()).ok()?;
+            Some((range, mode))
+        })
+        .collect()
+}
+
+/// Records a mute or bleep over `ranges` (source time) on every source clip
+/// of `source_ref` they overlap, clamped to the clip. A range already
+/// censored on a clip takes the new mode. Locked clips are left alone.
+/// Returns the clips touched.
+pub fn attach_censors(
+    timeline: &mut Timeline,
+    source_ref: Option<&str>,
+    ranges: &[TimeRange],
+    mode: CensorMode,
+) -> Result<usize, String> {
+    let mut touched = 0;
+    for clip in &mut timeline.clips {
+        if clip.clip_type != "source_clip"
+            || clip.locked
+            || source_ref.is_some_and(|source| clip_master(clip) != source)
+        {
+            continue;
+        }
+        let overlapping = ranges
+            .iter()
+            .filter(|range| {
+                range.start_us < clip.source_end_us && range.end_us > clip.source_start_us
+            })
+            .map(|range| TimeRange {
+                start_us: range.start_us.max(clip.source_start_us),
+                end_us: range.end_us.min(clip.source_end_us),
+            })
+            .collect::<Vec<_>>();
+        if overlapping.is_empty() {
+            continue;
+        }
+        if let Some(track) = timeline
+            .tracks
+            .iter()
+            .find(|track| track.id == clip.track_id && track.locked)
+        {
+            return Err(format!("Track {} is locked.", track.id));
+        }
+
+        let mut censors = clip_censors(clip);
+        censors.retain(|(range, _)| {
+            !overlapping
+                .iter()
+                .any(|new| new.start_us == range.start_us && new.end_us == range.end_us)
+        });
+        censors.extend(overlapping.into_iter().map(|range| (range, mode)));
+        censors.sort_by_key(|(range, _)| range.start_us);
+        let entries = censors
+            .into_iter()
+            .map(|(range, mode)| {
+                json!({
+                    "sourceStartUs": range.start_us,
+                    "sourceEndUs": range.end_us,
+                    "mode": mode,
+                })
+            })
+            .collect::<Vec<_>>();
+        if !clip.effects.is_object() {
+            clip.effects = json!({});
+        }
+        if let Some(effects) = clip.effects.as_object_mut() {
+            effects.insert("censor".to_string(), Value::Array(entries));
+        }
+        touched += 1;
+    }
+    Ok(touched)
+}
+
+/// Keeps censoring through a rough-cut rebuild: mutes and bleeps on the
+/// `previous` timeline are attached again, and censor cuts are listed in
+/// `meta.censoredCuts` so the renderer never joins footage across them.
+pub fn carry_over(timeline: &mut Timeline, previous: Option<&Timeline>, set: &ProposedCutSet) {
+    for clip in previous.into_iter().flat_map(|previous| &previous.clips) {
+        for (range, mode) in clip_censors(clip) {
+            // The rebuilt clips are not on locked tracks yet, so this cannot fail.
+            let _ = attach_censors(timeline, Some(clip_master(clip)), &[range], mode);
+        }
+    }
+    let censored_cuts = set
+        .cuts
+        .iter()
+        .filter(|cut| {
+            cut.decision == CutDecision::Accept && cut.reason.as_deref() == Some(CENSOR_CUT_REASON)
+        })
+        .map(|cut| json!({ "sourceStartUs": cut.start_us, "sourceEndUs": cut.end_us }))
+        .collect::<Vec<_>>();
+    if censored_cuts.is_empty() {
+        return;
+    }
+    if !timeline.meta.is_object() {
+        timeline.meta = json!({});
+    }
+    if let Some(meta) = timeline.meta.as_object_mut() {
+        meta.insert("censoredCuts".to_string(), Value::Array(censored_cuts));
+    }
+}
+
+fn detect_profanity_blocking(request: DetectProfanityRequest) -> Result<ProfanityReport, String> {
+    let word_list = request.word_list.unwrap_or_default();
+    let builtin = request.builtin.unwrap_or(word_list.is_empty());
+    let terms = detection_terms(&word_list, builtin);
+    if terms.is_empty() {
+        return Err(
+            "No words to detect: pass a word list or enable the built-in list.".to_string(),
+        );
+    }
+    let transcript = read_transcript(&request.project_id)?;
+    let timeline = read_timeline(&request.project_id).ok();
+    let source_ref = transcript.source_ref.as_deref();
+
+    let mut counts = BTreeMap::new();
+    let mut ranges = Vec::new();
+    let hits = find_flagged_words(&transcript, &terms)
+        .into_iter()
+        .map(|(word, term)| {
+            *counts.entry(term.clone()).or_insert(0) += 1;
+            ranges.push(TimeRange {
+                start_us: word.start_us,
+                end_us: word.end_us,
+            });
+            let to_timeline = |source_us| {
+                timeline
+                    .as_ref()
+                    .and_then(|timeline| source_to_timeline_us(timeline, source_ref, source_us))
+            };
+            ProfanityHit {
+                word_id: word.id.clone(),
+                text: word.text.clone(),
+                term,
+                source_start_us: word.start_us,
+                source_end_us: word.end_us,
+                timeline_start_us: to_timeline(word.start_us),
+                timeline_end_us: to_timeline(word.end_us.saturating_sub(1)).map(|us| us + 1),
+            }
+        })
+        .collect();
+    Ok(ProfanityReport {
+        hits,
+        counts,
+        ranges: normalize_ranges(ranges, u64::MAX),
+    })
+}
+
+fn apply_censor_blocking(request: ApplyCensorRequest) -> Result<CensorResult, String> {
+    let padding_ms = request.padding_ms.unwrap_or(50);
+    if !PADDING_MS_RANGE.contains(&padding_ms) {
+        return Err(format!(
+            "Padding must be {}-{} ms, got {padding_ms}.",
+            PADDING_MS_RANGE.start(),
+            PADDING_MS_RANGE.end()
+        ));
+    }
+    let padding_us = u64::from(padding_ms) * 1000;
+    let padded = request
+        .ranges
+        .iter()
+        .map(|range| TimeRange {
+            start_us: range.start_us.saturating_sub(padding_us),
+            end_us: range.end_us.saturating_add(padding_us),
+        })
+        .collect::<Vec<_>>();
+
+    if request.mode == CensorMode::Cut {
+        let mut set = cuts::load_or_derive_cut_set(&request.project_id)?;
+        let ranges = normalize_ranges(padded, set.duration_us);
+        if ranges.is_empty() {
+            return Err("No ranges to censor.".to_string());
+        }
+        let _save = timeline_merge::lock_saves();
+        cuts::append_cuts(&mut set, &ranges, CENSOR_CUT_REASON);
+        cuts::write_proposed_cuts(&set)?;
+        let timeline = cuts::build_timeline_from_decisions(&set);
+        write_timeline(&timeline)?;
+        update_project_status(&request.project_id, ProjectStatus::RoughCutReady)?;
+        return Ok(CensorResult {
+            mode: request.mode,
+            ranges,
+            censored_clips: 0,
+            timeline,
+        });
+    }
+
+    let ranges = normalize_ranges(padded, u64::MAX);
+    if ranges.is_empty() {
+        return Err("No ranges to censor.".to_string());
+    }
+    let source_ref = read_transcript(&request.project_id)
+        .ok()
+        .and_then(|transcript| transcript.source_ref);
+    let _save = timeline_merge::lock_saves();
+    let mut timeline = read_timeline(&request.project_id)?;
+    let censored_clips =
+        attach_censors(&mut timeline, source_ref.as_deref(), &ranges, request.mode)?;
+    if censored_clips == 0 {
+        return Err("None of the ranges are on the timeline.".to_string());
+    }
+    timeline.version = timeline.version.saturating_add(1);
+    timeline.updated_at = now_iso();
+    write_timeline(&timeline)?;
+    Ok(CensorResult {
+        mode: request.mode,
+        ranges,
+        censored_clips,
+        timeline,
+    })
+}
+
+/// Scans the transcript for flagged words and reports where they are.
+#[tauri::command]
+pub async fn detect_profanity(request: DetectProfanityRequest) -> Result<ProfanityReport, String> {
+    tauri::async_runtime::spawn_blocking(move || detect_profanity_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// Mutes or bleeps source ranges in the rendered audio, or cuts them out of
+/// the rough cut.
+#[tauri::command]
+pub async fn apply_censor(request: ApplyCensorRequest) -> Result<CensorResult, String> {
+    tauri::async_runtime::spawn_blocking(move || apply_censor_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_terms_match_word_endings_only() {
+        let terms = detection_terms(&["Darn*".to_string(), "heck".to_string()], false);
+        assert_eq!(terms, ["darn*", "heck"]);
+        assert!(matches_term("darn*", "darned"));
+        assert!(matches_term("heck", "heck"));
+        assert!(!matches_term("heck", "hecking"));
+        assert!(!matches_term("dick", "dickens"));
+        assert!(matches_term("fuck*", "fucking"));
+        assert!(!matches_term("*", "anything"));
+    }
+}
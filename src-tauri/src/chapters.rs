@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+
+use crate::markers::{self, Marker, CHAPTER_KIND};
+use crate::planner::{self, PlannedChapter, PlannerRun};
+use crate::sponsors;
+use crate::timeline::{source_to_timeline_us, TimeRange};
+use crate::transcript::read_transcript;
+use crate::{now_iso, read_timeline, timeline_merge, write_timeline, Timeline};
+
+/// YouTube ignores chapter lists with a chapter shorter than this.
+const MIN_CHAPTER_US: u64 = 10_000_000;
+/// ... or with fewer chapters than this.
+const MIN_CHAPTERS: usize = 3;
+const MAX_CHAPTERS_RANGE: std::ops::RangeInclusive<u32> = 2..=50;
+/// Appended to the description line of a chapter that is mostly a sponsor
+/// read.
+const SPONSOR_SUFFIX: &str = " (Sponsor)";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateChaptersRequest {
+    project_id: String,
+    model: Option<String>,
+    max_chapters: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChaptersRequest {
+    project_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterList {
+    pub chapters: Vec<Marker>,
+    /// Chapters at least half covered by a marked sponsor read.
+    pub sponsored_chapter_ids: Vec<String>,
+    /// `0:00 Title` lines for a YouTube description.
+    pub description: String,
+    /// Why YouTube would not show these as chapters, if it wouldn't.
+    pub warnings: Vec<String>,
+    pub planner: Option<PlannerRun>,
+}
+
+/// `M:SS`, or `H:MM:SS` from an hour on, as YouTube writes timestamps.
+pub fn youtube_timestamp(us: u64) -> String {
+    let seconds = us / 1_000_000;
+    let (hours, minutes, seconds) = (seconds / 3600, (seconds / 60) % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// The description lines for `chapters` and what would stop YouTube from
+/// using them.
+pub fn chapter_description(chapters: &[Marker]) -> (String, Vec<String>) {
+    let description = chapters
+        .iter()
+        .map(|chapter| format!("{} {}", youtube_timestamp(chapter.start_us), chapter.text))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut warnings = Vec::new();
+    if chapters.len() < MIN_CHAPTERS {
+        warnings.push(format!("YouTube needs at least {MIN_CHAPTERS} chapters."));
+    }
+    if chapters
+        .first()
+        .is_some_and(|chapter| chapter.start_us >= 1_000_000)
+    {
+        warnings.push("The first chapter must start at 0:00.".to_string());
+    }
+    for chapter in chapters {
+        if chapter.end_us - chapter.start_us < MIN_CHAPTER_US {
+            warnings.push(format!(
+                "Chapter \"{}\" at {} is shorter than {}s.",
+                chapter.text,
+                youtube_timestamp(chapter.start_us),
+                MIN_CHAPTER_US / 1_000_000
+            ));
+        }
+    }
+    (description, warnings)
+}
+
+/// Moves planned chapter starts onto the timeline. A start inside a cut
+/// moves to where the footage resumes; the first chapter starts at zero
+/// and chapters too short to keep are merged into the one before.
+pub fn timeline_chapters(
+    timeline: &Timeline,
+    source_ref: Option<&str>,
+    planned: &[PlannedChapter],
+) -> Vec<(u64, String)> {
+    let mut starts = planned
+        .iter()
+        .filter_map(|chapter| {
+            let start_us =
+                source_to_timeline_us(timeline, source_ref, chapter.start_us).or_else(|| {
+                    timeline
+                        .clips
+                        .iter()
+                        .filter(|clip| clip.clip_type == "source_clip")
+                        .filter(|clip| source_ref.map_or(true, |source| clip.source_ref == source))
+                        .filter(|clip| clip.source_start_us >= chapter.start_us)
+                        .map(|clip| clip.start_us)
+                        .min()
+                })?;
+            Some((start_us, chapter.title.clone()))
+        })
+        .collect::<Vec<_>>();
+    starts.sort_by_key(|(start_us, _)| *start_us);
+    if let Some(first) = starts.first_mut() {
+        first.0 = 0;
+    }
+
+    let mut kept: Vec<(u64, String)> = Vec::new();
+    for (start_us, title) in starts {
+        if kept
+            .last()
+            .map_or(true, |(previous, _)| start_us >= previous + MIN_CHAPTER_US)
+        {
+            kept.push((start_us, title));
+        }
+    }
+    while kept.len() > 1
+        && kept.last().is_some_and(|(start_us, _)| {
+            timeline.duration_us.saturating_sub(*start_us) < MIN_CHAPTER_US
+        })
+    {
+        kept.pop();
+    }
+    kept
+}
+
+/// Ids of chapters that are at least half sponsor read.
+pub fn sponsored_chapters(chapters: &[Marker], sponsor_spans: &[TimeRange]) -> Vec<String> {
+    chapters
+        .iter()
+        .filter(|chapter| {
+            let covered = sponsor_spans
+                .iter()
+                .map(|span| {
+                    span.end_us
+                        .min(chapter.end_us)
+                        .saturating_sub(span.start_us.max(chapter.start_us))
+                })
+                .sum::<u64>();
+            covered > 0 && covered * 2 >= chapter.end_us.saturating_sub(chapter.start_us)
+        })
+        .map(|chapter| chapter.id.clone())
+        .collect()
+}
+
+fn chapter_list(timeline: &Timeline, planner: Option<PlannerRun>) -> ChapterList {
+    let chapters = markers::markers(timeline, Some(CHAPTER_KIND));
+    let segments = sponsors::read_segments(&timeline.project_id).unwrap_or_else(|error| {
+        tracing::warn!("Ignoring sponsor segments: {error}");
+        Vec::new()
+    });
+    let sponsored_chapter_ids =
+        sponsored_chapters(&chapters, &sponsors::timeline_spans(timeline, &segments));
+    let labelled = chapters
+        .iter()
+        .map(|chapter| {
+            let mut chapter = chapter.clone();
+            if sponsored_chapter_ids.contains(&chapter.id) {
+                chapter.text.push_str(SPONSOR_SUFFIX);
+            }
+            chapter
+        })
+        .collect::<Vec<_>>();
+    let (description, warnings) = chapter_description(&labelled);
+    ChapterList {
+        chapters,
+        sponsored_chapter_ids,
+        description,
+        warnings,
+        planner,
+    }
+}
+
+fn generate_chapters_blocking(request: GenerateChaptersRequest) -> Result<ChapterList, String> {
+    let max_chapters = request.max_chapters.unwrap_or(12);
+    if !MAX_CHAPTERS_RANGE.contains(&max_chapters) {
+        return Err(format!(
+            "Max chapters must be {}-{}, got {max_chapters}.",
+            MAX_CHAPTERS_RANGE.start(),
+            MAX_CHAPTERS_RANGE.end()
+        ));
+    }
+    let transcript = read_transcript(&request.project_id)?;
+    let (planned, run) = planner::plan_chapters(request.model, &transcript, max_chapters as usize)?;
+
+    let _save = timeline_merge::lock_saves();
+    let mut timeline = read_timeline(&request.project_id)?;
+    let entries = timeline_chapters(&timeline, transcript.source_ref.as_deref(), &planned);
+    if entries.is_empty() {
+        return Err("None of the planned chapters fall on the edited timeline.".to_string());
+    }
+    markers::replace_markers(&mut timeline, CHAPTER_KIND, &entries)?;
+    timeline.version = timeline.version.saturating_add(1);
+    timeline.updated_at = now_iso();
+    write_timeline(&timeline)?;
+    Ok(chapter_list(&timeline, Some(run)))
+}
+
+/// Plans topical chapters with the planner backend and writes them as
+/// chapter markers, replacing earlier ones. Edit them with the markers
+/// commands and read the result back with `get_chapters`.
+#[tauri::command]
+pub async fn generate_chapters(request: GenerateChaptersRequest) -> Result<ChapterList, String> {
+    tauri::async_runtime::spawn_blocking(move || generate_chapters_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// The current chapter markers and their description lines.
+#[tauri::command]
+pub async fn get_chapters(request: GetChaptersRequest) -> Result<ChapterList, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let timeline = read_timeline(&request.project_id)?;
+        Ok(chapter_list(&timeline, None))
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapter(start_s: u64, end_s: u64, text: &str) -> Marker {
+        Marker {
+            id: format!("chapter-{start_s}"),
+            kind: CHAPTER_KIND.to_string(),
+            start_us: start_s * 1_000_000,
+            end_us: end_s * 1_000_000,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn description_uses_youtube_timestamps_and_flags_short_chapters() {
+        let (description, warnings) = chapter_description(&[
+            chapter(0, 95, "Intro"),
+            chapter(95, 100, "Setup"),
+            chapter(100, 3725, "Deep dive"),
+        ]);
+        assert_eq!(description, "0:00 Intro\n1:35 Setup\n1:40 Deep dive");
+        assert_eq!(warnings, ["Chapter \"Setup\" at 1:35 is shorter than 10s."]);
+        assert_eq!(youtube_timestamp(3_725_000_000), "1:02:05");
+
+        let (_, warnings) = chapter_description(&[chapter(5, 60, "Late start")]);
+        assert_eq!(warnings.len(), 2);
+    }
+}
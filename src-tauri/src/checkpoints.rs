@@ -0,0 +1,164 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::path_safety;
+
+/// Stages of `start_editing`, in the order they complete. The script writes
+/// `checkpoints/<stage>.json` as each one finishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Stage {
+    Ingest,
+    Transcript,
+    Plan,
+}
+
+const STAGES: [Stage; 3] = [Stage::Ingest, Stage::Transcript, Stage::Plan];
+
+impl Stage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ingest => "ingest",
+            Self::Transcript => "transcript",
+            Self::Plan => "plan",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageCheckpoint {
+    pub stage: Stage,
+    pub key: String,
+    pub completed_at: String,
+}
+
+/// What each stage's output depends on. A checkpoint is only reused when its
+/// key matches, so changing the input file, language or a model redoes that
+/// stage and everything after it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageKeys {
+    pub ingest: String,
+    pub transcript: String,
+    pub plan: String,
+}
+
+impl StageKeys {
+    fn get(&self, stage: Stage) -> &str {
+        match stage {
+            Stage::Ingest => &self.ingest,
+            Stage::Transcript => &self.transcript,
+            Stage::Plan => &self.plan,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCheckpointsRequest {
+    project_id: String,
+}
+
+fn checkpoints_dir(project_id: &str) -> Result<PathBuf, String> {
+    Ok(path_safety::project_dir(project_id)?.join("checkpoints"))
+}
+
+pub fn chain_hash(previous: &str, parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(previous.as_bytes());
+    for part in parts {
+        hasher.update([0u8]);
+        hasher.update(part.as_bytes());
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Size and mtime of `input`, standing in for its contents; hashing a
+/// multi-GB source on every run would cost more than the stages it saves.
+pub fn input_fingerprint(input: &str) -> (u64, u128) {
+    fs::metadata(input)
+        .map(|meta| {
+            let modified = meta
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|elapsed| elapsed.as_millis())
+                .unwrap_or(0);
+            (meta.len(), modified)
+        })
+        .unwrap_or((0, 0))
+}
+
+pub fn stage_keys(
+    input: &str,
+    mode: &str,
+    language: &str,
+    transcription_model: Option<&str>,
+    cut_planner_model: Option<&str>,
+) -> StageKeys {
+    let (size, modified) = input_fingerprint(input);
+    let ingest = chain_hash("", &[input, &size.to_string(), &modified.to_string(), mode]);
+    let transcript = chain_hash(&ingest, &[language, transcription_model.unwrap_or("")]);
+    let plan = chain_hash(&transcript, &[cut_planner_model.unwrap_or("")]);
+    StageKeys {
+        ingest,
+        transcript,
+        plan,
+    }
+}
+
+fn read_checkpoint(project_id: &str, stage: Stage) -> Option<StageCheckpoint> {
+    let path = checkpoints_dir(project_id)
+        .ok()?
+        .join(format!("{}.json", stage.as_str()));
+    let raw = fs::read_to_string(path).ok()?;
+    serde_json::from_str::<StageCheckpoint>(&raw).ok()
+}
+
+pub fn read_checkpoints(project_id: &str) -> Vec<StageCheckpoint> {
+    STAGES
+        .iter()
+        .filter_map(|stage| read_checkpoint(project_id, *stage))
+        .collect()
+}
+
+/// The last stage whose checkpoint, and every one before it, matches `keys`.
+pub fn resume_stage(project_id: &str, keys: &StageKeys) -> Option<Stage> {
+    let mut resume = None;
+    for stage in STAGES {
+        match read_checkpoint(project_id, stage) {
+            Some(checkpoint) if checkpoint.stage == stage && checkpoint.key == keys.get(stage) => {
+                resume = Some(stage)
+            }
+            _ => break,
+        }
+    }
+    resume
+}
+
+pub fn clear_checkpoints(project_id: &str) -> Result<(), String> {
+    let dir = checkpoints_dir(project_id)?;
+    if dir.exists() {
+        fs::remove_dir_all(&dir)
+            .map_err(|error| format!("Failed clearing pipeline checkpoints: {error}"))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_pipeline_checkpoints(
+    request: ListCheckpointsRequest,
+) -> Result<Vec<StageCheckpoint>, String> {
+    tauri::async_runtime::spawn_blocking(move || Ok(read_checkpoints(&request.project_id)))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,290 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::{
+    create_project, render_workers, run_ingest_media, run_render_video, run_start_editing,
+    url_ingest, CreateProjectRequest, MediaIngestRequest, ProjectSettings, RenderVideoRequest,
+    StartEditingRequest,
+};
+
+const USAGE: &str = "\
+Usage:
+  lapaas-ai-editor-desktop render --project <id> [--quality <draft|balanced|quality>]
+      [--output-name <name>] [--encoder <auto|software|name>] [--burn-subtitles]
+      [--target-lufs <lufs>] [--no-watermark] [--no-verify]
+      [--no-cache] [--worker <worker id> [--proxy]]
+  lapaas-ai-editor-desktop edit --input <file|url> [--project <id>] [--name <name>]
+      [--fps <fps>] [--language <tag>] [--mode <hybrid|local|api>] [--restart] [--render]
+      [--keep-edits]
+  lapaas-ai-editor-desktop worker [--bind <address>] [--port <port>] [--token <token>]
+  lapaas-ai-editor-desktop help
+
+Runs the pipeline without opening a window. Results are printed to stdout
+as JSON; progress and errors go to stderr. Exit status is 0 on success.
+`worker` serves renders to other machines on the network until stopped. It
+speaks plain HTTP, so its token is sent in cleartext; use it on trusted
+networks only.";
+
+/// Flags of each subcommand that take no value.
+const RENDER_SWITCHES: &[&str] = &[
+    "burn-subtitles",
+    "no-watermark",
+    "no-verify",
+    "no-cache",
+    "proxy",
+];
+const EDIT_SWITCHES: &[&str] = &["restart", "render", "keep-edits"];
+
+/// `--key value` pairs, plus `switches` mapped to `true`. Positional
+/// arguments and repeated flags are errors.
+fn parse_flags(args: &[String], switches: &[&str]) -> Result<BTreeMap<String, String>, String> {
+    let mut flags = BTreeMap::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let Some(name) = arg.strip_prefix("--") else {
+            return Err(format!("Unexpected argument: {arg}"));
+        };
+        let value = if switches.contains(&name) {
+            "true".to_string()
+        } else {
+            args.next()
+                .filter(|value| !value.starts_with("--"))
+                .ok_or_else(|| format!("--{name} needs a value"))?
+                .clone()
+        };
+        if flags.insert(name.to_string(), value).is_some() {
+            return Err(format!("--{name} given more than once"));
+        }
+    }
+    Ok(flags)
+}
+
+/// Removes and returns `--name`, erroring on flags left over at the end.
+struct Flags(BTreeMap<String, String>);
+
+impl Flags {
+    fn take(&mut self, name: &str) -> Option<String> {
+        self.0.remove(name)
+    }
+
+    fn required(&mut self, name: &str) -> Result<String, String> {
+        self.take(name)
+            .ok_or_else(|| format!("Missing required flag --{name}"))
+    }
+
+    fn switch(&mut self, name: &str) -> bool {
+        self.take(name).is_some()
+    }
+
+    fn finish(self) -> Result<(), String> {
+        match self.0.keys().next() {
+            Some(name) => Err(format!("Unknown flag --{name}")),
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse_fps(value: Option<String>) -> Result<Option<u32>, String> {
+    value
+        .map(|fps| {
+            fps.parse::<u32>()
+                .map_err(|_| format!("--fps must be a whole number, got {fps}"))
+        })
+        .transpose()
+}
+
+fn parse_target_lufs(value: Option<String>) -> Result<Option<f64>, String> {
+    value
+        .map(|lufs| {
+            lufs.parse::<f64>()
+                .map_err(|_| format!("--target-lufs must be a number, got {lufs}"))
+        })
+        .transpose()
+}
+
+async fn render(mut flags: Flags) -> Result<Value, String> {
+    let target_lufs = parse_target_lufs(flags.take("target-lufs"))?;
+    let request = RenderVideoRequest {
+        project_id: flags.required("project")?,
+        output_name: flags.take("output-name"),
+        burn_subtitles: Some(flags.switch("burn-subtitles")),
+        quality: flags.take("quality"),
+        encoder: flags.take("encoder"),
+        priority: None,
+        normalize_loudness: target_lufs.map(|_| true),
+        target_lufs,
+        watermark: Some(!flags.switch("no-watermark")),
+        verify: Some(!flags.switch("no-verify")),
+        use_cache: Some(!flags.switch("no-cache")),
+        worker: flags.take("worker"),
+        use_proxy: Some(flags.switch("proxy")),
+    };
+    flags.finish()?;
+    run_render_video(request).await
+}
+
+/// Ingests `--input` into a new or existing project and builds the rough
+/// cut, then renders it when `--render` is given.
+async fn edit(mut flags: Flags) -> Result<Value, String> {
+    let input = flags.required("input")?;
+    let existing = flags.take("project");
+    let name = flags.take("name");
+    let fps = parse_fps(flags.take("fps"))?;
+    let language = flags.take("language");
+    let mode = flags.take("mode");
+    let force_restart = flags.switch("restart");
+    let then_render = flags.switch("render");
+    let merge_edits = flags.switch("keep-edits");
+    flags.finish()?;
+
+    let input_path = Path::new(&input);
+    if !url_ingest::is_url(&input) && !input_path.is_file() {
+        return Err(format!("Input file not found: {input}"));
+    }
+    let project_id = match existing {
+        Some(project_id) => project_id,
+        None => {
+            let name = name.unwrap_or_else(|| {
+                input_path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "Untitled".to_string())
+            });
+            let project = create_project(CreateProjectRequest {
+                name,
+                settings: ProjectSettings {
+                    aspect_ratio: "16:9".to_string(),
+                    fps: fps.unwrap_or(30),
+                    resolution: "1080p".to_string(),
+                    language: language.clone().unwrap_or_else(|| "en".to_string()),
+                    ai_mode: mode.clone().unwrap_or_else(|| "hybrid".to_string()),
+                    fallback_policy: None,
+                    transcription_model: None,
+                    cut_planner_model: None,
+                    template_planner_model: None,
+                    lut: None,
+                },
+            })
+            .await?;
+            eprintln!("Created project {}", project.id);
+            project.id
+        }
+    };
+
+    eprintln!("Ingesting {input}");
+    let ingest = run_ingest_media(MediaIngestRequest {
+        project_id: project_id.clone(),
+        input: input.clone(),
+        generate_proxy: Some(false),
+        generate_waveform: Some(false),
+        priority: None,
+    })
+    .await?;
+    // A URL was downloaded by the ingest; edit the local copy.
+    let input = ingest
+        .get("sourcePath")
+        .and_then(Value::as_str)
+        .map_or(input, str::to_string);
+    eprintln!("Building rough cut");
+    let editing = run_start_editing(StartEditingRequest {
+        project_id: project_id.clone(),
+        input,
+        mode,
+        language,
+        fps,
+        source_ref: None,
+        fallback_policy: None,
+        transcription_model: None,
+        cut_planner_model: None,
+        rough_cut_options: None,
+        force_restart: Some(force_restart),
+        priority: None,
+        merge_edits: Some(merge_edits),
+    })
+    .await?;
+    let render = if then_render {
+        eprintln!("Rendering");
+        Some(
+            run_render_video(RenderVideoRequest {
+                project_id: project_id.clone(),
+                output_name: None,
+                burn_subtitles: None,
+                quality: None,
+                encoder: None,
+                priority: None,
+                normalize_loudness: None,
+                target_lufs: None,
+                watermark: None,
+                verify: None,
+                use_cache: None,
+                worker: None,
+                use_proxy: None,
+            })
+            .await?,
+        )
+    } else {
+        None
+    };
+    Ok(serde_json::json!({
+        "ok": true,
+        "projectId": project_id,
+        "ingest": ingest,
+        "editing": editing,
+        "render": render,
+    }))
+}
+
+/// Serves renders to other machines; only returns on a startup error.
+fn worker(mut flags: Flags) -> Result<Value, String> {
+    let port = flags
+        .take("port")
+        .map(|port| {
+            port.parse::<u16>()
+                .map_err(|_| format!("--port must be a port number, got {port}"))
+        })
+        .transpose()?;
+    let token = flags.take("token");
+    let address = flags.take("bind");
+    flags.finish()?;
+    let server = render_workers::start_worker(address, port, token)?;
+    eprintln!(
+        "Render worker listening on {}:{}\nAdd it on the editing machine with token {}\n\
+         The token is sent unencrypted; only use this on a trusted network.",
+        server.address, server.port, server.token
+    );
+    server.run();
+    Ok(serde_json::json!({ "ok": true }))
+}
+
+/// Runs a CLI subcommand if the arguments (without the executable) start
+/// with one, returning the exit code; None means launch the app normally.
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, rest) = args.split_first()?;
+    let outcome = match command.as_str() {
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            return Some(0);
+        }
+        "render" => parse_flags(rest, RENDER_SWITCHES)
+            .and_then(|flags| tauri::async_runtime::block_on(render(Flags(flags)))),
+        "edit" => parse_flags(rest, EDIT_SWITCHES)
+            .and_then(|flags| tauri::async_runtime::block_on(edit(Flags(flags)))),
+        "worker" => parse_flags(rest, &[]).and_then(|flags| worker(Flags(flags))),
+        _ => return None,
+    };
+    match outcome {
+        Ok(result) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
+            );
+            Some(0)
+        }
+        Err(error) => {
+            eprintln!("Error: {error}\n\n{USAGE}");
+            Some(1)
+        }
+    }
+}
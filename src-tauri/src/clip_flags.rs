@@ -0,0 +1,236 @@
+use serde::Deserialize;
+
+use crate::timeline::TimeRange;
+use crate::timeline_merge::{self, subtract_ranges};
+use crate::{now_iso, read_timeline, write_timeline, Timeline, TimelineClip};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetClipFlagsRequest {
+    project_id: String,
+    clip_ids: Vec<String>,
+    /// Left as is when omitted.
+    locked: Option<bool>,
+    protected_from_ai: Option<bool>,
+}
+
+/// Source range of a locked or AI-protected source clip, which rebuilds of
+/// the rough cut keep.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtectedRange {
+    pub source_ref: String,
+    pub start_us: u64,
+    pub end_us: u64,
+    pub locked: bool,
+    pub protected_from_ai: bool,
+}
+
+pub fn is_unset(flag: &bool) -> bool {
+    !*flag
+}
+
+/// Clips AI plans and automatic passes must leave alone.
+pub fn is_ai_protected(clip: &TimelineClip) -> bool {
+    clip.locked || clip.protected_from_ai
+}
+
+pub fn ensure_unlocked(clip: &TimelineClip, force: bool) -> Result<(), String> {
+    if clip.locked && !force {
+        return Err(format!(
+            "Clip {} is locked; unlock it or force the change.",
+            clip.clip_id
+        ));
+    }
+    Ok(())
+}
+
+pub fn protected_ranges(timeline: &Timeline) -> Vec<ProtectedRange> {
+    timeline
+        .clips
+        .iter()
+        .filter(|clip| clip.clip_type == "source_clip" && is_ai_protected(clip))
+        .map(|clip| ProtectedRange {
+            source_ref: clip.source_ref.clone(),
+            start_us: clip.source_start_us,
+            end_us: clip.source_end_us,
+            locked: clip.locked,
+            protected_from_ai: clip.protected_from_ai,
+        })
+        .collect()
+}
+
+/// Cut ranges of `source_ref` without the parts inside a protected range.
+pub fn spare_protected(
+    ranges: Vec<TimeRange>,
+    source_ref: &str,
+    protected: &[ProtectedRange],
+) -> Vec<TimeRange> {
+    let mut holes = protected
+        .iter()
+        .filter(|range| range.source_ref == source_ref)
+        .map(|range| (range.start_us, range.end_us))
+        .collect::<Vec<_>>();
+    if holes.is_empty() {
+        return ranges;
+    }
+    holes.sort_unstable();
+    ranges
+        .into_iter()
+        .flat_map(|range| subtract_ranges(range.start_us, range.end_us, &holes))
+        .map(|(start_us, end_us)| TimeRange { start_us, end_us })
+        .collect()
+}
+
+/// Splits rebuilt source clips at the edges of protected ranges and flags
+/// the pieces inside them, so locks survive a rebuild of the rough cut.
+/// Source clips are renumbered when anything was split.
+pub fn carry_over(timeline: &mut Timeline, protected: &[ProtectedRange]) {
+    if protected.is_empty() {
+        return;
+    }
+    let mut split = false;
+    let mut clips = Vec::with_capacity(timeline.clips.len());
+    for clip in std::mem::take(&mut timeline.clips) {
+        let ranges = protected
+            .iter()
+            .filter(|range| {
+                range.source_ref == clip.source_ref
+                    && range.start_us < clip.source_end_us
+                    && range.end_us > clip.source_start_us
+            })
+            .collect::<Vec<_>>();
+        let rebuilt =
+            clip.clip_type == "source_clip" && clip.speed == 1.0 && clip.speed_keyframes.is_empty();
+        if ranges.is_empty() || !rebuilt {
+            clips.push(clip);
+            continue;
+        }
+
+        let mut points = vec![clip.source_start_us, clip.source_end_us];
+        for range in &ranges {
+            points.extend(
+                [range.start_us, range.end_us]
+                    .into_iter()
+                    .filter(|point| *point > clip.source_start_us && *point < clip.source_end_us),
+            );
+        }
+        points.sort_unstable();
+        points.dedup();
+        split |= points.len() > 2;
+        for window in points.windows(2) {
+            let (start_us, end_us) = (window[0], window[1]);
+            let mut piece = clip.clone();
+            piece.start_us = clip.start_us + (start_us - clip.source_start_us);
+            piece.end_us = piece.start_us + (end_us - start_us);
+            piece.source_start_us = start_us;
+            piece.source_end_us = end_us;
+            for range in ranges
+                .iter()
+                .filter(|range| range.start_us <= start_us && range.end_us >= end_us)
+            {
+                piece.locked |= range.locked;
+                piece.protected_from_ai |= range.protected_from_ai;
+            }
+            clips.push(piece);
+        }
+    }
+    if split {
+        for (index, clip) in clips
+            .iter_mut()
+            .filter(|clip| clip.clip_type == "source_clip")
+            .enumerate()
+        {
+            clip.clip_id = format!("clip-{}", index + 1);
+        }
+    }
+    timeline.clips = clips;
+}
+
+fn set_clip_flags_blocking(request: SetClipFlagsRequest) -> Result<Vec<TimelineClip>, String> {
+    let _save = timeline_merge::lock_saves();
+    let mut timeline = read_timeline(&request.project_id)?;
+    if let Some(missing) = request
+        .clip_ids
+        .iter()
+        .find(|id| !timeline.clips.iter().any(|clip| &clip.clip_id == *id))
+    {
+        return Err(format!("Clip not found: {missing}"));
+    }
+    let mut updated = Vec::new();
+    for clip in timeline
+        .clips
+        .iter_mut()
+        .filter(|clip| request.clip_ids.contains(&clip.clip_id))
+    {
+        if let Some(locked) = request.locked {
+            clip.locked = locked;
+        }
+        if let Some(protected_from_ai) = request.protected_from_ai {
+            clip.protected_from_ai = protected_from_ai;
+        }
+        updated.push(clip.clone());
+    }
+    timeline.version = timeline.version.saturating_add(1);
+    timeline.updated_at = now_iso();
+    write_timeline(&timeline)?;
+    Ok(updated)
+}
+
+/// Locks clips against edits and/or protects them from AI cuts and plans.
+/// Returns the updated clips.
+#[tauri::command]
+pub async fn set_clip_flags(request: SetClipFlagsRequest) -> Result<Vec<TimelineClip>, String> {
+    tauri::async_runtime::spawn_blocking(move || set_clip_flags_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start_us: u64, end_us: u64) -> ProtectedRange {
+        ProtectedRange {
+            source_ref: "source-video".to_string(),
+            start_us,
+            end_us,
+            locked: false,
+            protected_from_ai: true,
+        }
+    }
+
+    #[test]
+    fn cuts_spare_protected_ranges() {
+        let cuts = vec![
+            TimeRange {
+                start_us: 0,
+                end_us: 4_000_000,
+            },
+            TimeRange {
+                start_us: 8_000_000,
+                end_us: 9_000_000,
+            },
+        ];
+        let spared = spare_protected(cuts, "source-video", &[range(1_000_000, 2_000_000)])
+            .into_iter()
+            .map(|range| (range.start_us, range.end_us))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spared,
+            [
+                (0, 1_000_000),
+                (2_000_000, 4_000_000),
+                (8_000_000, 9_000_000)
+            ]
+        );
+        let other = spare_protected(
+            vec![TimeRange {
+                start_us: 0,
+                end_us: 4_000_000,
+            }],
+            "camera-b",
+            &[range(1_000_000, 2_000_000)],
+        );
+        assert_eq!(other.len(), 1);
+    }
+}
@@ -0,0 +1,433 @@
+use std::io::Read;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, State};
+use tiny_http::{Header, Method, Request, Response, Server, StatusCode};
+use tungstenite::handshake::derive_accept_key;
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+use crate::settings::{self, ControlApiSettings};
+use crate::{
+    actions, create_project, edit_now, get_render_history, get_timeline, ingest_media,
+    list_projects, project_bundle, render_video, scheduler, start_editing, windows,
+};
+
+const API_PREFIX: &str = "/api/";
+const EVENTS_PATH: &str = "/api/events";
+/// Event streams send a ping this often so dead clients are noticed.
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+/// Event stream clients, each fed by a thread writing to its socket.
+static SUBSCRIBERS: Mutex<Vec<Sender<String>>> = Mutex::new(Vec::new());
+
+struct Running {
+    server: Arc<Server>,
+    port: u16,
+    token: String,
+}
+
+/// The API server while it runs, registered as managed state.
+#[derive(Default)]
+pub struct ControlApi(Mutex<Option<Running>>);
+
+impl ControlApi {
+    fn lock(&self) -> MutexGuard<'_, Option<Running>> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlApiStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    /// `http://127.0.0.1:<port>/api/`, for copying into other tools.
+    pub url: Option<String>,
+}
+
+fn subscribers() -> MutexGuard<'static, Vec<Sender<String>>> {
+    SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Forwards an app event to connected event stream clients.
+pub fn publish<S: Serialize>(event: &str, payload: &S) {
+    let mut subscribers = subscribers();
+    if subscribers.is_empty() {
+        return;
+    }
+    let message = json!({ "event": event, "payload": payload }).to_string();
+    subscribers.retain(|subscriber| subscriber.send(message.clone()).is_ok());
+}
+
+/// 256-bit token from the OS random number generator, hex-encoded.
+pub fn generate_token() -> Result<String, String> {
+    let mut bytes = [0_u8; 32];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|error| format!("Failed generating API token: {error}"))?;
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+/// Fills in a token when the API is enabled without one. True if it did.
+pub fn ensure_token(settings: &mut ControlApiSettings) -> Result<bool, String> {
+    if !settings.enabled || !settings.token.trim().is_empty() {
+        return Ok(false);
+    }
+    settings.token = generate_token()?;
+    Ok(true)
+}
+
+/// Compares without bailing at the first differing byte.
+pub fn token_matches(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0, |diff, (left, right)| diff | (left ^ right))
+            == 0
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str())
+}
+
+/// Bearer token from the `Authorization` header, or from `?token=` since
+/// browser WebSocket clients cannot set headers.
+pub fn request_token(request: &Request) -> Option<String> {
+    if let Some(bearer) =
+        header(request, "Authorization").and_then(|value| value.strip_prefix("Bearer "))
+    {
+        return Some(bearer.trim().to_string());
+    }
+    let (_, query) = request.url().split_once('?')?;
+    query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+        .map(str::to_string)
+}
+
+fn json_response(status: u16, body: &Value) -> Response<std::io::Cursor<Vec<u8>>> {
+    let content_type =
+        Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+    Response::from_data(body.to_string().into_bytes())
+        .with_status_code(StatusCode(status))
+        .with_header(content_type)
+}
+
+pub fn respond(request: Request, status: u16, body: Value) {
+    if let Err(error) = request.respond(json_response(status, &body)) {
+        tracing::debug!("Control API client went away: {error}");
+    }
+}
+
+pub fn error_body(error: impl Into<String>) -> Value {
+    json!({ "ok": false, "error": error.into() })
+}
+
+fn parse<T: DeserializeOwned>(body: Value) -> Result<T, String> {
+    serde_json::from_value(body).map_err(|error| format!("Invalid request body: {error}"))
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|error| format!("Serialize error: {error}"))
+}
+
+/// Tauri commands callable as `POST /api/<command>`.
+const COMMANDS: &[&str] = &[
+    "list_projects",
+    "create_project",
+    "ingest_media",
+    "start_editing",
+    "edit_now",
+    "render_video",
+    "get_timeline",
+    "get_render_history",
+    "list_scheduled_jobs",
+    "set_queue_paused",
+    "export_project_bundle",
+    "import_project_bundle",
+    "open_project_window",
+    "list_actions",
+    "run_action",
+];
+
+/// Runs one of [`COMMANDS`] with `body` as its `request` argument, going
+/// through the scheduler and notifications like a call from the UI.
+async fn call(app: &AppHandle, command: &str, body: Value) -> Result<Value, String> {
+    match command {
+        "list_projects" => to_value(list_projects().await?),
+        "create_project" => to_value(create_project(parse(body)?).await?),
+        "ingest_media" => ingest_media(app.clone(), parse(body)?).await,
+        "start_editing" => to_value(start_editing(app.clone(), parse(body)?).await?),
+        "edit_now" => to_value(edit_now(app.clone(), parse(body)?).await?),
+        "render_video" => render_video(app.clone(), parse(body)?).await,
+        "get_timeline" => to_value(get_timeline(parse(body)?).await?),
+        "get_render_history" => get_render_history(parse(body)?).await,
+        "list_scheduled_jobs" => to_value(scheduler::list_scheduled_jobs(app.state())),
+        "set_queue_paused" => {
+            scheduler::set_queue_paused(app.clone(), parse(body)?);
+            Ok(Value::Null)
+        }
+        "export_project_bundle" => {
+            to_value(project_bundle::export_project_bundle(parse(body)?).await?)
+        }
+        "import_project_bundle" => {
+            to_value(project_bundle::import_project_bundle(parse(body)?).await?)
+        }
+        "open_project_window" => {
+            to_value(windows::open_project_window(app.clone(), app.state(), parse(body)?).await?)
+        }
+        "list_actions" => to_value(actions::list_actions()),
+        "run_action" => to_value(actions::run_action(parse(body)?).await?),
+        _ => Err(format!("Unknown command: {command}")),
+    }
+}
+
+/// `POST /api/<command>` with the command's request object as the body.
+fn handle_command(app: &AppHandle, mut request: Request) {
+    let path = request.url().split('?').next().unwrap_or_default();
+    let command = path.trim_start_matches(API_PREFIX).to_string();
+    let mut raw = String::new();
+    let read = request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut raw);
+    if let Err(error) = read {
+        respond(
+            request,
+            400,
+            error_body(format!("Failed reading body: {error}")),
+        );
+        return;
+    }
+    let body = if raw.trim().is_empty() {
+        Ok(json!({}))
+    } else {
+        serde_json::from_str::<Value>(&raw).map_err(|error| format!("Invalid JSON body: {error}"))
+    };
+    let body = match body {
+        Ok(body) => body,
+        Err(error) => {
+            respond(request, 400, error_body(error));
+            return;
+        }
+    };
+    if !COMMANDS.contains(&command.as_str()) {
+        respond(
+            request,
+            404,
+            error_body(format!("Unknown command: {command}")),
+        );
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tracing::info!("Control API call: {command}");
+        match call(&app, &command, body).await {
+            Ok(result) => respond(request, 200, json!({ "ok": true, "result": result })),
+            Err(error) => respond(request, 400, error_body(error)),
+        }
+    });
+}
+
+fn status_body(app: &AppHandle) -> Value {
+    json!({
+        "ok": true,
+        "result": {
+            "version": env!("CARGO_PKG_VERSION"),
+            "queuePaused": scheduler::is_paused(app),
+            "activeJobs": scheduler::active_jobs(app).len(),
+        }
+    })
+}
+
+/// Writes published events to one WebSocket client until it goes away or
+/// the server stops.
+fn stream_events(
+    mut socket: WebSocket<Box<dyn tiny_http::ReadWrite + Send>>,
+    events: Receiver<String>,
+) {
+    loop {
+        let message = match events.recv_timeout(PING_INTERVAL) {
+            Ok(event) => Message::Text(event),
+            Err(RecvTimeoutError::Timeout) => Message::Ping(Vec::new()),
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        if socket.send(message).is_err() {
+            return;
+        }
+    }
+    let _ = socket.close(None);
+    let _ = socket.flush();
+}
+
+/// `GET /api/events`: upgrades to a WebSocket that receives job events as
+/// `{"event": ..., "payload": ...}` text messages.
+fn handle_events(request: Request) {
+    let upgrade =
+        header(&request, "Upgrade").is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+    let Some(key) = header(&request, "Sec-WebSocket-Key").filter(|_| upgrade) else {
+        respond(request, 400, error_body("Expected a WebSocket upgrade"));
+        return;
+    };
+    let accept = Header::from_bytes("Sec-WebSocket-Accept", derive_accept_key(key.as_bytes()))
+        .expect("accept key is a valid header value");
+    let stream = request.upgrade(
+        "websocket",
+        Response::empty(StatusCode(101)).with_header(accept),
+    );
+    let socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+    let (sender, events) = mpsc::channel();
+    subscribers().push(sender);
+    thread::spawn(move || stream_events(socket, events));
+}
+
+fn handle(app: &AppHandle, token: &str, request: Request) {
+    let authorized = request_token(&request).is_some_and(|given| token_matches(token, &given));
+    if !authorized {
+        respond(request, 401, error_body("Missing or invalid API token"));
+        return;
+    }
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    match (request.method(), path.as_str()) {
+        (Method::Get, EVENTS_PATH) => handle_events(request),
+        (Method::Get, "/api/status") => {
+            let body = status_body(app);
+            respond(request, 200, body);
+        }
+        (Method::Post, path) if path.starts_with(API_PREFIX) => handle_command(app, request),
+        _ => respond(request, 404, error_body("Not found")),
+    }
+}
+
+fn start(app: &AppHandle, settings: &ControlApiSettings) -> Result<Running, String> {
+    // Loopback only: the token is the sole protection, so the API is never
+    // reachable from the network.
+    let server = Server::http(("127.0.0.1", settings.port)).map_err(|error| {
+        format!(
+            "Failed starting control API on port {}: {error}",
+            settings.port
+        )
+    })?;
+    let server = Arc::new(server);
+    let token = settings.token.trim().to_string();
+    thread::spawn({
+        let app = app.clone();
+        let server = Arc::clone(&server);
+        let token = token.clone();
+        move || {
+            for request in server.incoming_requests() {
+                handle(&app, &token, request);
+            }
+        }
+    });
+    tracing::info!("Control API listening on 127.0.0.1:{}", settings.port);
+    Ok(Running {
+        server,
+        port: settings.port,
+        token,
+    })
+}
+
+fn stop_running(running: Running) {
+    running.server.unblock();
+    // Dropping the senders ends every event stream.
+    subscribers().clear();
+    tracing::info!("Control API on port {} stopped", running.port);
+}
+
+/// Starts, stops or restarts the server to match `settings`.
+pub fn apply(app: &AppHandle, settings: &ControlApiSettings) {
+    let state = app.state::<ControlApi>();
+    let mut running = state.lock();
+    let token = settings.token.trim();
+    let unchanged = running
+        .as_ref()
+        .is_some_and(|current| current.port == settings.port && current.token == token);
+    if settings.enabled && unchanged {
+        return;
+    }
+    if let Some(current) = running.take() {
+        stop_running(current);
+    }
+    if !settings.enabled {
+        return;
+    }
+    if token.is_empty() {
+        tracing::warn!("Control API enabled without a token; not starting it");
+        return;
+    }
+    match start(app, settings) {
+        Ok(started) => *running = Some(started),
+        Err(error) => tracing::warn!("{error}"),
+    }
+}
+
+pub fn stop(app: &AppHandle) {
+    if let Some(running) = app.state::<ControlApi>().lock().take() {
+        stop_running(running);
+    }
+}
+
+/// Starts the API at launch if it is enabled, saving a generated token.
+pub fn init(app: &AppHandle) {
+    let mut app_settings = match settings::read_app_settings() {
+        Ok(app_settings) => app_settings,
+        Err(error) => {
+            tracing::warn!("Control API not started: {error}");
+            return;
+        }
+    };
+    match ensure_token(&mut app_settings.control_api) {
+        Ok(true) => {
+            if let Err(error) = settings::write_app_settings(&app_settings) {
+                tracing::warn!("Failed saving control API token: {error}");
+            }
+        }
+        Ok(false) => {}
+        Err(error) => {
+            tracing::warn!("Control API not started: {error}");
+            return;
+        }
+    }
+    apply(app, &app_settings.control_api);
+}
+
+#[tauri::command]
+pub fn control_api_status(api: State<'_, ControlApi>) -> ControlApiStatus {
+    let port = api.lock().as_ref().map(|running| running.port);
+    ControlApiStatus {
+        running: port.is_some(),
+        port,
+        url: port.map(|port| format!("http://127.0.0.1:{port}{API_PREFIX}")),
+    }
+}
+
+/// The stored bearer token, for copying into automation clients;
+/// `get_app_settings` only returns it masked.
+#[tauri::command]
+pub async fn reveal_control_api_token() -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(|| Ok(settings::read_app_settings()?.control_api.token))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
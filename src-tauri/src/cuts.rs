@@ -0,0 +1,606 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::project_status::ProjectStatus;
+use crate::timeline::{self, build_rough_cut_timeline, RoughCutOptions, TimeRange};
+use crate::{censor, clip_flags, markers, speed, sponsors};
+use crate::{
+    inherit_timeline_identity, now_iso, path_safety, read_timeline, update_project_status,
+    write_timeline, Timeline, TimelineClip,
+};
+
+/// Reviewer verdict on a single AI-proposed cut. Proposed cuts start out
+/// accepted because the initial rough cut already applies every one of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CutDecision {
+    Accept,
+    Reject,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposedCut {
+    pub id: String,
+    pub start_us: u64,
+    pub end_us: u64,
+    pub reason: Option<String>,
+    pub confidence: Option<f64>,
+    pub decision: CutDecision,
+}
+
+/// Everything needed to rebuild the rough cut after decisions change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposedCutSet {
+    pub project_id: String,
+    pub source_ref: String,
+    pub duration_us: u64,
+    pub fps: u32,
+    pub created_at: String,
+    pub updated_at: String,
+    pub cuts: Vec<ProposedCut>,
+    #[serde(default)]
+    pub options: RoughCutOptions,
+}
+
+/// Shape of a `removeRanges` entry as emitted by the planning scripts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedRemoveRange {
+    pub start_us: u64,
+    pub end_us: u64,
+    pub reason: Option<String>,
+    pub confidence: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListProposedCutsRequest {
+    project_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetCutDecisionRequest {
+    project_id: String,
+    cut_id: String,
+    decision: CutDecision,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyCutDecisionsRequest {
+    project_id: String,
+}
+
+fn proposed_cuts_file_path(project_id: &str) -> Result<PathBuf, String> {
+    Ok(path_safety::project_dir(project_id)?.join("proposed_cuts.json"))
+}
+
+pub fn read_proposed_cuts(project_id: &str) -> Result<ProposedCutSet, String> {
+    let file_path = proposed_cuts_file_path(project_id)?;
+    if !file_path.exists() {
+        return Err("No proposed cuts for this project.".to_string());
+    }
+    let raw = fs::read_to_string(&file_path)
+        .map_err(|error| format!("Failed reading proposed cuts file: {error}"))?;
+    serde_json::from_str::<ProposedCutSet>(&raw)
+        .map_err(|error| format!("Invalid proposed cuts JSON: {error}"))
+}
+
+pub fn write_proposed_cuts(set: &ProposedCutSet) -> Result<(), String> {
+    let file_path = proposed_cuts_file_path(&set.project_id)?;
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating project dir: {error}"))?;
+    }
+    let serialized = serde_json::to_string_pretty(set)
+        .map_err(|error| format!("Proposed cuts serialize error: {error}"))?;
+    fs::write(&file_path, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing proposed cuts file: {error}"))
+}
+
+/// Assigns stable IDs to the planner output and persists it for review.
+pub fn record_proposed_cuts(
+    project_id: &str,
+    source_ref: &str,
+    duration_us: u64,
+    fps: u32,
+    planned: Vec<PlannedRemoveRange>,
+    options: RoughCutOptions,
+) -> Result<ProposedCutSet, String> {
+    let cuts = planned
+        .into_iter()
+        .filter(|range| range.end_us > range.start_us)
+        .enumerate()
+        .map(|(index, range)| ProposedCut {
+            id: format!("cut-{}", index + 1),
+            start_us: range.start_us,
+            end_us: range.end_us,
+            reason: range.reason,
+            confidence: range.confidence,
+            decision: CutDecision::Accept,
+        })
+        .collect();
+
+    let now = now_iso();
+    let set = ProposedCutSet {
+        project_id: project_id.to_string(),
+        source_ref: source_ref.to_string(),
+        duration_us,
+        fps,
+        created_at: now.clone(),
+        updated_at: now,
+        cuts,
+        options,
+    };
+    write_proposed_cuts(&set)?;
+    Ok(set)
+}
+
+pub fn accepted_ranges(set: &ProposedCutSet) -> Vec<TimeRange> {
+    set.cuts
+        .iter()
+        .filter(|cut| cut.decision == CutDecision::Accept)
+        .map(|cut| TimeRange {
+            start_us: cut.start_us,
+            end_us: cut.end_us,
+        })
+        .collect()
+}
+
+/// Loads the cut set for a project, or reconstructs one from the gaps between
+/// the source clips of its current timeline when no planner run recorded it.
+pub fn load_or_derive_cut_set(project_id: &str) -> Result<ProposedCutSet, String> {
+    if let Ok(set) = read_proposed_cuts(project_id) {
+        return Ok(set);
+    }
+    let timeline = read_timeline(project_id)?;
+    let mut source_clips = timeline
+        .clips
+        .iter()
+        .filter(|clip| clip.clip_type == "source_clip")
+        .collect::<Vec<_>>();
+    let first = source_clips
+        .first()
+        .ok_or_else(|| "Timeline has no source clips to edit.".to_string())?;
+    let source_ref = first.source_ref.clone();
+    source_clips.retain(|clip| clip.source_ref == source_ref);
+    source_clips.sort_by_key(|clip| clip.source_start_us);
+
+    let duration_us = source_clips
+        .iter()
+        .map(|clip| clip.source_end_us)
+        .max()
+        .unwrap_or(0);
+    let mut cursor = 0_u64;
+    let mut planned = Vec::new();
+    for clip in &source_clips {
+        if clip.source_start_us > cursor {
+            planned.push(PlannedRemoveRange {
+                start_us: cursor,
+                end_us: clip.source_start_us,
+                reason: Some("existing".to_string()),
+                confidence: None,
+            });
+        }
+        cursor = cursor.max(clip.source_end_us);
+    }
+
+    record_proposed_cuts(
+        project_id,
+        &source_ref,
+        duration_us,
+        timeline.fps,
+        planned,
+        RoughCutOptions::default(),
+    )
+}
+
+/// Adds user-authored removals to the set as accepted cuts.
+pub fn append_cuts(set: &mut ProposedCutSet, ranges: &[TimeRange], reason: &str) {
+    let mut next_index = set
+        .cuts
+        .iter()
+        .filter_map(|cut| cut.id.strip_prefix("cut-"))
+        .filter_map(|suffix| suffix.parse::<usize>().ok())
+        .max()
+        .unwrap_or(0);
+    for range in ranges {
+        next_index += 1;
+        set.cuts.push(ProposedCut {
+            id: format!("cut-{next_index}"),
+            start_us: range.start_us,
+            end_us: range.end_us,
+            reason: Some(reason.to_string()),
+            confidence: None,
+            decision: CutDecision::Accept,
+        });
+    }
+    set.cuts.sort_by_key(|cut| cut.start_us);
+    set.updated_at = now_iso();
+}
+
+/// Clips that play media of their own keep their length when moved; the
+/// rest (captions, titles, overlays) are trimmed to the footage left under
+/// them.
+const MEDIA_CLIP_TYPES: &[&str] = &["source_clip", "audio_clip"];
+
+/// Whether `clip` is main-track footage of `source_ref`, directly or as a
+/// multicam angle of it.
+fn is_cut_footage(clip: &TimelineClip, source_ref: &str, main_track: Option<&str>) -> bool {
+    let master = clip
+        .meta
+        .pointer("/multicam/masterSourceRef")
+        .and_then(Value::as_str)
+        .unwrap_or(&clip.source_ref);
+    clip.clip_type == "source_clip"
+        && master == source_ref
+        && main_track.map_or(true, |track| clip.track_id == track)
+}
+
+/// Where a timeline point of the `old` cut plays in the `new` one, found
+/// through the source time shown there. A start in footage that was cut
+/// moves to the next kept clip; an end moves back to the previous one.
+fn map_position(old: &[TimelineClip], new: &[TimelineClip], time_us: u64, is_end: bool) -> u64 {
+    let old_end = old.last().map_or(0, |clip| clip.end_us);
+    let new_end = new.last().map_or(0, |clip| clip.end_us);
+    if time_us >= old_end && (!is_end || time_us > old_end) {
+        return new_end + (time_us - old_end);
+    }
+    let covers = |start: u64, end: u64, at: u64| {
+        if is_end {
+            start < at && at <= end
+        } else {
+            start <= at && at < end
+        }
+    };
+    let source_us = match old
+        .iter()
+        .find(|clip| covers(clip.start_us, clip.end_us, time_us))
+    {
+        Some(clip) => {
+            clip.source_start_us + speed::timeline_to_source_offset(clip, time_us - clip.start_us)
+        }
+        // In a gap between clips: pin to the footage next to it.
+        None if is_end => match old.iter().rev().find(|clip| clip.end_us <= time_us) {
+            Some(clip) => clip.source_end_us,
+            None => return time_us,
+        },
+        None => match old.iter().find(|clip| clip.start_us > time_us) {
+            Some(clip) => clip.source_start_us,
+            None => return time_us,
+        },
+    };
+    if let Some(clip) = new
+        .iter()
+        .find(|clip| covers(clip.source_start_us, clip.source_end_us, source_us))
+    {
+        return clip.start_us
+            + speed::source_to_timeline_offset(clip, source_us - clip.source_start_us);
+    }
+    if is_end {
+        new.iter()
+            .rev()
+            .find(|clip| clip.source_end_us <= source_us)
+            .map_or(0, |clip| clip.end_us)
+    } else {
+        new.iter()
+            .find(|clip| clip.source_start_us >= source_us)
+            .map_or(new_end, |clip| clip.start_us)
+    }
+}
+
+/// Gives each rebuilt clip the look of the previous clip it shares the most
+/// source with: effects (censors are attached again separately), transform
+/// and speed. Keyframes only carry over to an unchanged source range. The
+/// clips are then laid out back to back again.
+fn inherit_clip_looks(clips: &mut [TimelineClip], previous: &[TimelineClip]) {
+    let mut cursor = 0;
+    for clip in clips.iter_mut() {
+        let overlap = |other: &TimelineClip| {
+            clip.source_end_us
+                .min(other.source_end_us)
+                .saturating_sub(clip.source_start_us.max(other.source_start_us))
+        };
+        if let Some(other) = previous
+            .iter()
+            .filter(|other| overlap(other) > 0)
+            .max_by_key(|other| overlap(other))
+        {
+            clip.effects = other.effects.clone();
+            if let Some(effects) = clip.effects.as_object_mut() {
+                effects.remove("censor");
+            }
+            clip.transform = other.transform.clone();
+            clip.speed = other.speed;
+            if other.source_start_us == clip.source_start_us
+                && other.source_end_us == clip.source_end_us
+            {
+                clip.speed_keyframes = other.speed_keyframes.clone();
+            }
+        }
+        clip.start_us = cursor;
+        clip.end_us = cursor + speed::timeline_length_us(clip);
+        cursor = clip.end_us;
+    }
+}
+
+/// Swaps the main-track footage of `previous` for the `rebuilt` source clips
+/// and keeps every other track and clip, moved to where the footage under
+/// it now plays. Clips whose footage was cut away entirely are dropped.
+fn merge_rebuilt(previous: &Timeline, rebuilt: Timeline, source_ref: &str) -> Timeline {
+    let main_track = timeline::main_track_clips(previous)
+        .first()
+        .map(|clip| clip.track_id.clone());
+    let mut old_main = previous
+        .clips
+        .iter()
+        .filter(|clip| is_cut_footage(clip, source_ref, main_track.as_deref()))
+        .cloned()
+        .collect::<Vec<_>>();
+    old_main.sort_by_key(|clip| clip.start_us);
+    let mut new_main = rebuilt.clips;
+    new_main.sort_by_key(|clip| clip.start_us);
+    if let Some(track_id) = &main_track {
+        for clip in &mut new_main {
+            clip.track_id = track_id.clone();
+        }
+    }
+    inherit_clip_looks(&mut new_main, &old_main);
+
+    let mut clips = new_main.clone();
+    for clip in previous
+        .clips
+        .iter()
+        .filter(|clip| !is_cut_footage(clip, source_ref, main_track.as_deref()))
+    {
+        let mut clip = clip.clone();
+        let length = clip.end_us - clip.start_us;
+        clip.start_us = map_position(&old_main, &new_main, clip.start_us, false);
+        if MEDIA_CLIP_TYPES.contains(&clip.clip_type.as_str())
+            || clip.clip_type == markers::MARKER_CLIP_TYPE
+        {
+            clip.end_us = clip.start_us + length;
+        } else {
+            clip.end_us = map_position(&old_main, &new_main, clip.end_us, true);
+            if clip.end_us <= clip.start_us {
+                continue;
+            }
+            if clip.source_end_us > clip.source_start_us {
+                clip.source_end_us = clip
+                    .source_end_us
+                    .min(clip.source_start_us + (clip.end_us - clip.start_us));
+            }
+        }
+        clips.push(clip);
+    }
+
+    let mut tracks = previous.tracks.clone();
+    for track in rebuilt.tracks {
+        let used = clips.iter().any(|clip| clip.track_id == track.id);
+        if used && !tracks.iter().any(|existing| existing.id == track.id) {
+            tracks.push(track);
+        }
+    }
+    let mut meta = match &previous.meta {
+        Value::Object(meta) => meta.clone(),
+        _ => Map::new(),
+    };
+    meta.remove("censoredCuts");
+    if let Value::Object(rebuilt_meta) = rebuilt.meta {
+        meta.extend(rebuilt_meta);
+    }
+
+    let mut timeline = Timeline {
+        id: previous.id.clone(),
+        project_id: previous.project_id.clone(),
+        version: previous.version,
+        status: rebuilt.status,
+        fps: rebuilt.fps,
+        duration_us: clips
+            .iter()
+            .filter(|clip| clip.clip_type != markers::MARKER_CLIP_TYPE)
+            .map(|clip| clip.end_us)
+            .max()
+            .unwrap_or(0),
+        created_at: previous.created_at.clone(),
+        updated_at: rebuilt.updated_at,
+        tracks,
+        clips,
+        meta: Value::Object(meta),
+    };
+    markers::respan_chapters(&mut timeline);
+    timeline
+}
+
+/// Rebuilds the rough cut's footage from accepted removals only, keeping the
+/// existing timeline identity and bumping its version. Titles, captions,
+/// markers, overlays and audio stay and follow the footage they sat on.
+/// Locked and AI-protected clips are never cut into. Does not persist.
+pub fn build_timeline_from_decisions(set: &ProposedCutSet) -> Timeline {
+    let previous = read_timeline(&set.project_id).ok();
+    let protected = previous
+        .as_ref()
+        .map(clip_flags::protected_ranges)
+        .unwrap_or_default();
+    let mut rebuilt = build_rough_cut_timeline(
+        set.project_id.clone(),
+        set.duration_us,
+        set.fps,
+        set.source_ref.clone(),
+        clip_flags::spare_protected(accepted_ranges(set), &set.source_ref, &protected),
+        &set.options,
+        &sponsors::protected_ranges(&set.project_id),
+    );
+    clip_flags::carry_over(&mut rebuilt, &protected);
+    let mut timeline = match &previous {
+        Some(previous) => merge_rebuilt(previous, rebuilt, &set.source_ref),
+        None => rebuilt,
+    };
+    inherit_timeline_identity(&mut timeline);
+    censor::carry_over(&mut timeline, previous.as_ref(), set);
+    timeline
+}
+
+pub fn rebuild_timeline_from_decisions(set: &ProposedCutSet) -> Result<Timeline, String> {
+    let timeline = build_timeline_from_decisions(set);
+    write_timeline(&timeline)?;
+    Ok(timeline)
+}
+
+#[tauri::command]
+pub async fn list_proposed_cuts(
+    request: ListProposedCutsRequest,
+) -> Result<ProposedCutSet, String> {
+    tauri::async_runtime::spawn_blocking(move || read_proposed_cuts(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn set_cut_decision(request: SetCutDecisionRequest) -> Result<ProposedCut, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut set = read_proposed_cuts(&request.project_id)?;
+        let cut = set
+            .cuts
+            .iter_mut()
+            .find(|cut| cut.id == request.cut_id)
+            .ok_or_else(|| format!("Cut not found: {}", request.cut_id))?;
+        cut.decision = request.decision;
+        let updated = cut.clone();
+        set.updated_at = now_iso();
+        write_proposed_cuts(&set)?;
+        Ok(updated)
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn apply_cut_decisions(request: ApplyCutDecisionsRequest) -> Result<Timeline, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let set = read_proposed_cuts(&request.project_id)?;
+        let timeline = rebuild_timeline_from_decisions(&set)?;
+        update_project_status(&request.project_id, ProjectStatus::RoughCutReady)?;
+        Ok(timeline)
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use serde_json::json;
+
+    fn clip(
+        id: &str,
+        track_id: &str,
+        clip_type: &str,
+        span: (u64, u64),
+        source: (u64, u64),
+    ) -> TimelineClip {
+        let mut clip = test_fixtures::clip(id, track_id, span.0, span.1);
+        clip.clip_type = clip_type.to_string();
+        (clip.source_start_us, clip.source_end_us) = source;
+        clip
+    }
+
+    fn timeline(tracks: &[(&str, &str)], clips: Vec<TimelineClip>) -> Timeline {
+        let tracks = tracks
+            .iter()
+            .enumerate()
+            .map(|(order, (id, kind))| test_fixtures::track(id, kind, order as u32))
+            .collect();
+        let mut timeline = test_fixtures::timeline(30, tracks, clips);
+        timeline.version = 3;
+        timeline.duration_us = 4_000_000;
+        timeline
+    }
+
+    #[test]
+    fn rebuild_keeps_other_tracks_and_moves_them_with_the_footage() {
+        let mut first = clip("a", "main", "source_clip", (0, 2_000_000), (0, 2_000_000));
+        first.transform = json!({ "scale": 2 });
+        let previous = timeline(
+            &[("main", "video"), ("captions", "caption"), ("vo", "audio")],
+            vec![
+                first,
+                clip(
+                    "b",
+                    "main",
+                    "source_clip",
+                    (2_000_000, 4_000_000),
+                    (4_000_000, 6_000_000),
+                ),
+                clip(
+                    "cap",
+                    "captions",
+                    "caption_clip",
+                    (1_500_000, 2_500_000),
+                    (0, 0),
+                ),
+                clip("title", "captions", "title", (2_500_000, 3_500_000), (0, 0)),
+                clip(
+                    "take",
+                    "vo",
+                    "audio_clip",
+                    (3_000_000, 4_000_000),
+                    (0, 1_000_000),
+                ),
+            ],
+        );
+        // Source 1s..5s is now cut.
+        let rebuilt = timeline(
+            &[("track-video-main", "video")],
+            vec![
+                clip(
+                    "clip-1",
+                    "track-video-main",
+                    "source_clip",
+                    (0, 1_000_000),
+                    (0, 1_000_000),
+                ),
+                clip(
+                    "clip-2",
+                    "track-video-main",
+                    "source_clip",
+                    (1_000_000, 2_000_000),
+                    (5_000_000, 6_000_000),
+                ),
+            ],
+        );
+        let merged = merge_rebuilt(&previous, rebuilt, "source-video");
+        let spans = merged
+            .clips
+            .iter()
+            .map(|clip| {
+                (
+                    clip.clip_id.as_str(),
+                    clip.track_id.as_str(),
+                    clip.start_us,
+                    clip.end_us,
+                )
+            })
+            .collect::<Vec<_>>();
+        // The caption sat only on cut footage and goes with it.
+        assert_eq!(
+            spans,
+            [
+                ("clip-1", "main", 0, 1_000_000),
+                ("clip-2", "main", 1_000_000, 2_000_000),
+                ("title", "captions", 1_000_000, 1_500_000),
+                ("take", "vo", 1_000_000, 2_000_000),
+            ]
+        );
+        assert_eq!(merged.clips[0].transform, json!({ "scale": 2 }));
+        assert_eq!(merged.tracks.len(), 3);
+        assert_eq!(merged.duration_us, 2_000_000);
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::captions::CAPTION_CLIP_TYPE;
+use crate::cuts::read_proposed_cuts;
+use crate::subtitles::timeline_words;
+use crate::timeline::{main_track_clips, normalize_ranges, TimeRange};
+use crate::timeline_stats::content_end_us;
+use crate::transcript::{read_transcript, Transcript};
+use crate::{read_timeline, Timeline, TimelineClip};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditAnalyticsRequest {
+    project_id: String,
+}
+
+/// Transcript-derived pacing of the edit, in timeline time.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeechAnalytics {
+    pub word_count: usize,
+    pub speech_us: u64,
+    pub silence_us: u64,
+    /// Share of the edit with someone speaking, 0-1.
+    pub speech_ratio: f64,
+    /// Over the whole edit.
+    pub words_per_minute: f64,
+    /// Share of speech under a caption clip, 0-1.
+    pub caption_coverage: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditAnalytics {
+    pub duration_us: u64,
+    /// Footage the edit draws on, per source, before cuts.
+    pub source_duration_us: u64,
+    pub total_removed_us: u64,
+    /// Points on the main track where the picture jumps to other footage.
+    pub cut_count: usize,
+    pub shot_count: usize,
+    pub average_shot_us: u64,
+    /// Missing when the project has no transcript.
+    pub speech: Option<SpeechAnalytics>,
+}
+
+/// Shot lengths on the main track: clips that continue the previous one's
+/// footage without a jump count as one shot.
+fn shot_lengths(clips: &[&TimelineClip]) -> Vec<u64> {
+    let mut shots = Vec::<u64>::new();
+    let mut previous: Option<&TimelineClip> = None;
+    for clip in clips {
+        let length = clip.end_us.saturating_sub(clip.start_us);
+        let continues = previous.is_some_and(|previous| {
+            previous.source_ref == clip.source_ref
+                && previous.source_end_us == clip.source_start_us
+                && previous.end_us == clip.start_us
+        });
+        match shots.last_mut() {
+            Some(last) if continues => *last += length,
+            _ => shots.push(length),
+        }
+        previous = Some(clip);
+    }
+    shots
+}
+
+fn covered_us(ranges: &[TimeRange]) -> u64 {
+    ranges
+        .iter()
+        .map(|range| range.end_us - range.start_us)
+        .sum()
+}
+
+fn intersection_us(left: &[TimeRange], right: &[TimeRange]) -> u64 {
+    left.iter()
+        .flat_map(|a| {
+            right.iter().map(move |b| {
+                a.end_us
+                    .min(b.end_us)
+                    .saturating_sub(a.start_us.max(b.start_us))
+            })
+        })
+        .sum()
+}
+
+fn ratio(part: u64, whole: u64) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+fn speech_analytics(
+    transcript: &Transcript,
+    timeline: &Timeline,
+    duration_us: u64,
+) -> SpeechAnalytics {
+    let words = timeline_words(transcript, timeline)
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    let speech = normalize_ranges(
+        words
+            .iter()
+            .map(|word| TimeRange {
+                start_us: word.start_us,
+                end_us: word.end_us,
+            })
+            .collect(),
+        duration_us,
+    );
+    let captions = normalize_ranges(
+        timeline
+            .clips
+            .iter()
+            .filter(|clip| clip.clip_type == CAPTION_CLIP_TYPE)
+            .map(|clip| TimeRange {
+                start_us: clip.start_us,
+                end_us: clip.end_us,
+            })
+            .collect(),
+        duration_us,
+    );
+    let speech_us = covered_us(&speech);
+    // Segments without word timings come through as one span.
+    let word_count = words
+        .iter()
+        .map(|word| word.text.split_whitespace().count())
+        .sum::<usize>();
+    let minutes = duration_us as f64 / 60_000_000.0;
+    SpeechAnalytics {
+        word_count,
+        speech_us,
+        silence_us: duration_us.saturating_sub(speech_us),
+        speech_ratio: ratio(speech_us, duration_us),
+        words_per_minute: if minutes > 0.0 {
+            word_count as f64 / minutes
+        } else {
+            0.0
+        },
+        caption_coverage: ratio(intersection_us(&speech, &captions), speech_us),
+    }
+}
+
+/// Pacing figures for the edit. `source_durations` gives known footage
+/// lengths; other sources count up to the last point the edit uses.
+pub fn edit_analytics(
+    timeline: &Timeline,
+    transcript: Option<&Transcript>,
+    source_durations: &BTreeMap<String, u64>,
+) -> EditAnalytics {
+    let duration_us = content_end_us(timeline);
+
+    let mut kept = BTreeMap::<&str, Vec<TimeRange>>::new();
+    for clip in timeline
+        .clips
+        .iter()
+        .filter(|clip| clip.clip_type == "source_clip")
+    {
+        kept.entry(clip.source_ref.as_str())
+            .or_default()
+            .push(TimeRange {
+                start_us: clip.source_start_us,
+                end_us: clip.source_end_us,
+            });
+    }
+    let mut source_duration_us = 0;
+    let mut kept_us = 0;
+    for (source_ref, ranges) in kept {
+        let used_until = ranges.iter().map(|range| range.end_us).max().unwrap_or(0);
+        let extent = source_durations
+            .get(source_ref)
+            .copied()
+            .unwrap_or(used_until)
+            .max(used_until);
+        source_duration_us += extent;
+        kept_us += covered_us(&normalize_ranges(ranges, extent));
+    }
+
+    let shots = shot_lengths(&main_track_clips(timeline));
+    EditAnalytics {
+        duration_us,
+        source_duration_us,
+        total_removed_us: source_duration_us.saturating_sub(kept_us),
+        cut_count: shots.len().saturating_sub(1),
+        shot_count: shots.len(),
+        average_shot_us: shots.iter().sum::<u64>() / shots.len().max(1) as u64,
+        speech: transcript.map(|transcript| speech_analytics(transcript, timeline, duration_us)),
+    }
+}
+
+/// Cut count, shot length, removed footage, speech/silence, words per
+/// minute and caption coverage of the current edit, for judging pacing
+/// before a render.
+#[tauri::command]
+pub async fn get_edit_analytics(request: EditAnalyticsRequest) -> Result<EditAnalytics, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let timeline = read_timeline(&request.project_id)?;
+        let transcript = read_transcript(&request.project_id).ok();
+        let source_durations = read_proposed_cuts(&request.project_id)
+            .map(|set| BTreeMap::from([(set.source_ref, set.duration_us)]))
+            .unwrap_or_default();
+        Ok(edit_analytics(
+            &timeline,
+            transcript.as_ref(),
+            &source_durations,
+        ))
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_fixtures;
+
+    fn clip(id: &str, start_us: u64, source_start_us: u64, length_us: u64) -> TimelineClip {
+        let mut clip = test_fixtures::clip(id, "video-main", start_us, start_us + length_us);
+        clip.source_start_us = source_start_us;
+        clip.source_end_us = source_start_us + length_us;
+        clip
+    }
+
+    #[test]
+    fn analytics_count_cuts_removed_footage_and_speech() {
+        let mut caption = clip("caption-1", 0, 0, 2_000_000);
+        caption.clip_type = CAPTION_CLIP_TYPE.to_string();
+        let timeline = test_fixtures::timeline(
+            30,
+            Vec::new(),
+            vec![
+                clip("clip-1", 0, 0, 2_000_000),
+                clip("clip-2", 2_000_000, 2_000_000, 2_000_000),
+                clip("clip-3", 4_000_000, 10_000_000, 2_000_000),
+                caption,
+            ],
+        );
+        let transcript: Transcript = serde_json::from_value(json!({
+            "segments": [
+                {"id": "s1", "startUs": 1_000_000, "endUs": 3_000_000, "text": "two words"},
+                {"id": "s2", "startUs": 6_000_000, "endUs": 8_000_000, "text": "cut away"},
+            ]
+        }))
+        .unwrap();
+        let durations = BTreeMap::from([("source-video".to_string(), 20_000_000)]);
+        let analytics = edit_analytics(&timeline, Some(&transcript), &durations);
+        assert_eq!(analytics.duration_us, 6_000_000);
+        assert_eq!((analytics.shot_count, analytics.cut_count), (2, 1));
+        assert_eq!(analytics.average_shot_us, 3_000_000);
+        assert_eq!(analytics.total_removed_us, 14_000_000);
+        let speech = analytics.speech.unwrap();
+        assert_eq!((speech.word_count, speech.speech_us), (2, 2_000_000));
+        assert_eq!(speech.caption_coverage, 0.5);
+        assert_eq!(speech.words_per_minute, 20.0);
+    }
+}
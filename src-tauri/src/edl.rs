@@ -0,0 +1,351 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::project_status::ProjectStatus;
+use crate::{
+    generate_project_id, inherit_timeline_identity, mark_timeline_written, now_iso, read_projects,
+    write_timeline, Timeline, TimelineClip, TimelineTrack,
+};
+
+/// Where the media for an EDL reel lives.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EdlReelSource {
+    source_ref: String,
+    /// Timecode of the first frame of the media; source timecodes in the EDL
+    /// are relative to it. Defaults to `00:00:00:00`.
+    start_timecode: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportEdlRequest {
+    project_id: String,
+    path: String,
+    /// Reel name (or `FROM CLIP NAME`) to media asset.
+    #[serde(default)]
+    source_map: HashMap<String, EdlReelSource>,
+    /// Defaults to the project frame rate.
+    fps: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EdlImport {
+    pub title: Option<String>,
+    pub event_count: usize,
+    pub unmapped_reels: BTreeSet<String>,
+    pub warnings: Vec<String>,
+    pub timeline: Timeline,
+}
+
+#[derive(Debug, Clone)]
+struct EdlEvent {
+    number: String,
+    reel: String,
+    channels: String,
+    transition: String,
+    source_in: u64,
+    source_out: u64,
+    record_in: u64,
+    record_out: u64,
+    clip_name: Option<String>,
+}
+
+/// Converts `HH:MM:SS:FF` (any of `:;.` as separators) to a frame count,
+/// applying SMPTE drop-frame numbering when requested.
+fn timecode_to_frames(timecode: &str, fps: u32, drop_frame: bool) -> Option<u64> {
+    let parts = timecode
+        .split([':', ';', '.'])
+        .map(|part| part.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let [hours, minutes, seconds, frames] = parts[..] else {
+        return None;
+    };
+    let fps = u64::from(fps.max(1));
+    let mut total = ((hours * 3600 + minutes * 60 + seconds) * fps) + frames;
+    if drop_frame && fps % 30 == 0 {
+        let dropped_per_minute = fps / 15;
+        let total_minutes = hours * 60 + minutes;
+        total = total.saturating_sub(dropped_per_minute * (total_minutes - total_minutes / 10));
+    }
+    Some(total)
+}
+
+/// Drop-frame timecode runs at the NTSC rate (fps * 1000/1001).
+fn frames_to_us(frames: u64, fps: u32, drop_frame: bool) -> u64 {
+    let fps = u128::from(fps.max(1));
+    let frames = u128::from(frames);
+    let us = if drop_frame {
+        (frames * 1_001_000_000 + fps * 500) / (fps * 1_000)
+    } else {
+        (frames * 1_000_000 + fps / 2) / fps
+    };
+    us as u64
+}
+
+fn is_timecode(token: &str) -> bool {
+    token.split([':', ';', '.']).count() == 4
+        && token
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, ':' | ';' | '.'))
+}
+
+fn parse_edl(
+    raw: &str,
+    fps: u32,
+    warnings: &mut Vec<String>,
+) -> (Option<String>, bool, Vec<EdlEvent>) {
+    let mut title = None;
+    let mut drop_frame = false;
+    let mut events: Vec<EdlEvent> = Vec::new();
+
+    for line in raw.lines() {
+        let trimmed = line.trim();
+        if let Some(value) = trimmed.strip_prefix("TITLE:") {
+            title = Some(value.trim().to_string());
+            continue;
+        }
+        if let Some(value) = trimmed.strip_prefix("FCM:") {
+            drop_frame = value.trim().eq_ignore_ascii_case("DROP FRAME");
+            continue;
+        }
+        if let Some(comment) = trimmed.strip_prefix('*') {
+            let comment = comment.trim();
+            if let Some(name) = comment.strip_prefix("FROM CLIP NAME:") {
+                if let Some(event) = events.last_mut() {
+                    event.clip_name = Some(name.trim().to_string());
+                }
+            }
+            continue;
+        }
+        if trimmed.starts_with("M2") {
+            warnings.push(format!(
+                "Speed change ignored: {}",
+                trimmed.split_whitespace().collect::<Vec<_>>().join(" ")
+            ));
+            continue;
+        }
+
+        let tokens = trimmed.split_whitespace().collect::<Vec<_>>();
+        if tokens.len() < 8 || !tokens[0].chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let times = &tokens[tokens.len() - 4..];
+        if !times.iter().all(|token| is_timecode(token)) {
+            warnings.push(format!("Unparseable event line: {trimmed}"));
+            continue;
+        }
+        let drop_frame = drop_frame || times.iter().any(|token| token.contains(';'));
+        let frames = times
+            .iter()
+            .filter_map(|token| timecode_to_frames(token, fps, drop_frame))
+            .collect::<Vec<_>>();
+        if frames.len() != 4 {
+            warnings.push(format!("Unparseable event line: {trimmed}"));
+            continue;
+        }
+        events.push(EdlEvent {
+            number: tokens[0].to_string(),
+            reel: tokens[1].to_string(),
+            channels: tokens[2].to_uppercase(),
+            transition: tokens[3].to_uppercase(),
+            source_in: frames_to_us(frames[0], fps, drop_frame),
+            source_out: frames_to_us(frames[1], fps, drop_frame),
+            record_in: frames_to_us(frames[2], fps, drop_frame),
+            record_out: frames_to_us(frames[3], fps, drop_frame),
+            clip_name: None,
+        });
+    }
+
+    (title, drop_frame, events)
+}
+
+fn project_fps(project_id: &str) -> Option<u32> {
+    read_projects()
+        .ok()?
+        .into_iter()
+        .find(|project| project.id == project_id)
+        .map(|project| project.settings.fps)
+}
+
+/// Audio-only events land on `track-audio-N`; anything carrying picture goes
+/// to the main video track (its audio travels with the source clip).
+fn event_track(channels: &str) -> String {
+    if channels.contains('V') || channels == "B" {
+        return "track-video-main".to_string();
+    }
+    let channel = channels
+        .trim_start_matches('A')
+        .parse::<u32>()
+        .unwrap_or(1)
+        .max(1);
+    format!("track-audio-{channel}")
+}
+
+fn import_edl_blocking(request: ImportEdlRequest) -> Result<EdlImport, String> {
+    let raw = fs::read_to_string(&request.path)
+        .map_err(|error| format!("Failed reading EDL file: {error}"))?;
+    let fps = request
+        .fps
+        .or_else(|| project_fps(&request.project_id))
+        .unwrap_or(30)
+        .max(1);
+
+    let mut warnings = Vec::new();
+    let (title, drop_frame, events) = parse_edl(&raw, fps, &mut warnings);
+    if events.is_empty() {
+        return Err("No CMX3600 events found in EDL.".to_string());
+    }
+    let record_origin = events
+        .iter()
+        .map(|event| event.record_in)
+        .min()
+        .unwrap_or(0);
+
+    let mut unmapped_reels = BTreeSet::new();
+    let mut audio_tracks = BTreeSet::new();
+    let mut clips = Vec::new();
+    for event in &events {
+        if matches!(event.reel.as_str(), "BL" | "BLK" | "BLACK") {
+            continue;
+        }
+        if event.transition != "C" {
+            warnings.push(format!(
+                "Event {}: transition {} imported as a cut.",
+                event.number, event.transition
+            ));
+        }
+        if event.record_out <= event.record_in || event.source_out <= event.source_in {
+            warnings.push(format!("Event {}: empty range skipped.", event.number));
+            continue;
+        }
+
+        let mapping = request.source_map.get(&event.reel).or_else(|| {
+            event
+                .clip_name
+                .as_ref()
+                .and_then(|name| request.source_map.get(name))
+        });
+        let (source_ref, source_origin) = match mapping {
+            Some(mapping) => {
+                let origin = match mapping.start_timecode.as_deref() {
+                    Some(timecode) => {
+                        let frames = timecode_to_frames(timecode, fps, drop_frame)
+                            .ok_or_else(|| format!("Invalid start timecode: {timecode}"))?;
+                        frames_to_us(frames, fps, drop_frame)
+                    }
+                    None => 0,
+                };
+                (mapping.source_ref.clone(), origin)
+            }
+            None => {
+                unmapped_reels.insert(event.reel.clone());
+                (
+                    event
+                        .clip_name
+                        .clone()
+                        .unwrap_or_else(|| event.reel.clone()),
+                    0,
+                )
+            }
+        };
+
+        let track_id = event_track(&event.channels);
+        if track_id != "track-video-main" {
+            audio_tracks.insert(track_id.clone());
+        }
+        let start_us = event.record_in - record_origin;
+        let duration_us = event.record_out - event.record_in;
+        let source_start_us = event.source_in.saturating_sub(source_origin);
+        clips.push(TimelineClip {
+            clip_id: format!("clip-{}", clips.len() + 1),
+            track_id,
+            clip_type: "source_clip".to_string(),
+            start_us,
+            end_us: start_us + duration_us,
+            source_start_us,
+            source_end_us: source_start_us + duration_us,
+            speed: 1.0,
+            speed_keyframes: Vec::new(),
+            locked: false,
+            protected_from_ai: false,
+            source_ref,
+            effects: serde_json::json!({}),
+            transform: serde_json::json!({}),
+            meta: serde_json::json!({
+                "generatedBy": "edl-import",
+                "edlEvent": event.number,
+                "reel": event.reel,
+                "clipName": event.clip_name,
+            }),
+        });
+    }
+
+    let mut tracks = vec![TimelineTrack {
+        id: "track-video-main".to_string(),
+        name: "Main Video".to_string(),
+        kind: "video".to_string(),
+        order: 0,
+        locked: false,
+    }];
+    for track_id in audio_tracks {
+        let channel = track_id.trim_start_matches("track-audio-").to_string();
+        tracks.push(TimelineTrack {
+            id: track_id,
+            name: format!("Audio {channel}"),
+            kind: "audio".to_string(),
+            order: tracks.len() as u32,
+            locked: false,
+        });
+    }
+    tracks.push(TimelineTrack {
+        id: "track-captions".to_string(),
+        name: "Captions".to_string(),
+        kind: "caption".to_string(),
+        order: tracks.len() as u32,
+        locked: false,
+    });
+
+    let now = now_iso();
+    let mut timeline = Timeline {
+        id: format!("timeline-{}", generate_project_id()),
+        project_id: request.project_id.clone(),
+        version: 1,
+        status: "ROUGH_CUT_READY".to_string(),
+        fps,
+        duration_us: clips.iter().map(|clip| clip.end_us).max().unwrap_or(0),
+        created_at: now.clone(),
+        updated_at: now,
+        tracks,
+        clips,
+        meta: serde_json::json!({
+            "importedFrom": {
+                "format": "cmx3600",
+                "path": request.path,
+                "title": title,
+                "dropFrame": drop_frame,
+            }
+        }),
+    };
+    inherit_timeline_identity(&mut timeline);
+    write_timeline(&timeline)?;
+    mark_timeline_written(&request.project_id, ProjectStatus::RoughCutReady)?;
+
+    Ok(EdlImport {
+        title,
+        event_count: events.len(),
+        unmapped_reels,
+        warnings,
+        timeline,
+    })
+}
+
+#[tauri::command]
+pub async fn import_edl(request: ImportEdlRequest) -> Result<EdlImport, String> {
+    tauri::async_runtime::spawn_blocking(move || import_edl_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
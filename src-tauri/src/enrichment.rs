@@ -0,0 +1,520 @@
+use std::collections::HashMap;
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::titles::{TitleAnimation, TitlePayload, TitlePosition, TITLE_CLIP_TYPE, TITLE_TRACK_ID};
+use crate::{
+    now_iso, path_safety, read_timeline, write_timeline, Timeline, TimelineClip, TimelineTrack,
+};
+
+const TEMPLATE_TRACK_ID: &str = "track-template-overlay";
+const BROLL_TRACK_ID: &str = "track-broll";
+/// `meta.generatedBy` values of clips a previous plan put on the timeline.
+const GENERATED_BY: [&str; 3] = [
+    "ai-template-planner",
+    "ai-stock-planner",
+    "ai-title-planner",
+];
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TemplatePlacement {
+    pub id: String,
+    pub template_id: String,
+    pub category: String,
+    pub start_us: u64,
+    pub end_us: u64,
+    pub confidence: Option<f64>,
+    pub content: Value,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AssetSuggestion {
+    pub id: String,
+    pub provider: String,
+    /// `video` or `image`.
+    pub kind: String,
+    pub query: String,
+    pub start_us: u64,
+    pub end_us: u64,
+    pub effects: Value,
+    pub attribution: Value,
+    /// Set once the asset was fetched: `{ status, localPath, license, ... }`.
+    pub media: Value,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TitleSuggestion {
+    pub id: String,
+    pub text: String,
+    pub start_us: u64,
+    pub end_us: u64,
+    pub position: TitlePosition,
+    pub animation: TitleAnimation,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct OverlayArtifact {
+    placement_id: String,
+    asset_id: String,
+    path: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct OverlayArtifacts {
+    template_overlays: Vec<OverlayArtifact>,
+    asset_overlays: Vec<OverlayArtifact>,
+}
+
+/// The parts of `template-plan.json` (or the `edit_now` result) that turn
+/// into clips.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EnrichmentPlan {
+    pub template_placements: Vec<TemplatePlacement>,
+    pub asset_suggestions: Vec<AssetSuggestion>,
+    pub title_suggestions: Vec<TitleSuggestion>,
+    overlay_artifacts: OverlayArtifacts,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyEnrichmentPlanRequest {
+    project_id: String,
+    /// Defaults to the project's `template-plan.json`.
+    plan: Option<EnrichmentPlan>,
+    /// Report what would change without saving the timeline.
+    dry_run: Option<bool>,
+    /// Replace earlier generated clips even when they are locked. Clips
+    /// protected from AI are kept regardless.
+    force: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictKind {
+    /// The target track is locked.
+    LockedTrack,
+    /// A clip the plan doesn't replace already covers the range.
+    Overlap,
+    /// The suggestion falls outside the timeline.
+    OutOfBounds,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnrichmentConflict {
+    pub suggestion_id: String,
+    pub kind: ConflictKind,
+    pub track_id: String,
+    pub clip_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedClip {
+    pub suggestion_id: String,
+    pub clip_id: String,
+    pub clip_type: String,
+    pub track_id: String,
+    pub start_us: u64,
+    pub end_us: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnrichmentReport {
+    pub dry_run: bool,
+    pub added: Vec<AppliedClip>,
+    /// Clips from an earlier plan that the new ones replace.
+    pub removed_clip_ids: Vec<String>,
+    pub created_tracks: Vec<String>,
+    /// Suggestions that were skipped.
+    pub conflicts: Vec<EnrichmentConflict>,
+    pub timeline: Timeline,
+}
+
+fn read_template_plan(project_id: &str) -> Result<EnrichmentPlan, String> {
+    let file_path = path_safety::project_dir(project_id)?.join("template-plan.json");
+    if !file_path.exists() {
+        return Err("No template plan found; run Edit Now first.".to_string());
+    }
+    let raw = fs::read_to_string(&file_path)
+        .map_err(|error| format!("Failed reading template plan: {error}"))?;
+    serde_json::from_str::<EnrichmentPlan>(&raw)
+        .map_err(|error| format!("Invalid template plan JSON: {error}"))
+}
+
+fn is_generated(clip: &TimelineClip) -> bool {
+    clip.meta
+        .get("generatedBy")
+        .and_then(Value::as_str)
+        .is_some_and(|generated_by| GENERATED_BY.contains(&generated_by))
+}
+
+fn track_kind(track_id: &str) -> (&'static str, &'static str) {
+    match track_id {
+        TEMPLATE_TRACK_ID => ("Template Overlay", "template"),
+        BROLL_TRACK_ID => ("B-roll / Assets", "video"),
+        _ => ("Titles", "title"),
+    }
+}
+
+/// A clip waiting to be placed, before conflict checks.
+struct Candidate {
+    suggestion_id: String,
+    track_id: &'static str,
+    clip_type: &'static str,
+    start_us: u64,
+    end_us: u64,
+    source_ref: String,
+    effects: Value,
+    meta: Value,
+}
+
+fn text_field(content: &Value, key: &str) -> String {
+    content
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn title_candidate(
+    suggestion_id: &str,
+    start_us: u64,
+    end_us: u64,
+    title: TitlePayload,
+) -> Result<Candidate, String> {
+    let title =
+        serde_json::to_value(title).map_err(|error| format!("Title serialize error: {error}"))?;
+    Ok(Candidate {
+        suggestion_id: suggestion_id.to_string(),
+        track_id: TITLE_TRACK_ID,
+        clip_type: TITLE_CLIP_TYPE,
+        start_us,
+        end_us,
+        source_ref: TITLE_CLIP_TYPE.to_string(),
+        effects: serde_json::json!({}),
+        meta: serde_json::json!({
+            "generatedBy": "ai-title-planner",
+            "placementId": suggestion_id,
+            "title": title,
+        }),
+    })
+}
+
+fn plan_candidates(plan: EnrichmentPlan) -> Result<Vec<Candidate>, String> {
+    let template_paths = plan
+        .overlay_artifacts
+        .template_overlays
+        .into_iter()
+        .map(|artifact| (artifact.placement_id, artifact.path))
+        .collect::<HashMap<_, _>>();
+    let asset_paths = plan
+        .overlay_artifacts
+        .asset_overlays
+        .into_iter()
+        .map(|artifact| (artifact.asset_id, artifact.path))
+        .collect::<HashMap<_, _>>();
+
+    let mut candidates = Vec::new();
+    for placement in plan.template_placements {
+        let duration_us = placement.end_us.saturating_sub(placement.start_us);
+        // Title templates become real title clips instead of rendered overlays.
+        if placement.category == "title" {
+            let headline = text_field(&placement.content, "headline");
+            if !headline.is_empty() {
+                let title = TitlePayload {
+                    text: headline,
+                    ..TitlePayload::new(duration_us)
+                };
+                candidates.push(title_candidate(
+                    &placement.id,
+                    placement.start_us,
+                    placement.end_us,
+                    title,
+                )?);
+                continue;
+            }
+        }
+        let overlay_path = template_paths
+            .get(&placement.id)
+            .cloned()
+            .unwrap_or_default();
+        candidates.push(Candidate {
+            suggestion_id: placement.id.clone(),
+            track_id: TEMPLATE_TRACK_ID,
+            clip_type: "template_clip",
+            start_us: placement.start_us,
+            end_us: placement.end_us,
+            source_ref: if overlay_path.is_empty() {
+                format!("template:{}", placement.template_id)
+            } else {
+                overlay_path.clone()
+            },
+            effects: serde_json::json!({
+                "in": { "type": "fade", "durationUs": 120_000 },
+                "out": { "type": "fade", "durationUs": 120_000 },
+            }),
+            meta: serde_json::json!({
+                "generatedBy": "ai-template-planner",
+                "placementId": placement.id,
+                "templateId": placement.template_id,
+                "category": placement.category,
+                "confidence": placement.confidence,
+                "content": placement.content,
+                "overlayPath": overlay_path,
+            }),
+        });
+    }
+
+    for asset in plan.asset_suggestions {
+        let media_path = text_field(&asset.media, "localPath");
+        let overlay_path = asset_paths.get(&asset.id).cloned().unwrap_or_default();
+        let source_ref = if !media_path.is_empty() {
+            media_path.clone()
+        } else if !overlay_path.is_empty() {
+            overlay_path.clone()
+        } else {
+            format!(
+                "stock:{}:{}",
+                asset.provider,
+                asset
+                    .query
+                    .to_lowercase()
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join("-")
+            )
+        };
+        let attribution = asset
+            .media
+            .get("attribution")
+            .cloned()
+            .unwrap_or(asset.attribution);
+        let media_status = match text_field(&asset.media, "status") {
+            status if status.is_empty() => "placeholder".to_string(),
+            status => status,
+        };
+        candidates.push(Candidate {
+            suggestion_id: asset.id.clone(),
+            track_id: BROLL_TRACK_ID,
+            clip_type: "asset_clip",
+            start_us: asset.start_us,
+            end_us: asset.end_us,
+            source_ref,
+            effects: asset.effects,
+            meta: serde_json::json!({
+                "generatedBy": "ai-stock-planner",
+                "placementId": asset.id,
+                "provider": asset.provider,
+                "kind": asset.kind,
+                "query": asset.query,
+                "attribution": attribution,
+                "mediaStatus": media_status,
+                "mediaPath": media_path,
+                "license": text_field(&asset.media, "license"),
+                "overlayPath": overlay_path,
+            }),
+        });
+    }
+
+    for suggestion in plan.title_suggestions {
+        if suggestion.text.trim().is_empty() {
+            continue;
+        }
+        let duration_us = suggestion.end_us.saturating_sub(suggestion.start_us);
+        let title = TitlePayload {
+            text: suggestion.text.trim().to_string(),
+            position: suggestion.position,
+            animation: suggestion.animation,
+            ..TitlePayload::new(duration_us)
+        };
+        candidates.push(title_candidate(
+            &suggestion.id,
+            suggestion.start_us,
+            suggestion.end_us,
+            title,
+        )?);
+    }
+    Ok(candidates)
+}
+
+fn apply_plan(
+    mut timeline: Timeline,
+    plan: EnrichmentPlan,
+    dry_run: bool,
+    force: bool,
+) -> Result<EnrichmentReport, String> {
+    let candidates = plan_candidates(plan)?;
+    let locked = |timeline: &Timeline, track_id: &str| {
+        timeline
+            .tracks
+            .iter()
+            .any(|track| track.id == track_id && track.locked)
+    };
+
+    // Earlier generated clips are replaced, except on locked tracks and
+    // where the clip itself is locked or protected.
+    let mut removed_clip_ids = Vec::new();
+    let tracks = timeline.tracks.clone();
+    timeline.clips.retain(|clip| {
+        let keep = !is_generated(clip)
+            || clip.protected_from_ai
+            || (clip.locked && !force)
+            || tracks
+                .iter()
+                .any(|track| track.id == clip.track_id && track.locked);
+        if !keep {
+            removed_clip_ids.push(clip.clip_id.clone());
+        }
+        keep
+    });
+
+    let mut added = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut created_tracks = Vec::new();
+    let mut next_index = HashMap::<&str, usize>::new();
+    for candidate in candidates {
+        let conflict = |kind, clip_id: Option<String>| EnrichmentConflict {
+            suggestion_id: candidate.suggestion_id.clone(),
+            kind,
+            track_id: candidate.track_id.to_string(),
+            clip_id,
+        };
+        let end_us = candidate.end_us.min(timeline.duration_us);
+        if candidate.start_us >= end_us {
+            conflicts.push(conflict(ConflictKind::OutOfBounds, None));
+            continue;
+        }
+        if locked(&timeline, candidate.track_id) {
+            conflicts.push(conflict(ConflictKind::LockedTrack, None));
+            continue;
+        }
+        let overlapping = timeline.clips.iter().find(|clip| {
+            clip.track_id == candidate.track_id
+                && clip.start_us < end_us
+                && candidate.start_us < clip.end_us
+                && !added
+                    .iter()
+                    .any(|applied: &AppliedClip| applied.clip_id == clip.clip_id)
+        });
+        if let Some(clip) = overlapping {
+            conflicts.push(conflict(ConflictKind::Overlap, Some(clip.clip_id.clone())));
+            continue;
+        }
+
+        if !timeline
+            .tracks
+            .iter()
+            .any(|track| track.id == candidate.track_id)
+        {
+            let (name, kind) = track_kind(candidate.track_id);
+            let order = timeline
+                .tracks
+                .iter()
+                .map(|track| track.order + 1)
+                .max()
+                .unwrap_or(0);
+            timeline.tracks.push(TimelineTrack {
+                id: candidate.track_id.to_string(),
+                name: name.to_string(),
+                kind: kind.to_string(),
+                order,
+                locked: false,
+            });
+            created_tracks.push(candidate.track_id.to_string());
+        }
+
+        let prefix = match candidate.clip_type {
+            "template_clip" => "template-clip",
+            "asset_clip" => "asset-clip",
+            _ => "title-clip",
+        };
+        let index = next_index.entry(prefix).or_insert(0);
+        let clip_id = loop {
+            *index += 1;
+            let clip_id = format!("{prefix}-{index}");
+            if !timeline.clips.iter().any(|clip| clip.clip_id == clip_id) {
+                break clip_id;
+            }
+        };
+        let mut meta = candidate.meta;
+        // A clamped title keeps a payload duration that matches its clip.
+        if let Some(title) = meta.get_mut("title").and_then(Value::as_object_mut) {
+            title.insert(
+                "durationUs".to_string(),
+                Value::from(end_us - candidate.start_us),
+            );
+        }
+        timeline.clips.push(TimelineClip {
+            clip_id: clip_id.clone(),
+            track_id: candidate.track_id.to_string(),
+            clip_type: candidate.clip_type.to_string(),
+            start_us: candidate.start_us,
+            end_us,
+            source_start_us: 0,
+            source_end_us: end_us - candidate.start_us,
+            speed: 1.0,
+            speed_keyframes: Vec::new(),
+            locked: false,
+            protected_from_ai: false,
+            source_ref: candidate.source_ref,
+            effects: candidate.effects,
+            transform: serde_json::json!({}),
+            meta,
+        });
+        added.push(AppliedClip {
+            suggestion_id: candidate.suggestion_id,
+            clip_id,
+            clip_type: candidate.clip_type.to_string(),
+            track_id: candidate.track_id.to_string(),
+            start_us: candidate.start_us,
+            end_us,
+        });
+    }
+    timeline.clips.sort_by_key(|clip| clip.start_us);
+
+    if !dry_run {
+        timeline.version = timeline.version.saturating_add(1);
+        timeline.updated_at = now_iso();
+        write_timeline(&timeline)?;
+    }
+    Ok(EnrichmentReport {
+        dry_run,
+        added,
+        removed_clip_ids,
+        created_tracks,
+        conflicts,
+        timeline,
+    })
+}
+
+#[tauri::command]
+pub async fn apply_enrichment_plan(
+    request: ApplyEnrichmentPlanRequest,
+) -> Result<EnrichmentReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let plan = match request.plan {
+            Some(plan) => plan,
+            None => read_template_plan(&request.project_id)?,
+        };
+        let timeline = read_timeline(&request.project_id)?;
+        apply_plan(
+            timeline,
+            plan,
+            request.dry_run.unwrap_or(false),
+            request.force.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
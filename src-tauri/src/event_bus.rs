@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::{now_iso, path_safety, shutdown, windows};
+
+pub const PROJECT_STATUS_EVENT: &str = "project://status";
+pub const TIMELINE_SAVED_EVENT: &str = "timeline://saved";
+pub const PREVIEW_PROGRESS_EVENT: &str = "preview://progress";
+pub const REMOTE_RENDER_PROGRESS_EVENT: &str = "render://remote-progress";
+pub const INGEST_DOWNLOAD_EVENT: &str = "ingest://download-progress";
+pub const CAPTURE_PROGRESS_EVENT: &str = "capture://progress";
+pub const PLAYER_POSITION_EVENT: &str = "player://position";
+const JOURNAL_FILE: &str = "events.jsonl";
+/// Journals past this size are cut back to the newest [`KEEP_ENTRIES`].
+const MAX_JOURNAL_BYTES: u64 = 4 * 1024 * 1024;
+const KEEP_ENTRIES: usize = 5000;
+const DEFAULT_LIMIT: usize = 500;
+
+/// Set once the app is up; before that (and in CLI mode) events are only
+/// journaled.
+static APP: OnceLock<AppHandle> = OnceLock::new();
+/// Last sequence number written per project, loaded from the journal on
+/// first use. Also serializes appends.
+static SEQUENCES: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+
+/// One line of `<project>/events.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    /// Increases by one per event within a project.
+    pub seq: u64,
+    pub at: String,
+    pub event: String,
+    pub payload: Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetEventJournalRequest {
+    project_id: String,
+    /// Only entries with a greater `seq`; all of them when absent.
+    since: Option<u64>,
+    limit: Option<usize>,
+}
+
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+fn sequences() -> MutexGuard<'static, BTreeMap<String, u64>> {
+    SEQUENCES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn journal_path(project_id: &str) -> Result<PathBuf, String> {
+    path_safety::project_file(project_id, JOURNAL_FILE)
+}
+
+/// Entries in a journal file; lines that don't parse (a write cut short)
+/// are skipped.
+fn read_journal(path: &Path) -> Result<Vec<JournalEntry>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = fs::read_to_string(path)
+        .map_err(|error| format!("Failed reading event journal: {error}"))?;
+    Ok(raw
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn trim_journal(path: &Path) -> Result<(), String> {
+    let entries = read_journal(path)?;
+    let kept = &entries[entries.len().saturating_sub(KEEP_ENTRIES)..];
+    let mut contents = String::new();
+    for entry in kept {
+        let line = serde_json::to_string(entry)
+            .map_err(|error| format!("Event serialize error: {error}"))?;
+        contents.push_str(&line);
+        contents.push('\n');
+    }
+    let temp = path.with_extension("jsonl.tmp");
+    fs::write(&temp, contents)
+        .map_err(|error| format!("Failed trimming event journal: {error}"))?;
+    fs::rename(&temp, path).map_err(|error| format!("Failed trimming event journal: {error}"))
+}
+
+fn append(project_id: &str, event: &str, payload: Value) -> Result<(), String> {
+    let path = journal_path(project_id)?;
+    let mut sequences = sequences();
+    let last = match sequences.get(project_id) {
+        Some(last) => *last,
+        None => read_journal(&path)?.last().map_or(0, |entry| entry.seq),
+    };
+    let entry = JournalEntry {
+        seq: last + 1,
+        at: now_iso(),
+        event: event.to_string(),
+        payload,
+    };
+    let line =
+        serde_json::to_string(&entry).map_err(|error| format!("Event serialize error: {error}"))?;
+
+    let _write = shutdown::begin_write();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating project dir: {error}"))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|error| format!("Failed opening event journal: {error}"))?;
+    writeln!(file, "{line}").map_err(|error| format!("Failed writing event journal: {error}"))?;
+    sequences.insert(project_id.to_string(), entry.seq);
+
+    let size = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+    if size > MAX_JOURNAL_BYTES {
+        trim_journal(&path)?;
+    }
+    Ok(())
+}
+
+/// The one way project state changes are announced: the event is appended
+/// to the project's journal, then sent to the windows showing the project.
+/// A journal failure is logged rather than failing the change itself.
+pub fn publish<S: Serialize>(project_id: &str, event: &str, payload: &S) {
+    let payload = match serde_json::to_value(payload) {
+        Ok(payload) => payload,
+        Err(error) => {
+            tracing::warn!("Dropping {event} event for {project_id}: {error}");
+            return;
+        }
+    };
+    if let Err(error) = append(project_id, event, payload.clone()) {
+        tracing::warn!("Failed journaling {event} for {project_id}: {error}");
+    }
+    if let Some(app) = APP.get() {
+        windows::emit_for_project(app, project_id, event, payload);
+    }
+}
+
+/// Sends an event to the project's windows without journaling it, for
+/// high-rate state nobody needs to catch up on, like playback position.
+pub fn emit<S: Serialize>(project_id: &str, event: &str, payload: &S) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    match serde_json::to_value(payload) {
+        Ok(payload) => windows::emit_for_project(app, project_id, event, payload),
+        Err(error) => tracing::warn!("Dropping {event} event for {project_id}: {error}"),
+    }
+}
+
+/// Every entry in a project's journal, oldest first.
+pub fn journal(project_id: &str) -> Result<Vec<JournalEntry>, String> {
+    read_journal(&journal_path(project_id)?)
+}
+
+/// Journal entries after `since`, oldest first, so the UI can catch up on
+/// what happened while it wasn't listening.
+#[tauri::command]
+pub async fn get_event_journal(
+    request: GetEventJournalRequest,
+) -> Result<Vec<JournalEntry>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let since = request.since.unwrap_or(0);
+        Ok(journal(&request.project_id)?
+            .into_iter()
+            .filter(|entry| entry.seq > since)
+            .take(request.limit.unwrap_or(DEFAULT_LIMIT))
+            .collect())
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
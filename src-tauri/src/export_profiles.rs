@@ -0,0 +1,416 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::external_assets::check_render_attribution;
+use crate::media::{probe_video_dimensions, resolve_source_path};
+use crate::project_status::ProjectStatus;
+use crate::render_history::{record_timeline_snapshot, timeline_snapshot};
+use crate::subtitles::write_styled_subtitles;
+use crate::{
+    path_safety, read_timeline, run_node_script, script_path, update_project_status, Timeline,
+};
+
+/// How the crop window is placed inside the source frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReframeMode {
+    #[default]
+    Center,
+    /// Follow `subject_tracking.json` from the pipeline, centering otherwise.
+    Subject,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportProfile {
+    pub id: String,
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub reframe: ReframeMode,
+}
+
+/// A preset id (`landscape`, `vertical`, `square`) or a full custom profile.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ExportProfileSpec {
+    Preset(String),
+    Custom(ExportProfile),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderProfilesRequest {
+    project_id: String,
+    profiles: Vec<ExportProfileSpec>,
+    /// Base file name; each profile renders to `<base>-<profileId>.mp4`.
+    output_name: Option<String>,
+    burn_subtitles: Option<bool>,
+    quality: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileRender {
+    pub profile: ExportProfile,
+    pub ok: bool,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubjectSample {
+    time_us: u64,
+    /// Subject center as a fraction of the frame.
+    x: f64,
+    y: f64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubjectTrack {
+    source_ref: Option<String>,
+    #[serde(default)]
+    samples: Vec<SubjectSample>,
+}
+
+/// `subject_tracking.json` as written by the pipeline. Top-level samples
+/// apply to every source.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct SubjectTracking {
+    tracks: Vec<SubjectTrack>,
+    samples: Vec<SubjectSample>,
+}
+
+/// Crop rectangle as fractions of the source frame.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CropRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+pub fn preset_profiles() -> Vec<ExportProfile> {
+    [
+        ("landscape", "Landscape 16:9", 1920, 1080),
+        ("vertical", "Vertical 9:16", 1080, 1920),
+        ("square", "Square 1:1", 1080, 1080),
+    ]
+    .into_iter()
+    .map(|(id, name, width, height)| ExportProfile {
+        id: id.to_string(),
+        name: name.to_string(),
+        width,
+        height,
+        reframe: ReframeMode::Center,
+    })
+    .collect()
+}
+
+pub fn resolve_profile(spec: ExportProfileSpec) -> Result<ExportProfile, String> {
+    let profile = match spec {
+        ExportProfileSpec::Preset(id) => preset_profiles()
+            .into_iter()
+            .find(|profile| profile.id == id)
+            .ok_or_else(|| format!("Unknown export profile: {id}"))?,
+        ExportProfileSpec::Custom(profile) => profile,
+    };
+    if profile.width == 0
+        || profile.height == 0
+        || profile.width % 2 == 1
+        || profile.height % 2 == 1
+    {
+        return Err(format!(
+            "Export profile {} needs a non-zero, even frame size.",
+            profile.id
+        ));
+    }
+    if profile.id.is_empty()
+        || !profile
+            .id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid export profile id: {:?}", profile.id));
+    }
+    Ok(profile)
+}
+
+/// Largest window of the target aspect ratio that fits the source, centered
+/// on `(center_x, center_y)` and kept inside the frame.
+pub fn crop_for_aspect(
+    source: (u32, u32),
+    target: (u32, u32),
+    center_x: f64,
+    center_y: f64,
+) -> CropRect {
+    let source_aspect = f64::from(source.0) / f64::from(source.1);
+    let target_aspect = f64::from(target.0) / f64::from(target.1);
+    let (width, height) = if target_aspect < source_aspect {
+        (target_aspect / source_aspect, 1.0)
+    } else {
+        (1.0, source_aspect / target_aspect)
+    };
+    CropRect {
+        x: (center_x - width / 2.0).clamp(0.0, 1.0 - width),
+        y: (center_y - height / 2.0).clamp(0.0, 1.0 - height),
+        width,
+        height,
+    }
+}
+
+fn read_subject_tracking(project_id: &str) -> Option<SubjectTracking> {
+    let file_path = path_safety::project_dir(project_id)
+        .ok()?
+        .join("subject_tracking.json");
+    let raw = fs::read_to_string(file_path).ok()?;
+    serde_json::from_str::<SubjectTracking>(&raw)
+        .map_err(|error| tracing::warn!("Ignoring invalid subject tracking: {error}"))
+        .ok()
+}
+
+/// Mean subject position over a clip's source range.
+fn subject_center(
+    tracking: &SubjectTracking,
+    source_ref: &str,
+    start_us: u64,
+    end_us: u64,
+) -> Option<(f64, f64)> {
+    let samples = tracking
+        .tracks
+        .iter()
+        .filter(|track| {
+            track
+                .source_ref
+                .as_deref()
+                .map_or(true, |source| source == source_ref)
+        })
+        .flat_map(|track| track.samples.iter())
+        .chain(tracking.samples.iter())
+        .filter(|sample| sample.time_us >= start_us && sample.time_us < end_us)
+        .collect::<Vec<_>>();
+    if samples.is_empty() {
+        return None;
+    }
+    let count = samples.len() as f64;
+    Some((
+        samples.iter().map(|sample| sample.x).sum::<f64>() / count,
+        samples.iter().map(|sample| sample.y).sum::<f64>() / count,
+    ))
+}
+
+/// Copy of the timeline with a crop transform on every source clip.
+fn reframe_timeline(
+    timeline: &Timeline,
+    profile: &ExportProfile,
+    source_sizes: &HashMap<String, (u32, u32)>,
+    tracking: Option<&SubjectTracking>,
+) -> Timeline {
+    let mut reframed = timeline.clone();
+    for clip in &mut reframed.clips {
+        if clip.clip_type != "source_clip" {
+            continue;
+        }
+        let source = source_sizes
+            .get(&clip.source_ref)
+            .copied()
+            .unwrap_or((1920, 1080));
+        let center = match (profile.reframe, tracking) {
+            (ReframeMode::Subject, Some(tracking)) => subject_center(
+                tracking,
+                &clip.source_ref,
+                clip.source_start_us,
+                clip.source_end_us,
+            ),
+            _ => None,
+        }
+        .unwrap_or((0.5, 0.5));
+        let crop = crop_for_aspect(source, (profile.width, profile.height), center.0, center.1);
+
+        if !clip.transform.is_object() {
+            clip.transform = serde_json::json!({});
+        }
+        if let Some(transform) = clip.transform.as_object_mut() {
+            transform.insert(
+                "crop".to_string(),
+                serde_json::to_value(crop).unwrap_or(Value::Null),
+            );
+        }
+    }
+    reframed.meta = serde_json::json!({
+        "exportProfile": profile,
+        "baseTimelineVersion": timeline.version,
+    });
+    reframed
+}
+
+fn render_profile(
+    request: &RenderProfilesRequest,
+    timeline: &Timeline,
+    profile: &ExportProfile,
+    source_sizes: &HashMap<String, (u32, u32)>,
+    tracking: Option<&SubjectTracking>,
+) -> Result<Value, String> {
+    let reframed = reframe_timeline(timeline, profile, source_sizes, tracking);
+    let project_dir = path_safety::project_dir(&request.project_id)?;
+    let timeline_path: PathBuf = project_dir
+        .join("renders")
+        .join("profiles")
+        .join(format!("{}.timeline.json", profile.id));
+    if let Some(parent) = timeline_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating profile dir: {error}"))?;
+    }
+    let serialized = serde_json::to_string_pretty(&reframed)
+        .map_err(|error| format!("Timeline serialize error: {error}"))?;
+    fs::write(&timeline_path, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing profile timeline: {error}"))?;
+
+    let base = request
+        .output_name
+        .as_deref()
+        .map(|name| name.trim().trim_end_matches(".mp4"))
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("lapaas-{}", request.project_id));
+    let mut args = vec![
+        "--project-id".to_string(),
+        request.project_id.clone(),
+        "--timeline-path".to_string(),
+        timeline_path.to_string_lossy().to_string(),
+        "--frame-size".to_string(),
+        format!("{}x{}", profile.width, profile.height),
+        "--output-name".to_string(),
+        format!("{base}-{}.mp4", profile.id),
+        "--burn-subtitles".to_string(),
+        request.burn_subtitles.unwrap_or(false).to_string(),
+        "--quality".to_string(),
+        request
+            .quality
+            .clone()
+            .unwrap_or_else(|| "balanced".to_string()),
+    ];
+    if request.burn_subtitles.unwrap_or(false) {
+        let subtitles_path = timeline_path.with_file_name(format!("{}.subtitles.ass", profile.id));
+        match write_styled_subtitles(
+            &request.project_id,
+            timeline,
+            (profile.width, profile.height),
+            &subtitles_path,
+        ) {
+            Ok(Some(path)) => {
+                args.push("--subtitles-ass".to_string());
+                args.push(path.to_string_lossy().to_string());
+            }
+            Ok(None) => {}
+            Err(error) => tracing::warn!("Falling back to unstyled subtitles: {error}"),
+        }
+    }
+    let raw = run_node_script(&script_path("scripts/render_pipeline.mjs")?, &args)?;
+    let mut result = serde_json::from_str::<Value>(&raw)
+        .map_err(|error| format!("Invalid render JSON: {error}"))?;
+
+    if let Some(render_id) = result.get("renderId").and_then(Value::as_str) {
+        // Staleness is judged against the project timeline, not the copy.
+        let snapshot = timeline_snapshot(timeline);
+        if let Err(error) = record_timeline_snapshot(&request.project_id, render_id, &snapshot) {
+            tracing::warn!("Failed recording render timeline snapshot: {error}");
+        }
+    }
+    if let Some(object) = result.as_object_mut() {
+        object.insert(
+            "exportProfile".to_string(),
+            serde_json::to_value(profile).unwrap_or(Value::Null),
+        );
+    }
+    Ok(result)
+}
+
+fn render_profiles_blocking(request: RenderProfilesRequest) -> Result<Vec<ProfileRender>, String> {
+    let profiles = request
+        .profiles
+        .iter()
+        .cloned()
+        .map(resolve_profile)
+        .collect::<Result<Vec<_>, _>>()?;
+    if profiles.is_empty() {
+        return Err("Choose at least one export profile.".to_string());
+    }
+    let timeline = read_timeline(&request.project_id)?;
+
+    let mut source_sizes = HashMap::new();
+    for clip in &timeline.clips {
+        if clip.clip_type != "source_clip" || source_sizes.contains_key(&clip.source_ref) {
+            continue;
+        }
+        let size = resolve_source_path(&request.project_id, Some(&clip.source_ref))
+            .ok()
+            .and_then(|path| probe_video_dimensions(&path));
+        if let Some(size) = size {
+            source_sizes.insert(clip.source_ref.clone(), size);
+        }
+    }
+    let tracking = profiles
+        .iter()
+        .any(|profile| profile.reframe == ReframeMode::Subject)
+        .then(|| read_subject_tracking(&request.project_id))
+        .flatten();
+
+    check_render_attribution(&request.project_id)?;
+    update_project_status(&request.project_id, ProjectStatus::RenderInProgress)?;
+    let mut renders = Vec::new();
+    for profile in profiles {
+        tracing::info!("Rendering export profile {}", profile.id);
+        let outcome = render_profile(
+            &request,
+            &timeline,
+            &profile,
+            &source_sizes,
+            tracking.as_ref(),
+        );
+        renders.push(match outcome {
+            Ok(result) => ProfileRender {
+                profile,
+                ok: true,
+                result: Some(result),
+                error: None,
+            },
+            Err(error) => ProfileRender {
+                profile,
+                ok: false,
+                result: None,
+                error: Some(error),
+            },
+        });
+    }
+    let status = if renders.iter().any(|render| render.ok) {
+        ProjectStatus::RenderDone
+    } else {
+        ProjectStatus::RenderFailed
+    };
+    update_project_status(&request.project_id, status)?;
+    Ok(renders)
+}
+
+#[tauri::command]
+pub async fn list_export_profiles() -> Result<Vec<ExportProfile>, String> {
+    Ok(preset_profiles())
+}
+
+#[tauri::command]
+pub async fn render_export_profiles(
+    request: RenderProfilesRequest,
+) -> Result<Vec<ProfileRender>, String> {
+    tauri::async_runtime::spawn_blocking(move || render_profiles_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
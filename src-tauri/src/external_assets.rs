@@ -0,0 +1,289 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{now_iso, path_safety, read_timeline};
+
+/// One stock asset downloaded for a project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalAsset {
+    pub provider: String,
+    #[serde(default)]
+    pub provider_asset_id: String,
+    /// Where the file was downloaded from.
+    #[serde(default)]
+    pub source_url: String,
+    /// The provider's page for the asset.
+    #[serde(default)]
+    pub page_url: String,
+    pub local_path: String,
+    #[serde(default)]
+    pub license: String,
+    #[serde(default)]
+    pub query: String,
+    #[serde(default)]
+    pub creator_name: String,
+    #[serde(default)]
+    pub creator_url: String,
+    #[serde(default)]
+    pub attribution_required: bool,
+    pub fetched_at: String,
+}
+
+/// Per-project credits; each line is shown as-is in the end credits.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CreditsSettings {
+    pub lines: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectAssetsRequest {
+    project_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveCreditsRequest {
+    project_id: String,
+    credits: CreditsSettings,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalAssetStatus {
+    #[serde(flatten)]
+    pub asset: ExternalAsset,
+    pub used_in_timeline: bool,
+    /// Whether a credits line names the creator (or provider).
+    pub credited: bool,
+    /// Line to add to the credits when one is required and missing.
+    pub suggested_credit: String,
+}
+
+fn project_file(project_id: &str, name: &str) -> Result<PathBuf, String> {
+    Ok(path_safety::project_dir(project_id)?.join(name))
+}
+
+fn read_json_or_default<T: Default + for<'de> Deserialize<'de>>(
+    file_path: &Path,
+    label: &str,
+) -> Result<T, String> {
+    if !file_path.exists() {
+        return Ok(T::default());
+    }
+    let raw = fs::read_to_string(file_path)
+        .map_err(|error| format!("Failed reading {label}: {error}"))?;
+    serde_json::from_str::<T>(&raw).map_err(|error| format!("Invalid {label} JSON: {error}"))
+}
+
+fn write_json<T: Serialize>(file_path: &Path, value: &T, label: &str) -> Result<(), String> {
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent).map_err(|error| format!("Failed creating data dir: {error}"))?;
+    }
+    let serialized = serde_json::to_string_pretty(value)
+        .map_err(|error| format!("{label} serialize error: {error}"))?;
+    fs::write(file_path, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing {label}: {error}"))
+}
+
+pub fn read_external_assets(project_id: &str) -> Result<Vec<ExternalAsset>, String> {
+    read_json_or_default(
+        &project_file(project_id, "external_assets.json")?,
+        "external asset ledger",
+    )
+}
+
+pub fn read_credits(project_id: &str) -> Result<CreditsSettings, String> {
+    read_json_or_default(&project_file(project_id, "credits.json")?, "credits")
+}
+
+/// Creative Commons licenses other than CC0 require credit even when the
+/// provider doesn't flag it.
+fn license_requires_attribution(license: &str) -> bool {
+    let license = license.to_uppercase().replace('-', " ");
+    license.contains("CC BY") || license.contains("CREATIVE COMMONS ATTRIBUTION")
+}
+
+fn str_field(value: &Value, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Adds the downloaded assets among an `edit_now` result's asset
+/// suggestions to the project's ledger. Assets are keyed by local path; a
+/// re-fetch refreshes the entry.
+pub fn register_from_edit_now(project_id: &str, suggestions: &[Value]) -> Result<usize, String> {
+    let mut ledger = read_external_assets(project_id)?;
+    let mut registered = 0;
+    for suggestion in suggestions {
+        let Some(media) = suggestion.get("media") else {
+            continue;
+        };
+        let local_path = str_field(media, "localPath");
+        let status = str_field(media, "status");
+        if local_path.is_empty() || !matches!(status.as_str(), "downloaded" | "cached") {
+            continue;
+        }
+        let attribution = media.get("attribution").cloned().unwrap_or(Value::Null);
+        let license = str_field(media, "license");
+        let asset = ExternalAsset {
+            provider: str_field(suggestion, "provider"),
+            provider_asset_id: str_field(media, "providerAssetId"),
+            source_url: str_field(media, "sourceUrl"),
+            page_url: str_field(&attribution, "pageUrl"),
+            local_path: local_path.clone(),
+            query: str_field(suggestion, "query"),
+            creator_name: str_field(&attribution, "creatorName"),
+            creator_url: str_field(&attribution, "creatorUrl"),
+            attribution_required: attribution
+                .get("required")
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+                || license_requires_attribution(&license),
+            license,
+            fetched_at: now_iso(),
+        };
+        match ledger
+            .iter_mut()
+            .find(|entry| entry.local_path == local_path)
+        {
+            // A cache hit carries less detail than the original download.
+            Some(entry) if status == "cached" => {
+                entry.attribution_required |= asset.attribution_required;
+            }
+            Some(entry) => *entry = asset,
+            None => ledger.push(asset),
+        }
+        registered += 1;
+    }
+    if registered > 0 {
+        write_json(
+            &project_file(project_id, "external_assets.json")?,
+            &ledger,
+            "external asset ledger",
+        )?;
+    }
+    Ok(registered)
+}
+
+fn suggested_credit(asset: &ExternalAsset) -> String {
+    let provider = if asset.provider.is_empty() {
+        "stock provider".to_string()
+    } else {
+        asset.provider.clone()
+    };
+    let mut line = if asset.creator_name.is_empty() {
+        format!("Footage from {provider}")
+    } else {
+        format!("Footage by {} on {provider}", asset.creator_name)
+    };
+    if !asset.license.is_empty() {
+        line.push_str(&format!(" ({})", asset.license));
+    }
+    line
+}
+
+fn is_credited(asset: &ExternalAsset, credits: &CreditsSettings) -> bool {
+    let needle = if asset.creator_name.is_empty() {
+        asset.provider.to_lowercase()
+    } else {
+        asset.creator_name.to_lowercase()
+    };
+    credits.lines.iter().any(|line| {
+        let line = line.to_lowercase();
+        (!needle.is_empty() && line.contains(&needle))
+            || (!asset.page_url.is_empty() && line.contains(&asset.page_url.to_lowercase()))
+    })
+}
+
+fn asset_statuses(project_id: &str) -> Result<Vec<ExternalAssetStatus>, String> {
+    let ledger = read_external_assets(project_id)?;
+    let credits = read_credits(project_id)?;
+    let timeline = read_timeline(project_id).ok();
+    Ok(ledger
+        .into_iter()
+        .map(|asset| {
+            let used_in_timeline = timeline.as_ref().is_some_and(|timeline| {
+                timeline.clips.iter().any(|clip| {
+                    clip.source_ref == asset.local_path
+                        || clip.meta.get("mediaPath").and_then(Value::as_str)
+                            == Some(asset.local_path.as_str())
+                })
+            });
+            ExternalAssetStatus {
+                used_in_timeline,
+                credited: is_credited(&asset, &credits),
+                suggested_credit: suggested_credit(&asset),
+                asset,
+            }
+        })
+        .collect())
+}
+
+/// Refuses to render while the timeline uses an asset whose license needs
+/// attribution that the project credits don't give.
+pub fn check_render_attribution(project_id: &str) -> Result<(), String> {
+    let missing = asset_statuses(project_id)?
+        .into_iter()
+        .filter(|status| {
+            status.used_in_timeline && status.asset.attribution_required && !status.credited
+        })
+        .map(|status| status.suggested_credit)
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "Render blocked: add credits for {} stock asset(s): {}",
+        missing.len(),
+        missing.join("; ")
+    ))
+}
+
+#[tauri::command]
+pub async fn list_external_assets(
+    request: ProjectAssetsRequest,
+) -> Result<Vec<ExternalAssetStatus>, String> {
+    tauri::async_runtime::spawn_blocking(move || asset_statuses(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn get_credits(request: ProjectAssetsRequest) -> Result<CreditsSettings, String> {
+    tauri::async_runtime::spawn_blocking(move || read_credits(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn save_credits(request: SaveCreditsRequest) -> Result<CreditsSettings, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let credits = CreditsSettings {
+            lines: request
+                .credits
+                .lines
+                .iter()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty())
+                .collect(),
+        };
+        write_json(
+            &project_file(&request.project_id, "credits.json")?,
+            &credits,
+            "credits",
+        )?;
+        Ok(credits)
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
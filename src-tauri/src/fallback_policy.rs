@@ -0,0 +1,374 @@
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{append_telemetry_event, now_iso};
+
+/// Where a candidate runs. Maps onto the scripts' `--fallback-policy`
+/// values, pinned to a single side so the Rust runner owns the ordering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Provider {
+    Local,
+    Cloud,
+}
+
+impl Provider {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "local" => Some(Self::Local),
+            "cloud" | "api" => Some(Self::Cloud),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Local => "local",
+            Self::Cloud => "cloud",
+        }
+    }
+
+    fn script_policy(self) -> &'static str {
+        match self {
+            Self::Local => "local-only",
+            Self::Cloud => "api-only",
+        }
+    }
+}
+
+/// Ordered candidates for each stage. Empty chains leave the choice to the
+/// request (or the script's defaults).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FallbackPolicy {
+    pub providers: Vec<Provider>,
+    pub transcription: Vec<String>,
+    pub cut_planner: Vec<String>,
+    pub template_planner: Vec<String>,
+}
+
+/// The policy as sent by the frontend: either the compact text form or the
+/// structured object.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum FallbackPolicyInput {
+    Text(String),
+    Structured(FallbackPolicy),
+}
+
+impl FallbackPolicyInput {
+    pub fn resolve(&self) -> Result<FallbackPolicy, String> {
+        match self {
+            Self::Text(text) => FallbackPolicy::parse(text),
+            Self::Structured(policy) => Ok(policy.clone()),
+        }
+    }
+}
+
+/// One thing the runner will try.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Candidate {
+    pub provider: Option<Provider>,
+    pub transcription_model: Option<String>,
+    pub cut_planner_model: Option<String>,
+    pub template_planner_model: Option<String>,
+}
+
+impl Candidate {
+    /// Script arguments selecting this candidate.
+    pub fn script_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut push = |flag: &str, value: Option<&str>| {
+            if let Some(value) = value.filter(|value| !value.trim().is_empty()) {
+                args.push(flag.to_string());
+                args.push(value.to_string());
+            }
+        };
+        push(
+            "--fallback-policy",
+            self.provider.map(Provider::script_policy),
+        );
+        push("--transcription-model", self.transcription_model.as_deref());
+        push("--cut-planner-model", self.cut_planner_model.as_deref());
+        push(
+            "--template-planner-model",
+            self.template_planner_model.as_deref(),
+        );
+        args
+    }
+
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(provider) = self.provider {
+            parts.push(provider.as_str().to_string());
+        }
+        for model in [
+            &self.transcription_model,
+            &self.cut_planner_model,
+            &self.template_planner_model,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            parts.push(model.clone());
+        }
+        if parts.is_empty() {
+            "default".to_string()
+        } else {
+            parts.join(" / ")
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FallbackAttempt {
+    pub attempt: usize,
+    pub candidate: Candidate,
+    pub ok: bool,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FallbackReport {
+    pub policy: FallbackPolicy,
+    pub attempts: Vec<FallbackAttempt>,
+    /// The candidate that succeeded, if any did.
+    pub used: Option<Candidate>,
+}
+
+fn split_chain(clause: &str) -> Vec<String> {
+    clause
+        .replace('→', ">")
+        .replace("->", ">")
+        .split([',', '>'])
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+impl FallbackPolicy {
+    /// Parses the compact form, e.g. `large-v3 → medium → small; cloud → local`.
+    /// Clauses are separated by `;`; a clause may be prefixed with its stage
+    /// (`transcription:`, `cuts:`, `templates:`) and otherwise feeds the
+    /// transcription chain. A chain of `local`/`cloud` sets the provider
+    /// order. The legacy `local-first`-style values are still accepted.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut policy = Self::default();
+        for clause in text.split(';').map(str::trim).filter(|c| !c.is_empty()) {
+            match clause.to_ascii_lowercase().as_str() {
+                "local-first" => policy.providers = vec![Provider::Local, Provider::Cloud],
+                "api-first" => policy.providers = vec![Provider::Cloud, Provider::Local],
+                "local-only" => policy.providers = vec![Provider::Local],
+                "api-only" => policy.providers = vec![Provider::Cloud],
+                _ => policy.parse_chain(clause)?,
+            }
+        }
+        Ok(policy)
+    }
+
+    fn parse_chain(&mut self, clause: &str) -> Result<(), String> {
+        let (stage, chain) = match clause.split_once(':') {
+            // Ollama tags contain `:` too, so only known stage names count.
+            Some((stage, chain)) if Self::stage_slot(stage).is_some() => (Some(stage), chain),
+            _ => (None, clause),
+        };
+        let items = split_chain(chain);
+        if items.is_empty() {
+            return Err(format!(
+                "Fallback policy clause '{clause}' has no candidates."
+            ));
+        }
+        let providers = items
+            .iter()
+            .map(|item| Provider::parse(item))
+            .collect::<Option<Vec<_>>>();
+        match (stage, providers) {
+            (None, Some(providers)) => self.providers = providers,
+            (Some(stage), _) => *self.stage_chain(stage) = items,
+            (None, None) => self.transcription = items,
+        }
+        Ok(())
+    }
+
+    fn stage_slot(stage: &str) -> Option<usize> {
+        match stage.trim().to_ascii_lowercase().as_str() {
+            "transcription" | "transcribe" | "whisper" => Some(0),
+            "cuts" | "cut-planner" | "planner" => Some(1),
+            "templates" | "template-planner" => Some(2),
+            _ => None,
+        }
+    }
+
+    fn stage_chain(&mut self, stage: &str) -> &mut Vec<String> {
+        match Self::stage_slot(stage) {
+            Some(1) => &mut self.cut_planner,
+            Some(2) => &mut self.template_planner,
+            _ => &mut self.transcription,
+        }
+    }
+
+    /// Expands the policy into the order the runner tries things. Each
+    /// provider is tried in turn; on the local side the model chains advance
+    /// together (a shorter chain keeps its last entry). Cloud runs use the
+    /// API's own models, so it gets a single candidate. Stages without a
+    /// chain use `defaults`.
+    pub fn candidates(&self, defaults: &Candidate) -> Vec<Candidate> {
+        let providers = if self.providers.is_empty() {
+            vec![None]
+        } else {
+            self.providers.iter().copied().map(Some).collect()
+        };
+        let pick = |chain: &[String], index: usize, fallback: &Option<String>| {
+            chain
+                .get(index)
+                .or_else(|| chain.last())
+                .cloned()
+                .or_else(|| fallback.clone())
+        };
+        let steps = self
+            .transcription
+            .len()
+            .max(self.cut_planner.len())
+            .max(self.template_planner.len())
+            .max(1);
+
+        let mut candidates = Vec::new();
+        for provider in providers {
+            let steps = if provider == Some(Provider::Cloud) {
+                1
+            } else {
+                steps
+            };
+            for index in 0..steps {
+                let candidate = if provider == Some(Provider::Cloud) {
+                    Candidate {
+                        provider,
+                        ..Candidate::default()
+                    }
+                } else {
+                    Candidate {
+                        provider,
+                        transcription_model: pick(
+                            &self.transcription,
+                            index,
+                            &defaults.transcription_model,
+                        ),
+                        cut_planner_model: pick(
+                            &self.cut_planner,
+                            index,
+                            &defaults.cut_planner_model,
+                        ),
+                        template_planner_model: pick(
+                            &self.template_planner,
+                            index,
+                            &defaults.template_planner_model,
+                        ),
+                    }
+                };
+                if !candidates.contains(&candidate) {
+                    candidates.push(candidate);
+                }
+            }
+        }
+        candidates
+    }
+}
+
+fn record_attempt(project_id: &str, pipeline: &str, attempt: &FallbackAttempt) {
+    let event = serde_json::json!({
+        "timestamp": now_iso(),
+        "projectId": project_id,
+        "pipeline": format!("{pipeline}-fallback"),
+        "status": if attempt.ok { "ATTEMPT_SUCCEEDED" } else { "ATTEMPT_FAILED" },
+        "error": attempt.error.clone().unwrap_or_default(),
+        "totalDurationMs": attempt.duration_ms,
+        "stageDurationsMs": {},
+        "meta": {
+            "attempt": attempt.attempt,
+            "candidate": attempt.candidate,
+        },
+    });
+    if let Err(error) = append_telemetry_event(project_id, &event) {
+        tracing::warn!("Failed recording fallback attempt: {error}");
+    }
+}
+
+/// Runs `run` for each candidate until one succeeds, recording every attempt
+/// in telemetry. Fails with the last error once every candidate has failed.
+pub fn run_with_fallback<T>(
+    project_id: &str,
+    pipeline: &str,
+    policy: FallbackPolicy,
+    defaults: &Candidate,
+    mut run: impl FnMut(&Candidate) -> Result<T, String>,
+) -> Result<(T, FallbackReport), String> {
+    let candidates = policy.candidates(defaults);
+    let mut report = FallbackReport {
+        policy,
+        attempts: Vec::new(),
+        used: None,
+    };
+    for (index, candidate) in candidates.iter().enumerate() {
+        let started = Instant::now();
+        let result = run(candidate);
+        let attempt = FallbackAttempt {
+            attempt: index + 1,
+            candidate: candidate.clone(),
+            ok: result.is_ok(),
+            error: result.as_ref().err().cloned(),
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+        record_attempt(project_id, pipeline, &attempt);
+        report.attempts.push(attempt);
+        match result {
+            Ok(value) => {
+                if index > 0 {
+                    tracing::info!(
+                        "{pipeline} succeeded on fallback {}: {}",
+                        index + 1,
+                        candidate.describe()
+                    );
+                }
+                report.used = Some(candidate.clone());
+                return Ok((value, report));
+            }
+            Err(error) if index + 1 < candidates.len() => tracing::warn!(
+                "{pipeline} failed with {}; trying next fallback: {error}",
+                candidate.describe()
+            ),
+            Err(_) => {}
+        }
+    }
+    let last_error = report
+        .attempts
+        .last()
+        .and_then(|attempt| attempt.error.clone())
+        .unwrap_or_default();
+    Err(format!(
+        "{pipeline} failed after {} attempt(s): {last_error}",
+        report.attempts.len()
+    ))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseFallbackPolicyRequest {
+    policy: FallbackPolicyInput,
+}
+
+/// Shows how a policy will be interpreted, for the settings screen.
+#[tauri::command]
+pub async fn parse_fallback_policy(request: ParseFallbackPolicyRequest) -> Result<Value, String> {
+    let policy = request.policy.resolve()?;
+    let candidates = policy.candidates(&Candidate::default());
+    Ok(serde_json::json!({
+        "policy": policy,
+        "candidates": candidates,
+    }))
+}
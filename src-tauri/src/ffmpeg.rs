@@ -0,0 +1,444 @@
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{shutdown, workspace_root};
+
+/// Oldest release the render pipeline is tested against.
+pub const MIN_FFMPEG_VERSION: (u32, u32) = (4, 4);
+const REQUIRED_ENCODERS: &[&str] = &["libx264", "aac"];
+/// `subtitles`/`ass` burn captions, `drawtext` renders title clips.
+const REQUIRED_FILTERS: &[&str] = &["subtitles", "ass", "drawtext"];
+
+/// A static build `install_ffmpeg` fetches for one platform.
+struct PinnedBuild {
+    os: &'static str,
+    arch: &'static str,
+    /// An asset of a dated release, never a moving tag like `latest`.
+    url: &'static str,
+    /// Digest of that asset, recorded when the pin was bumped; the download
+    /// must match it exactly.
+    sha256: &'static str,
+}
+
+/// Pinned builds for `install_ffmpeg`. A platform is added only together
+/// with the sha256 of the published asset, so nothing fetched at install
+/// time decides what is trusted. Platforms without an entry need ffmpeg
+/// installed by hand or a `url` and `sha256` passed in.
+const PINNED_BUILDS: &[PinnedBuild] = &[];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FfmpegSource {
+    /// `FFMPEG_BIN` / `FFPROBE_BIN`.
+    EnvOverride,
+    /// A build downloaded by `install_ffmpeg`.
+    Installed,
+    SystemPath,
+    CommonLocation,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FfmpegInfo {
+    /// Found, new enough and has every required encoder and filter.
+    pub ok: bool,
+    pub ffmpeg_path: Option<String>,
+    pub ffprobe_path: Option<String>,
+    pub source: Option<FfmpegSource>,
+    /// First line of `ffmpeg -version`.
+    pub version: Option<String>,
+    pub meets_minimum: bool,
+    pub min_version: String,
+    pub missing_encoders: Vec<String>,
+    pub missing_filters: Vec<String>,
+    pub install_dir: String,
+    /// Whether `install_ffmpeg` has a pinned build for this platform.
+    pub can_auto_install: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallFfmpegRequest {
+    /// Archive (.zip or .tar.xz) to install instead of the pinned build.
+    url: Option<String>,
+    /// Required with `url`.
+    sha256: Option<String>,
+}
+
+fn executable(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{name}.exe")
+    } else {
+        name.to_string()
+    }
+}
+
+fn install_dir() -> Result<PathBuf, String> {
+    Ok(workspace_root()?
+        .join("desktop")
+        .join("data")
+        .join("tools")
+        .join("ffmpeg"))
+}
+
+fn common_dirs() -> Vec<PathBuf> {
+    if cfg!(windows) {
+        let mut dirs = vec![PathBuf::from(r"C:\ffmpeg\bin")];
+        if let Some(base) = env::var_os("ProgramFiles") {
+            dirs.push(PathBuf::from(base).join("ffmpeg").join("bin"));
+        }
+        dirs
+    } else {
+        ["/opt/homebrew/bin", "/usr/local/bin", "/usr/bin"]
+            .iter()
+            .map(PathBuf::from)
+            .collect()
+    }
+}
+
+/// Finds `ffmpeg` and `ffprobe`, preferring a pair from the same place.
+fn locate() -> Option<(PathBuf, PathBuf, FfmpegSource)> {
+    if let Some(ffmpeg) = env::var_os("FFMPEG_BIN").map(PathBuf::from) {
+        let ffprobe = env::var_os("FFPROBE_BIN")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| ffmpeg.with_file_name(executable("ffprobe")));
+        return Some((ffmpeg, ffprobe, FfmpegSource::EnvOverride));
+    }
+
+    let mut dirs = Vec::new();
+    if let Ok(dir) = install_dir() {
+        dirs.push((dir.join("bin"), FfmpegSource::Installed));
+    }
+    if let Some(path) = env::var_os("PATH") {
+        dirs.extend(env::split_paths(&path).map(|dir| (dir, FfmpegSource::SystemPath)));
+    }
+    dirs.extend(
+        common_dirs()
+            .into_iter()
+            .map(|dir| (dir, FfmpegSource::CommonLocation)),
+    );
+    dirs.into_iter().find_map(|(dir, source)| {
+        let ffmpeg = dir.join(executable("ffmpeg"));
+        let ffprobe = dir.join(executable("ffprobe"));
+        (ffmpeg.is_file() && ffprobe.is_file()).then_some((ffmpeg, ffprobe, source))
+    })
+}
+
+/// Program to run for ffmpeg; plain `ffmpeg` when nothing was found so the
+/// spawn error still names it.
+pub fn ffmpeg_binary() -> PathBuf {
+    locate().map_or_else(|| PathBuf::from("ffmpeg"), |(ffmpeg, _, _)| ffmpeg)
+}
+
+pub fn ffprobe_binary() -> PathBuf {
+    locate().map_or_else(|| PathBuf::from("ffprobe"), |(_, ffprobe, _)| ffprobe)
+}
+
+/// `PATH` with the resolved ffmpeg directory first, for child processes
+/// (the Node pipeline scripts) that look ffmpeg up by name.
+pub fn child_path() -> Option<OsString> {
+    let (ffmpeg, _, _) = locate()?;
+    let dir = ffmpeg.parent()?.to_path_buf();
+    let mut dirs = vec![dir];
+    if let Some(path) = env::var_os("PATH") {
+        dirs.extend(env::split_paths(&path));
+    }
+    env::join_paths(dirs).ok()
+}
+
+/// Ends a capture the way ffmpeg's console does, `q` on stdin, so the file
+/// gets finalized; stops it outright once `timeout` passes.
+pub fn stop_capture(child: &mut Child, timeout: Duration) {
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(b"q\n");
+    }
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if let Ok(Some(_)) = child.try_wait() {
+            return;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    shutdown::terminate_child(child);
+}
+
+/// `(major, minor)` from `ffmpeg version 6.1.1 ...` or `ffmpeg version n7.1-...`.
+/// Git builds (`N-113000-g...`) have no release number.
+fn parse_version(line: &str) -> Option<(u32, u32)> {
+    let token = line.split_whitespace().nth(2)?;
+    let token = token.strip_prefix('n').unwrap_or(token);
+    let mut parts = token.split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().and_then(|part| part.parse().ok()).unwrap_or(0);
+    Some((major, minor))
+}
+
+pub(crate) fn run_listing(ffmpeg: &Path, flag: &str) -> Result<String, String> {
+    let output = Command::new(ffmpeg)
+        .args(["-hide_banner", flag])
+        .output()
+        .map_err(|error| format!("Failed to execute ffmpeg: {error}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "ffmpeg {flag} failed: {}",
+            stderr.trim().chars().take(300).collect::<String>()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Names from `-encoders` / `-filters` output, which list the flags column
+/// first and the name second.
+pub(crate) fn listed_names(listing: &str) -> Vec<String> {
+    listing
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(str::to_string)
+        .collect()
+}
+
+fn missing(required: &[&str], available: &[String]) -> Vec<String> {
+    required
+        .iter()
+        .filter(|name| !available.iter().any(|available| available == *name))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// The pinned build for this platform.
+fn pinned_build() -> Option<&'static PinnedBuild> {
+    PINNED_BUILDS
+        .iter()
+        .find(|build| build.os == env::consts::OS && build.arch == env::consts::ARCH)
+}
+
+pub fn ffmpeg_info() -> FfmpegInfo {
+    let min_version = format!("{}.{}", MIN_FFMPEG_VERSION.0, MIN_FFMPEG_VERSION.1);
+    let mut info = FfmpegInfo {
+        ok: false,
+        ffmpeg_path: None,
+        ffprobe_path: None,
+        source: None,
+        version: None,
+        meets_minimum: false,
+        min_version,
+        missing_encoders: Vec::new(),
+        missing_filters: Vec::new(),
+        install_dir: install_dir()
+            .map(|dir| dir.join("bin").to_string_lossy().to_string())
+            .unwrap_or_default(),
+        can_auto_install: pinned_build().is_some(),
+        error: None,
+    };
+    let Some((ffmpeg, ffprobe, source)) = locate() else {
+        info.error = Some("ffmpeg and ffprobe were not found.".to_string());
+        return info;
+    };
+    info.ffmpeg_path = Some(ffmpeg.to_string_lossy().to_string());
+    info.ffprobe_path = Some(ffprobe.to_string_lossy().to_string());
+    info.source = Some(source);
+
+    let version = match run_listing(&ffmpeg, "-version") {
+        Ok(output) => output.lines().next().unwrap_or_default().trim().to_string(),
+        Err(error) => {
+            info.error = Some(error);
+            return info;
+        }
+    };
+    // Unnumbered git builds are assumed current.
+    info.meets_minimum = !parse_version(&version).is_some_and(|parsed| parsed < MIN_FFMPEG_VERSION);
+    info.version = Some(version);
+
+    let listings = run_listing(&ffmpeg, "-encoders")
+        .and_then(|encoders| Ok((encoders, run_listing(&ffmpeg, "-filters")?)));
+    match listings {
+        Ok((encoders, filters)) => {
+            info.missing_encoders = missing(REQUIRED_ENCODERS, &listed_names(&encoders));
+            info.missing_filters = missing(REQUIRED_FILTERS, &listed_names(&filters));
+        }
+        Err(error) => {
+            info.error = Some(error);
+            return info;
+        }
+    }
+
+    info.ok =
+        info.meets_minimum && info.missing_encoders.is_empty() && info.missing_filters.is_empty();
+    if !info.meets_minimum {
+        info.error = Some(format!("ffmpeg {} or newer is required.", info.min_version));
+    } else if !info.ok {
+        info.error = Some(format!(
+            "ffmpeg is missing: {}",
+            info.missing_encoders
+                .iter()
+                .chain(&info.missing_filters)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    info
+}
+
+pub(crate) fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file =
+        fs::File::open(path).map_err(|error| format!("Failed opening download: {error}"))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 16];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|error| format!("Failed reading download: {error}"))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+fn find_binary(dir: &Path, name: &str) -> Option<PathBuf> {
+    for entry in fs::read_dir(dir).ok()?.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_dir() {
+            if let Some(found) = find_binary(&path, name) {
+                return Some(found);
+            }
+        } else if path.file_name().is_some_and(|file| file == name) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+fn install_blocking(request: InstallFfmpegRequest) -> Result<FfmpegInfo, String> {
+    let http = Client::builder()
+        .timeout(Duration::from_secs(1800))
+        .build()
+        .map_err(|error| format!("HTTP client error: {error}"))?;
+
+    let (url, expected) = match request.url.filter(|url| !url.trim().is_empty()) {
+        Some(url) => {
+            let sha256 = request
+                .sha256
+                .filter(|sha256| !sha256.trim().is_empty())
+                .ok_or_else(|| "A sha256 checksum is required with a custom url.".to_string())?;
+            (url.trim().to_string(), sha256.trim().to_lowercase())
+        }
+        None => {
+            let build = pinned_build().ok_or_else(|| {
+                format!(
+                    "No pinned ffmpeg build for {}-{}; install ffmpeg yourself or pass a url.",
+                    env::consts::OS,
+                    env::consts::ARCH
+                )
+            })?;
+            (build.url.to_string(), build.sha256.to_lowercase())
+        }
+    };
+
+    let dir = install_dir()?;
+    let staging = dir.join("staging");
+    if staging.exists() {
+        fs::remove_dir_all(&staging)
+            .map_err(|error| format!("Failed clearing ffmpeg staging dir: {error}"))?;
+    }
+    fs::create_dir_all(&staging)
+        .map_err(|error| format!("Failed creating ffmpeg staging dir: {error}"))?;
+
+    let archive_name = url
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or("ffmpeg-archive");
+    let archive = staging.join(archive_name);
+    tracing::info!("Downloading ffmpeg from {url}");
+    let mut response = http
+        .get(&url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| format!("Failed downloading ffmpeg: {error}"))?;
+    let mut file =
+        fs::File::create(&archive).map_err(|error| format!("Failed creating download: {error}"))?;
+    response
+        .copy_to(&mut file)
+        .map_err(|error| format!("Failed downloading ffmpeg: {error}"))?;
+    drop(file);
+
+    let actual = sha256_file(&archive)?;
+    if actual != expected {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(format!(
+            "ffmpeg download checksum mismatch: expected {expected}, got {actual}."
+        ));
+    }
+
+    // bsdtar (macOS, Windows 10+) reads zip as well as tar.xz.
+    let extract = staging.join("extract");
+    fs::create_dir_all(&extract)
+        .map_err(|error| format!("Failed creating ffmpeg extract dir: {error}"))?;
+    let output = Command::new("tar")
+        .arg("-xf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(&extract)
+        .output()
+        .map_err(|error| format!("Failed to execute tar: {error}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "Extracting ffmpeg failed: {}",
+            stderr.trim().chars().take(300).collect::<String>()
+        ));
+    }
+
+    let bin_dir = dir.join("bin");
+    fs::create_dir_all(&bin_dir)
+        .map_err(|error| format!("Failed creating ffmpeg bin dir: {error}"))?;
+    for name in ["ffmpeg", "ffprobe"] {
+        let name = executable(name);
+        let found = find_binary(&extract, &name)
+            .ok_or_else(|| format!("{name} not found in the downloaded archive."))?;
+        let target = bin_dir.join(&name);
+        fs::copy(&found, &target).map_err(|error| format!("Failed installing {name}: {error}"))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&target, fs::Permissions::from_mode(0o755))
+                .map_err(|error| format!("Failed marking {name} executable: {error}"))?;
+        }
+    }
+    let _ = fs::remove_dir_all(&staging);
+    tracing::info!("Installed ffmpeg into {}", bin_dir.display());
+    Ok(ffmpeg_info())
+}
+
+#[tauri::command]
+pub async fn get_ffmpeg_info() -> Result<FfmpegInfo, String> {
+    tauri::async_runtime::spawn_blocking(ffmpeg_info)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))
+}
+
+#[tauri::command]
+pub async fn install_ffmpeg(request: Option<InstallFfmpegRequest>) -> Result<FfmpegInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || install_blocking(request.unwrap_or_default()))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
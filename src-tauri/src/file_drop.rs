@@ -0,0 +1,159 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::Instrument;
+
+use crate::media::MediaKind;
+use crate::windows::ProjectWindows;
+use crate::{run_ingest_media, scheduler, MediaIngestRequest};
+
+pub const MEDIA_DROPPED_EVENT: &str = "media://dropped";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DropState {
+    /// Not media, not a file, or no project open; nothing was ingested.
+    Rejected,
+    /// Ingest job has a slot and is running.
+    Started,
+    Ingested,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaDropEvent {
+    pub path: String,
+    pub project_id: Option<String>,
+    /// Scheduler job running the ingest; set from `started` on.
+    pub job_id: Option<u64>,
+    pub state: DropState,
+    pub error: Option<String>,
+    /// `ingest_media` output once ingested.
+    pub result: Option<Value>,
+}
+
+/// Drop events go to the window the files were dropped on.
+fn emit(app: &AppHandle, label: &str, event: MediaDropEvent) {
+    let _ = app.emit_to(label, MEDIA_DROPPED_EVENT, event);
+}
+
+fn unsupported_reason(path: &Path) -> Option<String> {
+    if !path.is_file() {
+        return Some("Only files can be imported; folders are skipped.".to_string());
+    }
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let extension = extension.as_str();
+    MediaKind::of_path(path)
+        .is_none()
+        .then(|| format!("Unsupported file type .{extension}."))
+}
+
+async fn ingest_dropped(app: AppHandle, label: String, project_id: String, path: PathBuf) {
+    let display = path.to_string_lossy().to_string();
+    let mut event = MediaDropEvent {
+        path: display.clone(),
+        project_id: Some(project_id.clone()),
+        job_id: None,
+        state: DropState::Started,
+        error: None,
+        result: None,
+    };
+    let permit = match scheduler::acquire(
+        &app,
+        scheduler::JobKind::Ingest,
+        Some(scheduler::Priority::Interactive),
+        &project_id,
+    )
+    .await
+    {
+        Ok(permit) => permit,
+        Err(error) => {
+            event.state = DropState::Failed;
+            event.error = Some(error);
+            emit(&app, &label, event);
+            return;
+        }
+    };
+    event.job_id = Some(permit.job_id());
+    emit(&app, &label, event.clone());
+
+    let outcome = run_ingest_media(MediaIngestRequest {
+        project_id,
+        input: display,
+        generate_proxy: None,
+        generate_waveform: None,
+        priority: None,
+    })
+    .instrument(permit.span())
+    .await;
+    drop(permit);
+    match outcome {
+        Ok(result) => {
+            event.state = DropState::Ingested;
+            event.result = Some(result);
+        }
+        Err(error) => {
+            tracing::warn!("Dropped file {} failed to ingest: {error}", event.path);
+            event.state = DropState::Failed;
+            event.error = Some(error);
+        }
+    }
+    emit(&app, &label, event);
+}
+
+fn reject(app: &AppHandle, label: &str, path: &Path, project_id: Option<String>, error: String) {
+    tracing::info!("Ignoring dropped path {}: {error}", path.display());
+    emit(
+        app,
+        label,
+        MediaDropEvent {
+            path: path.to_string_lossy().to_string(),
+            project_id,
+            job_id: None,
+            state: DropState::Rejected,
+            error: Some(error),
+            result: None,
+        },
+    );
+}
+
+/// Called for a window's drop event: each supported file becomes an
+/// ingest job for the project open in that window, reported back to it
+/// through `media://dropped`.
+pub fn handle_drop(app: &AppHandle, label: &str, paths: &[PathBuf]) {
+    let project_id = app.state::<ProjectWindows>().project_for(label);
+    ingest_paths(app, label, project_id, paths);
+}
+
+/// Same as a drop on window `label`, but into a given project; used for
+/// files the app is opened with.
+pub fn ingest_paths(app: &AppHandle, label: &str, project_id: Option<String>, paths: &[PathBuf]) {
+    for path in paths {
+        let Some(project_id) = project_id.clone() else {
+            reject(
+                app,
+                label,
+                path,
+                None,
+                "Open a project before dropping media.".to_string(),
+            );
+            continue;
+        };
+        if let Some(error) = unsupported_reason(path) {
+            reject(app, label, path, Some(project_id), error);
+            continue;
+        }
+        tauri::async_runtime::spawn(ingest_dropped(
+            app.clone(),
+            label.to_string(),
+            project_id,
+            path.clone(),
+        ));
+    }
+}
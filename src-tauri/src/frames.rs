@@ -0,0 +1,348 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::ffmpeg::ffmpeg_binary;
+use crate::media::resolve_source_path;
+use crate::preview::PREVIEW_DIR;
+use crate::timeline::timeline_to_source_us;
+use crate::{luts, path_safety, read_timeline, subtitles, TimelineClip};
+
+/// Scrubber frames kept under `preview/frames`; older ones are dropped.
+const PREVIEW_FRAME_CACHE: usize = 400;
+const DEFAULT_PREVIEW_WIDTH: u32 = 640;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportFrameRequest {
+    project_id: String,
+    at_us: u64,
+    /// Defaults to `frames/frame-<atUs>.<format>` in the project directory.
+    path: Option<String>,
+    /// `png` (default), `jpg` or `webp`.
+    format: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedFrame {
+    pub path: String,
+    pub format: String,
+    /// Timeline position after snapping to the start of its frame.
+    pub timeline_us: u64,
+    pub clip_id: String,
+    pub source_ref: String,
+    pub source_us: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewFrameRequest {
+    project_id: String,
+    at_us: u64,
+    /// Output width in pixels; the height follows the picture's aspect.
+    width: Option<u32>,
+    /// `jpg` (default) or `png`.
+    format: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewFrame {
+    /// Cached image; the same position and edit give the same file.
+    pub path: String,
+    pub format: String,
+    pub width: u32,
+    /// Timeline position after snapping to the start of its frame.
+    pub timeline_us: u64,
+    /// None over a gap, which renders black.
+    pub clip_id: Option<String>,
+    pub source_us: Option<u64>,
+}
+
+/// `at_us` snapped back to the start of the frame it falls in.
+fn snap_to_frame(at_us: u64, fps: u32) -> u64 {
+    let fps = u128::from(fps.max(1));
+    let frame_index = u128::from(at_us) * fps / 1_000_000;
+    (frame_index * 1_000_000 / fps) as u64
+}
+
+/// The render's crop for a clip transform (fractions of the source frame),
+/// as an ffmpeg `crop` filter.
+fn crop_filter(transform: &Value) -> Option<String> {
+    let crop = transform.get("crop")?;
+    let fraction = |key: &str| {
+        crop.get(key)
+            .and_then(Value::as_f64)
+            .unwrap_or(0.0)
+            .clamp(0.0, 1.0)
+    };
+    let (width, height) = (fraction("width"), fraction("height"));
+    if width <= 0.0 || height <= 0.0 {
+        return None;
+    }
+    let x = fraction("x").min(1.0 - width);
+    let y = fraction("y").min(1.0 - height);
+    Some(format!(
+        "crop=trunc(iw*{width:.6}/2)*2:trunc(ih*{height:.6}/2)*2:trunc(iw*{x:.6}):trunc(ih*{y:.6})"
+    ))
+}
+
+/// Filters the render applies to a source clip's picture: its LUT (or the
+/// project's), then the crop, then scaling to `width`.
+fn clip_video_filter(project_id: &str, clip: &TimelineClip, width: u32) -> String {
+    let lut = clip
+        .effects
+        .get("lut")
+        .and_then(Value::as_str)
+        .and_then(|reference| luts::resolve_lut(reference).ok())
+        .or_else(|| luts::project_lut(project_id));
+    let mut filters = Vec::new();
+    if let Some(lut) = lut {
+        let escaped = lut
+            .to_string_lossy()
+            .replace('\\', "/")
+            .replace(':', "\\:")
+            .replace(',', "\\,")
+            .replace('[', "\\[")
+            .replace(']', "\\]");
+        // Escaped the way the render script escapes filter paths.
+        filters.push(format!("lut3d=file={escaped}"));
+    }
+    filters.extend(crop_filter(&clip.transform));
+    filters.push(format!("scale={width}:-2"));
+    filters.join(",")
+}
+
+fn file_stamp(path: &Path) -> String {
+    fs::metadata(path)
+        .map(|metadata| {
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |elapsed| elapsed.as_secs());
+            format!("{}:{modified}", metadata.len())
+        })
+        .unwrap_or_default()
+}
+
+fn frame_key(parts: &str) -> String {
+    Sha256::digest(parts.as_bytes())
+        .iter()
+        .take(12)
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Drops the oldest cached frames past [`PREVIEW_FRAME_CACHE`].
+fn prune_frame_cache(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut frames = entries
+        .flatten()
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect::<Vec<_>>();
+    if frames.len() <= PREVIEW_FRAME_CACHE {
+        return;
+    }
+    frames.sort();
+    for (_, path) in &frames[..frames.len() - PREVIEW_FRAME_CACHE] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn preview_frame_blocking(request: PreviewFrameRequest) -> Result<PreviewFrame, String> {
+    let format = match request.format.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("jpg") | Some("jpeg") => "jpg",
+        Some("png") => "png",
+        Some(other) => return Err(format!("Unsupported frame format: {other}")),
+    };
+    // Even widths keep the scaled height computable for chroma subsampling.
+    let width = request
+        .width
+        .unwrap_or(DEFAULT_PREVIEW_WIDTH)
+        .clamp(16, 3840)
+        / 2
+        * 2;
+
+    let timeline = read_timeline(&request.project_id)?;
+    let timeline_us = snap_to_frame(request.at_us, timeline.fps);
+    if timeline_us >= timeline.duration_us.max(1) {
+        return Err(format!("{timeline_us}us is past the end of the timeline."));
+    }
+    let shown = timeline_to_source_us(&timeline, timeline_us);
+
+    let mut command = Command::new(ffmpeg_binary());
+    command.args(["-y", "-hide_banner", "-loglevel", "error"]);
+    let key = match shown {
+        Some((clip, source_us)) => {
+            let source_path = resolve_source_path(&request.project_id, Some(&clip.source_ref))?;
+            let filter = clip_video_filter(&request.project_id, clip, width);
+            command
+                .arg("-ss")
+                .arg(format!("{:.6}", source_us as f64 / 1_000_000.0))
+                .arg("-i")
+                .arg(&source_path)
+                .args(["-frames:v", "1", "-an", "-vf", &filter]);
+            format!(
+                "{}|{}|{source_us}|{filter}|{format}",
+                source_path.display(),
+                file_stamp(&source_path)
+            )
+        }
+        None => {
+            let (frame_width, frame_height) =
+                subtitles::timeline_frame_size(&request.project_id, &timeline);
+            let height = (u64::from(width) * u64::from(frame_height)
+                / u64::from(frame_width.max(1))) as u32
+                / 2
+                * 2;
+            command
+                .args(["-f", "lavfi", "-i"])
+                .arg(format!("color=c=black:s={width}x{}", height.max(2)));
+            command.args(["-frames:v", "1"]);
+            format!("gap|{width}x{height}|{format}")
+        }
+    };
+    if format == "jpg" {
+        command.args(["-q:v", "3"]);
+    }
+
+    let dir = path_safety::project_dir(&request.project_id)?
+        .join(PREVIEW_DIR)
+        .join("frames");
+    fs::create_dir_all(&dir).map_err(|error| format!("Failed creating frame dir: {error}"))?;
+    let output = dir.join(format!("{}.{format}", frame_key(&key)));
+    if !output.is_file() {
+        // Written under a temporary name so a failed decode never leaves a
+        // broken frame in the cache.
+        let partial = dir.join(format!("{}.part.{format}", frame_key(&key)));
+        let result = command
+            .arg(&partial)
+            .output()
+            .map_err(|error| format!("Failed to execute ffmpeg: {error}"))?;
+        if !result.status.success() || !partial.is_file() {
+            let _ = fs::remove_file(&partial);
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            return Err(format!(
+                "ffmpeg preview frame failed: {}",
+                stderr.trim().chars().take(300).collect::<String>()
+            ));
+        }
+        fs::rename(&partial, &output)
+            .map_err(|error| format!("Failed saving preview frame: {error}"))?;
+        prune_frame_cache(&dir);
+    }
+
+    Ok(PreviewFrame {
+        path: output.to_string_lossy().to_string(),
+        format: format.to_string(),
+        width,
+        timeline_us,
+        clip_id: shown.map(|(clip, _)| clip.clip_id.clone()),
+        source_us: shown.map(|(_, source_us)| source_us),
+    })
+}
+
+fn export_frame_blocking(request: ExportFrameRequest) -> Result<ExportedFrame, String> {
+    let format = request
+        .format
+        .as_deref()
+        .map(str::to_lowercase)
+        .unwrap_or_else(|| "png".to_string());
+    let codec_args: &[&str] = match format.as_str() {
+        "png" => &[],
+        "jpg" | "jpeg" => &["-q:v", "2"],
+        "webp" => &["-quality", "90"],
+        other => return Err(format!("Unsupported frame format: {other}")),
+    };
+
+    let timeline = read_timeline(&request.project_id)?;
+    let timeline_us = snap_to_frame(request.at_us, timeline.fps);
+    let (clip, source_us) = timeline_to_source_us(&timeline, timeline_us)
+        .ok_or_else(|| format!("No video clip at {timeline_us}us on the timeline."))?;
+    let source_path = resolve_source_path(&request.project_id, Some(&clip.source_ref))?;
+
+    let output = match request.path.filter(|path| !path.trim().is_empty()) {
+        Some(path) => PathBuf::from(path),
+        None => path_safety::project_dir(&request.project_id)?
+            .join("frames")
+            .join(format!("frame-{timeline_us}.{format}")),
+    };
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating frame dir: {error}"))?;
+    }
+
+    // Input-side seeking decodes up to the exact timestamp when re-encoding,
+    // so this is frame-accurate without decoding the whole file.
+    let result = Command::new(ffmpeg_binary())
+        .args(["-y", "-hide_banner", "-loglevel", "error", "-ss"])
+        .arg(format!("{:.6}", source_us as f64 / 1_000_000.0))
+        .arg("-i")
+        .arg(&source_path)
+        .args(["-frames:v", "1", "-an"])
+        .args(codec_args)
+        .arg(&output)
+        .output()
+        .map_err(|error| format!("Failed to execute ffmpeg: {error}"))?;
+    if !result.status.success() || !output.is_file() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(format!(
+            "ffmpeg frame export failed: {}",
+            stderr.trim().chars().take(300).collect::<String>()
+        ));
+    }
+
+    Ok(ExportedFrame {
+        path: output.to_string_lossy().to_string(),
+        format,
+        timeline_us,
+        clip_id: clip.clip_id.clone(),
+        source_ref: clip.source_ref.clone(),
+        source_us,
+    })
+}
+
+#[tauri::command]
+pub async fn export_frame(request: ExportFrameRequest) -> Result<ExportedFrame, String> {
+    tauri::async_runtime::spawn_blocking(move || export_frame_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// The timeline frame at `atUs` as a cached JPEG or PNG, with the clip's LUT
+/// and crop applied, for scrubbing without a playback engine.
+#[tauri::command]
+pub async fn get_preview_frame(request: PreviewFrameRequest) -> Result<PreviewFrame, String> {
+    tauri::async_runtime::spawn_blocking(move || preview_frame_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snaps_positions_and_builds_the_crop() {
+        assert_eq!(snap_to_frame(1_050_000, 24), 1_041_666);
+        assert_eq!(snap_to_frame(999, 30), 0);
+
+        let transform = serde_json::json!({
+            "crop": { "x": 0.9, "y": 0.0, "width": 0.5, "height": 1.0 }
+        });
+        assert_eq!(
+            crop_filter(&transform).as_deref(),
+            Some("crop=trunc(iw*0.500000/2)*2:trunc(ih*1.000000/2)*2:trunc(iw*0.500000):trunc(ih*0.000000)")
+        );
+        assert_eq!(crop_filter(&serde_json::json!({})), None);
+    }
+}
@@ -0,0 +1,492 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::workspace_root;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuInfo {
+    pub name: String,
+    pub vendor: String,
+    /// Dedicated memory, or the shared pool for unified-memory GPUs.
+    pub vram_bytes: Option<u64>,
+    /// Apple Silicon and similar, where the GPU draws from system RAM.
+    pub unified_memory: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskInfo {
+    pub path: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HardwareInfo {
+    pub os: String,
+    pub arch: String,
+    pub cpu_model: Option<String>,
+    pub cpu_cores: usize,
+    pub total_memory_bytes: Option<u64>,
+    pub gpus: Vec<GpuInfo>,
+    /// Space on the volume holding project data.
+    pub disk: Option<DiskInfo>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HardwareTier {
+    /// Under 6 GB of model memory.
+    Minimal,
+    Standard,
+    Performance,
+    /// 24 GB or more.
+    Workstation,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPick {
+    pub model: String,
+    /// Runtime id as used by `install_model` and model discovery.
+    pub runtime: String,
+    pub download_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelRecommendation {
+    pub tier: HardwareTier,
+    /// `metal`, `cuda`, `rocm` or `cpu`.
+    pub accelerator: String,
+    /// Memory assumed to be free for models when picking sizes.
+    pub memory_budget_bytes: u64,
+    pub whisper: ModelPick,
+    pub llm: ModelPick,
+    pub reasons: Vec<String>,
+    pub warnings: Vec<String>,
+    pub hardware: HardwareInfo,
+}
+
+/// stdout of a probe command, or None if it is missing or fails.
+pub(crate) fn probe(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!stdout.is_empty()).then_some(stdout)
+}
+
+pub(crate) fn powershell(script: &str) -> Option<String> {
+    probe(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", script],
+    )
+}
+
+/// Value of a `key : value` line in /proc/cpuinfo or /proc/meminfo.
+fn proc_field(path: &str, key: &str) -> Option<String> {
+    let raw = fs::read_to_string(path).ok()?;
+    raw.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        (name.trim() == key).then(|| value.trim().to_string())
+    })
+}
+
+fn cpu_model() -> Option<String> {
+    if cfg!(target_os = "macos") {
+        probe("sysctl", &["-n", "machdep.cpu.brand_string"])
+    } else if cfg!(windows) {
+        powershell("(Get-CimInstance Win32_Processor | Select-Object -First 1).Name")
+    } else {
+        proc_field("/proc/cpuinfo", "model name")
+    }
+}
+
+fn total_memory() -> Option<u64> {
+    if cfg!(target_os = "macos") {
+        probe("sysctl", &["-n", "hw.memsize"])?.parse().ok()
+    } else if cfg!(windows) {
+        powershell("(Get-CimInstance Win32_ComputerSystem).TotalPhysicalMemory")?
+            .parse()
+            .ok()
+    } else {
+        let kib = proc_field("/proc/meminfo", "MemTotal")?;
+        kib.trim_end_matches("kB")
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .map(|kib| kib * 1024)
+    }
+}
+
+fn vendor_of(name: &str) -> String {
+    let lower = name.to_lowercase();
+    let vendor =
+        if lower.contains("nvidia") || lower.contains("geforce") || lower.contains("quadro") {
+            "NVIDIA"
+        } else if lower.contains("amd") || lower.contains("radeon") {
+            "AMD"
+        } else if lower.contains("intel") {
+            "Intel"
+        } else if lower.contains("apple") {
+            "Apple"
+        } else {
+            "Unknown"
+        };
+    vendor.to_string()
+}
+
+/// `nvidia-smi` works the same on Linux and Windows and, unlike WMI,
+/// reports VRAM above 4 GB correctly.
+fn nvidia_gpus() -> Vec<GpuInfo> {
+    let Some(listing) = probe(
+        "nvidia-smi",
+        &[
+            "--query-gpu=name,memory.total",
+            "--format=csv,noheader,nounits",
+        ],
+    ) else {
+        return Vec::new();
+    };
+    listing
+        .lines()
+        .filter_map(|line| {
+            let (name, mib) = line.rsplit_once(',')?;
+            Some(GpuInfo {
+                name: name.trim().to_string(),
+                vendor: "NVIDIA".to_string(),
+                vram_bytes: mib.trim().parse::<u64>().ok().map(|mib| mib * 1024 * 1024),
+                unified_memory: false,
+            })
+        })
+        .collect()
+}
+
+/// "8 GB" / "1536 MB" from system_profiler.
+fn parse_size(raw: &str) -> Option<u64> {
+    let mut parts = raw.split_whitespace();
+    let value = parts.next()?.parse::<u64>().ok()?;
+    match parts.next()?.to_uppercase().as_str() {
+        "GB" => Some(value * GIB),
+        "MB" => Some(value * 1024 * 1024),
+        _ => None,
+    }
+}
+
+fn macos_gpus(total_memory: Option<u64>) -> Vec<GpuInfo> {
+    let Some(raw) = probe("system_profiler", &["SPDisplaysDataType", "-json"]) else {
+        return Vec::new();
+    };
+    let parsed = serde_json::from_str::<Value>(&raw).unwrap_or(Value::Null);
+    let Some(displays) = parsed.get("SPDisplaysDataType").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    displays
+        .iter()
+        .filter_map(|display| {
+            let name = display.get("sppci_model")?.as_str()?.to_string();
+            let vram = ["spdisplays_vram", "spdisplays_vram_shared"]
+                .iter()
+                .find_map(|key| display.get(*key)?.as_str().and_then(parse_size));
+            // Apple Silicon reports no VRAM; the GPU shares system memory.
+            let unified_memory = vram.is_none() && name.starts_with("Apple");
+            Some(GpuInfo {
+                vendor: vendor_of(&name),
+                vram_bytes: if unified_memory { total_memory } else { vram },
+                unified_memory,
+                name,
+            })
+        })
+        .collect()
+}
+
+fn windows_gpus() -> Vec<GpuInfo> {
+    let Some(listing) = powershell(
+        "Get-CimInstance Win32_VideoController | ForEach-Object { $_.Name + '|' + $_.AdapterRAM }",
+    ) else {
+        return Vec::new();
+    };
+    listing
+        .lines()
+        .filter_map(|line| {
+            let (name, bytes) = line.rsplit_once('|')?;
+            let name = name.trim().to_string();
+            Some(GpuInfo {
+                vendor: vendor_of(&name),
+                // AdapterRAM is a 32-bit field, so cards above 4 GB read as 4 GB.
+                vram_bytes: bytes.trim().parse().ok().filter(|bytes| *bytes > 0),
+                unified_memory: false,
+                name,
+            })
+        })
+        .collect()
+}
+
+/// amdgpu exposes VRAM in sysfs; other Linux GPUs only get a name from lspci.
+fn linux_gpus() -> Vec<GpuInfo> {
+    let amd_vram = fs::read_dir("/sys/class/drm")
+        .ok()
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            fs::read_to_string(entry.path().join("device").join("mem_info_vram_total")).ok()
+        })
+        .filter_map(|raw| raw.trim().parse::<u64>().ok())
+        .max();
+    let Some(listing) = probe("lspci", &[]) else {
+        return Vec::new();
+    };
+    listing
+        .lines()
+        .filter(|line| line.contains("VGA compatible controller") || line.contains("3D controller"))
+        .filter_map(|line| {
+            let name = line.splitn(3, ':').nth(2)?.trim().to_string();
+            let vendor = vendor_of(&name);
+            Some(GpuInfo {
+                vram_bytes: if vendor == "AMD" { amd_vram } else { None },
+                unified_memory: false,
+                vendor,
+                name,
+            })
+        })
+        .collect()
+}
+
+fn gpus(total_memory: Option<u64>) -> Vec<GpuInfo> {
+    if cfg!(target_os = "macos") {
+        return macos_gpus(total_memory);
+    }
+    let nvidia = nvidia_gpus();
+    let others = if cfg!(windows) {
+        windows_gpus()
+    } else {
+        linux_gpus()
+    };
+    // nvidia-smi has the accurate VRAM, so drop its cards from the generic list.
+    let mut gpus = others
+        .into_iter()
+        .filter(|gpu| nvidia.is_empty() || gpu.vendor != "NVIDIA")
+        .collect::<Vec<_>>();
+    gpus.splice(0..0, nvidia);
+    gpus
+}
+
+pub(crate) fn disk_info(path: &Path) -> Option<DiskInfo> {
+    let display = path.to_string_lossy().to_string();
+    if cfg!(windows) {
+        let drive = display.chars().next().filter(char::is_ascii_alphabetic)?;
+        let listing = powershell(&format!(
+            "$d = Get-PSDrive -Name {drive}; \"$($d.Used)|$($d.Free)\""
+        ))?;
+        let (used, free) = listing.split_once('|')?;
+        let used = used.trim().parse::<u64>().ok()?;
+        let free = free.trim().parse::<u64>().ok()?;
+        return Some(DiskInfo {
+            path: display,
+            total_bytes: used + free,
+            available_bytes: free,
+        });
+    }
+    // POSIX output: Filesystem 1024-blocks Used Available Capacity Mounted-on
+    let listing = probe("df", &["-Pk", &display])?;
+    let columns = listing
+        .lines()
+        .nth(1)?
+        .split_whitespace()
+        .collect::<Vec<_>>();
+    Some(DiskInfo {
+        path: display,
+        total_bytes: columns.get(1)?.parse::<u64>().ok()? * 1024,
+        available_bytes: columns.get(3)?.parse::<u64>().ok()? * 1024,
+    })
+}
+
+pub fn hardware_info() -> HardwareInfo {
+    let mut warnings = Vec::new();
+    let cpu_cores = std::thread::available_parallelism()
+        .map(|cores| cores.get())
+        .unwrap_or(1);
+    let total_memory_bytes = total_memory();
+    if total_memory_bytes.is_none() {
+        warnings.push("Could not read total memory.".to_string());
+    }
+    let gpus = gpus(total_memory_bytes);
+    if gpus.is_empty() {
+        warnings.push("No GPU detected; recommendations assume CPU-only inference.".to_string());
+    }
+    let data_dir = workspace_root()
+        .map(|root| root.join("desktop").join("data"))
+        .ok()
+        .filter(|dir| dir.is_dir())
+        .or_else(|| workspace_root().ok());
+    let disk = data_dir.as_deref().and_then(disk_info);
+    if disk.is_none() {
+        warnings.push("Could not read free disk space.".to_string());
+    }
+    HardwareInfo {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        cpu_model: cpu_model(),
+        cpu_cores,
+        total_memory_bytes,
+        gpus,
+        disk,
+        warnings,
+    }
+}
+
+/// Whisper sizes by tier, with approximate download sizes.
+const WHISPER_MODELS: [(&str, u64); 4] = [
+    ("base", 150 * 1024 * 1024),
+    ("small", 500 * 1024 * 1024),
+    ("large-v3-turbo", 1600 * 1024 * 1024),
+    ("large-v3", 3100 * 1024 * 1024),
+];
+
+/// Ollama models by tier (the Qwen3 line from the LLM provider catalog).
+const LLM_MODELS: [(&str, u64); 4] = [
+    ("qwen3:1.7b", 1400 * 1024 * 1024),
+    ("qwen3:4b", 2600 * 1024 * 1024),
+    ("qwen3:8b", 5200 * 1024 * 1024),
+    ("qwen3:14b", 9300 * 1024 * 1024),
+];
+
+fn tier_for(budget: u64) -> HardwareTier {
+    match budget / GIB {
+        0..=5 => HardwareTier::Minimal,
+        6..=11 => HardwareTier::Standard,
+        12..=23 => HardwareTier::Performance,
+        _ => HardwareTier::Workstation,
+    }
+}
+
+fn tier_index(tier: HardwareTier) -> usize {
+    match tier {
+        HardwareTier::Minimal => 0,
+        HardwareTier::Standard => 1,
+        HardwareTier::Performance => 2,
+        HardwareTier::Workstation => 3,
+    }
+}
+
+fn format_gib(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / GIB as f64)
+}
+
+pub fn recommend_for(hardware: HardwareInfo) -> ModelRecommendation {
+    let mut reasons = Vec::new();
+    let mut warnings = Vec::new();
+    let ram = hardware.total_memory_bytes.unwrap_or(8 * GIB);
+    let best_gpu = hardware
+        .gpus
+        .iter()
+        .filter(|gpu| gpu.vram_bytes.is_some())
+        .max_by_key(|gpu| gpu.vram_bytes);
+
+    let (accelerator, budget) = match best_gpu {
+        Some(gpu) if gpu.unified_memory => {
+            reasons.push(format!(
+                "{} shares {} of unified memory; about three quarters is usable for models.",
+                gpu.name,
+                format_gib(ram)
+            ));
+            ("metal", ram / 4 * 3)
+        }
+        Some(gpu) if gpu.vendor == "NVIDIA" || gpu.vendor == "AMD" => {
+            let vram = gpu.vram_bytes.unwrap_or(0);
+            reasons.push(format!("{} has {} of VRAM.", gpu.name, format_gib(vram)));
+            let accelerator = if gpu.vendor == "NVIDIA" {
+                "cuda"
+            } else {
+                "rocm"
+            };
+            (accelerator, vram)
+        }
+        _ => {
+            reasons.push(format!(
+                "No usable GPU; half of {} RAM is budgeted for CPU inference.",
+                format_gib(ram)
+            ));
+            ("cpu", ram / 2)
+        }
+    };
+
+    let tier = tier_for(budget);
+    let mut whisper_index = tier_index(tier);
+    let mut llm_index = tier_index(tier);
+    if accelerator == "cpu" {
+        // CPU inference is compute-bound long before it runs out of memory.
+        whisper_index = whisper_index.min(if hardware.cpu_cores >= 8 { 2 } else { 1 });
+        llm_index = llm_index.min(1);
+        reasons.push(format!(
+            "Capped model sizes for CPU inference on {} cores.",
+            hardware.cpu_cores
+        ));
+    }
+
+    let whisper_runtime = match accelerator {
+        "metal" => "mlx",
+        "cuda" => "faster_whisper",
+        _ => "whisper_cpp",
+    };
+    let (whisper_model, whisper_bytes) = WHISPER_MODELS[whisper_index];
+    let (llm_model, llm_bytes) = LLM_MODELS[llm_index];
+
+    if let Some(disk) = &hardware.disk {
+        let needed = whisper_bytes + llm_bytes;
+        if disk.available_bytes < needed + 2 * GIB {
+            warnings.push(format!(
+                "Only {} free on {}; the recommended models need about {}.",
+                format_gib(disk.available_bytes),
+                disk.path,
+                format_gib(needed)
+            ));
+        }
+    }
+    warnings.extend(hardware.warnings.iter().cloned());
+
+    ModelRecommendation {
+        tier,
+        accelerator: accelerator.to_string(),
+        memory_budget_bytes: budget,
+        whisper: ModelPick {
+            model: whisper_model.to_string(),
+            runtime: whisper_runtime.to_string(),
+            download_bytes: whisper_bytes,
+        },
+        llm: ModelPick {
+            model: llm_model.to_string(),
+            runtime: "ollama".to_string(),
+            download_bytes: llm_bytes,
+        },
+        reasons,
+        warnings,
+        hardware,
+    }
+}
+
+#[tauri::command]
+pub async fn get_hardware_info() -> Result<HardwareInfo, String> {
+    tauri::async_runtime::spawn_blocking(hardware_info)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))
+}
+
+#[tauri::command]
+pub async fn recommend_models() -> Result<ModelRecommendation, String> {
+    tauri::async_runtime::spawn_blocking(|| recommend_for(hardware_info()))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))
+}
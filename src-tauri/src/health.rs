@@ -0,0 +1,310 @@
+use std::env;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use reqwest::blocking::Client;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{ffmpeg, hardware, integrity, node_runtime, now_iso, workspace_root};
+
+const DEFAULT_BACKEND_PORT: &str = "43123";
+const BACKEND_TIMEOUT: Duration = Duration::from_secs(3);
+const GIB: u64 = 1024 * 1024 * 1024;
+/// Below this a render or model download is likely to fail part way.
+const DISK_FAILED_BYTES: u64 = 2 * GIB;
+const DISK_DEGRADED_BYTES: u64 = 10 * GIB;
+
+/// Ordered from best to worst so the overall status is the maximum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckStatus {
+    Ok,
+    Degraded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheck {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    /// What to do about it; None when the check passed.
+    pub hint: Option<String>,
+    pub duration_ms: u64,
+    /// The subsystem's own report, e.g. `FfmpegInfo`, for the detail view.
+    pub details: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemHealth {
+    pub ok: bool,
+    pub status: CheckStatus,
+    pub checked_at: String,
+    pub checks: Vec<HealthCheck>,
+}
+
+struct Outcome {
+    status: CheckStatus,
+    message: String,
+    hint: Option<String>,
+    details: Value,
+}
+
+impl Outcome {
+    fn new(status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            hint: None,
+            details: Value::Null,
+        }
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    fn details(mut self, details: impl Serialize) -> Self {
+        self.details = serde_json::to_value(details).unwrap_or(Value::Null);
+        self
+    }
+}
+
+fn check_node() -> Outcome {
+    let info = node_runtime::node_runtime_info();
+    let outcome = match (&info.version, &info.error) {
+        (Some(version), None) => Outcome::new(CheckStatus::Ok, format!("Node.js {version}")),
+        (Some(_), Some(error)) => Outcome::new(CheckStatus::Degraded, error.clone())
+            .hint("Install a newer Node.js, or point nodePath in settings at one."),
+        (None, error) => Outcome::new(
+            CheckStatus::Failed,
+            error
+                .clone()
+                .unwrap_or_else(node_runtime::missing_node_message),
+        )
+        .hint("Install Node.js, or set NODE_BIN or nodePath in settings."),
+    };
+    outcome.details(info)
+}
+
+fn check_ffmpeg() -> Outcome {
+    let info = ffmpeg::ffmpeg_info();
+    let install_hint = if info.can_auto_install {
+        "Install the pinned build from settings (install_ffmpeg)."
+    } else {
+        "Install ffmpeg and ffprobe and make sure they are on PATH."
+    };
+    let outcome = if info.ffmpeg_path.is_none() {
+        Outcome::new(CheckStatus::Failed, info.error.clone().unwrap_or_default()).hint(install_hint)
+    } else if let Some(error) = info.error.clone().filter(|_| !info.ok) {
+        Outcome::new(CheckStatus::Degraded, error).hint(install_hint)
+    } else {
+        Outcome::new(
+            CheckStatus::Ok,
+            info.version.clone().unwrap_or_else(|| "ffmpeg".to_string()),
+        )
+    };
+    outcome.details(info)
+}
+
+fn backend_url() -> String {
+    let port = env::var("LAPAAS_DESKTOP_PORT")
+        .ok()
+        .filter(|port| !port.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_BACKEND_PORT.to_string());
+    format!("http://127.0.0.1:{}/health", port.trim())
+}
+
+fn check_backend() -> Outcome {
+    let url = backend_url();
+    let hint = "Restart the app; the backend server is started with it.";
+    let client = match Client::builder().timeout(BACKEND_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(error) => {
+            return Outcome::new(
+                CheckStatus::Failed,
+                format!("Failed creating HTTP client: {error}"),
+            )
+        }
+    };
+    let outcome = match client.get(&url).send() {
+        Ok(response) if response.status().is_success() => {
+            Outcome::new(CheckStatus::Ok, "Backend server is reachable")
+        }
+        Ok(response) => Outcome::new(
+            CheckStatus::Degraded,
+            format!("Backend server answered {}", response.status()),
+        )
+        .hint(hint),
+        Err(error) => Outcome::new(
+            CheckStatus::Failed,
+            format!("Backend server is not reachable: {error}"),
+        )
+        .hint(hint),
+    };
+    outcome.details(serde_json::json!({ "url": url }))
+}
+
+fn check_models() -> Outcome {
+    let report = crate::script_path("scripts/model_runtime_health.mjs").and_then(|script| {
+        let raw = crate::run_node_script(&script, &[])?;
+        serde_json::from_str::<Value>(&raw)
+            .map_err(|error| format!("Invalid model health JSON: {error}"))
+    });
+    let report = match report {
+        Ok(report) => report,
+        Err(error) => {
+            return Outcome::new(CheckStatus::Failed, error)
+                .hint("The model check runs on Node.js; see the node check.")
+        }
+    };
+    let count = |status: &str| report["summary"][status].as_u64().unwrap_or(0);
+    let (healthy, degraded) = (count("healthy"), count("degraded"));
+    let first_recommendation = report["recommendations"]
+        .as_array()
+        .and_then(|items| items.first())
+        .and_then(|item| item.as_str().map(str::to_string));
+    let outcome = if healthy > 0 {
+        Outcome::new(
+            CheckStatus::Ok,
+            format!("{healthy} model runtime(s) healthy"),
+        )
+    } else if degraded > 0 {
+        let outcome = Outcome::new(
+            CheckStatus::Degraded,
+            format!("{degraded} model runtime(s) degraded, none healthy"),
+        );
+        match first_recommendation {
+            Some(hint) => outcome.hint(hint),
+            None => outcome,
+        }
+    } else {
+        Outcome::new(CheckStatus::Failed, "No model runtime is available").hint(
+            first_recommendation
+                .unwrap_or_else(|| "Install Ollama or whisper.cpp from the models page.".into()),
+        )
+    };
+    outcome.details(report)
+}
+
+fn check_disk() -> Outcome {
+    let data_dir = match workspace_root() {
+        Ok(root) => root.join("desktop").join("data"),
+        Err(error) => return Outcome::new(CheckStatus::Failed, error),
+    };
+    let Some(disk) = hardware::disk_info(&data_dir) else {
+        return Outcome::new(CheckStatus::Degraded, "Could not read free disk space.");
+    };
+    let free = format!("{:.1} GiB free", disk.available_bytes as f64 / GIB as f64);
+    let hint = "Free up space, or delete old renders and unused models.";
+    let outcome = if disk.available_bytes < DISK_FAILED_BYTES {
+        Outcome::new(CheckStatus::Failed, format!("Only {free}")).hint(hint)
+    } else if disk.available_bytes < DISK_DEGRADED_BYTES {
+        Outcome::new(CheckStatus::Degraded, format!("Low on space: {free}")).hint(hint)
+    } else {
+        Outcome::new(CheckStatus::Ok, free)
+    };
+    outcome.details(disk)
+}
+
+fn check_store() -> Outcome {
+    let report = match integrity::integrity_check() {
+        Ok(report) => report,
+        Err(error) => return Outcome::new(CheckStatus::Failed, error),
+    };
+    let outcome = if report.ok {
+        Outcome::new(
+            CheckStatus::Ok,
+            format!("{} project(s), no issues", report.indexed_projects),
+        )
+    } else {
+        let first = &report.issues[0];
+        let outcome = Outcome::new(
+            CheckStatus::Degraded,
+            format!("{} issue(s): {}", report.issues.len(), first.message),
+        );
+        match &first.fix {
+            Some(fix) => outcome.hint(format!("{} ({})", fix.label, fix.command)),
+            None => outcome.hint("Run repair_store, or restore a backup."),
+        }
+    };
+    outcome.details(report)
+}
+
+type Check = fn() -> Outcome;
+
+const CHECKS: [(&str, Check); 6] = [
+    ("node", check_node),
+    ("ffmpeg", check_ffmpeg),
+    ("backend", check_backend),
+    ("models", check_models),
+    ("disk", check_disk),
+    ("store", check_store),
+];
+
+/// Runs every check on its own thread; the slowest (model runtimes) bounds
+/// the total.
+pub fn system_health_blocking() -> SystemHealth {
+    let checks = thread::scope(|scope| {
+        let handles = CHECKS
+            .iter()
+            .map(|(name, check)| {
+                let handle = scope.spawn(move || {
+                    let started = Instant::now();
+                    let outcome = check();
+                    (outcome, started.elapsed().as_millis() as u64)
+                });
+                (*name, handle)
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|(name, handle)| {
+                let (outcome, duration_ms) = handle
+                    .join()
+                    .unwrap_or_else(|_| (Outcome::new(CheckStatus::Failed, "Check panicked"), 0));
+                HealthCheck {
+                    name,
+                    status: outcome.status,
+                    message: outcome.message,
+                    hint: outcome.hint,
+                    duration_ms,
+                    details: outcome.details,
+                }
+            })
+            .collect::<Vec<_>>()
+    });
+    let status = checks
+        .iter()
+        .map(|check| check.status)
+        .max()
+        .unwrap_or(CheckStatus::Ok);
+    for check in checks
+        .iter()
+        .filter(|check| check.status != CheckStatus::Ok)
+    {
+        tracing::warn!(
+            "Health check {} {:?}: {}",
+            check.name,
+            check.status,
+            check.message
+        );
+    }
+    SystemHealth {
+        ok: status == CheckStatus::Ok,
+        status,
+        checked_at: now_iso(),
+        checks,
+    }
+}
+
+#[tauri::command]
+pub async fn system_health() -> Result<SystemHealth, String> {
+    tauri::async_runtime::spawn_blocking(system_health_blocking)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))
+}
@@ -0,0 +1,527 @@
+use serde::{Deserialize, Serialize};
+
+use crate::media::resolve_source_path;
+use crate::planner::{self, PlannerRun};
+use crate::project_status::ProjectStatus;
+use crate::timeline::{build_rough_cut_timeline, RoughCutOptions, TimeRange};
+use crate::transcript::{read_transcript, write_transcript, Transcript};
+use crate::{
+    generate_project_id, mark_timeline_written, now_iso, publish_project_status, read_projects,
+    read_timeline, sponsors, write_projects, write_timeline, Project,
+};
+
+const TARGET_DURATION_RANGE_S: std::ops::RangeInclusive<f64> = 10.0..=180.0;
+const COUNT_RANGE: std::ops::RangeInclusive<u32> = 1..=10;
+/// Silence inside a highlight longer than this is cut down to it.
+const MAX_PAUSE_US: u64 = 600_000;
+/// A pause this long ends a candidate: the excerpt has lost its thread.
+const DEAD_AIR_US: u64 = 3_000_000;
+/// Candidates sent to the planner per highlight asked for.
+const PLANNER_CANDIDATES_PER_PICK: usize = 3;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractHighlightsRequest {
+    project_id: String,
+    target_duration_s: Option<f64>,
+    count: Option<u32>,
+    /// Rank the best heuristic candidates with the planner model as well.
+    use_planner: Option<bool>,
+    model: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Highlight {
+    /// The child project holding this highlight's timeline.
+    pub project_id: String,
+    pub title: String,
+    pub source_start_us: u64,
+    pub source_end_us: u64,
+    pub score: f64,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedHighlights {
+    pub parent_project_id: String,
+    pub highlights: Vec<Highlight>,
+    /// Set when the planner scored the candidates.
+    pub planner: Option<PlannerRun>,
+    /// Export profile each highlight is meant to be rendered with.
+    pub export_profile: String,
+}
+
+/// A sentence, or a whole segment when it has no word timings.
+#[derive(Debug, Clone)]
+struct Unit {
+    start_us: u64,
+    end_us: u64,
+    text: String,
+    words: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub start_us: u64,
+    pub end_us: u64,
+    pub text: String,
+    pub score: f64,
+    pub reasons: Vec<String>,
+    /// Silences inside the candidate longer than [`MAX_PAUSE_US`].
+    pauses: Vec<TimeRange>,
+}
+
+fn ends_sentence(text: &str) -> bool {
+    text.trim_end_matches(['"', '\'', ')', '”', '’'])
+        .ends_with(['.', '?', '!', '…'])
+}
+
+fn sentence_units(transcript: &Transcript) -> Vec<Unit> {
+    let mut units = Vec::new();
+    for segment in &transcript.segments {
+        if segment.words.is_empty() {
+            if !segment.text.trim().is_empty() {
+                units.push(Unit {
+                    start_us: segment.start_us,
+                    end_us: segment.end_us,
+                    text: segment.text.trim().to_string(),
+                    words: segment.text.split_whitespace().count(),
+                });
+            }
+            continue;
+        }
+        let mut current: Option<Unit> = None;
+        for word in segment
+            .words
+            .iter()
+            .filter(|word| !word.text.trim().is_empty())
+        {
+            let unit = current.get_or_insert_with(|| Unit {
+                start_us: word.start_us,
+                end_us: word.end_us,
+                text: String::new(),
+                words: 0,
+            });
+            if !unit.text.is_empty() {
+                unit.text.push(' ');
+            }
+            unit.text.push_str(word.text.trim());
+            unit.end_us = unit.end_us.max(word.end_us);
+            unit.words += 1;
+            if ends_sentence(&word.text) {
+                units.extend(current.take());
+            }
+        }
+        units.extend(current);
+    }
+    units.sort_by_key(|unit| unit.start_us);
+    units
+}
+
+/// Scores an excerpt on pace, dead air, an opening question and a clean
+/// ending. Returns the score (0-1) and what drove it.
+fn score_units(units: &[Unit]) -> (f64, Vec<String>) {
+    let (Some(first), Some(last)) = (units.first(), units.last()) else {
+        return (0.0, Vec::new());
+    };
+    let span_us = (last.end_us - first.start_us).max(1);
+    let spoken_us = units
+        .iter()
+        .map(|unit| unit.end_us - unit.start_us)
+        .sum::<u64>()
+        .min(span_us);
+    let words = units.iter().map(|unit| unit.words).sum::<usize>();
+    let words_per_s = words as f64 / (span_us as f64 / 1_000_000.0);
+
+    let mut reasons = Vec::new();
+    let pace = (words_per_s / 3.0).min(1.0);
+    if words_per_s >= 2.5 {
+        reasons.push("fast-paced".to_string());
+    }
+    let density = spoken_us as f64 / span_us as f64;
+    if density < 0.7 {
+        reasons.push("long pauses".to_string());
+    }
+    let mut score = 0.5 * pace + 0.3 * density;
+    if first.text.ends_with('?') {
+        score += 0.15;
+        reasons.push("opens with a question".to_string());
+    }
+    if ends_sentence(&last.text) {
+        score += 0.05;
+    } else {
+        reasons.push("ends mid-sentence".to_string());
+    }
+    (score.clamp(0.0, 1.0), reasons)
+}
+
+/// Sentence-aligned excerpts within 25% of `target_us`, best first. Each
+/// sentence starts at most one candidate, the one closest to the target.
+pub fn highlight_candidates(transcript: &Transcript, target_us: u64) -> Vec<Candidate> {
+    let units = sentence_units(transcript);
+    let (min_us, max_us) = (target_us * 3 / 4, target_us * 5 / 4);
+    let mut candidates = Vec::new();
+    for start in 0..units.len() {
+        let mut best: Option<usize> = None;
+        for end in start..units.len() {
+            if end > start
+                && units[end].start_us.saturating_sub(units[end - 1].end_us) > DEAD_AIR_US
+            {
+                break;
+            }
+            let span = units[end].end_us - units[start].start_us;
+            if span > max_us {
+                break;
+            }
+            let closer = best.map_or(true, |best| {
+                span.abs_diff(target_us)
+                    < (units[best].end_us - units[start].start_us).abs_diff(target_us)
+            });
+            if span >= min_us && closer {
+                best = Some(end);
+            }
+        }
+        let Some(end) = best else {
+            continue;
+        };
+        let excerpt = &units[start..=end];
+        let (score, reasons) = score_units(excerpt);
+        let pauses = excerpt
+            .windows(2)
+            .filter(|pair| pair[1].start_us.saturating_sub(pair[0].end_us) > MAX_PAUSE_US)
+            .map(|pair| TimeRange {
+                start_us: pair[0].end_us + MAX_PAUSE_US / 2,
+                end_us: pair[1].start_us - MAX_PAUSE_US / 2,
+            })
+            .collect();
+        candidates.push(Candidate {
+            start_us: units[start].start_us,
+            end_us: units[end].end_us,
+            text: excerpt
+                .iter()
+                .map(|unit| unit.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            score,
+            reasons,
+            pauses,
+        });
+    }
+    candidates.sort_by(|left, right| right.score.total_cmp(&left.score));
+    candidates
+}
+
+/// The best `count` candidates that don't overlap, kept in score order.
+pub fn pick_highlights(candidates: Vec<Candidate>, count: usize) -> Vec<Candidate> {
+    let mut picked: Vec<Candidate> = Vec::new();
+    for candidate in candidates {
+        if picked.len() >= count {
+            break;
+        }
+        let overlaps = picked
+            .iter()
+            .any(|other| candidate.start_us < other.end_us && candidate.end_us > other.start_us);
+        if !overlaps {
+            picked.push(candidate);
+        }
+    }
+    picked
+}
+
+fn default_title(text: &str) -> String {
+    let words = text.split_whitespace().collect::<Vec<_>>();
+    if words.len() <= 8 {
+        return words.join(" ");
+    }
+    format!("{}…", words[..8].join(" "))
+}
+
+/// Creates the child project for one highlight: its own transcript excerpt
+/// and a rough cut of the parent's source trimmed to the highlight.
+fn create_highlight_project(
+    parent: &Project,
+    transcript: &Transcript,
+    source_path: &str,
+    source_duration_us: u64,
+    candidate: &Candidate,
+    title: &str,
+    index: usize,
+) -> Result<Project, String> {
+    let now = now_iso();
+    let mut settings = parent.settings.clone();
+    settings.aspect_ratio = "9:16".to_string();
+    let project = Project {
+        // Ids are time-based; the index keeps a batch apart.
+        id: format!("{}-{index}", generate_project_id()),
+        name: format!("{} – {title}", parent.name),
+        settings,
+        status: ProjectStatus::ProjectCreated.as_str().to_string(),
+        parent_id: Some(parent.id.clone()),
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    let mut projects = read_projects()?;
+    projects.push(project.clone());
+    write_projects(&projects)?;
+    publish_project_status(&project, None);
+
+    let mut excerpt = transcript.clone();
+    excerpt.source_ref = Some(source_path.to_string());
+    excerpt.segments.retain(|segment| {
+        segment.start_us < candidate.end_us && segment.end_us > candidate.start_us
+    });
+    write_transcript(&project.id, excerpt)?;
+
+    let mut remove_ranges = vec![
+        TimeRange {
+            start_us: 0,
+            end_us: candidate.start_us,
+        },
+        TimeRange {
+            start_us: candidate.end_us,
+            end_us: source_duration_us,
+        },
+    ];
+    remove_ranges.extend(candidate.pauses.iter().cloned());
+    let mut timeline = build_rough_cut_timeline(
+        project.id.clone(),
+        source_duration_us,
+        project.settings.fps,
+        source_path.to_string(),
+        remove_ranges,
+        &RoughCutOptions::default(),
+        &sponsors::protected_ranges(&project.id),
+    );
+    timeline.meta["highlight"] = serde_json::json!({
+        "parentProjectId": parent.id,
+        "title": title,
+        "sourceStartUs": candidate.start_us,
+        "sourceEndUs": candidate.end_us,
+        "score": candidate.score,
+    });
+    write_timeline(&timeline)?;
+    mark_timeline_written(&project.id, ProjectStatus::RoughCutReady)?;
+    Ok(project)
+}
+
+fn extract_highlights_blocking(
+    request: ExtractHighlightsRequest,
+) -> Result<ExtractedHighlights, String> {
+    let target_s = request.target_duration_s.unwrap_or(45.0);
+    if !TARGET_DURATION_RANGE_S.contains(&target_s) {
+        return Err(format!(
+            "Target duration must be {}-{}s, got {target_s}.",
+            TARGET_DURATION_RANGE_S.start(),
+            TARGET_DURATION_RANGE_S.end()
+        ));
+    }
+    let count = request.count.unwrap_or(3);
+    if !COUNT_RANGE.contains(&count) {
+        return Err(format!(
+            "Highlight count must be {}-{}, got {count}.",
+            COUNT_RANGE.start(),
+            COUNT_RANGE.end()
+        ));
+    }
+    let count = count as usize;
+
+    let parent = read_projects()?
+        .into_iter()
+        .find(|project| project.id == request.project_id)
+        .ok_or_else(|| "Project not found.".to_string())?;
+    let transcript = read_transcript(&request.project_id)?;
+    let candidates = highlight_candidates(&transcript, (target_s * 1_000_000.0) as u64);
+    if candidates.is_empty() {
+        return Err(format!(
+            "The transcript has no stretch of speech around {target_s}s to extract."
+        ));
+    }
+
+    let mut planner_run = None;
+    let mut titles = Vec::new();
+    let mut picked = if request.use_planner.unwrap_or(false) {
+        let mut shortlist = pick_highlights(candidates, count * PLANNER_CANDIDATES_PER_PICK);
+        let excerpts = shortlist
+            .iter()
+            .enumerate()
+            .map(|(id, candidate)| (id, candidate.text.clone()))
+            .collect::<Vec<_>>();
+        let (scores, run) = planner::score_highlights(request.model, &excerpts)?;
+        for (id, candidate) in shortlist.iter_mut().enumerate() {
+            match scores.iter().find(|score| score.id == id) {
+                Some(score) => {
+                    candidate.score = 0.4 * candidate.score + 0.6 * score.score;
+                    candidate
+                        .reasons
+                        .push(format!("planner score {:.2}", score.score));
+                    if !score.title.trim().is_empty() {
+                        titles.push((candidate.start_us, score.title.trim().to_string()));
+                    }
+                }
+                None => candidate.score *= 0.4,
+            }
+        }
+        shortlist.sort_by(|left, right| right.score.total_cmp(&left.score));
+        planner_run = Some(run);
+        shortlist
+    } else {
+        pick_highlights(candidates, count)
+    };
+    picked.truncate(count);
+
+    let source_ref = transcript.source_ref.clone();
+    let source_path = resolve_source_path(&request.project_id, source_ref.as_deref())?
+        .to_string_lossy()
+        .to_string();
+    let transcript_end_us = transcript
+        .segments
+        .iter()
+        .map(|segment| segment.end_us)
+        .max()
+        .unwrap_or(0);
+    let source_duration_us = read_timeline(&request.project_id)
+        .ok()
+        .and_then(|timeline| {
+            timeline
+                .clips
+                .iter()
+                .filter(|clip| clip.clip_type == "source_clip")
+                .filter(|clip| {
+                    source_ref
+                        .as_ref()
+                        .map_or(true, |source| &clip.source_ref == source)
+                })
+                .map(|clip| clip.source_end_us)
+                .max()
+        })
+        .unwrap_or(0)
+        .max(transcript_end_us);
+
+    let mut highlights = Vec::new();
+    for (index, candidate) in picked.iter().enumerate() {
+        let title = titles
+            .iter()
+            .find(|(start_us, _)| *start_us == candidate.start_us)
+            .map_or_else(
+                || default_title(&candidate.text),
+                |(_, title)| title.clone(),
+            );
+        let project = create_highlight_project(
+            &parent,
+            &transcript,
+            &source_path,
+            source_duration_us,
+            candidate,
+            &title,
+            index + 1,
+        )?;
+        highlights.push(Highlight {
+            project_id: project.id,
+            title,
+            source_start_us: candidate.start_us,
+            source_end_us: candidate.end_us,
+            score: candidate.score,
+            reasons: candidate.reasons.clone(),
+        });
+    }
+
+    Ok(ExtractedHighlights {
+        parent_project_id: request.project_id,
+        highlights,
+        planner: planner_run,
+        export_profile: "vertical".to_string(),
+    })
+}
+
+/// Finds the best stand-alone excerpts of the transcript and makes each a
+/// child project with its own timeline, ready to render with the vertical
+/// export profile. Highlights are cut from the source, not the parent's
+/// edited timeline.
+#[tauri::command]
+pub async fn extract_highlights(
+    request: ExtractHighlightsRequest,
+) -> Result<ExtractedHighlights, String> {
+    tauri::async_runtime::spawn_blocking(move || extract_highlights_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::{TranscriptSegment, TranscriptWord};
+    use serde_json::Map;
+
+    /// One segment per `(start_s, text)`, words 0.3s apart.
+    fn transcript(segments: &[(u64, &str)]) -> Transcript {
+        let segments = segments
+            .iter()
+            .enumerate()
+            .map(|(index, (start_s, text))| {
+                let start_us = start_s * 1_000_000;
+                let words = text
+                    .split_whitespace()
+                    .enumerate()
+                    .map(|(word_index, word)| TranscriptWord {
+                        id: format!("w-{index}-{word_index}"),
+                        text: word.to_string(),
+                        normalized: String::new(),
+                        start_us: start_us + word_index as u64 * 300_000,
+                        end_us: start_us + word_index as u64 * 300_000 + 250_000,
+                        confidence: None,
+                        speaker: None,
+                        extra: Map::new(),
+                    })
+                    .collect::<Vec<TranscriptWord>>();
+                TranscriptSegment {
+                    id: format!("s-{index}"),
+                    start_us,
+                    end_us: words.last().map_or(start_us, |word| word.end_us),
+                    text: text.to_string(),
+                    confidence: None,
+                    speaker: None,
+                    words,
+                    extra: Map::new(),
+                }
+            })
+            .collect();
+        Transcript {
+            project_id: String::new(),
+            language: None,
+            source_ref: None,
+            segments,
+            speaker_labels: Default::default(),
+            word_count: 0,
+            updated_at: String::new(),
+            extra: Map::new(),
+        }
+    }
+
+    #[test]
+    fn highlights_prefer_hooks_and_do_not_overlap() {
+        let sentence = "this is one plain sentence that goes on for a while.";
+        let transcript = transcript(&[
+            (0, sentence),
+            (4, sentence),
+            (8, sentence),
+            (30, "why does nobody talk about this?"),
+            (32, sentence),
+            (36, sentence),
+        ]);
+        let candidates = highlight_candidates(&transcript, 10_000_000);
+        assert!(candidates
+            .iter()
+            .all(|candidate| (7_500_000..=12_500_000)
+                .contains(&(candidate.end_us - candidate.start_us))));
+        assert_eq!(candidates[0].start_us, 30_000_000);
+        assert!(candidates[0]
+            .reasons
+            .contains(&"opens with a question".to_string()));
+
+        let picked = pick_highlights(candidates, 3);
+        assert_eq!(picked.len(), 2);
+        assert!(picked[0].end_us <= picked[1].start_us || picked[1].end_us <= picked[0].start_us);
+    }
+}
@@ -0,0 +1,128 @@
+use std::process::Command;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::settings::{self, HookCommand, HookSettings};
+use crate::{path_safety, shutdown};
+
+const MAX_TIMEOUT_SECS: u64 = 3600;
+/// Key under which non-fatal hook failures are added to a job's result.
+const WARNINGS_KEY: &str = "hookWarnings";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookPoint {
+    PostIngest,
+    PreRender,
+    PostRender,
+}
+
+impl HookPoint {
+    fn name(self) -> &'static str {
+        match self {
+            Self::PostIngest => "post-ingest",
+            Self::PreRender => "pre-render",
+            Self::PostRender => "post-render",
+        }
+    }
+
+    fn commands(self, hooks: &HookSettings) -> &[HookCommand] {
+        match self {
+            Self::PostIngest => &hooks.post_ingest,
+            Self::PreRender => &hooks.pre_render,
+            Self::PostRender => &hooks.post_render,
+        }
+    }
+}
+
+fn run_hook(
+    point: HookPoint,
+    hook: &HookCommand,
+    project_id: &str,
+    input: &[u8],
+) -> Result<(), String> {
+    let mut command = Command::new(hook.command.trim());
+    command
+        .args(&hook.args)
+        .env("LAPAAS_HOOK", point.name())
+        .env("LAPAAS_PROJECT_ID", project_id);
+    if let Some(dir) = path_safety::project_dir(project_id)
+        .ok()
+        .filter(|dir| dir.is_dir())
+    {
+        command.current_dir(dir);
+    }
+    let timeout = Duration::from_secs(hook.timeout_secs.clamp(1, MAX_TIMEOUT_SECS));
+    let output = shutdown::run_tracked_with_timeout(&mut command, input.to_vec(), timeout)
+        .map_err(|error| format!("failed to run: {error}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(format!("exited with {}: {}", output.status, stderr.trim()))
+}
+
+/// Runs the user's commands for `point` in order, each getting `payload`
+/// plus `hook`, `projectId` and `projectDir` as JSON on stdin. A failing
+/// hook marked fatal fails the job; other failures are returned so the
+/// caller can report them alongside its result.
+pub fn run(point: HookPoint, project_id: &str, payload: Value) -> Result<Vec<String>, String> {
+    let hooks = match settings::read_app_settings() {
+        Ok(settings) => settings.hooks,
+        Err(error) => {
+            tracing::warn!("Skipping {} hooks: {error}", point.name());
+            return Ok(Vec::new());
+        }
+    };
+    let commands = point
+        .commands(&hooks)
+        .iter()
+        .filter(|hook| !hook.command.trim().is_empty())
+        .collect::<Vec<_>>();
+    if commands.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut event = json!({
+        "hook": point,
+        "projectId": project_id,
+        "projectDir": path_safety::project_dir(project_id)?.to_string_lossy(),
+    });
+    if let (Some(event), Value::Object(payload)) = (event.as_object_mut(), payload) {
+        event.extend(payload);
+    }
+    let input = serde_json::to_vec(&event)
+        .map_err(|error| format!("Hook payload serialize error: {error}"))?;
+
+    let mut warnings = Vec::new();
+    for hook in commands {
+        tracing::info!("Running {} hook: {}", point.name(), hook.command);
+        if let Err(error) = run_hook(point, hook, project_id, &input) {
+            let message = format!("{} hook `{}` {error}", point.name(), hook.command.trim());
+            if hook.fatal {
+                return Err(message);
+            }
+            tracing::warn!("{message}");
+            warnings.push(message);
+        }
+    }
+    Ok(warnings)
+}
+
+/// Adds non-fatal hook failures to a job's JSON result.
+pub fn attach_warnings(result: &mut Value, warnings: Vec<String>) {
+    if warnings.is_empty() {
+        return;
+    }
+    let Some(object) = result.as_object_mut() else {
+        return;
+    };
+    match object.get_mut(WARNINGS_KEY).and_then(Value::as_array_mut) {
+        Some(existing) => existing.extend(warnings.into_iter().map(Value::from)),
+        None => {
+            object.insert(WARNINGS_KEY.to_string(), Value::from(warnings));
+        }
+    }
+}
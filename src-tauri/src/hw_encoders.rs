@@ -0,0 +1,159 @@
+use std::process::Command;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ffmpeg::{ffmpeg_binary, listed_names, run_listing};
+
+/// Software encoder every render can fall back to.
+pub const SOFTWARE_ENCODER: &str = "libx264";
+
+/// Hardware encoders the render pipeline has quality presets for
+/// (`HW_ENCODER_ARGS` in scripts/lib/metal_accel.mjs).
+const KNOWN_ENCODERS: &[(&str, &str, &str)] = &[
+    ("h264_videotoolbox", "VideoToolbox", "h264"),
+    ("hevc_videotoolbox", "VideoToolbox", "hevc"),
+    ("h264_nvenc", "NVENC", "h264"),
+    ("hevc_nvenc", "NVENC", "hevc"),
+    ("h264_qsv", "QuickSync", "h264"),
+    ("hevc_qsv", "QuickSync", "hevc"),
+    ("h264_amf", "AMF", "h264"),
+    ("hevc_amf", "AMF", "hevc"),
+];
+
+static DETECTED: Mutex<Option<Vec<HwEncoder>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HwEncoder {
+    pub name: String,
+    pub vendor: String,
+    pub codec: String,
+    /// Listed by `ffmpeg -encoders`.
+    pub listed: bool,
+    /// A short test encode succeeded, so the driver and device are present.
+    pub working: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListHwEncodersRequest {
+    /// Re-run detection instead of returning the cached result.
+    refresh: Option<bool>,
+}
+
+/// Encodes three black frames to the null muxer. Builds often list encoders
+/// whose driver or device is missing, so being listed isn't enough.
+fn test_encode(name: &str) -> Result<(), String> {
+    let output = Command::new(ffmpeg_binary())
+        .args([
+            "-hide_banner",
+            "-loglevel",
+            "error",
+            "-f",
+            "lavfi",
+            "-i",
+            "color=c=black:s=320x240:r=30",
+            "-frames:v",
+            "3",
+            "-pix_fmt",
+            "yuv420p",
+            "-c:v",
+            name,
+            "-f",
+            "null",
+            "-",
+        ])
+        .output()
+        .map_err(|error| format!("Failed to execute ffmpeg: {error}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(stderr.trim().chars().take(300).collect())
+}
+
+fn detect() -> Result<Vec<HwEncoder>, String> {
+    let listing = run_listing(&ffmpeg_binary(), "-encoders")?;
+    let available = listed_names(&listing);
+    Ok(KNOWN_ENCODERS
+        .iter()
+        .map(|(name, vendor, codec)| {
+            let listed = available.iter().any(|available| available == name);
+            let result = if listed {
+                test_encode(name)
+            } else {
+                Err("Not included in this ffmpeg build.".to_string())
+            };
+            HwEncoder {
+                name: name.to_string(),
+                vendor: vendor.to_string(),
+                codec: codec.to_string(),
+                listed,
+                working: result.is_ok(),
+                error: result.err(),
+            }
+        })
+        .collect())
+}
+
+/// Detected encoders, probing ffmpeg on first use or when `refresh` is set.
+pub fn hw_encoders(refresh: bool) -> Result<Vec<HwEncoder>, String> {
+    if !refresh {
+        if let Some(cached) = DETECTED.lock().ok().and_then(|guard| guard.clone()) {
+            return Ok(cached);
+        }
+    }
+    let detected = detect()?;
+    for encoder in detected.iter().filter(|encoder| encoder.working) {
+        tracing::info!(
+            "Hardware encoder available: {} ({})",
+            encoder.name,
+            encoder.vendor
+        );
+    }
+    if let Ok(mut guard) = DETECTED.lock() {
+        *guard = Some(detected.clone());
+    }
+    Ok(detected)
+}
+
+/// Maps a render request's `encoder` to the name passed to the pipeline.
+/// `None` keeps the pipeline's automatic choice. A hardware encoder that
+/// isn't working here falls back to software rather than failing the render.
+pub fn resolve_encoder(requested: &str) -> Result<Option<String>, String> {
+    let requested = requested.trim();
+    match requested {
+        "" | "auto" => return Ok(None),
+        "software" | SOFTWARE_ENCODER => return Ok(Some(SOFTWARE_ENCODER.to_string())),
+        _ => {}
+    }
+    if !KNOWN_ENCODERS.iter().any(|(name, _, _)| *name == requested) {
+        return Err(format!(
+            "Unknown encoder: {requested}. Use auto, software or one of list_hw_encoders."
+        ));
+    }
+    let working = hw_encoders(false)
+        .map(|encoders| {
+            encoders
+                .iter()
+                .any(|encoder| encoder.name == requested && encoder.working)
+        })
+        .unwrap_or(false);
+    if working {
+        return Ok(Some(requested.to_string()));
+    }
+    tracing::warn!("Encoder {requested} is not available; rendering with {SOFTWARE_ENCODER}");
+    Ok(Some(SOFTWARE_ENCODER.to_string()))
+}
+
+#[tauri::command]
+pub async fn list_hw_encoders(
+    request: Option<ListHwEncodersRequest>,
+) -> Result<Vec<HwEncoder>, String> {
+    let refresh = request.unwrap_or_default().refresh.unwrap_or(false);
+    tauri::async_runtime::spawn_blocking(move || hw_encoders(refresh))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
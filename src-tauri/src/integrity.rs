@@ -0,0 +1,366 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    backups, media_pool, now_iso, path_safety, read_projects, read_timeline, write_projects,
+    Project, ProjectSettings,
+};
+
+/// Statuses reached only after a timeline has been written.
+const TIMELINE_STATUSES: &[&str] = &[
+    "ROUGH_CUT_READY",
+    "ENRICHED_TIMELINE_READY",
+    "RENDER_IN_PROGRESS",
+    "RENDER_DONE",
+    "RENDER_FAILED",
+    "AGENTIC_EDIT_DONE",
+];
+
+/// Files that mark a data directory as holding a project.
+const PROJECT_MARKERS: &[&str] = &["timeline.json", "transcript.json", "media", "backups"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IntegrityIssueKind {
+    /// `projects.json` doesn't parse; nothing else can be checked.
+    CorruptIndex,
+    /// A project directory with no entry in `projects.json`.
+    OrphanDirectory,
+    /// An index entry whose data directory is gone.
+    MissingDirectory,
+    /// The project has been edited but its `timeline.json` is gone.
+    MissingTimeline,
+    CorruptTimeline,
+}
+
+/// A command the UI can `invoke` as-is to fix an issue.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixCommand {
+    pub command: String,
+    pub args: Value,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityIssue {
+    pub kind: IntegrityIssueKind,
+    pub project_id: Option<String>,
+    pub message: String,
+    pub fix: Option<FixCommand>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub ok: bool,
+    pub checked_at: String,
+    pub indexed_projects: usize,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdoptOrphanRequest {
+    project_id: String,
+    /// Defaults to the name in the newest backup, else the directory name.
+    name: Option<String>,
+    settings: Option<ProjectSettings>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneProjectsRequest {
+    project_ids: Vec<String>,
+}
+
+fn data_dir() -> Result<PathBuf, String> {
+    path_safety::data_dir()
+}
+
+fn looks_like_project(dir: &Path) -> bool {
+    PROJECT_MARKERS
+        .iter()
+        .any(|marker| dir.join(marker).exists())
+}
+
+fn fix(command: &str, args: Value, label: &str) -> Option<FixCommand> {
+    Some(FixCommand {
+        command: command.to_string(),
+        args: serde_json::json!({ "request": args }),
+        label: label.to_string(),
+    })
+}
+
+fn check_project_timeline(project: &Project, dir: &Path, issues: &mut Vec<IntegrityIssue>) {
+    if dir.join("timeline.json").is_file() {
+        if let Err(error) = read_timeline(&project.id) {
+            issues.push(IntegrityIssue {
+                kind: IntegrityIssueKind::CorruptTimeline,
+                project_id: Some(project.id.clone()),
+                message: error,
+                fix: fix(
+                    "repair_store",
+                    serde_json::json!({ "projectId": project.id }),
+                    "Repair timeline",
+                ),
+            });
+        }
+        return;
+    }
+    if !TIMELINE_STATUSES.contains(&project.status.as_str()) {
+        return;
+    }
+    let backup = backups::list_backups_for(&project.id)
+        .ok()
+        .and_then(|backups| {
+            backups
+                .into_iter()
+                .find(|backup| backup.timeline_version.is_some())
+        });
+    issues.push(IntegrityIssue {
+        kind: IntegrityIssueKind::MissingTimeline,
+        project_id: Some(project.id.clone()),
+        message: format!(
+            "Project \"{}\" is {} but has no timeline.json.",
+            project.name, project.status
+        ),
+        fix: backup.and_then(|backup| {
+            fix(
+                "restore_backup",
+                serde_json::json!({ "projectId": project.id, "backupId": backup.id }),
+                "Restore latest backup",
+            )
+        }),
+    });
+}
+
+pub fn integrity_check() -> Result<IntegrityReport, String> {
+    let data_dir = data_dir()?;
+    let mut issues = Vec::new();
+    let projects = match read_projects() {
+        Ok(projects) => projects,
+        Err(error) => {
+            issues.push(IntegrityIssue {
+                kind: IntegrityIssueKind::CorruptIndex,
+                project_id: None,
+                message: error,
+                fix: fix(
+                    "repair_store",
+                    serde_json::json!({}),
+                    "Repair project index",
+                ),
+            });
+            return Ok(IntegrityReport {
+                ok: false,
+                checked_at: now_iso(),
+                indexed_projects: 0,
+                issues,
+            });
+        }
+    };
+
+    let indexed = projects
+        .iter()
+        .map(|project| project.id.as_str())
+        .collect::<HashSet<_>>();
+    for project in &projects {
+        let dir = data_dir.join(&project.id);
+        if !dir.is_dir() {
+            // A project that was created but never got any data is fine.
+            if project.status == "PROJECT_CREATED" || project.status == "SETTINGS_SAVED" {
+                continue;
+            }
+            issues.push(IntegrityIssue {
+                kind: IntegrityIssueKind::MissingDirectory,
+                project_id: Some(project.id.clone()),
+                message: format!(
+                    "Project \"{}\" has no data directory at {}.",
+                    project.name,
+                    dir.display()
+                ),
+                fix: fix(
+                    "prune_project_entries",
+                    serde_json::json!({ "projectIds": [project.id] }),
+                    "Remove from project list",
+                ),
+            });
+            continue;
+        }
+        check_project_timeline(project, &dir, &mut issues);
+    }
+
+    if data_dir.is_dir() {
+        let entries =
+            fs::read_dir(&data_dir).map_err(|error| format!("Failed reading data dir: {error}"))?;
+        let mut orphans = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir() && looks_like_project(path))
+            .filter_map(|path| {
+                let name = path.file_name()?.to_string_lossy().to_string();
+                (!indexed.contains(name.as_str())).then_some(name)
+            })
+            .collect::<Vec<_>>();
+        orphans.sort();
+        for project_id in orphans {
+            issues.push(IntegrityIssue {
+                kind: IntegrityIssueKind::OrphanDirectory,
+                message: format!("Directory {project_id} holds project data but isn't listed."),
+                fix: fix(
+                    "adopt_orphan_project",
+                    serde_json::json!({ "projectId": project_id }),
+                    "Add back to project list",
+                ),
+                project_id: Some(project_id),
+            });
+        }
+    }
+
+    Ok(IntegrityReport {
+        ok: issues.is_empty(),
+        checked_at: now_iso(),
+        indexed_projects: projects.len(),
+        issues,
+    })
+}
+
+/// Logs the integrity report at launch so problems show up before the UI
+/// hits them. The UI fetches the same report with `startup_integrity_check`.
+pub fn log_startup_check() {
+    match integrity_check() {
+        Ok(report) if report.ok => {
+            tracing::info!("Store integrity OK ({} projects)", report.indexed_projects);
+        }
+        Ok(report) => {
+            tracing::warn!("Store integrity: {} issue(s)", report.issues.len());
+            for issue in &report.issues {
+                tracing::warn!("Store issue {:?}: {}", issue.kind, issue.message);
+            }
+        }
+        Err(error) => tracing::error!("Store integrity check failed: {error}"),
+    }
+}
+
+fn default_settings(fps: u32) -> ProjectSettings {
+    ProjectSettings {
+        aspect_ratio: "16:9".to_string(),
+        fps,
+        resolution: "1080p".to_string(),
+        language: "en".to_string(),
+        ai_mode: "hybrid".to_string(),
+        fallback_policy: None,
+        transcription_model: None,
+        cut_planner_model: None,
+        template_planner_model: None,
+        lut: None,
+    }
+}
+
+/// The project record saved in the newest backup, if any.
+fn backed_up_project(project_id: &str, dir: &Path) -> Option<Project> {
+    let backup = backups::list_backups_for(project_id)
+        .ok()?
+        .into_iter()
+        .next()?;
+    let raw = fs::read_to_string(dir.join("backups").join(backup.id).join("project.json")).ok()?;
+    serde_json::from_str::<Project>(&raw).ok()
+}
+
+fn adopt_orphan_blocking(request: AdoptOrphanRequest) -> Result<Project, String> {
+    let project_id = request.project_id.trim().to_string();
+    let dir = path_safety::project_dir(&project_id)?;
+    if !dir.is_dir() {
+        return Err(format!("Project directory not found: {project_id}"));
+    }
+    let mut projects = read_projects()?;
+    if projects.iter().any(|project| project.id == project_id) {
+        return Err(format!("Project {project_id} is already listed."));
+    }
+
+    let timeline = read_timeline(&project_id).ok();
+    let backed_up = backed_up_project(&project_id, &dir);
+    let now = now_iso();
+    let project = Project {
+        id: project_id.clone(),
+        name: request
+            .name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .or_else(|| backed_up.as_ref().map(|project| project.name.clone()))
+            .unwrap_or_else(|| project_id.clone()),
+        settings: request
+            .settings
+            .or_else(|| backed_up.as_ref().map(|project| project.settings.clone()))
+            .unwrap_or_else(|| {
+                default_settings(timeline.as_ref().map_or(30, |timeline| timeline.fps))
+            }),
+        status: if timeline.is_some() {
+            "ROUGH_CUT_READY".to_string()
+        } else {
+            "PROJECT_CREATED".to_string()
+        },
+        parent_id: backed_up
+            .as_ref()
+            .and_then(|project| project.parent_id.clone()),
+        created_at: backed_up
+            .map(|project| project.created_at)
+            .unwrap_or_else(|| now.clone()),
+        updated_at: now,
+    };
+    projects.push(project.clone());
+    write_projects(&projects)?;
+    Ok(project)
+}
+
+/// Drops index entries whose data directory is gone. Entries that still have
+/// data are refused so this can't hide a live project.
+fn prune_projects_blocking(request: PruneProjectsRequest) -> Result<Vec<Project>, String> {
+    let data_dir = data_dir()?;
+    if let Some(project_id) = request
+        .project_ids
+        .iter()
+        .find(|project_id| data_dir.join(project_id).is_dir())
+    {
+        return Err(format!(
+            "Project {project_id} still has a data directory; delete it instead."
+        ));
+    }
+    let projects = read_projects()?;
+    let (pruned, kept): (Vec<_>, Vec<_>) = projects
+        .into_iter()
+        .partition(|project| request.project_ids.contains(&project.id));
+    if !pruned.is_empty() {
+        write_projects(&kept)?;
+    }
+    for project in &pruned {
+        media_pool::release_project(&project.id)?;
+    }
+    Ok(pruned)
+}
+
+#[tauri::command]
+pub async fn startup_integrity_check() -> Result<IntegrityReport, String> {
+    tauri::async_runtime::spawn_blocking(integrity_check)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn adopt_orphan_project(request: AdoptOrphanRequest) -> Result<Project, String> {
+    tauri::async_runtime::spawn_blocking(move || adopt_orphan_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn prune_project_entries(request: PruneProjectsRequest) -> Result<Vec<Project>, String> {
+    tauri::async_runtime::spawn_blocking(move || prune_projects_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
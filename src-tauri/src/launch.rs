@@ -0,0 +1,235 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::media::{AUDIO_EXTENSIONS, VIDEO_EXTENSIONS};
+use crate::windows::{self, ProjectWindows, MAIN_WINDOW};
+use crate::{file_drop, path_safety, project_bundle, read_projects};
+
+/// Scheme registered for deep links, e.g.
+/// `lapaas-editor://project/proj-123` or `lapaas-editor://open?path=...`.
+pub const DEEP_LINK_SCHEME: &str = "lapaas-editor";
+
+/// Something the app was asked to open from outside: a file association,
+/// a file dragged onto the app icon, or a deep link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LaunchTarget {
+    Bundle(PathBuf),
+    Media(PathBuf),
+    Project(String),
+}
+
+/// Requests that arrive before the UI is listening are held until it calls
+/// `launch_ready`.
+#[derive(Default)]
+pub struct PendingLaunch(Mutex<(bool, Vec<LaunchTarget>)>);
+
+/// `%XX` escapes in a URL path or query value.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| value.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(if bytes[index] == b'+' {
+                    b' '
+                } else {
+                    bytes[index]
+                });
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn file_target(path: PathBuf) -> Option<LaunchTarget> {
+    if !path.is_file() {
+        return None;
+    }
+    if project_bundle::is_bundle(&path) {
+        return Some(LaunchTarget::Bundle(path));
+    }
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let extension = extension.as_str();
+    (VIDEO_EXTENSIONS.contains(&extension) || AUDIO_EXTENSIONS.contains(&extension))
+        .then_some(LaunchTarget::Media(path))
+}
+
+fn deep_link_target(rest: &str) -> Option<LaunchTarget> {
+    let (route, query) = rest.split_once('?').unwrap_or((rest, ""));
+    match route.trim_end_matches('/').split_once('/') {
+        Some(("project", project_id)) => {
+            let project_id = percent_decode(project_id);
+            path_safety::validate_project_id(&project_id).ok()?;
+            Some(LaunchTarget::Project(project_id))
+        }
+        None if route.trim_end_matches('/') == "open" => query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("path="))
+            .and_then(|path| file_target(PathBuf::from(percent_decode(path)))),
+        _ => None,
+    }
+}
+
+/// Reads one launch argument or opened URL. Relative paths are resolved
+/// against `cwd`; anything unrecognised is ignored.
+fn parse_target(arg: &str, cwd: &Path) -> Option<LaunchTarget> {
+    if let Some(rest) = arg
+        .strip_prefix(DEEP_LINK_SCHEME)
+        .and_then(|rest| rest.strip_prefix("://"))
+    {
+        return deep_link_target(rest);
+    }
+    if let Some(path) = arg.strip_prefix("file://") {
+        return file_target(PathBuf::from(percent_decode(path)));
+    }
+    if arg.starts_with('-') {
+        return None;
+    }
+    file_target(cwd.join(arg))
+}
+
+/// Targets in a command line, skipping the executable itself.
+pub fn parse_args(argv: &[String], cwd: &Path) -> Vec<LaunchTarget> {
+    argv.iter()
+        .skip(1)
+        .filter_map(|arg| parse_target(arg, cwd))
+        .collect()
+}
+
+/// URLs from macOS open events: `file://` paths and deep links.
+#[cfg(target_os = "macos")]
+pub fn parse_urls<'a>(urls: impl IntoIterator<Item = &'a str>) -> Vec<LaunchTarget> {
+    urls.into_iter()
+        .filter_map(|url| parse_target(url, Path::new("")))
+        .collect()
+}
+
+/// The project media opened from outside goes into: whatever the main
+/// window shows, else the most recent project, which the UI opens on start.
+fn media_project(app: &AppHandle) -> Option<String> {
+    app.state::<ProjectWindows>()
+        .project_for(MAIN_WINDOW)
+        .or_else(|| {
+            read_projects()
+                .ok()?
+                .last()
+                .map(|project| project.id.clone())
+        })
+}
+
+fn route(app: &AppHandle, targets: Vec<LaunchTarget>) {
+    let mut media = Vec::new();
+    for target in targets {
+        match target {
+            LaunchTarget::Project(project_id) => windows::open_in_ui(app, &project_id),
+            LaunchTarget::Media(path) => media.push(path),
+            LaunchTarget::Bundle(path) => {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    let imported = tauri::async_runtime::spawn_blocking({
+                        let path = path.clone();
+                        move || project_bundle::import_bundle(&path)
+                    })
+                    .await
+                    .map_err(|error| format!("Task join error: {error}"))
+                    .and_then(|result| result);
+                    match imported {
+                        Ok(project) => windows::open_in_ui(&app, &project.id),
+                        Err(error) => {
+                            tracing::warn!("Failed opening {}: {error}", path.display())
+                        }
+                    }
+                });
+            }
+        }
+    }
+    if media.is_empty() {
+        return;
+    }
+    let project_id = media_project(app);
+    let label = project_id
+        .as_deref()
+        .and_then(|project_id| app.state::<ProjectWindows>().window_for(project_id))
+        .unwrap_or_else(|| MAIN_WINDOW.to_string());
+    if let Some(project_id) = &project_id {
+        windows::open_in_ui(app, project_id);
+    }
+    file_drop::ingest_paths(app, &label, project_id, &media);
+}
+
+/// Routes `targets` now, or once the UI is ready if it isn't yet.
+pub fn submit(app: &AppHandle, targets: Vec<LaunchTarget>) {
+    if targets.is_empty() {
+        return;
+    }
+    tracing::info!("Opening from outside the app: {targets:?}");
+    {
+        let pending = app.state::<PendingLaunch>();
+        let mut pending = pending
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if !pending.0 {
+            pending.1.extend(targets);
+            return;
+        }
+    }
+    route(app, targets);
+}
+
+/// Called by the UI once it listens for `app://open-project`; routes the
+/// files and links the app was launched with.
+#[tauri::command]
+pub fn launch_ready(app: AppHandle, pending: State<'_, PendingLaunch>) {
+    let targets = {
+        let mut pending = pending
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        pending.0 = true;
+        std::mem::take(&mut pending.1)
+    };
+    if !targets.is_empty() {
+        route(&app, targets);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_percent_escapes() {
+        assert_eq!(percent_decode("My%20Clip%2B1.mp4"), "My Clip+1.mp4");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%E2%9C%93"), "\u{2713}");
+    }
+
+    #[test]
+    fn reads_project_deep_links() {
+        assert_eq!(
+            parse_target("lapaas-editor://project/proj-123", Path::new("")),
+            Some(LaunchTarget::Project("proj-123".to_string()))
+        );
+        assert_eq!(
+            parse_target("lapaas-editor://project/..%2F..", Path::new("")),
+            None
+        );
+        assert_eq!(parse_target("--flag", Path::new("")), None);
+    }
+}
@@ -0,0 +1,190 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+use crate::settings::{self, LoggingSettings};
+use crate::workspace_root;
+
+const LOG_PREFIX: &str = "editor";
+const LOG_SUFFIX: &str = "log";
+/// Daily files kept before the oldest is deleted.
+const MAX_LOG_FILES: usize = 14;
+const LEVELS: [&str; 5] = ["ERROR", "WARN", "INFO", "DEBUG", "TRACE"];
+
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentLogsRequest {
+    /// Defaults to 500.
+    lines: Option<usize>,
+    /// Minimum level, e.g. `warn`; defaults to everything.
+    level: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLine {
+    pub timestamp: String,
+    pub level: String,
+    /// Scheduler job the line was logged under, if any.
+    pub job_id: Option<u64>,
+    pub text: String,
+}
+
+pub fn logs_dir() -> Result<PathBuf, String> {
+    Ok(workspace_root()?.join("desktop").join("data").join("logs"))
+}
+
+/// Turns `{ "level": "info", "modules": { "whisper": "debug" } }` into a
+/// filter. Module keys are this app's modules; anything with `::` is used
+/// as a full target. `RUST_LOG` wins when set.
+fn build_filter(logging: &LoggingSettings) -> EnvFilter {
+    if let Ok(filter) = EnvFilter::try_from_default_env() {
+        return filter;
+    }
+    let crate_name = module_path!().split("::").next().unwrap_or_default();
+    let mut directives = vec![logging.level.clone()];
+    for (module, level) in &logging.modules {
+        if module.contains("::") {
+            directives.push(format!("{module}={level}"));
+        } else {
+            directives.push(format!("{crate_name}::{module}={level}"));
+        }
+    }
+    EnvFilter::try_new(directives.join(",")).unwrap_or_else(|error| {
+        eprintln!("[Tauri] Invalid log levels in settings ({error}); using info");
+        EnvFilter::new("info")
+    })
+}
+
+/// Installs the subscriber: human-readable lines to stderr as before, and
+/// the same lines to a daily-rotated file under `desktop/data/logs`.
+pub fn init() {
+    let logging = settings::read_app_settings()
+        .map(|settings| settings.logging)
+        .unwrap_or_default();
+    let (filter, handle) = reload::Layer::new(build_filter(&logging));
+    let file_layer = logs_dir()
+        .and_then(|dir| {
+            RollingFileAppender::builder()
+                .rotation(Rotation::DAILY)
+                .filename_prefix(LOG_PREFIX)
+                .filename_suffix(LOG_SUFFIX)
+                .max_log_files(MAX_LOG_FILES)
+                .build(dir)
+                .map_err(|error| format!("Failed opening log file: {error}"))
+        })
+        .map(|appender| fmt::layer().with_ansi(false).with_writer(appender));
+    let file_error = file_layer.as_ref().err().cloned();
+
+    let initialized = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(file_layer.ok())
+        .try_init();
+    if initialized.is_ok() {
+        let _ = FILTER.set(handle);
+    }
+    if let Some(error) = file_error {
+        tracing::warn!("Logging to stderr only: {error}");
+    }
+}
+
+/// Applies changed log levels without a restart.
+pub fn apply_settings(logging: &LoggingSettings) {
+    if let Some(handle) = FILTER.get() {
+        if let Err(error) = handle.reload(build_filter(logging)) {
+            tracing::warn!("Failed applying log levels: {error}");
+        }
+    }
+}
+
+/// Like `tauri::async_runtime::spawn_blocking`, but the closure runs inside
+/// the caller's span so its log lines keep the job id.
+pub fn spawn_blocking<F, R>(task: F) -> tauri::async_runtime::JoinHandle<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let span = tracing::Span::current();
+    tauri::async_runtime::spawn_blocking(move || span.in_scope(task))
+}
+
+fn parse_line(line: &str) -> Option<LogLine> {
+    let mut parts = line.split_whitespace();
+    let timestamp = parts.next()?.to_string();
+    let level = parts.next().filter(|level| LEVELS.contains(level))?;
+    let job_id = line.split_once("job{id=").and_then(|(_, rest)| {
+        rest.split(|c: char| !c.is_ascii_digit())
+            .next()
+            .and_then(|id| id.parse().ok())
+    });
+    Some(LogLine {
+        timestamp,
+        level: level.to_string(),
+        job_id,
+        text: line.to_string(),
+    })
+}
+
+fn recent_logs(lines: usize, level: Option<String>) -> Result<Vec<LogLine>, String> {
+    let max_rank = match level {
+        Some(level) => LEVELS
+            .iter()
+            .position(|known| known.eq_ignore_ascii_case(level.trim()))
+            .ok_or_else(|| format!("Unknown log level: {level}"))?,
+        None => LEVELS.len() - 1,
+    };
+    let dir = logs_dir()?;
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files = fs::read_dir(&dir)
+        .map_err(|error| format!("Failed reading logs dir: {error}"))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_PREFIX) && name.ends_with(LOG_SUFFIX))
+        })
+        .collect::<Vec<_>>();
+    // Dated names sort chronologically; read newest first.
+    files.sort();
+    files.reverse();
+
+    let mut collected: Vec<LogLine> = Vec::new();
+    for file in files {
+        let raw = fs::read_to_string(&file)
+            .map_err(|error| format!("Failed reading log file: {error}"))?;
+        let mut matching = raw
+            .lines()
+            .filter_map(parse_line)
+            .filter(|line| LEVELS.iter().position(|known| *known == line.level) <= Some(max_rank))
+            .collect::<Vec<_>>();
+        let keep = lines.saturating_sub(collected.len());
+        let skip = matching.len().saturating_sub(keep);
+        matching.drain(..skip);
+        matching.append(&mut collected);
+        collected = matching;
+        if collected.len() >= lines {
+            break;
+        }
+    }
+    Ok(collected)
+}
+
+#[tauri::command]
+pub async fn get_recent_logs(request: Option<RecentLogsRequest>) -> Result<Vec<LogLine>, String> {
+    let request = request.unwrap_or_default();
+    let lines = request.lines.unwrap_or(500);
+    tauri::async_runtime::spawn_blocking(move || recent_logs(lines, request.level))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,262 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ffmpeg::ffmpeg_binary;
+use crate::media::resolve_source_path;
+use crate::render_history::{find_render_mut, read_render_history, write_render_history};
+
+/// Integrated loudness targets `normalize_loudness` accepts, in LUFS.
+pub const TARGET_LUFS_RANGE: std::ops::RangeInclusive<f64> = -40.0..=-5.0;
+/// Same target the render script's one-pass stage uses.
+pub const DEFAULT_TARGET_LUFS: f64 = -16.0;
+const TRUE_PEAK_DBTP: f64 = -1.5;
+const LOUDNESS_RANGE_LU: f64 = 11.0;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeasureLoudnessRequest {
+    project_id: String,
+    /// Source reference to measure; defaults to the project's ingested media.
+    asset_id: Option<String>,
+}
+
+/// What `loudnorm` reports for one pass.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoudnessMeasurement {
+    pub integrated_lufs: f64,
+    pub true_peak_dbtp: f64,
+    pub loudness_range_lu: f64,
+    pub threshold_lufs: f64,
+    pub target_offset_lu: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeasuredAsset {
+    pub source_path: String,
+    pub loudness: LoudnessMeasurement,
+}
+
+/// Both passes of a normalization, as stored in render history.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoudnessReport {
+    pub target_lufs: f64,
+    pub true_peak_dbtp: f64,
+    pub measured: LoudnessMeasurement,
+    /// Loudness of the normalized audio.
+    pub output: LoudnessMeasurement,
+}
+
+pub fn validate_target_lufs(target_lufs: f64) -> Result<f64, String> {
+    if !TARGET_LUFS_RANGE.contains(&target_lufs) {
+        return Err(format!(
+            "Target loudness must be {} to {} LUFS, got {target_lufs}.",
+            TARGET_LUFS_RANGE.start(),
+            TARGET_LUFS_RANGE.end()
+        ));
+    }
+    Ok(target_lufs)
+}
+
+/// Reads the JSON block `loudnorm=print_format=json` prints last on stderr.
+/// `prefix` is `input` for the measured values and `output` for the result.
+pub fn parse_loudnorm_json(stderr: &str, prefix: &str) -> Option<LoudnessMeasurement> {
+    let start = stderr.rfind('{')?;
+    let end = stderr[start..].find('}')? + start;
+    let block = serde_json::from_str::<Value>(&stderr[start..=end]).ok()?;
+    let field = |key: &str| block.get(key)?.as_str()?.trim().parse::<f64>().ok();
+    Some(LoudnessMeasurement {
+        integrated_lufs: field(&format!("{prefix}_i"))?,
+        true_peak_dbtp: field(&format!("{prefix}_tp"))?,
+        loudness_range_lu: field(&format!("{prefix}_lra"))?,
+        threshold_lufs: field(&format!("{prefix}_thresh"))?,
+        target_offset_lu: field("target_offset")?,
+    })
+}
+
+fn loudnorm_filter(target_lufs: f64) -> String {
+    format!(
+        "loudnorm=I={target_lufs}:TP={TRUE_PEAK_DBTP}:LRA={LOUDNESS_RANGE_LU}:print_format=json"
+    )
+}
+
+fn run_ffmpeg(args: &[String], stage: &str) -> Result<String, String> {
+    let output = Command::new(ffmpeg_binary())
+        .args(args)
+        .output()
+        .map_err(|error| format!("Failed to execute ffmpeg: {error}"))?;
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg {stage} failed: {}",
+            stderr.trim().chars().take(300).collect::<String>()
+        ));
+    }
+    Ok(stderr)
+}
+
+fn measure_with_target(path: &Path, target_lufs: f64) -> Result<LoudnessMeasurement, String> {
+    let args = [
+        "-hide_banner".to_string(),
+        "-nostats".to_string(),
+        "-i".to_string(),
+        path.to_string_lossy().to_string(),
+        "-vn".to_string(),
+        "-af".to_string(),
+        loudnorm_filter(target_lufs),
+        "-f".to_string(),
+        "null".to_string(),
+        "-".to_string(),
+    ];
+    let stderr = run_ffmpeg(&args, "loudness measurement")?;
+    parse_loudnorm_json(&stderr, "input")
+        .ok_or_else(|| "ffmpeg did not report loudness; does the file have audio?".to_string())
+}
+
+/// First `loudnorm` pass over a file's audio.
+pub fn measure_file(path: &Path) -> Result<LoudnessMeasurement, String> {
+    measure_with_target(path, DEFAULT_TARGET_LUFS)
+}
+
+/// Two-pass EBU R128 normalization of `path` in place: the first pass
+/// measures, the second applies a linear gain from those measurements. Video
+/// is copied untouched.
+pub fn normalize_file(path: &Path, target_lufs: f64) -> Result<LoudnessReport, String> {
+    let measured = measure_with_target(path, target_lufs)?;
+    if !measured.integrated_lufs.is_finite() {
+        return Err("The audio is silent; nothing to normalize.".to_string());
+    }
+
+    let normalized = path.with_extension("loudnorm.mp4");
+    let filter = format!(
+        "loudnorm=I={target_lufs}:TP={TRUE_PEAK_DBTP}:LRA={LOUDNESS_RANGE_LU}\
+         :measured_I={}:measured_TP={}:measured_LRA={}:measured_thresh={}\
+         :offset={}:linear=true:print_format=json",
+        measured.integrated_lufs,
+        measured.true_peak_dbtp,
+        measured.loudness_range_lu,
+        measured.threshold_lufs,
+        measured.target_offset_lu,
+    );
+    let args = [
+        "-y",
+        "-hide_banner",
+        "-nostats",
+        "-i",
+        &path.to_string_lossy(),
+        "-map",
+        "0",
+        "-c:v",
+        "copy",
+        "-af",
+        &filter,
+        "-c:a",
+        "aac",
+        "-b:a",
+        "160k",
+        "-ar",
+        "48000",
+        "-movflags",
+        "+faststart",
+        &normalized.to_string_lossy(),
+    ]
+    .map(str::to_string);
+    let stderr = match run_ffmpeg(&args, "loudness normalization") {
+        Ok(stderr) => stderr,
+        Err(error) => {
+            let _ = fs::remove_file(&normalized);
+            return Err(error);
+        }
+    };
+    let output = parse_loudnorm_json(&stderr, "output")
+        .ok_or_else(|| "ffmpeg did not report the normalized loudness.".to_string())?;
+    fs::rename(&normalized, path)
+        .map_err(|error| format!("Failed replacing render with normalized audio: {error}"))?;
+
+    Ok(LoudnessReport {
+        target_lufs,
+        true_peak_dbtp: TRUE_PEAK_DBTP,
+        measured,
+        output,
+    })
+}
+
+/// Stores a normalization report on a render history entry.
+pub fn record_render_loudness(
+    project_id: &str,
+    render_id: &str,
+    report: &LoudnessReport,
+) -> Result<(), String> {
+    let mut history = read_render_history(project_id)?;
+    let entry = find_render_mut(&mut history, render_id)?;
+    entry.insert(
+        "loudness".to_string(),
+        serde_json::to_value(report)
+            .map_err(|error| format!("Loudness serialize error: {error}"))?,
+    );
+    entry.insert("loudnormApplied".to_string(), Value::Bool(true));
+    write_render_history(project_id, &history)
+}
+
+/// Output file of a render result, for the normalization pass.
+pub fn render_output_path(result: &Value) -> Option<PathBuf> {
+    result
+        .get("outputPath")
+        .and_then(Value::as_str)
+        .map(PathBuf::from)
+        .filter(|path| path.is_file())
+}
+
+fn measure_loudness_blocking(request: MeasureLoudnessRequest) -> Result<MeasuredAsset, String> {
+    let source_path = resolve_source_path(&request.project_id, request.asset_id.as_deref())?;
+    let loudness = measure_file(&source_path)?;
+    Ok(MeasuredAsset {
+        source_path: source_path.to_string_lossy().to_string(),
+        loudness,
+    })
+}
+
+/// EBU R128 loudness of a media file, from a `loudnorm` analysis pass.
+#[tauri::command]
+pub async fn measure_loudness(request: MeasureLoudnessRequest) -> Result<MeasuredAsset, String> {
+    tauri::async_runtime::spawn_blocking(move || measure_loudness_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_last_loudnorm_block() {
+        let stderr = r#"Input #0, mov,mp4 {not json}
+[Parsed_loudnorm_0 @ 0x600003a1c000]
+{
+	"input_i" : "-27.61",
+	"input_tp" : "-4.47",
+	"input_lra" : "18.06",
+	"input_thresh" : "-39.20",
+	"output_i" : "-16.58",
+	"output_tp" : "-1.50",
+	"output_lra" : "14.78",
+	"output_thresh" : "-27.71",
+	"normalization_type" : "dynamic",
+	"target_offset" : "0.58"
+}
+"#;
+        let input = parse_loudnorm_json(stderr, "input").unwrap();
+        assert_eq!(input.integrated_lufs, -27.61);
+        assert_eq!(input.target_offset_lu, 0.58);
+        let output = parse_loudnorm_json(stderr, "output").unwrap();
+        assert_eq!(output.true_peak_dbtp, -1.5);
+        assert!(parse_loudnorm_json("no block here", "input").is_none());
+        assert!(validate_target_lufs(-50.0).is_err());
+    }
+}
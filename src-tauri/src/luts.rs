@@ -0,0 +1,359 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    clip_flags, now_iso, read_projects, read_timeline, timeline_merge, workspace_root,
+    write_timeline,
+};
+
+/// Grid sizes ffmpeg's `lut3d` accepts.
+const LUT_SIZE_RANGE: std::ops::RangeInclusive<u32> = 2..=256;
+/// Largest .cube file imported; a 256³ grid is about 300 MB of text.
+const MAX_CUBE_BYTES: u64 = 512 * 1024 * 1024;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportLutRequest {
+    path: String,
+    /// Library name; defaults to the file name.
+    name: Option<String>,
+    /// Overwrite a library LUT of the same name.
+    replace: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetClipLutRequest {
+    project_id: String,
+    clip_id: String,
+    /// Library name or .cube path; `None` falls back to the project LUT.
+    lut: Option<String>,
+    /// Change the clip even when it is locked.
+    force: Option<bool>,
+}
+
+/// Header of a parsed 3D .cube file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CubeLut {
+    pub title: Option<String>,
+    pub size: u32,
+    pub domain_min: [f64; 3],
+    pub domain_max: [f64; 3],
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LutEntry {
+    pub name: String,
+    pub path: String,
+    #[serde(flatten)]
+    pub cube: CubeLut,
+}
+
+fn triple(values: &[&str], line: usize) -> Result<[f64; 3], String> {
+    let parsed = values
+        .iter()
+        .map(|value| value.parse::<f64>().ok().filter(|value| value.is_finite()))
+        .collect::<Option<Vec<_>>>();
+    match parsed.as_deref() {
+        Some(&[a, b, c]) => Ok([a, b, c]),
+        _ => Err(format!("Line {line}: expected three numbers.")),
+    }
+}
+
+/// Parses an Adobe/Resolve .cube file, checking that it is a 3D LUT with
+/// exactly size³ entries.
+pub fn parse_cube(text: &str) -> Result<CubeLut, String> {
+    let mut title = None;
+    let mut size = None;
+    let mut domain_min = [0.0; 3];
+    let mut domain_max = [1.0; 3];
+    let mut entries = 0_u64;
+
+    for (index, raw) in text.lines().enumerate() {
+        let line = index + 1;
+        let content = raw.split('#').next().unwrap_or_default().trim();
+        if content.is_empty() {
+            continue;
+        }
+        let mut parts = content.split_whitespace();
+        let keyword = parts.next().unwrap_or_default();
+        let values = parts.collect::<Vec<_>>();
+        match keyword {
+            "TITLE" => {
+                title = Some(
+                    content["TITLE".len()..]
+                        .trim()
+                        .trim_matches('"')
+                        .to_string(),
+                );
+            }
+            "LUT_3D_SIZE" => {
+                let parsed = values
+                    .first()
+                    .and_then(|value| value.parse::<u32>().ok())
+                    .filter(|size| LUT_SIZE_RANGE.contains(size))
+                    .ok_or_else(|| {
+                        format!(
+                            "Line {line}: LUT_3D_SIZE must be {}-{}.",
+                            LUT_SIZE_RANGE.start(),
+                            LUT_SIZE_RANGE.end()
+                        )
+                    })?;
+                size = Some(parsed);
+            }
+            "LUT_1D_SIZE" => {
+                return Err("1D LUTs are not supported; export a 3D .cube.".to_string());
+            }
+            "DOMAIN_MIN" => domain_min = triple(&values, line)?,
+            "DOMAIN_MAX" => domain_max = triple(&values, line)?,
+            keyword if keyword.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                // LUT_3D_INPUT_RANGE and other vendor keywords.
+            }
+            _ => {
+                let mut row = vec![keyword];
+                row.extend(values);
+                triple(&row, line)?;
+                if size.is_none() {
+                    return Err(format!("Line {line}: data before LUT_3D_SIZE."));
+                }
+                entries += 1;
+            }
+        }
+    }
+
+    let size = size.ok_or_else(|| "Missing LUT_3D_SIZE; not a 3D .cube file.".to_string())?;
+    let expected = u64::from(size).pow(3);
+    if entries != expected {
+        return Err(format!(
+            "Expected {expected} entries for a {size}³ LUT, found {entries}."
+        ));
+    }
+    if (0..3).any(|axis| domain_min[axis] >= domain_max[axis]) {
+        return Err("DOMAIN_MIN must be below DOMAIN_MAX.".to_string());
+    }
+    Ok(CubeLut {
+        title,
+        size,
+        domain_min,
+        domain_max,
+    })
+}
+
+pub fn read_cube_file(path: &Path) -> Result<CubeLut, String> {
+    if path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        != Some("cube".to_string())
+    {
+        return Err(format!("{} is not a .cube file.", path.display()));
+    }
+    let size = fs::metadata(path)
+        .map_err(|error| format!("Failed reading LUT {}: {error}", path.display()))?
+        .len();
+    if size > MAX_CUBE_BYTES {
+        return Err(format!("LUT {} is too large.", path.display()));
+    }
+    let text = fs::read_to_string(path)
+        .map_err(|error| format!("Failed reading LUT {}: {error}", path.display()))?;
+    parse_cube(&text).map_err(|error| format!("Invalid LUT {}: {error}", path.display()))
+}
+
+fn library_dir() -> Result<PathBuf, String> {
+    Ok(workspace_root()?.join("desktop").join("data").join("luts"))
+}
+
+/// Library name from a user-supplied name or file stem.
+fn library_name(raw: &str) -> String {
+    raw.trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .trim_matches('-')
+        .to_string()
+}
+
+pub fn list_library() -> Result<Vec<LutEntry>, String> {
+    let dir = library_dir()?;
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut entries = fs::read_dir(&dir)
+        .map_err(|error| format!("Failed reading LUT library: {error}"))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("cube"))
+        .filter_map(|path| match read_cube_file(&path) {
+            Ok(cube) => Some(LutEntry {
+                name: path.file_stem()?.to_string_lossy().to_string(),
+                path: path.to_string_lossy().to_string(),
+                cube,
+            }),
+            Err(error) => {
+                tracing::warn!("Skipping LUT: {error}");
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(entries)
+}
+
+/// A library name or a .cube path, checked to parse.
+pub fn resolve_lut(reference: &str) -> Result<PathBuf, String> {
+    let reference = reference.trim();
+    let name = library_name(reference);
+    if !name.is_empty() {
+        let library = library_dir()?.join(format!("{name}.cube"));
+        if library.is_file() {
+            read_cube_file(&library)?;
+            return Ok(library);
+        }
+    }
+    let path = PathBuf::from(reference);
+    if !path.is_file() {
+        return Err(format!("LUT not found: {reference}"));
+    }
+    read_cube_file(&path)?;
+    Ok(path)
+}
+
+/// The project's LUT, if one is set and still on disk.
+pub fn project_lut(project_id: &str) -> Option<PathBuf> {
+    read_projects()
+        .ok()?
+        .into_iter()
+        .find(|project| project.id == project_id)?
+        .settings
+        .lut
+        .filter(|path| path.is_file())
+}
+
+fn import_lut_blocking(request: ImportLutRequest) -> Result<LutEntry, String> {
+    let source = PathBuf::from(request.path.trim());
+    let cube = read_cube_file(&source)?;
+    let name = library_name(
+        request
+            .name
+            .as_deref()
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or(&source.file_stem().unwrap_or_default().to_string_lossy()),
+    );
+    if name.is_empty() {
+        return Err("LUT name must contain letters or digits.".to_string());
+    }
+    let dir = library_dir()?;
+    fs::create_dir_all(&dir).map_err(|error| format!("Failed creating LUT library: {error}"))?;
+    let target = dir.join(format!("{name}.cube"));
+    if target.exists() && !request.replace.unwrap_or(false) {
+        return Err(format!("A LUT named {name} already exists."));
+    }
+    fs::copy(&source, &target).map_err(|error| format!("Failed importing LUT: {error}"))?;
+    Ok(LutEntry {
+        name,
+        path: target.to_string_lossy().to_string(),
+        cube,
+    })
+}
+
+fn set_clip_lut_blocking(request: SetClipLutRequest) -> Result<Value, String> {
+    let lut = request
+        .lut
+        .as_deref()
+        .filter(|lut| !lut.trim().is_empty())
+        .map(resolve_lut)
+        .transpose()?;
+    let _save = timeline_merge::lock_saves();
+    let mut timeline = read_timeline(&request.project_id)?;
+    let clip = timeline
+        .clips
+        .iter_mut()
+        .find(|clip| clip.clip_id == request.clip_id && clip.clip_type == "source_clip")
+        .ok_or_else(|| format!("Source clip not found: {}", request.clip_id))?;
+    if let Some(track) = timeline
+        .tracks
+        .iter()
+        .find(|track| track.id == clip.track_id && track.locked)
+    {
+        return Err(format!("Track {} is locked.", track.id));
+    }
+    clip_flags::ensure_unlocked(clip, request.force.unwrap_or(false))?;
+    if !clip.effects.is_object() {
+        clip.effects = json!({});
+    }
+    if let Some(effects) = clip.effects.as_object_mut() {
+        match &lut {
+            Some(path) => {
+                effects.insert("lut".to_string(), json!(path.to_string_lossy()));
+            }
+            None => {
+                effects.remove("lut");
+            }
+        }
+    }
+    let effects = clip.effects.clone();
+    timeline.version = timeline.version.saturating_add(1);
+    timeline.updated_at = now_iso();
+    write_timeline(&timeline)?;
+    Ok(effects)
+}
+
+/// Validates a .cube file and copies it into the app's LUT library.
+#[tauri::command]
+pub async fn import_lut(request: ImportLutRequest) -> Result<LutEntry, String> {
+    tauri::async_runtime::spawn_blocking(move || import_lut_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn list_luts() -> Result<Vec<LutEntry>, String> {
+    tauri::async_runtime::spawn_blocking(list_library)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// Sets or clears a clip's LUT, which replaces the project LUT for that
+/// clip. Returns the clip's effects.
+#[tauri::command]
+pub async fn set_clip_lut(request: SetClipLutRequest) -> Result<Value, String> {
+    tauri::async_runtime::spawn_blocking(move || set_clip_lut_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cube(size: u32, entries: usize) -> String {
+        let mut text = format!("# exported\nTITLE \"Log to Rec709\"\nLUT_3D_SIZE {size}\n");
+        text.push_str("DOMAIN_MIN 0 0 0\nDOMAIN_MAX 1 1 1\n");
+        for _ in 0..entries {
+            text.push_str("0.5 0.25 1.0\n");
+        }
+        text
+    }
+
+    #[test]
+    fn cube_needs_a_full_3d_grid() {
+        let lut = parse_cube(&cube(2, 8)).unwrap();
+        assert_eq!(lut.title.as_deref(), Some("Log to Rec709"));
+        assert_eq!(lut.size, 2);
+        assert!(parse_cube(&cube(2, 7)).unwrap_err().contains("Expected 8"));
+        assert!(parse_cube("LUT_1D_SIZE 4\n0 0 0\n").is_err());
+        assert!(parse_cube("LUT_3D_SIZE 2\n0 0\n").is_err());
+        assert_eq!(library_name(" Sony S-Log3 "), "sony-s-log3");
+    }
+}
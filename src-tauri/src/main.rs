@@ -1,4 +1,5 @@
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::{Arc, Mutex};
@@ -6,6 +7,108 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tracing::Instrument;
+
+use pipeline_result::{EditNowResponse, EditNowResult, StartEditingResponse, StartEditingResult};
+use project_status::{ProjectStatus, StatusError};
+use timeline::{
+    build_multi_source_timeline, build_rough_cut_timeline, RoughCutOptions, RoughCutSource,
+    SourceArrangement, TimeRange,
+};
+
+mod actions;
+mod audio_devices;
+mod audio_enhance;
+mod autosave;
+mod backups;
+mod branding;
+mod captions;
+mod censor;
+mod chapters;
+mod checkpoints;
+mod cli;
+mod clip_flags;
+mod control_api;
+mod cuts;
+mod edit_analytics;
+mod edl;
+mod enrichment;
+mod event_bus;
+mod export_profiles;
+mod external_assets;
+mod fallback_policy;
+mod ffmpeg;
+mod file_drop;
+mod frames;
+mod hardware;
+mod health;
+mod highlights;
+mod hooks;
+mod hw_encoders;
+mod integrity;
+mod launch;
+mod logging;
+mod loudness;
+mod luts;
+mod markers;
+mod media;
+mod media_pool;
+mod media_roots;
+mod model_downloads;
+mod model_registry;
+mod multicam;
+mod node_runtime;
+mod notifications;
+mod onboarding;
+mod otio;
+mod path_safety;
+mod pickers;
+mod pipeline;
+mod pipeline_result;
+mod planner;
+mod player;
+mod plugins;
+mod power;
+mod preview;
+mod project_bundle;
+mod project_config;
+mod project_status;
+mod render_cache;
+mod render_estimate;
+mod render_export;
+mod render_history;
+mod render_verification;
+mod render_workers;
+mod s3;
+mod sample_project;
+mod scheduler;
+mod screen_capture;
+mod script_retry;
+mod settings;
+mod shutdown;
+mod single_instance;
+mod silence;
+mod speed;
+mod sponsors;
+mod store_cache;
+mod store_repair;
+mod subtitles;
+mod support;
+mod text_edit;
+mod timeline;
+mod timeline_merge;
+mod timeline_packed;
+mod timeline_stats;
+mod timeline_validation;
+mod timeline_window;
+mod titles;
+mod transcript;
+mod tray;
+mod url_ingest;
+mod validation;
+mod voiceover;
+mod whisper;
+mod windows;
 
 fn workspace_root() -> Result<PathBuf, String> {
     // 1. Check for explicit override (useful for dev/CI)
@@ -62,42 +165,32 @@ fn workspace_root() -> Result<PathBuf, String> {
 }
 
 fn node_binary() -> String {
-    // 1. Explicit override (useful for dev/CI)
-    if let Ok(v) = std::env::var("NODE_BIN") {
-        return v;
-    }
-    // 2. Bundled sidecar inside the .app (Tauri externalBin convention)
-    //    The binary is placed next to the main executable as "node"
-    if let Ok(exe) = std::env::current_exe() {
-        let sidecar = exe.parent().map(|p| p.join("node")).unwrap_or_default();
-        if sidecar.exists() {
-            return sidecar.to_string_lossy().to_string();
-        }
-        // macOS .app: Contents/MacOS/node
-        let macos_sidecar = exe
-            .parent()
-            .and_then(|p| p.parent())
-            .map(|p| p.join("MacOS").join("node"))
-            .unwrap_or_default();
-        if macos_sidecar.exists() {
-            return macos_sidecar.to_string_lossy().to_string();
-        }
-    }
-    // 3. Fall back to system node (dev mode / user has Node installed)
-    "node".to_string()
+    node_runtime::resolve_node_binary()
 }
 
 fn run_node_script(script_path: &Path, args: &[String]) -> Result<String, String> {
+    // Every script builds its data paths from `--project-id`, so check it once here.
+    if let Some(index) = args.iter().position(|arg| arg == "--project-id") {
+        path_safety::validate_project_id(args.get(index + 1).map_or("", String::as_str))?;
+    }
     let root = workspace_root()?;
     let mut command = Command::new(node_binary());
     command.current_dir(&root).arg(script_path);
+    if let Some(path) = ffmpeg::child_path() {
+        command.env("PATH", path);
+    }
+    if let Some(roots) = media_roots::script_env() {
+        command.env(media_roots::MEDIA_ROOTS_ENV, roots);
+    }
     for arg in args {
         command.arg(arg);
     }
 
-    let output = command
-        .output()
-        .map_err(|error| format!("Failed to execute script {:?}: {error}", script_path))?;
+    let output = shutdown::run_tracked(&mut command)
+        .map_err(|error| match error.kind() {
+            std::io::ErrorKind::NotFound => node_runtime::missing_node_message(),
+            _ => format!("Failed to execute script {:?}: {error}", script_path),
+        })?;
 
     if output.status.success() {
         let stdout = String::from_utf8(output.stdout)
@@ -106,6 +199,7 @@ fn run_node_script(script_path: &Path, args: &[String]) -> Result<String, String
     } else {
         let stderr =
             String::from_utf8(output.stderr).unwrap_or_else(|_| "Unknown script error".to_string());
+        support::record_script_failure(script_path, args, &stderr);
         Err(stderr.trim().to_string())
     }
 }
@@ -121,44 +215,43 @@ fn projects_file_path() -> Result<PathBuf, String> {
 }
 
 fn timeline_file_path(project_id: &str) -> Result<PathBuf, String> {
-    let root = workspace_root()?;
-    Ok(root
-        .join("desktop")
-        .join("data")
-        .join(project_id)
-        .join("timeline.json"))
+    Ok(path_safety::project_dir(project_id)?.join("timeline.json"))
 }
 
 fn render_history_file_path(project_id: &str) -> Result<PathBuf, String> {
-    let root = workspace_root()?;
-    Ok(root
-        .join("desktop")
-        .join("data")
-        .join(project_id)
+    Ok(path_safety::project_dir(project_id)?
         .join("renders")
         .join("history.json"))
 }
 
 fn telemetry_summary_file_path(project_id: &str) -> Result<PathBuf, String> {
-    let root = workspace_root()?;
-    Ok(root
-        .join("desktop")
-        .join("data")
-        .join(project_id)
+    Ok(path_safety::project_dir(project_id)?
         .join("telemetry")
         .join("summary.json"))
 }
 
 fn telemetry_events_file_path(project_id: &str) -> Result<PathBuf, String> {
-    let root = workspace_root()?;
-    Ok(root
-        .join("desktop")
-        .join("data")
-        .join(project_id)
+    Ok(path_safety::project_dir(project_id)?
         .join("telemetry")
         .join("events.jsonl"))
 }
 
+/// Appends one event to the project's telemetry log, in the shape the
+/// pipeline scripts write.
+fn append_telemetry_event(project_id: &str, event: &Value) -> Result<(), String> {
+    let path = telemetry_events_file_path(project_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating telemetry dir: {error}"))?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|error| format!("Failed opening telemetry events: {error}"))?;
+    writeln!(file, "{event}").map_err(|error| format!("Failed writing telemetry: {error}"))
+}
+
 fn ensure_projects_store() -> Result<PathBuf, String> {
     let file_path = projects_file_path()?;
     if let Some(parent) = file_path.parent() {
@@ -192,6 +285,9 @@ struct ProjectSettings {
     transcription_model: Option<String>,
     cut_planner_model: Option<String>,
     template_planner_model: Option<String>,
+    /// .cube LUT applied to every source clip without its own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    lut: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -201,6 +297,9 @@ struct Project {
     name: String,
     settings: ProjectSettings,
     status: String,
+    /// The project this one was cut from, e.g. for an extracted highlight.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parent_id: Option<String>,
     created_at: String,
     updated_at: String,
 }
@@ -223,16 +322,12 @@ struct UpdateProjectSettingsRequest {
 #[serde(rename_all = "camelCase")]
 struct MediaIngestRequest {
     project_id: String,
+    /// A file, or an http(s) URL: direct media links download as they are,
+    /// video site pages go through yt-dlp.
     input: String,
     generate_proxy: Option<bool>,
     generate_waveform: Option<bool>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct TimeRange {
-    start_us: u64,
-    end_us: u64,
+    priority: Option<scheduler::Priority>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -255,6 +350,20 @@ struct TimelineClip {
     end_us: u64,
     source_start_us: u64,
     source_end_us: u64,
+    /// Playback rate; the clip spans its source range divided by this.
+    #[serde(
+        default = "speed::default_speed",
+        skip_serializing_if = "speed::is_default_speed"
+    )]
+    speed: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    speed_keyframes: Vec<speed::SpeedKeyframe>,
+    /// Refuses edits unless the command is forced.
+    #[serde(default, skip_serializing_if = "clip_flags::is_unset")]
+    locked: bool,
+    /// Kept out of AI cuts and plans, e.g. a sponsor segment.
+    #[serde(default, skip_serializing_if = "clip_flags::is_unset")]
+    protected_from_ai: bool,
     source_ref: String,
     effects: Value,
     transform: Value,
@@ -274,6 +383,69 @@ struct Timeline {
     updated_at: String,
     tracks: Vec<TimelineTrack>,
     clips: Vec<TimelineClip>,
+    #[serde(default)]
+    meta: Value,
+}
+
+/// Timeline fixtures for unit tests, so a new clip or track field gets its
+/// default in one place.
+#[cfg(test)]
+pub(crate) mod test_fixtures {
+    use serde_json::json;
+
+    use super::{Timeline, TimelineClip, TimelineTrack};
+
+    pub(crate) fn track(id: &str, kind: &str, order: u32) -> TimelineTrack {
+        TimelineTrack {
+            id: id.to_string(),
+            name: id.to_string(),
+            kind: kind.to_string(),
+            order,
+            locked: false,
+        }
+    }
+
+    /// A `source_clip` of `source-video` whose source range equals its
+    /// timeline span.
+    pub(crate) fn clip(id: &str, track_id: &str, start_us: u64, end_us: u64) -> TimelineClip {
+        TimelineClip {
+            clip_id: id.to_string(),
+            track_id: track_id.to_string(),
+            clip_type: "source_clip".to_string(),
+            start_us,
+            end_us,
+            source_start_us: start_us,
+            source_end_us: end_us,
+            speed: 1.0,
+            speed_keyframes: Vec::new(),
+            locked: false,
+            protected_from_ai: false,
+            source_ref: "source-video".to_string(),
+            effects: json!({}),
+            transform: json!({}),
+            meta: json!({}),
+        }
+    }
+
+    pub(crate) fn timeline(
+        fps: u32,
+        tracks: Vec<TimelineTrack>,
+        clips: Vec<TimelineClip>,
+    ) -> Timeline {
+        Timeline {
+            id: "timeline-test".to_string(),
+            project_id: "project-test".to_string(),
+            version: 1,
+            status: "ROUGH_CUT_READY".to_string(),
+            fps,
+            duration_us: clips.iter().map(|clip| clip.end_us).max().unwrap_or(0),
+            created_at: "0".to_string(),
+            updated_at: "0".to_string(),
+            tracks,
+            clips,
+            meta: json!({}),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -284,6 +456,11 @@ struct CreateRoughCutTimelineRequest {
     fps: u32,
     source_ref: Option<String>,
     remove_ranges: Option<Vec<TimeRange>>,
+    options: Option<RoughCutOptions>,
+    /// When present, replaces `sourceRef`/`removeRanges`/`durationUs` with
+    /// several inputs arranged according to `arrangement`.
+    sources: Option<Vec<RoughCutSource>>,
+    arrangement: Option<SourceArrangement>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -309,6 +486,10 @@ struct GetProjectTelemetryRequest {
 #[serde(rename_all = "camelCase")]
 struct SaveTimelineRequest {
     timeline: Timeline,
+    /// Refuse to save when `validate_timeline` would report errors.
+    reject_invalid: Option<bool>,
+    /// Save even when `timeline.version` is behind the stored timeline.
+    force: Option<bool>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -320,9 +501,16 @@ struct StartEditingRequest {
     language: Option<String>,
     fps: Option<u32>,
     source_ref: Option<String>,
-    fallback_policy: Option<String>,
+    fallback_policy: Option<fallback_policy::FallbackPolicyInput>,
     transcription_model: Option<String>,
     cut_planner_model: Option<String>,
+    rough_cut_options: Option<RoughCutOptions>,
+    /// Redo every stage instead of resuming from saved checkpoints.
+    force_restart: Option<bool>,
+    priority: Option<scheduler::Priority>,
+    /// Keep clips edited by hand since the last run and only regenerate the
+    /// rest of the timeline, instead of replacing it.
+    merge_edits: Option<bool>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -332,17 +520,41 @@ struct EditNowRequest {
     fps: Option<u32>,
     source_ref: Option<String>,
     fetch_external: Option<bool>,
-    fallback_policy: Option<String>,
+    fallback_policy: Option<fallback_policy::FallbackPolicyInput>,
     template_planner_model: Option<String>,
+    priority: Option<scheduler::Priority>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct RenderVideoRequest {
     project_id: String,
     output_name: Option<String>,
     burn_subtitles: Option<bool>,
     quality: Option<String>,
+    /// `auto` (default), `software`, or a name from `list_hw_encoders`.
+    encoder: Option<String>,
+    priority: Option<scheduler::Priority>,
+    /// Two-pass EBU R128 normalization of the output instead of the
+    /// script's one-pass stage.
+    normalize_loudness: Option<bool>,
+    /// Integrated loudness for `normalizeLoudness`, in LUFS. Defaults to -16.
+    target_lufs: Option<f64>,
+    /// Overlay the project's branding logo. Defaults to true; turn it off
+    /// for client deliveries.
+    watermark: Option<bool>,
+    /// Probe the output afterwards for duration drift, missing audio, A/V
+    /// sync and long black or silent stretches. Defaults to true.
+    verify: Option<bool>,
+    /// Reuse clip segments cached by earlier renders and cache new ones.
+    /// Defaults to true; the app's render cache setting can turn it off.
+    use_cache: Option<bool>,
+    /// Id of a remote worker from the app settings to render on instead of
+    /// this machine.
+    worker: Option<String>,
+    /// With `worker`, ship the ingest proxy in place of the source file: a
+    /// much smaller upload, for draft renders.
+    use_proxy: Option<bool>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -370,36 +582,70 @@ fn generate_project_id() -> String {
 
 fn read_projects() -> Result<Vec<Project>, String> {
     let file_path = ensure_projects_store()?;
+    if let Some(projects) = store_cache::projects(&file_path) {
+        return Ok(projects);
+    }
     let raw = fs::read_to_string(&file_path)
         .map_err(|error| format!("Failed reading projects store: {error}"))?;
-    serde_json::from_str::<Vec<Project>>(&raw)
-        .map_err(|error| format!("Invalid projects JSON: {error}"))
+    let projects = serde_json::from_str::<Vec<Project>>(&raw)
+        .map_err(|error| format!("Invalid projects JSON: {error}; run repair_store to recover."))?;
+    store_cache::store_projects(&file_path, &projects);
+    Ok(projects)
 }
 
 fn write_projects(projects: &[Project]) -> Result<(), String> {
     let file_path = ensure_projects_store()?;
     let serialized = serde_json::to_string_pretty(projects)
         .map_err(|error| format!("Serialize error: {error}"))?;
+    let _write = shutdown::begin_write();
     fs::write(&file_path, format!("{serialized}\n"))
-        .map_err(|error| format!("Failed writing projects store: {error}"))
+        .map_err(|error| format!("Failed writing projects store: {error}"))?;
+    store_cache::store_projects(&file_path, projects);
+    Ok(())
 }
 
-fn update_project_status(project_id: &str, status: &str) -> Result<(), String> {
+/// Moves a project to `status` if the state machine in `project_status`
+/// allows it from where the project is now.
+fn update_project_status(project_id: &str, status: ProjectStatus) -> Result<(), StatusError> {
+    set_project_status(project_id, status, project_status::Transition::Step)
+}
+
+/// Moves a project onto `status` after a whole timeline was written in one
+/// go, which skips the pipeline steps; see `ProjectStatus::can_take_timeline`.
+fn mark_timeline_written(project_id: &str, status: ProjectStatus) -> Result<(), StatusError> {
+    set_project_status(project_id, status, project_status::Transition::TimelineWritten)
+}
+
+fn set_project_status(
+    project_id: &str,
+    status: ProjectStatus,
+    transition: project_status::Transition,
+) -> Result<(), StatusError> {
     let mut projects = read_projects()?;
-    let mut found = false;
-    let now = now_iso();
-    for project in &mut projects {
-        if project.id == project_id {
-            project.status = status.to_string();
-            project.updated_at = now.clone();
-            found = true;
-            break;
-        }
-    }
-    if !found {
-        return Err("Project not found.".to_string());
+    let timeline_exists = timeline_file_path(project_id).is_ok_and(|path| path.is_file());
+    let project = projects
+        .iter_mut()
+        .find(|project| project.id == project_id)
+        .ok_or(StatusError::ProjectNotFound)?;
+    project_status::check_transition(&project.status, status, transition, timeline_exists)?;
+    let previous = std::mem::replace(&mut project.status, status.as_str().to_string());
+    project.updated_at = now_iso();
+    let project = project.clone();
+    write_projects(&projects)?;
+    if previous != project.status {
+        publish_project_status(&project, Some(&previous));
     }
-    write_projects(&projects)
+    Ok(())
+}
+
+/// Announces a project's status after it was written, through the event
+/// bus so it lands in the project's journal too.
+fn publish_project_status(project: &Project, previous: Option<&str>) {
+    event_bus::publish(
+        &project.id,
+        event_bus::PROJECT_STATUS_EVENT,
+        &project_status::status_event(&project.status, previous, &project.updated_at),
+    );
 }
 
 fn read_timeline(project_id: &str) -> Result<Timeline, String> {
@@ -407,141 +653,59 @@ fn read_timeline(project_id: &str) -> Result<Timeline, String> {
     if !file_path.exists() {
         return Err("Timeline not found.".to_string());
     }
+    if let Some(timeline) = store_cache::timeline(project_id, &file_path) {
+        return Ok(timeline);
+    }
     let raw = fs::read_to_string(&file_path)
         .map_err(|error| format!("Failed reading timeline file: {error}"))?;
-    serde_json::from_str::<Timeline>(&raw)
-        .map_err(|error| format!("Invalid timeline JSON: {error}"))
+    let timeline = serde_json::from_str::<Timeline>(&raw)
+        .map_err(|error| format!("Invalid timeline JSON: {error}; run repair_store to recover."))?;
+    store_cache::store_timeline(project_id, &file_path, &timeline);
+    Ok(timeline)
 }
 
 fn write_timeline(timeline: &Timeline) -> Result<(), String> {
     let file_path = ensure_timeline_store(&timeline.project_id)?;
     let serialized = serde_json::to_string_pretty(timeline)
         .map_err(|error| format!("Timeline serialize error: {error}"))?;
-    fs::write(&file_path, format!("{serialized}\n"))
-        .map_err(|error| format!("Failed writing timeline file: {error}"))
-}
-
-fn normalize_ranges(ranges: Vec<TimeRange>, duration_us: u64) -> Vec<TimeRange> {
-    let mut normalized = ranges
-        .into_iter()
-        .map(|range| TimeRange {
-            start_us: range.start_us.min(duration_us),
-            end_us: range.end_us.min(duration_us),
-        })
-        .filter(|range| range.end_us > range.start_us)
-        .collect::<Vec<_>>();
-
-    normalized.sort_by_key(|range| range.start_us);
-    let mut merged: Vec<TimeRange> = Vec::new();
-
-    for range in normalized {
-        if let Some(last) = merged.last_mut() {
-            if range.start_us <= last.end_us {
-                if range.end_us > last.end_us {
-                    last.end_us = range.end_us;
-                }
-                continue;
-            }
-        }
-        merged.push(range);
+    {
+        let _write = shutdown::begin_write();
+        fs::write(&file_path, format!("{serialized}\n"))
+            .map_err(|error| format!("Failed writing timeline file: {error}"))?;
     }
-
-    merged
+    store_cache::store_timeline(&timeline.project_id, &file_path, timeline);
+    event_bus::publish(
+        &timeline.project_id,
+        event_bus::TIMELINE_SAVED_EVENT,
+        &serde_json::json!({
+            "timelineId": timeline.id,
+            "version": timeline.version,
+            "updatedAt": timeline.updated_at,
+        }),
+    );
+    Ok(())
 }
 
-fn invert_ranges(remove_ranges: &[TimeRange], duration_us: u64) -> Vec<TimeRange> {
-    if duration_us == 0 {
-        return Vec::new();
+/// Makes a freshly built timeline replace the project's current one: it keeps
+/// the stored id and creation time and bumps the version.
+fn inherit_timeline_identity(timeline: &mut Timeline) {
+    if let Ok(previous) = read_timeline(&timeline.project_id) {
+        timeline.id = previous.id;
+        timeline.created_at = previous.created_at;
+        timeline.version = previous.version.saturating_add(1);
     }
-
-    let mut keep_ranges = Vec::new();
-    let mut cursor = 0_u64;
-
-    for range in remove_ranges {
-        if range.start_us > cursor {
-            keep_ranges.push(TimeRange {
-                start_us: cursor,
-                end_us: range.start_us,
-            });
-        }
-        cursor = range.end_us.max(cursor);
-    }
-
-    if cursor < duration_us {
-        keep_ranges.push(TimeRange {
-            start_us: cursor,
-            end_us: duration_us,
-        });
-    }
-
-    keep_ranges
 }
 
-fn build_rough_cut_timeline(
-    project_id: String,
-    duration_us: u64,
-    fps: u32,
-    source_ref: String,
-    remove_ranges: Vec<TimeRange>,
-) -> Timeline {
-    let remove_ranges = normalize_ranges(remove_ranges, duration_us);
-    let keep_ranges = invert_ranges(&remove_ranges, duration_us);
-
-    let video_track = TimelineTrack {
-        id: "track-video-main".to_string(),
-        name: "Main Video".to_string(),
-        kind: "video".to_string(),
-        order: 0,
-        locked: false,
-    };
-    let captions_track = TimelineTrack {
-        id: "track-captions".to_string(),
-        name: "Captions".to_string(),
-        kind: "caption".to_string(),
-        order: 1,
-        locked: false,
+/// Copies the transcript a pipeline run reported into the typed transcript
+/// store. Best-effort: a malformed transcript must not fail the pipeline.
+fn persist_pipeline_transcript(project_id: &str, transcript_path: Option<&str>, source_ref: &str) {
+    let Some(path) = transcript_path else {
+        return;
     };
-
-    let mut clips = Vec::new();
-    let mut timeline_cursor = 0_u64;
-
-    for (index, keep) in keep_ranges.iter().enumerate() {
-        let clip_duration = keep.end_us - keep.start_us;
-        let timeline_start = timeline_cursor;
-        let timeline_end = timeline_start + clip_duration;
-
-        clips.push(TimelineClip {
-            clip_id: format!("clip-{}", index + 1),
-            track_id: video_track.id.clone(),
-            clip_type: "source_clip".to_string(),
-            start_us: timeline_start,
-            end_us: timeline_end,
-            source_start_us: keep.start_us,
-            source_end_us: keep.end_us,
-            source_ref: source_ref.clone(),
-            effects: serde_json::json!({}),
-            transform: serde_json::json!({}),
-            meta: serde_json::json!({
-                "generatedBy": "ai-rough-cut",
-                "removeRangesApplied": remove_ranges
-            }),
-        });
-
-        timeline_cursor = timeline_end;
-    }
-
-    let now = now_iso();
-    Timeline {
-        id: format!("timeline-{}", generate_project_id()),
-        project_id,
-        version: 1,
-        status: "ROUGH_CUT_READY".to_string(),
-        fps: fps.max(1),
-        duration_us: timeline_cursor,
-        created_at: now.clone(),
-        updated_at: now,
-        tracks: vec![video_track, captions_track],
-        clips,
+    if let Err(error) =
+        transcript::import_pipeline_transcript(project_id, Path::new(path), source_ref)
+    {
+        tracing::warn!("Failed to store pipeline transcript: {error}");
     }
 }
 
@@ -597,6 +761,22 @@ async fn first_run_checks() -> Result<Value, String> {
     let script = script_path("scripts/first_run_checks.mjs")?;
     let args = Vec::<String>::new();
 
+    let node = tauri::async_runtime::spawn_blocking(node_runtime::node_runtime_info)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?;
+    let node_json = serde_json::to_value(&node)
+        .map_err(|error| format!("Node runtime serialize error: {error}"))?;
+    // The checks themselves run on Node, so report a missing runtime directly.
+    if !node.found {
+        return Ok(serde_json::json!({
+            "ok": false,
+            "status": "fail",
+            "checkedAt": now_iso(),
+            "nodeRuntime": node_json,
+            "recommendations": [node_runtime::missing_node_message()],
+        }));
+    }
+
     let raw =
         match tauri::async_runtime::spawn_blocking(move || run_node_script(&script, &args)).await {
             Ok(Ok(payload)) => payload,
@@ -604,44 +784,23 @@ async fn first_run_checks() -> Result<Value, String> {
             Err(error) => return Err(format!("Task join error: {error}")),
         };
 
-    serde_json::from_str::<Value>(&raw)
-        .map_err(|error| format!("Invalid first-run checks JSON: {error}"))
-}
-
-#[derive(Deserialize)]
-struct InstallRequest {
-    runtime: String,
-    model: Option<String>,
-}
-
-#[tauri::command]
-async fn install_model(request: InstallRequest) -> Result<Value, String> {
-    let script = script_path("scripts/model_runtime_install.mjs")?;
-    let mut args = vec!["--runtime".to_string(), request.runtime.clone()];
-    if let Some(model) = request.model.clone() {
-        if !model.trim().is_empty() {
-            args.push("--model".to_string());
-            args.push(model);
+    let mut result = serde_json::from_str::<Value>(&raw)
+        .map_err(|error| format!("Invalid first-run checks JSON: {error}"))?;
+    if let Some(object) = result.as_object_mut() {
+        object.insert("nodeRuntime".to_string(), node_json);
+        if !node.meets_minimum {
+            if object.get("status").and_then(Value::as_str) == Some("pass") {
+                object.insert("status".to_string(), Value::from("warn"));
+            }
+            if let Some(recommendations) = object
+                .get_mut("recommendations")
+                .and_then(Value::as_array_mut)
+            {
+                recommendations.extend(node.error.clone().map(Value::from));
+            }
         }
     }
-
-    let runtime = request.runtime;
-    let model = request.model.unwrap_or_default();
-    let output = tauri::async_runtime::spawn_blocking(move || run_node_script(&script, &args))
-        .await
-        .map_err(|error| format!("Task join error: {error}"))??;
-
-    if let Ok(parsed) = serde_json::from_str::<Value>(&output) {
-        return Ok(parsed);
-    }
-
-    Ok(serde_json::json!({
-        "ok": true,
-        "runtime": runtime,
-        "model": model,
-        "status": "installed",
-        "output": output,
-    }))
+    Ok(result)
 }
 
 #[tauri::command]
@@ -653,21 +812,28 @@ async fn list_projects() -> Result<Vec<Project>, String> {
 
 #[tauri::command]
 async fn create_project(request: CreateProjectRequest) -> Result<Project, String> {
+    let mut validator = validation::Validator::new();
+    validator.name("name", &request.name);
+    validator.project_settings("settings", &request.settings);
+    validator.finish()?;
+
     tauri::async_runtime::spawn_blocking(move || {
         let mut projects = read_projects()?;
         let now = now_iso();
 
         let project = Project {
             id: generate_project_id(),
-            name: request.name,
+            name: request.name.trim().to_string(),
             settings: request.settings,
-            status: "PROJECT_CREATED".to_string(),
+            status: ProjectStatus::ProjectCreated.as_str().to_string(),
+            parent_id: None,
             created_at: now.clone(),
             updated_at: now,
         };
 
         projects.push(project.clone());
         write_projects(&projects)?;
+        publish_project_status(&project, None);
         Ok(project)
     })
     .await
@@ -676,23 +842,35 @@ async fn create_project(request: CreateProjectRequest) -> Result<Project, String
 
 #[tauri::command]
 async fn update_project_settings(request: UpdateProjectSettingsRequest) -> Result<Project, String> {
+    let mut validator = validation::Validator::new();
+    validator.project_id("projectId", &request.project_id);
+    validator.project_settings("settings", &request.settings);
+    validator.finish()?;
+
     tauri::async_runtime::spawn_blocking(move || {
         let mut projects = read_projects()?;
         let now = now_iso();
-        let mut found: Option<Project> = None;
+        let mut found: Option<(Project, bool)> = None;
 
         for project in &mut projects {
             if project.id == request.project_id {
                 project.settings = request.settings.clone();
                 project.updated_at = now.clone();
-                project.status = "SETTINGS_SAVED".to_string();
-                found = Some(project.clone());
+                // Saving settings mid-pipeline keeps the project where it is.
+                let fresh = project.status == ProjectStatus::ProjectCreated.as_str();
+                if fresh {
+                    project.status = ProjectStatus::SettingsSaved.as_str().to_string();
+                }
+                found = Some((project.clone(), fresh));
                 break;
             }
         }
 
-        let project = found.ok_or_else(|| "Project not found.".to_string())?;
+        let (project, changed) = found.ok_or_else(|| "Project not found.".to_string())?;
         write_projects(&projects)?;
+        if changed {
+            publish_project_status(&project, Some(ProjectStatus::ProjectCreated.as_str()));
+        }
         Ok(project)
     })
     .await
@@ -700,11 +878,58 @@ async fn update_project_settings(request: UpdateProjectSettingsRequest) -> Resul
 }
 
 #[tauri::command]
-async fn ingest_media(request: MediaIngestRequest) -> Result<Value, String> {
+async fn ingest_media(app: tauri::AppHandle, request: MediaIngestRequest) -> Result<Value, String> {
+    let permit = scheduler::acquire(
+        &app,
+        scheduler::JobKind::Ingest,
+        request.priority,
+        &request.project_id,
+    )
+    .await?;
+    run_ingest_media(request).instrument(permit.span()).await
+}
+
+/// The file the ingest script reads: the download for http(s) inputs, and
+/// the media pool's copy while the pool is enabled.
+fn ingest_source(
+    project_id: &str,
+    input: &str,
+) -> Result<(String, Option<media_pool::PooledMedia>), String> {
+    let download = if url_ingest::is_url(input) {
+        Some(url_ingest::fetch(project_id, input)?)
+    } else {
+        None
+    };
+    if !settings::read_app_settings()?.media_pool.enabled {
+        let source = download.map_or_else(
+            || input.to_string(),
+            |path| path.to_string_lossy().to_string(),
+        );
+        return Ok((source, None));
+    }
+    let pooled = match download {
+        Some(path) => {
+            let pooled = media_pool::import_from(project_id, &path, input.trim().to_string())?;
+            // The pool keeps its own link or copy.
+            let _ = fs::remove_file(&path);
+            pooled
+        }
+        None => media_pool::import(project_id, input)?,
+    };
+    Ok((pooled.path.to_string_lossy().to_string(), Some(pooled)))
+}
+
+async fn run_ingest_media(request: MediaIngestRequest) -> Result<Value, String> {
     let script = script_path("scripts/media_ingest.mjs")?;
-    let args = vec![
+    let (source, pooled) = logging::spawn_blocking({
+        let (project_id, input) = (request.project_id.clone(), request.input.clone());
+        move || ingest_source(&project_id, &input)
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))??;
+    let mut args = vec![
         "--input".to_string(),
-        request.input.clone(),
+        source,
         "--project-id".to_string(),
         request.project_id.clone(),
         "--generate-proxy".to_string(),
@@ -720,29 +945,63 @@ async fn ingest_media(request: MediaIngestRequest) -> Result<Value, String> {
             "false".to_string()
         },
     ];
+    if let Some(pooled) = &pooled {
+        args.push("--content-hash".to_string());
+        args.push(pooled.hash.clone());
+        args.push("--original-path".to_string());
+        args.push(pooled.original_path.clone());
+    }
 
-    let raw = tauri::async_runtime::spawn_blocking(move || run_node_script(&script, &args))
+    let raw = logging::spawn_blocking(move || run_node_script(&script, &args))
         .await
         .map_err(|error| format!("Task join error: {error}"))??;
 
-    serde_json::from_str::<Value>(&raw)
-        .map_err(|error| format!("Invalid media ingest JSON: {error}"))
+    let mut result = serde_json::from_str::<Value>(&raw)
+        .map_err(|error| format!("Invalid media ingest JSON: {error}"))?;
+    let warnings = logging::spawn_blocking({
+        let payload = serde_json::json!({ "input": request.input, "result": result });
+        move || hooks::run(hooks::HookPoint::PostIngest, &request.project_id, payload)
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))??;
+    hooks::attach_warnings(&mut result, warnings);
+    Ok(result)
 }
 
 #[tauri::command]
 async fn create_rough_cut_timeline(
     request: CreateRoughCutTimelineRequest,
 ) -> Result<Timeline, String> {
+    let mut validator = validation::Validator::new();
+    validator.project_id("projectId", &request.project_id);
+    validator.fps("fps", request.fps);
+    validator.finish()?;
+
     tauri::async_runtime::spawn_blocking(move || {
-        let timeline = build_rough_cut_timeline(
-            request.project_id,
-            request.duration_us,
-            request.fps,
-            request
-                .source_ref
-                .unwrap_or_else(|| "source-video".to_string()),
-            request.remove_ranges.unwrap_or_default(),
-        );
+        let options = request.options.unwrap_or_default();
+        // Marked sponsor reads are never cut.
+        let protected = sponsors::protected_ranges(&request.project_id);
+        let timeline = match request.sources.filter(|sources| !sources.is_empty()) {
+            Some(sources) => build_multi_source_timeline(
+                request.project_id,
+                request.fps,
+                sources,
+                request.arrangement.unwrap_or_default(),
+                &options,
+                &protected,
+            ),
+            None => build_rough_cut_timeline(
+                request.project_id,
+                request.duration_us,
+                request.fps,
+                request
+                    .source_ref
+                    .unwrap_or_else(|| "source-video".to_string()),
+                request.remove_ranges.unwrap_or_default(),
+                &options,
+                &protected,
+            ),
+        };
 
         write_timeline(&timeline)?;
         Ok(timeline)
@@ -761,24 +1020,7 @@ async fn get_timeline(request: GetTimelineRequest) -> Result<Timeline, String> {
 #[tauri::command]
 async fn get_render_history(request: GetRenderHistoryRequest) -> Result<Value, String> {
     tauri::async_runtime::spawn_blocking(move || {
-        let file_path = render_history_file_path(&request.project_id)?;
-        if !file_path.exists() {
-            return Ok::<Value, String>(serde_json::json!({
-                "projectId": request.project_id,
-                "history": []
-            }));
-        }
-
-        let raw = fs::read_to_string(&file_path)
-            .map_err(|error| format!("Failed reading render history file: {error}"))?;
-        let parsed = serde_json::from_str::<Value>(&raw)
-            .map_err(|error| format!("Invalid render history JSON: {error}"))?;
-        let history = if parsed.is_array() {
-            parsed
-        } else {
-            serde_json::json!([])
-        };
-
+        let history = render_history::read_render_history(&request.project_id)?;
         Ok(serde_json::json!({
             "projectId": request.project_id,
             "history": history
@@ -833,31 +1075,95 @@ async fn get_project_telemetry(request: GetProjectTelemetryRequest) -> Result<Va
     .map_err(|error| format!("Task join error: {error}"))?
 }
 
+fn save_timeline_blocking(request: SaveTimelineRequest) -> Result<Timeline, String> {
+    let mut timeline = request.timeline;
+    speed::apply_clip_speeds(&mut timeline)?;
+    timeline.duration_us = timeline_stats::content_end_us(&timeline);
+    if request.reject_invalid.unwrap_or(false) {
+        let diagnostics = timeline_validation::validate(&timeline.project_id, &timeline);
+        if timeline_validation::has_errors(&diagnostics) {
+            return Err(timeline_validation::error_summary(&diagnostics));
+        }
+    }
+    let _save = timeline_merge::lock_saves();
+    let stored = timeline_merge::stored_timeline(&timeline.project_id)?;
+    timeline_merge::mark_user_edits(stored.as_ref(), &mut timeline);
+    timeline.version = timeline_merge::next_version(
+        stored.as_ref(),
+        timeline.version,
+        request.force.unwrap_or(false),
+    )?;
+    timeline.updated_at = now_iso();
+    write_timeline(&timeline)?;
+    if let Err(error) = autosave::clear_autosave(&timeline.project_id) {
+        tracing::warn!("Failed clearing autosave: {error}");
+    }
+    Ok(timeline)
+}
+
 #[tauri::command]
 async fn save_timeline(request: SaveTimelineRequest) -> Result<Timeline, String> {
-    tauri::async_runtime::spawn_blocking(move || {
-        let mut timeline = request.timeline;
-        timeline.version = timeline.version.saturating_add(1);
-        timeline.updated_at = now_iso();
-        write_timeline(&timeline)?;
-        Ok(timeline)
-    })
-    .await
-    .map_err(|error| format!("Task join error: {error}"))?
+    tauri::async_runtime::spawn_blocking(move || save_timeline_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
 }
 
 #[tauri::command]
-async fn start_editing(request: StartEditingRequest) -> Result<Value, String> {
+async fn start_editing(
+    app: tauri::AppHandle,
+    request: StartEditingRequest,
+) -> Result<StartEditingResponse, String> {
+    let permit = scheduler::acquire(
+        &app,
+        scheduler::JobKind::Transcription,
+        request.priority,
+        &request.project_id,
+    )
+    .await?;
+    let project_id = request.project_id.clone();
+    let outcome = run_start_editing(request).instrument(permit.span()).await;
+    drop(permit);
+    notifications::notify_job(&app, notifications::NotifiedJob::StartEditing, &project_id, &outcome);
+    outcome
+}
+
+async fn run_start_editing(request: StartEditingRequest) -> Result<StartEditingResponse, String> {
     let script = script_path("scripts/start_editing_pipeline.mjs")?;
     let mode = request.mode.unwrap_or_else(|| "hybrid".to_string());
     let language = request.language.unwrap_or_else(|| "en".to_string());
     let fps = request.fps.unwrap_or(30);
+    let mut validator = validation::Validator::new();
+    validator.project_id("projectId", &request.project_id);
+    validator.language("language", &language);
+    validator.fps("fps", fps);
+    validator.finish()?;
     let source_ref = request
         .source_ref
         .unwrap_or_else(|| "source-video".to_string());
-    let fallback_policy = request.fallback_policy.unwrap_or_default();
-    let transcription_model = request.transcription_model.unwrap_or_default();
-    let cut_planner_model = request.cut_planner_model.unwrap_or_default();
+    let policy = match &request.fallback_policy {
+        Some(input) => input.resolve()?,
+        None => fallback_policy::FallbackPolicy::default(),
+    };
+    let project_config = logging::spawn_blocking({
+        let project_id = request.project_id.clone();
+        move || project_config::for_run(&project_id)
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))??;
+    let defaults = fallback_policy::Candidate {
+        transcription_model: request
+            .transcription_model
+            .filter(|model| !model.trim().is_empty())
+            .or(project_config.models.transcription.clone()),
+        cut_planner_model: request
+            .cut_planner_model
+            .filter(|model| !model.trim().is_empty())
+            .or(project_config.models.cut_planner.clone()),
+        ..fallback_policy::Candidate::default()
+    };
+    let rough_cut_options = request.rough_cut_options.unwrap_or_default();
+    let force_restart = request.force_restart.unwrap_or(false);
+    let merge_edits = request.merge_edits.unwrap_or(false);
 
     let mut args = vec![
         "--project-id".to_string(),
@@ -865,85 +1171,211 @@ async fn start_editing(request: StartEditingRequest) -> Result<Value, String> {
         "--input".to_string(),
         request.input.clone(),
         "--mode".to_string(),
-        mode,
+        mode.clone(),
         "--language".to_string(),
-        language,
+        language.clone(),
         "--fps".to_string(),
         fps.to_string(),
         "--source-ref".to_string(),
         source_ref.clone(),
     ];
+    args.extend(project_config.script_args());
 
-    if !fallback_policy.trim().is_empty() {
-        args.push("--fallback-policy".to_string());
-        args.push(fallback_policy);
-    }
-    if !transcription_model.trim().is_empty() {
-        args.push("--transcription-model".to_string());
-        args.push(transcription_model);
-    }
-    if !cut_planner_model.trim().is_empty() {
-        args.push("--cut-planner-model".to_string());
-        args.push(cut_planner_model);
-    }
-
-    let raw = tauri::async_runtime::spawn_blocking(move || run_node_script(&script, &args))
-        .await
-        .map_err(|error| format!("Task join error: {error}"))??;
-
-    let pipeline: Value = serde_json::from_str(&raw)
-        .map_err(|error| format!("Invalid start editing JSON: {error}"))?;
+    let ((raw, retries), fallback) = logging::spawn_blocking({
+        let project_id = request.project_id.clone();
+        let input = request.input.clone();
+        move || {
+            let retry = settings::read_app_settings()?.script_retry;
+            if force_restart {
+                checkpoints::clear_checkpoints(&project_id)?;
+            }
+            fallback_policy::run_with_fallback(
+                &project_id,
+                "start-editing",
+                policy,
+                &defaults,
+                |candidate| {
+                    let mut args = args.clone();
+                    args.extend(candidate.script_args());
+                    let keys = checkpoints::stage_keys(
+                        &input,
+                        &mode,
+                        &language,
+                        candidate.transcription_model.as_deref(),
+                        candidate.cut_planner_model.as_deref(),
+                    );
+                    args.push("--checkpoint-keys".to_string());
+                    args.push(serde_json::to_string(&keys).unwrap_or_default());
+                    script_retry::run_with_retry(
+                        &project_id,
+                        "start-editing",
+                        &retry,
+                        || {
+                            // Re-checked per attempt: a failed attempt may
+                            // have completed stages before it stopped.
+                            let mut args = args.clone();
+                            if let Some(stage) = checkpoints::resume_stage(&project_id, &keys) {
+                                tracing::info!(
+                                    "Resuming start editing after the {} stage",
+                                    stage.as_str()
+                                );
+                                args.push("--resume-from".to_string());
+                                args.push(stage.as_str().to_string());
+                            }
+                            run_node_script(&script, &args)
+                        },
+                    )
+                },
+            )
+        }
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))??;
 
-    let duration_us = pipeline
-        .get("durationUs")
-        .and_then(Value::as_u64)
-        .ok_or_else(|| "Pipeline response missing durationUs.".to_string())?;
+    let pipeline = StartEditingResult::parse(&raw, &request.project_id)?;
 
-    let remove_ranges: Vec<TimeRange> = serde_json::from_value(
-        pipeline
-            .get("removeRanges")
-            .cloned()
-            .unwrap_or_else(|| serde_json::json!([])),
-    )
-    .map_err(|error| format!("Invalid removeRanges payload: {error}"))?;
+    let mut used_models = fallback
+        .used
+        .iter()
+        .flat_map(|used| [used.transcription_model.clone(), used.cut_planner_model.clone()])
+        .flatten()
+        .collect::<Vec<_>>();
+    if let Some(model) = &pipeline.transcription.adapter.model {
+        used_models.push(model.clone());
+    }
+    let _ = logging::spawn_blocking(move || {
+        if let Err(error) = model_registry::touch_models(&used_models) {
+            tracing::warn!("Failed updating model last-used times: {error}");
+        }
+    })
+    .await;
 
-    let timeline = tauri::async_runtime::spawn_blocking({
+    let duration_us = pipeline.duration_us;
+    let (timeline, merge) = logging::spawn_blocking({
         let project_id = request.project_id.clone();
         let source_ref = source_ref.clone();
+        let transcript_path = pipeline.transcript_path.clone();
+        let planned_ranges = pipeline.remove_ranges.clone();
         move || {
-            let timeline =
-                build_rough_cut_timeline(project_id, duration_us, fps, source_ref, remove_ranges);
+            persist_pipeline_transcript(&project_id, transcript_path.as_deref(), &source_ref);
+            let proposed = cuts::record_proposed_cuts(
+                &project_id,
+                &source_ref,
+                duration_us,
+                fps,
+                planned_ranges,
+                rough_cut_options,
+            )?;
+            let _save = timeline_merge::lock_saves();
+            let previous = if merge_edits {
+                timeline_merge::stored_timeline(&project_id)?
+            } else {
+                read_timeline(&project_id).ok()
+            };
+            let protected = previous
+                .as_ref()
+                .map(clip_flags::protected_ranges)
+                .unwrap_or_default();
+            let mut timeline = build_rough_cut_timeline(
+                project_id.clone(),
+                duration_us,
+                fps,
+                source_ref.clone(),
+                clip_flags::spare_protected(
+                    cuts::accepted_ranges(&proposed),
+                    &source_ref,
+                    &protected,
+                ),
+                &proposed.options,
+                &sponsors::protected_ranges(&project_id),
+            );
+            let merge = match previous.filter(|_| merge_edits) {
+                Some(previous) => Some(timeline_merge::preserve_user_edits(
+                    &previous,
+                    &mut timeline,
+                )),
+                None => {
+                    clip_flags::carry_over(&mut timeline, &protected);
+                    None
+                }
+            };
             write_timeline(&timeline)?;
-            Ok::<Timeline, String>(timeline)
+            Ok::<_, String>((timeline, merge))
         }
     })
     .await
     .map_err(|error| format!("Task join error: {error}"))??;
 
-    let _ = tauri::async_runtime::spawn_blocking({
+    let _ = logging::spawn_blocking({
         let project_id = request.project_id.clone();
-        move || update_project_status(&project_id, "ROUGH_CUT_READY")
+        move || {
+            // The run finished, so there is nothing left to resume.
+            if let Err(error) = checkpoints::clear_checkpoints(&project_id) {
+                tracing::warn!("{error}");
+            }
+            mark_timeline_written(&project_id, ProjectStatus::RoughCutReady)
+        }
     })
     .await
     .map_err(|error| format!("Task join error: {error}"))??;
 
-    Ok(serde_json::json!({
-        "ok": true,
-        "pipeline": pipeline,
-        "timeline": timeline
-    }))
+    Ok(StartEditingResponse {
+        ok: true,
+        pipeline,
+        timeline,
+        merge,
+        fallback,
+        script_attempts: retries,
+    })
 }
 
 #[tauri::command]
-async fn edit_now(request: EditNowRequest) -> Result<Value, String> {
+async fn edit_now(
+    app: tauri::AppHandle,
+    request: EditNowRequest,
+) -> Result<EditNowResponse, String> {
+    let permit = scheduler::acquire(
+        &app,
+        scheduler::JobKind::Planning,
+        request.priority,
+        &request.project_id,
+    )
+    .await?;
+    let project_id = request.project_id.clone();
+    let outcome = run_edit_now(request).instrument(permit.span()).await;
+    drop(permit);
+    notifications::notify_job(&app, notifications::NotifiedJob::EditNow, &project_id, &outcome);
+    outcome
+}
+
+async fn run_edit_now(request: EditNowRequest) -> Result<EditNowResponse, String> {
     let script = script_path("scripts/edit_now_pipeline.mjs")?;
     let fps = request.fps.unwrap_or(30);
+    let mut validator = validation::Validator::new();
+    validator.project_id("projectId", &request.project_id);
+    validator.fps("fps", fps);
+    validator.finish()?;
     let source_ref = request
         .source_ref
         .unwrap_or_else(|| "source-video".to_string());
     let fetch_external = request.fetch_external.unwrap_or(true);
-    let fallback_policy = request.fallback_policy.unwrap_or_default();
-    let template_planner_model = request.template_planner_model.unwrap_or_default();
+    let policy = match &request.fallback_policy {
+        Some(input) => input.resolve()?,
+        None => fallback_policy::FallbackPolicy::default(),
+    };
+    let project_config = logging::spawn_blocking({
+        let project_id = request.project_id.clone();
+        move || project_config::for_run(&project_id)
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))??;
+    let defaults = fallback_policy::Candidate {
+        template_planner_model: request
+            .template_planner_model
+            .filter(|model| !model.trim().is_empty())
+            .or(project_config.models.template_planner.clone()),
+        ..fallback_policy::Candidate::default()
+    };
 
     let mut args = vec![
         "--project-id".to_string(),
@@ -959,48 +1391,204 @@ async fn edit_now(request: EditNowRequest) -> Result<Value, String> {
             "false".to_string()
         },
     ];
+    args.extend(project_config.script_args());
 
-    if !fallback_policy.trim().is_empty() {
-        args.push("--fallback-policy".to_string());
-        args.push(fallback_policy);
-    }
-    if !template_planner_model.trim().is_empty() {
-        args.push("--template-planner-model".to_string());
-        args.push(template_planner_model);
-    }
+    let ((raw, retries), fallback) = logging::spawn_blocking({
+        let project_id = request.project_id.clone();
+        move || {
+            let retry = settings::read_app_settings()?.script_retry;
+            fallback_policy::run_with_fallback(
+                &project_id,
+                "edit-now",
+                policy,
+                &defaults,
+                |candidate| {
+                    let mut args = args.clone();
+                    args.extend(candidate.script_args());
+                    script_retry::run_with_retry(&project_id, "edit-now", &retry, || {
+                        run_node_script(&script, &args)
+                    })
+                },
+            )
+        }
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))??;
 
-    let raw = tauri::async_runtime::spawn_blocking(move || run_node_script(&script, &args))
-        .await
-        .map_err(|error| format!("Task join error: {error}"))??;
+    let result = EditNowResult::parse(&raw, &request.project_id)?;
 
-    let result: Value =
-        serde_json::from_str(&raw).map_err(|error| format!("Invalid edit now JSON: {error}"))?;
+    let registered = logging::spawn_blocking({
+        let project_id = request.project_id.clone();
+        let suggestions = result.asset_suggestions.clone();
+        move || external_assets::register_from_edit_now(&project_id, &suggestions)
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?;
+    if let Err(error) = registered {
+        tracing::warn!("Failed recording fetched assets: {error}");
+    }
 
-    let _ = tauri::async_runtime::spawn_blocking({
+    let _ = logging::spawn_blocking({
         let project_id = request.project_id.clone();
-        move || update_project_status(&project_id, "ENRICHED_TIMELINE_READY")
+        move || mark_timeline_written(&project_id, ProjectStatus::EnrichedTimelineReady)
     })
     .await
     .map_err(|error| format!("Task join error: {error}"))??;
 
-    Ok(result)
+    Ok(EditNowResponse {
+        result,
+        fallback,
+        script_attempts: retries,
+    })
 }
 
 #[tauri::command]
-async fn render_video(request: RenderVideoRequest) -> Result<Value, String> {
+async fn render_video(app: tauri::AppHandle, request: RenderVideoRequest) -> Result<Value, String> {
+    let kind = if request.worker.is_some() {
+        scheduler::JobKind::RemoteRender
+    } else {
+        scheduler::JobKind::Render
+    };
+    let permit = scheduler::acquire(&app, kind, request.priority, &request.project_id).await?;
+    let project_id = request.project_id.clone();
+    let outcome = run_render_video(request).instrument(permit.span()).await;
+    drop(permit);
+    notifications::notify_job(&app, notifications::NotifiedJob::Render, &project_id, &outcome);
+    outcome
+}
+
+/// Records a failed render. The render error is what the caller needs, so a
+/// refused or failed status update is only logged.
+async fn mark_render_failed(project_id: &str) {
+    let project_id = project_id.to_string();
+    let outcome = logging::spawn_blocking({
+        let project_id = project_id.clone();
+        move || update_project_status(&project_id, ProjectStatus::RenderFailed)
+    })
+    .await;
+    match outcome {
+        Ok(Ok(())) => {}
+        Ok(Err(error)) => tracing::warn!("Failed marking {project_id} as RENDER_FAILED: {error}"),
+        Err(error) => tracing::warn!("Task join error: {error}"),
+    }
+}
+
+async fn run_render_video(request: RenderVideoRequest) -> Result<Value, String> {
+    // Remote renders pass the same attribution gate as local ones.
+    logging::spawn_blocking({
+        let project_id = request.project_id.clone();
+        move || external_assets::check_render_attribution(&project_id)
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))??;
+
+    if let Some(worker_id) = request.worker.clone() {
+        return logging::spawn_blocking(move || {
+            render_workers::render_on_worker(&worker_id, request)
+        })
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?;
+    }
     let script = script_path("scripts/render_pipeline.mjs")?;
     let output_name = request.output_name.unwrap_or_default();
     let burn_subtitles = request.burn_subtitles.unwrap_or(false);
+    let watermark = request.watermark.unwrap_or(true);
+    let verify = request.verify.unwrap_or(true);
+    let use_cache = request.use_cache.unwrap_or(true);
     let quality = request.quality.unwrap_or_else(|| "balanced".to_string());
+    let target_lufs = match request.normalize_loudness {
+        Some(true) => Some(loudness::validate_target_lufs(
+            request.target_lufs.unwrap_or(loudness::DEFAULT_TARGET_LUFS),
+        )?),
+        _ => None,
+    };
 
-    let _ = tauri::async_runtime::spawn_blocking({
+    logging::spawn_blocking({
         let project_id = request.project_id.clone();
-        move || update_project_status(&project_id, "RENDER_IN_PROGRESS")
+        move || update_project_status(&project_id, ProjectStatus::RenderInProgress)
     })
     .await
     .map_err(|error| format!("Task join error: {error}"))??;
 
-    let args = vec![
+    // Snapshot before the script reads the timeline so edits made while the
+    // render runs show up as staleness.
+    let snapshot = logging::spawn_blocking({
+        let project_id = request.project_id.clone();
+        move || {
+            read_timeline(&project_id).map(|timeline| render_history::timeline_snapshot(&timeline))
+        }
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+    .ok();
+
+    // Enabled plugins run on a copy of the timeline; the script renders
+    // their result and the saved timeline is left untouched. Pre-render
+    // hooks see whichever timeline will be rendered.
+    let (staged_timeline, mut hook_warnings) = match logging::spawn_blocking({
+        let project_id = request.project_id.clone();
+        let output_name = output_name.clone();
+        let quality = quality.clone();
+        move || {
+            let staged = plugins::run_pre_render(&project_id)?;
+            let timeline_path = match &staged {
+                Some(path) => path.clone(),
+                None => timeline_file_path(&project_id)?,
+            };
+            let payload = serde_json::json!({
+                "timelinePath": timeline_path.to_string_lossy(),
+                "outputName": output_name,
+                "quality": quality,
+                "burnSubtitles": burn_subtitles,
+            });
+            let warnings = hooks::run(hooks::HookPoint::PreRender, &project_id, payload)?;
+            Ok::<_, String>((staged, warnings))
+        }
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+    {
+        Ok(staged) => staged,
+        Err(error_message) => {
+            mark_render_failed(&request.project_id).await;
+            return Err(error_message);
+        }
+    };
+
+    // Captions are rebuilt from the transcript so they follow the current
+    // cuts and the saved subtitle style; the pipeline SRT is the fallback.
+    let styled_subtitles = if burn_subtitles {
+        let project_id = request.project_id.clone();
+        let staged_timeline = staged_timeline.clone();
+        logging::spawn_blocking(move || {
+            let timeline = match &staged_timeline {
+                Some(path) => plugins::read_staged_timeline(path)?,
+                None => read_timeline(&project_id)?,
+            };
+            let frame_size = subtitles::timeline_frame_size(&project_id, &timeline);
+            let output = subtitles::styled_subtitles_path(&project_id)?;
+            subtitles::write_styled_subtitles(&project_id, &timeline, frame_size, &output)
+        })
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+        .unwrap_or_else(|error| {
+            tracing::warn!("Falling back to unstyled subtitles: {error}");
+            None
+        })
+    } else {
+        None
+    };
+
+    let encoder = match request.encoder.clone() {
+        Some(requested) => {
+            logging::spawn_blocking(move || hw_encoders::resolve_encoder(&requested))
+                .await
+                .map_err(|error| format!("Task join error: {error}"))??
+        }
+        None => None,
+    };
+
+    let mut args = vec![
         "--project-id".to_string(),
         request.project_id.clone(),
         "--output-name".to_string(),
@@ -1014,40 +1602,194 @@ async fn render_video(request: RenderVideoRequest) -> Result<Value, String> {
         "--quality".to_string(),
         quality,
     ];
+    if let Some(path) = styled_subtitles {
+        args.push("--subtitles-ass".to_string());
+        args.push(path.to_string_lossy().to_string());
+    }
+    if let Some(encoder) = encoder {
+        args.push("--encoder".to_string());
+        args.push(encoder);
+    }
+    let rendered_timeline = staged_timeline.clone();
+    if let Some(path) = staged_timeline {
+        args.push("--timeline-path".to_string());
+        args.push(path.to_string_lossy().to_string());
+    }
+    if target_lufs.is_some() {
+        args.push("--loudnorm".to_string());
+        args.push("false".to_string());
+    }
 
-    let raw =
-        match tauri::async_runtime::spawn_blocking(move || run_node_script(&script, &args)).await {
-            Ok(Ok(payload)) => payload,
-            Ok(Err(error_message)) => {
-                let _ = tauri::async_runtime::spawn_blocking({
-                    let project_id = request.project_id.clone();
-                    move || update_project_status(&project_id, "RENDER_FAILED")
-                })
-                .await
-                .map_err(|error| format!("Task join error: {error}"))??;
-                return Err(error_message);
-            }
-            Err(error) => {
-                let _ = tauri::async_runtime::spawn_blocking({
-                    let project_id = request.project_id.clone();
-                    move || update_project_status(&project_id, "RENDER_FAILED")
-                })
-                .await
-                .map_err(|join_error| format!("Task join error: {join_error}"))??;
-                return Err(format!("Task join error: {error}"));
+    let raw = match logging::spawn_blocking({
+        let project_id = request.project_id.clone();
+        move || {
+            args.extend(project_config::for_run(&project_id)?.script_args());
+            args.extend(branding::render_args(&project_id, watermark)?);
+            args.extend(render_cache::render_args(&project_id, use_cache)?);
+            if let Some(lut) = luts::project_lut(&project_id) {
+                args.push("--lut".to_string());
+                args.push(lut.to_string_lossy().to_string());
             }
-        };
+            run_node_script(&script, &args)
+        }
+    })
+    .await
+    {
+        Ok(Ok(payload)) => payload,
+        Ok(Err(error_message)) => {
+            mark_render_failed(&request.project_id).await;
+            return Err(error_message);
+        }
+        Err(error) => {
+            mark_render_failed(&request.project_id).await;
+            return Err(format!("Task join error: {error}"));
+        }
+    };
 
-    let result: Value =
+    let mut result: Value =
         serde_json::from_str(&raw).map_err(|error| format!("Invalid render JSON: {error}"))?;
+    if result.get("segmentCache").is_some_and(Value::is_object) {
+        let project_id = request.project_id.clone();
+        let recorded = result.clone();
+        let cache =
+            logging::spawn_blocking(move || render_cache::record_render(&project_id, &recorded))
+                .await
+                .map_err(|error| format!("Task join error: {error}"))?;
+        if let Err(error) = cache {
+            tracing::warn!("Failed updating render cache index: {error}");
+        }
+    }
 
-    let _ = tauri::async_runtime::spawn_blocking({
+    let render_id = result
+        .get("renderId")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    if let (Some(target_lufs), Some(output_path)) =
+        (target_lufs, loudness::render_output_path(&result))
+    {
         let project_id = request.project_id.clone();
-        move || update_project_status(&project_id, "RENDER_DONE")
+        let render_id = render_id.clone();
+        let normalized = logging::spawn_blocking(move || {
+            let report = loudness::normalize_file(&output_path, target_lufs)?;
+            if let Some(render_id) = render_id {
+                if let Err(error) =
+                    loudness::record_render_loudness(&project_id, &render_id, &report)
+                {
+                    tracing::warn!("Failed recording render loudness: {error}");
+                }
+            }
+            Ok::<_, String>(report)
+        })
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?;
+        if let Some(object) = result.as_object_mut() {
+            match normalized {
+                Ok(report) => {
+                    object.insert("loudnormApplied".to_string(), Value::Bool(true));
+                    object.insert(
+                        "loudness".to_string(),
+                        serde_json::to_value(report).unwrap_or(Value::Null),
+                    );
+                }
+                Err(error) => {
+                    if let Some(warnings) =
+                        object.get_mut("warnings").and_then(Value::as_array_mut)
+                    {
+                        warnings.push(Value::from(format!(
+                            "Audio loudness normalization failed (non-critical): {error}"
+                        )));
+                    }
+                }
+            }
+        }
+    }
+    if let Some(output_path) = loudness::render_output_path(&result).filter(|_| verify) {
+        let project_id = request.project_id.clone();
+        let render_id = render_id.clone();
+        // Branding stingers lengthen the output beyond the timeline.
+        let stinger_us = result
+            .get("stingerDurationUs")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        let verified = logging::spawn_blocking(move || {
+            let timeline = match &rendered_timeline {
+                Some(path) => plugins::read_staged_timeline(path)?,
+                None => read_timeline(&project_id)?,
+            };
+            let expected_us = timeline_stats::content_end_us(&timeline) + stinger_us;
+            let verification = render_verification::verify_render(&output_path, expected_us)?;
+            if let Some(render_id) = render_id {
+                if let Err(error) = render_verification::record_render_verification(
+                    &project_id,
+                    &render_id,
+                    &verification,
+                ) {
+                    tracing::warn!("Failed recording render verification: {error}");
+                }
+            }
+            Ok::<_, String>(verification)
+        })
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?;
+        if let Some(object) = result.as_object_mut() {
+            match verified {
+                Ok(verification) => {
+                    object.insert(
+                        "verification".to_string(),
+                        serde_json::to_value(verification).unwrap_or(Value::Null),
+                    );
+                }
+                Err(error) => {
+                    if let Some(warnings) =
+                        object.get_mut("warnings").and_then(Value::as_array_mut)
+                    {
+                        warnings.push(Value::from(format!(
+                            "Render verification could not run (non-critical): {error}"
+                        )));
+                    }
+                }
+            }
+        }
+    }
+    if let (Some(render_id), Some(snapshot)) = (render_id, snapshot) {
+        let project_id = request.project_id.clone();
+        let recorded = snapshot.clone();
+        let stamped = logging::spawn_blocking(move || {
+            render_history::record_timeline_snapshot(&project_id, &render_id, &recorded)
+        })
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?;
+        match stamped {
+            Ok(()) => {
+                if let Some(object) = result.as_object_mut() {
+                    object.insert("timelineId".to_string(), Value::from(snapshot.timeline_id));
+                    object.insert(
+                        "timelineVersion".to_string(),
+                        Value::from(snapshot.timeline_version),
+                    );
+                    object.insert("timelineHash".to_string(), Value::from(snapshot.timeline_hash));
+                }
+            }
+            Err(error) => tracing::warn!("Failed recording render timeline snapshot: {error}"),
+        }
+    }
+
+    let _ = logging::spawn_blocking({
+        let project_id = request.project_id.clone();
+        move || update_project_status(&project_id, ProjectStatus::RenderDone)
     })
     .await
     .map_err(|error| format!("Task join error: {error}"))??;
 
+    hook_warnings.extend(
+        logging::spawn_blocking({
+            let payload = serde_json::json!({ "result": result });
+            move || hooks::run(hooks::HookPoint::PostRender, &request.project_id, payload)
+        })
+        .await
+        .map_err(|error| format!("Task join error: {error}"))??,
+    );
+    hooks::attach_warnings(&mut result, hook_warnings);
     Ok(result)
 }
 
@@ -1060,6 +1802,7 @@ async fn open_path(request: OpenPathRequest) -> Result<Value, String> {
     let reveal = request.reveal.unwrap_or(true);
 
     let status = tauri::async_runtime::spawn_blocking(move || {
+        let target_path = path_safety::ensure_openable(Path::new(&target_path))?;
         let mut command = Command::new("open");
         if reveal {
             command.arg("-R");
@@ -1095,6 +1838,7 @@ struct TranscribeRequest {
     source_ref: Option<String>,
     fallback_policy: Option<String>,
     transcription_model: Option<String>,
+    priority: Option<scheduler::Priority>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -1203,13 +1947,30 @@ struct AgenticProgressRequest {
 // ── Pipeline: Standalone Transcription ──────────────────────────────────
 
 #[tauri::command]
-async fn pipeline_transcribe(request: TranscribeRequest) -> Result<Value, String> {
+async fn pipeline_transcribe(
+    app: tauri::AppHandle,
+    request: TranscribeRequest,
+) -> Result<Value, String> {
+    let permit = scheduler::acquire(
+        &app,
+        scheduler::JobKind::Transcription,
+        request.priority,
+        &request.project_id,
+    )
+    .await?;
+    run_pipeline_transcribe(request).instrument(permit.span()).await
+}
+
+async fn run_pipeline_transcribe(request: TranscribeRequest) -> Result<Value, String> {
     let script = script_path("scripts/transcribe_only.mjs")?;
-    let root = workspace_root()?;
-    let p_dir = root.join("desktop").join("data").join(&request.project_id);
+    let p_dir = path_safety::project_dir(&request.project_id)?;
     let mode = request.mode.unwrap_or_else(|| "hybrid".to_string());
     let language = request.language.unwrap_or_else(|| "en".to_string());
+    let mut validator = validation::Validator::new();
+    validator.language("language", &language);
+    validator.finish()?;
     let source_ref = request.source_ref.unwrap_or_else(|| "source-video".to_string());
+    let source_ref_for_store = source_ref.clone();
 
     let mut args = vec![
         "--project-id".to_string(), request.project_id.clone(),
@@ -1223,20 +1984,26 @@ async fn pipeline_transcribe(request: TranscribeRequest) -> Result<Value, String
     if let Some(tm) = request.transcription_model { if !tm.is_empty() { args.push("--transcription-model".to_string()); args.push(tm); } }
 
     let pid = request.project_id.clone();
-    let _ = tauri::async_runtime::spawn_blocking({
+    let _ = logging::spawn_blocking({
         let pid = pid.clone();
-        move || update_project_status(&pid, "TRANSCRIBING")
+        move || update_project_status(&pid, ProjectStatus::Transcribing)
     }).await;
 
-    let raw = tauri::async_runtime::spawn_blocking(move || run_node_script(&script, &args))
+    let raw = logging::spawn_blocking(move || run_node_script(&script, &args))
         .await.map_err(|e| format!("Task join error: {e}"))??;
+    let result = serde_json::from_str::<Value>(&raw).map_err(|e| format!("Invalid JSON: {e}"))?;
 
-    let _ = tauri::async_runtime::spawn_blocking({
+    let _ = logging::spawn_blocking({
         let pid2 = pid.clone();
-        move || update_project_status(&pid2, "TRANSCRIPT_READY")
+        let result = result.clone();
+        move || {
+            let transcript_path = result.get("transcriptPath").and_then(Value::as_str);
+            persist_pipeline_transcript(&pid2, transcript_path, &source_ref_for_store);
+            update_project_status(&pid2, ProjectStatus::TranscriptReady)
+        }
     }).await;
 
-    serde_json::from_str::<Value>(&raw).map_err(|e| format!("Invalid JSON: {e}"))
+    Ok(result)
 }
 
 // ── Pipeline: Standalone Cut Planning ───────────────────────────────────
@@ -1244,8 +2011,7 @@ async fn pipeline_transcribe(request: TranscribeRequest) -> Result<Value, String
 #[tauri::command]
 async fn pipeline_cut_plan(request: CutPlanRequest) -> Result<Value, String> {
     let script = script_path("scripts/cut_plan_only.mjs")?;
-    let root = workspace_root()?;
-    let p_dir = root.join("desktop").join("data").join(&request.project_id);
+    let p_dir = path_safety::project_dir(&request.project_id)?;
     let source_ref = request.source_ref.unwrap_or_else(|| "source-video".to_string());
     let mode = request.mode.unwrap_or_else(|| "heuristic".to_string());
 
@@ -1262,7 +2028,7 @@ async fn pipeline_cut_plan(request: CutPlanRequest) -> Result<Value, String> {
     let pid = request.project_id.clone();
     let _ = tauri::async_runtime::spawn_blocking({
         let pid = pid.clone();
-        move || update_project_status(&pid, "PLANNING_CUTS")
+        move || update_project_status(&pid, ProjectStatus::PlanningCuts)
     }).await;
 
     let raw = tauri::async_runtime::spawn_blocking(move || run_node_script(&script, &args))
@@ -1270,7 +2036,7 @@ async fn pipeline_cut_plan(request: CutPlanRequest) -> Result<Value, String> {
 
     let _ = tauri::async_runtime::spawn_blocking({
         let pid2 = pid.clone();
-        move || update_project_status(&pid2, "CUTS_READY")
+        move || update_project_status(&pid2, ProjectStatus::CutsReady)
     }).await;
 
     serde_json::from_str::<Value>(&raw).map_err(|e| format!("Invalid JSON: {e}"))
@@ -1281,8 +2047,7 @@ async fn pipeline_cut_plan(request: CutPlanRequest) -> Result<Value, String> {
 #[tauri::command]
 async fn pipeline_overlay_plan_chunk(request: OverlayPlanChunkRequest) -> Result<Value, String> {
     let script = script_path("scripts/overlay_plan_chunk.mjs")?;
-    let root = workspace_root()?;
-    let p_dir = root.join("desktop").join("data").join(&request.project_id);
+    let p_dir = path_safety::project_dir(&request.project_id)?;
     let chunk_index = request.chunk_index.unwrap_or(0);
     let chunk_start = request.chunk_start_us.unwrap_or(0);
     let chunk_end = request.chunk_end_us.unwrap_or(60_000_000);
@@ -1310,8 +2075,7 @@ async fn pipeline_overlay_plan_chunk(request: OverlayPlanChunkRequest) -> Result
 #[tauri::command]
 async fn pipeline_fetch_asset(request: FetchAssetRequest) -> Result<Value, String> {
     let script = script_path("scripts/fetch_free_assets.mjs")?;
-    let root = workspace_root()?;
-    let p_dir = root.join("desktop").join("data").join(&request.project_id);
+    let p_dir = path_safety::project_dir(&request.project_id)?;
     let kind = request.kind.unwrap_or_else(|| "image".to_string());
     let provider = request.provider.unwrap_or_else(|| "pexels".to_string());
 
@@ -1334,8 +2098,7 @@ async fn pipeline_fetch_asset(request: FetchAssetRequest) -> Result<Value, Strin
 #[tauri::command]
 async fn agentic_edit(request: AgenticEditRequest) -> Result<Value, String> {
     let script = script_path("scripts/agentic_editing_pipeline.mjs")?;
-    let root = workspace_root()?;
-    let p_dir = root.join("desktop").join("data").join(&request.project_id);
+    let p_dir = path_safety::project_dir(&request.project_id)?;
     let language = request.language.unwrap_or_else(|| "hi".to_string());
     let fps = request.fps.unwrap_or(30);
     let mode = request.mode.unwrap_or_else(|| "hybrid".to_string());
@@ -1358,7 +2121,7 @@ async fn agentic_edit(request: AgenticEditRequest) -> Result<Value, String> {
     let pid = request.project_id.clone();
     let _ = tauri::async_runtime::spawn_blocking({
         let pid = pid.clone();
-        move || update_project_status(&pid, "AGENTIC_EDIT_IN_PROGRESS")
+        move || update_project_status(&pid, ProjectStatus::AgenticEditInProgress)
     }).await;
 
     let raw = tauri::async_runtime::spawn_blocking(move || run_node_script(&script, &args))
@@ -1366,7 +2129,7 @@ async fn agentic_edit(request: AgenticEditRequest) -> Result<Value, String> {
 
     let _ = tauri::async_runtime::spawn_blocking({
         let pid2 = pid.clone();
-        move || update_project_status(&pid2, "AGENTIC_EDIT_DONE")
+        move || update_project_status(&pid2, ProjectStatus::AgenticEditDone)
     }).await;
 
     serde_json::from_str::<Value>(&raw).map_err(|e| format!("Invalid JSON: {e}"))
@@ -1377,8 +2140,7 @@ async fn agentic_edit(request: AgenticEditRequest) -> Result<Value, String> {
 #[tauri::command]
 async fn agentic_edit_progress(request: AgenticProgressRequest) -> Result<Value, String> {
     tauri::async_runtime::spawn_blocking(move || {
-        let root = workspace_root()?;
-        let progress_file = root.join("desktop").join("data").join(&request.project_id).join("agent_state.json");
+        let progress_file = path_safety::project_dir(&request.project_id)?.join("agent_state.json");
         if !progress_file.exists() {
             return Ok(serde_json::json!({ "status": "idle", "percent": 0 }));
         }
@@ -1393,8 +2155,7 @@ async fn agentic_edit_progress(request: AgenticProgressRequest) -> Result<Value,
 #[tauri::command]
 async fn export_fcpxml(request: ExportFcpxmlRequest) -> Result<Value, String> {
     let script = script_path("scripts/export_fcpxml.mjs")?;
-    let root = workspace_root()?;
-    let p_dir = root.join("desktop").join("data").join(&request.project_id);
+    let p_dir = path_safety::project_dir(&request.project_id)?;
     let output = p_dir.join("project.fcpxml");
 
     let args = vec![
@@ -1548,8 +2309,7 @@ async fn ollama_pull_model(request: OllamaPullRequest) -> Result<Value, String>
 #[tauri::command]
 async fn get_project_data(request: ProjectDataRequest) -> Result<Value, String> {
     tauri::async_runtime::spawn_blocking(move || {
-        let root = workspace_root()?;
-        let file_path = root.join("desktop").join("data").join(&request.project_id).join(&request.file_name);
+        let file_path = path_safety::project_file(&request.project_id, &request.file_name)?;
         if !file_path.exists() {
             return Err("Report not found".to_string());
         }
@@ -1566,8 +2326,7 @@ async fn save_project_data(request: SaveProjectDataRequest) -> Result<Value, Str
         return Err(format!("Writing to {} is not allowed", request.file_name));
     }
     tauri::async_runtime::spawn_blocking(move || {
-        let root = workspace_root()?;
-        let file_path = root.join("desktop").join("data").join(&request.project_id).join(&request.file_name);
+        let file_path = path_safety::project_file(&request.project_id, &request.file_name)?;
         if let Some(parent) = file_path.parent() {
             fs::create_dir_all(parent).map_err(|e| format!("Failed creating dir: {e}"))?;
         }
@@ -1584,8 +2343,7 @@ async fn save_project_data(request: SaveProjectDataRequest) -> Result<Value, Str
 #[tauri::command]
 async fn save_project_state(request: SaveProjectStateRequest) -> Result<Value, String> {
     tauri::async_runtime::spawn_blocking(move || {
-        let root = workspace_root()?;
-        let project_dir = root.join("desktop").join("data").join(&request.project_id);
+        let project_dir = path_safety::project_dir(&request.project_id)?;
         fs::create_dir_all(&project_dir).map_err(|e| format!("Failed creating dir: {e}"))?;
 
         if let Some(state) = &request.state {
@@ -1622,8 +2380,7 @@ async fn save_project_state(request: SaveProjectStateRequest) -> Result<Value, S
 #[tauri::command]
 async fn load_project(request: LoadProjectRequest) -> Result<Value, String> {
     tauri::async_runtime::spawn_blocking(move || {
-        let root = workspace_root()?;
-        let project_dir = root.join("desktop").join("data").join(&request.project_id);
+        let project_dir = path_safety::project_dir(&request.project_id)?;
 
         let state = {
             let state_path = project_dir.join("state.json");
@@ -1695,10 +2452,10 @@ fn run_auto_setup(root: &Path) {
     let node = node_binary();
     let setup_script = root.join("scripts").join("auto_setup.mjs");
     if !setup_script.exists() {
-        eprintln!("[Tauri] auto_setup.mjs not found, skipping auto-setup");
+        tracing::warn!("auto_setup.mjs not found, skipping auto-setup");
         return;
     }
-    eprintln!("[Tauri] Running auto-setup...");
+    tracing::info!("Running auto-setup...");
     match Command::new(&node)
         .arg(&setup_script)
         .current_dir(root)
@@ -1707,16 +2464,16 @@ fn run_auto_setup(root: &Path) {
         Ok(output) => {
             let stderr = String::from_utf8_lossy(&output.stderr);
             if !stderr.is_empty() {
-                eprintln!("{}", stderr.trim_end());
+                tracing::info!("{}", stderr.trim_end());
             }
             if output.status.success() {
-                eprintln!("[Tauri] Auto-setup completed");
+                tracing::info!("Auto-setup completed");
             } else {
-                eprintln!("[Tauri] Auto-setup finished with warnings");
+                tracing::warn!("Auto-setup finished with warnings");
             }
         }
         Err(e) => {
-            eprintln!("[Tauri] Auto-setup failed to run: {e}");
+            tracing::error!("Auto-setup failed to run: {e}");
         }
     }
 }
@@ -1726,7 +2483,7 @@ fn ensure_npm_modules(root: &Path) {
     if node_modules.exists() {
         return;
     }
-    eprintln!("[Tauri] node_modules not found, running npm install...");
+    tracing::info!("node_modules not found, running npm install...");
     match Command::new("npm")
         .args(["install", "--prefer-offline", "--no-audit", "--no-fund"])
         .current_dir(root)
@@ -1734,21 +2491,21 @@ fn ensure_npm_modules(root: &Path) {
     {
         Ok(output) => {
             if output.status.success() {
-                eprintln!("[Tauri] npm install completed");
+                tracing::info!("npm install completed");
             } else {
                 let stderr = String::from_utf8_lossy(&output.stderr);
-                eprintln!("[Tauri] npm install failed: {}", stderr.chars().take(200).collect::<String>());
+                tracing::warn!("npm install failed: {}", stderr.chars().take(200).collect::<String>());
             }
         }
         Err(e) => {
-            eprintln!("[Tauri] npm install failed to run: {e}");
+            tracing::error!("npm install failed to run: {e}");
         }
     }
 }
 
 fn start_backend_server() -> Option<std::process::Child> {
     let root = workspace_root().ok()?;
-    eprintln!("[Tauri] Workspace root: {:?}", root);
+    tracing::info!("Workspace root: {:?}", root);
 
     // Ensure node_modules exist (critical for .app first launch)
     ensure_npm_modules(&root);
@@ -1758,11 +2515,11 @@ fn start_backend_server() -> Option<std::process::Child> {
 
     let server_script = root.join("desktop").join("backend").join("server.mjs");
     if !server_script.exists() {
-        eprintln!("[Tauri] Backend script not found: {:?}", server_script);
+        tracing::warn!("Backend script not found: {:?}", server_script);
         return None;
     }
     let node = node_binary();
-    eprintln!("[Tauri] Starting backend: {} {:?}", node, server_script);
+    tracing::info!("Starting backend: {} {:?}", node, server_script);
     match Command::new(&node)
         .arg(&server_script)
         .current_dir(&root)
@@ -1770,31 +2527,52 @@ fn start_backend_server() -> Option<std::process::Child> {
         .spawn()
     {
         Ok(child) => {
-            eprintln!("[Tauri] Backend server started (pid={})", child.id());
+            tracing::info!("Backend server started (pid={})", child.id());
             Some(child)
         }
         Err(e) => {
-            eprintln!("[Tauri] Failed to start backend server: {e}");
+            tracing::error!("Failed to start backend server: {e}");
             None
         }
     }
 }
 
 fn main() {
-    // Start the HTTP backend server as a background process.
-    // The UI health-check will connect to it automatically.
-    let backend_child: Arc<Mutex<Option<std::process::Child>>> =
-        Arc::new(Mutex::new(start_backend_server()));
+    logging::init();
+    support::install_panic_hook();
+
+    // `render`, `edit` and `help` run headless and exit without a window.
+    if let Some(code) = cli::run(&std::env::args().skip(1).collect::<Vec<_>>()) {
+        std::process::exit(code);
+    }
+
+    // Filled in setup, which a second instance never reaches.
+    let backend_child: Arc<Mutex<Option<std::process::Child>>> = Arc::new(Mutex::new(None));
 
     let backend_child_clone = Arc::clone(&backend_child);
+    let backend_child_setup = Arc::clone(&backend_child);
 
     tauri::Builder::default()
+        // First, so a second launch hands over before it touches the stores.
+        .plugin(tauri_plugin_single_instance::init(
+            single_instance::handle_second_launch,
+        ))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .manage(scheduler::Scheduler::default())
+        .manage(windows::ProjectWindows::default())
+        .manage(notifications::Notifications::default())
+        .manage(power::SleepGuard::default())
+        .manage(launch::PendingLaunch::default())
+        .manage(control_api::ControlApi::default())
+        .manage(store_cache::StoreCache::default())
         .invoke_handler(tauri::generate_handler![
             discover_models,
             model_health,
             hardware_diagnostics,
             first_run_checks,
-            install_model,
+            model_downloads::install_model,
             list_projects,
             create_project,
             update_project_settings,
@@ -1831,15 +2609,283 @@ fn main() {
             save_project_state,
             load_project,
             // Auto-setup
-            run_setup
+            run_setup,
+            // Rough-cut review
+            cuts::list_proposed_cuts,
+            cuts::set_cut_decision,
+            cuts::apply_cut_decisions,
+            // Transcript store
+            transcript::save_transcript,
+            transcript::get_transcript,
+            transcript::search_transcript,
+            // Text-based editing
+            text_edit::apply_text_edit,
+            text_edit::remove_filler_words,
+            // Silence detection
+            silence::detect_silence,
+            // Interchange
+            otio::export_otio,
+            otio::import_otio,
+            edl::import_edl,
+            // App settings
+            settings::get_app_settings,
+            settings::save_app_settings,
+            // Remote render export
+            render_export::export_render,
+            // Render history management
+            render_history::delete_render,
+            render_history::annotate_render,
+            render_history::pin_render,
+            render_history::is_render_stale,
+            // Frame export
+            frames::export_frame,
+            frames::get_preview_frame,
+            // Export profiles
+            export_profiles::list_export_profiles,
+            export_profiles::render_export_profiles,
+            // Subtitle styling
+            subtitles::get_subtitle_style,
+            subtitles::save_subtitle_style,
+            subtitles::preview_subtitle_style,
+            // Title clips
+            titles::add_title_clip,
+            titles::update_title_clip,
+            // Enrichment plan
+            enrichment::apply_enrichment_plan,
+            // External assets
+            external_assets::list_external_assets,
+            external_assets::get_credits,
+            external_assets::save_credits,
+            // Clip speed
+            speed::set_clip_speed,
+            // Timeline validation
+            timeline_validation::validate_timeline,
+            // Timeline stats
+            timeline_stats::recompute_timeline_stats,
+            // Autosave
+            autosave::autosave_timeline,
+            autosave::check_recovery,
+            autosave::restore_autosave,
+            autosave::discard_autosave,
+            // Backups
+            backups::create_backup,
+            backups::list_backups,
+            backups::restore_backup,
+            // Store repair
+            store_repair::repair_store,
+            // Store integrity
+            integrity::startup_integrity_check,
+            integrity::adopt_orphan_project,
+            integrity::prune_project_entries,
+            // Node runtime
+            node_runtime::get_node_runtime_info,
+            // FFmpeg
+            ffmpeg::get_ffmpeg_info,
+            ffmpeg::install_ffmpeg,
+            // Hardware encoders
+            hw_encoders::list_hw_encoders,
+            // Hardware and model recommendations
+            hardware::get_hardware_info,
+            hardware::recommend_models,
+            // Model downloads
+            model_downloads::pause_model_download,
+            model_downloads::list_model_downloads,
+            // Model registry
+            model_registry::list_installed_models,
+            model_registry::uninstall_model,
+            model_registry::model_disk_usage,
+            // Native transcription
+            whisper::transcribe_media,
+            // Native planner
+            planner::plan_cuts,
+            planner::plan_templates,
+            // Fallback policy
+            fallback_policy::parse_fallback_policy,
+            // Pipeline checkpoints
+            checkpoints::list_pipeline_checkpoints,
+            // Job scheduler
+            scheduler::list_scheduled_jobs,
+            scheduler::set_queue_paused,
+            // Logs
+            logging::get_recent_logs,
+            // Support
+            support::create_support_bundle,
+            // Health
+            health::system_health,
+            // Onboarding
+            onboarding::get_onboarding_state,
+            onboarding::complete_onboarding_step,
+            sample_project::create_sample_project,
+            // Native pickers
+            pickers::pick_media_files,
+            pickers::pick_output_directory,
+            // Validation
+            validation::validate_project_settings,
+            // Windows
+            windows::set_active_project,
+            windows::open_project_window,
+            windows::list_project_windows,
+            // Shutdown
+            shutdown::take_interrupted_jobs,
+            // Project bundles and launch routing
+            project_bundle::export_project_bundle,
+            project_bundle::import_project_bundle,
+            launch::launch_ready,
+            // Control API
+            control_api::control_api_status,
+            control_api::reveal_control_api_token,
+            // Plugins
+            plugins::list_plugins,
+            // Event journal
+            event_bus::get_event_journal,
+            // Project status
+            project_status::get_project_status_history,
+            // Timeline merge
+            timeline_merge::merge_timeline,
+            timeline_window::get_timeline_window,
+            // Packed timeline IPC
+            timeline_packed::get_timeline_packed,
+            timeline_packed::save_timeline_packed,
+            // Native pipeline
+            pipeline::run_pipeline,
+            // Project config overrides
+            project_config::get_project_config,
+            // Captions
+            captions::generate_captions,
+            // Speakers
+            transcript::label_speakers,
+            transcript::get_speaker_stats,
+            // Multicam
+            multicam::plan_multicam_cut,
+            // Highlights
+            highlights::extract_highlights,
+            // Markers and chapters
+            markers::list_markers,
+            markers::save_marker,
+            markers::delete_marker,
+            chapters::generate_chapters,
+            chapters::get_chapters,
+            // Censoring
+            censor::detect_profanity,
+            censor::apply_censor,
+            // Loudness
+            loudness::measure_loudness,
+            // Audio enhancement
+            audio_enhance::enhance_audio,
+            audio_enhance::set_clip_audio_enhance,
+            audio_enhance::list_enhanced_audio,
+            // LUTs
+            luts::import_lut,
+            luts::list_luts,
+            luts::set_clip_lut,
+            // Branding
+            branding::get_branding,
+            branding::set_branding,
+            // Clip flags
+            clip_flags::set_clip_flags,
+            // Sponsor segments
+            sponsors::mark_sponsor_segment,
+            sponsors::list_sponsor_segments,
+            sponsors::remove_sponsor_segment,
+            sponsors::detect_sponsor_segments,
+            // Edit analytics
+            edit_analytics::get_edit_analytics,
+            // Render estimates
+            render_estimate::estimate_render,
+            // Render verification
+            render_verification::verify_render_output,
+            // Preview
+            preview::build_preview,
+            // Render cache
+            render_cache::get_render_cache,
+            render_cache::clear_render_cache,
+            // Render workers
+            render_workers::list_render_workers,
+            render_workers::add_render_worker,
+            render_workers::remove_render_worker,
+            // Media roots
+            media_roots::remap_media_root,
+            // Media pool
+            media_pool::get_media_pool,
+            media_pool::prune_media_pool,
+            // Voiceover
+            voiceover::start_voiceover_recording,
+            voiceover::stop_voiceover_recording,
+            // Audio devices
+            audio_devices::list_audio_devices,
+            // Screen capture
+            screen_capture::start_screen_capture,
+            screen_capture::stop_screen_capture,
+            // Player
+            player::player_load,
+            player::player_seek,
+            player::player_play,
+            player::player_pause,
+            player::player_stop,
+            // Actions
+            actions::list_actions,
+            actions::run_action
         ])
-        .on_window_event(move |_window, event| {
+        .setup(move |app| {
+            // Start the HTTP backend server as a background process.
+            // The UI health-check will connect to it automatically.
+            if let Ok(mut guard) = backend_child_setup.lock() {
+                *guard = start_backend_server();
+            }
+            // Report broken or orphaned project data before the UI trips over it.
+            integrity::log_startup_check();
+            tray::init(app.handle())?;
+            // Files and links the app was started with; later ones arrive
+            // through the single-instance handoff.
+            let cwd = std::env::current_dir().unwrap_or_default();
+            let argv = std::env::args().collect::<Vec<_>>();
+            event_bus::init(app.handle());
+            store_cache::init(app.handle());
+            launch::submit(app.handle(), launch::parse_args(&argv, &cwd));
+            control_api::init(app.handle());
+            // macOS delivers file opens and deep links as URL events instead.
+            #[cfg(target_os = "macos")]
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+                let handle = app.handle().clone();
+                app.handle().deep_link().on_open_url(move |event| {
+                    let urls = event.urls();
+                    let targets = launch::parse_urls(urls.iter().map(|url| url.as_str()));
+                    launch::submit(&handle, targets);
+                });
+            }
+            Ok(())
+        })
+        .on_window_event(move |window, event| {
+            if let tauri::WindowEvent::Focused(focused) = event {
+                notifications::on_focus_changed(window.app_handle(), window.label(), *focused);
+            }
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                let app = window.app_handle();
+                // Closing the last window quits; ask first if jobs would be cut off.
+                if windows::is_last_window(app, window.label())
+                    && !scheduler::active_jobs(app).is_empty()
+                {
+                    api.prevent_close();
+                    shutdown::request_quit(app);
+                }
+            }
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                file_drop::handle_drop(window.app_handle(), window.label(), paths);
+            }
             if let tauri::WindowEvent::Destroyed = event {
-                // Kill the backend server when the last window closes
+                windows::forget_window(window.app_handle(), window.label());
+                notifications::on_focus_changed(window.app_handle(), window.label(), false);
+                if !windows::is_last_window(window.app_handle(), window.label()) {
+                    return;
+                }
+                // Stop jobs and flush writes, then the backend server, when the
+                // last window closes
+                shutdown::run(window.app_handle());
                 if let Ok(mut guard) = backend_child_clone.lock() {
                     if let Some(ref mut child) = *guard {
-                        let _ = child.kill();
-                        eprintln!("[Tauri] Backend server stopped");
+                        shutdown::terminate_child(child);
+                        tracing::info!("Backend server stopped");
                     }
                     *guard = None;
                 }
@@ -1851,7 +2897,7 @@ fn main() {
     // Ensure backend is killed if run() returns
     if let Ok(mut guard) = backend_child.lock() {
         if let Some(child) = guard.as_mut() {
-            let _ = child.kill();
+            shutdown::terminate_child(child);
         }
     };
 }
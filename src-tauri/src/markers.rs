@@ -0,0 +1,329 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    now_iso, read_timeline, timeline_merge, write_timeline, Timeline, TimelineClip, TimelineTrack,
+};
+
+pub const MARKER_CLIP_TYPE: &str = "marker";
+pub const MARKER_TRACK_KIND: &str = "marker";
+/// Chapters span from one to the next and live on their own track; every
+/// other kind is a one-frame point marker.
+pub const CHAPTER_KIND: &str = "chapter";
+const DEFAULT_KIND: &str = "note";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Marker {
+    pub id: String,
+    pub kind: String,
+    pub start_us: u64,
+    pub end_us: u64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListMarkersRequest {
+    project_id: String,
+    kind: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveMarkerRequest {
+    project_id: String,
+    /// Updates this marker; omit to add one.
+    marker_id: Option<String>,
+    kind: Option<String>,
+    start_us: u64,
+    text: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteMarkerRequest {
+    project_id: String,
+    marker_id: String,
+}
+
+fn marker_from_clip(clip: &TimelineClip) -> Marker {
+    let text = |key: &str| {
+        clip.meta
+            .get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+    };
+    Marker {
+        id: clip.clip_id.clone(),
+        kind: Some(text("kind"))
+            .filter(|kind| !kind.is_empty())
+            .unwrap_or(DEFAULT_KIND)
+            .to_string(),
+        start_us: clip.start_us,
+        end_us: clip.end_us,
+        text: text("text").to_string(),
+    }
+}
+
+/// The timeline's markers of `kind` (all kinds when `None`) in time order.
+pub fn markers(timeline: &Timeline, kind: Option<&str>) -> Vec<Marker> {
+    let mut markers = timeline
+        .clips
+        .iter()
+        .filter(|clip| clip.clip_type == MARKER_CLIP_TYPE)
+        .map(marker_from_clip)
+        .filter(|marker| kind.map_or(true, |kind| marker.kind == kind))
+        .collect::<Vec<_>>();
+    markers.sort_by_key(|marker| marker.start_us);
+    markers
+}
+
+fn track_for(kind: &str) -> (&'static str, &'static str) {
+    if kind == CHAPTER_KIND {
+        ("track-chapters", "Chapters")
+    } else {
+        ("track-markers", "Markers")
+    }
+}
+
+/// The track markers of `kind` go on, added if missing.
+fn marker_track(timeline: &mut Timeline, kind: &str) -> Result<String, String> {
+    let (id, name) = track_for(kind);
+    if let Some(track) = timeline.tracks.iter().find(|track| track.id == id) {
+        if track.locked {
+            return Err(format!("Track {} is locked.", track.id));
+        }
+        return Ok(track.id.clone());
+    }
+    timeline.tracks.push(TimelineTrack {
+        id: id.to_string(),
+        name: name.to_string(),
+        kind: MARKER_TRACK_KIND.to_string(),
+        order: timeline.tracks.len() as u32,
+        locked: false,
+    });
+    Ok(id.to_string())
+}
+
+fn marker_clip(
+    id: String,
+    track_id: &str,
+    kind: &str,
+    start_us: u64,
+    end_us: u64,
+    text: &str,
+) -> TimelineClip {
+    TimelineClip {
+        clip_id: id,
+        track_id: track_id.to_string(),
+        clip_type: MARKER_CLIP_TYPE.to_string(),
+        start_us,
+        end_us,
+        source_start_us: start_us,
+        source_end_us: end_us,
+        speed: 1.0,
+        speed_keyframes: Vec::new(),
+        locked: false,
+        protected_from_ai: false,
+        source_ref: "marker".to_string(),
+        effects: json!({}),
+        transform: json!({}),
+        meta: json!({ "kind": kind, "text": text }),
+    }
+}
+
+fn frame_us(timeline: &Timeline) -> u64 {
+    1_000_000 / u64::from(timeline.fps.max(1))
+}
+
+/// Stretches each chapter to the start of the next, the last to the end
+/// of the timeline.
+pub fn respan_chapters(timeline: &mut Timeline) {
+    let mut starts = timeline
+        .clips
+        .iter()
+        .filter(|clip| {
+            clip.clip_type == MARKER_CLIP_TYPE && marker_from_clip(clip).kind == CHAPTER_KIND
+        })
+        .map(|clip| clip.start_us)
+        .collect::<Vec<_>>();
+    starts.sort_unstable();
+    let duration_us = timeline.duration_us;
+    for clip in &mut timeline.clips {
+        if clip.clip_type != MARKER_CLIP_TYPE || marker_from_clip(clip).kind != CHAPTER_KIND {
+            continue;
+        }
+        let end_us = starts
+            .iter()
+            .find(|&&start| start > clip.start_us)
+            .copied()
+            .unwrap_or(duration_us)
+            .max(clip.start_us + 1);
+        clip.end_us = end_us;
+        clip.source_end_us = end_us;
+    }
+}
+
+/// Swaps every marker of `kind` for `entries` of `(start_us, text)`.
+pub fn replace_markers(
+    timeline: &mut Timeline,
+    kind: &str,
+    entries: &[(u64, String)],
+) -> Result<Vec<Marker>, String> {
+    let track_id = marker_track(timeline, kind)?;
+    timeline
+        .clips
+        .retain(|clip| clip.clip_type != MARKER_CLIP_TYPE || marker_from_clip(clip).kind != kind);
+    let frame_us = frame_us(timeline);
+    for (index, (start_us, text)) in entries.iter().enumerate() {
+        timeline.clips.push(marker_clip(
+            format!("{kind}-{}", index + 1),
+            &track_id,
+            kind,
+            *start_us,
+            start_us + frame_us,
+            text,
+        ));
+    }
+    if kind == CHAPTER_KIND {
+        respan_chapters(timeline);
+    }
+    Ok(markers(timeline, Some(kind)))
+}
+
+/// Adds a marker at `start_us`, or moves/renames `marker_id`, and returns
+/// its id. The caller saves the timeline.
+pub fn put_marker(
+    timeline: &mut Timeline,
+    marker_id: Option<&str>,
+    kind: Option<&str>,
+    start_us: u64,
+    text: &str,
+) -> Result<String, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("Marker text is required.".to_string());
+    }
+    if start_us >= timeline.duration_us {
+        return Err(format!(
+            "Marker at {start_us}us is past the timeline end ({}us).",
+            timeline.duration_us
+        ));
+    }
+    let existing = match marker_id {
+        Some(id) => Some(
+            timeline
+                .clips
+                .iter()
+                .position(|clip| clip.clip_type == MARKER_CLIP_TYPE && clip.clip_id == id)
+                .ok_or_else(|| format!("Marker not found: {id}"))?,
+        ),
+        None => None,
+    };
+    let kind = kind
+        .map(str::trim)
+        .filter(|kind| !kind.is_empty())
+        .map(str::to_string)
+        .or_else(|| existing.map(|index| marker_from_clip(&timeline.clips[index]).kind))
+        .unwrap_or_else(|| DEFAULT_KIND.to_string());
+    let track_id = marker_track(timeline, &kind)?;
+    let clash = timeline.clips.iter().enumerate().any(|(index, clip)| {
+        Some(index) != existing
+            && clip.track_id == track_id
+            && clip.clip_type == MARKER_CLIP_TYPE
+            && clip.start_us == start_us
+    });
+    if clash {
+        return Err(format!(
+            "A marker already starts at {start_us}us on {track_id}."
+        ));
+    }
+
+    let end_us = (start_us + frame_us(timeline)).min(timeline.duration_us);
+    let id = match existing {
+        Some(index) => timeline.clips.remove(index).clip_id,
+        None => format!("{kind}-{}", now_iso().replace([':', '.'], "-")),
+    };
+    timeline.clips.push(marker_clip(
+        id.clone(),
+        &track_id,
+        &kind,
+        start_us,
+        end_us,
+        text,
+    ));
+    respan_chapters(timeline);
+    Ok(id)
+}
+
+fn save_marker_blocking(request: SaveMarkerRequest) -> Result<Marker, String> {
+    let _save = timeline_merge::lock_saves();
+    let mut timeline = read_timeline(&request.project_id)?;
+    let id = put_marker(
+        &mut timeline,
+        request.marker_id.as_deref(),
+        request.kind.as_deref(),
+        request.start_us,
+        &request.text,
+    )?;
+
+    timeline.version = timeline.version.saturating_add(1);
+    timeline.updated_at = now_iso();
+    write_timeline(&timeline)?;
+    markers(&timeline, None)
+        .into_iter()
+        .find(|marker| marker.id == id)
+        .ok_or_else(|| format!("Marker not found: {id}"))
+}
+
+fn delete_marker_blocking(request: DeleteMarkerRequest) -> Result<Vec<Marker>, String> {
+    let _save = timeline_merge::lock_saves();
+    let mut timeline = read_timeline(&request.project_id)?;
+    let index = timeline
+        .clips
+        .iter()
+        .position(|clip| clip.clip_type == MARKER_CLIP_TYPE && clip.clip_id == request.marker_id)
+        .ok_or_else(|| format!("Marker not found: {}", request.marker_id))?;
+    let track_id = &timeline.clips[index].track_id;
+    if timeline
+        .tracks
+        .iter()
+        .any(|track| &track.id == track_id && track.locked)
+    {
+        return Err(format!("Track {track_id} is locked."));
+    }
+    timeline.clips.remove(index);
+    respan_chapters(&mut timeline);
+
+    timeline.version = timeline.version.saturating_add(1);
+    timeline.updated_at = now_iso();
+    write_timeline(&timeline)?;
+    Ok(markers(&timeline, None))
+}
+
+#[tauri::command]
+pub async fn list_markers(request: ListMarkersRequest) -> Result<Vec<Marker>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let timeline = read_timeline(&request.project_id)?;
+        Ok(markers(&timeline, request.kind.as_deref()))
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// Adds a marker, or moves/renames one when `markerId` is given.
+#[tauri::command]
+pub async fn save_marker(request: SaveMarkerRequest) -> Result<Marker, String> {
+    tauri::async_runtime::spawn_blocking(move || save_marker_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// Removes a marker and returns the ones left.
+#[tauri::command]
+pub async fn delete_marker(request: DeleteMarkerRequest) -> Result<Vec<Marker>, String> {
+    tauri::async_runtime::spawn_blocking(move || delete_marker_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
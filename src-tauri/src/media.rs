@@ -0,0 +1,116 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ffmpeg::ffprobe_binary;
+use crate::{media_roots, path_safety};
+
+/// Extensions the ingest and render scripts accept, by kind.
+pub const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov", "mkv", "webm", "avi", "m4v"];
+pub const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "m4a", "aac", "flac"];
+pub const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp", "gif"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MediaKind {
+    Video,
+    Audio,
+    Image,
+}
+
+impl MediaKind {
+    /// By extension, the way the scripts tell audio and images from video.
+    pub fn of_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        let extension = extension.as_str();
+        if VIDEO_EXTENSIONS.contains(&extension) {
+            Some(Self::Video)
+        } else if AUDIO_EXTENSIONS.contains(&extension) {
+            Some(Self::Audio)
+        } else if IMAGE_EXTENSIONS.contains(&extension) {
+            Some(Self::Image)
+        } else {
+            None
+        }
+    }
+
+    /// `video`, `audio` or `image`, as ingest writes it to `kind`.
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "video" => Some(Self::Video),
+            "audio" => Some(Self::Audio),
+            "image" => Some(Self::Image),
+            _ => None,
+        }
+    }
+}
+
+fn project_dir(project_id: &str) -> Result<PathBuf, String> {
+    path_safety::project_dir(project_id)
+}
+
+pub fn decode_file_url(reference: &str) -> String {
+    reference
+        .strip_prefix("file://")
+        .unwrap_or(reference)
+        .replace("%20", " ")
+}
+
+/// Resolves a clip `sourceRef` to a media file on disk, the same way the render
+/// script does: an explicit path wins, otherwise the project's ingested source.
+pub fn resolve_source_path(project_id: &str, source_ref: Option<&str>) -> Result<PathBuf, String> {
+    if let Some(reference) = source_ref.map(str::trim).filter(|r| !r.is_empty()) {
+        let candidate = media_roots::reference_path(reference);
+        if candidate.is_file() {
+            return Ok(candidate);
+        }
+    }
+
+    let metadata_path = project_dir(project_id)?.join("media").join("metadata.json");
+    if metadata_path.exists() {
+        let raw = fs::read_to_string(&metadata_path)
+            .map_err(|error| format!("Failed reading media metadata: {error}"))?;
+        let metadata = serde_json::from_str::<Value>(&raw)
+            .map_err(|error| format!("Invalid media metadata JSON: {error}"))?;
+        if let Some(path) = metadata.get("sourcePath").and_then(Value::as_str) {
+            let candidate = media_roots::reference_path(path);
+            if candidate.is_file() {
+                return Ok(candidate);
+            }
+        }
+    }
+
+    Err(format!(
+        "Cannot resolve media for source {:?}; ingest the file first.",
+        source_ref.unwrap_or("source-video")
+    ))
+}
+
+/// Width and height of the first video stream, via ffprobe.
+pub fn probe_video_dimensions(path: &Path) -> Option<(u32, u32)> {
+    let output = Command::new(ffprobe_binary())
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-show_entries",
+            "stream=width,height",
+            "-of",
+            "csv=s=x:p=0",
+        ])
+        .arg(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let (width, height) = text.trim().split_once('x')?;
+    let width = width.trim().parse::<u32>().ok()?;
+    let height = height.trim().parse::<u32>().ok()?;
+    (width > 0 && height > 0).then_some((width, height))
+}
@@ -0,0 +1,496 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{media_roots, now_iso, path_safety};
+
+const POOL_DIR: &str = "media-pool";
+const INDEX_FILE: &str = "index.json";
+
+/// Project files that can point at pooled media.
+const REFERENCING_FILES: &[&str] = &[
+    "timeline.json",
+    "media/metadata.json",
+    "media/assets.json",
+    "transcript.json",
+];
+
+/// Serializes index updates across ingests and commands.
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+/// One imported file, stored once as `<hash>.<ext>` however many projects
+/// use it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolEntry {
+    /// XXH64 of the file contents, 16 hex digits.
+    pub hash: String,
+    pub file_name: String,
+    /// Where the file was first imported from: a path, or the URL it was
+    /// downloaded from.
+    pub original_path: String,
+    pub bytes: u64,
+    /// Modification time of `original_path` in Unix seconds, so re-importing
+    /// an unchanged file skips hashing.
+    pub modified: u64,
+    pub imported_at: String,
+    /// Projects referencing the entry; it's deleted when this empties.
+    pub projects: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PoolIndex {
+    pub entries: BTreeMap<String, PoolEntry>,
+}
+
+/// What ingest hands the pipeline in place of the picked file.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PooledMedia {
+    pub hash: String,
+    pub path: PathBuf,
+    pub original_path: String,
+    /// True if the contents were already in the pool.
+    pub reused: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaPoolStats {
+    pub path: String,
+    pub entries: Vec<PoolEntry>,
+    pub total_bytes: u64,
+    /// Bytes that would be stored again without deduplication.
+    pub saved_bytes: u64,
+    /// Files deleted by this call.
+    pub removed: usize,
+}
+
+const PRIME_1: u64 = 0x9E37_79B1_85EB_CA87;
+const PRIME_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const PRIME_3: u64 = 0x1656_67B1_9E37_79F9;
+const PRIME_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const PRIME_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+/// Streaming XXH64 with seed 0: fast enough to hash multi-gigabyte camera
+/// files on ingest, and not meant to resist deliberate collisions.
+pub struct Xxh64 {
+    acc: [u64; 4],
+    buffer: [u8; 32],
+    buffered: usize,
+    total_len: u64,
+}
+
+fn round(acc: u64, lane: u64) -> u64 {
+    acc.wrapping_add(lane.wrapping_mul(PRIME_2))
+        .rotate_left(31)
+        .wrapping_mul(PRIME_1)
+}
+
+fn merge_round(hash: u64, acc: u64) -> u64 {
+    (hash ^ round(0, acc))
+        .wrapping_mul(PRIME_1)
+        .wrapping_add(PRIME_4)
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap_or_default())
+}
+
+impl Default for Xxh64 {
+    fn default() -> Self {
+        Self {
+            acc: [
+                PRIME_1.wrapping_add(PRIME_2),
+                PRIME_2,
+                0,
+                0u64.wrapping_sub(PRIME_1),
+            ],
+            buffer: [0; 32],
+            buffered: 0,
+            total_len: 0,
+        }
+    }
+}
+
+impl Xxh64 {
+    fn stripe(&mut self, stripe: &[u8]) {
+        for (index, lane) in stripe.chunks_exact(8).enumerate() {
+            self.acc[index] = round(self.acc[index], read_u64(lane));
+        }
+    }
+
+    pub fn update(&mut self, mut input: &[u8]) {
+        self.total_len += input.len() as u64;
+        if self.buffered > 0 {
+            let take = input.len().min(32 - self.buffered);
+            self.buffer[self.buffered..self.buffered + take].copy_from_slice(&input[..take]);
+            self.buffered += take;
+            input = &input[take..];
+            if self.buffered < 32 {
+                return;
+            }
+            let buffer = self.buffer;
+            self.stripe(&buffer);
+            self.buffered = 0;
+        }
+        let mut stripes = input.chunks_exact(32);
+        for stripe in &mut stripes {
+            self.stripe(stripe);
+        }
+        let rest = stripes.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    pub fn finish(&self) -> u64 {
+        let mut hash = if self.total_len >= 32 {
+            let [v1, v2, v3, v4] = self.acc;
+            let hash = v1
+                .rotate_left(1)
+                .wrapping_add(v2.rotate_left(7))
+                .wrapping_add(v3.rotate_left(12))
+                .wrapping_add(v4.rotate_left(18));
+            self.acc
+                .iter()
+                .fold(hash, |hash, acc| merge_round(hash, *acc))
+        } else {
+            PRIME_5
+        };
+        hash = hash.wrapping_add(self.total_len);
+
+        let mut rest = &self.buffer[..self.buffered];
+        while rest.len() >= 8 {
+            hash = (hash ^ round(0, read_u64(rest)))
+                .rotate_left(27)
+                .wrapping_mul(PRIME_1)
+                .wrapping_add(PRIME_4);
+            rest = &rest[8..];
+        }
+        if rest.len() >= 4 {
+            let lane = u32::from_le_bytes(rest[..4].try_into().unwrap_or_default());
+            hash = (hash ^ u64::from(lane).wrapping_mul(PRIME_1))
+                .rotate_left(23)
+                .wrapping_mul(PRIME_2)
+                .wrapping_add(PRIME_3);
+            rest = &rest[4..];
+        }
+        for byte in rest {
+            hash = (hash ^ u64::from(*byte).wrapping_mul(PRIME_5))
+                .rotate_left(11)
+                .wrapping_mul(PRIME_1);
+        }
+
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(PRIME_2);
+        hash ^= hash >> 29;
+        hash = hash.wrapping_mul(PRIME_3);
+        hash ^ (hash >> 32)
+    }
+}
+
+pub fn hash_file(path: &Path) -> Result<String, String> {
+    let mut file =
+        File::open(path).map_err(|error| format!("Failed opening {}: {error}", path.display()))?;
+    let mut hasher = Xxh64::default();
+    let mut buffer = vec![0u8; 1 << 20];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|error| format!("Failed reading {}: {error}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:016x}", hasher.finish()))
+}
+
+fn lock_index() -> std::sync::MutexGuard<'static, ()> {
+    INDEX_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub fn pool_dir() -> Result<PathBuf, String> {
+    Ok(path_safety::data_dir()?.join(POOL_DIR))
+}
+
+fn read_index(dir: &Path) -> Result<PoolIndex, String> {
+    let path = dir.join(INDEX_FILE);
+    if !path.is_file() {
+        return Ok(PoolIndex::default());
+    }
+    let raw = fs::read_to_string(&path)
+        .map_err(|error| format!("Failed reading media pool index: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("Invalid media pool index: {error}"))
+}
+
+fn write_index(dir: &Path, index: &PoolIndex) -> Result<(), String> {
+    let serialized =
+        serde_json::to_string_pretty(index).map_err(|error| format!("Serialize error: {error}"))?;
+    let temp = dir.join(format!("{INDEX_FILE}.tmp"));
+    fs::write(&temp, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing media pool index: {error}"))?;
+    fs::rename(&temp, dir.join(INDEX_FILE))
+        .map_err(|error| format!("Failed writing media pool index: {error}"))
+}
+
+fn modified_secs(metadata: &fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// `<hash>.<ext>`, keeping the extension so ffmpeg and the scripts can tell
+/// images and audio from video.
+fn pool_file_name(hash: &str, source: &Path) -> String {
+    match source
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| !ext.is_empty() && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+    {
+        Some(ext) => format!("{hash}.{}", ext.to_ascii_lowercase()),
+        None => hash.to_string(),
+    }
+}
+
+/// Hard-links `source` into the pool, copying when it lives on another
+/// volume.
+fn store(source: &Path, target: &Path) -> Result<(), String> {
+    let temp = target.with_extension("part");
+    let _ = fs::remove_file(&temp);
+    if fs::hard_link(source, &temp).is_err() {
+        fs::copy(source, &temp)
+            .map_err(|error| format!("Failed copying media into the pool: {error}"))?;
+    }
+    fs::rename(&temp, target)
+        .map_err(|error| format!("Failed copying media into the pool: {error}"))
+}
+
+/// Adds `input` to the pool (or finds it there) and records that
+/// `project_id` uses it.
+pub fn import(project_id: &str, input: &str) -> Result<PooledMedia, String> {
+    let source = media_roots::reference_path(input);
+    let original = source.to_string_lossy().to_string();
+    import_from(project_id, &source, original)
+}
+
+/// [`import`] for a file that stands in for `original`, e.g. a download.
+pub fn import_from(
+    project_id: &str,
+    source: &Path,
+    original: String,
+) -> Result<PooledMedia, String> {
+    path_safety::validate_project_id(project_id)?;
+    let metadata = fs::metadata(source)
+        .map_err(|error| format!("Failed reading {}: {error}", source.display()))?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a file.", source.display()));
+    }
+    let (bytes, modified) = (metadata.len(), modified_secs(&metadata));
+    let dir = pool_dir()?;
+    fs::create_dir_all(&dir).map_err(|error| format!("Failed creating media pool dir: {error}"))?;
+
+    let known = {
+        let _index = lock_index();
+        read_index(&dir)?.entries.into_values().find(|entry| {
+            (entry.original_path == original && entry.bytes == bytes && entry.modified == modified)
+                || source == dir.join(&entry.file_name)
+        })
+    };
+    let hash = match known {
+        Some(entry) => entry.hash,
+        None => hash_file(source)?,
+    };
+
+    let _index = lock_index();
+    let mut index = read_index(&dir)?;
+    let existing = index
+        .entries
+        .get(&hash)
+        .filter(|entry| dir.join(&entry.file_name).is_file())
+        .map(|entry| entry.file_name.clone());
+    let reused = existing.is_some();
+    let file_name = match existing {
+        Some(file_name) => file_name,
+        None => {
+            let file_name = pool_file_name(&hash, source);
+            store(source, &dir.join(&file_name))?;
+            index.entries.insert(
+                hash.clone(),
+                PoolEntry {
+                    hash: hash.clone(),
+                    file_name: file_name.clone(),
+                    original_path: original.clone(),
+                    bytes,
+                    modified,
+                    imported_at: now_iso(),
+                    projects: BTreeSet::new(),
+                },
+            );
+            file_name
+        }
+    };
+    if let Some(entry) = index.entries.get_mut(&hash) {
+        entry.projects.insert(project_id.to_string());
+    }
+    write_index(&dir, &index)?;
+    if reused {
+        tracing::info!("Reusing pooled media {hash} for {project_id}");
+    }
+    Ok(PooledMedia {
+        hash,
+        path: dir.join(file_name),
+        original_path: original,
+        reused,
+    })
+}
+
+/// Drops entries nothing references any more and deletes their files, plus
+/// leftovers from interrupted imports. Referenced files are never touched.
+fn collect(dir: &Path, index: &mut PoolIndex) -> usize {
+    let released = index
+        .entries
+        .iter()
+        .filter(|(_, entry)| entry.projects.is_empty())
+        .map(|(hash, _)| hash.clone())
+        .collect::<Vec<_>>();
+    for hash in &released {
+        index.entries.remove(hash);
+    }
+    let kept = index
+        .entries
+        .values()
+        .map(|entry| entry.file_name.as_str())
+        .collect::<BTreeSet<_>>();
+    let Ok(files) = fs::read_dir(dir) else {
+        return 0;
+    };
+    files
+        .filter_map(Result::ok)
+        .filter(|file| file.path().is_file())
+        .filter(|file| {
+            let name = file.file_name().to_string_lossy().to_string();
+            name != INDEX_FILE && !kept.contains(name.as_str())
+        })
+        .filter(|file| fs::remove_file(file.path()).is_ok())
+        .count()
+}
+
+/// Releases every pool reference held by `project_id`, e.g. once its data
+/// is gone. Returns the number of files deleted.
+pub fn release_project(project_id: &str) -> Result<usize, String> {
+    let dir = pool_dir()?;
+    if !dir.is_dir() {
+        return Ok(0);
+    }
+    let _index = lock_index();
+    let mut index = read_index(&dir)?;
+    for entry in index.entries.values_mut() {
+        entry.projects.remove(project_id);
+    }
+    let removed = collect(&dir, &mut index);
+    write_index(&dir, &index)?;
+    Ok(removed)
+}
+
+/// True while one of the project's files still mentions the pooled file.
+fn still_referenced(project_id: &str, file_name: &str) -> bool {
+    let Ok(project_dir) = path_safety::project_dir(project_id) else {
+        return false;
+    };
+    REFERENCING_FILES.iter().any(|relative| {
+        fs::read_to_string(project_dir.join(relative)).is_ok_and(|raw| raw.contains(file_name))
+    })
+}
+
+fn stats(dir: &Path, index: &PoolIndex, removed: usize) -> MediaPoolStats {
+    let entries = index.entries.values().cloned().collect::<Vec<_>>();
+    MediaPoolStats {
+        path: dir.to_string_lossy().to_string(),
+        total_bytes: entries.iter().map(|entry| entry.bytes).sum(),
+        saved_bytes: entries
+            .iter()
+            .map(|entry| entry.bytes * (entry.projects.len().max(1) as u64 - 1))
+            .sum(),
+        entries,
+        removed,
+    }
+}
+
+fn get_media_pool_blocking() -> Result<MediaPoolStats, String> {
+    let dir = pool_dir()?;
+    let _index = lock_index();
+    Ok(stats(&dir, &read_index(&dir)?, 0))
+}
+
+/// Re-counts references from the projects' own files, so re-ingested or
+/// deleted projects stop pinning media, then removes what's unreferenced.
+fn prune_media_pool_blocking() -> Result<MediaPoolStats, String> {
+    let dir = pool_dir()?;
+    if !dir.is_dir() {
+        return Ok(stats(&dir, &PoolIndex::default(), 0));
+    }
+    let _index = lock_index();
+    let mut index = read_index(&dir)?;
+    for entry in index.entries.values_mut() {
+        let file_name = entry.file_name.clone();
+        entry
+            .projects
+            .retain(|project_id| still_referenced(project_id, &file_name));
+    }
+    let removed = collect(&dir, &mut index);
+    write_index(&dir, &index)?;
+    tracing::info!("Pruned {removed} file(s) from the media pool");
+    Ok(stats(&dir, &index, removed))
+}
+
+#[tauri::command]
+pub async fn get_media_pool() -> Result<MediaPoolStats, String> {
+    tauri::async_runtime::spawn_blocking(get_media_pool_blocking)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn prune_media_pool() -> Result<MediaPoolStats, String> {
+    tauri::async_runtime::spawn_blocking(prune_media_pool_blocking)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xxh64(input: &[u8], split: usize) -> u64 {
+        let mut hasher = Xxh64::default();
+        hasher.update(&input[..split]);
+        hasher.update(&input[split..]);
+        hasher.finish()
+    }
+
+    #[test]
+    fn hashes_match_xxh64_regardless_of_chunking() {
+        assert_eq!(xxh64(b"", 0), 0xEF46_DB37_51D8_E999);
+        assert_eq!(xxh64(b"abc", 1), 0x44BC_2CF5_AD77_0999);
+        let long = (0..1000u32)
+            .map(|i| (i * 7 % 251) as u8)
+            .collect::<Vec<_>>();
+        let whole = xxh64(&long, 0);
+        // Low half as stored in a `zstd --check` frame of the same bytes.
+        assert_eq!(whole as u32, 0x1FF9_57D5);
+        assert_eq!(xxh64(&long, 5), whole);
+        assert_eq!(xxh64(&long, 999), whole);
+        assert_eq!(xxh64(&long, 1000), whole);
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::media::decode_file_url;
+use crate::settings::read_app_settings;
+use crate::{now_iso, path_safety, read_timeline, timeline_merge, write_timeline};
+
+/// Carries the media roots to the pipeline scripts as a JSON object.
+pub const MEDIA_ROOTS_ENV: &str = "LAPAAS_MEDIA_ROOTS";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemapMediaRootRequest {
+    project_id: String,
+    /// Folder (or `${NAME}` alias) the references point into now.
+    old_root: String,
+    /// What replaces it, e.g. `${MEDIA_ROOT}` or a new mount point.
+    new_root: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemapMediaRootResult {
+    pub clips_updated: usize,
+    /// Project files other than the timeline that were rewritten.
+    pub files_updated: Vec<String>,
+    /// Path references that still don't resolve to a file.
+    pub missing: Vec<String>,
+}
+
+/// Alias names are `[A-Za-z0-9_]`, like environment variables.
+pub fn is_root_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// `${NAME}/rest` as a path under the root `NAME`; None for references
+/// without an alias or with an unknown one.
+pub fn expand(reference: &str, roots: &BTreeMap<String, PathBuf>) -> Option<PathBuf> {
+    let (name, rest) = reference.strip_prefix("${")?.split_once('}')?;
+    let root = roots.get(name).filter(|_| is_root_name(name))?;
+    let rest = rest.trim_start_matches(['/', '\\']);
+    Some(if rest.is_empty() {
+        root.clone()
+    } else {
+        root.join(rest)
+    })
+}
+
+/// A reference as a path: aliases expanded, `file://` stripped.
+pub fn expand_reference(reference: &str, roots: &BTreeMap<String, PathBuf>) -> PathBuf {
+    expand(reference, roots).unwrap_or_else(|| PathBuf::from(decode_file_url(reference)))
+}
+
+pub fn media_roots() -> BTreeMap<String, PathBuf> {
+    read_app_settings()
+        .map(|settings| settings.media_roots)
+        .unwrap_or_default()
+}
+
+/// [`expand_reference`] with the roots from the app settings.
+pub fn reference_path(reference: &str) -> PathBuf {
+    expand_reference(reference, &media_roots())
+}
+
+/// Value of [`MEDIA_ROOTS_ENV`] for a script run; None without roots.
+pub fn script_env() -> Option<String> {
+    let roots = media_roots();
+    if roots.is_empty() {
+        return None;
+    }
+    serde_json::to_string(&roots).ok()
+}
+
+/// `reference` with its leading `old_root` replaced by `new_root`, matching
+/// whole path components; None when it doesn't start with `old_root`.
+pub fn remap_reference(reference: &str, old_root: &str, new_root: &str) -> Option<String> {
+    let old_root = old_root.trim().trim_end_matches(['/', '\\']);
+    let new_root = new_root.trim().trim_end_matches(['/', '\\']);
+    if old_root.is_empty() {
+        return None;
+    }
+    let path = decode_file_url(reference);
+    if path == old_root {
+        return Some(new_root.to_string());
+    }
+    let rest = path
+        .strip_prefix(old_root)
+        .filter(|rest| rest.starts_with(['/', '\\']))?;
+    Some(format!("{new_root}{rest}"))
+}
+
+fn looks_like_path(reference: &str) -> bool {
+    reference.starts_with("${") || Path::new(&decode_file_url(reference)).is_absolute()
+}
+
+/// Remaps a string field of a project JSON file, e.g. `sourcePath` in the
+/// ingest metadata. True if the file changed.
+fn remap_file_field(
+    path: &Path,
+    field: &[&str],
+    old_root: &str,
+    new_root: &str,
+) -> Result<bool, String> {
+    if !path.is_file() {
+        return Ok(false);
+    }
+    let raw = fs::read_to_string(path)
+        .map_err(|error| format!("Failed reading {}: {error}", path.display()))?;
+    let Ok(mut value) = serde_json::from_str::<Value>(&raw) else {
+        return Ok(false);
+    };
+    let Some(slot) = field
+        .iter()
+        .try_fold(&mut value, |value, key| value.get_mut(*key))
+    else {
+        return Ok(false);
+    };
+    let Some(remapped) = slot
+        .as_str()
+        .and_then(|current| remap_reference(current, old_root, new_root))
+    else {
+        return Ok(false);
+    };
+    *slot = Value::String(remapped);
+    let serialized = serde_json::to_string_pretty(&value)
+        .map_err(|error| format!("Serialize error: {error}"))?;
+    fs::write(path, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing {}: {error}", path.display()))?;
+    Ok(true)
+}
+
+fn remap_media_root_blocking(
+    request: RemapMediaRootRequest,
+) -> Result<RemapMediaRootResult, String> {
+    if request.old_root.trim().is_empty() || request.new_root.trim().is_empty() {
+        return Err("Both the old and the new root are required.".to_string());
+    }
+    let (old_root, new_root) = (request.old_root.as_str(), request.new_root.as_str());
+    let project_dir = path_safety::project_dir(&request.project_id)?;
+
+    let _save = timeline_merge::lock_saves();
+    let mut timeline = read_timeline(&request.project_id)?;
+    let mut clips_updated = 0;
+    for clip in &mut timeline.clips {
+        if let Some(remapped) = remap_reference(&clip.source_ref, old_root, new_root) {
+            clip.source_ref = remapped;
+            clips_updated += 1;
+        }
+    }
+    if clips_updated > 0 {
+        timeline.version = timeline.version.saturating_add(1);
+        timeline.updated_at = now_iso();
+        write_timeline(&timeline)?;
+    }
+
+    // The render script falls back to these for clips without a path.
+    let mut files_updated = Vec::new();
+    for (relative, field) in [
+        ("media/metadata.json", &["sourcePath"][..]),
+        ("transcript.json", &["source", "path"][..]),
+    ] {
+        if remap_file_field(&project_dir.join(relative), field, old_root, new_root)? {
+            files_updated.push(relative.to_string());
+        }
+    }
+
+    let roots = media_roots();
+    let missing = timeline
+        .clips
+        .iter()
+        .map(|clip| clip.source_ref.as_str())
+        .filter(|reference| looks_like_path(reference))
+        .filter(|reference| !expand_reference(reference, &roots).is_file())
+        .map(str::to_string)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    tracing::info!(
+        "Remapped {clips_updated} clip(s) of {} from {old_root} to {new_root}",
+        request.project_id
+    );
+    Ok(RemapMediaRootResult {
+        clips_updated,
+        files_updated,
+        missing,
+    })
+}
+
+/// Rewrites the project's source refs under `oldRoot` to `newRoot`, e.g.
+/// `/Volumes/Footage` to `${MEDIA_ROOT}`, and lists references that still
+/// point at nothing.
+#[tauri::command]
+pub async fn remap_media_root(
+    request: RemapMediaRootRequest,
+) -> Result<RemapMediaRootResult, String> {
+    tauri::async_runtime::spawn_blocking(move || remap_media_root_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aliases_expand_and_roots_remap_by_component() {
+        let roots = BTreeMap::from([("MEDIA_ROOT".to_string(), PathBuf::from("/mnt/nas"))]);
+        assert_eq!(
+            expand_reference("${MEDIA_ROOT}/shoot/a.mp4", &roots),
+            PathBuf::from("/mnt/nas/shoot/a.mp4")
+        );
+        assert_eq!(expand("${OTHER}/a.mp4", &roots), None);
+        assert_eq!(
+            expand_reference("file:///clips/a%20b.mp4", &roots),
+            PathBuf::from("/clips/a b.mp4")
+        );
+
+        assert_eq!(
+            remap_reference(
+                "/Volumes/Footage/day1/a.mp4",
+                "/Volumes/Footage/",
+                "${MEDIA_ROOT}"
+            )
+            .as_deref(),
+            Some("${MEDIA_ROOT}/day1/a.mp4")
+        );
+        assert_eq!(
+            remap_reference("/Volumes/Footage2/a.mp4", "/Volumes/Footage", "/mnt/nas"),
+            None
+        );
+        assert_eq!(remap_reference("source-video", "/Volumes", "/mnt"), None);
+    }
+}
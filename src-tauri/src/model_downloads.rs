@@ -0,0 +1,602 @@
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use reqwest::blocking::Client;
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use reqwest::redirect::Policy;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+use crate::ffmpeg::sha256_file;
+use crate::{model_registry, workspace_root};
+
+pub const PROGRESS_EVENT: &str = "model://download-progress";
+
+const WHISPER_CPP_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+/// Progress events are throttled to this interval.
+const EMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Pause flags of the downloads running right now, keyed by download id.
+static ACTIVE: Mutex<Vec<(String, Arc<AtomicBool>)>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DownloadState {
+    Downloading,
+    Verifying,
+    /// Stopped by `pause_model_download`; installing again resumes it.
+    Paused,
+    Installed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallModelRequest {
+    /// `ollama`, `whisper_cpp`, or any runtime when `url` is given.
+    runtime: String,
+    model: Option<String>,
+    /// Direct download URL; defaults to the runtime's model repository.
+    url: Option<String>,
+    /// Expected sha256. Hugging Face downloads supply their own.
+    sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PauseModelDownloadRequest {
+    runtime: String,
+    model: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DownloadProgressEvent {
+    download_id: String,
+    runtime: String,
+    model: String,
+    state: DownloadState,
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>,
+    /// Average over this session, so a resumed download starts from zero.
+    bytes_per_second: f64,
+    detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallModelResult {
+    pub ok: bool,
+    pub download_id: String,
+    pub runtime: String,
+    pub model: String,
+    pub status: DownloadState,
+    pub path: Option<String>,
+    pub size_bytes: u64,
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingDownload {
+    pub download_id: String,
+    pub runtime: String,
+    pub file_name: String,
+    pub active: bool,
+    pub downloaded_bytes: u64,
+}
+
+fn download_id(runtime: &str, model: &str) -> String {
+    format!("{runtime}:{model}")
+}
+
+pub fn models_dir() -> Result<PathBuf, String> {
+    Ok(workspace_root()?
+        .join("desktop")
+        .join("data")
+        .join("models"))
+}
+
+/// Removes the pause flag when a download stops, however it stops.
+struct ActiveGuard(String);
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        if let Ok(mut active) = ACTIVE.lock() {
+            active.retain(|(id, _)| *id != self.0);
+        }
+    }
+}
+
+fn register(id: &str) -> Result<(Arc<AtomicBool>, ActiveGuard), String> {
+    let mut active = ACTIVE
+        .lock()
+        .map_err(|_| "Download registry lock poisoned".to_string())?;
+    if active.iter().any(|(active_id, _)| active_id == id) {
+        return Err(format!("{id} is already downloading."));
+    }
+    let flag = Arc::new(AtomicBool::new(false));
+    active.push((id.to_string(), flag.clone()));
+    Ok((flag, ActiveGuard(id.to_string())))
+}
+
+pub fn is_downloading(runtime: &str, model: &str) -> bool {
+    let id = download_id(runtime, model);
+    ACTIVE
+        .lock()
+        .map(|active| active.iter().any(|(active_id, _)| *active_id == id))
+        .unwrap_or(false)
+}
+
+struct Reporter {
+    app: AppHandle,
+    download_id: String,
+    runtime: String,
+    model: String,
+    started: Instant,
+    session_start_bytes: u64,
+    last_emit: Option<Instant>,
+}
+
+impl Reporter {
+    fn emit(
+        &mut self,
+        state: DownloadState,
+        downloaded: u64,
+        total: Option<u64>,
+        detail: Option<String>,
+    ) {
+        let now = Instant::now();
+        let throttled = state == DownloadState::Downloading
+            && self
+                .last_emit
+                .is_some_and(|last| now.duration_since(last) < EMIT_INTERVAL);
+        if throttled {
+            return;
+        }
+        self.last_emit = Some(now);
+        let elapsed = now.duration_since(self.started).as_secs_f64();
+        let session_bytes = downloaded.saturating_sub(self.session_start_bytes);
+        let _ = self.app.emit(
+            PROGRESS_EVENT,
+            DownloadProgressEvent {
+                download_id: self.download_id.clone(),
+                runtime: self.runtime.clone(),
+                model: self.model.clone(),
+                state,
+                downloaded_bytes: downloaded,
+                total_bytes: total,
+                bytes_per_second: if elapsed > 0.0 {
+                    session_bytes as f64 / elapsed
+                } else {
+                    0.0
+                },
+                detail,
+            },
+        );
+    }
+}
+
+fn http_client() -> Result<Client, String> {
+    // No overall timeout: multi-GB models take as long as they take.
+    Client::builder()
+        .connect_timeout(Duration::from_secs(30))
+        .timeout(None)
+        .build()
+        .map_err(|error| format!("HTTP client error: {error}"))
+}
+
+/// Hugging Face puts the sha256 of LFS files in `X-Linked-Etag` on the
+/// redirect it answers `resolve/` URLs with.
+fn remote_sha256(url: &str) -> Option<String> {
+    let client = Client::builder()
+        .redirect(Policy::none())
+        .timeout(Duration::from_secs(30))
+        .build()
+        .ok()?;
+    let response = client.head(url).send().ok()?;
+    let etag = response
+        .headers()
+        .get("x-linked-etag")?
+        .to_str()
+        .ok()?
+        .trim()
+        .trim_start_matches("W/")
+        .trim_matches('"')
+        .to_lowercase();
+    (etag.len() == 64 && etag.chars().all(|c| c.is_ascii_hexdigit())).then_some(etag)
+}
+
+/// Total size from `Content-Range: bytes 100-199/2000`.
+fn range_total(response: &reqwest::blocking::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+pub(crate) fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// How `download_resumable` ended.
+pub(crate) enum RangeDownload {
+    Complete,
+    Stopped { downloaded: u64, total: Option<u64> },
+}
+
+/// Streams `url` into `part`, asking for a `Range` that continues whatever
+/// an earlier run left there. `stop` is polled between chunks; `progress`
+/// gets `(resumed_from, downloaded, total)` after every chunk.
+pub(crate) fn download_resumable(
+    client: &Client,
+    url: &str,
+    part: &Path,
+    stop: &dyn Fn() -> bool,
+    progress: &mut dyn FnMut(u64, u64, Option<u64>),
+) -> Result<RangeDownload, String> {
+    let mut offset = fs::metadata(part).map(|meta| meta.len()).unwrap_or(0);
+    let mut request = client.get(url);
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={offset}-"));
+    }
+    let mut response = request
+        .send()
+        .map_err(|error| format!("Failed downloading {url}: {error}"))?;
+
+    let total = match response.status() {
+        StatusCode::PARTIAL_CONTENT => range_total(&response),
+        // The server ignored the range, so start over.
+        status if status.is_success() => {
+            offset = 0;
+            response.content_length()
+        }
+        // The part file already holds the whole body.
+        StatusCode::RANGE_NOT_SATISFIABLE => Some(offset),
+        status => return Err(format!("Download of {url} failed with HTTP {status}")),
+    };
+    if total == Some(offset) {
+        return Ok(RangeDownload::Complete);
+    }
+
+    if let Some(parent) = part.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating download dir: {error}"))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(offset > 0)
+        .truncate(offset == 0)
+        .open(part)
+        .map_err(|error| format!("Failed opening download: {error}"))?;
+    let mut buffer = vec![0u8; 1 << 16];
+    let mut downloaded = offset;
+    loop {
+        if stop() {
+            file.flush()
+                .map_err(|error| format!("Failed writing download: {error}"))?;
+            return Ok(RangeDownload::Stopped { downloaded, total });
+        }
+        let read = response
+            .read(&mut buffer)
+            .map_err(|error| format!("Download interrupted; try again to resume: {error}"))?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..read])
+            .map_err(|error| format!("Failed writing download: {error}"))?;
+        downloaded += read as u64;
+        progress(offset, downloaded, total);
+    }
+    file.flush()
+        .map_err(|error| format!("Failed writing download: {error}"))?;
+    if let Some(total) = total.filter(|total| downloaded < *total) {
+        return Err(format!(
+            "Download ended at {downloaded} of {total} bytes; try again to resume."
+        ));
+    }
+    Ok(RangeDownload::Complete)
+}
+
+/// Downloads `url` to `dest`, appending to `<dest>.part` from an earlier
+/// paused or interrupted run. The file only gets its final name once the
+/// checksum matches.
+fn download_file(
+    url: &str,
+    dest: &Path,
+    expected_sha256: &str,
+    paused: &AtomicBool,
+    reporter: &mut Reporter,
+) -> Result<DownloadState, String> {
+    if dest.is_file() && sha256_file(dest)? == expected_sha256 {
+        return Ok(DownloadState::Installed);
+    }
+    let part = part_path(dest);
+    reporter.session_start_bytes = fs::metadata(&part).map(|meta| meta.len()).unwrap_or(0);
+
+    let client = http_client()?;
+    let outcome = download_resumable(
+        &client,
+        url,
+        &part,
+        &|| paused.load(Ordering::Relaxed),
+        &mut |resumed_from, downloaded, total| {
+            reporter.session_start_bytes = resumed_from;
+            reporter.emit(DownloadState::Downloading, downloaded, total, None);
+        },
+    )?;
+    if let RangeDownload::Stopped { downloaded, total } = outcome {
+        reporter.emit(DownloadState::Paused, downloaded, total, None);
+        return Ok(DownloadState::Paused);
+    }
+
+    let size = fs::metadata(&part).map(|meta| meta.len()).unwrap_or(0);
+    reporter.emit(DownloadState::Verifying, size, Some(size), None);
+    let actual = sha256_file(&part)?;
+    if actual != expected_sha256 {
+        let _ = fs::remove_file(&part);
+        return Err(format!(
+            "Checksum mismatch for {url}: expected {expected_sha256}, got {actual}."
+        ));
+    }
+    fs::rename(&part, dest).map_err(|error| format!("Failed installing model: {error}"))?;
+    Ok(DownloadState::Installed)
+}
+
+pub(crate) fn ollama_host() -> String {
+    std::env::var("OLLAMA_HOST")
+        .ok()
+        .filter(|host| !host.trim().is_empty())
+        .map(|host| {
+            let host = host.trim().trim_end_matches('/');
+            if host.starts_with("http") {
+                host.to_string()
+            } else {
+                format!("http://{host}")
+            }
+        })
+        .unwrap_or_else(|| "http://127.0.0.1:11434".to_string())
+}
+
+/// Streams `/api/pull`. Ollama keeps partial layers and checks each layer's
+/// sha256 digest itself, so pausing drops the stream and resuming pulls again.
+fn pull_ollama(
+    model: &str,
+    paused: &AtomicBool,
+    reporter: &mut Reporter,
+) -> Result<DownloadState, String> {
+    let client = http_client()?;
+    let response = client
+        .post(format!("{}/api/pull", ollama_host()))
+        .header("Content-Type", "application/json")
+        // `name` for Ollama releases that predate `model`.
+        .body(serde_json::json!({ "model": model, "name": model, "stream": true }).to_string())
+        .send()
+        .map_err(|error| format!("Failed to reach Ollama; is it running? {error}"))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        return Err(format!(
+            "ollama pull failed with HTTP {status}: {}",
+            body.chars().take(300).collect::<String>()
+        ));
+    }
+
+    // Layer sizes by digest; progress is summed over all of them.
+    let mut layers: Vec<(String, u64, u64)> = Vec::new();
+    for line in BufReader::new(response).lines() {
+        if paused.load(Ordering::Relaxed) {
+            let downloaded = layers.iter().map(|layer| layer.2).sum();
+            let total = layers.iter().map(|layer| layer.1).sum();
+            reporter.emit(DownloadState::Paused, downloaded, Some(total), None);
+            return Ok(DownloadState::Paused);
+        }
+        let line = line.map_err(|error| format!("Ollama pull interrupted: {error}"))?;
+        let Ok(update) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        if let Some(error) = update.get("error").and_then(Value::as_str) {
+            return Err(format!("ollama pull failed: {error}"));
+        }
+        let status = update
+            .get("status")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        if let Some(digest) = update.get("digest").and_then(Value::as_str) {
+            let total = update.get("total").and_then(Value::as_u64).unwrap_or(0);
+            let completed = update.get("completed").and_then(Value::as_u64).unwrap_or(0);
+            match layers.iter_mut().find(|layer| layer.0 == digest) {
+                Some(layer) => {
+                    layer.1 = total.max(layer.1);
+                    layer.2 = completed.max(layer.2);
+                }
+                None => layers.push((digest.to_string(), total, completed)),
+            }
+        }
+        let downloaded = layers.iter().map(|layer| layer.2).sum();
+        let total = layers.iter().map(|layer| layer.1).sum();
+        if status == "success" {
+            return Ok(DownloadState::Installed);
+        }
+        let state = if status.contains("verifying") {
+            DownloadState::Verifying
+        } else {
+            DownloadState::Downloading
+        };
+        reporter.emit(state, downloaded, Some(total), Some(status));
+    }
+    Err("Ollama closed the pull stream before it finished.".to_string())
+}
+
+fn install_model_blocking(
+    app: AppHandle,
+    request: InstallModelRequest,
+) -> Result<InstallModelResult, String> {
+    let runtime = request.runtime.trim().to_string();
+    let model = request
+        .model
+        .map(|model| model.trim().to_string())
+        .filter(|model| !model.is_empty())
+        .ok_or_else(|| format!("Missing required model for {runtime} install."))?;
+    let id = download_id(&runtime, &model);
+    let (paused, _guard) = register(&id)?;
+    let mut reporter = Reporter {
+        app,
+        download_id: id.clone(),
+        runtime: runtime.clone(),
+        model: model.clone(),
+        started: Instant::now(),
+        session_start_bytes: 0,
+        last_emit: None,
+    };
+
+    let outcome = if runtime == "ollama" && request.url.is_none() {
+        pull_ollama(&model, &paused, &mut reporter).map(|state| (state, None, None))
+    } else {
+        let url = match (request.url, runtime.as_str()) {
+            (Some(url), _) => url.trim().to_string(),
+            (None, "whisper_cpp") => format!("{WHISPER_CPP_BASE_URL}/ggml-{model}.bin"),
+            (None, _) => return Err(format!("Unsupported runtime without a url: {runtime}")),
+        };
+        let file_name = url
+            .split('?')
+            .next()
+            .and_then(|path| path.rsplit('/').next())
+            .filter(|name| !name.is_empty() && !name.contains(".."))
+            .ok_or_else(|| format!("Can't tell the file name from {url}"))?
+            .to_string();
+        let expected = request
+            .sha256
+            .map(|sha256| sha256.trim().to_lowercase())
+            .filter(|sha256| !sha256.is_empty())
+            .or_else(|| remote_sha256(&url))
+            .ok_or_else(|| format!("No checksum is published for {url}; pass sha256."))?;
+        let dest = models_dir()?.join(&runtime).join(file_name);
+        download_file(&url, &dest, &expected, &paused, &mut reporter)
+            .map(|state| (state, Some(dest), Some(expected)))
+    };
+
+    let (state, path, sha256) = match outcome {
+        Ok(outcome) => outcome,
+        Err(error) => {
+            reporter.emit(DownloadState::Failed, 0, None, Some(error.clone()));
+            return Err(error);
+        }
+    };
+    let size_bytes = match (&path, state) {
+        (Some(path), DownloadState::Installed) => fs::metadata(path).map(|meta| meta.len()),
+        (Some(path), _) => fs::metadata(part_path(path)).map(|meta| meta.len()),
+        (None, _) => Ok(0),
+    }
+    .unwrap_or(0);
+    if state == DownloadState::Installed {
+        reporter.emit(state, size_bytes, Some(size_bytes), None);
+        tracing::info!("Installed {runtime} model {model}");
+        if let Err(error) = model_registry::record_install(
+            &runtime,
+            &model,
+            path.as_deref(),
+            size_bytes,
+            sha256.clone(),
+        ) {
+            tracing::warn!("Failed recording {runtime} model {model}: {error}");
+        }
+    }
+    Ok(InstallModelResult {
+        ok: true,
+        download_id: id,
+        runtime,
+        model,
+        status: state,
+        path: path.map(|path| path.to_string_lossy().to_string()),
+        size_bytes,
+        sha256,
+    })
+}
+
+/// Downloads still in progress plus paused or interrupted `.part` files.
+fn pending_downloads() -> Result<Vec<PendingDownload>, String> {
+    let active = ACTIVE
+        .lock()
+        .map_err(|_| "Download registry lock poisoned".to_string())?
+        .iter()
+        .map(|(id, _)| id.clone())
+        .collect::<Vec<_>>();
+    let mut pending = Vec::new();
+    let Ok(runtimes) = fs::read_dir(models_dir()?) else {
+        return Ok(pending);
+    };
+    for runtime_dir in runtimes.filter_map(Result::ok) {
+        let runtime = runtime_dir.file_name().to_string_lossy().to_string();
+        let Ok(files) = fs::read_dir(runtime_dir.path()) else {
+            continue;
+        };
+        for file in files.filter_map(Result::ok) {
+            let name = file.file_name().to_string_lossy().to_string();
+            let Some(file_name) = name.strip_suffix(".part") else {
+                continue;
+            };
+            let model = file_name
+                .strip_prefix("ggml-")
+                .and_then(|name| name.strip_suffix(".bin"))
+                .unwrap_or(file_name);
+            let download_id = download_id(&runtime, model);
+            pending.push(PendingDownload {
+                active: active.contains(&download_id),
+                download_id,
+                runtime: runtime.clone(),
+                file_name: file_name.to_string(),
+                downloaded_bytes: file.metadata().map(|meta| meta.len()).unwrap_or(0),
+            });
+        }
+    }
+    Ok(pending)
+}
+
+/// Installs a model with `model://download-progress` events. Running it
+/// again after a pause or failure resumes from the partial download.
+#[tauri::command]
+pub async fn install_model(
+    app: AppHandle,
+    request: InstallModelRequest,
+) -> Result<InstallModelResult, String> {
+    tauri::async_runtime::spawn_blocking(move || install_model_blocking(app, request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn pause_model_download(request: PauseModelDownloadRequest) -> Result<bool, String> {
+    let id = download_id(request.runtime.trim(), request.model.trim());
+    let active = ACTIVE
+        .lock()
+        .map_err(|_| "Download registry lock poisoned".to_string())?;
+    Ok(active
+        .iter()
+        .find(|(active_id, _)| *active_id == id)
+        .map(|(_, flag)| flag.store(true, Ordering::Relaxed))
+        .is_some())
+}
+
+#[tauri::command]
+pub async fn list_model_downloads() -> Result<Vec<PendingDownload>, String> {
+    tauri::async_runtime::spawn_blocking(pending_downloads)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,378 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::hardware::{disk_info, DiskInfo};
+use crate::model_downloads::{is_downloading, models_dir, ollama_host};
+use crate::now_iso;
+
+/// Models not used for this long are listed as reclaimable.
+const UNUSED_AFTER_SECS: u64 = 30 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledModel {
+    pub runtime: String,
+    pub model: String,
+    /// File on disk; Ollama models live in Ollama's own store.
+    pub path: Option<String>,
+    pub size_bytes: u64,
+    pub sha256: Option<String>,
+    pub installed_at: String,
+    pub last_used_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModelRegistry {
+    models: Vec<InstalledModel>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallModelRequest {
+    runtime: String,
+    model: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallModelResult {
+    pub runtime: String,
+    pub model: String,
+    pub freed_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeUsage {
+    pub runtime: String,
+    pub model_count: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDiskUsage {
+    pub total_bytes: u64,
+    pub runtimes: Vec<RuntimeUsage>,
+    /// Not used in the last 30 days, largest first.
+    pub unused: Vec<InstalledModel>,
+    pub reclaimable_bytes: u64,
+    /// The volume models are downloaded to.
+    pub disk: Option<DiskInfo>,
+}
+
+fn registry_path() -> Result<PathBuf, String> {
+    Ok(models_dir()?.join("registry.json"))
+}
+
+fn read_registry() -> Result<Vec<InstalledModel>, String> {
+    let path = registry_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = fs::read_to_string(&path)
+        .map_err(|error| format!("Failed reading model registry: {error}"))?;
+    serde_json::from_str::<ModelRegistry>(&raw)
+        .map(|registry| registry.models)
+        .map_err(|error| format!("Failed parsing model registry: {error}"))
+}
+
+fn write_registry(models: &[InstalledModel]) -> Result<(), String> {
+    let path = registry_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating models dir: {error}"))?;
+    }
+    let registry = ModelRegistry {
+        models: models.to_vec(),
+    };
+    let raw = serde_json::to_string_pretty(&registry)
+        .map_err(|error| format!("Model registry serialize error: {error}"))?;
+    fs::write(path, format!("{raw}\n"))
+        .map_err(|error| format!("Failed writing model registry: {error}"))
+}
+
+fn same_model(entry: &InstalledModel, runtime: &str, model: &str) -> bool {
+    entry.runtime == runtime && entry.model == model
+}
+
+/// Adds or refreshes the entry for a model that just finished installing.
+pub fn record_install(
+    runtime: &str,
+    model: &str,
+    path: Option<&Path>,
+    size_bytes: u64,
+    sha256: Option<String>,
+) -> Result<(), String> {
+    let mut models = read_registry()?;
+    let last_used_at = models
+        .iter()
+        .find(|entry| same_model(entry, runtime, model))
+        .and_then(|entry| entry.last_used_at.clone());
+    models.retain(|entry| !same_model(entry, runtime, model));
+    models.push(InstalledModel {
+        runtime: runtime.to_string(),
+        model: model.to_string(),
+        path: path.map(|path| path.to_string_lossy().to_string()),
+        size_bytes,
+        sha256,
+        installed_at: now_iso(),
+        last_used_at,
+    });
+    write_registry(&models)
+}
+
+/// Stamps `lastUsedAt` on every registered model with one of these names.
+/// Pipelines name models without their runtime, so any runtime matches.
+pub fn touch_models(names: &[String]) -> Result<(), String> {
+    let names = names
+        .iter()
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        return Ok(());
+    }
+    let mut models = read_registry()?;
+    let now = now_iso();
+    let mut changed = false;
+    for entry in models
+        .iter_mut()
+        .filter(|entry| names.contains(&entry.model.as_str()))
+    {
+        entry.last_used_at = Some(now.clone());
+        changed = true;
+    }
+    if changed {
+        write_registry(&models)?;
+    }
+    Ok(())
+}
+
+fn ollama_client() -> Option<Client> {
+    Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .ok()
+}
+
+/// `(name, size)` of every model Ollama has, or None if it isn't running.
+fn ollama_models() -> Option<Vec<(String, u64)>> {
+    let response = ollama_client()?
+        .get(format!("{}/api/tags", ollama_host()))
+        .send()
+        .ok()?;
+    let body = serde_json::from_str::<Value>(&response.text().ok()?).ok()?;
+    Some(
+        body.get("models")?
+            .as_array()?
+            .iter()
+            .filter_map(|model| {
+                Some((
+                    model.get("name")?.as_str()?.to_string(),
+                    model.get("size").and_then(Value::as_u64).unwrap_or(0),
+                ))
+            })
+            .collect(),
+    )
+}
+
+/// Ollama reports `llama3.2:3b` as given but a bare name as `name:latest`.
+fn ollama_name_matches(listed: &str, model: &str) -> bool {
+    listed == model || listed.strip_suffix(":latest") == Some(model)
+}
+
+/// Registry entries reconciled with disk: file models that were deleted by
+/// hand are dropped, and Ollama models get their real sizes. Models pulled
+/// outside the app are adopted so they show up for cleanup too.
+pub(crate) fn installed_models() -> Result<Vec<InstalledModel>, String> {
+    let original = read_registry()?;
+    let mut models = original
+        .iter()
+        .filter(|entry| match &entry.path {
+            Some(path) => Path::new(path).is_file(),
+            None => true,
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    for entry in models.iter_mut() {
+        if let Some(size) = entry
+            .path
+            .as_ref()
+            .and_then(|path| fs::metadata(path).ok())
+            .map(|meta| meta.len())
+        {
+            entry.size_bytes = size;
+        }
+    }
+
+    if let Some(listed) = ollama_models() {
+        models.retain(|entry| {
+            entry.runtime != "ollama"
+                || listed
+                    .iter()
+                    .any(|(name, _)| ollama_name_matches(name, &entry.model))
+        });
+        for (name, size) in listed {
+            match models
+                .iter_mut()
+                .find(|entry| entry.runtime == "ollama" && ollama_name_matches(&name, &entry.model))
+            {
+                Some(entry) => entry.size_bytes = size,
+                None => models.push(InstalledModel {
+                    runtime: "ollama".to_string(),
+                    model: name,
+                    path: None,
+                    size_bytes: size,
+                    sha256: None,
+                    installed_at: now_iso(),
+                    last_used_at: None,
+                }),
+            }
+        }
+    }
+
+    let changed = serde_json::to_value(&models).ok() != serde_json::to_value(&original).ok();
+    if changed {
+        write_registry(&models)?;
+    }
+    Ok(models)
+}
+
+fn uninstall_model_blocking(
+    request: UninstallModelRequest,
+) -> Result<UninstallModelResult, String> {
+    let runtime = request.runtime.trim().to_string();
+    let model = request.model.trim().to_string();
+    if is_downloading(&runtime, &model) {
+        return Err(format!(
+            "{runtime} model {model} is downloading; pause it first."
+        ));
+    }
+    let mut models = installed_models()?;
+    let entry = models
+        .iter()
+        .find(|entry| same_model(entry, &runtime, &model))
+        .cloned()
+        .ok_or_else(|| format!("{runtime} model {model} is not installed."))?;
+
+    match &entry.path {
+        Some(path) => {
+            fs::remove_file(path).map_err(|error| format!("Failed removing model: {error}"))?;
+            let _ = fs::remove_file(format!("{path}.part"));
+        }
+        None if runtime == "ollama" => {
+            let client = ollama_client().ok_or_else(|| "HTTP client error".to_string())?;
+            let response = client
+                .delete(format!("{}/api/delete", ollama_host()))
+                .header("Content-Type", "application/json")
+                .body(serde_json::json!({ "model": model, "name": model }).to_string())
+                .send()
+                .map_err(|error| format!("Failed to reach Ollama; is it running? {error}"))?;
+            // 404: already removed outside the app.
+            if !response.status().is_success() && response.status().as_u16() != 404 {
+                let status = response.status();
+                let body = response.text().unwrap_or_default();
+                return Err(format!(
+                    "ollama delete failed with HTTP {status}: {}",
+                    body.chars().take(300).collect::<String>()
+                ));
+            }
+        }
+        None => {}
+    }
+
+    models.retain(|other| !same_model(other, &runtime, &model));
+    write_registry(&models)?;
+    tracing::info!(
+        "Uninstalled {runtime} model {model} ({} bytes)",
+        entry.size_bytes
+    );
+    Ok(UninstallModelResult {
+        runtime,
+        model,
+        freed_bytes: entry.size_bytes,
+    })
+}
+
+fn is_unused(entry: &InstalledModel, now: u64) -> bool {
+    let last = entry
+        .last_used_at
+        .as_deref()
+        .unwrap_or(&entry.installed_at)
+        .parse::<u64>()
+        .unwrap_or(0);
+    now.saturating_sub(last) >= UNUSED_AFTER_SECS
+}
+
+fn model_disk_usage_blocking() -> Result<ModelDiskUsage, String> {
+    let models = installed_models()?;
+    let now = now_iso().parse::<u64>().unwrap_or(0);
+    let mut runtimes: Vec<RuntimeUsage> = Vec::new();
+    for entry in &models {
+        match runtimes
+            .iter_mut()
+            .find(|usage| usage.runtime == entry.runtime)
+        {
+            Some(usage) => {
+                usage.model_count += 1;
+                usage.bytes += entry.size_bytes;
+            }
+            None => runtimes.push(RuntimeUsage {
+                runtime: entry.runtime.clone(),
+                model_count: 1,
+                bytes: entry.size_bytes,
+            }),
+        }
+    }
+    runtimes.sort_by_key(|usage| std::cmp::Reverse(usage.bytes));
+    let mut unused = models
+        .iter()
+        .filter(|entry| is_unused(entry, now))
+        .cloned()
+        .collect::<Vec<_>>();
+    unused.sort_by_key(|entry| std::cmp::Reverse(entry.size_bytes));
+
+    let dir = models_dir()?;
+    let disk = if dir.is_dir() {
+        disk_info(&dir)
+    } else {
+        dir.parent().and_then(disk_info)
+    };
+    Ok(ModelDiskUsage {
+        total_bytes: models.iter().map(|entry| entry.size_bytes).sum(),
+        reclaimable_bytes: unused.iter().map(|entry| entry.size_bytes).sum(),
+        runtimes,
+        unused,
+        disk,
+    })
+}
+
+#[tauri::command]
+pub async fn list_installed_models() -> Result<Vec<InstalledModel>, String> {
+    tauri::async_runtime::spawn_blocking(installed_models)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn uninstall_model(
+    request: UninstallModelRequest,
+) -> Result<UninstallModelResult, String> {
+    tauri::async_runtime::spawn_blocking(move || uninstall_model_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn model_disk_usage() -> Result<ModelDiskUsage, String> {
+    tauri::async_runtime::spawn_blocking(model_disk_usage_blocking)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
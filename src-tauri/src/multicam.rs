@@ -0,0 +1,399 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::transcript::{read_transcript, Transcript};
+use crate::{
+    clip_flags, now_iso, read_timeline, speed, timeline_merge, write_timeline, Timeline,
+    TimelineClip,
+};
+
+const MIN_SHOT_RANGE_US: std::ops::RangeInclusive<u64> = 500_000..=30_000_000;
+const REACTION_RATIO_RANGE: std::ops::RangeInclusive<f64> = 0.0..=0.5;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanMulticamCutRequest {
+    project_id: String,
+    /// Diarization speaker id to the asset (source ref) of their camera.
+    speaker_to_asset: BTreeMap<String, String>,
+    /// Angle for unmapped speakers; defaults to the transcript's source.
+    wide_asset: Option<String>,
+    min_shot_us: Option<u64>,
+    /// Share of a long shot given to a cutaway of the listener, 0-0.5.
+    reaction_ratio: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MulticamShot {
+    pub source_start_us: u64,
+    pub source_end_us: u64,
+    pub asset: String,
+    /// Who is talking, also during their listener's reaction shot.
+    pub speaker: String,
+    /// A cutaway to the listener rather than the speaker.
+    pub reaction: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MulticamCut {
+    pub shots: Vec<MulticamShot>,
+    pub switched_clips: usize,
+    pub timeline: Timeline,
+}
+
+/// `(start, end, speaker)` runs of speech in source time, one per change of
+/// speaker. Speech without a speaker doesn't break a run.
+pub fn speaker_turns(transcript: &Transcript) -> Vec<(u64, u64, String)> {
+    let mut spans = Vec::new();
+    for segment in &transcript.segments {
+        if segment.words.is_empty() {
+            if let Some(speaker) = &segment.speaker {
+                spans.push((segment.start_us, segment.end_us, speaker));
+            }
+            continue;
+        }
+        for word in &segment.words {
+            if let Some(speaker) = word.speaker.as_ref().or(segment.speaker.as_ref()) {
+                spans.push((word.start_us, word.end_us, speaker));
+            }
+        }
+    }
+    spans.sort_by_key(|(start_us, _, _)| *start_us);
+
+    let mut turns: Vec<(u64, u64, String)> = Vec::new();
+    for (start_us, end_us, speaker) in spans {
+        match turns.last_mut() {
+            Some(turn) if &turn.2 == speaker => turn.1 = turn.1.max(end_us),
+            _ => turns.push((start_us, end_us, speaker.clone())),
+        }
+    }
+    turns
+}
+
+/// Shots covering `[0, end_us)` of the source. A switch waits until the
+/// current shot has run `min_shot_us`, and is dropped if the turn is over
+/// by then. Shots at least three minimums long give `reaction_ratio` of
+/// their length, centred, to the previous other angle.
+pub fn plan_shots(
+    turns: &[(u64, u64, String)],
+    speaker_to_asset: &BTreeMap<String, String>,
+    wide_asset: &str,
+    end_us: u64,
+    min_shot_us: u64,
+    reaction_ratio: f64,
+) -> Vec<MulticamShot> {
+    let angle = |speaker: &str| {
+        speaker_to_asset
+            .get(speaker)
+            .map_or(wide_asset, String::as_str)
+    };
+    let mut shots: Vec<MulticamShot> = Vec::new();
+    for (start_us, turn_end_us, speaker) in turns {
+        let asset = angle(speaker);
+        let Some(last) = shots.last() else {
+            shots.push(MulticamShot {
+                source_start_us: 0,
+                source_end_us: end_us,
+                asset: asset.to_string(),
+                speaker: speaker.clone(),
+                reaction: false,
+            });
+            continue;
+        };
+        if last.asset == asset {
+            continue;
+        }
+        let switch_us = (*start_us).max(last.source_start_us + min_shot_us);
+        if switch_us >= *turn_end_us || switch_us >= end_us {
+            continue;
+        }
+        shots.push(MulticamShot {
+            source_start_us: switch_us,
+            source_end_us: end_us,
+            asset: asset.to_string(),
+            speaker: speaker.clone(),
+            reaction: false,
+        });
+    }
+    for index in 1..shots.len() {
+        shots[index - 1].source_end_us = shots[index].source_start_us;
+    }
+    // A short final shot is folded into the one before it.
+    if shots.len() > 1
+        && shots[shots.len() - 1].source_end_us - shots[shots.len() - 1].source_start_us
+            < min_shot_us
+    {
+        shots.pop();
+        if let Some(last) = shots.last_mut() {
+            last.source_end_us = end_us;
+        }
+    }
+
+    if reaction_ratio <= 0.0 {
+        return shots;
+    }
+    let mut with_reactions: Vec<MulticamShot> = Vec::new();
+    for (index, shot) in shots.iter().enumerate() {
+        let length = shot.source_end_us - shot.source_start_us;
+        let reaction_us = ((length as f64 * reaction_ratio) as u64).max(min_shot_us);
+        let listener = shots[..index]
+            .iter()
+            .rev()
+            .chain(&shots[index + 1..])
+            .find(|other| other.asset != shot.asset);
+        let Some(listener) = listener.filter(|_| length >= 3 * min_shot_us) else {
+            with_reactions.push(shot.clone());
+            continue;
+        };
+        if length.saturating_sub(reaction_us) < 2 * min_shot_us {
+            with_reactions.push(shot.clone());
+            continue;
+        }
+        let reaction_start = shot.source_start_us + (length - reaction_us) / 2;
+        let reaction_end = reaction_start + reaction_us;
+        with_reactions.push(MulticamShot {
+            source_end_us: reaction_start,
+            ..shot.clone()
+        });
+        with_reactions.push(MulticamShot {
+            source_start_us: reaction_start,
+            source_end_us: reaction_end,
+            asset: listener.asset.clone(),
+            speaker: shot.speaker.clone(),
+            reaction: true,
+        });
+        with_reactions.push(MulticamShot {
+            source_start_us: reaction_end,
+            ..shot.clone()
+        });
+    }
+    with_reactions
+}
+
+/// Splits `clip` where `shots` switch angle and points each piece at its
+/// shot's asset.
+fn switch_clip(clip: TimelineClip, master: &str, shots: &[MulticamShot]) -> Vec<TimelineClip> {
+    let mut pieces = Vec::new();
+    let mut rest = clip;
+    for shot in shots.iter().skip(1) {
+        if let Some((left, right)) = speed::split_at_source(&rest, shot.source_start_us) {
+            pieces.push(left);
+            rest = right;
+        }
+    }
+    pieces.push(rest);
+
+    let base_id = pieces[0].clip_id.clone();
+    for (index, piece) in pieces.iter_mut().enumerate() {
+        if index > 0 {
+            piece.clip_id = format!("{base_id}-cam{index}");
+        }
+        let Some(shot) = shots.iter().find(|shot| {
+            piece.source_start_us >= shot.source_start_us
+                && piece.source_start_us < shot.source_end_us
+        }) else {
+            continue;
+        };
+        piece.source_ref = shot.asset.clone();
+        if !piece.meta.is_object() {
+            piece.meta = json!({});
+        }
+        piece.meta["multicam"] = json!({
+            "masterSourceRef": master,
+            "speaker": shot.speaker,
+            "reaction": shot.reaction,
+        });
+    }
+    pieces
+}
+
+fn plan_multicam_cut_blocking(request: PlanMulticamCutRequest) -> Result<MulticamCut, String> {
+    let min_shot_us = request.min_shot_us.unwrap_or(2_000_000);
+    if !MIN_SHOT_RANGE_US.contains(&min_shot_us) {
+        return Err(format!(
+            "Minimum shot length must be {}-{}us, got {min_shot_us}.",
+            MIN_SHOT_RANGE_US.start(),
+            MIN_SHOT_RANGE_US.end()
+        ));
+    }
+    let reaction_ratio = request.reaction_ratio.unwrap_or(0.0);
+    if !REACTION_RATIO_RANGE.contains(&reaction_ratio) {
+        return Err(format!(
+            "Reaction cut ratio must be {}-{}, got {reaction_ratio}.",
+            REACTION_RATIO_RANGE.start(),
+            REACTION_RATIO_RANGE.end()
+        ));
+    }
+    if request.speaker_to_asset.is_empty() {
+        return Err("Map at least one speaker to a camera angle.".to_string());
+    }
+    if let Some((speaker, _)) = request
+        .speaker_to_asset
+        .iter()
+        .find(|(_, asset)| asset.trim().is_empty())
+    {
+        return Err(format!("Speaker {speaker} has no camera angle."));
+    }
+
+    let transcript = read_transcript(&request.project_id)?;
+    let speakers = transcript.speakers();
+    if speakers.is_empty() {
+        return Err("The transcript has no speakers; run diarization first.".to_string());
+    }
+    if let Some(unknown) = request
+        .speaker_to_asset
+        .keys()
+        .find(|speaker| !speakers.contains(&speaker.as_str()))
+    {
+        return Err(format!("Unknown speaker {unknown}."));
+    }
+
+    let _save = timeline_merge::lock_saves();
+    let mut timeline = read_timeline(&request.project_id)?;
+    let track = timeline
+        .tracks
+        .iter()
+        .filter(|track| track.kind == "video")
+        .min_by_key(|track| track.order)
+        .ok_or_else(|| "The timeline has no video track.".to_string())?;
+    if track.locked {
+        return Err(format!("Track {} is locked.", track.id));
+    }
+    let track_id = track.id.clone();
+
+    // Clips switched by an earlier run are planned again from their master.
+    let master_of = |clip: &TimelineClip| {
+        clip.meta
+            .pointer("/multicam/masterSourceRef")
+            .and_then(Value::as_str)
+            .map_or_else(|| clip.source_ref.clone(), str::to_string)
+    };
+    let master = match transcript.source_ref.clone() {
+        Some(source_ref) => source_ref,
+        None => timeline
+            .clips
+            .iter()
+            .find(|clip| clip.track_id == track_id && clip.clip_type == "source_clip")
+            .map(master_of)
+            .ok_or_else(|| "The main video track has no source clips.".to_string())?,
+    };
+    let is_master_clip = |clip: &TimelineClip| {
+        clip.track_id == track_id && clip.clip_type == "source_clip" && master_of(clip) == master
+    };
+
+    let end_us = timeline
+        .clips
+        .iter()
+        .filter(|clip| is_master_clip(clip))
+        .map(|clip| clip.source_end_us)
+        .max()
+        .unwrap_or(0);
+    let wide_asset = request
+        .wide_asset
+        .as_deref()
+        .map(str::trim)
+        .filter(|asset| !asset.is_empty())
+        .unwrap_or(&master)
+        .to_string();
+    let shots = plan_shots(
+        &speaker_turns(&transcript),
+        &request.speaker_to_asset,
+        &wide_asset,
+        end_us,
+        min_shot_us,
+        reaction_ratio,
+    );
+
+    let mut switched_clips = 0;
+    let mut clips = Vec::with_capacity(timeline.clips.len());
+    for mut clip in std::mem::take(&mut timeline.clips) {
+        if !is_master_clip(&clip) || clip_flags::is_ai_protected(&clip) || shots.is_empty() {
+            clips.push(clip);
+            continue;
+        }
+        clip.source_ref = master.clone();
+        switched_clips += 1;
+        clips.extend(switch_clip(clip, &master, &shots));
+    }
+    timeline.clips = clips;
+
+    timeline.version = timeline.version.saturating_add(1);
+    timeline.updated_at = now_iso();
+    write_timeline(&timeline)?;
+    Ok(MulticamCut {
+        shots,
+        switched_clips,
+        timeline,
+    })
+}
+
+/// Cuts the main video track between camera angles following who is
+/// speaking. Angles must be in sync with the transcript's source; the
+/// existing cuts are kept.
+#[tauri::command]
+pub async fn plan_multicam_cut(request: PlanMulticamCutRequest) -> Result<MulticamCut, String> {
+    tauri::async_runtime::spawn_blocking(move || plan_multicam_cut_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(start_s: u64, end_s: u64, speaker: &str) -> (u64, u64, String) {
+        (start_s * 1_000_000, end_s * 1_000_000, speaker.to_string())
+    }
+
+    #[test]
+    fn shots_follow_speakers_and_respect_min_length() {
+        let mapping = BTreeMap::from([
+            ("HOST".to_string(), "cam-a".to_string()),
+            ("GUEST".to_string(), "cam-b".to_string()),
+        ]);
+        let turns = [
+            turn(0, 5, "HOST"),
+            // Over before the host's shot has run 2s: skipped.
+            turn(1, 2, "GUEST"),
+            turn(6, 20, "GUEST"),
+            turn(20, 21, "OTHER"),
+            turn(21, 30, "HOST"),
+        ];
+        let shots = plan_shots(&turns, &mapping, "wide", 30_000_000, 2_000_000, 0.0);
+        let summary = shots
+            .iter()
+            .map(|shot| {
+                (
+                    shot.source_start_us / 1_000_000,
+                    shot.source_end_us / 1_000_000,
+                    shot.asset.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        // The unmapped speaker gets the wide angle, held for the minimum.
+        assert_eq!(
+            summary,
+            [
+                (0, 6, "cam-a"),
+                (6, 20, "cam-b"),
+                (20, 22, "wide"),
+                (22, 30, "cam-a")
+            ]
+        );
+
+        let shots = plan_shots(&turns, &mapping, "wide", 30_000_000, 2_000_000, 0.25);
+        let reaction = shots
+            .iter()
+            .find(|shot| shot.reaction && shot.speaker == "GUEST")
+            .unwrap();
+        assert_eq!(reaction.asset, "cam-a");
+        assert_eq!(
+            (reaction.source_start_us, reaction.source_end_us),
+            (11_250_000, 14_750_000)
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::settings::read_app_settings;
+
+/// Oldest major version the pipeline scripts run on (`engines` in package.json).
+pub const MIN_NODE_MAJOR: u32 = 22;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NodeRuntimeSource {
+    /// The `NODE_BIN` environment variable.
+    EnvOverride,
+    /// `nodePath` in the app settings.
+    AppSettings,
+    /// A `node` binary shipped next to the app executable.
+    Sidecar,
+    SystemPath,
+    /// nvm, Volta, Homebrew, Program Files and similar install locations,
+    /// which apps launched from Finder or the Start menu don't have on PATH.
+    CommonLocation,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeCandidate {
+    pub path: String,
+    pub source: NodeRuntimeSource,
+    pub exists: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeRuntimeInfo {
+    pub found: bool,
+    pub path: Option<String>,
+    pub source: Option<NodeRuntimeSource>,
+    /// `node --version` output, e.g. `v22.3.0`.
+    pub version: Option<String>,
+    pub meets_minimum: bool,
+    pub min_major: u32,
+    /// Every location checked, in resolution order.
+    pub candidates: Vec<NodeCandidate>,
+    pub error: Option<String>,
+}
+
+fn executable_name() -> &'static str {
+    if cfg!(windows) {
+        "node.exe"
+    } else {
+        "node"
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+fn sidecar_candidates() -> Vec<PathBuf> {
+    let Ok(exe) = env::current_exe() else {
+        return Vec::new();
+    };
+    let mut candidates = Vec::new();
+    if let Some(dir) = exe.parent() {
+        candidates.push(dir.join(executable_name()));
+        // macOS .app: Contents/MacOS/node
+        if let Some(contents) = dir.parent() {
+            candidates.push(contents.join("MacOS").join(executable_name()));
+        }
+    }
+    candidates
+}
+
+fn path_candidates() -> Vec<PathBuf> {
+    env::var_os("PATH")
+        .map(|path| {
+            env::split_paths(&path)
+                .map(|dir| dir.join(executable_name()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Newest `~/.nvm/versions/node/v*/bin/node` first.
+fn nvm_candidates(home: &Path) -> Vec<PathBuf> {
+    let versions_dir = env::var_os("NVM_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".nvm"))
+        .join("versions")
+        .join("node");
+    let Ok(entries) = std::fs::read_dir(versions_dir) else {
+        return Vec::new();
+    };
+    let mut versions = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            Some((parse_version(&name)?, entry.path()))
+        })
+        .collect::<Vec<_>>();
+    versions.sort_by_key(|(version, _)| std::cmp::Reverse(*version));
+    versions
+        .into_iter()
+        .map(|(_, dir)| dir.join("bin").join(executable_name()))
+        .collect()
+}
+
+fn common_location_candidates() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(home) = home_dir() {
+        candidates.extend(nvm_candidates(&home));
+        candidates.push(home.join(".volta").join("bin").join(executable_name()));
+    }
+    if cfg!(windows) {
+        for var in ["ProgramFiles", "ProgramFiles(x86)", "LOCALAPPDATA"] {
+            if let Some(base) = env::var_os(var) {
+                let base = PathBuf::from(base);
+                candidates.push(base.join("nodejs").join("node.exe"));
+                candidates.push(base.join("Programs").join("nodejs").join("node.exe"));
+            }
+        }
+    } else {
+        candidates.push(PathBuf::from("/opt/homebrew/bin/node"));
+        candidates.push(PathBuf::from("/usr/local/bin/node"));
+        candidates.push(PathBuf::from("/usr/bin/node"));
+    }
+    candidates
+}
+
+/// Every place a Node binary may be found, in the order they are tried.
+fn candidates() -> Vec<NodeCandidate> {
+    let mut ordered = Vec::new();
+    if let Some(path) = env::var_os("NODE_BIN") {
+        ordered.push((PathBuf::from(path), NodeRuntimeSource::EnvOverride));
+    }
+    if let Some(path) = read_app_settings()
+        .ok()
+        .and_then(|settings| settings.node_path)
+        .filter(|path| !path.trim().is_empty())
+    {
+        ordered.push((PathBuf::from(path.trim()), NodeRuntimeSource::AppSettings));
+    }
+    ordered.extend(
+        sidecar_candidates()
+            .into_iter()
+            .map(|path| (path, NodeRuntimeSource::Sidecar)),
+    );
+    ordered.extend(
+        path_candidates()
+            .into_iter()
+            .map(|path| (path, NodeRuntimeSource::SystemPath)),
+    );
+    ordered.extend(
+        common_location_candidates()
+            .into_iter()
+            .map(|path| (path, NodeRuntimeSource::CommonLocation)),
+    );
+
+    let mut seen = Vec::new();
+    ordered
+        .into_iter()
+        .filter(|(path, _)| {
+            let duplicate = seen.contains(path);
+            seen.push(path.clone());
+            !duplicate
+        })
+        .map(|(path, source)| NodeCandidate {
+            exists: path.is_file(),
+            path: path.to_string_lossy().to_string(),
+            source,
+        })
+        .collect()
+}
+
+/// `NODE_BIN` is honoured even when it names a command rather than a file,
+/// as it always has been.
+fn usable(candidate: &NodeCandidate) -> bool {
+    candidate.exists || candidate.source == NodeRuntimeSource::EnvOverride
+}
+
+/// Path to the Node binary to run scripts with. Falls back to plain `node`
+/// so the spawn error still names the missing program.
+pub fn resolve_node_binary() -> String {
+    candidates()
+        .into_iter()
+        .find(usable)
+        .map(|candidate| candidate.path)
+        .unwrap_or_else(|| "node".to_string())
+}
+
+/// `(major, minor, patch)` from `v22.3.0` or `22.3.0`.
+fn parse_version(raw: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = raw.trim().trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().unwrap_or("0").parse().ok()?;
+    let patch = parts.next().unwrap_or("0").parse().ok()?;
+    Some((major, minor, patch))
+}
+
+pub fn missing_node_message() -> String {
+    format!(
+        "Node.js {MIN_NODE_MAJOR}+ was not found. Install it, or set NODE_BIN or nodePath in settings."
+    )
+}
+
+pub fn node_runtime_info() -> NodeRuntimeInfo {
+    let candidates = candidates();
+    let Some(chosen) = candidates
+        .iter()
+        .find(|candidate| usable(candidate))
+        .cloned()
+    else {
+        return NodeRuntimeInfo {
+            found: false,
+            path: None,
+            source: None,
+            version: None,
+            meets_minimum: false,
+            min_major: MIN_NODE_MAJOR,
+            candidates,
+            error: Some(missing_node_message()),
+        };
+    };
+
+    let (version, error) = match Command::new(&chosen.path).arg("--version").output() {
+        Ok(output) if output.status.success() => (
+            Some(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+            None,
+        ),
+        Ok(output) => (
+            None,
+            Some(
+                String::from_utf8_lossy(&output.stderr)
+                    .trim()
+                    .chars()
+                    .take(300)
+                    .collect(),
+            ),
+        ),
+        Err(error) => (
+            None,
+            Some(format!("Failed to run {}: {error}", chosen.path)),
+        ),
+    };
+    let meets_minimum = version
+        .as_deref()
+        .and_then(parse_version)
+        .is_some_and(|(major, _, _)| major >= MIN_NODE_MAJOR);
+    let error = error.or_else(|| {
+        (!meets_minimum).then(|| {
+            format!(
+                "Node.js {} is too old; {MIN_NODE_MAJOR}+ is required.",
+                version.as_deref().unwrap_or("?")
+            )
+        })
+    });
+    NodeRuntimeInfo {
+        found: true,
+        path: Some(chosen.path),
+        source: Some(chosen.source),
+        version,
+        meets_minimum,
+        min_major: MIN_NODE_MAJOR,
+        candidates,
+        error,
+    }
+}
+
+#[tauri::command]
+pub async fn get_node_runtime_info() -> Result<NodeRuntimeInfo, String> {
+    tauri::async_runtime::spawn_blocking(node_runtime_info)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))
+}
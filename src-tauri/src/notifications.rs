@@ -0,0 +1,122 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::settings::{self, NotificationSettings};
+use crate::windows;
+
+/// Desktop notifications have no click callback, but clicking one brings
+/// the app to the front; a focus this soon after is taken as the click.
+const CLICK_THROUGH_WINDOW: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Copy)]
+pub enum NotifiedJob {
+    StartEditing,
+    EditNow,
+    Render,
+}
+
+impl NotifiedJob {
+    fn enabled(self, settings: &NotificationSettings) -> bool {
+        settings.enabled
+            && match self {
+                Self::StartEditing => settings.start_editing,
+                Self::EditNow => settings.edit_now,
+                Self::Render => settings.render,
+            }
+    }
+
+    fn title(self, succeeded: bool) -> &'static str {
+        match (self, succeeded) {
+            (Self::StartEditing, true) => "Transcript ready",
+            (Self::StartEditing, false) => "Transcription failed",
+            (Self::EditNow, true) => "Edit ready",
+            (Self::EditNow, false) => "Edit failed",
+            (Self::Render, true) => "Render finished",
+            (Self::Render, false) => "Render failed",
+        }
+    }
+}
+
+/// Focus state and the project of the last job notification, registered
+/// as managed state.
+#[derive(Default)]
+pub struct Notifications {
+    focused_windows: Mutex<Vec<String>>,
+    last: Mutex<Option<(String, Instant)>>,
+}
+
+pub fn show(app: &AppHandle, title: &str, body: &str) {
+    if let Err(error) = app.notification().builder().title(title).body(body).show() {
+        tracing::warn!("Failed showing notification: {error}");
+    }
+}
+
+/// Notifies that a pipeline job for `project_id` finished or failed,
+/// unless the settings turn that off.
+pub fn notify_job<T>(
+    app: &AppHandle,
+    job: NotifiedJob,
+    project_id: &str,
+    outcome: &Result<T, String>,
+) {
+    let settings = settings::read_app_settings()
+        .map(|settings| settings.notifications)
+        .unwrap_or_default();
+    if !job.enabled(&settings) {
+        return;
+    }
+    let state = app.state::<Notifications>();
+    let focused = !state
+        .focused_windows
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .is_empty();
+    if settings.only_in_background && focused {
+        return;
+    }
+
+    let name = windows::project_name(project_id).unwrap_or_else(|_| project_id.to_string());
+    let body = match outcome {
+        Ok(_) => format!("{name} is ready. Click to open it."),
+        Err(error) => format!("{name}: {error}"),
+    };
+    show(app, job.title(outcome.is_ok()), &body);
+    *state
+        .last
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) =
+        Some((project_id.to_string(), Instant::now()));
+}
+
+/// Tracks window focus; the first focus after a notification brings up its
+/// project.
+pub fn on_focus_changed(app: &AppHandle, label: &str, focused: bool) {
+    let state = app.state::<Notifications>();
+    {
+        let mut windows = state
+            .focused_windows
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        windows.retain(|open| open != label);
+        if focused {
+            windows.push(label.to_string());
+        } else {
+            return;
+        }
+    }
+    let Some((project_id, at)) = state
+        .last
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .take()
+    else {
+        return;
+    };
+    if at.elapsed() > CLICK_THROUGH_WINDOW {
+        return;
+    }
+    windows::open_in_ui(app, &project_id);
+}
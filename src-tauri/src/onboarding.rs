@@ -0,0 +1,241 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{self, AppSettings, CompletedStep};
+use crate::{ffmpeg, model_registry, node_runtime, now_iso, read_projects};
+
+/// First-run wizard steps, in the order the wizard walks them. Each needs
+/// the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OnboardingStep {
+    DataDir,
+    NodeFound,
+    FfmpegFound,
+    ModelInstalled,
+    SampleProject,
+}
+
+const STEPS: [OnboardingStep; 5] = [
+    OnboardingStep::DataDir,
+    OnboardingStep::NodeFound,
+    OnboardingStep::FfmpegFound,
+    OnboardingStep::ModelInstalled,
+    OnboardingStep::SampleProject,
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepState {
+    pub step: OnboardingStep,
+    pub done: bool,
+    pub completed_at: Option<String>,
+    /// Why the step can't be completed yet, e.g. ffmpeg is still missing.
+    pub blocker: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingState {
+    pub steps: Vec<StepState>,
+    /// First step not done yet; None once the wizard is finished.
+    pub current: Option<OnboardingStep>,
+    pub finished: bool,
+    pub data_dir: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompleteStepRequest {
+    step: OnboardingStep,
+    /// Required for `dataDir`.
+    data_dir: Option<String>,
+    /// Required for `sampleProject`.
+    project_id: Option<String>,
+}
+
+/// Checks that decide whether the environment steps are satisfied. These
+/// are re-run on every read, so a step installed outside the wizard is
+/// picked up and one that broke since shows its blocker again.
+fn detect(step: OnboardingStep) -> Result<(), String> {
+    match step {
+        OnboardingStep::NodeFound => {
+            let info = node_runtime::node_runtime_info();
+            match info.error {
+                Some(error) if !info.meets_minimum => Err(error),
+                _ => Ok(()),
+            }
+        }
+        OnboardingStep::FfmpegFound => {
+            let info = ffmpeg::ffmpeg_info();
+            if info.ffmpeg_path.is_some() {
+                Ok(())
+            } else {
+                Err(info
+                    .error
+                    .unwrap_or_else(|| "ffmpeg was not found.".to_string()))
+            }
+        }
+        OnboardingStep::ModelInstalled => {
+            if model_registry::installed_models()?.is_empty() {
+                Err("No transcription or planner model is installed yet.".to_string())
+            } else {
+                Ok(())
+            }
+        }
+        OnboardingStep::DataDir | OnboardingStep::SampleProject => Ok(()),
+    }
+}
+
+fn is_detected(step: OnboardingStep) -> bool {
+    matches!(
+        step,
+        OnboardingStep::NodeFound | OnboardingStep::FfmpegFound | OnboardingStep::ModelInstalled
+    )
+}
+
+fn completed_at(settings: &AppSettings, step: OnboardingStep) -> Option<String> {
+    settings
+        .onboarding
+        .completed
+        .iter()
+        .find(|completed| completed.step == step)
+        .map(|completed| completed.completed_at.clone())
+}
+
+fn mark_completed(settings: &mut AppSettings, step: OnboardingStep) {
+    if completed_at(settings, step).is_none() {
+        settings.onboarding.completed.push(CompletedStep {
+            step,
+            completed_at: now_iso(),
+        });
+    }
+}
+
+fn build_state(settings: &mut AppSettings) -> (OnboardingState, bool) {
+    let mut changed = false;
+    let mut steps = Vec::new();
+    let mut blocked = false;
+    for step in STEPS {
+        let blocker = if is_detected(step) {
+            detect(step).err()
+        } else {
+            None
+        };
+        // Detected steps complete themselves once everything before is done.
+        if !blocked
+            && blocker.is_none()
+            && is_detected(step)
+            && completed_at(settings, step).is_none()
+        {
+            mark_completed(settings, step);
+            changed = true;
+        }
+        let completed_at = completed_at(settings, step);
+        let done = completed_at.is_some() && blocker.is_none();
+        blocked |= !done;
+        steps.push(StepState {
+            step,
+            done,
+            completed_at,
+            blocker,
+        });
+    }
+    let current = steps
+        .iter()
+        .find(|state| !state.done)
+        .map(|state| state.step);
+    if current.is_none() && settings.onboarding.finished_at.is_none() {
+        settings.onboarding.finished_at = Some(now_iso());
+        changed = true;
+    }
+    let state = OnboardingState {
+        steps,
+        current,
+        finished: settings.onboarding.finished_at.is_some(),
+        data_dir: settings.onboarding.data_dir.clone(),
+    };
+    (state, changed)
+}
+
+fn onboarding_state() -> Result<OnboardingState, String> {
+    let mut settings = settings::read_app_settings()?;
+    let (state, changed) = build_state(&mut settings);
+    if changed {
+        settings::write_app_settings(&settings)?;
+    }
+    Ok(state)
+}
+
+/// Creates the directory and writes a probe file so a read-only or
+/// unmounted location is caught here rather than at the first import.
+fn check_data_dir(raw: &str) -> Result<String, String> {
+    let dir = PathBuf::from(raw.trim());
+    if raw.trim().is_empty() || !dir.is_absolute() {
+        return Err("Choose an absolute path for the data directory.".to_string());
+    }
+    fs::create_dir_all(&dir).map_err(|error| format!("Failed creating data dir: {error}"))?;
+    let probe = dir.join(".write-test");
+    fs::write(&probe, b"ok").map_err(|error| format!("Data dir is not writable: {error}"))?;
+    let _ = fs::remove_file(&probe);
+    Ok(dir.to_string_lossy().to_string())
+}
+
+fn complete_step(request: CompleteStepRequest) -> Result<OnboardingState, String> {
+    let mut settings = settings::read_app_settings()?;
+    let (state, _) = build_state(&mut settings);
+    if let Some(pending) = state
+        .steps
+        .iter()
+        .take_while(|state| state.step != request.step)
+        .find(|state| !state.done)
+    {
+        return Err(format!(
+            "Complete the {:?} step before {:?}.",
+            pending.step, request.step
+        ));
+    }
+    match request.step {
+        OnboardingStep::DataDir => {
+            let raw = request
+                .data_dir
+                .ok_or_else(|| "dataDir is required for the dataDir step.".to_string())?;
+            settings.onboarding.data_dir = Some(check_data_dir(&raw)?);
+        }
+        OnboardingStep::SampleProject => {
+            let project_id = request
+                .project_id
+                .ok_or_else(|| "projectId is required for the sampleProject step.".to_string())?;
+            if !read_projects()?
+                .iter()
+                .any(|project| project.id == project_id)
+            {
+                return Err(format!("Project not found: {project_id}"));
+            }
+        }
+        step => detect(step)?,
+    }
+    mark_completed(&mut settings, request.step);
+    let (state, _) = build_state(&mut settings);
+    settings::write_app_settings(&settings)?;
+    tracing::info!("Onboarding step {:?} completed", request.step);
+    Ok(state)
+}
+
+#[tauri::command]
+pub async fn get_onboarding_state() -> Result<OnboardingState, String> {
+    tauri::async_runtime::spawn_blocking(onboarding_state)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn complete_onboarding_step(
+    request: CompleteStepRequest,
+) -> Result<OnboardingState, String> {
+    tauri::async_runtime::spawn_blocking(move || complete_step(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,1012 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::media::{decode_file_url, resolve_source_path};
+use crate::project_status::ProjectStatus;
+use crate::{
+    generate_project_id, inherit_timeline_identity, mark_timeline_written, now_iso, path_safety,
+    read_timeline, speed, write_timeline, Timeline, TimelineClip, TimelineTrack,
+};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOtioRequest {
+    project_id: String,
+    /// Defaults to `project.otio` in the project directory.
+    path: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOtioRequest {
+    project_id: String,
+    path: String,
+    /// Convert and report without touching the stored timeline.
+    dry_run: Option<bool>,
+}
+
+/// Something in the OTIO document the internal timeline cannot represent.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtioIssue {
+    pub feature: String,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtioImport {
+    pub dry_run: bool,
+    pub unsupported: Vec<OtioIssue>,
+    pub clip_count: usize,
+    pub marker_count: usize,
+    pub timeline: Timeline,
+}
+
+/// Converts microseconds to an OTIO `RationalTime` at the timeline frame rate.
+/// Values are kept fractional so off-grid cuts survive the round trip.
+pub fn rational_time(time_us: u64, rate: u32) -> Value {
+    let rate = f64::from(rate.max(1));
+    json!({
+        "OTIO_SCHEMA": "RationalTime.1",
+        "rate": rate,
+        "value": time_us as f64 * rate / 1_000_000.0,
+    })
+}
+
+pub fn time_range(start_us: u64, duration_us: u64, rate: u32) -> Value {
+    json!({
+        "OTIO_SCHEMA": "TimeRange.1",
+        "start_time": rational_time(start_us, rate),
+        "duration": rational_time(duration_us, rate),
+    })
+}
+
+fn gap(duration_us: u64, rate: u32) -> Value {
+    json!({
+        "OTIO_SCHEMA": "Gap.1",
+        "name": "",
+        "source_range": time_range(0, duration_us, rate),
+        "effects": [],
+        "markers": [],
+        "enabled": true,
+        "metadata": {},
+    })
+}
+
+fn media_reference(source_ref: &str, media_path: Option<String>) -> Value {
+    match media_path {
+        Some(path) => json!({
+            "OTIO_SCHEMA": "ExternalReference.1",
+            "name": source_ref,
+            "target_url": format!("file://{}", path.replace(' ', "%20")),
+            "available_range": null,
+            "metadata": {},
+        }),
+        None => json!({
+            "OTIO_SCHEMA": "MissingReference.1",
+            "name": source_ref,
+            "available_range": null,
+            "metadata": {},
+        }),
+    }
+}
+
+/// OTIO source ranges are in source time, so a sped-up clip covers more
+/// source than its timeline length. Constant speed becomes a
+/// `LinearTimeWarp`; keyframed speed has no OTIO equivalent and is reported.
+fn clip(
+    clip: &TimelineClip,
+    trimmed_us: u64,
+    rate: u32,
+    resolve_media: &dyn Fn(&str) -> Option<String>,
+    issues: &mut Vec<OtioIssue>,
+) -> Value {
+    let source_start_us = (clip.source_start_us
+        + speed::timeline_to_source_offset(clip, trimmed_us))
+    .min(clip.source_end_us);
+    let mut effects = Vec::new();
+    if !clip.speed_keyframes.is_empty() {
+        issues.push(OtioIssue {
+            feature: "speed keyframes".to_string(),
+            location: format!("clip {}", clip.clip_id),
+        });
+    } else if clip.speed != 1.0 {
+        effects.push(json!({
+            "OTIO_SCHEMA": "LinearTimeWarp.1",
+            "name": "",
+            "effect_name": "LinearTimeWarp",
+            "time_scalar": clip.speed,
+            "metadata": {},
+        }));
+    }
+    json!({
+        "OTIO_SCHEMA": "Clip.2",
+        "name": clip.clip_id,
+        "source_range": time_range(source_start_us, clip.source_end_us - source_start_us, rate),
+        "media_references": {
+            "DEFAULT_MEDIA": media_reference(&clip.source_ref, resolve_media(&clip.source_ref)),
+        },
+        "active_media_reference_key": "DEFAULT_MEDIA",
+        "effects": effects,
+        "markers": [],
+        "enabled": true,
+        "metadata": {
+            "aiVideoEditor": {
+                "clipId": clip.clip_id,
+                "clipType": clip.clip_type,
+                "effects": clip.effects,
+                "transform": clip.transform,
+                "meta": clip.meta,
+            }
+        },
+    })
+}
+
+/// Lays a track's clips out back to back, filling holes with gaps. OTIO
+/// tracks cannot overlap, so a clip starting before the previous one ends
+/// loses its head (and is dropped if nothing is left).
+fn track_children(
+    clips: &[&TimelineClip],
+    rate: u32,
+    resolve_media: &dyn Fn(&str) -> Option<String>,
+    issues: &mut Vec<OtioIssue>,
+) -> Vec<Value> {
+    let mut sorted = clips.to_vec();
+    sorted.sort_by_key(|clip| clip.start_us);
+
+    let mut children = Vec::new();
+    let mut cursor = 0_u64;
+    for item in sorted {
+        let start_us = item.start_us.max(cursor);
+        if item.end_us <= start_us {
+            continue;
+        }
+        if start_us > cursor {
+            children.push(gap(start_us - cursor, rate));
+        }
+        children.push(clip(
+            item,
+            start_us - item.start_us,
+            rate,
+            resolve_media,
+            issues,
+        ));
+        cursor = item.end_us;
+    }
+    children
+}
+
+fn marker(clip: &TimelineClip, track: &TimelineTrack, rate: u32) -> Value {
+    let is_caption = track.kind == "caption";
+    let name = clip
+        .meta
+        .get("text")
+        .or_else(|| clip.meta.get("reason"))
+        .and_then(Value::as_str)
+        .unwrap_or(&clip.clip_id);
+    json!({
+        "OTIO_SCHEMA": "Marker.2",
+        "name": name,
+        "color": if is_caption { "GREEN" } else { "RED" },
+        "marked_range": time_range(clip.start_us, clip.end_us.saturating_sub(clip.start_us), rate),
+        "comment": "",
+        "metadata": {
+            "aiVideoEditor": {
+                "clipId": clip.clip_id,
+                "clipType": clip.clip_type,
+                "trackId": track.id,
+                "meta": clip.meta,
+            }
+        },
+    })
+}
+
+/// Converts the internal timeline into an OTIO `Timeline.1` document.
+/// Video and audio tracks become OTIO tracks; caption and marker tracks have
+/// no OTIO equivalent and are exported as markers on the top-level stack.
+/// Anything that cannot be written faithfully is pushed to `issues`.
+pub fn timeline_to_otio(
+    timeline: &Timeline,
+    resolve_media: &dyn Fn(&str) -> Option<String>,
+    issues: &mut Vec<OtioIssue>,
+) -> Value {
+    let rate = timeline.fps.max(1);
+    let mut tracks = timeline.tracks.iter().collect::<Vec<_>>();
+    tracks.sort_by_key(|track| track.order);
+
+    let mut otio_tracks = Vec::new();
+    let mut markers = Vec::new();
+    for track in tracks {
+        let clips = timeline
+            .clips
+            .iter()
+            .filter(|clip| clip.track_id == track.id)
+            .collect::<Vec<_>>();
+        let kind = match track.kind.as_str() {
+            "video" => "Video",
+            "audio" => "Audio",
+            _ => {
+                markers.extend(clips.iter().map(|clip| marker(clip, track, rate)));
+                continue;
+            }
+        };
+        otio_tracks.push(json!({
+            "OTIO_SCHEMA": "Track.1",
+            "name": track.name,
+            "kind": kind,
+            "children": track_children(&clips, rate, resolve_media, issues),
+            "source_range": null,
+            "effects": [],
+            "markers": [],
+            "enabled": true,
+            "metadata": { "aiVideoEditor": { "trackId": track.id, "locked": track.locked } },
+        }));
+    }
+
+    json!({
+        "OTIO_SCHEMA": "Timeline.1",
+        "name": timeline.project_id,
+        "global_start_time": null,
+        "metadata": {
+            "aiVideoEditor": {
+                "projectId": timeline.project_id,
+                "timelineId": timeline.id,
+                "version": timeline.version,
+            }
+        },
+        "tracks": {
+            "OTIO_SCHEMA": "Stack.1",
+            "name": "tracks",
+            "children": otio_tracks,
+            "source_range": null,
+            "effects": [],
+            "markers": markers,
+            "enabled": true,
+            "metadata": {},
+        },
+    })
+}
+
+fn schema_name(item: &Value) -> &str {
+    item.get("OTIO_SCHEMA")
+        .and_then(Value::as_str)
+        .and_then(|schema| schema.split('.').next())
+        .unwrap_or("")
+}
+
+fn item_name(item: &Value) -> &str {
+    item.get("name").and_then(Value::as_str).unwrap_or("")
+}
+
+/// Inverse of `rational_time`.
+pub fn rational_time_to_us(time: &Value) -> Option<u64> {
+    let rate = time.get("rate")?.as_f64().filter(|rate| *rate > 0.0)?;
+    let value = time.get("value")?.as_f64()?;
+    Some((value.max(0.0) * 1_000_000.0 / rate).round() as u64)
+}
+
+/// Returns `(start_us, duration_us)` of an OTIO `TimeRange`.
+pub fn time_range_to_us(range: &Value) -> Option<(u64, u64)> {
+    Some((
+        rational_time_to_us(range.get("start_time")?)?,
+        rational_time_to_us(range.get("duration")?)?,
+    ))
+}
+
+fn editor_metadata(item: &Value) -> Option<&Value> {
+    item.get("metadata")?.get("aiVideoEditor")
+}
+
+fn active_media_reference(clip: &Value) -> Option<&Value> {
+    if let Some(references) = clip.get("media_references") {
+        let key = clip
+            .get("active_media_reference_key")
+            .and_then(Value::as_str)
+            .unwrap_or("DEFAULT_MEDIA");
+        return references.get(key);
+    }
+    clip.get("media_reference")
+}
+
+fn clip_source_ref(clip: &Value) -> String {
+    let reference = active_media_reference(clip);
+    let name = reference.map(item_name).filter(|name| !name.is_empty());
+    // Our own exports carry the original sourceRef as the reference name.
+    if editor_metadata(clip).is_some() {
+        if let Some(name) = name {
+            return name.to_string();
+        }
+    }
+    reference
+        .and_then(|reference| reference.get("target_url"))
+        .and_then(Value::as_str)
+        .map(decode_file_url)
+        .or_else(|| name.map(str::to_string))
+        .unwrap_or_else(|| item_name(clip).to_string())
+}
+
+fn report_effects(item: &Value, location: &str, issues: &mut Vec<OtioIssue>) {
+    let effects = item.get("effects").and_then(Value::as_array);
+    for effect in effects.into_iter().flatten() {
+        let feature = match schema_name(effect) {
+            "LinearTimeWarp" | "FreezeFrame" => "speed effect".to_string(),
+            _ => format!(
+                "effect {}",
+                effect
+                    .get("effect_name")
+                    .and_then(Value::as_str)
+                    .unwrap_or(schema_name(effect))
+            ),
+        };
+        issues.push(OtioIssue {
+            feature,
+            location: location.to_string(),
+        });
+    }
+}
+
+fn import_marker(
+    marker: &Value,
+    default_track_id: &str,
+    issues: &mut Vec<OtioIssue>,
+) -> Option<TimelineClip> {
+    let Some((start_us, duration_us)) = marker.get("marked_range").and_then(time_range_to_us)
+    else {
+        issues.push(OtioIssue {
+            feature: "marker without range".to_string(),
+            location: format!("marker {}", item_name(marker)),
+        });
+        return None;
+    };
+    let metadata = editor_metadata(marker);
+    let text = |key: &str| {
+        metadata
+            .and_then(|meta| meta.get(key))
+            .and_then(Value::as_str)
+    };
+    Some(TimelineClip {
+        clip_id: text("clipId").unwrap_or_default().to_string(),
+        track_id: text("trackId").unwrap_or(default_track_id).to_string(),
+        clip_type: text("clipType").unwrap_or("marker").to_string(),
+        start_us,
+        end_us: start_us + duration_us,
+        source_start_us: start_us,
+        source_end_us: start_us + duration_us,
+        speed: 1.0,
+        speed_keyframes: Vec::new(),
+        locked: false,
+        protected_from_ai: false,
+        source_ref: "marker".to_string(),
+        effects: json!({}),
+        transform: json!({}),
+        meta: metadata
+            .and_then(|meta| meta.get("meta"))
+            .cloned()
+            .unwrap_or_else(|| json!({ "text": item_name(marker) })),
+    })
+}
+
+fn import_clip(
+    clip: &Value,
+    track_id: &str,
+    cursor: u64,
+    location: &str,
+    issues: &mut Vec<OtioIssue>,
+) -> Option<TimelineClip> {
+    let source_range = clip
+        .get("source_range")
+        .filter(|range| !range.is_null())
+        .or_else(|| {
+            active_media_reference(clip)?
+                .get("available_range")
+                .filter(|range| !range.is_null())
+        });
+    let Some((source_start_us, duration_us)) = source_range.and_then(time_range_to_us) else {
+        issues.push(OtioIssue {
+            feature: "clip without source range".to_string(),
+            location: location.to_string(),
+        });
+        return None;
+    };
+    report_effects(clip, location, issues);
+
+    let metadata = editor_metadata(clip);
+    let restored = |key: &str| {
+        metadata
+            .and_then(|meta| meta.get(key))
+            .cloned()
+            .unwrap_or_else(|| json!({}))
+    };
+    Some(TimelineClip {
+        clip_id: metadata
+            .and_then(|meta| meta.get("clipId"))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        track_id: track_id.to_string(),
+        clip_type: metadata
+            .and_then(|meta| meta.get("clipType"))
+            .and_then(Value::as_str)
+            .unwrap_or("source_clip")
+            .to_string(),
+        start_us: cursor,
+        end_us: cursor + duration_us,
+        source_start_us,
+        source_end_us: source_start_us + duration_us,
+        speed: 1.0,
+        speed_keyframes: Vec::new(),
+        locked: false,
+        protected_from_ai: false,
+        source_ref: clip_source_ref(clip),
+        effects: restored("effects"),
+        transform: restored("transform"),
+        meta: match metadata.and_then(|meta| meta.get("meta")) {
+            Some(meta) => meta.clone(),
+            None => json!({ "generatedBy": "otio-import", "otioName": item_name(clip) }),
+        },
+    })
+}
+
+/// First rate found on any clip or gap, used as the timeline frame rate.
+fn document_rate(item: &Value) -> Option<f64> {
+    if let Some(rate) = item
+        .get("source_range")
+        .and_then(|range| range.get("duration"))
+        .and_then(|duration| duration.get("rate"))
+        .and_then(Value::as_f64)
+    {
+        return Some(rate);
+    }
+    item.get("children")
+        .and_then(Value::as_array)?
+        .iter()
+        .find_map(document_rate)
+}
+
+/// Converts an OTIO `Timeline.1` document back into the internal timeline.
+/// Anything that can't be represented is skipped and listed in the issues.
+pub fn otio_to_timeline(
+    document: &Value,
+    project_id: &str,
+    issues: &mut Vec<OtioIssue>,
+) -> Result<Timeline, String> {
+    if schema_name(document) != "Timeline" {
+        return Err(format!(
+            "Expected an OTIO Timeline, found {:?}.",
+            document
+                .get("OTIO_SCHEMA")
+                .and_then(Value::as_str)
+                .unwrap_or("nothing")
+        ));
+    }
+    let stack = document
+        .get("tracks")
+        .filter(|stack| schema_name(stack) == "Stack")
+        .ok_or_else(|| "OTIO timeline has no track stack.".to_string())?;
+    let fps = document_rate(stack).unwrap_or(30.0).round().max(1.0) as u32;
+
+    let mut tracks = Vec::new();
+    let mut clips = Vec::new();
+    let mut markers = Vec::new();
+    let (mut video_count, mut audio_count) = (0, 0);
+    let children = stack.get("children").and_then(Value::as_array);
+
+    for (track_index, track) in children.into_iter().flatten().enumerate() {
+        let location = format!("track {} ({})", track_index + 1, item_name(track));
+        if schema_name(track) != "Track" {
+            issues.push(OtioIssue {
+                feature: format!("nested {}", schema_name(track).to_lowercase()),
+                location,
+            });
+            continue;
+        }
+        let kind = match track.get("kind").and_then(Value::as_str) {
+            Some("Audio") => "audio",
+            _ => "video",
+        };
+        let generated_id = if kind == "audio" {
+            audio_count += 1;
+            format!("track-audio-{audio_count}")
+        } else {
+            video_count += 1;
+            if video_count == 1 {
+                "track-video-main".to_string()
+            } else {
+                format!("track-video-{video_count}")
+            }
+        };
+        let track_id = editor_metadata(track)
+            .and_then(|meta| meta.get("trackId"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or(generated_id);
+        report_effects(track, &location, issues);
+        let track_markers = track.get("markers").and_then(Value::as_array);
+        for marker in track_markers.into_iter().flatten() {
+            markers.extend(import_marker(marker, "track-markers", issues));
+        }
+
+        let mut cursor = 0_u64;
+        let items = track.get("children").and_then(Value::as_array);
+        for (item_index, item) in items.into_iter().flatten().enumerate() {
+            let item_location = format!("{location} item {} ({})", item_index + 1, item_name(item));
+            if !item.get("enabled").and_then(Value::as_bool).unwrap_or(true) {
+                issues.push(OtioIssue {
+                    feature: "disabled item".to_string(),
+                    location: item_location,
+                });
+                continue;
+            }
+            match schema_name(item) {
+                "Gap" => {
+                    let duration = item.get("source_range").and_then(time_range_to_us);
+                    cursor += duration.map(|(_, duration_us)| duration_us).unwrap_or(0);
+                }
+                "Clip" => {
+                    if let Some(clip) = import_clip(item, &track_id, cursor, &item_location, issues)
+                    {
+                        cursor = clip.end_us;
+                        clips.push(clip);
+                    }
+                }
+                "Transition" => issues.push(OtioIssue {
+                    feature: "transition".to_string(),
+                    location: item_location,
+                }),
+                other => {
+                    issues.push(OtioIssue {
+                        feature: format!("nested {}", other.to_lowercase()),
+                        location: item_location,
+                    });
+                    // Keep later items in place even though this one is dropped.
+                    let duration = item.get("source_range").and_then(time_range_to_us);
+                    cursor += duration.map(|(_, duration_us)| duration_us).unwrap_or(0);
+                }
+            }
+        }
+
+        tracks.push(TimelineTrack {
+            id: track_id,
+            name: item_name(track).to_string(),
+            kind: kind.to_string(),
+            order: tracks.len() as u32,
+            locked: editor_metadata(track)
+                .and_then(|meta| meta.get("locked"))
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+        });
+    }
+
+    let stack_markers = stack.get("markers").and_then(Value::as_array);
+    for marker in stack_markers.into_iter().flatten() {
+        markers.extend(import_marker(marker, "track-markers", issues));
+    }
+    for marker in &markers {
+        if !tracks.iter().any(|track| track.id == marker.track_id) {
+            let caption = marker.track_id == "track-captions";
+            tracks.push(TimelineTrack {
+                id: marker.track_id.clone(),
+                name: if caption { "Captions" } else { "Markers" }.to_string(),
+                kind: if caption { "caption" } else { "marker" }.to_string(),
+                order: tracks.len() as u32,
+                locked: false,
+            });
+        }
+    }
+    if !tracks.iter().any(|track| track.kind == "caption") {
+        tracks.push(TimelineTrack {
+            id: "track-captions".to_string(),
+            name: "Captions".to_string(),
+            kind: "caption".to_string(),
+            order: tracks.len() as u32,
+            locked: false,
+        });
+    }
+
+    clips.extend(markers);
+    for (index, clip) in clips.iter_mut().enumerate() {
+        if clip.clip_id.is_empty() {
+            clip.clip_id = format!("clip-{}", index + 1);
+        }
+    }
+
+    let now = now_iso();
+    Ok(Timeline {
+        id: format!("timeline-{}", generate_project_id()),
+        project_id: project_id.to_string(),
+        version: 1,
+        status: "ROUGH_CUT_READY".to_string(),
+        fps,
+        duration_us: clips.iter().map(|clip| clip.end_us).max().unwrap_or(0),
+        created_at: now.clone(),
+        updated_at: now,
+        tracks,
+        clips,
+        meta: json!({ "importedFrom": { "format": "otio", "name": item_name(document) } }),
+    })
+}
+
+fn export_otio_blocking(request: ExportOtioRequest) -> Result<Value, String> {
+    let timeline = read_timeline(&request.project_id)?;
+    let output = match request.path.filter(|path| !path.trim().is_empty()) {
+        Some(path) => PathBuf::from(path),
+        None => path_safety::project_dir(&request.project_id)?.join("project.otio"),
+    };
+
+    let project_id = request.project_id.clone();
+    let resolve_media = move |source_ref: &str| {
+        resolve_source_path(&project_id, Some(source_ref))
+            .ok()
+            .map(|path| path.to_string_lossy().to_string())
+    };
+    let mut unsupported = Vec::new();
+    let document = timeline_to_otio(&timeline, &resolve_media, &mut unsupported);
+
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating export dir: {error}"))?;
+    }
+    let serialized = serde_json::to_string_pretty(&document)
+        .map_err(|error| format!("OTIO serialize error: {error}"))?;
+    fs::write(&output, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing OTIO file: {error}"))?;
+
+    Ok(json!({
+        "ok": true,
+        "path": output.to_string_lossy(),
+        "unsupported": unsupported,
+    }))
+}
+
+#[tauri::command]
+pub async fn export_otio(request: ExportOtioRequest) -> Result<Value, String> {
+    tauri::async_runtime::spawn_blocking(move || export_otio_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+fn import_otio_blocking(request: ImportOtioRequest) -> Result<OtioImport, String> {
+    let raw = fs::read_to_string(&request.path)
+        .map_err(|error| format!("Failed reading OTIO file: {error}"))?;
+    let document = serde_json::from_str::<Value>(&raw)
+        .map_err(|error| format!("Invalid OTIO JSON: {error}"))?;
+
+    let mut unsupported = Vec::new();
+    let mut timeline = otio_to_timeline(&document, &request.project_id, &mut unsupported)?;
+    let dry_run = request.dry_run.unwrap_or(false);
+    if !dry_run {
+        inherit_timeline_identity(&mut timeline);
+        write_timeline(&timeline)?;
+        mark_timeline_written(&request.project_id, ProjectStatus::RoughCutReady)?;
+    }
+
+    let marker_count = timeline
+        .clips
+        .iter()
+        .filter(|clip| {
+            timeline.tracks.iter().any(|track| {
+                track.id == clip.track_id && track.kind != "video" && track.kind != "audio"
+            })
+        })
+        .count();
+    Ok(OtioImport {
+        dry_run,
+        unsupported,
+        clip_count: timeline.clips.len() - marker_count,
+        marker_count,
+        timeline,
+    })
+}
+
+#[tauri::command]
+pub async fn import_otio(request: ImportOtioRequest) -> Result<OtioImport, String> {
+    tauri::async_runtime::spawn_blocking(move || import_otio_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{timeline, track};
+
+    fn source_clip(
+        id: &str,
+        track_id: &str,
+        start_us: u64,
+        end_us: u64,
+        source_start_us: u64,
+    ) -> TimelineClip {
+        let mut clip = crate::test_fixtures::clip(id, track_id, start_us, end_us);
+        clip.source_start_us = source_start_us;
+        clip.source_end_us = source_start_us + (end_us - start_us);
+        clip
+    }
+
+    fn no_media(_: &str) -> Option<String> {
+        None
+    }
+
+    fn value(time: &Value) -> f64 {
+        time["value"].as_f64().unwrap()
+    }
+
+    #[test]
+    fn rational_time_counts_frames_at_rate() {
+        assert_eq!(value(&rational_time(1_000_000, 30)), 30.0);
+        assert_eq!(value(&rational_time(2_500_000, 24)), 60.0);
+        assert_eq!(rational_time(0, 25)["rate"].as_f64(), Some(25.0));
+    }
+
+    #[test]
+    fn rational_time_keeps_sub_frame_precision() {
+        // Half a frame at 30 fps.
+        let half_frame_us = 1_000_000 / 60;
+        let frames = value(&rational_time(half_frame_us, 30));
+        assert!((frames - 0.5).abs() < 1e-3, "{frames}");
+    }
+
+    #[test]
+    fn zero_fps_falls_back_to_one() {
+        let time = rational_time(3_000_000, 0);
+        assert_eq!(time["rate"].as_f64(), Some(1.0));
+        assert_eq!(value(&time), 3.0);
+    }
+
+    #[test]
+    fn time_range_uses_start_and_duration() {
+        let range = time_range(1_000_000, 500_000, 30);
+        assert_eq!(value(&range["start_time"]), 30.0);
+        assert_eq!(value(&range["duration"]), 15.0);
+    }
+
+    #[test]
+    fn clips_map_source_ranges_and_fill_gaps() {
+        let timeline = timeline(
+            30,
+            vec![track("track-video-main", "video", 0)],
+            vec![
+                source_clip("clip-1", "track-video-main", 0, 1_000_000, 2_000_000),
+                source_clip(
+                    "clip-2",
+                    "track-video-main",
+                    2_000_000,
+                    3_000_000,
+                    5_000_000,
+                ),
+            ],
+        );
+        let document = timeline_to_otio(&timeline, &no_media, &mut Vec::new());
+        let children = document["tracks"]["children"][0]["children"]
+            .as_array()
+            .unwrap();
+
+        assert_eq!(children.len(), 3);
+        assert_eq!(children[0]["OTIO_SCHEMA"], "Clip.2");
+        assert_eq!(value(&children[0]["source_range"]["start_time"]), 60.0);
+        assert_eq!(value(&children[0]["source_range"]["duration"]), 30.0);
+        assert_eq!(children[1]["OTIO_SCHEMA"], "Gap.1");
+        assert_eq!(value(&children[1]["source_range"]["duration"]), 30.0);
+        assert_eq!(value(&children[2]["source_range"]["start_time"]), 150.0);
+    }
+
+    #[test]
+    fn overlapping_clip_loses_its_head() {
+        let timeline = timeline(
+            25,
+            vec![track("track-video-main", "video", 0)],
+            vec![
+                source_clip("clip-1", "track-video-main", 0, 2_000_000, 0),
+                source_clip(
+                    "clip-2",
+                    "track-video-main",
+                    1_000_000,
+                    3_000_000,
+                    10_000_000,
+                ),
+                source_clip("clip-3", "track-video-main", 1_500_000, 1_800_000, 0),
+            ],
+        );
+        let document = timeline_to_otio(&timeline, &no_media, &mut Vec::new());
+        let children = document["tracks"]["children"][0]["children"]
+            .as_array()
+            .unwrap();
+
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[1]["name"], "clip-2");
+        assert_eq!(value(&children[1]["source_range"]["start_time"]), 275.0);
+        assert_eq!(value(&children[1]["source_range"]["duration"]), 25.0);
+    }
+
+    #[test]
+    fn sped_up_clip_exports_source_span_and_time_warp() {
+        let mut fast = source_clip("clip-1", "track-video-main", 0, 1_000_000, 2_000_000);
+        fast.source_end_us = 4_000_000;
+        fast.speed = 2.0;
+        let mut ramped = source_clip("clip-2", "track-video-main", 1_000_000, 2_000_000, 0);
+        ramped.speed_keyframes = vec![speed::SpeedKeyframe {
+            source_offset_us: 500_000,
+            speed: 0.5,
+        }];
+        let timeline = timeline(
+            30,
+            vec![track("track-video-main", "video", 0)],
+            vec![fast, ramped],
+        );
+        let mut issues = Vec::new();
+        let document = timeline_to_otio(&timeline, &no_media, &mut issues);
+        let children = &document["tracks"]["children"][0]["children"];
+
+        let range = &children[0]["source_range"];
+        assert_eq!(value(&range["start_time"]), 60.0);
+        assert_eq!(value(&range["duration"]), 60.0);
+        assert_eq!(children[0]["effects"][0]["OTIO_SCHEMA"], "LinearTimeWarp.1");
+        assert_eq!(children[0]["effects"][0]["time_scalar"].as_f64(), Some(2.0));
+        assert_eq!(children[1]["effects"], json!([]));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].feature, "speed keyframes");
+        assert_eq!(issues[0].location, "clip clip-2");
+    }
+
+    #[test]
+    fn caption_and_marker_tracks_become_stack_markers() {
+        let mut caption = source_clip("caption-1", "track-captions", 500_000, 1_500_000, 0);
+        caption.clip_type = "caption".to_string();
+        caption.meta = json!({ "text": "Hello there" });
+        let mut cut = source_clip("cut-gap-1", "track-rawcuts", 0, 250_000, 0);
+        cut.clip_type = "cut_marker".to_string();
+        cut.meta = json!({ "reason": "silence" });
+
+        let timeline = timeline(
+            30,
+            vec![
+                track("track-captions", "caption", 2),
+                track("track-video-main", "video", 0),
+                track("track-rawcuts", "marker", 1),
+            ],
+            vec![caption, cut],
+        );
+        let document = timeline_to_otio(&timeline, &no_media, &mut Vec::new());
+        let stack = &document["tracks"];
+        let markers = stack["markers"].as_array().unwrap();
+
+        assert_eq!(stack["children"].as_array().unwrap().len(), 1);
+        assert_eq!(markers.len(), 2);
+        assert_eq!(markers[0]["name"], "silence");
+        assert_eq!(markers[0]["color"], "RED");
+        assert_eq!(markers[1]["name"], "Hello there");
+        assert_eq!(markers[1]["color"], "GREEN");
+        assert_eq!(value(&markers[1]["marked_range"]["start_time"]), 15.0);
+        assert_eq!(value(&markers[1]["marked_range"]["duration"]), 30.0);
+    }
+
+    #[test]
+    fn resolved_media_becomes_external_reference() {
+        let timeline = timeline(
+            30,
+            vec![track("track-video-main", "video", 0)],
+            vec![source_clip("clip-1", "track-video-main", 0, 1_000_000, 0)],
+        );
+        let resolve = |_: &str| Some("/media/My Clip.mov".to_string());
+        let document = timeline_to_otio(&timeline, &resolve, &mut Vec::new());
+        let reference =
+            &document["tracks"]["children"][0]["children"][0]["media_references"]["DEFAULT_MEDIA"];
+
+        assert_eq!(reference["OTIO_SCHEMA"], "ExternalReference.1");
+        assert_eq!(reference["target_url"], "file:///media/My%20Clip.mov");
+    }
+
+    #[test]
+    fn rational_time_round_trips_to_microseconds() {
+        for time_us in [0, 1, 33_333, 1_000_000, 3_723_456_789] {
+            assert_eq!(
+                rational_time_to_us(&rational_time(time_us, 30)),
+                Some(time_us)
+            );
+        }
+        assert_eq!(
+            rational_time_to_us(&json!({ "rate": 0.0, "value": 1.0 })),
+            None
+        );
+    }
+
+    #[test]
+    fn export_then_import_restores_clips_and_markers() {
+        let mut caption = source_clip("caption-1", "track-captions", 500_000, 1_500_000, 500_000);
+        caption.clip_type = "caption".to_string();
+        caption.source_ref = "marker".to_string();
+        caption.meta = json!({ "text": "Hello there" });
+        let original = timeline(
+            30,
+            vec![
+                track("track-video-main", "video", 0),
+                track("track-captions", "caption", 1),
+            ],
+            vec![
+                source_clip("clip-1", "track-video-main", 0, 1_000_000, 2_000_000),
+                source_clip(
+                    "clip-2",
+                    "track-video-main",
+                    2_000_000,
+                    3_000_000,
+                    5_000_000,
+                ),
+                caption,
+            ],
+        );
+        let document = timeline_to_otio(&original, &no_media, &mut Vec::new());
+        let mut issues = Vec::new();
+        let imported = otio_to_timeline(&document, "project-test", &mut issues).unwrap();
+
+        assert!(issues.is_empty(), "{issues:?}");
+        assert_eq!(imported.fps, 30);
+        assert_eq!(imported.tracks.len(), 2);
+        assert_eq!(imported.clips.len(), 3);
+        for (before, after) in original.clips.iter().zip(&imported.clips) {
+            assert_eq!(before.clip_id, after.clip_id);
+            assert_eq!(before.track_id, after.track_id);
+            assert_eq!(before.clip_type, after.clip_type);
+            assert_eq!(before.start_us, after.start_us);
+            assert_eq!(before.end_us, after.end_us);
+            assert_eq!(before.source_start_us, after.source_start_us);
+            assert_eq!(before.source_ref, after.source_ref);
+            assert_eq!(before.meta, after.meta);
+        }
+    }
+
+    #[test]
+    fn import_reports_speed_effects_and_nested_stacks() {
+        let document = json!({
+            "OTIO_SCHEMA": "Timeline.1",
+            "name": "from resolve",
+            "tracks": {
+                "OTIO_SCHEMA": "Stack.1",
+                "children": [{
+                    "OTIO_SCHEMA": "Track.1",
+                    "name": "V1",
+                    "kind": "Video",
+                    "children": [
+                        {
+                            "OTIO_SCHEMA": "Clip.1",
+                            "name": "A001",
+                            "source_range": time_range(0, 1_000_000, 24),
+                            "media_reference": {
+                                "OTIO_SCHEMA": "ExternalReference.1",
+                                "target_url": "file:///media/A%20001.mov"
+                            },
+                            "effects": [{ "OTIO_SCHEMA": "LinearTimeWarp.1", "time_scalar": 2.0 }]
+                        },
+                        {
+                            "OTIO_SCHEMA": "Stack.1",
+                            "name": "compound",
+                            "source_range": time_range(0, 500_000, 24),
+                            "children": []
+                        },
+                        {
+                            "OTIO_SCHEMA": "Clip.1",
+                            "name": "A002",
+                            "source_range": time_range(250_000, 500_000, 24)
+                        }
+                    ]
+                }]
+            }
+        });
+        let mut issues = Vec::new();
+        let imported = otio_to_timeline(&document, "project-test", &mut issues).unwrap();
+        let features = issues
+            .iter()
+            .map(|issue| issue.feature.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(features, ["speed effect", "nested stack"]);
+        assert_eq!(imported.fps, 24);
+        assert_eq!(imported.clips.len(), 2);
+        assert_eq!(imported.clips[0].source_ref, "/media/A 001.mov");
+        assert_eq!(imported.clips[1].start_us, 1_500_000);
+        assert_eq!(imported.clips[1].source_start_us, 250_000);
+    }
+}
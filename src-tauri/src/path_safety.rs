@@ -0,0 +1,150 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::{settings, workspace_root};
+
+/// Longest project id accepted; generated ids are `proj-` plus 16 digits.
+const MAX_PROJECT_ID_LEN: usize = 64;
+
+pub fn data_dir() -> Result<PathBuf, String> {
+    Ok(workspace_root()?.join("desktop").join("data"))
+}
+
+/// Project ids become directory names, so only `[A-Za-z0-9_-]` is allowed
+/// and the first character can't be `-` (it would read as a flag to the
+/// pipeline scripts).
+pub fn validate_project_id(project_id: &str) -> Result<(), String> {
+    let valid = !project_id.is_empty()
+        && project_id.len() <= MAX_PROJECT_ID_LEN
+        && !project_id.starts_with('-')
+        && project_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid project id: {project_id:?}"))
+    }
+}
+
+/// A single file name inside a project directory: no separators, no `..`.
+pub fn validate_file_name(name: &str) -> Result<(), String> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(part)), None) if part == name && !name.contains('\\') => Ok(()),
+        _ => Err(format!("Invalid file name: {name:?}")),
+    }
+}
+
+/// Canonical form of `path`, resolving symlinks in the part that exists.
+/// The rest is appended as-is; callers only append validated components.
+fn canonical(path: &Path) -> Result<PathBuf, String> {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    loop {
+        match existing.canonicalize() {
+            Ok(resolved) => {
+                return Ok(rest
+                    .iter()
+                    .rev()
+                    .fold(resolved, |acc: PathBuf, part| acc.join(part)))
+            }
+            Err(_) => {
+                let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+                    return Err(format!("Failed resolving path: {}", path.display()));
+                };
+                rest.push(name.to_os_string());
+                existing = parent;
+            }
+        }
+    }
+}
+
+/// Errors unless `path` resolves to somewhere inside `root`.
+pub fn ensure_inside(path: &Path, root: &Path) -> Result<PathBuf, String> {
+    let escapes = || format!("Path escapes {}: {}", root.display(), path.display());
+    if path
+        .components()
+        .any(|component| component == Component::ParentDir)
+    {
+        return Err(escapes());
+    }
+    let resolved = canonical(path)?;
+    if resolved.starts_with(canonical(root)?) {
+        Ok(resolved)
+    } else {
+        Err(escapes())
+    }
+}
+
+/// `desktop/data/<project_id>`, after checking the id and that the result,
+/// symlinks included, stays inside the data directory.
+pub fn project_dir(project_id: &str) -> Result<PathBuf, String> {
+    validate_project_id(project_id)?;
+    let root = data_dir()?;
+    let dir = root.join(project_id);
+    ensure_inside(&dir, &root)?;
+    Ok(dir)
+}
+
+/// A file directly inside the project directory, named by the caller.
+pub fn project_file(project_id: &str, file_name: &str) -> Result<PathBuf, String> {
+    validate_file_name(file_name)?;
+    Ok(project_dir(project_id)?.join(file_name))
+}
+
+/// Directories `open_path` may reveal: the data directory and folders the
+/// user picked themselves, in the onboarding wizard or a native picker.
+fn openable_roots() -> Vec<PathBuf> {
+    let mut roots = data_dir().into_iter().collect::<Vec<_>>();
+    if let Ok(settings) = settings::read_app_settings() {
+        roots.extend(settings.onboarding.data_dir.map(PathBuf::from));
+        roots.extend(
+            settings
+                .pickers
+                .chosen_directories
+                .into_iter()
+                .map(PathBuf::from),
+        );
+    }
+    roots
+}
+
+pub fn ensure_openable(path: &Path) -> Result<PathBuf, String> {
+    openable_roots()
+        .iter()
+        .find_map(|root| ensure_inside(path, root).ok())
+        .ok_or_else(|| {
+            format!(
+                "Only files in the app's data folder or folders you picked can be opened: {}",
+                path.display()
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_ids_cannot_leave_the_data_dir() {
+        assert!(validate_project_id("proj-1712345678901234").is_ok());
+        for id in ["", "..", "../..", "a/b", "a\\b", "-rf", "proj 1", "proj.1"] {
+            assert!(validate_project_id(id).is_err(), "{id:?} accepted");
+        }
+    }
+
+    #[test]
+    fn file_names_are_single_components() {
+        assert!(validate_file_name("chunk_review_decisions.json").is_ok());
+        for name in ["", ".", "..", "../x.json", "a/b.json", "a\\b.json", "/etc/passwd"] {
+            assert!(validate_file_name(name).is_err(), "{name:?} accepted");
+        }
+    }
+
+    #[test]
+    fn ensure_inside_rejects_parent_components() {
+        let root = std::env::temp_dir();
+        assert!(ensure_inside(&root.join("a").join("b.json"), &root).is_ok());
+        assert!(ensure_inside(&root.join("..").join("x"), &root).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, FilePath};
+
+use crate::media::{MediaKind, AUDIO_EXTENSIONS, IMAGE_EXTENSIONS, VIDEO_EXTENSIONS};
+use crate::settings;
+
+const MEDIA_PICKER: &str = "media";
+const OUTPUT_PICKER: &str = "output";
+const MAX_CHOSEN_DIRECTORIES: usize = 50;
+
+impl MediaKind {
+    fn filter(self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            Self::Video => ("Video", VIDEO_EXTENSIONS),
+            Self::Audio => ("Audio", AUDIO_EXTENSIONS),
+            Self::Image => ("Images", IMAGE_EXTENSIONS),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PickMediaFilesRequest {
+    /// Defaults to video and audio.
+    kinds: Option<Vec<MediaKind>>,
+    /// Defaults to true.
+    multiple: Option<bool>,
+    title: Option<String>,
+    /// Pickers with different ids remember their own last directory, e.g.
+    /// `broll` vs the main import. Defaults to `media`.
+    picker_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PickOutputDirectoryRequest {
+    title: Option<String>,
+    /// Defaults to `output`.
+    picker_id: Option<String>,
+}
+
+fn picker_id(requested: Option<String>, default: &str) -> String {
+    requested
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| default.to_string())
+}
+
+fn last_directory(picker: &str) -> Option<PathBuf> {
+    settings::read_app_settings()
+        .ok()?
+        .pickers
+        .last_directories
+        .get(picker)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_dir())
+}
+
+fn remember_directory(picker: &str, dir: &Path) {
+    let saved = settings::read_app_settings().and_then(|mut settings| {
+        let dir = dir.to_string_lossy().to_string();
+        let pickers = &mut settings.pickers;
+        pickers
+            .last_directories
+            .insert(picker.to_string(), dir.clone());
+        pickers.chosen_directories.retain(|chosen| *chosen != dir);
+        pickers.chosen_directories.push(dir);
+        let excess = pickers
+            .chosen_directories
+            .len()
+            .saturating_sub(MAX_CHOSEN_DIRECTORIES);
+        pickers.chosen_directories.drain(..excess);
+        settings::write_app_settings(&settings)
+    });
+    if let Err(error) = saved {
+        tracing::warn!("Failed remembering {picker} picker directory: {error}");
+    }
+}
+
+fn into_paths(picked: Vec<FilePath>) -> Result<Vec<String>, String> {
+    picked
+        .into_iter()
+        .map(|path| {
+            path.into_path()
+                .map(|path| path.to_string_lossy().to_string())
+                .map_err(|error| format!("Failed resolving picked path: {error}"))
+        })
+        .collect()
+}
+
+/// Empty when the user cancels.
+fn pick_media_files_blocking(
+    app: &AppHandle,
+    request: PickMediaFilesRequest,
+) -> Result<Vec<String>, String> {
+    let picker = picker_id(request.picker_id, MEDIA_PICKER);
+    let kinds = request
+        .kinds
+        .filter(|kinds| !kinds.is_empty())
+        .unwrap_or_else(|| vec![MediaKind::Video, MediaKind::Audio]);
+
+    let mut dialog = app.dialog().file();
+    if kinds.len() > 1 {
+        let all = kinds
+            .iter()
+            .flat_map(|kind| kind.filter().1.iter().copied())
+            .collect::<Vec<_>>();
+        dialog = dialog.add_filter("All media", &all);
+    }
+    for kind in &kinds {
+        let (name, extensions) = kind.filter();
+        dialog = dialog.add_filter(name, extensions);
+    }
+    if let Some(title) = request.title {
+        dialog = dialog.set_title(title);
+    }
+    if let Some(dir) = last_directory(&picker) {
+        dialog = dialog.set_directory(dir);
+    }
+
+    let picked = if request.multiple.unwrap_or(true) {
+        dialog.blocking_pick_files().unwrap_or_default()
+    } else {
+        dialog.blocking_pick_file().into_iter().collect()
+    };
+    let paths = into_paths(picked)?;
+    if let Some(parent) = paths.first().and_then(|path| Path::new(path).parent()) {
+        remember_directory(&picker, parent);
+    }
+    Ok(paths)
+}
+
+fn pick_output_directory_blocking(
+    app: &AppHandle,
+    request: PickOutputDirectoryRequest,
+) -> Result<Option<String>, String> {
+    let picker = picker_id(request.picker_id, OUTPUT_PICKER);
+    let mut dialog = app.dialog().file();
+    if let Some(title) = request.title {
+        dialog = dialog.set_title(title);
+    }
+    if let Some(dir) = last_directory(&picker) {
+        dialog = dialog.set_directory(dir);
+    }
+    let Some(picked) = dialog.blocking_pick_folder() else {
+        return Ok(None);
+    };
+    let dir = into_paths(vec![picked])?.remove(0);
+    remember_directory(&picker, Path::new(&dir));
+    Ok(Some(dir))
+}
+
+#[tauri::command]
+pub async fn pick_media_files(
+    app: AppHandle,
+    request: Option<PickMediaFilesRequest>,
+) -> Result<Vec<String>, String> {
+    let request = request.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || pick_media_files_blocking(&app, request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// None when the user cancels.
+#[tauri::command]
+pub async fn pick_output_directory(
+    app: AppHandle,
+    request: Option<PickOutputDirectoryRequest>,
+) -> Result<Option<String>, String> {
+    let request = request.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || pick_output_directory_blocking(&app, request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
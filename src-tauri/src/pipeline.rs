@@ -0,0 +1,872 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::Instrument;
+
+use crate::audio_enhance::{self, AudioEnhanceMethod, EnhanceOptions};
+use crate::checkpoints::{chain_hash, input_fingerprint};
+use crate::project_status::ProjectStatus;
+use crate::script_retry::{self, RetryReport};
+use crate::settings::{self, PipelineSettings, RetrySettings};
+use crate::timeline::{build_rough_cut_timeline, RoughCutOptions};
+use crate::{
+    cuts, event_bus, external_assets, logging, now_iso, path_safety, persist_pipeline_transcript,
+    project_config, run_node_script, scheduler, script_path, sponsors, update_project_status,
+    validation, write_timeline,
+};
+
+pub const PIPELINE_PROGRESS_EVENT: &str = "pipeline://progress";
+/// Per-project stage checkpoints, one `<stage>.json` each.
+const CHECKPOINT_DIR: &str = "pipeline";
+
+/// Everything the stages of one run are configured from.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineParams {
+    pub input: String,
+    pub mode: String,
+    pub language: String,
+    pub fps: u32,
+    pub source_ref: String,
+    pub transcription_model: Option<String>,
+    pub cut_plan_mode: String,
+    pub llm_provider: Option<String>,
+    pub llm_model: Option<String>,
+    pub rough_cut_options: RoughCutOptions,
+    pub fetch_external: bool,
+    /// Runs the `enhance` stage with this method; skipped when unset.
+    pub enhance_audio: Option<AudioEnhanceMethod>,
+    /// `project.config.json` overrides, passed to every Node stage.
+    #[serde(skip)]
+    pub project_args: Vec<String>,
+}
+
+/// What a stage runs with: the run's parameters and the outputs of the
+/// stages it depends on.
+pub struct StageContext<'a> {
+    pub project_id: &'a str,
+    pub params: &'a PipelineParams,
+    outputs: &'a BTreeMap<&'static str, Value>,
+}
+
+impl StageContext<'_> {
+    pub fn output(&self, stage: &str) -> Result<&Value, String> {
+        self.outputs
+            .get(stage)
+            .ok_or_else(|| format!("Stage output missing: {stage}"))
+    }
+}
+
+pub type StageFinish = fn(&StageContext, &Value) -> Result<(), String>;
+
+/// The parts of a stage that don't depend on how it is run.
+#[derive(Clone)]
+pub struct StageSpec {
+    pub id: &'static str,
+    pub depends_on: &'static [&'static str],
+    /// Set while the stage runs and once it is done.
+    pub running: Option<ProjectStatus>,
+    pub done: Option<ProjectStatus>,
+    /// The parameters the stage's output depends on. A checkpoint is only
+    /// reused while these, and every dependency's, are unchanged.
+    pub key: fn(&PipelineParams) -> Vec<String>,
+    /// Runs after the stage succeeds, whatever ran it, to record its output
+    /// in the project.
+    pub finish: Option<StageFinish>,
+}
+
+/// One step of the pipeline. Implementations differ in what does the work:
+/// a Node script, Rust code, or an HTTP backend.
+pub trait Stage: Send + Sync {
+    fn spec(&self) -> &StageSpec;
+    fn run(&self, context: &StageContext) -> Result<Value, String>;
+}
+
+/// Runs a script under `scripts/` and parses its stdout as the output.
+pub struct NodeStage {
+    pub spec: StageSpec,
+    pub script: &'static str,
+    pub args: fn(&StageContext) -> Result<Vec<String>, String>,
+}
+
+impl Stage for NodeStage {
+    fn spec(&self) -> &StageSpec {
+        &self.spec
+    }
+
+    fn run(&self, context: &StageContext) -> Result<Value, String> {
+        let mut args = (self.args)(context)?;
+        args.extend(context.params.project_args.iter().cloned());
+        let raw = run_node_script(&script_path(self.script)?, &args)?;
+        serde_json::from_str(&raw)
+            .map_err(|error| format!("Invalid {} stage JSON: {error}", self.spec.id))
+    }
+}
+
+pub struct NativeStage {
+    pub spec: StageSpec,
+    pub run: fn(&StageContext) -> Result<Value, String>,
+}
+
+impl Stage for NativeStage {
+    fn spec(&self) -> &StageSpec {
+        &self.spec
+    }
+
+    fn run(&self, context: &StageContext) -> Result<Value, String> {
+        (self.run)(context)
+    }
+}
+
+/// POSTs the stage id, parameters and dependency outputs as JSON and takes
+/// the response body as the output. Replaces a built-in stage configured in
+/// `pipeline.httpStages`.
+pub struct HttpStage {
+    pub spec: StageSpec,
+    pub url: String,
+    http: Client,
+}
+
+impl HttpStage {
+    pub fn new(spec: StageSpec, url: String, timeout_secs: u64) -> Result<Self, String> {
+        let http = Client::builder()
+            .timeout(Duration::from_secs(timeout_secs.max(1)))
+            .build()
+            .map_err(|error| format!("HTTP client error: {error}"))?;
+        Ok(Self { spec, url, http })
+    }
+}
+
+impl Stage for HttpStage {
+    fn spec(&self) -> &StageSpec {
+        &self.spec
+    }
+
+    fn run(&self, context: &StageContext) -> Result<Value, String> {
+        let inputs = self
+            .spec
+            .depends_on
+            .iter()
+            .map(|stage| Ok((*stage, context.output(stage)?)))
+            .collect::<Result<BTreeMap<_, _>, String>>()?;
+        let body = json!({
+            "stage": self.spec.id,
+            "projectId": context.project_id,
+            "projectDir": path_safety::project_dir(context.project_id)?.to_string_lossy(),
+            "params": context.params,
+            "inputs": inputs,
+        });
+        let response = self
+            .http
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .map_err(|error| {
+                format!(
+                    "Failed calling {} stage at {}: {error}",
+                    self.spec.id, self.url
+                )
+            })?;
+        let status = response.status();
+        let text = response
+            .text()
+            .map_err(|error| format!("Failed reading {} stage response: {error}", self.spec.id))?;
+        if !status.is_success() {
+            return Err(format!(
+                "{} stage failed with HTTP {status}: {}",
+                self.spec.id,
+                text.trim()
+            ));
+        }
+        serde_json::from_str(&text)
+            .map_err(|error| format!("Invalid {} stage JSON: {error}", self.spec.id))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Checkpoint {
+    stage: String,
+    key: String,
+    completed_at: String,
+    output: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageReport {
+    pub stage: &'static str,
+    /// Taken from a checkpoint instead of running.
+    pub reused: bool,
+    pub attempts: Option<RetryReport>,
+    pub output: Value,
+}
+
+/// A validated stage graph.
+pub struct Pipeline {
+    /// In dependency order.
+    stages: Vec<Box<dyn Stage>>,
+}
+
+impl Pipeline {
+    /// Orders `stages` so each runs after its dependencies, keeping the given
+    /// order otherwise. Fails on unknown dependencies, duplicate ids and
+    /// cycles.
+    pub fn new(stages: Vec<Box<dyn Stage>>) -> Result<Self, String> {
+        let ids = stages
+            .iter()
+            .map(|stage| stage.spec().id)
+            .collect::<Vec<_>>();
+        if ids.iter().collect::<BTreeSet<_>>().len() != ids.len() {
+            return Err("Pipeline has duplicate stage ids.".to_string());
+        }
+        for stage in &stages {
+            for dependency in stage.spec().depends_on {
+                if !ids.contains(dependency) {
+                    return Err(format!(
+                        "Stage {} depends on unknown stage {dependency}",
+                        stage.spec().id
+                    ));
+                }
+            }
+        }
+
+        let mut pending = stages.into_iter().map(Some).collect::<Vec<_>>();
+        let mut placed = BTreeSet::new();
+        let mut ordered = Vec::new();
+        while ordered.len() < ids.len() {
+            let ready = pending.iter().position(|stage| {
+                stage.as_ref().is_some_and(|stage| {
+                    stage
+                        .spec()
+                        .depends_on
+                        .iter()
+                        .all(|dependency| placed.contains(dependency))
+                })
+            });
+            let Some(index) = ready else {
+                return Err("Pipeline stages depend on each other in a cycle.".to_string());
+            };
+            let stage = pending[index].take().unwrap_or_else(|| unreachable!());
+            placed.insert(stage.spec().id);
+            ordered.push(stage);
+        }
+        Ok(Self { stages: ordered })
+    }
+
+    pub fn stage_ids(&self) -> Vec<&'static str> {
+        self.stages.iter().map(|stage| stage.spec().id).collect()
+    }
+
+    /// Swaps in an HTTP backend for every stage that has one configured.
+    fn with_backends(self, settings: &PipelineSettings) -> Result<Self, String> {
+        let stages = self
+            .stages
+            .into_iter()
+            .map(|stage| match settings.http_stages.get(stage.spec().id) {
+                Some(url) if !url.trim().is_empty() => Ok(Box::new(HttpStage::new(
+                    stage.spec().clone(),
+                    url.trim().to_string(),
+                    settings.http_timeout_secs,
+                )?) as Box<dyn Stage>),
+                _ => Ok(stage),
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self { stages })
+    }
+
+    /// `until` and everything it depends on, or every stage.
+    fn plan(&self, until: Option<&str>) -> Result<BTreeSet<&'static str>, String> {
+        let Some(target) = until else {
+            return Ok(self.stage_ids().into_iter().collect());
+        };
+        let mut needed = BTreeSet::new();
+        let mut queue = vec![target];
+        while let Some(id) = queue.pop() {
+            let stage = self
+                .stages
+                .iter()
+                .find(|stage| stage.spec().id == id)
+                .ok_or_else(|| format!("Unknown pipeline stage: {id}"))?;
+            if needed.insert(stage.spec().id) {
+                queue.extend(stage.spec().depends_on.iter().copied());
+            }
+        }
+        Ok(needed)
+    }
+
+    /// Runs the planned stages in order. A stage whose checkpoint matches its
+    /// key is skipped, unless a dependency ran again in this run. Each stage
+    /// is retried per `retry` and announced on [`PIPELINE_PROGRESS_EVENT`].
+    pub fn run(
+        &self,
+        project_id: &str,
+        params: &PipelineParams,
+        until: Option<&str>,
+        retry: &RetrySettings,
+    ) -> Result<Vec<StageReport>, String> {
+        let planned = self.plan(until)?;
+        let total = planned.len();
+        let mut outputs = BTreeMap::new();
+        let mut keys = BTreeMap::<&str, String>::new();
+        let mut reran = BTreeSet::new();
+        let mut reports = Vec::new();
+
+        for stage in self
+            .stages
+            .iter()
+            .filter(|stage| planned.contains(stage.spec().id))
+        {
+            let spec = stage.spec();
+            let index = reports.len();
+            let previous = spec
+                .depends_on
+                .iter()
+                .map(|dependency| keys.get(dependency).map_or("", String::as_str))
+                .collect::<Vec<_>>()
+                .join("\0");
+            let parts = (spec.key)(params);
+            let key = chain_hash(
+                &previous,
+                &parts.iter().map(String::as_str).collect::<Vec<_>>(),
+            );
+            let dependency_reran = spec
+                .depends_on
+                .iter()
+                .any(|dependency| reran.contains(dependency));
+            keys.insert(spec.id, key.clone());
+
+            if !dependency_reran {
+                if let Some(checkpoint) =
+                    read_checkpoint(project_id, spec.id).filter(|checkpoint| checkpoint.key == key)
+                {
+                    progress(project_id, spec.id, "reused", index, total, None);
+                    outputs.insert(spec.id, checkpoint.output.clone());
+                    reports.push(StageReport {
+                        stage: spec.id,
+                        reused: true,
+                        attempts: None,
+                        output: checkpoint.output,
+                    });
+                    continue;
+                }
+            }
+
+            progress(project_id, spec.id, "started", index, total, None);
+            if let Some(status) = spec.running {
+                update_project_status(project_id, status)?;
+            }
+            let context = StageContext {
+                project_id,
+                params,
+                outputs: &outputs,
+            };
+            let label = format!("pipeline-{}", spec.id);
+            let result = script_retry::run_with_retry(project_id, &label, retry, || {
+                let output = stage.run(&context)?;
+                if let Some(finish) = spec.finish {
+                    finish(&context, &output)?;
+                }
+                Ok(output)
+            });
+            let (output, attempts) = match result {
+                Ok(done) => done,
+                Err(error) => {
+                    progress(project_id, spec.id, "failed", index, total, Some(&error));
+                    return Err(format!("{} stage failed: {error}", spec.id));
+                }
+            };
+            if let Some(status) = spec.done {
+                update_project_status(project_id, status)?;
+            }
+            write_checkpoint(
+                project_id,
+                &Checkpoint {
+                    stage: spec.id.to_string(),
+                    key,
+                    completed_at: now_iso(),
+                    output: output.clone(),
+                },
+            )?;
+            progress(project_id, spec.id, "done", index, total, None);
+            reran.insert(spec.id);
+            outputs.insert(spec.id, output.clone());
+            reports.push(StageReport {
+                stage: spec.id,
+                reused: false,
+                attempts: Some(attempts),
+                output,
+            });
+        }
+        Ok(reports)
+    }
+}
+
+fn progress(
+    project_id: &str,
+    stage: &str,
+    state: &str,
+    index: usize,
+    total: usize,
+    error: Option<&str>,
+) {
+    event_bus::publish(
+        project_id,
+        PIPELINE_PROGRESS_EVENT,
+        &json!({
+            "stage": stage,
+            "state": state,
+            "index": index,
+            "total": total,
+            "error": error,
+        }),
+    );
+}
+
+fn checkpoint_path(project_id: &str, stage: &str) -> Result<PathBuf, String> {
+    Ok(path_safety::project_dir(project_id)?
+        .join(CHECKPOINT_DIR)
+        .join(format!("{stage}.json")))
+}
+
+fn read_checkpoint(project_id: &str, stage: &str) -> Option<Checkpoint> {
+    let raw = fs::read_to_string(checkpoint_path(project_id, stage).ok()?).ok()?;
+    serde_json::from_str::<Checkpoint>(&raw)
+        .ok()
+        .filter(|checkpoint| checkpoint.stage == stage)
+}
+
+fn write_checkpoint(project_id: &str, checkpoint: &Checkpoint) -> Result<(), String> {
+    let path = checkpoint_path(project_id, &checkpoint.stage)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating pipeline checkpoint dir: {error}"))?;
+    }
+    let serialized = serde_json::to_string_pretty(checkpoint)
+        .map_err(|error| format!("Checkpoint serialize error: {error}"))?;
+    fs::write(&path, serialized)
+        .map_err(|error| format!("Failed writing pipeline checkpoint: {error}"))
+}
+
+pub fn clear_checkpoints(project_id: &str) -> Result<(), String> {
+    let dir = path_safety::project_dir(project_id)?.join(CHECKPOINT_DIR);
+    if dir.exists() {
+        fs::remove_dir_all(&dir)
+            .map_err(|error| format!("Failed clearing pipeline checkpoints: {error}"))?;
+    }
+    Ok(())
+}
+
+fn project_dir_arg(context: &StageContext) -> Result<String, String> {
+    Ok(path_safety::project_dir(context.project_id)?
+        .to_string_lossy()
+        .to_string())
+}
+
+fn push_optional(args: &mut Vec<String>, flag: &str, value: &Option<String>) {
+    if let Some(value) = value.as_deref().filter(|value| !value.trim().is_empty()) {
+        args.push(flag.to_string());
+        args.push(value.to_string());
+    }
+}
+
+fn probe_args(context: &StageContext) -> Result<Vec<String>, String> {
+    Ok(vec![
+        "--input".to_string(),
+        context.params.input.clone(),
+        "--project-id".to_string(),
+        context.project_id.to_string(),
+        "--generate-proxy".to_string(),
+        "true".to_string(),
+        "--generate-waveform".to_string(),
+        "true".to_string(),
+    ])
+}
+
+fn transcribe_args(context: &StageContext) -> Result<Vec<String>, String> {
+    let params = context.params;
+    let mut args = vec![
+        "--project-id".to_string(),
+        context.project_id.to_string(),
+        "--project-dir".to_string(),
+        project_dir_arg(context)?,
+        "--input".to_string(),
+        params.input.clone(),
+        "--mode".to_string(),
+        params.mode.clone(),
+        "--language".to_string(),
+        params.language.clone(),
+        "--source-ref".to_string(),
+        params.source_ref.clone(),
+    ];
+    push_optional(
+        &mut args,
+        "--transcription-model",
+        &params.transcription_model,
+    );
+    Ok(args)
+}
+
+fn plan_args(context: &StageContext) -> Result<Vec<String>, String> {
+    let params = context.params;
+    let mut args = vec![
+        "--project-id".to_string(),
+        context.project_id.to_string(),
+        "--project-dir".to_string(),
+        project_dir_arg(context)?,
+        "--input".to_string(),
+        params.input.clone(),
+        "--source-ref".to_string(),
+        params.source_ref.clone(),
+        "--mode".to_string(),
+        params.cut_plan_mode.clone(),
+    ];
+    push_optional(&mut args, "--llm-provider", &params.llm_provider);
+    push_optional(&mut args, "--llm-model", &params.llm_model);
+    Ok(args)
+}
+
+fn enrich_args(context: &StageContext) -> Result<Vec<String>, String> {
+    let params = context.params;
+    Ok(vec![
+        "--project-id".to_string(),
+        context.project_id.to_string(),
+        "--fps".to_string(),
+        params.fps.to_string(),
+        "--source-ref".to_string(),
+        params.source_ref.clone(),
+        "--fetch-external".to_string(),
+        params.fetch_external.to_string(),
+    ])
+}
+
+/// Records the cut plan as proposed cuts and writes the rough-cut timeline
+/// built from the accepted ones.
+fn build_timeline(context: &StageContext) -> Result<Value, String> {
+    let plan = context.output("plan")?;
+    let duration_us = plan
+        .get("durationUs")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| "Cut plan missing durationUs.".to_string())?;
+    let planned = serde_json::from_value::<Vec<cuts::PlannedRemoveRange>>(
+        plan.get("removeRanges")
+            .cloned()
+            .unwrap_or_else(|| json!([])),
+    )
+    .map_err(|error| format!("Invalid removeRanges payload: {error}"))?;
+    let params = context.params;
+    let proposed = cuts::record_proposed_cuts(
+        context.project_id,
+        &params.source_ref,
+        duration_us,
+        params.fps,
+        planned,
+        params.rough_cut_options.clone(),
+    )?;
+    let timeline = build_rough_cut_timeline(
+        context.project_id.to_string(),
+        duration_us,
+        params.fps,
+        params.source_ref.clone(),
+        cuts::accepted_ranges(&proposed),
+        &proposed.options,
+        &sponsors::protected_ranges(context.project_id),
+    );
+    write_timeline(&timeline)?;
+    Ok(json!({
+        "timelineId": timeline.id,
+        "version": timeline.version,
+        "durationUs": timeline.duration_us,
+        "clipCount": timeline.clips.len(),
+    }))
+}
+
+fn store_transcript(context: &StageContext, output: &Value) -> Result<(), String> {
+    let transcript_path = output.get("transcriptPath").and_then(Value::as_str);
+    persist_pipeline_transcript(
+        context.project_id,
+        transcript_path,
+        &context.params.source_ref,
+    );
+    Ok(())
+}
+
+fn register_assets(context: &StageContext, output: &Value) -> Result<(), String> {
+    let suggestions = output
+        .get("assetSuggestions")
+        .and_then(Value::as_array)
+        .map_or(&[][..], Vec::as_slice);
+    if let Err(error) = external_assets::register_from_edit_now(context.project_id, suggestions) {
+        tracing::warn!("Failed recording fetched assets: {error}");
+    }
+    Ok(())
+}
+
+/// Cleans up the input's audio and switches the timeline's clips to it.
+fn enhance_audio(context: &StageContext) -> Result<Value, String> {
+    let Some(method) = context.params.enhance_audio else {
+        return Ok(json!({ "skipped": true }));
+    };
+    let params = context.params;
+    let enhanced = audio_enhance::enhance_asset(
+        context.project_id,
+        &params.source_ref,
+        Path::new(&params.input),
+        EnhanceOptions {
+            method,
+            ..EnhanceOptions::default()
+        },
+    )?;
+    let enabled_clips = audio_enhance::enable_on_timeline(context.project_id, &enhanced)?;
+    Ok(json!({
+        "skipped": false,
+        "audioPath": enhanced.audio_path,
+        "previewBeforePath": enhanced.preview_before_path,
+        "previewAfterPath": enhanced.preview_after_path,
+        "enabledClips": enabled_clips,
+    }))
+}
+
+/// probe → transcribe → plan → build → enrich, then the optional enhance.
+pub fn editing_pipeline() -> Result<Pipeline, String> {
+    let stages: Vec<Box<dyn Stage>> = vec![
+        Box::new(NodeStage {
+            spec: StageSpec {
+                id: "probe",
+                depends_on: &[],
+                running: None,
+                done: None,
+                key: |params| {
+                    let (size, modified) = input_fingerprint(&params.input);
+                    vec![params.input.clone(), size.to_string(), modified.to_string()]
+                },
+                finish: None,
+            },
+            script: "scripts/media_ingest.mjs",
+            args: probe_args,
+        }),
+        Box::new(NodeStage {
+            spec: StageSpec {
+                id: "transcribe",
+                depends_on: &["probe"],
+                running: Some(ProjectStatus::Transcribing),
+                done: Some(ProjectStatus::TranscriptReady),
+                key: |params| {
+                    vec![
+                        params.mode.clone(),
+                        params.language.clone(),
+                        params.source_ref.clone(),
+                        params.transcription_model.clone().unwrap_or_default(),
+                    ]
+                },
+                finish: Some(store_transcript),
+            },
+            script: "scripts/transcribe_only.mjs",
+            args: transcribe_args,
+        }),
+        Box::new(NodeStage {
+            spec: StageSpec {
+                id: "plan",
+                depends_on: &["transcribe"],
+                running: Some(ProjectStatus::PlanningCuts),
+                done: Some(ProjectStatus::CutsReady),
+                key: |params| {
+                    vec![
+                        params.cut_plan_mode.clone(),
+                        params.llm_provider.clone().unwrap_or_default(),
+                        params.llm_model.clone().unwrap_or_default(),
+                        params.project_args.join(" "),
+                    ]
+                },
+                finish: None,
+            },
+            script: "scripts/cut_plan_only.mjs",
+            args: plan_args,
+        }),
+        Box::new(NativeStage {
+            spec: StageSpec {
+                id: "build",
+                depends_on: &["plan"],
+                running: None,
+                done: Some(ProjectStatus::RoughCutReady),
+                key: |params| {
+                    vec![
+                        params.fps.to_string(),
+                        serde_json::to_string(&params.rough_cut_options).unwrap_or_default(),
+                    ]
+                },
+                finish: None,
+            },
+            run: build_timeline,
+        }),
+        Box::new(NodeStage {
+            spec: StageSpec {
+                id: "enrich",
+                depends_on: &["build"],
+                running: None,
+                done: Some(ProjectStatus::EnrichedTimelineReady),
+                key: |params| vec![params.fetch_external.to_string()],
+                finish: Some(register_assets),
+            },
+            script: "scripts/edit_now_pipeline.mjs",
+            args: enrich_args,
+        }),
+        Box::new(NativeStage {
+            spec: StageSpec {
+                id: "enhance",
+                depends_on: &["enrich"],
+                running: None,
+                done: None,
+                key: |params| {
+                    vec![params
+                        .enhance_audio
+                        .map(|method| method.name().to_string())
+                        .unwrap_or_default()]
+                },
+                finish: None,
+            },
+            run: enhance_audio,
+        }),
+    ];
+    Pipeline::new(stages)
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunPipelineRequest {
+    project_id: String,
+    input: String,
+    mode: Option<String>,
+    language: Option<String>,
+    fps: Option<u32>,
+    source_ref: Option<String>,
+    transcription_model: Option<String>,
+    cut_plan_mode: Option<String>,
+    llm_provider: Option<String>,
+    llm_model: Option<String>,
+    rough_cut_options: Option<RoughCutOptions>,
+    fetch_external: Option<bool>,
+    /// Clean up the audio after the timeline is built.
+    enhance_audio: Option<AudioEnhanceMethod>,
+    /// Stop after this stage; the whole pipeline when absent.
+    until: Option<String>,
+    /// Ignore checkpoints and run every stage again.
+    force_restart: Option<bool>,
+    priority: Option<scheduler::Priority>,
+}
+
+/// Runs the native editing pipeline, stage by stage, resuming from
+/// checkpoints where the inputs are unchanged.
+#[tauri::command]
+pub async fn run_pipeline(
+    app: tauri::AppHandle,
+    request: RunPipelineRequest,
+) -> Result<Value, String> {
+    let mut params = PipelineParams {
+        input: request.input,
+        mode: request.mode.unwrap_or_else(|| "hybrid".to_string()),
+        language: request.language.unwrap_or_else(|| "en".to_string()),
+        fps: request.fps.unwrap_or(30),
+        source_ref: request
+            .source_ref
+            .unwrap_or_else(|| "source-video".to_string()),
+        transcription_model: request.transcription_model,
+        cut_plan_mode: request
+            .cut_plan_mode
+            .unwrap_or_else(|| "heuristic".to_string()),
+        llm_provider: request.llm_provider,
+        llm_model: request.llm_model,
+        rough_cut_options: request.rough_cut_options.unwrap_or_default(),
+        fetch_external: request.fetch_external.unwrap_or(true),
+        enhance_audio: request.enhance_audio,
+        project_args: Vec::new(),
+    };
+    let mut validator = validation::Validator::new();
+    validator.project_id("projectId", &request.project_id);
+    validator.language("language", &params.language);
+    validator.fps("fps", params.fps);
+    validator.finish()?;
+
+    let permit = scheduler::acquire(
+        &app,
+        scheduler::JobKind::Transcription,
+        request.priority,
+        &request.project_id,
+    )
+    .await?;
+    let project_id = request.project_id;
+    let until = request.until;
+    let force_restart = request.force_restart.unwrap_or(false);
+    let reports = logging::spawn_blocking(move || {
+        let settings = settings::read_app_settings()?;
+        let config = project_config::for_run(&project_id)?;
+        params.transcription_model = params
+            .transcription_model
+            .or(config.models.transcription.clone());
+        params.llm_model = params.llm_model.or(config.models.cut_planner.clone());
+        params.project_args = config.script_args();
+        let pipeline = editing_pipeline()?.with_backends(&settings.pipeline)?;
+        if force_restart {
+            clear_checkpoints(&project_id)?;
+        }
+        pipeline.run(
+            &project_id,
+            &params,
+            until.as_deref(),
+            &settings.script_retry,
+        )
+    })
+    .instrument(permit.span())
+    .await
+    .map_err(|error| format!("Task join error: {error}"))??;
+    drop(permit);
+    Ok(json!({ "ok": true, "stages": reports }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stage(id: &'static str, depends_on: &'static [&'static str]) -> Box<dyn Stage> {
+        Box::new(NativeStage {
+            spec: StageSpec {
+                id,
+                depends_on,
+                running: None,
+                done: None,
+                key: |_| Vec::new(),
+                finish: None,
+            },
+            run: |_| Ok(Value::Null),
+        })
+    }
+
+    #[test]
+    fn stages_run_after_their_dependencies() {
+        let pipeline = Pipeline::new(vec![
+            stage("build", &["plan"]),
+            stage("probe", &[]),
+            stage("plan", &["probe"]),
+            stage("enrich", &["build"]),
+        ])
+        .unwrap();
+        assert_eq!(pipeline.stage_ids(), ["probe", "plan", "build", "enrich"]);
+        assert_eq!(
+            pipeline.plan(Some("plan")).unwrap(),
+            BTreeSet::from(["probe", "plan"])
+        );
+
+        assert!(Pipeline::new(vec![stage("a", &["b"]), stage("b", &["a"])]).is_err());
+        assert!(Pipeline::new(vec![stage("a", &["missing"])]).is_err());
+        assert_eq!(
+            editing_pipeline().unwrap().stage_ids(),
+            ["probe", "transcribe", "plan", "build", "enrich", "enhance"]
+        );
+    }
+}
@@ -0,0 +1,311 @@
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::cuts::PlannedRemoveRange;
+use crate::fallback_policy::FallbackReport;
+use crate::script_retry::RetryReport;
+use crate::timeline_merge::RerunMerge;
+use crate::Timeline;
+
+/// The newest result schema the pipeline scripts write (`schemaVersion`).
+/// Bump it together with the scripts when a field changes shape.
+pub const PIPELINE_RESULT_VERSION: u32 = 1;
+
+/// Scripts from before the field existed wrote version 1.
+fn legacy_version() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitlePaths {
+    pub srt: String,
+    pub vtt: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionAdapter {
+    pub kind: String,
+    pub runtime: Option<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionSummary {
+    pub adapter: TranscriptionAdapter,
+    #[serde(default)]
+    pub fallback_policy: Value,
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanningSummary {
+    /// Which planner produced the cuts and with what model.
+    #[serde(default)]
+    pub cut_planner: Value,
+    #[serde(default)]
+    pub analysis: Value,
+}
+
+/// What `scripts/start_editing_pipeline.mjs` prints on success.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartEditingResult {
+    #[serde(default = "legacy_version")]
+    pub schema_version: u32,
+    pub ok: bool,
+    pub project_id: String,
+    pub mode: String,
+    pub language: String,
+    pub input_path: String,
+    pub source_ref: String,
+    pub fps: u32,
+    pub duration_us: u64,
+    pub transcript_path: Option<String>,
+    pub cut_plan_path: Option<String>,
+    pub subtitle_paths: Option<SubtitlePaths>,
+    #[serde(default)]
+    pub stage_durations_ms: BTreeMap<String, f64>,
+    pub telemetry_path: Option<String>,
+    pub resumed_from: Option<String>,
+    pub transcription: TranscriptionSummary,
+    pub planning: PlanningSummary,
+    pub remove_ranges: Vec<PlannedRemoveRange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplatePlacement {
+    pub id: String,
+    pub template_id: String,
+    pub template_name: Option<String>,
+    pub category: Option<String>,
+    pub start_us: u64,
+    pub end_us: u64,
+    pub confidence: Option<f64>,
+    #[serde(default)]
+    pub content: Value,
+    pub constraints: Option<Value>,
+}
+
+/// What `scripts/edit_now_pipeline.mjs` prints on success.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditNowResult {
+    #[serde(default = "legacy_version")]
+    pub schema_version: u32,
+    pub ok: bool,
+    pub project_id: String,
+    pub template_plan_path: Option<String>,
+    pub timeline_path: Option<String>,
+    pub fetch_external: bool,
+    pub template_placements: Vec<TemplatePlacement>,
+    /// Kept loose: `external_assets` reads the media details it needs.
+    #[serde(default)]
+    pub asset_suggestions: Vec<Value>,
+    #[serde(default)]
+    pub asset_fetch_summary: Value,
+    #[serde(default)]
+    pub planner: Value,
+    #[serde(default)]
+    pub fallback_policy: Value,
+    #[serde(default)]
+    pub retry: Value,
+    #[serde(default)]
+    pub stage_durations_ms: BTreeMap<String, f64>,
+    pub telemetry_path: Option<String>,
+    #[serde(default)]
+    pub validation_warnings: Vec<Value>,
+    #[serde(default)]
+    pub ai_decisions: Value,
+    #[serde(default)]
+    pub timeline: Value,
+}
+
+/// What `start_editing` returns.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartEditingResponse {
+    pub ok: bool,
+    pub pipeline: StartEditingResult,
+    pub timeline: Timeline,
+    /// Present when the run was merged with hand edits (`mergeEdits`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merge: Option<RerunMerge>,
+    pub fallback: FallbackReport,
+    pub script_attempts: RetryReport,
+}
+
+/// What `edit_now` returns: the script's result with how it was run.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditNowResponse {
+    #[serde(flatten)]
+    pub result: EditNowResult,
+    pub fallback: FallbackReport,
+    pub script_attempts: RetryReport,
+}
+
+fn check_version(script: &str, version: u32) -> Result<(), String> {
+    if version > PIPELINE_RESULT_VERSION {
+        return Err(format!(
+            "{script} result uses schema v{version}, but this app only reads up to \
+             v{PIPELINE_RESULT_VERSION}. Update the app."
+        ));
+    }
+    Ok(())
+}
+
+fn check_confidence(issues: &mut Vec<String>, field: &str, confidence: Option<f64>) {
+    if let Some(confidence) = confidence {
+        if !(0.0..=1.0).contains(&confidence) {
+            issues.push(format!("{field}.confidence must be between 0 and 1"));
+        }
+    }
+}
+
+fn finish_issues(script: &str, issues: Vec<String>) -> Result<(), String> {
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Invalid {script} result: {}", issues.join("; ")))
+    }
+}
+
+/// Parses `raw` as `T`, naming the script and field on failure rather than
+/// leaving a missing value to surface later in the UI.
+fn parse<T: DeserializeOwned>(script: &str, raw: &str) -> Result<T, String> {
+    let value = serde_json::from_str::<Value>(raw)
+        .map_err(|error| format!("Invalid {script} JSON: {error}"))?;
+    let version = value
+        .get("schemaVersion")
+        .and_then(Value::as_u64)
+        .map_or(legacy_version(), |version| {
+            u32::try_from(version).unwrap_or(u32::MAX)
+        });
+    check_version(script, version)?;
+    serde_json::from_value(value).map_err(|error| format!("Invalid {script} result: {error}"))
+}
+
+impl StartEditingResult {
+    pub fn parse(raw: &str, project_id: &str) -> Result<Self, String> {
+        let result = parse::<Self>("start editing", raw)?;
+        result.validate(project_id)?;
+        Ok(result)
+    }
+
+    fn validate(&self, project_id: &str) -> Result<(), String> {
+        let mut issues = Vec::new();
+        if !self.ok {
+            issues.push("ok is false".to_string());
+        }
+        if self.project_id != project_id {
+            issues.push(format!(
+                "projectId is {}, expected {project_id}",
+                self.project_id
+            ));
+        }
+        if self.duration_us == 0 {
+            issues.push("durationUs must be positive".to_string());
+        }
+        for (index, range) in self.remove_ranges.iter().enumerate() {
+            let field = format!("removeRanges[{index}]");
+            if range.end_us <= range.start_us {
+                issues.push(format!("{field} ends before it starts"));
+            }
+            if range.end_us > self.duration_us {
+                issues.push(format!("{field} ends after durationUs"));
+            }
+            check_confidence(&mut issues, &field, range.confidence);
+        }
+        finish_issues("start editing", issues)
+    }
+}
+
+impl EditNowResult {
+    pub fn parse(raw: &str, project_id: &str) -> Result<Self, String> {
+        let result = parse::<Self>("edit now", raw)?;
+        result.validate(project_id)?;
+        Ok(result)
+    }
+
+    fn validate(&self, project_id: &str) -> Result<(), String> {
+        let mut issues = Vec::new();
+        if !self.ok {
+            issues.push("ok is false".to_string());
+        }
+        if self.project_id != project_id {
+            issues.push(format!(
+                "projectId is {}, expected {project_id}",
+                self.project_id
+            ));
+        }
+        for (index, placement) in self.template_placements.iter().enumerate() {
+            let field = format!("templatePlacements[{index}]");
+            if placement.end_us <= placement.start_us {
+                issues.push(format!("{field} ends before it starts"));
+            }
+            check_confidence(&mut issues, &field, placement.confidence);
+        }
+        finish_issues("edit now", issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn start_editing(remove_ranges: Value) -> Value {
+        json!({
+            "ok": true,
+            "projectId": "project-1",
+            "mode": "hybrid",
+            "language": "en",
+            "inputPath": "/tmp/in.mp4",
+            "sourceRef": "source-video",
+            "fps": 30,
+            "durationUs": 10_000_000,
+            "transcription": { "adapter": { "kind": "whisper" } },
+            "planning": {},
+            "removeRanges": remove_ranges,
+        })
+    }
+
+    #[test]
+    fn results_are_checked_at_the_boundary() {
+        // No schemaVersion: written before the field existed.
+        let legacy = start_editing(json!([
+            { "startUs": 0, "endUs": 500_000, "confidence": 0.9 }
+        ]));
+        let parsed = StartEditingResult::parse(&legacy.to_string(), "project-1").unwrap();
+        assert_eq!(parsed.schema_version, 1);
+        assert_eq!(parsed.remove_ranges.len(), 1);
+
+        let mut newer = legacy.clone();
+        newer["schemaVersion"] = json!(PIPELINE_RESULT_VERSION + 1);
+        let error = StartEditingResult::parse(&newer.to_string(), "project-1").unwrap_err();
+        assert!(error.contains("Update the app"));
+
+        let bad = start_editing(json!([
+            { "startUs": 500, "endUs": 100 },
+            { "startUs": 0, "endUs": 20_000_000, "confidence": 3.0 }
+        ]));
+        let error = StartEditingResult::parse(&bad.to_string(), "project-1").unwrap_err();
+        assert!(error.contains("removeRanges[0] ends before it starts"));
+        assert!(error.contains("removeRanges[1] ends after durationUs"));
+        assert!(error.contains("removeRanges[1].confidence"));
+
+        let mut missing = legacy;
+        missing.as_object_mut().unwrap().remove("durationUs");
+        let error = StartEditingResult::parse(&missing.to_string(), "project-1").unwrap_err();
+        assert!(error.contains("durationUs"));
+    }
+}
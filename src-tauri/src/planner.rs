@@ -0,0 +1,766 @@
+use std::fs;
+use std::time::Duration;
+
+use reqwest::blocking::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::cuts::{self, PlannedRemoveRange};
+use crate::model_downloads::ollama_host;
+use crate::settings::{read_app_settings, PlannerSettings};
+use crate::transcript::{read_transcript, Transcript};
+use crate::{model_registry, now_iso, path_safety, read_projects, workspace_root};
+
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
+const DEFAULT_CUT_PLAN_PROMPT: &str =
+    "You are an expert video editor AI. Analyze this transcript deeply.";
+const DEFAULT_TEMPLATE_PLAN_PROMPT: &str =
+    "You are an expert video editor AI. Analyze this transcript chunk and suggest overlay templates.";
+const DEFAULT_CHAPTER_PROMPT: &str =
+    "You are an expert video editor AI. Split this transcript into topical chapters for viewers.";
+const DEFAULT_HIGHLIGHT_PROMPT: &str =
+    "You are an expert social video editor AI. Judge which excerpts work as standalone short clips.";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanCutsRequest {
+    project_id: String,
+    /// Overrides the model from the planner settings.
+    model: Option<String>,
+    /// Extra editing instructions appended to the prompt.
+    instructions: Option<String>,
+    /// Save the plan as the project's proposed cuts for review.
+    record: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateOption {
+    id: String,
+    name: String,
+    #[serde(default)]
+    category: String,
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanTemplatesRequest {
+    project_id: String,
+    /// Templates the planner may choose from.
+    templates: Vec<TemplateOption>,
+    /// Source range to plan for; defaults to the whole transcript.
+    start_us: Option<u64>,
+    end_us: Option<u64>,
+    model: Option<String>,
+    /// Most placements to ask for.
+    max_overlays: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannerRun {
+    pub backend: String,
+    pub model: String,
+    /// Requests made, including retries after malformed output.
+    pub attempts: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CutPlan {
+    pub project_id: String,
+    pub plan_id: String,
+    pub remove_ranges: Vec<PlannedCut>,
+    pub planner: PlannerRun,
+    pub recorded: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedCut {
+    pub start_us: u64,
+    pub end_us: u64,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub confidence: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplatePlacement {
+    pub template_id: String,
+    pub start_us: u64,
+    pub end_us: u64,
+    pub headline: String,
+    #[serde(default)]
+    pub subline: String,
+    #[serde(default)]
+    pub asset_query: Option<String>,
+    #[serde(default)]
+    pub asset_kind: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplatePlan {
+    pub project_id: String,
+    pub start_us: u64,
+    pub end_us: u64,
+    pub overlays: Vec<TemplatePlacement>,
+    pub planner: PlannerRun,
+}
+
+/// The planner's verdict on one highlight candidate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HighlightScore {
+    pub id: usize,
+    pub score: f64,
+    #[serde(default)]
+    pub title: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CutPlanReply {
+    remove_ranges: Vec<PlannedCut>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TemplatePlanReply {
+    overlays: Vec<TemplatePlacement>,
+}
+
+/// A chapter start in source time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedChapter {
+    pub start_us: u64,
+    pub title: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChapterReply {
+    chapters: Vec<PlannedChapter>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HighlightReply {
+    scores: Vec<HighlightScore>,
+}
+
+/// A user override from `custom_prompts.json`, shared with the Node pipeline.
+fn custom_prompt(stage: &str) -> Option<String> {
+    let path = workspace_root()
+        .ok()?
+        .join("desktop")
+        .join("data")
+        .join("custom_prompts.json");
+    let raw = fs::read_to_string(path).ok()?;
+    let prompts = serde_json::from_str::<Value>(&raw).ok()?;
+    prompts
+        .get("prompts")?
+        .get(stage)?
+        .as_str()
+        .map(str::trim)
+        .filter(|prompt| !prompt.is_empty())
+        .map(str::to_string)
+}
+
+/// `OPENAI_API_KEY` (or `LAPAAS_API_KEY`) from `ai_config.json`, then the
+/// environment.
+fn configured_api_key() -> Option<String> {
+    let from_config = workspace_root()
+        .ok()
+        .and_then(|root| {
+            fs::read_to_string(root.join("desktop").join("data").join("ai_config.json")).ok()
+        })
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok());
+    ["OPENAI_API_KEY", "LAPAAS_API_KEY"].iter().find_map(|key| {
+        from_config
+            .as_ref()
+            .and_then(|config| config.get(*key))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .or_else(|| std::env::var(key).ok())
+            .filter(|value| !value.trim().is_empty())
+    })
+}
+
+struct Chat {
+    role: &'static str,
+    content: String,
+}
+
+struct PlannerClient {
+    settings: PlannerSettings,
+    http: Client,
+}
+
+impl PlannerClient {
+    fn new(model: Option<String>) -> Result<Self, String> {
+        let mut settings = read_app_settings()?.planner;
+        if let Some(model) = model.filter(|model| !model.trim().is_empty()) {
+            settings.model = model.trim().to_string();
+        }
+        if settings.backend != "ollama" && settings.backend != "openai" {
+            return Err(format!(
+                "Unknown planner backend: {}; use ollama or openai.",
+                settings.backend
+            ));
+        }
+        let http = Client::builder()
+            .timeout(Duration::from_secs(settings.timeout_secs.max(10)))
+            .build()
+            .map_err(|error| format!("HTTP client error: {error}"))?;
+        Ok(Self { settings, http })
+    }
+
+    fn run(&self, attempts: u32) -> PlannerRun {
+        PlannerRun {
+            backend: self.settings.backend.clone(),
+            model: self.settings.model.clone(),
+            attempts,
+        }
+    }
+
+    fn base_url(&self) -> String {
+        match &self.settings.base_url {
+            Some(url) if !url.trim().is_empty() => url.trim().trim_end_matches('/').to_string(),
+            _ if self.settings.backend == "ollama" => ollama_host(),
+            _ => OPENAI_BASE_URL.to_string(),
+        }
+    }
+
+    fn send(&self, messages: &[Chat]) -> Result<String, String> {
+        let messages = messages
+            .iter()
+            .map(|message| serde_json::json!({ "role": message.role, "content": message.content }))
+            .collect::<Vec<_>>();
+        let (url, body) = if self.settings.backend == "ollama" {
+            (
+                format!("{}/api/chat", self.base_url()),
+                serde_json::json!({
+                    "model": self.settings.model,
+                    "messages": messages,
+                    "stream": false,
+                    "format": "json",
+                    "options": { "temperature": 0.2 },
+                }),
+            )
+        } else {
+            (
+                format!("{}/chat/completions", self.base_url()),
+                serde_json::json!({
+                    "model": self.settings.model,
+                    "messages": messages,
+                    "temperature": 0.2,
+                }),
+            )
+        };
+        let mut request = self
+            .http
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .body(body.to_string());
+        if self.settings.backend == "openai" {
+            if let Some(key) = self
+                .settings
+                .api_key
+                .clone()
+                .filter(|key| !key.trim().is_empty())
+                .or_else(configured_api_key)
+            {
+                request = request.header("Authorization", format!("Bearer {}", key.trim()));
+            }
+        }
+        let response = request
+            .send()
+            .map_err(|error| format!("Failed calling planner at {url}: {error}"))?;
+        let status = response.status();
+        let text = response
+            .text()
+            .map_err(|error| format!("Failed reading planner response: {error}"))?;
+        if !status.is_success() {
+            return Err(format!(
+                "Planner request failed with HTTP {status}: {}",
+                text.chars().take(300).collect::<String>()
+            ));
+        }
+        let reply = serde_json::from_str::<Value>(&text)
+            .map_err(|error| format!("Invalid planner response JSON: {error}"))?;
+        let content = if self.settings.backend == "ollama" {
+            reply.pointer("/message/content")
+        } else {
+            reply.pointer("/choices/0/message/content")
+        };
+        content
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| "Planner response has no message content.".to_string())
+    }
+
+    /// Sends the prompt and parses the reply, retrying with the validation
+    /// error when the model returns something malformed.
+    fn complete<T, F>(&self, system: &str, user: String, validate: F) -> Result<(T, u32), String>
+    where
+        T: DeserializeOwned,
+        F: Fn(T) -> Result<T, String>,
+    {
+        let max_attempts = self.settings.max_attempts.max(1);
+        let mut messages = vec![
+            Chat {
+                role: "system",
+                content: system.to_string(),
+            },
+            Chat {
+                role: "user",
+                content: user,
+            },
+        ];
+        let mut last_error = String::new();
+        for attempt in 1..=max_attempts {
+            let reply = self.send(&messages)?;
+            let parsed = extract_json(&reply)
+                .ok_or_else(|| "The reply contained no JSON object.".to_string())
+                .and_then(|json| {
+                    serde_json::from_str::<T>(json)
+                        .map_err(|error| format!("The JSON did not match the schema: {error}"))
+                })
+                .and_then(&validate);
+            match parsed {
+                Ok(value) => return Ok((value, attempt)),
+                Err(error) => {
+                    tracing::warn!("Planner attempt {attempt} malformed: {error}");
+                    last_error = error.clone();
+                    messages.push(Chat {
+                        role: "assistant",
+                        content: reply,
+                    });
+                    messages.push(Chat {
+                        role: "user",
+                        content: format!(
+                            "That reply was invalid: {error}\nReply again with only the corrected JSON object."
+                        ),
+                    });
+                }
+            }
+        }
+        Err(format!(
+            "Planner returned malformed output after {max_attempts} attempts: {last_error}"
+        ))
+    }
+}
+
+/// The outermost `{...}` of a reply, skipping `<think>` blocks and code
+/// fences that chat models wrap around JSON.
+fn extract_json(reply: &str) -> Option<&str> {
+    let body = match reply.rfind("</think>") {
+        Some(index) => &reply[index + "</think>".len()..],
+        None => reply,
+    };
+    let start = body.find('{')?;
+    let end = body.rfind('}')?;
+    (end > start).then(|| &body[start..=end])
+}
+
+fn transcript_span(transcript: &Transcript) -> u64 {
+    transcript
+        .segments
+        .iter()
+        .map(|segment| segment.end_us)
+        .max()
+        .unwrap_or(0)
+}
+
+fn simplified_segments(transcript: &Transcript, start_us: u64, end_us: u64) -> Value {
+    Value::Array(
+        transcript
+            .segments
+            .iter()
+            .filter(|segment| segment.start_us >= start_us && segment.start_us < end_us)
+            .map(|segment| {
+                serde_json::json!({
+                    "startUs": segment.start_us,
+                    "endUs": segment.end_us,
+                    "text": segment.text.chars().take(400).collect::<String>(),
+                })
+            })
+            .collect(),
+    )
+}
+
+fn validate_cuts(reply: CutPlanReply, duration_us: u64) -> Result<CutPlanReply, String> {
+    for (index, range) in reply.remove_ranges.iter().enumerate() {
+        if range.end_us <= range.start_us {
+            return Err(format!("removeRanges[{index}] ends before it starts."));
+        }
+        if duration_us > 0 && range.start_us >= duration_us {
+            return Err(format!(
+                "removeRanges[{index}] starts after the transcript ends at {duration_us}."
+            ));
+        }
+        if range
+            .confidence
+            .is_some_and(|confidence| !(0.0..=1.0).contains(&confidence))
+        {
+            return Err(format!("removeRanges[{index}].confidence must be 0-1."));
+        }
+    }
+    Ok(reply)
+}
+
+fn plan_cuts_blocking(request: PlanCutsRequest) -> Result<CutPlan, String> {
+    let transcript = read_transcript(&request.project_id)?;
+    if transcript.segments.is_empty() {
+        return Err("Transcript has no segments to plan cuts from.".to_string());
+    }
+    let duration_us = transcript_span(&transcript);
+    let client = PlannerClient::new(request.model)?;
+    let system = custom_prompt("cut_plan").unwrap_or_else(|| DEFAULT_CUT_PLAN_PROMPT.to_string());
+    let mut user = format!(
+        "Transcript segments:\n{}\n\n\
+         Identify sections to CUT (remove): filler words, long pauses, repetitions, off-topic tangents.\n\
+         Times are microseconds within [0, {duration_us}].\n\n\
+         Respond ONLY with this JSON (no markdown, no explanation):\n\
+         {{\"removeRanges\": [{{\"startUs\": 0, \"endUs\": 0, \"reason\": \"filler-word|silence|repetition|tangent\", \"confidence\": 0.9}}]}}",
+        simplified_segments(&transcript, 0, u64::MAX)
+    );
+    if let Some(instructions) = request
+        .instructions
+        .as_deref()
+        .map(str::trim)
+        .filter(|instructions| !instructions.is_empty())
+    {
+        user.push_str(&format!("\n\nEditor instructions: {instructions}"));
+    }
+
+    let (reply, attempts) = client.complete(&system, user, |reply: CutPlanReply| {
+        validate_cuts(reply, duration_us)
+    })?;
+    let mut remove_ranges = reply.remove_ranges;
+    for range in &mut remove_ranges {
+        range.end_us = range.end_us.min(duration_us);
+    }
+    remove_ranges.retain(|range| range.end_us > range.start_us);
+    remove_ranges.sort_by_key(|range| range.start_us);
+    let planner = client.run(attempts);
+    let plan_id = format!("cp-{}", now_iso());
+
+    // Same file the Node cut planner writes, so later stages pick it up.
+    let project_dir = path_safety::project_dir(&request.project_id)?;
+    fs::create_dir_all(&project_dir)
+        .map_err(|error| format!("Failed creating project dir: {error}"))?;
+    let source_ref = transcript
+        .source_ref
+        .clone()
+        .unwrap_or_else(|| "source-video".to_string());
+    let payload = serde_json::json!({
+        "planId": plan_id,
+        "projectId": request.project_id,
+        "createdAt": now_iso(),
+        "mode": "llm",
+        "sourceRef": source_ref,
+        "planner": { "model": planner.model, "strategy": format!("native-{}-cut-planner", planner.backend) },
+        "removeRanges": remove_ranges,
+    });
+    let serialized = serde_json::to_string_pretty(&payload)
+        .map_err(|error| format!("Cut plan serialize error: {error}"))?;
+    fs::write(project_dir.join("cut-plan.json"), format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing cut plan: {error}"))?;
+
+    let recorded = request.record.unwrap_or(false);
+    if recorded {
+        let existing = cuts::read_proposed_cuts(&request.project_id).ok();
+        let fps = existing
+            .as_ref()
+            .map(|set| set.fps)
+            .or_else(|| {
+                read_projects().ok().and_then(|projects| {
+                    projects
+                        .into_iter()
+                        .find(|project| project.id == request.project_id)
+                        .map(|project| project.settings.fps)
+                })
+            })
+            .unwrap_or(30);
+        cuts::record_proposed_cuts(
+            &request.project_id,
+            &source_ref,
+            existing
+                .as_ref()
+                .map_or(duration_us, |set| set.duration_us.max(duration_us)),
+            fps,
+            remove_ranges
+                .iter()
+                .map(|range| PlannedRemoveRange {
+                    start_us: range.start_us,
+                    end_us: range.end_us,
+                    reason: range.reason.clone(),
+                    confidence: range.confidence,
+                })
+                .collect(),
+            existing.map(|set| set.options).unwrap_or_default(),
+        )?;
+    }
+    if let Err(error) = model_registry::touch_models(std::slice::from_ref(&planner.model)) {
+        tracing::warn!("Failed updating model last-used time: {error}");
+    }
+
+    Ok(CutPlan {
+        project_id: request.project_id,
+        plan_id,
+        remove_ranges,
+        planner,
+        recorded,
+    })
+}
+
+fn validate_placements(
+    reply: TemplatePlanReply,
+    templates: &[TemplateOption],
+    start_us: u64,
+    end_us: u64,
+) -> Result<TemplatePlanReply, String> {
+    if reply.overlays.is_empty() {
+        return Err("overlays is empty.".to_string());
+    }
+    for (index, overlay) in reply.overlays.iter().enumerate() {
+        if !templates
+            .iter()
+            .any(|template| template.id == overlay.template_id)
+        {
+            return Err(format!(
+                "overlays[{index}].templateId {} is not in the template list.",
+                overlay.template_id
+            ));
+        }
+        if overlay.end_us <= overlay.start_us {
+            return Err(format!("overlays[{index}] ends before it starts."));
+        }
+        if overlay.start_us < start_us || overlay.start_us >= end_us {
+            return Err(format!(
+                "overlays[{index}].startUs must be within [{start_us}, {end_us}]."
+            ));
+        }
+        if overlay.headline.trim().is_empty() {
+            return Err(format!("overlays[{index}].headline is empty."));
+        }
+    }
+    Ok(reply)
+}
+
+fn truncate_words(text: &str, max_words: usize) -> String {
+    let words = text.split_whitespace().collect::<Vec<_>>();
+    if words.len() <= max_words {
+        return words.join(" ");
+    }
+    format!("{}…", words[..max_words].join(" "))
+}
+
+fn plan_templates_blocking(request: PlanTemplatesRequest) -> Result<TemplatePlan, String> {
+    if request.templates.is_empty() {
+        return Err("No templates given to plan with.".to_string());
+    }
+    let transcript = read_transcript(&request.project_id)?;
+    let start_us = request.start_us.unwrap_or(0);
+    let end_us = request
+        .end_us
+        .unwrap_or_else(|| transcript_span(&transcript));
+    if end_us <= start_us {
+        return Err("Planning range ends before it starts.".to_string());
+    }
+    let segments = simplified_segments(&transcript, start_us, end_us);
+    if segments.as_array().is_some_and(Vec::is_empty) {
+        return Err("No transcript segments in the planning range.".to_string());
+    }
+    let template_list = request
+        .templates
+        .iter()
+        .take(30)
+        .map(|template| {
+            serde_json::json!({
+                "id": template.id,
+                "name": template.name,
+                "category": template.category,
+                "description": template.description,
+            })
+        })
+        .collect::<Vec<_>>();
+    let max_overlays = request.max_overlays.unwrap_or(3).max(1);
+
+    let client = PlannerClient::new(request.model)?;
+    let system =
+        custom_prompt("template_plan").unwrap_or_else(|| DEFAULT_TEMPLATE_PLAN_PROMPT.to_string());
+    let user = format!(
+        "Transcript chunk:\n{segments}\n\nAvailable templates:\n{}\n\n\
+         Rules:\n\
+         - Suggest 1-{max_overlays} overlays for the most impactful moments\n\
+         - Match template style to content (stat for numbers, quote for key statements, list for enumerations)\n\
+         - headline: catchy English, max 8 words; subline: English, max 50 chars\n\
+         - assetQuery: search query for a background image or video\n\
+         - All startUs/endUs must be within [{start_us}, {end_us}]\n\n\
+         Respond ONLY with this JSON (no markdown):\n\
+         {{\"overlays\": [{{\"templateId\": \"template-id-from-list\", \"startUs\": {start_us}, \"endUs\": {}, \
+         \"headline\": \"catchy headline\", \"subline\": \"supporting text\", \
+         \"assetQuery\": \"search query\", \"assetKind\": \"image\"}}]}}",
+        Value::Array(template_list),
+        start_us + 2_000_000
+    );
+
+    let templates = &request.templates;
+    let (reply, attempts) = client.complete(&system, user, |reply: TemplatePlanReply| {
+        validate_placements(reply, templates, start_us, end_us)
+    })?;
+    let overlays = reply
+        .overlays
+        .into_iter()
+        .take(max_overlays as usize)
+        .map(|mut overlay| {
+            overlay.end_us = overlay.end_us.min(end_us);
+            overlay.headline = truncate_words(&overlay.headline, 8);
+            overlay.subline = overlay.subline.chars().take(52).collect();
+            overlay
+        })
+        .collect();
+    let planner = client.run(attempts);
+    if let Err(error) = model_registry::touch_models(std::slice::from_ref(&planner.model)) {
+        tracing::warn!("Failed updating model last-used time: {error}");
+    }
+    Ok(TemplatePlan {
+        project_id: request.project_id,
+        start_us,
+        end_us,
+        overlays,
+        planner,
+    })
+}
+
+fn validate_highlight_scores(
+    reply: HighlightReply,
+    candidates: &[(usize, String)],
+) -> Result<HighlightReply, String> {
+    for (index, score) in reply.scores.iter().enumerate() {
+        if !candidates.iter().any(|(id, _)| *id == score.id) {
+            return Err(format!(
+                "scores[{index}].id {} is not a candidate.",
+                score.id
+            ));
+        }
+        if !(0.0..=1.0).contains(&score.score) {
+            return Err(format!("scores[{index}].score must be 0-1."));
+        }
+    }
+    Ok(reply)
+}
+
+/// Asks the planner how well each `(id, text)` excerpt stands alone as a
+/// short, with a title for it. Candidates it leaves out get no score.
+pub fn score_highlights(
+    model: Option<String>,
+    candidates: &[(usize, String)],
+) -> Result<(Vec<HighlightScore>, PlannerRun), String> {
+    let client = PlannerClient::new(model)?;
+    let system =
+        custom_prompt("highlight_score").unwrap_or_else(|| DEFAULT_HIGHLIGHT_PROMPT.to_string());
+    let excerpts = candidates
+        .iter()
+        .map(|(id, text)| serde_json::json!({ "id": id, "text": truncate_words(text, 250) }))
+        .collect::<Vec<_>>();
+    let user = format!(
+        "Candidate excerpts:\n{}\n\n\
+         Score each from 0 to 1 for how well it works as a standalone short clip: a strong hook, \
+         one complete idea, a satisfying end. Give each a short title (max 8 words).\n\n\
+         Respond ONLY with this JSON (no markdown, no explanation):\n\
+         {{\"scores\": [{{\"id\": 0, \"score\": 0.8, \"title\": \"...\"}}]}}",
+        Value::Array(excerpts)
+    );
+    let (reply, attempts) = client.complete(&system, user, |reply: HighlightReply| {
+        validate_highlight_scores(reply, candidates)
+    })?;
+    let planner = client.run(attempts);
+    if let Err(error) = model_registry::touch_models(std::slice::from_ref(&planner.model)) {
+        tracing::warn!("Failed updating model last-used time: {error}");
+    }
+    Ok((reply.scores, planner))
+}
+
+fn validate_chapters(reply: ChapterReply, duration_us: u64) -> Result<ChapterReply, String> {
+    if reply.chapters.is_empty() {
+        return Err("chapters is empty.".to_string());
+    }
+    for (index, chapter) in reply.chapters.iter().enumerate() {
+        if chapter.title.trim().is_empty() {
+            return Err(format!("chapters[{index}].title is empty."));
+        }
+        if duration_us > 0 && chapter.start_us >= duration_us {
+            return Err(format!(
+                "chapters[{index}] starts after the transcript ends at {duration_us}."
+            ));
+        }
+    }
+    Ok(reply)
+}
+
+/// Asks the planner for topical chapters over the whole transcript, in
+/// source time and start order.
+pub fn plan_chapters(
+    model: Option<String>,
+    transcript: &Transcript,
+    max_chapters: usize,
+) -> Result<(Vec<PlannedChapter>, PlannerRun), String> {
+    if transcript.segments.is_empty() {
+        return Err("Transcript has no segments to find chapters in.".to_string());
+    }
+    let duration_us = transcript_span(transcript);
+    let client = PlannerClient::new(model)?;
+    let system =
+        custom_prompt("chapter_plan").unwrap_or_else(|| DEFAULT_CHAPTER_PROMPT.to_string());
+    let user = format!(
+        "Transcript segments:\n{}\n\n\
+         Split the video into at most {max_chapters} chapters where the topic changes. The first \
+         chapter starts at 0; each should cover at least a minute when the video allows. Titles \
+         are short (max 6 words) and say what the chapter is about.\n\
+         Times are microseconds within [0, {duration_us}].\n\n\
+         Respond ONLY with this JSON (no markdown, no explanation):\n\
+         {{\"chapters\": [{{\"startUs\": 0, \"title\": \"...\"}}]}}",
+        simplified_segments(transcript, 0, u64::MAX)
+    );
+    let (reply, attempts) = client.complete(&system, user, |reply: ChapterReply| {
+        validate_chapters(reply, duration_us)
+    })?;
+    let mut chapters = reply.chapters;
+    chapters.sort_by_key(|chapter| chapter.start_us);
+    for chapter in &mut chapters {
+        chapter.title = chapter.title.trim().to_string();
+    }
+    let planner = client.run(attempts);
+    if let Err(error) = model_registry::touch_models(std::slice::from_ref(&planner.model)) {
+        tracing::warn!("Failed updating model last-used time: {error}");
+    }
+    Ok((chapters, planner))
+}
+
+/// Plans cuts from the stored transcript by calling the configured LLM
+/// directly instead of going through the Node cut planner.
+#[tauri::command]
+pub async fn plan_cuts(request: PlanCutsRequest) -> Result<CutPlan, String> {
+    tauri::async_runtime::spawn_blocking(move || plan_cuts_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn plan_templates(request: PlanTemplatesRequest) -> Result<TemplatePlan, String> {
+    tauri::async_runtime::spawn_blocking(move || plan_templates_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
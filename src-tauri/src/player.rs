@@ -0,0 +1,598 @@
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::media::resolve_source_path;
+use crate::preview::PREVIEW_DIR;
+use crate::settings::{read_app_settings, PlayerSettings};
+use crate::shutdown::{self, ChildGuard};
+use crate::timeline::main_track_clips;
+use crate::{event_bus, path_safety, read_timeline, windows};
+
+/// How long mpv gets to open its IPC socket.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const REPLY_TIMEOUT: Duration = Duration::from_secs(3);
+/// Minimum gap between two position events while playing.
+const POSITION_INTERVAL: Duration = Duration::from_millis(50);
+const EDL_FILE: &str = "playback.edl";
+
+#[cfg(unix)]
+type IpcStream = std::os::unix::net::UnixStream;
+#[cfg(windows)]
+type IpcStream = std::fs::File;
+
+/// Players by project; one mpv window each.
+static PLAYERS: Mutex<BTreeMap<String, Player>> = Mutex::new(BTreeMap::new());
+
+struct Player {
+    child: Child,
+    _tracked: ChildGuard,
+    control: IpcClient,
+    segments: Vec<PlaybackSegment>,
+}
+
+/// A kept range of the edit as it sits in the EDL: mpv plays the segments
+/// back to back, so removed ranges and gaps are skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PlaybackSegment {
+    /// Where the segment starts in mpv's playback time.
+    playback_start_us: u64,
+    timeline_start_us: u64,
+    timeline_end_us: u64,
+    /// Length in the source, which mpv plays at normal speed.
+    source_len_us: u64,
+}
+
+impl PlaybackSegment {
+    fn playback_end_us(&self) -> u64 {
+        self.playback_start_us + self.source_len_us
+    }
+}
+
+/// A source range of the EDL.
+struct EdlEntry {
+    path: PathBuf,
+    timeline_start_us: u64,
+    timeline_end_us: u64,
+    source_start_us: u64,
+    source_end_us: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlayerStatus {
+    Playing,
+    Paused,
+    /// The mpv window was closed or the player stopped.
+    Closed,
+}
+
+/// Payload of `PLAYER_POSITION_EVENT`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerPosition {
+    pub timeline_us: u64,
+    pub status: PlayerStatus,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerLoadRequest {
+    project_id: String,
+    /// Where playback starts; the beginning when unset.
+    at_us: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerSeekRequest {
+    project_id: String,
+    at_us: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerRequest {
+    project_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedPlayer {
+    pub edl_path: String,
+    /// Kept ranges of the edit, as played.
+    pub segments: usize,
+    /// Length of the playback, removed ranges excluded.
+    pub playback_duration_us: u64,
+}
+
+/// A JSON IPC connection to mpv: one command per line, replies matched by
+/// `request_id`. Events mpv broadcasts to every client are skipped.
+struct IpcClient {
+    stream: BufReader<IpcStream>,
+    next_request: u64,
+}
+
+impl IpcClient {
+    fn send(&mut self, command: Value) -> Result<Value, String> {
+        self.next_request += 1;
+        let request_id = self.next_request;
+        let line = json!({ "command": command, "request_id": request_id }).to_string();
+        self.stream
+            .get_mut()
+            .write_all(format!("{line}\n").as_bytes())
+            .map_err(|error| format!("Failed sending to mpv: {error}"))?;
+        let deadline = Instant::now() + REPLY_TIMEOUT;
+        let mut reply = String::new();
+        while Instant::now() < deadline {
+            reply.clear();
+            match self.stream.read_line(&mut reply) {
+                Ok(0) => return Err("mpv closed the connection.".to_string()),
+                Ok(_) => {}
+                Err(error) if is_timeout(&error) => continue,
+                Err(error) => return Err(format!("Failed reading from mpv: {error}")),
+            }
+            let Ok(reply) = serde_json::from_str::<Value>(&reply) else {
+                continue;
+            };
+            if reply.get("request_id").and_then(Value::as_u64) != Some(request_id) {
+                continue;
+            }
+            return match reply.get("error").and_then(Value::as_str) {
+                Some("success") | None => Ok(reply.get("data").cloned().unwrap_or(Value::Null)),
+                Some(error) => Err(format!("mpv rejected {command}: {error}")),
+            };
+        }
+        Err("mpv did not answer in time.".to_string())
+    }
+}
+
+fn is_timeout(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+fn players() -> MutexGuard<'static, BTreeMap<String, Player>> {
+    PLAYERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn mpv_binary(settings: &PlayerSettings) -> Option<PathBuf> {
+    if let Some(path) = settings
+        .mpv_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    {
+        return Some(PathBuf::from(path));
+    }
+    let name = if cfg!(windows) { "mpv.exe" } else { "mpv" };
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// mpv's EDL (`# mpv EDL v0`): one `path,start,length` line per kept range.
+/// Paths carry a `%bytes%` length prefix so commas in them are safe.
+fn edl_document(entries: &[EdlEntry]) -> (String, Vec<PlaybackSegment>) {
+    let mut document = String::from("# mpv EDL v0\n");
+    let mut segments = Vec::with_capacity(entries.len());
+    let mut playback_us = 0;
+    for entry in entries {
+        let path = entry.path.to_string_lossy();
+        let source_len_us = entry.source_end_us - entry.source_start_us;
+        document.push_str(&format!(
+            "%{}%{path},{:.6},{:.6}\n",
+            path.len(),
+            entry.source_start_us as f64 / 1_000_000.0,
+            source_len_us as f64 / 1_000_000.0
+        ));
+        segments.push(PlaybackSegment {
+            playback_start_us: playback_us,
+            timeline_start_us: entry.timeline_start_us,
+            timeline_end_us: entry.timeline_end_us,
+            source_len_us,
+        });
+        playback_us += source_len_us;
+    }
+    (document, segments)
+}
+
+/// Scales `offset` from a span of `from` to a span of `to`; speed-changed
+/// clips play at source speed, so the two differ.
+fn rescale(offset: u64, from: u64, to: u64) -> u64 {
+    (u128::from(offset) * u128::from(to) / u128::from(from.max(1))) as u64
+}
+
+/// The timeline position shown at mpv's `playback_us`.
+fn timeline_at(segments: &[PlaybackSegment], playback_us: u64) -> u64 {
+    let Some(segment) = segments
+        .iter()
+        .rev()
+        .find(|segment| segment.playback_start_us <= playback_us)
+    else {
+        return 0;
+    };
+    let offset = (playback_us - segment.playback_start_us).min(segment.source_len_us);
+    segment.timeline_start_us
+        + rescale(
+            offset,
+            segment.source_len_us,
+            segment.timeline_end_us - segment.timeline_start_us,
+        )
+}
+
+/// Where mpv has to be to show `timeline_us`. Positions in a removed range
+/// land on the next kept one, or the end.
+fn playback_at(segments: &[PlaybackSegment], timeline_us: u64) -> u64 {
+    segments
+        .iter()
+        .find(|segment| timeline_us < segment.timeline_end_us)
+        .map_or_else(
+            || segments.last().map_or(0, PlaybackSegment::playback_end_us),
+            |segment| {
+                let offset = timeline_us.saturating_sub(segment.timeline_start_us);
+                segment.playback_start_us
+                    + rescale(
+                        offset,
+                        segment.timeline_end_us - segment.timeline_start_us,
+                        segment.source_len_us,
+                    )
+            },
+        )
+}
+
+fn write_edl(project_id: &str) -> Result<(PathBuf, Vec<PlaybackSegment>), String> {
+    let timeline = read_timeline(project_id)?;
+    let mut resolved = HashMap::<String, PathBuf>::new();
+    let mut entries = Vec::new();
+    for clip in main_track_clips(&timeline) {
+        if clip.end_us <= clip.start_us || clip.source_end_us <= clip.source_start_us {
+            continue;
+        }
+        let path = match resolved.get(&clip.source_ref) {
+            Some(path) => path.clone(),
+            None => {
+                let path = resolve_source_path(project_id, Some(&clip.source_ref))?;
+                resolved.insert(clip.source_ref.clone(), path.clone());
+                path
+            }
+        };
+        entries.push(EdlEntry {
+            path,
+            timeline_start_us: clip.start_us,
+            timeline_end_us: clip.end_us,
+            source_start_us: clip.source_start_us,
+            source_end_us: clip.source_end_us,
+        });
+    }
+    if entries.is_empty() {
+        return Err("The timeline has no video clips to play.".to_string());
+    }
+    let (document, segments) = edl_document(&entries);
+    let dir = path_safety::project_dir(project_id)?.join(PREVIEW_DIR);
+    fs::create_dir_all(&dir).map_err(|error| format!("Failed creating preview dir: {error}"))?;
+    let path = dir.join(EDL_FILE);
+    fs::write(&path, document).map_err(|error| format!("Failed writing EDL: {error}"))?;
+    Ok((path, segments))
+}
+
+fn ipc_path(project_id: &str) -> PathBuf {
+    let name = format!(
+        "lapaas-mpv-{}-{}",
+        std::process::id(),
+        project_id
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .take(24)
+            .collect::<String>()
+    );
+    if cfg!(windows) {
+        PathBuf::from(format!(r"\\.\pipe\{name}"))
+    } else {
+        env::temp_dir().join(format!("{name}.sock"))
+    }
+}
+
+#[cfg(unix)]
+fn open_ipc(path: &Path) -> io::Result<IpcStream> {
+    let stream = std::os::unix::net::UnixStream::connect(path)?;
+    stream.set_read_timeout(Some(Duration::from_millis(500)))?;
+    Ok(stream)
+}
+
+#[cfg(windows)]
+fn open_ipc(path: &Path) -> io::Result<IpcStream> {
+    fs::OpenOptions::new().read(true).write(true).open(path)
+}
+
+/// Connects once mpv has opened its socket, or fails if it exits first.
+fn connect(child: &mut Child, path: &Path) -> Result<IpcStream, String> {
+    let deadline = Instant::now() + CONNECT_TIMEOUT;
+    loop {
+        match open_ipc(path) {
+            Ok(stream) => return Ok(stream),
+            Err(error) if Instant::now() >= deadline => {
+                return Err(format!("Failed connecting to mpv: {error}"))
+            }
+            Err(_) => {}
+        }
+        if let Ok(Some(status)) = child.try_wait() {
+            return Err(format!("mpv exited during startup ({status})."));
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Streams mpv's position and pause state as timeline positions until the
+/// window closes, then drops the player.
+fn watch(project_id: String, pid: u32, stream: IpcStream, segments: Vec<PlaybackSegment>) {
+    let mut stream = BufReader::new(stream);
+    let observe = "{\"command\":[\"observe_property\",1,\"time-pos\"]}\n\
+                   {\"command\":[\"observe_property\",2,\"pause\"]}\n";
+    if stream.get_mut().write_all(observe.as_bytes()).is_err() {
+        return;
+    }
+    let (mut timeline_us, mut paused) = (0, true);
+    let mut last_sent = None::<Instant>;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match stream.read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(error) if is_timeout(&error) => continue,
+            Err(_) => break,
+        }
+        let Ok(event) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        if event.get("event").and_then(Value::as_str) != Some("property-change") {
+            continue;
+        }
+        let data = event.get("data");
+        match event.get("name").and_then(Value::as_str) {
+            Some("time-pos") => {
+                let Some(seconds) = data.and_then(Value::as_f64) else {
+                    continue;
+                };
+                timeline_us = timeline_at(&segments, (seconds.max(0.0) * 1_000_000.0) as u64);
+                if !paused && last_sent.is_some_and(|at| at.elapsed() < POSITION_INTERVAL) {
+                    continue;
+                }
+            }
+            Some("pause") => paused = data.and_then(Value::as_bool).unwrap_or(paused),
+            _ => continue,
+        }
+        last_sent = Some(Instant::now());
+        let status = if paused {
+            PlayerStatus::Paused
+        } else {
+            PlayerStatus::Playing
+        };
+        let position = PlayerPosition {
+            timeline_us,
+            status,
+        };
+        event_bus::emit(&project_id, event_bus::PLAYER_POSITION_EVENT, &position);
+    }
+
+    // A reload replaces the player; only drop it if it is still this one.
+    let mut players = players();
+    if players
+        .get(&project_id)
+        .is_some_and(|player| player.child.id() == pid)
+    {
+        if let Some(mut player) = players.remove(&project_id) {
+            shutdown::terminate_child(&mut player.child);
+        }
+    }
+    drop(players);
+    event_bus::emit(
+        &project_id,
+        event_bus::PLAYER_POSITION_EVENT,
+        &PlayerPosition {
+            timeline_us,
+            status: PlayerStatus::Closed,
+        },
+    );
+}
+
+fn stop_player(project_id: &str) {
+    let Some(mut player) = players().remove(project_id) else {
+        return;
+    };
+    let _ = player.control.send(json!(["quit"]));
+    shutdown::terminate_child(&mut player.child);
+}
+
+fn load_blocking(request: PlayerLoadRequest) -> Result<LoadedPlayer, String> {
+    let settings = read_app_settings()?;
+    let mpv = mpv_binary(&settings.player).ok_or_else(|| {
+        "mpv was not found; install it or set its path in the player settings.".to_string()
+    })?;
+    let (edl_path, segments) = write_edl(&request.project_id)?;
+    stop_player(&request.project_id);
+
+    let ipc = ipc_path(&request.project_id);
+    if !cfg!(windows) {
+        let _ = fs::remove_file(&ipc);
+    }
+    let start_us = playback_at(&segments, request.at_us.unwrap_or(0));
+    let title =
+        windows::project_name(&request.project_id).unwrap_or_else(|_| request.project_id.clone());
+    let mut child = Command::new(&mpv)
+        .arg(format!("--input-ipc-server={}", ipc.display()))
+        .args([
+            "--no-terminal",
+            "--idle=no",
+            "--keep-open=yes",
+            "--force-window=yes",
+            "--pause",
+            "--hr-seek=yes",
+        ])
+        .arg(format!("--title={title}"))
+        .arg(format!("--start={:.6}", start_us as f64 / 1_000_000.0))
+        .arg(&edl_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|error| format!("Failed starting mpv: {error}"))?;
+    let tracked = shutdown::track_child(&child);
+    let (control, events) = match connect(&mut child, &ipc)
+        .and_then(|control| connect(&mut child, &ipc).map(|events| (control, events)))
+    {
+        Ok(streams) => streams,
+        Err(error) => {
+            shutdown::terminate_child(&mut child);
+            return Err(error);
+        }
+    };
+
+    let pid = child.id();
+    let playback_duration_us = segments.last().map_or(0, PlaybackSegment::playback_end_us);
+    let loaded = LoadedPlayer {
+        edl_path: edl_path.to_string_lossy().to_string(),
+        segments: segments.len(),
+        playback_duration_us,
+    };
+    players().insert(
+        request.project_id.clone(),
+        Player {
+            child,
+            _tracked: tracked,
+            control: IpcClient {
+                stream: BufReader::new(control),
+                next_request: 0,
+            },
+            segments: segments.clone(),
+        },
+    );
+    let project_id = request.project_id;
+    thread::spawn(move || watch(project_id, pid, events, segments));
+    Ok(loaded)
+}
+
+/// Runs `action` against the project's player.
+fn with_player<T>(
+    project_id: &str,
+    action: impl FnOnce(&mut Player) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut players = players();
+    let player = players
+        .get_mut(project_id)
+        .ok_or_else(|| "No player is loaded for this project.".to_string())?;
+    action(player)
+}
+
+fn set_paused(project_id: &str, paused: bool) -> Result<(), String> {
+    with_player(project_id, |player| {
+        player
+            .control
+            .send(json!(["set_property", "pause", paused]))
+            .map(|_| ())
+    })
+}
+
+/// Opens the project's timeline in an mpv window, paused at `atUs`. The
+/// edit is played from an EDL of its kept ranges, so removed parts are
+/// skipped; `player://position` events report the timeline position.
+#[tauri::command]
+pub async fn player_load(request: PlayerLoadRequest) -> Result<LoadedPlayer, String> {
+    tauri::async_runtime::spawn_blocking(move || load_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// Seeks to a timeline position, frame-exact. Positions in a removed range
+/// land on the next kept one.
+#[tauri::command]
+pub async fn player_seek(request: PlayerSeekRequest) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        with_player(&request.project_id, |player| {
+            let playback_us = playback_at(&player.segments, request.at_us);
+            player
+                .control
+                .send(json!([
+                    "seek",
+                    playback_us as f64 / 1_000_000.0,
+                    "absolute+exact"
+                ]))
+                .map(|_| ())
+        })
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn player_play(request: PlayerRequest) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || set_paused(&request.project_id, false))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn player_pause(request: PlayerRequest) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || set_paused(&request.project_id, true))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// Closes the project's mpv window.
+#[tauri::command]
+pub async fn player_stop(request: PlayerRequest) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || stop_player(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edl_skips_removed_ranges_and_maps_positions() {
+        let entries = [
+            EdlEntry {
+                path: PathBuf::from("/media/a,b.mp4"),
+                timeline_start_us: 0,
+                timeline_end_us: 2_000_000,
+                source_start_us: 5_000_000,
+                source_end_us: 7_000_000,
+            },
+            // Played at 2x: 4s of source over 2s of timeline, after a gap.
+            EdlEntry {
+                path: PathBuf::from("/media/c.mp4"),
+                timeline_start_us: 3_000_000,
+                timeline_end_us: 5_000_000,
+                source_start_us: 0,
+                source_end_us: 4_000_000,
+            },
+        ];
+        let (document, segments) = edl_document(&entries);
+        assert_eq!(
+            document,
+            "# mpv EDL v0\n%14%/media/a,b.mp4,5.000000,2.000000\n\
+             %12%/media/c.mp4,0.000000,4.000000\n"
+        );
+        assert_eq!(timeline_at(&segments, 1_000_000), 1_000_000);
+        assert_eq!(timeline_at(&segments, 4_000_000), 4_000_000);
+        assert_eq!(playback_at(&segments, 2_500_000), 2_000_000);
+        assert_eq!(playback_at(&segments, 4_000_000), 4_000_000);
+        assert_eq!(playback_at(&segments, 9_000_000), 6_000_000);
+    }
+}
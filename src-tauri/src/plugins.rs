@@ -0,0 +1,320 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::settings::{self, PluginSettings};
+use crate::{
+    ffmpeg, path_safety, read_timeline, shutdown, timeline_validation, workspace_root, Timeline,
+};
+
+const PLUGINS_DIR: &str = "plugins";
+const MANIFEST_FILE: &str = "plugin.json";
+/// Version of the stdin/stdout contract below; plugins declare the one they
+/// were written against.
+const API_VERSION: u32 = 1;
+/// Per-project scratch space; each plugin gets a fresh subdirectory per run.
+pub const WORK_DIR: &str = "plugin-work";
+const STAGED_TIMELINE_FILE: &str = "timeline.json";
+const DEFAULT_TIMEOUT_SECS: u64 = 300;
+const MAX_TIMEOUT_SECS: u64 = 3600;
+/// Environment passed through to plugins; everything else is dropped.
+const INHERITED_ENV: &[&str] = &["HOME", "LANG", "SYSTEMROOT", "TEMP", "TMP", "USERPROFILE"];
+
+/// Where in the pipeline a plugin runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PluginStage {
+    /// After cut planning, on the timeline about to be rendered.
+    PreRender,
+}
+
+/// `plugins/<name>/plugin.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginManifest {
+    /// Must match the directory name.
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    pub api_version: u32,
+    pub stage: PluginStage,
+    /// Program inside the plugin directory, or an interpreter on PATH such
+    /// as `node` or `python3` with the script in `args`.
+    pub executable: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfo {
+    pub name: String,
+    pub dir: String,
+    pub enabled: bool,
+    pub manifest: Option<PluginManifest>,
+    /// Why the manifest could not be loaded; the plugin never runs then.
+    pub error: Option<String>,
+}
+
+/// Written to the plugin's stdin.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StageInput<'a> {
+    api_version: u32,
+    stage: PluginStage,
+    project_id: &'a str,
+    /// Read-only as far as the plugin is concerned; writes belong in
+    /// `work_dir`.
+    project_dir: String,
+    work_dir: String,
+    timeline: &'a Timeline,
+    options: Value,
+}
+
+/// Read from the plugin's stdout.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct StageOutput {
+    /// Replacement timeline; the input passes through when absent.
+    timeline: Option<Timeline>,
+    messages: Vec<String>,
+}
+
+fn plugins_root() -> Result<PathBuf, String> {
+    Ok(workspace_root()?.join(PLUGINS_DIR))
+}
+
+fn read_manifest(dir: &Path, name: &str) -> Result<PluginManifest, String> {
+    let raw = fs::read_to_string(dir.join(MANIFEST_FILE))
+        .map_err(|error| format!("Failed reading {MANIFEST_FILE}: {error}"))?;
+    let manifest = serde_json::from_str::<PluginManifest>(&raw)
+        .map_err(|error| format!("Invalid {MANIFEST_FILE}: {error}"))?;
+    if manifest.name != name {
+        return Err(format!(
+            "Manifest name {:?} does not match directory {name:?}",
+            manifest.name
+        ));
+    }
+    if manifest.api_version != API_VERSION {
+        return Err(format!(
+            "Plugin targets API version {}, this app supports {API_VERSION}",
+            manifest.api_version
+        ));
+    }
+    if manifest.executable.trim().is_empty() {
+        return Err("Manifest has no executable".to_string());
+    }
+    Ok(manifest)
+}
+
+/// Every directory under `plugins/` with its manifest or load error.
+fn discover(settings: &PluginSettings) -> Result<Vec<PluginInfo>, String> {
+    let root = plugins_root()?;
+    if !root.is_dir() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        fs::read_dir(&root).map_err(|error| format!("Failed reading plugins dir: {error}"))?;
+    let mut plugins = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let loaded = path_safety::validate_file_name(&name)
+                .and_then(|()| read_manifest(&entry.path(), &name));
+            let (manifest, error) = match loaded {
+                Ok(manifest) => (Some(manifest), None),
+                Err(error) => (None, Some(error)),
+            };
+            PluginInfo {
+                enabled: settings.enabled.contains(&name),
+                dir: entry.path().to_string_lossy().to_string(),
+                name,
+                manifest,
+                error,
+            }
+        })
+        .collect::<Vec<_>>();
+    plugins.sort_by(|left, right| left.name.cmp(&right.name));
+    Ok(plugins)
+}
+
+/// The program to start: a file inside the plugin directory when one
+/// exists, otherwise a bare command name looked up on PATH.
+fn resolve_executable(dir: &Path, executable: &str) -> Result<PathBuf, String> {
+    let bundled = dir.join(executable);
+    if bundled.is_file() {
+        return path_safety::ensure_inside(&bundled, dir);
+    }
+    let bare = Path::new(executable).components().count() == 1;
+    if bare {
+        Ok(PathBuf::from(executable))
+    } else {
+        Err(format!("Plugin executable not found: {executable}"))
+    }
+}
+
+fn fresh_dir(dir: &Path) -> Result<(), String> {
+    if dir.exists() {
+        fs::remove_dir_all(dir)
+            .map_err(|error| format!("Failed clearing plugin work dir: {error}"))?;
+    }
+    fs::create_dir_all(dir).map_err(|error| format!("Failed creating plugin work dir: {error}"))
+}
+
+/// Runs one plugin on `timeline` inside its own empty work directory,
+/// with a scrubbed environment and the manifest's timeout.
+fn run_plugin(
+    plugin_dir: &Path,
+    manifest: &PluginManifest,
+    project_id: &str,
+    timeline: &Timeline,
+    options: Value,
+) -> Result<Timeline, String> {
+    let name = &manifest.name;
+    let project_dir = path_safety::project_dir(project_id)?;
+    let work_dir = project_dir.join(WORK_DIR).join(name);
+    fresh_dir(&work_dir)?;
+
+    let input = serde_json::to_vec(&StageInput {
+        api_version: API_VERSION,
+        stage: manifest.stage,
+        project_id,
+        project_dir: project_dir.to_string_lossy().to_string(),
+        work_dir: work_dir.to_string_lossy().to_string(),
+        timeline,
+        options,
+    })
+    .map_err(|error| format!("Plugin input serialize error: {error}"))?;
+
+    let mut command = Command::new(resolve_executable(plugin_dir, &manifest.executable)?);
+    command
+        .args(&manifest.args)
+        .current_dir(&work_dir)
+        .env_clear()
+        .env(
+            "PATH",
+            ffmpeg::child_path()
+                .or_else(|| std::env::var_os("PATH"))
+                .unwrap_or_default(),
+        )
+        .env("TMPDIR", &work_dir)
+        .env("LAPAAS_PLUGIN_DIR", plugin_dir)
+        .env("LAPAAS_PLUGIN_WORK_DIR", &work_dir);
+    for key in INHERITED_ENV {
+        if let Some(value) = std::env::var_os(key) {
+            command.env(key, value);
+        }
+    }
+    let timeout = Duration::from_secs(manifest.timeout_secs.clamp(1, MAX_TIMEOUT_SECS));
+    tracing::info!("Running plugin {name} on project {project_id}");
+    let output = shutdown::run_tracked_with_timeout(&mut command, input, timeout)
+        .map_err(|error| format!("Plugin {name} failed to run: {error}"))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !stderr.trim().is_empty() {
+        tracing::info!("Plugin {name} stderr: {}", stderr.trim());
+    }
+    if !output.status.success() {
+        return Err(format!(
+            "Plugin {name} exited with {}: {}",
+            output.status,
+            stderr.trim()
+        ));
+    }
+
+    let result = serde_json::from_slice::<StageOutput>(&output.stdout)
+        .map_err(|error| format!("Plugin {name} returned invalid JSON: {error}"))?;
+    for message in &result.messages {
+        tracing::info!("Plugin {name}: {message}");
+    }
+    let Some(staged) = result.timeline else {
+        return Ok(timeline.clone());
+    };
+    if staged.project_id != project_id {
+        return Err(format!(
+            "Plugin {name} returned a timeline for another project"
+        ));
+    }
+    let diagnostics = timeline_validation::validate(project_id, &staged);
+    if timeline_validation::has_errors(&diagnostics) {
+        return Err(format!(
+            "Plugin {name} returned a broken timeline: {}",
+            timeline_validation::error_summary(&diagnostics)
+        ));
+    }
+    Ok(staged)
+}
+
+/// Passes the project's timeline through every enabled pre-render plugin,
+/// in settings order, and writes the result for the render script. None
+/// when no plugin is enabled, so the saved timeline is rendered as is.
+pub fn run_pre_render(project_id: &str) -> Result<Option<PathBuf>, String> {
+    let settings = settings::read_app_settings()?.plugins;
+    if settings.enabled.is_empty() {
+        return Ok(None);
+    }
+    let plugins = discover(&settings)?;
+    let mut timeline = read_timeline(project_id)?;
+    let mut ran = 0;
+    for name in &settings.enabled {
+        let plugin = plugins
+            .iter()
+            .find(|plugin| &plugin.name == name)
+            .ok_or_else(|| format!("Enabled plugin {name} is not installed"))?;
+        let manifest = plugin.manifest.as_ref().ok_or_else(|| {
+            format!(
+                "Plugin {name} can't load: {}",
+                plugin.error.as_deref().unwrap_or_default()
+            )
+        })?;
+        if manifest.stage != PluginStage::PreRender {
+            continue;
+        }
+        let options = settings.options.get(name).cloned().unwrap_or(Value::Null);
+        timeline = run_plugin(
+            Path::new(&plugin.dir),
+            manifest,
+            project_id,
+            &timeline,
+            options,
+        )?;
+        ran += 1;
+    }
+    if ran == 0 {
+        return Ok(None);
+    }
+
+    let path = path_safety::project_dir(project_id)?
+        .join(WORK_DIR)
+        .join(STAGED_TIMELINE_FILE);
+    let serialized = serde_json::to_string_pretty(&timeline)
+        .map_err(|error| format!("Timeline serialize error: {error}"))?;
+    fs::write(&path, serialized)
+        .map_err(|error| format!("Failed writing plugin timeline: {error}"))?;
+    Ok(Some(path))
+}
+
+pub fn read_staged_timeline(path: &Path) -> Result<Timeline, String> {
+    let raw = fs::read_to_string(path)
+        .map_err(|error| format!("Failed reading plugin timeline: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("Invalid plugin timeline JSON: {error}"))
+}
+
+#[tauri::command]
+pub async fn list_plugins() -> Result<Vec<PluginInfo>, String> {
+    tauri::async_runtime::spawn_blocking(|| discover(&settings::read_app_settings()?.plugins))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,146 @@
+#[cfg(unix)]
+use std::process::{Child, Command, Stdio};
+use std::sync::{Mutex, MutexGuard};
+
+use tauri::{AppHandle, Manager};
+
+use crate::settings;
+
+const REASON: &str = "Rendering or processing video";
+
+/// Keeps the machine awake while it is held; the OS lets it sleep again
+/// once dropped.
+enum Inhibitor {
+    /// `caffeinate` or `systemd-inhibit` running until killed; either also
+    /// exits on its own if the app dies.
+    #[cfg(unix)]
+    Process(Child),
+    /// Thread holding `SetThreadExecutionState`; it clears the flag and
+    /// exits when the sender is dropped.
+    #[cfg(windows)]
+    Thread(std::sync::mpsc::Sender<()>),
+}
+
+impl Drop for Inhibitor {
+    fn drop(&mut self) {
+        match self {
+            #[cfg(unix)]
+            Self::Process(child) => {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+            #[cfg(windows)]
+            Self::Thread(_) => {}
+        }
+    }
+}
+
+/// Sleep inhibitor held while the scheduler has jobs, registered as
+/// managed state.
+#[derive(Default)]
+pub struct SleepGuard(Mutex<Option<Inhibitor>>);
+
+impl SleepGuard {
+    fn lock(&self) -> MutexGuard<'_, Option<Inhibitor>> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn inhibit() -> Result<Inhibitor, String> {
+    // -i blocks idle sleep; -w exits along with this process.
+    Command::new("caffeinate")
+        .args(["-i", "-w", &std::process::id().to_string()])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(Inhibitor::Process)
+        .map_err(|error| format!("Failed starting caffeinate: {error}"))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn inhibit() -> Result<Inhibitor, String> {
+    // The lock lasts as long as the inner command, which follows this
+    // process so a crash can't leave the machine awake.
+    Command::new("systemd-inhibit")
+        .args([
+            "--what=idle:sleep",
+            "--who=Lapaas AI Editor",
+            &format!("--why={REASON}"),
+            "--mode=block",
+            "tail",
+            &format!("--pid={}", std::process::id()),
+            "-f",
+            "/dev/null",
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(Inhibitor::Process)
+        .map_err(|error| format!("Failed starting systemd-inhibit: {error}"))
+}
+
+#[cfg(windows)]
+fn inhibit() -> Result<Inhibitor, String> {
+    const ES_CONTINUOUS: u32 = 0x8000_0000;
+    const ES_SYSTEM_REQUIRED: u32 = 0x0000_0001;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn SetThreadExecutionState(flags: u32) -> u32;
+    }
+
+    // The flag belongs to the calling thread, so one thread holds it for
+    // as long as the inhibitor lives.
+    let (release, released) = std::sync::mpsc::channel::<()>();
+    let (started, result) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        // SAFETY: plain Win32 call with constant flags.
+        let previous = unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED) };
+        let _ = started.send(previous != 0);
+        if previous == 0 {
+            return;
+        }
+        // Blocks until the sender is dropped.
+        let _ = released.recv();
+        // SAFETY: as above.
+        unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+    });
+    match result.recv() {
+        Ok(true) => Ok(Inhibitor::Thread(release)),
+        _ => Err(format!("SetThreadExecutionState refused: {REASON}")),
+    }
+}
+
+/// Called by the scheduler when a job starts: keeps the machine awake
+/// unless the settings opt out.
+pub fn job_started(app: &AppHandle) {
+    let guard = app.state::<SleepGuard>();
+    let mut inhibitor = guard.lock();
+    if inhibitor.is_some() {
+        return;
+    }
+    let enabled = settings::read_app_settings()
+        .map(|settings| settings.power.prevent_sleep)
+        .unwrap_or(true);
+    if !enabled {
+        return;
+    }
+    match inhibit() {
+        Ok(held) => {
+            tracing::info!("Preventing sleep: {REASON}");
+            *inhibitor = Some(held);
+        }
+        Err(error) => tracing::warn!("Could not prevent sleep: {error}"),
+    }
+}
+
+/// Called by the scheduler once no job is running or queued.
+pub fn queue_drained(app: &AppHandle) {
+    if app.state::<SleepGuard>().lock().take().is_some() {
+        tracing::info!("Job queue drained; allowing sleep again");
+    }
+}
@@ -0,0 +1,491 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+use tracing::Instrument;
+
+use crate::ffmpeg::ffmpeg_binary;
+use crate::media::resolve_source_path;
+use crate::render_history::timeline_content_hash;
+use crate::timeline::main_track_clips;
+use crate::{
+    event_bus, logging, path_safety, read_timeline, run_node_script, scheduler, script_path,
+    Timeline,
+};
+
+pub const PREVIEW_DIR: &str = "preview";
+const CHUNKS_DIR: &str = "chunks";
+const PREVIEW_FILE: &str = "preview.mp4";
+const MANIFEST_FILE: &str = "preview.json";
+/// Part of every chunk key; bump when the chunk encoding changes so cached
+/// chunks are rendered again.
+const CHUNK_FORMAT: u32 = 1;
+/// Gaps shorter than a preview frame are dropped.
+const MIN_GAP_US: u64 = 42_000;
+
+/// Projects with a preview build running.
+static BUILDING: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildPreviewRequest {
+    project_id: String,
+    /// Render every chunk again instead of reusing cached ones.
+    rebuild: Option<bool>,
+    /// Defaults to background so previews don't hold up renders.
+    priority: Option<scheduler::Priority>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewStage {
+    Chunks,
+    Concat,
+    Done,
+}
+
+/// Payload of `PREVIEW_PROGRESS_EVENT`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewProgress {
+    pub stage: PreviewStage,
+    pub completed: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ChunkSource {
+    Clip {
+        path: PathBuf,
+        source_start_us: u64,
+        source_end_us: u64,
+        /// Average rate over the clip; ramps play at a constant speed.
+        speed: f64,
+    },
+    Gap,
+}
+
+/// One stretch of the main track, rendered to `chunks/<key>.mp4`.
+#[derive(Debug, Clone, PartialEq)]
+struct PreviewChunk {
+    start_us: u64,
+    end_us: u64,
+    key: String,
+    source: ChunkSource,
+}
+
+/// What `preview/preview.json` records about the last build.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewResult {
+    pub path: String,
+    pub duration_us: u64,
+    pub timeline_version: u32,
+    /// Compare with the current timeline to tell whether the preview is stale.
+    pub timeline_hash: String,
+    pub chunk_count: usize,
+    pub rendered_chunks: usize,
+    pub cached_chunks: usize,
+}
+
+/// Clears the in-progress mark however the build ends.
+struct BuildGuard(String);
+
+impl Drop for BuildGuard {
+    fn drop(&mut self) {
+        if let Ok(mut building) = BUILDING.lock() {
+            building.remove(&self.0);
+        }
+    }
+}
+
+fn start_build(project_id: &str) -> Result<BuildGuard, String> {
+    let mut building = BUILDING
+        .lock()
+        .map_err(|_| "Preview registry lock poisoned".to_string())?;
+    if !building.insert(project_id.to_string()) {
+        return Err("A preview is already building for this project.".to_string());
+    }
+    Ok(BuildGuard(project_id.to_string()))
+}
+
+fn chunk_key(parts: &str) -> String {
+    Sha256::digest(format!("{CHUNK_FORMAT}|{parts}").as_bytes())
+        .iter()
+        .take(12)
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Size and modification time, so replacing a source file re-renders its
+/// chunks.
+fn file_stamp(path: &Path) -> String {
+    fs::metadata(path)
+        .map(|metadata| {
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |elapsed| elapsed.as_secs());
+            format!("{}:{modified}", metadata.len())
+        })
+        .unwrap_or_default()
+}
+
+fn gap_chunk(start_us: u64, end_us: u64) -> PreviewChunk {
+    PreviewChunk {
+        start_us,
+        end_us,
+        key: chunk_key(&format!("gap|{}", end_us - start_us)),
+        source: ChunkSource::Gap,
+    }
+}
+
+/// Splits the main video track into clip and gap chunks covering the
+/// timeline from zero.
+fn plan_chunks(
+    timeline: &Timeline,
+    resolve: impl Fn(&str) -> Result<PathBuf, String>,
+) -> Result<Vec<PreviewChunk>, String> {
+    let mut chunks = Vec::new();
+    let mut cursor_us = 0;
+    for clip in main_track_clips(timeline) {
+        let start_us = clip.start_us.max(cursor_us);
+        if start_us >= clip.end_us {
+            continue;
+        }
+        if start_us >= cursor_us + MIN_GAP_US {
+            chunks.push(gap_chunk(cursor_us, start_us));
+        }
+        let path = resolve(&clip.source_ref)?;
+        let speed = (clip.source_end_us - clip.source_start_us) as f64
+            / (clip.end_us - clip.start_us) as f64;
+        // An overlapped start is skipped in source time too.
+        let source_start_us =
+            clip.source_start_us + ((start_us - clip.start_us) as f64 * speed).round() as u64;
+        chunks.push(PreviewChunk {
+            start_us,
+            end_us: clip.end_us,
+            key: chunk_key(&format!(
+                "clip|{}|{}|{source_start_us}|{}|{speed:.6}",
+                path.display(),
+                file_stamp(&path),
+                clip.source_end_us
+            )),
+            source: ChunkSource::Clip {
+                path,
+                source_start_us,
+                source_end_us: clip.source_end_us,
+                speed,
+            },
+        });
+        cursor_us = clip.end_us;
+    }
+    Ok(chunks)
+}
+
+fn run_ffmpeg(args: &[String], stage: &str) -> Result<(), String> {
+    let output = Command::new(ffmpeg_binary())
+        .args(args)
+        .output()
+        .map_err(|error| format!("Failed to execute ffmpeg: {error}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(format!(
+        "ffmpeg {stage} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+            .trim()
+            .chars()
+            .take(300)
+            .collect::<String>()
+    ))
+}
+
+/// Renders one chunk to a temporary file first so a failed encode never
+/// leaves a broken chunk in the cache.
+fn render_chunk(chunk: &PreviewChunk, output: &Path) -> Result<(), String> {
+    let partial = output.with_extension("part.mp4");
+    let seconds = format!(
+        "{:.6}",
+        (chunk.end_us - chunk.start_us) as f64 / 1_000_000.0
+    );
+    let rendered = match &chunk.source {
+        ChunkSource::Clip {
+            path,
+            source_start_us,
+            source_end_us,
+            speed,
+        } => {
+            let script = script_path("scripts/render_pipeline.mjs")?;
+            let args = [
+                "--preview-chunk".to_string(),
+                "--source".to_string(),
+                path.to_string_lossy().to_string(),
+                "--start-us".to_string(),
+                source_start_us.to_string(),
+                "--end-us".to_string(),
+                source_end_us.to_string(),
+                "--output".to_string(),
+                partial.to_string_lossy().to_string(),
+                "--speed".to_string(),
+                format!("{speed:.6}"),
+            ];
+            run_node_script(&script, &args).map(|_| ())
+        }
+        // Same frame, rate and audio layout as the script's preview chunks.
+        ChunkSource::Gap => run_ffmpeg(
+            &[
+                "-y",
+                "-loglevel",
+                "error",
+                "-f",
+                "lavfi",
+                "-i",
+                &format!("color=c=black:s=854x480:r=24:d={seconds}"),
+                "-f",
+                "lavfi",
+                "-i",
+                "anullsrc=r=48000:cl=stereo",
+                "-t",
+                &seconds,
+                "-c:v",
+                "libx264",
+                "-preset",
+                "ultrafast",
+                "-crf",
+                "30",
+                "-pix_fmt",
+                "yuv420p",
+                "-c:a",
+                "aac",
+                "-b:a",
+                "96k",
+                "-movflags",
+                "+faststart",
+                &partial.to_string_lossy(),
+            ]
+            .map(str::to_string),
+            "preview gap",
+        ),
+    };
+    if let Err(error) = rendered {
+        let _ = fs::remove_file(&partial);
+        return Err(error);
+    }
+    fs::rename(&partial, output).map_err(|error| format!("Failed saving preview chunk: {error}"))
+}
+
+fn concat_line(path: &Path) -> String {
+    format!("file '{}'\n", path.to_string_lossy().replace('\'', "'\\''"))
+}
+
+/// Joins the chunks without re-encoding.
+fn concat_chunks(chunk_paths: &[PathBuf], dir: &Path) -> Result<PathBuf, String> {
+    let list = dir.join("concat.txt");
+    fs::write(
+        &list,
+        chunk_paths
+            .iter()
+            .map(|path| concat_line(path))
+            .collect::<String>(),
+    )
+    .map_err(|error| format!("Failed writing preview concat list: {error}"))?;
+    let output = dir.join(PREVIEW_FILE);
+    let partial = output.with_extension("part.mp4");
+    let joined = run_ffmpeg(
+        &[
+            "-y",
+            "-loglevel",
+            "error",
+            "-f",
+            "concat",
+            "-safe",
+            "0",
+            "-i",
+            &list.to_string_lossy(),
+            "-c",
+            "copy",
+            "-movflags",
+            "+faststart",
+            &partial.to_string_lossy(),
+        ]
+        .map(str::to_string),
+        "preview concat",
+    );
+    let _ = fs::remove_file(&list);
+    if let Err(error) = joined {
+        let _ = fs::remove_file(&partial);
+        return Err(error);
+    }
+    fs::rename(&partial, &output).map_err(|error| format!("Failed saving preview: {error}"))?;
+    Ok(output)
+}
+
+/// Removes cached chunks the current timeline no longer uses.
+fn prune_chunks(chunks_dir: &Path, keep: &BTreeSet<String>) {
+    let Ok(entries) = fs::read_dir(chunks_dir) else {
+        return;
+    };
+    for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string());
+        if stem.is_some_and(|stem| !keep.contains(&stem)) {
+            let _ = fs::remove_file(&path);
+        }
+    }
+}
+
+fn publish_progress(project_id: &str, stage: PreviewStage, completed: usize, total: usize) {
+    event_bus::publish(
+        project_id,
+        event_bus::PREVIEW_PROGRESS_EVENT,
+        &PreviewProgress {
+            stage,
+            completed,
+            total,
+        },
+    );
+}
+
+fn build_preview_blocking(project_id: &str, rebuild: bool) -> Result<PreviewResult, String> {
+    let _guard = start_build(project_id)?;
+    let timeline = read_timeline(project_id)?;
+    let chunks = plan_chunks(&timeline, |source_ref| {
+        resolve_source_path(project_id, Some(source_ref))
+    })?;
+    if chunks.is_empty() {
+        return Err("The timeline has no source clips to preview.".to_string());
+    }
+
+    let dir = path_safety::project_dir(project_id)?.join(PREVIEW_DIR);
+    let chunks_dir = dir.join(CHUNKS_DIR);
+    fs::create_dir_all(&chunks_dir)
+        .map_err(|error| format!("Failed creating preview dir: {error}"))?;
+
+    let total = chunks.len();
+    let mut chunk_paths = Vec::with_capacity(total);
+    let mut rendered_chunks = 0;
+    publish_progress(project_id, PreviewStage::Chunks, 0, total);
+    for (index, chunk) in chunks.iter().enumerate() {
+        let path = chunks_dir.join(format!("{}.mp4", chunk.key));
+        if rebuild || !path.is_file() {
+            render_chunk(chunk, &path)?;
+            rendered_chunks += 1;
+        }
+        chunk_paths.push(path);
+        publish_progress(project_id, PreviewStage::Chunks, index + 1, total);
+    }
+
+    publish_progress(project_id, PreviewStage::Concat, total, total);
+    let output = concat_chunks(&chunk_paths, &dir)?;
+    prune_chunks(
+        &chunks_dir,
+        &chunks.iter().map(|chunk| chunk.key.clone()).collect(),
+    );
+
+    let result = PreviewResult {
+        path: output.to_string_lossy().to_string(),
+        duration_us: chunks.last().map_or(0, |chunk| chunk.end_us),
+        timeline_version: timeline.version,
+        timeline_hash: timeline_content_hash(&timeline),
+        chunk_count: total,
+        rendered_chunks,
+        cached_chunks: total - rendered_chunks,
+    };
+    let serialized = serde_json::to_string_pretty(&result)
+        .map_err(|error| format!("Preview manifest serialize error: {error}"))?;
+    fs::write(dir.join(MANIFEST_FILE), format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing preview manifest: {error}"))?;
+    publish_progress(project_id, PreviewStage::Done, total, total);
+    Ok(result)
+}
+
+/// Renders a 480p fast-encode preview of the main video track into
+/// `<project>/preview/`. Each clip is a cached chunk keyed by its source
+/// range, so after an edit only the changed clips are encoded again.
+/// Progress is published as `PREVIEW_PROGRESS_EVENT`.
+#[tauri::command]
+pub async fn build_preview(
+    app: AppHandle,
+    request: BuildPreviewRequest,
+) -> Result<PreviewResult, String> {
+    let permit = scheduler::acquire(
+        &app,
+        scheduler::JobKind::Render,
+        Some(request.priority.unwrap_or(scheduler::Priority::Background)),
+        &request.project_id,
+    )
+    .await?;
+    let rebuild = request.rebuild.unwrap_or(false);
+    let result =
+        logging::spawn_blocking(move || build_preview_blocking(&request.project_id, rebuild))
+            .instrument(permit.span())
+            .await
+            .map_err(|error| format!("Task join error: {error}"))?;
+    drop(permit);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use crate::TimelineClip;
+
+    fn clip(start_us: u64, end_us: u64, source_start_us: u64, source_end_us: u64) -> TimelineClip {
+        let mut clip =
+            test_fixtures::clip(&format!("clip-{start_us}"), "video-main", start_us, end_us);
+        clip.source_start_us = source_start_us;
+        clip.source_end_us = source_end_us;
+        clip
+    }
+
+    #[test]
+    fn chunks_cover_gaps_and_keep_keys_for_unchanged_clips() {
+        let mut timeline = test_fixtures::timeline(
+            30,
+            Vec::new(),
+            vec![
+                clip(0, 2_000_000, 0, 2_000_000),
+                clip(3_000_000, 4_000_000, 5_000_000, 7_000_000),
+            ],
+        );
+        let resolve = |_: &str| Ok::<_, String>(PathBuf::from("/nonexistent/source.mp4"));
+        let chunks = plan_chunks(&timeline, resolve).unwrap();
+        let spans = chunks
+            .iter()
+            .map(|chunk| {
+                (
+                    chunk.start_us,
+                    chunk.end_us,
+                    chunk.source == ChunkSource::Gap,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            [
+                (0, 2_000_000, false),
+                (2_000_000, 3_000_000, true),
+                (3_000_000, 4_000_000, false)
+            ]
+        );
+        assert!(matches!(
+            chunks[2].source,
+            ChunkSource::Clip { speed, .. } if speed == 2.0
+        ));
+
+        timeline.clips[1].source_end_us = 6_000_000;
+        let edited = plan_chunks(&timeline, resolve).unwrap();
+        assert_eq!(edited[0].key, chunks[0].key);
+        assert_ne!(edited[2].key, chunks[2].key);
+    }
+}
@@ -0,0 +1,284 @@
+use std::fs::{self, File};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{
+    generate_project_id, now_iso, path_safety, plugins, preview, publish_project_status,
+    read_projects, write_projects, Project,
+};
+
+/// File extension of a project bundle; registered as a file association.
+pub const BUNDLE_EXTENSION: &str = "aivep";
+const FORMAT_VERSION: u32 = 1;
+const MANIFEST_ENTRY: &str = "manifest.json";
+const PROJECT_ENTRY: &str = "project.json";
+/// Project files live under this prefix inside the bundle.
+const FILES_PREFIX: &str = "files/";
+/// Project subdirectories left out of bundles: backups are per-machine
+/// history, and renders, previews and plugin output can be re-made from the
+/// timeline.
+const SKIPPED_DIRS: &[&str] = &[
+    "backups",
+    "renders",
+    preview::PREVIEW_DIR,
+    plugins::WORK_DIR,
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleManifest {
+    format_version: u32,
+    project_id: String,
+    /// Project directory on the exporting machine, rewritten to the new
+    /// one in the bundle's JSON files on import.
+    source_dir: String,
+    exported_at: String,
+    files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportProjectBundleRequest {
+    project_id: String,
+    /// `.aivep` file to write; the extension is added when missing.
+    output_path: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProjectBundleRequest {
+    path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedBundle {
+    pub path: String,
+    pub files: Vec<String>,
+    pub size_bytes: u64,
+}
+
+pub fn is_bundle(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case(BUNDLE_EXTENSION))
+}
+
+/// Files under `dir`, relative and `/`-separated, skipping [`SKIPPED_DIRS`]
+/// at the top level.
+pub fn project_files(dir: &Path, relative: &str, files: &mut Vec<String>) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|error| format!("Failed reading project dir: {error}"))?;
+    for entry in entries.filter_map(Result::ok) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = format!("{relative}{name}");
+        let file_type = entry
+            .file_type()
+            .map_err(|error| format!("Failed reading project dir: {error}"))?;
+        if file_type.is_dir() {
+            if relative.is_empty() && SKIPPED_DIRS.contains(&name.as_str()) {
+                continue;
+            }
+            project_files(&entry.path(), &format!("{path}/"), files)?;
+        } else if file_type.is_file() && !name.ends_with(".tmp") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// JSON-escaped form of a path, as it appears inside the project's files.
+pub fn json_escaped(path: &Path) -> String {
+    let quoted = serde_json::to_string(&path.to_string_lossy()).unwrap_or_default();
+    quoted.trim_matches('"').to_string()
+}
+
+/// Writes the project's bundle to `output`, which gets the bundle extension
+/// when it lacks one.
+pub fn export_bundle(project_id: &str, output: &Path) -> Result<ExportedBundle, String> {
+    let project = read_projects()?
+        .into_iter()
+        .find(|project| project.id == project_id)
+        .ok_or_else(|| "Project not found.".to_string())?;
+    let source_dir = path_safety::project_dir(&project.id)?;
+    let mut files = Vec::new();
+    if source_dir.is_dir() {
+        project_files(&source_dir, "", &mut files)?;
+    }
+    files.sort();
+
+    let mut output = output.to_path_buf();
+    if !is_bundle(&output) {
+        output.set_extension(BUNDLE_EXTENSION);
+    }
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating bundle dir: {error}"))?;
+    }
+    let write_error = |error: &dyn std::fmt::Display| format!("Failed writing bundle: {error}");
+    let file = File::create(&output).map_err(|error| write_error(&error))?;
+    let mut zip = ZipWriter::new(file);
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    // Media is already compressed; storing it keeps exports fast.
+    let stored = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Stored)
+        .large_file(true);
+
+    let manifest = BundleManifest {
+        format_version: FORMAT_VERSION,
+        project_id: project.id.clone(),
+        source_dir: json_escaped(&source_dir),
+        exported_at: now_iso(),
+        files: files.clone(),
+    };
+    for (name, value) in [
+        (
+            MANIFEST_ENTRY,
+            serde_json::to_vec_pretty(&manifest).map_err(|error| write_error(&error))?,
+        ),
+        (
+            PROJECT_ENTRY,
+            serde_json::to_vec_pretty(&project).map_err(|error| write_error(&error))?,
+        ),
+    ] {
+        zip.start_file(name, deflated)
+            .map_err(|error| write_error(&error))?;
+        io::Write::write_all(&mut zip, &value).map_err(|error| write_error(&error))?;
+    }
+    for relative in &files {
+        let options = if relative.ends_with(".json") || relative.ends_with(".jsonl") {
+            deflated
+        } else {
+            stored
+        };
+        zip.start_file(format!("{FILES_PREFIX}{relative}"), options)
+            .map_err(|error| write_error(&error))?;
+        let mut source = File::open(source_dir.join(relative))
+            .map_err(|error| format!("Failed reading {relative}: {error}"))?;
+        io::copy(&mut source, &mut zip).map_err(|error| write_error(&error))?;
+    }
+    zip.finish().map_err(|error| write_error(&error))?;
+
+    let size_bytes = fs::metadata(&output).map(|meta| meta.len()).unwrap_or(0);
+    tracing::info!(
+        "Exported project {} to {} ({} files)",
+        project.id,
+        output.display(),
+        files.len()
+    );
+    Ok(ExportedBundle {
+        path: output.to_string_lossy().to_string(),
+        files,
+        size_bytes,
+    })
+}
+
+fn read_entry<T: for<'de> Deserialize<'de>>(
+    archive: &mut ZipArchive<File>,
+    name: &str,
+) -> Result<T, String> {
+    let entry = archive
+        .by_name(name)
+        .map_err(|error| format!("Bundle is missing {name}: {error}"))?;
+    serde_json::from_reader(entry).map_err(|error| format!("Invalid {name} in bundle: {error}"))
+}
+
+/// Relative path of a bundle entry inside the project, or None for entries
+/// outside `files/` or that would escape the project directory.
+fn entry_target(name: &str) -> Option<PathBuf> {
+    let relative = Path::new(name.strip_prefix(FILES_PREFIX)?);
+    let safe = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    (safe && !name.ends_with('/')).then(|| relative.to_path_buf())
+}
+
+/// Unpacks a bundle as a new project. It always gets a fresh id, so
+/// importing the same bundle twice gives two independent copies.
+pub fn import_bundle(path: &Path) -> Result<Project, String> {
+    let read_error = |error: &dyn std::fmt::Display| format!("Failed reading bundle: {error}");
+    let file = File::open(path).map_err(|error| read_error(&error))?;
+    let mut archive = ZipArchive::new(file).map_err(|error| read_error(&error))?;
+    let manifest: BundleManifest = read_entry(&mut archive, MANIFEST_ENTRY)?;
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(format!(
+            "This bundle was made by a newer version of the app (format {}).",
+            manifest.format_version
+        ));
+    }
+    if manifest.source_dir.is_empty() {
+        return Err("Bundle manifest has no source directory.".to_string());
+    }
+    let mut project: Project = read_entry(&mut archive, PROJECT_ENTRY)?;
+
+    let old_id = project.id.clone();
+    project.id = generate_project_id();
+    let now = now_iso();
+    project.created_at = now.clone();
+    project.updated_at = now;
+    let target_dir = path_safety::project_dir(&project.id)?;
+    let new_dir = json_escaped(&target_dir);
+
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|error| read_error(&error))?;
+        let Some(relative) = entry_target(entry.name()) else {
+            continue;
+        };
+        let target = target_dir.join(&relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|error| format!("Failed creating project dir: {error}"))?;
+        }
+        if relative
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            // Absolute paths into the old project dir and the old id are
+            // rewritten so the timeline and metadata point at the copy.
+            let raw = io::read_to_string(&mut entry).map_err(|error| read_error(&error))?;
+            let rewritten = raw
+                .replace(&manifest.source_dir, &new_dir)
+                .replace(&format!("\"{old_id}\""), &format!("\"{}\"", project.id));
+            fs::write(&target, rewritten)
+        } else {
+            File::create(&target).and_then(|mut out| io::copy(&mut entry, &mut out).map(|_| ()))
+        }
+        .map_err(|error| format!("Failed extracting {}: {error}", relative.display()))?;
+    }
+
+    let mut projects = read_projects()?;
+    projects.push(project.clone());
+    write_projects(&projects)?;
+    publish_project_status(&project, None);
+    tracing::info!(
+        "Imported bundle {} as project {}",
+        path.display(),
+        project.id
+    );
+    Ok(project)
+}
+
+/// Packs a project's data directory into a single `.aivep` file that can
+/// be opened on another machine.
+#[tauri::command]
+pub async fn export_project_bundle(
+    request: ExportProjectBundleRequest,
+) -> Result<ExportedBundle, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        export_bundle(&request.project_id, Path::new(request.output_path.trim()))
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn import_project_bundle(request: ImportProjectBundleRequest) -> Result<Project, String> {
+    tauri::async_runtime::spawn_blocking(move || import_bundle(Path::new(request.path.trim())))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,292 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::path_safety;
+use crate::validation::FieldError;
+
+/// Optional, hand-edited overrides in the project directory.
+pub const PROJECT_CONFIG_FILE: &str = "project.config.json";
+
+/// Stages `scripts/lib/custom_prompts.mjs` looks prompts up by.
+const PROMPT_STAGES: &[&str] = &[
+    "high_retention_analysis",
+    "cut_plan",
+    "overlay_plan",
+    "template_plan",
+    "stock_suggestions",
+    "chunk_replan",
+];
+/// These decide what ffmpeg reads and writes, which stays the script's call.
+const RESERVED_FFMPEG_ARGS: &[&str] = &["-i", "-y", "-n", "-f"];
+const TEMPERATURE_RANGE: std::ops::RangeInclusive<f64> = 0.0..=2.0;
+const MAX_TOKENS_RANGE: std::ops::RangeInclusive<u32> = 1..=200_000;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelOverrides {
+    pub transcription: Option<String>,
+    pub cut_planner: Option<String>,
+    pub template_planner: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+}
+
+/// `project.config.json`. Every field is optional; what is set wins over the
+/// app settings but not over what a request asks for explicitly.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectConfig {
+    /// Output options added to every ffmpeg call of a render.
+    #[serde(default)]
+    pub ffmpeg_output_args: Vec<String>,
+    /// System prompts by stage, over the app-wide custom prompts.
+    #[serde(default)]
+    pub prompts: BTreeMap<String, String>,
+    #[serde(default)]
+    pub models: ModelOverrides,
+    #[serde(default)]
+    pub model_params: ModelParams,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedProjectConfig {
+    pub exists: bool,
+    pub config: ProjectConfig,
+    /// Dotted paths of keys this version doesn't read, likely typos.
+    pub unknown_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetProjectConfigRequest {
+    project_id: String,
+}
+
+/// Keys of `object` not in `known`, as `prefix.key`.
+fn unknown_keys(value: &Value, prefix: &str, known: &[&str], found: &mut Vec<String>) {
+    let Some(object) = value.as_object() else {
+        return;
+    };
+    for key in object.keys() {
+        if !known.contains(&key.as_str()) {
+            found.push(if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{prefix}.{key}")
+            });
+        }
+    }
+}
+
+fn find_unknown_keys(raw: &Value) -> Vec<String> {
+    let mut found = Vec::new();
+    unknown_keys(
+        raw,
+        "",
+        &["ffmpegOutputArgs", "prompts", "models", "modelParams"],
+        &mut found,
+    );
+    if let Some(prompts) = raw.get("prompts") {
+        unknown_keys(prompts, "prompts", PROMPT_STAGES, &mut found);
+    }
+    if let Some(models) = raw.get("models") {
+        unknown_keys(
+            models,
+            "models",
+            &["transcription", "cutPlanner", "templatePlanner"],
+            &mut found,
+        );
+    }
+    if let Some(params) = raw.get("modelParams") {
+        unknown_keys(
+            params,
+            "modelParams",
+            &["temperature", "maxTokens"],
+            &mut found,
+        );
+    }
+    found
+}
+
+fn field_error(field: &str, message: impl Into<String>) -> FieldError {
+    FieldError {
+        field: field.to_string(),
+        message: message.into(),
+    }
+}
+
+impl ProjectConfig {
+    pub fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        for (index, arg) in self.ffmpeg_output_args.iter().enumerate() {
+            if RESERVED_FFMPEG_ARGS.contains(&arg.trim()) {
+                errors.push(field_error(
+                    &format!("ffmpegOutputArgs[{index}]"),
+                    format!("{arg} is set by the render script"),
+                ));
+            }
+        }
+        for (stage, prompt) in &self.prompts {
+            if prompt.trim().is_empty() {
+                errors.push(field_error(
+                    &format!("prompts.{stage}"),
+                    "must not be empty",
+                ));
+            }
+        }
+        if let Some(temperature) = self.model_params.temperature {
+            if !TEMPERATURE_RANGE.contains(&temperature) {
+                errors.push(field_error(
+                    "modelParams.temperature",
+                    format!(
+                        "must be between {} and {}",
+                        TEMPERATURE_RANGE.start(),
+                        TEMPERATURE_RANGE.end()
+                    ),
+                ));
+            }
+        }
+        if let Some(max_tokens) = self.model_params.max_tokens {
+            if !MAX_TOKENS_RANGE.contains(&max_tokens) {
+                errors.push(field_error(
+                    "modelParams.maxTokens",
+                    format!(
+                        "must be between {} and {}",
+                        MAX_TOKENS_RANGE.start(),
+                        MAX_TOKENS_RANGE.end()
+                    ),
+                ));
+            }
+        }
+        errors
+    }
+
+    /// The overrides as flags the pipeline scripts understand. Scripts
+    /// ignore flags they don't use, so every script gets all of them.
+    pub fn script_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if !self.ffmpeg_output_args.is_empty() {
+            args.push("--ffmpeg-output-args".to_string());
+            args.push(serde_json::to_string(&self.ffmpeg_output_args).unwrap_or_default());
+        }
+        let prompts = self
+            .prompts
+            .iter()
+            .filter(|(stage, _)| PROMPT_STAGES.contains(&stage.as_str()))
+            .collect::<BTreeMap<_, _>>();
+        if !prompts.is_empty() {
+            args.push("--prompt-overrides".to_string());
+            args.push(serde_json::to_string(&prompts).unwrap_or_default());
+        }
+        let params = &self.model_params;
+        if params.temperature.is_some() || params.max_tokens.is_some() {
+            args.push("--llm-params".to_string());
+            args.push(serde_json::to_string(params).unwrap_or_default());
+        }
+        args
+    }
+}
+
+/// Parses `raw`, rejecting invalid values and listing keys it doesn't know.
+pub fn parse_project_config(raw: &str) -> Result<(ProjectConfig, Vec<String>), String> {
+    let value = serde_json::from_str::<Value>(raw)
+        .map_err(|error| format!("Invalid {PROJECT_CONFIG_FILE}: {error}"))?;
+    let unknown = find_unknown_keys(&value);
+    let config = serde_json::from_value::<ProjectConfig>(value)
+        .map_err(|error| format!("Invalid {PROJECT_CONFIG_FILE}: {error}"))?;
+    let errors = config.validate();
+    if !errors.is_empty() {
+        let details = errors
+            .iter()
+            .map(|error| format!("{}: {}", error.field, error.message))
+            .collect::<Vec<_>>()
+            .join("; ");
+        return Err(format!("Invalid {PROJECT_CONFIG_FILE}: {details}"));
+    }
+    Ok((config, unknown))
+}
+
+pub fn load_project_config(project_id: &str) -> Result<LoadedProjectConfig, String> {
+    let path = path_safety::project_dir(project_id)?.join(PROJECT_CONFIG_FILE);
+    if !path.is_file() {
+        return Ok(LoadedProjectConfig {
+            exists: false,
+            config: ProjectConfig::default(),
+            unknown_keys: Vec::new(),
+        });
+    }
+    let raw = fs::read_to_string(&path)
+        .map_err(|error| format!("Failed reading {PROJECT_CONFIG_FILE}: {error}"))?;
+    let (config, unknown_keys) = parse_project_config(&raw)?;
+    Ok(LoadedProjectConfig {
+        exists: true,
+        config,
+        unknown_keys,
+    })
+}
+
+/// The project's config for a script run, warning about unknown keys. An
+/// invalid file fails the run rather than being silently ignored.
+pub fn for_run(project_id: &str) -> Result<ProjectConfig, String> {
+    let loaded = load_project_config(project_id)?;
+    if !loaded.unknown_keys.is_empty() {
+        tracing::warn!(
+            "Ignoring unknown keys in {PROJECT_CONFIG_FILE}: {}",
+            loaded.unknown_keys.join(", ")
+        );
+    }
+    Ok(loaded.config)
+}
+
+/// The parsed overrides and any unknown keys, for a settings screen to show.
+#[tauri::command]
+pub async fn get_project_config(
+    request: GetProjectConfigRequest,
+) -> Result<LoadedProjectConfig, String> {
+    tauri::async_runtime::spawn_blocking(move || load_project_config(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_reports_unknown_keys_and_rejects_bad_values() {
+        let (config, unknown) = parse_project_config(
+            r#"{
+                "ffmpegOutputArgs": ["-threads", "4"],
+                "prompts": { "cut_plan": "Cut harder.", "cutplan": "typo" },
+                "models": { "cutPlanner": "llama3" },
+                "modelParams": { "temperature": 0.1 },
+                "render": {}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(unknown, ["render", "prompts.cutplan"]);
+        assert_eq!(config.models.cut_planner.as_deref(), Some("llama3"));
+        let args = config.script_args();
+        assert_eq!(args[0], "--ffmpeg-output-args");
+        assert_eq!(args[3], r#"{"cut_plan":"Cut harder."}"#);
+        assert_eq!(args[5], r#"{"temperature":0.1}"#);
+
+        let error = parse_project_config(
+            r#"{ "ffmpegOutputArgs": ["-y"], "modelParams": { "temperature": 5 } }"#,
+        )
+        .unwrap_err();
+        assert!(error.contains("ffmpegOutputArgs[0]"));
+        assert!(error.contains("modelParams.temperature"));
+    }
+}
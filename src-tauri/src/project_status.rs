@@ -0,0 +1,376 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::event_bus::{self, PROJECT_STATUS_EVENT};
+
+/// Where a project is in the pipeline. Stored as its SCREAMING_SNAKE_CASE
+/// name in `projects.json`, which the Node backend writes too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ProjectStatus {
+    ProjectCreated,
+    SettingsSaved,
+    Transcribing,
+    TranscriptReady,
+    PlanningCuts,
+    CutsReady,
+    RoughCutReady,
+    EnrichedTimelineReady,
+    AgenticEditInProgress,
+    AgenticEditDone,
+    RenderInProgress,
+    RenderDone,
+    RenderFailed,
+}
+
+const ALL: &[ProjectStatus] = &[
+    ProjectStatus::ProjectCreated,
+    ProjectStatus::SettingsSaved,
+    ProjectStatus::Transcribing,
+    ProjectStatus::TranscriptReady,
+    ProjectStatus::PlanningCuts,
+    ProjectStatus::CutsReady,
+    ProjectStatus::RoughCutReady,
+    ProjectStatus::EnrichedTimelineReady,
+    ProjectStatus::AgenticEditInProgress,
+    ProjectStatus::AgenticEditDone,
+    ProjectStatus::RenderInProgress,
+    ProjectStatus::RenderDone,
+    ProjectStatus::RenderFailed,
+];
+
+impl ProjectStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ProjectCreated => "PROJECT_CREATED",
+            Self::SettingsSaved => "SETTINGS_SAVED",
+            Self::Transcribing => "TRANSCRIBING",
+            Self::TranscriptReady => "TRANSCRIPT_READY",
+            Self::PlanningCuts => "PLANNING_CUTS",
+            Self::CutsReady => "CUTS_READY",
+            Self::RoughCutReady => "ROUGH_CUT_READY",
+            Self::EnrichedTimelineReady => "ENRICHED_TIMELINE_READY",
+            Self::AgenticEditInProgress => "AGENTIC_EDIT_IN_PROGRESS",
+            Self::AgenticEditDone => "AGENTIC_EDIT_DONE",
+            Self::RenderInProgress => "RENDER_IN_PROGRESS",
+            Self::RenderDone => "RENDER_DONE",
+            Self::RenderFailed => "RENDER_FAILED",
+        }
+    }
+
+    /// None for statuses this build doesn't know, e.g. ones written by an
+    /// older script.
+    pub fn parse(value: &str) -> Option<Self> {
+        ALL.iter().copied().find(|status| status.as_str() == value)
+    }
+
+    /// A step is running; nothing else may start until it finishes.
+    fn is_running(self) -> bool {
+        matches!(
+            self,
+            Self::Transcribing
+                | Self::PlanningCuts
+                | Self::AgenticEditInProgress
+                | Self::RenderInProgress
+        )
+    }
+
+    /// Statuses reached only after a timeline has been written.
+    pub fn has_timeline(self) -> bool {
+        matches!(
+            self,
+            Self::RoughCutReady
+                | Self::EnrichedTimelineReady
+                | Self::AgenticEditDone
+                | Self::RenderInProgress
+                | Self::RenderDone
+                | Self::RenderFailed
+        )
+    }
+
+    /// Statuses that work on the timeline, so entering them needs one on
+    /// disk whatever the previous status was. A failed render doesn't, so
+    /// the failure is recorded even if the timeline went missing.
+    pub fn needs_timeline(self) -> bool {
+        matches!(
+            self,
+            Self::EnrichedTimelineReady | Self::RenderInProgress | Self::RenderDone
+        )
+    }
+
+    /// Statuses a project may move to `self` from. A finished step only
+    /// follows its own in-progress status, and a step only starts once its
+    /// input is ready and nothing else is running.
+    fn predecessors(self) -> &'static [Self] {
+        use ProjectStatus::*;
+        match self {
+            // Only ever the first status.
+            ProjectCreated => &[],
+            SettingsSaved => &[ProjectCreated],
+            // A transcript can be redone until a timeline is built on it.
+            Transcribing => &[ProjectCreated, SettingsSaved, TranscriptReady, CutsReady],
+            TranscriptReady => &[Transcribing],
+            PlanningCuts => &[
+                TranscriptReady,
+                CutsReady,
+                RoughCutReady,
+                EnrichedTimelineReady,
+                AgenticEditDone,
+                RenderDone,
+                RenderFailed,
+            ],
+            CutsReady => &[PlanningCuts],
+            // Built from a cut plan, or straight from the transcript by the
+            // text and silence editors, or a re-cut of an existing timeline.
+            RoughCutReady => &[
+                TranscriptReady,
+                CutsReady,
+                EnrichedTimelineReady,
+                AgenticEditDone,
+                RenderDone,
+                RenderFailed,
+            ],
+            EnrichedTimelineReady => &[RoughCutReady, AgenticEditDone, RenderDone, RenderFailed],
+            AgenticEditInProgress => &[
+                RoughCutReady,
+                EnrichedTimelineReady,
+                AgenticEditDone,
+                RenderDone,
+                RenderFailed,
+            ],
+            AgenticEditDone => &[AgenticEditInProgress],
+            RenderInProgress => &[
+                RoughCutReady,
+                EnrichedTimelineReady,
+                AgenticEditDone,
+                RenderDone,
+                RenderFailed,
+            ],
+            RenderDone | RenderFailed => &[RenderInProgress],
+        }
+    }
+
+    /// Whether a project may move from `self` to `next`. Timeline
+    /// requirements are checked separately against the file.
+    pub fn can_become(self, next: Self) -> bool {
+        self == next || next.predecessors().contains(&self)
+    }
+
+    /// Whether a whole new timeline (an import, a one-shot edit or a
+    /// generated project) may land as `next`. That skips the steps, so it is
+    /// allowed from anywhere except while a step is running.
+    pub fn can_take_timeline(self, next: Self) -> bool {
+        matches!(next, Self::RoughCutReady | Self::EnrichedTimelineReady)
+            && (self == next || !self.is_running())
+    }
+}
+
+impl fmt::Display for ProjectStatus {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+/// Why a status change was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatusError {
+    ProjectNotFound,
+    InvalidTransition {
+        from: ProjectStatus,
+        to: ProjectStatus,
+    },
+    MissingTimeline(ProjectStatus),
+    /// Reading or writing the project index failed.
+    Store(String),
+}
+
+impl fmt::Display for StatusError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ProjectNotFound => formatter.write_str("Project not found."),
+            Self::InvalidTransition { from, to } => {
+                write!(formatter, "Invalid status transition: {from} -> {to}")
+            }
+            Self::MissingTimeline(to) => {
+                write!(formatter, "Project can't become {to} without a timeline.")
+            }
+            Self::Store(error) => formatter.write_str(error),
+        }
+    }
+}
+
+impl From<String> for StatusError {
+    fn from(error: String) -> Self {
+        Self::Store(error)
+    }
+}
+
+impl From<StatusError> for String {
+    fn from(error: StatusError) -> Self {
+        error.to_string()
+    }
+}
+
+/// How a status change came about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    /// A pipeline step started or finished.
+    Step,
+    /// A whole timeline was written in one go; see `can_take_timeline`.
+    TimelineWritten,
+}
+
+/// Checks a change from the stored status string. Unknown stored statuses
+/// allow any change so a project can't get stuck on one.
+pub fn check_transition(
+    current: &str,
+    next: ProjectStatus,
+    transition: Transition,
+    timeline_exists: bool,
+) -> Result<(), StatusError> {
+    if let Some(from) = ProjectStatus::parse(current) {
+        let allowed = match transition {
+            Transition::Step => from.can_become(next),
+            Transition::TimelineWritten => from.can_take_timeline(next),
+        };
+        if !allowed {
+            return Err(StatusError::InvalidTransition { from, to: next });
+        }
+    }
+    if next.needs_timeline() && !timeline_exists {
+        return Err(StatusError::MissingTimeline(next));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetProjectStatusHistoryRequest {
+    project_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusChange {
+    pub status: String,
+    pub previous: Option<String>,
+    pub at: String,
+}
+
+/// Status changes recorded in the project's event journal, oldest first.
+#[tauri::command]
+pub async fn get_project_status_history(
+    request: GetProjectStatusHistoryRequest,
+) -> Result<Vec<StatusChange>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        Ok(event_bus::journal(&request.project_id)?
+            .into_iter()
+            .filter(|entry| entry.event == PROJECT_STATUS_EVENT)
+            .map(|entry| StatusChange {
+                status: entry.payload["status"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                previous: entry.payload["previous"].as_str().map(str::to_string),
+                at: entry.at,
+            })
+            .collect())
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// Payload of a status event.
+pub fn status_event(status: &str, previous: Option<&str>, updated_at: &str) -> Value {
+    serde_json::json!({ "status": status, "previous": previous, "updatedAt": updated_at })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transitions_follow_the_predecessor_table() {
+        use ProjectStatus::*;
+        let accepted = [
+            (ProjectCreated, SettingsSaved),
+            (SettingsSaved, Transcribing),
+            (Transcribing, TranscriptReady),
+            (TranscriptReady, PlanningCuts),
+            (PlanningCuts, CutsReady),
+            (CutsReady, RoughCutReady),
+            (TranscriptReady, RoughCutReady),
+            (RoughCutReady, EnrichedTimelineReady),
+            (EnrichedTimelineReady, AgenticEditInProgress),
+            (AgenticEditInProgress, AgenticEditDone),
+            (AgenticEditDone, RenderInProgress),
+            (RenderInProgress, RenderDone),
+            (RenderInProgress, RenderFailed),
+            (RenderFailed, RenderInProgress),
+            (RenderDone, PlanningCuts),
+            (Transcribing, Transcribing),
+        ];
+        let rejected = [
+            (RenderDone, ProjectCreated),
+            (RoughCutReady, SettingsSaved),
+            (RenderDone, Transcribing),
+            (ProjectCreated, RoughCutReady),
+            (ProjectCreated, RenderDone),
+            (SettingsSaved, TranscriptReady),
+            (Transcribing, RenderDone),
+            (PlanningCuts, TranscriptReady),
+            (Transcribing, PlanningCuts),
+            (RenderInProgress, AgenticEditInProgress),
+            (AgenticEditInProgress, RenderDone),
+            (CutsReady, RenderInProgress),
+            (TranscriptReady, EnrichedTimelineReady),
+        ];
+        for (from, to) in accepted {
+            assert!(from.can_become(to), "{from} -> {to} should be allowed");
+        }
+        for (from, to) in rejected {
+            assert!(!from.can_become(to), "{from} -> {to} should be refused");
+        }
+
+        assert!(ProjectCreated.can_take_timeline(RoughCutReady));
+        assert!(RenderDone.can_take_timeline(EnrichedTimelineReady));
+        assert!(!RenderInProgress.can_take_timeline(RoughCutReady));
+        assert!(!ProjectCreated.can_take_timeline(RenderDone));
+    }
+
+    #[test]
+    fn timeline_statuses_need_the_file() {
+        assert_eq!(
+            check_transition(
+                "RENDER_DONE",
+                ProjectStatus::RenderInProgress,
+                Transition::Step,
+                false
+            ),
+            Err(StatusError::MissingTimeline(
+                ProjectStatus::RenderInProgress
+            ))
+        );
+        assert!(check_transition(
+            "RENDER_DONE",
+            ProjectStatus::RenderInProgress,
+            Transition::Step,
+            true
+        )
+        .is_ok());
+        // Statuses written by other tools don't block anything.
+        assert!(check_transition(
+            "ROUGH_CUT_PLAN_READY",
+            ProjectStatus::CutsReady,
+            Transition::Step,
+            false
+        )
+        .is_ok());
+        assert_eq!(
+            ProjectStatus::parse("ENRICHED_TIMELINE_READY"),
+            Some(ProjectStatus::EnrichedTimelineReady)
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::path_safety;
+use crate::settings::{read_app_settings, RenderCacheSettings};
+
+const CACHE_DIR: &str = "segment-cache";
+const INDEX_FILE: &str = "index.json";
+
+/// Serializes index updates across renders and commands.
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderCacheRequest {
+    project_id: String,
+}
+
+/// One cached segment, `<key>.mp4`. The render script derives the key from
+/// everything that shapes the segment: source file, in/out points, speed,
+/// crop, LUT, censors, audio replacement, seam handling and encoder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+    pub bytes: u64,
+    /// Unix seconds.
+    pub created_at: u64,
+    pub last_used_at: u64,
+    pub hits: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CacheIndex {
+    pub entries: BTreeMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderCacheStats {
+    pub enabled: bool,
+    pub path: String,
+    pub entry_count: usize,
+    pub total_bytes: u64,
+    pub max_bytes: u64,
+    /// Segments removed by this call.
+    pub evicted: usize,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn lock_index() -> std::sync::MutexGuard<'static, ()> {
+    INDEX_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub fn cache_dir(project_id: &str) -> Result<PathBuf, String> {
+    Ok(path_safety::project_dir(project_id)?
+        .join("renders")
+        .join(CACHE_DIR))
+}
+
+fn segment_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{key}.mp4"))
+}
+
+fn is_cache_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn read_index(dir: &Path) -> Result<CacheIndex, String> {
+    let path = dir.join(INDEX_FILE);
+    if !path.exists() {
+        return Ok(CacheIndex::default());
+    }
+    let raw = fs::read_to_string(&path)
+        .map_err(|error| format!("Failed reading render cache index: {error}"))?;
+    Ok(serde_json::from_str(&raw).unwrap_or_else(|error| {
+        tracing::warn!("Rebuilding invalid render cache index: {error}");
+        CacheIndex::default()
+    }))
+}
+
+fn write_index(dir: &Path, index: &CacheIndex) -> Result<(), String> {
+    let serialized = serde_json::to_string_pretty(index)
+        .map_err(|error| format!("Render cache index serialize error: {error}"))?;
+    fs::write(dir.join(INDEX_FILE), format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing render cache index: {error}"))
+}
+
+/// Drops entries whose file is gone and indexes segment files the index
+/// doesn't know about, e.g. after a render crashed before recording.
+fn reconcile(dir: &Path, index: &mut CacheIndex, now: u64) {
+    index
+        .entries
+        .retain(|key, _| segment_path(dir, key).is_file());
+    let Ok(files) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in files.filter_map(Result::ok).map(|entry| entry.path()) {
+        let key = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".mp4"))
+            .filter(|key| is_cache_key(key))
+            .map(str::to_string);
+        let Some(key) = key else {
+            continue;
+        };
+        index.entries.entry(key).or_insert_with(|| CacheEntry {
+            bytes: fs::metadata(&path).map_or(0, |metadata| metadata.len()),
+            created_at: now,
+            last_used_at: now,
+            hits: 0,
+        });
+    }
+}
+
+/// Keys to evict, least recently used first, until the cache fits in
+/// `max_bytes`. `keep` (the segments of the render that just finished) is
+/// only evicted when it alone is over the limit.
+pub fn eviction_order(index: &CacheIndex, max_bytes: u64, keep: &[String]) -> Vec<String> {
+    let mut total = index.entries.values().map(|entry| entry.bytes).sum::<u64>();
+    let mut candidates = index.entries.iter().collect::<Vec<_>>();
+    candidates.sort_by_key(|(key, entry)| (keep.contains(key), entry.last_used_at));
+    let mut evicted = Vec::new();
+    for (key, entry) in candidates {
+        if total <= max_bytes {
+            break;
+        }
+        total -= entry.bytes;
+        evicted.push(key.clone());
+    }
+    evicted
+}
+
+fn evict(dir: &Path, index: &mut CacheIndex, max_bytes: u64, keep: &[String]) -> usize {
+    let evicted = eviction_order(index, max_bytes, keep);
+    for key in &evicted {
+        if let Err(error) = fs::remove_file(segment_path(dir, key)) {
+            tracing::warn!("Failed evicting cached segment {key}: {error}");
+        }
+        index.entries.remove(key);
+    }
+    evicted.len()
+}
+
+fn stats(
+    dir: &Path,
+    index: &CacheIndex,
+    settings: &RenderCacheSettings,
+    evicted: usize,
+) -> RenderCacheStats {
+    RenderCacheStats {
+        enabled: settings.enabled,
+        path: dir.to_string_lossy().to_string(),
+        entry_count: index.entries.len(),
+        total_bytes: index.entries.values().map(|entry| entry.bytes).sum(),
+        max_bytes: settings.max_size_mb.saturating_mul(1024 * 1024),
+        evicted,
+    }
+}
+
+/// `--segment-cache <dir>` for the render script, unless caching is off.
+pub fn render_args(project_id: &str, enabled: bool) -> Result<Vec<String>, String> {
+    if !enabled || !read_app_settings()?.render_cache.enabled {
+        return Ok(Vec::new());
+    }
+    let dir = cache_dir(project_id)?;
+    fs::create_dir_all(&dir).map_err(|error| format!("Failed creating render cache: {error}"))?;
+    Ok(vec![
+        "--segment-cache".to_string(),
+        dir.to_string_lossy().to_string(),
+    ])
+}
+
+fn result_keys(result: &Value, field: &str) -> Vec<String> {
+    result
+        .get("segmentCache")
+        .and_then(|cache| cache.get(field))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .filter(|key| is_cache_key(key))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Records the segments a render reused and stored (`segmentCache` in the
+/// script's result), then evicts down to the configured size.
+pub fn record_render(project_id: &str, result: &Value) -> Result<RenderCacheStats, String> {
+    let settings = read_app_settings()?.render_cache;
+    let dir = cache_dir(project_id)?;
+    let _index = lock_index();
+    let mut index = read_index(&dir)?;
+    let now = unix_now();
+    let hits = result_keys(result, "hits");
+    let stored = result_keys(result, "stored");
+    for key in &stored {
+        let bytes = fs::metadata(segment_path(&dir, key)).map_or(0, |metadata| metadata.len());
+        index.entries.insert(
+            key.clone(),
+            CacheEntry {
+                bytes,
+                created_at: now,
+                last_used_at: now,
+                hits: 0,
+            },
+        );
+    }
+    for key in &hits {
+        if let Some(entry) = index.entries.get_mut(key) {
+            entry.last_used_at = now;
+            entry.hits += 1;
+        }
+    }
+    reconcile(&dir, &mut index, now);
+    let used = hits.into_iter().chain(stored).collect::<Vec<_>>();
+    let evicted = evict(
+        &dir,
+        &mut index,
+        settings.max_size_mb.saturating_mul(1024 * 1024),
+        &used,
+    );
+    write_index(&dir, &index)?;
+    Ok(stats(&dir, &index, &settings, evicted))
+}
+
+fn get_render_cache_blocking(project_id: &str) -> Result<RenderCacheStats, String> {
+    let settings = read_app_settings()?.render_cache;
+    let dir = cache_dir(project_id)?;
+    let _index = lock_index();
+    let mut index = read_index(&dir)?;
+    if dir.is_dir() {
+        reconcile(&dir, &mut index, unix_now());
+        write_index(&dir, &index)?;
+    }
+    Ok(stats(&dir, &index, &settings, 0))
+}
+
+fn clear_render_cache_blocking(project_id: &str) -> Result<RenderCacheStats, String> {
+    let settings = read_app_settings()?.render_cache;
+    let dir = cache_dir(project_id)?;
+    let _index = lock_index();
+    let evicted = read_index(&dir)?.entries.len();
+    if dir.is_dir() {
+        fs::remove_dir_all(&dir)
+            .map_err(|error| format!("Failed clearing render cache: {error}"))?;
+    }
+    Ok(stats(&dir, &CacheIndex::default(), &settings, evicted))
+}
+
+#[tauri::command]
+pub async fn get_render_cache(request: RenderCacheRequest) -> Result<RenderCacheStats, String> {
+    tauri::async_runtime::spawn_blocking(move || get_render_cache_blocking(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// Deletes every cached segment of the project; the next render encodes
+/// all clips again.
+#[tauri::command]
+pub async fn clear_render_cache(request: RenderCacheRequest) -> Result<RenderCacheStats, String> {
+    tauri::async_runtime::spawn_blocking(move || clear_render_cache_blocking(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(bytes: u64, last_used_at: u64) -> CacheEntry {
+        CacheEntry {
+            bytes,
+            created_at: 0,
+            last_used_at,
+            hits: 0,
+        }
+    }
+
+    #[test]
+    fn evicts_least_recently_used_but_spares_the_last_render() {
+        let index = CacheIndex {
+            entries: BTreeMap::from([
+                ("aa".to_string(), entry(40, 10)),
+                ("bb".to_string(), entry(40, 30)),
+                ("cc".to_string(), entry(40, 20)),
+                ("dd".to_string(), entry(40, 5)),
+            ]),
+        };
+        assert!(eviction_order(&index, 160, &[]).is_empty());
+        assert_eq!(eviction_order(&index, 100, &[]), ["dd", "aa"]);
+        assert_eq!(
+            eviction_order(&index, 100, &["dd".to_string()]),
+            ["aa", "cc"]
+        );
+        assert_eq!(eviction_order(&index, 0, &[]).len(), 4);
+    }
+}
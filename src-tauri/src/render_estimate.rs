@@ -0,0 +1,313 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::export_profiles::{resolve_profile, ExportProfileSpec};
+use crate::hw_encoders::{hw_encoders, resolve_encoder, SOFTWARE_ENCODER};
+use crate::media::{probe_video_dimensions, resolve_source_path};
+use crate::render_history::read_render_history;
+use crate::timeline_stats::content_end_us;
+use crate::{read_timeline, Timeline};
+
+const QUALITY_PRESETS: &[&str] = &["draft", "balanced", "quality"];
+/// Frame assumed when no source can be probed.
+const FALLBACK_FRAME: (u32, u32) = (1920, 1080);
+/// Bounds never get narrower than this around the expected value.
+const MIN_SPREAD: f64 = 0.15;
+/// Spread of the built-in figures used before any render is recorded.
+const DEFAULT_SPREAD: f64 = 0.5;
+/// Hardware encoders take roughly this share of libx264's time.
+const HARDWARE_TIME_FACTOR: f64 = 0.4;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimateRenderRequest {
+    project_id: String,
+    /// Quality preset, as for `render_video`. Defaults to `balanced`.
+    preset: Option<String>,
+    /// As for `render_video`; defaults to `auto`.
+    encoder: Option<String>,
+    /// Frame size of a profile render; defaults to the source frame.
+    profile: Option<ExportProfileSpec>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EstimateBasis {
+    /// Past renders with the same preset and encoder.
+    History,
+    /// Past renders with other settings, adjusted for them.
+    SimilarHistory,
+    /// Built-in figures; no usable past renders yet.
+    Defaults,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bounds {
+    pub low: u64,
+    pub expected: u64,
+    pub high: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderEstimate {
+    pub timeline_duration_us: u64,
+    pub width: u32,
+    pub height: u32,
+    pub preset: String,
+    /// `auto`, `libx264` or a hardware encoder name.
+    pub encoder: String,
+    pub render_ms: Bounds,
+    pub output_bytes: Bounds,
+    pub basis: EstimateBasis,
+    pub sample_count: usize,
+}
+
+/// Throughput of one finished render, per second of output and megapixel
+/// of frame.
+#[derive(Debug, Clone, PartialEq)]
+struct RenderSample {
+    preset: String,
+    encoder: String,
+    ms_per_second_mp: f64,
+    bytes_per_second_mp: f64,
+}
+
+fn is_hardware(encoder: &str) -> bool {
+    encoder != SOFTWARE_ENCODER
+}
+
+/// Built-in libx264 figures at each preset: render milliseconds and output
+/// bytes per second of video per megapixel.
+fn default_rates(preset: &str) -> (f64, f64) {
+    match preset {
+        "draft" => (150.0, 120_000.0),
+        "quality" => (600.0, 600_000.0),
+        _ => (300.0, 300_000.0),
+    }
+}
+
+/// Samples from `renders/history.json`. Only renders that recorded their
+/// output size and stage timings count.
+fn history_samples(history: &[Value]) -> Vec<RenderSample> {
+    history
+        .iter()
+        .filter(|entry| entry.get("status").and_then(Value::as_str) == Some("RENDER_DONE"))
+        .filter_map(|entry| {
+            let output = entry.get("output")?;
+            let seconds = output.get("durationUs")?.as_f64()? / 1_000_000.0;
+            let megapixels =
+                output.get("width")?.as_f64()? * output.get("height")?.as_f64()? / 1_000_000.0;
+            let bytes = output.get("bytes")?.as_f64()?;
+            let render_ms = entry
+                .get("stageDurationsMs")?
+                .as_object()?
+                .values()
+                .filter_map(Value::as_f64)
+                .sum::<f64>();
+            if seconds <= 0.0 || megapixels <= 0.0 || render_ms <= 0.0 {
+                return None;
+            }
+            let encoder = entry.get("encoder");
+            let fell_back = encoder
+                .and_then(|encoder| encoder.get("fallbacks"))
+                .and_then(Value::as_array)
+                .is_some_and(|fallbacks| !fallbacks.is_empty());
+            let used = encoder
+                .and_then(|encoder| encoder.get("used"))
+                .and_then(Value::as_str)
+                .unwrap_or("auto");
+            Some(RenderSample {
+                preset: entry
+                    .get("quality")
+                    .and_then(Value::as_str)
+                    .unwrap_or("balanced")
+                    .to_string(),
+                encoder: if fell_back { SOFTWARE_ENCODER } else { used }.to_string(),
+                ms_per_second_mp: render_ms / seconds / megapixels,
+                bytes_per_second_mp: bytes / seconds / megapixels,
+            })
+        })
+        .collect()
+}
+
+/// Rates of `sample` moved to `preset` and `encoder` using the ratios of
+/// the built-in figures.
+fn adjusted_rates(sample: &RenderSample, preset: &str, encoder: &str) -> (f64, f64) {
+    let (sample_ms, sample_bytes) = default_rates(&sample.preset);
+    let (target_ms, target_bytes) = default_rates(preset);
+    let mut ms = sample.ms_per_second_mp * target_ms / sample_ms;
+    match (is_hardware(&sample.encoder), is_hardware(encoder)) {
+        (false, true) => ms *= HARDWARE_TIME_FACTOR,
+        (true, false) => ms /= HARDWARE_TIME_FACTOR,
+        _ => {}
+    }
+    (ms, sample.bytes_per_second_mp * target_bytes / sample_bytes)
+}
+
+fn bounds(values: &[f64], spread: f64) -> Bounds {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let expected = sorted[sorted.len() / 2];
+    let low = sorted[0].min(expected * (1.0 - spread));
+    let high = sorted[sorted.len() - 1].max(expected * (1.0 + spread));
+    Bounds {
+        low: low.max(0.0).round() as u64,
+        expected: expected.round() as u64,
+        high: high.round() as u64,
+    }
+}
+
+/// Predicts render time and file size for `duration_us` of output at
+/// `frame`. Matching past renders are used when there are any, then other
+/// past renders adjusted for preset and encoder, then built-in figures.
+fn estimate(
+    samples: &[RenderSample],
+    duration_us: u64,
+    frame: (u32, u32),
+    preset: &str,
+    encoder: &str,
+) -> (Bounds, Bounds, EstimateBasis, usize) {
+    let scale =
+        duration_us as f64 / 1_000_000.0 * f64::from(frame.0) * f64::from(frame.1) / 1_000_000.0;
+    let matching = samples
+        .iter()
+        .filter(|sample| sample.preset == preset && sample.encoder == encoder)
+        .map(|sample| (sample.ms_per_second_mp, sample.bytes_per_second_mp))
+        .collect::<Vec<_>>();
+    let (rates, basis, spread) = if !matching.is_empty() {
+        (matching, EstimateBasis::History, MIN_SPREAD)
+    } else if !samples.is_empty() {
+        let adjusted = samples
+            .iter()
+            .map(|sample| adjusted_rates(sample, preset, encoder))
+            .collect();
+        (adjusted, EstimateBasis::SimilarHistory, MIN_SPREAD * 2.0)
+    } else {
+        let (mut ms, bytes) = default_rates(preset);
+        if is_hardware(encoder) {
+            ms *= HARDWARE_TIME_FACTOR;
+        }
+        (vec![(ms, bytes)], EstimateBasis::Defaults, DEFAULT_SPREAD)
+    };
+    let count = if basis == EstimateBasis::Defaults {
+        0
+    } else {
+        rates.len()
+    };
+    let times = rates.iter().map(|(ms, _)| ms * scale).collect::<Vec<_>>();
+    let sizes = rates
+        .iter()
+        .map(|(_, bytes)| bytes * scale)
+        .collect::<Vec<_>>();
+    (bounds(&times, spread), bounds(&sizes, spread), basis, count)
+}
+
+/// The encoder the render would use: `auto` picks hardware when a working
+/// hardware encoder was detected.
+fn effective_encoder(requested: Option<&str>) -> Result<String, String> {
+    if let Some(name) = resolve_encoder(requested.unwrap_or("auto"))? {
+        return Ok(name);
+    }
+    let hardware = hw_encoders(false)
+        .map(|encoders| encoders.iter().any(|encoder| encoder.working))
+        .unwrap_or(false);
+    Ok(if hardware { "auto" } else { SOFTWARE_ENCODER }.to_string())
+}
+
+/// Frame of the first source clip, which plain renders keep.
+fn source_frame(project_id: &str, timeline: &Timeline) -> (u32, u32) {
+    timeline
+        .clips
+        .iter()
+        .filter(|clip| clip.clip_type == "source_clip")
+        .min_by_key(|clip| clip.start_us)
+        .and_then(|clip| resolve_source_path(project_id, Some(&clip.source_ref)).ok())
+        .and_then(|path| probe_video_dimensions(&path))
+        .unwrap_or(FALLBACK_FRAME)
+}
+
+fn estimate_render_blocking(request: EstimateRenderRequest) -> Result<RenderEstimate, String> {
+    let preset = request
+        .preset
+        .as_deref()
+        .map(|preset| preset.trim().to_ascii_lowercase())
+        .unwrap_or_else(|| "balanced".to_string());
+    if !QUALITY_PRESETS.contains(&preset.as_str()) {
+        return Err(format!(
+            "Unknown preset: {preset}. Use {}.",
+            QUALITY_PRESETS.join(", ")
+        ));
+    }
+    let encoder = effective_encoder(request.encoder.as_deref())?;
+    let timeline = read_timeline(&request.project_id)?;
+    let (width, height) = match request.profile {
+        Some(spec) => {
+            let profile = resolve_profile(spec)?;
+            (profile.width, profile.height)
+        }
+        None => source_frame(&request.project_id, &timeline),
+    };
+    let duration_us = content_end_us(&timeline);
+    let samples = history_samples(&read_render_history(&request.project_id)?);
+    let (render_ms, output_bytes, basis, sample_count) =
+        estimate(&samples, duration_us, (width, height), &preset, &encoder);
+    Ok(RenderEstimate {
+        timeline_duration_us: duration_us,
+        width,
+        height,
+        preset,
+        encoder,
+        render_ms,
+        output_bytes,
+        basis,
+        sample_count,
+    })
+}
+
+/// Predicts how long a render takes and how large the file will be, with
+/// bounds, from the project's past renders.
+#[tauri::command]
+pub async fn estimate_render(request: EstimateRenderRequest) -> Result<RenderEstimate, String> {
+    tauri::async_runtime::spawn_blocking(move || estimate_render_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn estimates_scale_past_renders() {
+        let history = vec![
+            json!({
+                "status": "RENDER_DONE",
+                "quality": "balanced",
+                "encoder": { "requested": "libx264", "used": "libx264", "fallbacks": [] },
+                "stageDurationsMs": { "segments": 50_000, "concat": 10_000 },
+                "output": { "durationUs": 60_000_000, "width": 1000, "height": 1000, "bytes": 30_000_000 },
+            }),
+            json!({ "status": "RENDER_FAILED", "quality": "balanced" }),
+        ];
+        let samples = history_samples(&history);
+        assert_eq!(samples.len(), 1);
+
+        let (time, size, basis, count) =
+            estimate(&samples, 120_000_000, (1000, 1000), "balanced", "libx264");
+        assert_eq!((basis, count), (EstimateBasis::History, 1));
+        assert_eq!(time.expected, 120_000);
+        assert_eq!(size.expected, 60_000_000);
+        assert!(time.low < time.expected && time.high > time.expected);
+
+        let (time, _, basis, _) = estimate(&samples, 120_000_000, (1000, 1000), "draft", "libx264");
+        assert_eq!(basis, EstimateBasis::SimilarHistory);
+        assert_eq!(time.expected, 60_000);
+
+        let (_, _, basis, count) = estimate(&[], 60_000_000, (1920, 1080), "draft", "auto");
+        assert_eq!((basis, count), (EstimateBasis::Defaults, 0));
+    }
+}
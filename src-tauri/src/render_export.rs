@@ -0,0 +1,163 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::render_history::{
+    find_render, find_render_mut, read_render_history, write_render_history,
+};
+use crate::s3::{S3Client, UploadProgress};
+use crate::settings::read_app_settings;
+use crate::{now_iso, windows};
+
+/// Where to put the render; unset fields fall back to the object storage
+/// section of the app settings.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportDestination {
+    bucket: Option<String>,
+    /// Prefix the key is built under: `<prefix>/<projectId>/<file name>`.
+    prefix: Option<String>,
+    /// Full object key; overrides `prefix`.
+    key: Option<String>,
+    endpoint: Option<String>,
+    region: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRenderRequest {
+    project_id: String,
+    render_id: String,
+    destination: Option<ExportDestination>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportProgressEvent {
+    project_id: String,
+    render_id: String,
+    uploaded_bytes: u64,
+    total_bytes: u64,
+    part_number: u32,
+    part_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteExport {
+    pub provider: String,
+    pub bucket: String,
+    pub key: String,
+    pub url: String,
+    pub etag: Option<String>,
+    pub size_bytes: u64,
+    pub uploaded_at: String,
+}
+
+fn export_render_blocking(
+    app: AppHandle,
+    request: ExportRenderRequest,
+) -> Result<RemoteExport, String> {
+    let history = read_render_history(&request.project_id)?;
+    let entry = find_render(&history, &request.render_id)?;
+    let output_path = entry
+        .get("outputPath")
+        .and_then(Value::as_str)
+        .map(PathBuf::from)
+        .filter(|path| path.is_file())
+        .ok_or_else(|| "Render output file is missing.".to_string())?;
+
+    let destination = request.destination.unwrap_or_default();
+    let mut storage = read_app_settings()?.object_storage;
+    if destination.endpoint.is_some() {
+        storage.endpoint = destination.endpoint;
+    }
+    if let Some(region) = destination.region {
+        storage.region = region;
+    }
+    let bucket = destination
+        .bucket
+        .or_else(|| storage.default_bucket.clone())
+        .filter(|bucket| !bucket.trim().is_empty())
+        .ok_or_else(|| "No destination bucket given or configured.".to_string())?;
+    let key = destination.key.unwrap_or_else(|| {
+        let file_name = output_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| format!("{}.mp4", request.render_id));
+        let prefix = destination
+            .prefix
+            .or_else(|| storage.default_prefix.clone())
+            .unwrap_or_default();
+        [prefix.trim_matches('/'), &request.project_id, &file_name]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("/")
+    });
+
+    let client = S3Client::new(&storage)?;
+    let part_size = u64::from(storage.part_size_mb.max(5)) * 1024 * 1024;
+    let project_id = request.project_id.clone();
+    let render_id = request.render_id.clone();
+    let mut on_progress = |progress: UploadProgress| {
+        windows::emit_for_project(
+            &app,
+            &project_id,
+            "render-export-progress",
+            ExportProgressEvent {
+                project_id: project_id.clone(),
+                render_id: render_id.clone(),
+                uploaded_bytes: progress.uploaded_bytes,
+                total_bytes: progress.total_bytes,
+                part_number: progress.part_number,
+                part_count: progress.part_count,
+            },
+        );
+    };
+    tracing::info!(
+        "Uploading render {} to {}",
+        request.render_id,
+        client.object_url(&bucket, &key)
+    );
+    let uploaded =
+        client.upload_file_multipart(&bucket, &key, &output_path, part_size, &mut on_progress)?;
+
+    let export = RemoteExport {
+        provider: "s3".to_string(),
+        bucket,
+        key,
+        url: uploaded.url,
+        etag: uploaded.etag,
+        size_bytes: uploaded.size_bytes,
+        uploaded_at: now_iso(),
+    };
+
+    // Re-read so a render finishing during the upload isn't clobbered.
+    let mut history = read_render_history(&request.project_id)?;
+    let entry = find_render_mut(&mut history, &request.render_id)?;
+    let record = serde_json::to_value(&export)
+        .map_err(|error| format!("Remote export serialize error: {error}"))?;
+    match entry.get_mut("remoteExports").and_then(Value::as_array_mut) {
+        Some(exports) => exports.push(record),
+        None => {
+            entry.insert("remoteExports".to_string(), Value::Array(vec![record]));
+        }
+    }
+    write_render_history(&request.project_id, &history)?;
+
+    Ok(export)
+}
+
+#[tauri::command]
+pub async fn export_render(
+    app: AppHandle,
+    request: ExportRenderRequest,
+) -> Result<RemoteExport, String> {
+    tauri::async_runtime::spawn_blocking(move || export_render_blocking(app, request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,339 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::{now_iso, read_timeline, render_history_file_path, Timeline};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteRenderRequest {
+    project_id: String,
+    render_id: String,
+    /// Also remove the rendered files from disk. Defaults to true.
+    delete_files: Option<bool>,
+    /// Required to delete a pinned render.
+    force: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotateRenderRequest {
+    project_id: String,
+    render_id: String,
+    /// Empty string clears the note.
+    note: Option<String>,
+    /// 1-5; 0 clears the rating.
+    rating: Option<u8>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinRenderRequest {
+    project_id: String,
+    render_id: String,
+    /// Defaults to true; pass false to unpin.
+    pinned: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IsRenderStaleRequest {
+    project_id: String,
+    render_id: String,
+}
+
+/// Identifies the timeline content a render was produced from.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineSnapshot {
+    pub timeline_id: String,
+    pub timeline_version: u32,
+    pub timeline_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderStaleness {
+    pub render_id: String,
+    /// `None` when the render predates snapshot recording.
+    pub stale: Option<bool>,
+    pub render_timeline_version: Option<u32>,
+    pub current_timeline_version: u32,
+    pub render_timeline_hash: Option<String>,
+    pub current_timeline_hash: String,
+}
+
+/// Hash of everything that affects the rendered output. Ids, timestamps and
+/// the version counter are left out so a no-op save doesn't mark renders
+/// stale.
+pub fn timeline_content_hash(timeline: &Timeline) -> String {
+    let content = serde_json::json!({
+        "fps": timeline.fps,
+        "durationUs": timeline.duration_us,
+        "tracks": timeline.tracks,
+        "clips": timeline.clips,
+    });
+    let bytes = serde_json::to_vec(&content).unwrap_or_default();
+    Sha256::digest(&bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+pub fn timeline_snapshot(timeline: &Timeline) -> TimelineSnapshot {
+    TimelineSnapshot {
+        timeline_id: timeline.id.clone(),
+        timeline_version: timeline.version,
+        timeline_hash: timeline_content_hash(timeline),
+    }
+}
+
+/// Stamps a history entry with the timeline it was rendered from.
+pub fn record_timeline_snapshot(
+    project_id: &str,
+    render_id: &str,
+    snapshot: &TimelineSnapshot,
+) -> Result<(), String> {
+    let mut history = read_render_history(project_id)?;
+    let entry = find_render_mut(&mut history, render_id)?;
+    entry.insert(
+        "timelineId".to_string(),
+        Value::from(snapshot.timeline_id.clone()),
+    );
+    entry.insert(
+        "timelineVersion".to_string(),
+        Value::from(snapshot.timeline_version),
+    );
+    entry.insert(
+        "timelineHash".to_string(),
+        Value::from(snapshot.timeline_hash.clone()),
+    );
+    write_render_history(project_id, &history)
+}
+
+/// Reads `renders/history.json`, giving every entry a `renderId`. Entries
+/// written before the render script emitted ids are assigned one and saved.
+pub fn read_render_history(project_id: &str) -> Result<Vec<Value>, String> {
+    let file_path = render_history_file_path(project_id)?;
+    if !file_path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = fs::read_to_string(&file_path)
+        .map_err(|error| format!("Failed reading render history file: {error}"))?;
+    let parsed = serde_json::from_str::<Value>(&raw)
+        .map_err(|error| format!("Invalid render history JSON: {error}"))?;
+    let mut history = match parsed {
+        Value::Array(entries) => entries,
+        _ => Vec::new(),
+    };
+
+    let micros = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros();
+    let mut backfilled = false;
+    for (index, entry) in history.iter_mut().enumerate() {
+        let Some(entry) = entry.as_object_mut() else {
+            continue;
+        };
+        if entry.get("renderId").and_then(Value::as_str).is_none() {
+            entry.insert(
+                "renderId".to_string(),
+                Value::String(format!("render-legacy-{micros}-{index}")),
+            );
+            backfilled = true;
+        }
+    }
+    if backfilled {
+        write_render_history(project_id, &history)?;
+    }
+    Ok(history)
+}
+
+pub fn write_render_history(project_id: &str, history: &[Value]) -> Result<(), String> {
+    let file_path = render_history_file_path(project_id)?;
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating renders dir: {error}"))?;
+    }
+    let serialized = serde_json::to_string_pretty(history)
+        .map_err(|error| format!("Render history serialize error: {error}"))?;
+    fs::write(&file_path, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing render history file: {error}"))
+}
+
+fn is_render(entry: &Value, render_id: &str) -> bool {
+    entry.get("renderId").and_then(Value::as_str) == Some(render_id)
+}
+
+pub fn find_render<'a>(history: &'a [Value], render_id: &str) -> Result<&'a Value, String> {
+    history
+        .iter()
+        .find(|entry| is_render(entry, render_id))
+        .ok_or_else(|| format!("Render not found: {render_id}"))
+}
+
+pub fn find_render_mut<'a>(
+    history: &'a mut [Value],
+    render_id: &str,
+) -> Result<&'a mut serde_json::Map<String, Value>, String> {
+    history
+        .iter_mut()
+        .find(|entry| is_render(entry, render_id))
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| format!("Render not found: {render_id}"))
+}
+
+/// Output file plus every format variant the render script exported.
+fn render_files(entry: &Value) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Some(path) = entry.get("outputPath").and_then(Value::as_str) {
+        paths.push(PathBuf::from(path));
+    }
+    let exports = entry.get("formatExports").and_then(Value::as_array);
+    for export in exports.into_iter().flatten() {
+        if let Some(path) = export.get("path").and_then(Value::as_str) {
+            paths.push(PathBuf::from(path));
+        }
+    }
+    paths
+}
+
+/// Applies `update` to one entry and saves, returning the updated entry.
+fn update_render(
+    project_id: &str,
+    render_id: &str,
+    update: impl FnOnce(&mut serde_json::Map<String, Value>),
+) -> Result<Value, String> {
+    let mut history = read_render_history(project_id)?;
+    let entry = find_render_mut(&mut history, render_id)?;
+    update(entry);
+    entry.insert("updatedAt".to_string(), Value::String(now_iso()));
+    let updated = Value::Object(entry.clone());
+    write_render_history(project_id, &history)?;
+    Ok(updated)
+}
+
+fn delete_render_blocking(request: DeleteRenderRequest) -> Result<Value, String> {
+    let mut history = read_render_history(&request.project_id)?;
+    let entry = find_render(&history, &request.render_id)?;
+    let pinned = entry
+        .get("pinned")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    if pinned && !request.force.unwrap_or(false) {
+        return Err("Render is pinned; unpin it or pass force to delete.".to_string());
+    }
+
+    let mut removed_files = Vec::new();
+    if request.delete_files.unwrap_or(true) {
+        // A file shared with another entry (re-render to the same path) stays.
+        let still_used = history
+            .iter()
+            .filter(|other| !is_render(other, &request.render_id))
+            .flat_map(render_files)
+            .collect::<HashSet<_>>();
+        for path in render_files(entry) {
+            if still_used.contains(&path) || !path.is_file() {
+                continue;
+            }
+            fs::remove_file(&path).map_err(|error| {
+                format!("Failed removing render file {}: {error}", path.display())
+            })?;
+            removed_files.push(path.to_string_lossy().to_string());
+        }
+    }
+
+    history.retain(|entry| !is_render(entry, &request.render_id));
+    write_render_history(&request.project_id, &history)?;
+    Ok(serde_json::json!({
+        "ok": true,
+        "renderId": request.render_id,
+        "removedFiles": removed_files,
+    }))
+}
+
+#[tauri::command]
+pub async fn delete_render(request: DeleteRenderRequest) -> Result<Value, String> {
+    tauri::async_runtime::spawn_blocking(move || delete_render_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn annotate_render(request: AnnotateRenderRequest) -> Result<Value, String> {
+    if let Some(rating) = request.rating.filter(|rating| *rating > 5) {
+        return Err(format!("Rating must be between 1 and 5, got {rating}."));
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        update_render(&request.project_id, &request.render_id, |entry| {
+            match request.note.as_deref().map(str::trim) {
+                Some("") => {
+                    entry.remove("note");
+                }
+                Some(note) => {
+                    entry.insert("note".to_string(), Value::String(note.to_string()));
+                }
+                None => {}
+            }
+            match request.rating {
+                Some(0) => {
+                    entry.remove("rating");
+                }
+                Some(rating) => {
+                    entry.insert("rating".to_string(), Value::from(rating));
+                }
+                None => {}
+            }
+        })
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn pin_render(request: PinRenderRequest) -> Result<Value, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let pinned = request.pinned.unwrap_or(true);
+        update_render(&request.project_id, &request.render_id, |entry| {
+            entry.insert("pinned".to_string(), Value::Bool(pinned));
+        })
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn is_render_stale(request: IsRenderStaleRequest) -> Result<RenderStaleness, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let history = read_render_history(&request.project_id)?;
+        let entry = find_render(&history, &request.render_id)?;
+        let current = timeline_snapshot(&read_timeline(&request.project_id)?);
+
+        let render_timeline_hash = entry
+            .get("timelineHash")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let render_timeline_version = entry
+            .get("timelineVersion")
+            .and_then(Value::as_u64)
+            .map(|version| version as u32);
+        Ok(RenderStaleness {
+            render_id: request.render_id,
+            stale: render_timeline_hash
+                .as_ref()
+                .map(|hash| *hash != current.timeline_hash),
+            render_timeline_version,
+            current_timeline_version: current.timeline_version,
+            render_timeline_hash,
+            current_timeline_hash: current.timeline_hash,
+        })
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,380 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ffmpeg::{ffmpeg_binary, ffprobe_binary};
+use crate::read_timeline;
+use crate::render_history::{
+    find_render, find_render_mut, read_render_history, write_render_history,
+};
+use crate::silence::{parse_silencedetect, seconds_to_us, value_after};
+use crate::timeline::TimeRange;
+use crate::timeline_stats::content_end_us;
+
+/// Output may differ from the timeline by this much, or by 1% on long edits.
+const DURATION_TOLERANCE_US: u64 = 500_000;
+/// Audio and video streams may end this far apart.
+const AV_SYNC_TOLERANCE_US: u64 = 200_000;
+/// Shortest black or silent stretch reported.
+const MIN_GAP_SECONDS: f64 = 3.0;
+const BLACK_PIXEL_THRESHOLD: f64 = 0.1;
+const SILENCE_THRESHOLD_DB: f64 = -50.0;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyRenderRequest {
+    project_id: String,
+    render_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// Result of probing a finished render, stored as `verification` on its
+/// history entry.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderVerification {
+    pub passed: bool,
+    pub expected_duration_us: u64,
+    pub duration_us: Option<u64>,
+    pub checks: Vec<VerificationCheck>,
+    pub black_segments: Vec<TimeRange>,
+    pub silent_segments: Vec<TimeRange>,
+}
+
+/// Stream durations from ffprobe; either is `None` when the stream is missing.
+#[derive(Debug, Clone, PartialEq)]
+struct ProbedOutput {
+    duration_us: Option<u64>,
+    video_us: Option<u64>,
+    audio_us: Option<u64>,
+    has_video: bool,
+    has_audio: bool,
+}
+
+fn seconds_field(value: Option<&Value>) -> Option<u64> {
+    let seconds = value?.as_str()?.parse::<f64>().ok()?;
+    seconds.is_finite().then(|| seconds_to_us(seconds))
+}
+
+fn parse_probe(json: &str) -> Result<ProbedOutput, String> {
+    let probe = serde_json::from_str::<Value>(json)
+        .map_err(|error| format!("Invalid ffprobe JSON: {error}"))?;
+    let streams = probe
+        .get("streams")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    let stream = |kind: &str| {
+        streams
+            .iter()
+            .find(|stream| stream.get("codec_type").and_then(Value::as_str) == Some(kind))
+    };
+    Ok(ProbedOutput {
+        duration_us: seconds_field(
+            probe
+                .get("format")
+                .and_then(|format| format.get("duration")),
+        ),
+        video_us: stream("video").and_then(|stream| seconds_field(stream.get("duration"))),
+        audio_us: stream("audio").and_then(|stream| seconds_field(stream.get("duration"))),
+        has_video: stream("video").is_some(),
+        has_audio: stream("audio").is_some(),
+    })
+}
+
+fn probe_output(path: &Path) -> Result<ProbedOutput, String> {
+    let output = Command::new(ffprobe_binary())
+        .args([
+            "-v",
+            "error",
+            "-show_entries",
+            "stream=codec_type,duration:format=duration",
+            "-of",
+            "json",
+        ])
+        .arg(path)
+        .output()
+        .map_err(|error| format!("Failed to execute ffprobe: {error}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ffprobe failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+                .trim()
+                .chars()
+                .take(300)
+                .collect::<String>()
+        ));
+    }
+    parse_probe(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Turns `blackdetect` log lines into ranges.
+pub fn parse_blackdetect(stderr: &str) -> Vec<TimeRange> {
+    stderr
+        .lines()
+        .filter_map(|line| {
+            let start = value_after(line, "black_start:")?.parse::<f64>().ok()?;
+            let end = value_after(line, "black_end:")?.parse::<f64>().ok()?;
+            (end > start).then(|| TimeRange {
+                start_us: seconds_to_us(start),
+                end_us: seconds_to_us(end),
+            })
+        })
+        .collect()
+}
+
+/// One decoding pass with `blackdetect` and `silencedetect`.
+fn detect_gaps(
+    path: &Path,
+    probed: &ProbedOutput,
+) -> Result<(Vec<TimeRange>, Vec<TimeRange>), String> {
+    let mut args = vec![
+        "-hide_banner".to_string(),
+        "-nostats".to_string(),
+        "-i".to_string(),
+        path.to_string_lossy().to_string(),
+    ];
+    if probed.has_video {
+        args.extend([
+            "-vf".to_string(),
+            format!("blackdetect=d={MIN_GAP_SECONDS}:pix_th={BLACK_PIXEL_THRESHOLD}"),
+        ]);
+    }
+    if probed.has_audio {
+        args.extend([
+            "-af".to_string(),
+            format!("silencedetect=noise={SILENCE_THRESHOLD_DB}dB:d={MIN_GAP_SECONDS}"),
+        ]);
+    }
+    args.extend(["-f", "null", "-"].map(str::to_string));
+    let output = Command::new(ffmpeg_binary())
+        .args(&args)
+        .output()
+        .map_err(|error| format!("Failed to execute ffmpeg: {error}"))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg black/silence detection failed: {}",
+            stderr.trim().chars().take(300).collect::<String>()
+        ));
+    }
+    let duration_us = probed.duration_us.unwrap_or(0);
+    let silent = if probed.has_audio {
+        parse_silencedetect(&stderr, duration_us)
+    } else {
+        Vec::new()
+    };
+    Ok((parse_blackdetect(&stderr), silent))
+}
+
+fn check(name: &str, passed: bool, detail: String) -> VerificationCheck {
+    VerificationCheck {
+        name: name.to_string(),
+        passed,
+        detail,
+    }
+}
+
+fn seconds(us: u64) -> String {
+    format!("{:.2}s", us as f64 / 1_000_000.0)
+}
+
+fn gap_detail(kind: &str, segments: &[TimeRange]) -> String {
+    match segments {
+        [] => format!("No {kind} stretches of {MIN_GAP_SECONDS}s or more."),
+        [first, ..] => format!(
+            "{} {kind} stretch(es), first at {}-{}.",
+            segments.len(),
+            seconds(first.start_us),
+            seconds(first.end_us)
+        ),
+    }
+}
+
+/// Compares a probe of the output with what the timeline should produce.
+fn evaluate(
+    probed: &ProbedOutput,
+    expected_duration_us: u64,
+    black_segments: Vec<TimeRange>,
+    silent_segments: Vec<TimeRange>,
+) -> RenderVerification {
+    let mut checks = Vec::new();
+    let tolerance_us = DURATION_TOLERANCE_US.max(expected_duration_us / 100);
+    checks.push(match probed.duration_us {
+        Some(duration_us) => check(
+            "duration",
+            duration_us.abs_diff(expected_duration_us) <= tolerance_us,
+            format!(
+                "Output is {}, timeline is {} (tolerance {}).",
+                seconds(duration_us),
+                seconds(expected_duration_us),
+                seconds(tolerance_us)
+            ),
+        ),
+        None => check(
+            "duration",
+            false,
+            "ffprobe reported no duration.".to_string(),
+        ),
+    });
+    checks.push(check(
+        "videoStream",
+        probed.has_video,
+        if probed.has_video {
+            "Present."
+        } else {
+            "Missing."
+        }
+        .to_string(),
+    ));
+    checks.push(check(
+        "audioStream",
+        probed.has_audio,
+        if probed.has_audio {
+            "Present."
+        } else {
+            "Missing."
+        }
+        .to_string(),
+    ));
+    if let (Some(video_us), Some(audio_us)) = (probed.video_us, probed.audio_us) {
+        checks.push(check(
+            "avSync",
+            video_us.abs_diff(audio_us) <= AV_SYNC_TOLERANCE_US,
+            format!(
+                "Video ends at {}, audio at {}.",
+                seconds(video_us),
+                seconds(audio_us)
+            ),
+        ));
+    }
+    checks.push(check(
+        "black",
+        black_segments.is_empty(),
+        gap_detail("black", &black_segments),
+    ));
+    checks.push(check(
+        "silence",
+        silent_segments.is_empty(),
+        gap_detail("silent", &silent_segments),
+    ));
+    RenderVerification {
+        passed: checks.iter().all(|check| check.passed),
+        expected_duration_us,
+        duration_us: probed.duration_us,
+        checks,
+        black_segments,
+        silent_segments,
+    }
+}
+
+/// Probes a rendered file and checks its duration against the timeline, its
+/// streams and A/V sync, and looks for long black or silent stretches.
+pub fn verify_render(path: &Path, expected_duration_us: u64) -> Result<RenderVerification, String> {
+    let probed = probe_output(path)?;
+    let (black_segments, silent_segments) = detect_gaps(path, &probed)?;
+    Ok(evaluate(
+        &probed,
+        expected_duration_us,
+        black_segments,
+        silent_segments,
+    ))
+}
+
+/// Stores a verification on a render history entry.
+pub fn record_render_verification(
+    project_id: &str,
+    render_id: &str,
+    verification: &RenderVerification,
+) -> Result<(), String> {
+    let mut history = read_render_history(project_id)?;
+    let entry = find_render_mut(&mut history, render_id)?;
+    entry.insert(
+        "verification".to_string(),
+        serde_json::to_value(verification)
+            .map_err(|error| format!("Verification serialize error: {error}"))?,
+    );
+    write_render_history(project_id, &history)
+}
+
+fn verify_render_output_blocking(
+    request: VerifyRenderRequest,
+) -> Result<RenderVerification, String> {
+    let history = read_render_history(&request.project_id)?;
+    let entry = find_render(&history, &request.render_id)?;
+    let output_path = entry
+        .get("outputPath")
+        .and_then(Value::as_str)
+        .map(PathBuf::from)
+        .filter(|path| path.is_file())
+        .ok_or_else(|| format!("Render {} has no output file.", request.render_id))?;
+    let stinger_us = entry
+        .get("stingerDurationUs")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    let expected_us = content_end_us(&read_timeline(&request.project_id)?) + stinger_us;
+    let verification = verify_render(&output_path, expected_us)?;
+    record_render_verification(&request.project_id, &request.render_id, &verification)?;
+    Ok(verification)
+}
+
+/// Re-runs the post-render verification of an existing render against the
+/// current timeline and stores the result on its history entry.
+#[tauri::command]
+pub async fn verify_render_output(
+    request: VerifyRenderRequest,
+) -> Result<RenderVerification, String> {
+    tauri::async_runtime::spawn_blocking(move || verify_render_output_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verification_flags_drift_and_gaps() {
+        let probed = parse_probe(
+            r#"{"streams":[
+                {"codec_type":"video","duration":"60.000000"},
+                {"codec_type":"audio","duration":"59.100000"}
+            ],"format":{"duration":"60.000000"}}"#,
+        )
+        .unwrap();
+        assert!(probed.has_audio && probed.has_video);
+
+        let clean = evaluate(&probed, 60_200_000, Vec::new(), Vec::new());
+        let failed = clean
+            .checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(failed, ["avSync"]);
+        assert!(!clean.passed);
+
+        let black = parse_blackdetect(
+            "[blackdetect @ 0x1] black_start:10 black_end:14.5 black_duration:4.5\n",
+        );
+        assert_eq!(black[0].end_us, 14_500_000);
+        let long = evaluate(&probed, 90_000_000, black, Vec::new());
+        assert!(long
+            .checks
+            .iter()
+            .any(|check| check.name == "duration" && !check.passed));
+        assert!(long
+            .checks
+            .iter()
+            .any(|check| check.name == "black" && !check.passed));
+    }
+}
@@ -0,0 +1,1161 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::blocking::{Body, Client, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tiny_http::{Method, Request, Response, Server};
+
+use crate::control_api::{self, error_body, request_token, respond, token_matches};
+use crate::media::resolve_source_path;
+use crate::media_roots;
+use crate::project_status::ProjectStatus;
+use crate::render_history::{
+    read_render_history, record_timeline_snapshot, timeline_snapshot, write_render_history,
+};
+use crate::settings::{read_app_settings, write_app_settings, RemoteWorker};
+use crate::{
+    event_bus, path_safety, project_bundle, read_projects, read_timeline, run_render_video,
+    update_project_status, write_projects, RenderVideoRequest,
+};
+
+const API_PREFIX: &str = "/worker/";
+const DEFAULT_PORT: u16 = 47832;
+const JOBS_DIR: &str = "worker-jobs";
+const BUNDLE_FILE: &str = "project.aivep";
+const MEDIA_DIR: &str = "media";
+/// Where ingest writes the proxy inside a project.
+const PROXY_PATH: &str = "media/proxy.mp4";
+const MAX_SPEC_BYTES: u64 = 1024 * 1024;
+const STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Transfers report progress at most every this many bytes or 5%.
+const MIN_REPORT_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Jobs this machine holds in worker mode.
+static JOBS: Mutex<BTreeMap<String, WorkerJob>> = Mutex::new(BTreeMap::new());
+/// Keeps job ids unique when two jobs are created in the same microsecond.
+static NEXT_JOB: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddRenderWorkerRequest {
+    name: Option<String>,
+    /// `host:port`; the port defaults to the worker's default.
+    address: String,
+    token: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveRenderWorkerRequest {
+    worker_id: String,
+}
+
+/// What a worker reports at `GET /worker/status`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerInfo {
+    pub version: String,
+    /// A render is running; new ones are refused until it finishes.
+    pub busy: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderWorkerStatus {
+    pub id: String,
+    pub name: String,
+    pub address: String,
+    pub reachable: bool,
+    pub info: Option<WorkerInfo>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WorkerJobStage {
+    /// Waiting for the bundle, media and render spec.
+    Receiving,
+    Rendering,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerJobStatus {
+    pub job_id: String,
+    pub stage: WorkerJobStage,
+    pub error: Option<String>,
+    /// `render_video` result on the worker, once done.
+    pub result: Option<Value>,
+}
+
+struct WorkerJob {
+    status: WorkerJobStatus,
+    dir: PathBuf,
+    /// The project the bundle was imported as.
+    project_id: Option<String>,
+}
+
+/// Sent with `POST /worker/jobs/<id>/render` after the uploads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoteRenderSpec {
+    request: RenderVideoRequest,
+    /// Paths outside the project, as written in its files, to the name of
+    /// the uploaded copy.
+    media: BTreeMap<String, String>,
+    /// Paths to point at the bundle's proxy instead.
+    proxied: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RemoteRenderStage {
+    Packing,
+    Uploading,
+    Rendering,
+    Downloading,
+    Done,
+}
+
+/// Payload of `REMOTE_RENDER_PROGRESS_EVENT`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteRenderProgress {
+    pub worker_id: String,
+    pub stage: RemoteRenderStage,
+    /// Transferred so far while uploading or downloading.
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+/// The worker-mode server, bound and ready to [`run`](WorkerServer::run).
+pub struct WorkerServer {
+    server: Server,
+    pub address: String,
+    pub port: u16,
+    pub token: String,
+    max_upload_bytes: u64,
+}
+
+fn jobs() -> MutexGuard<'static, BTreeMap<String, WorkerJob>> {
+    JOBS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn unix_micros() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros()
+}
+
+/// `host:port` from what the user typed, with the default port when none
+/// is given.
+pub fn normalize_address(address: &str) -> Result<String, String> {
+    let trimmed = address.trim();
+    let host = trimmed
+        .strip_prefix("http://")
+        .unwrap_or(trimmed)
+        .trim_end_matches('/');
+    if host.is_empty() || host.contains('/') || host.contains(char::is_whitespace) {
+        return Err(format!("Invalid worker address: {address:?}"));
+    }
+    let has_port = host
+        .rsplit_once(':')
+        .is_some_and(|(name, port)| !name.is_empty() && port.parse::<u16>().is_ok());
+    Ok(if has_port {
+        host.to_string()
+    } else {
+        format!("{host}:{DEFAULT_PORT}")
+    })
+}
+
+/// Replaces whole JSON string values: `"<from>"` becomes `"<to>"`, both
+/// JSON-escaped.
+pub fn rewrite_paths(raw: &str, replacements: &[(String, PathBuf)]) -> String {
+    replacements
+        .iter()
+        .fold(raw.to_string(), |text, (from, to)| {
+            let from = serde_json::to_string(from).unwrap_or_default();
+            let to = serde_json::to_string(&to.to_string_lossy()).unwrap_or_default();
+            text.replace(&from, &to)
+        })
+}
+
+// Worker mode.
+
+fn jobs_dir() -> Result<PathBuf, String> {
+    Ok(path_safety::data_dir()?.join(JOBS_DIR))
+}
+
+fn is_busy() -> bool {
+    busy(&jobs())
+}
+
+fn busy(jobs: &BTreeMap<String, WorkerJob>) -> bool {
+    jobs.values()
+        .any(|job| job.status.stage == WorkerJobStage::Rendering)
+}
+
+/// Uploaded media names, as the upload route accepts them.
+fn valid_name(name: &str) -> bool {
+    path_safety::validate_file_name(name).is_ok() && !name.starts_with('.')
+}
+
+fn ok_body<S: Serialize>(result: &S) -> Value {
+    json!({ "ok": true, "result": result })
+}
+
+fn read_spec(request: &mut Request) -> Result<RemoteRenderSpec, String> {
+    let mut raw = String::new();
+    request
+        .as_reader()
+        .take(MAX_SPEC_BYTES)
+        .read_to_string(&mut raw)
+        .map_err(|error| format!("Failed reading body: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("Invalid render spec: {error}"))
+}
+
+/// Streams the request body into `target`, through a `.part` file so a cut
+/// upload never looks complete.
+/// Writes an upload to `target`. The size comes from `Content-Length` and is
+/// checked against the limit before anything touches the disk.
+fn receive_file(request: &mut Request, target: &Path, max_bytes: u64) -> Result<u64, String> {
+    let length = request
+        .body_length()
+        .ok_or_else(|| "Uploads need a Content-Length.".to_string())? as u64;
+    if length > max_bytes {
+        return Err(format!(
+            "Upload of {length} bytes is over this worker's {max_bytes} byte limit."
+        ));
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|error| format!("Failed creating job dir: {error}"))?;
+    }
+    let part = target.with_extension("part");
+    let mut file =
+        File::create(&part).map_err(|error| format!("Failed creating upload file: {error}"))?;
+    let bytes = io::copy(&mut request.as_reader().take(length), &mut file)
+        .map_err(|error| format!("Failed receiving upload: {error}"))?;
+    fs::rename(&part, target).map_err(|error| format!("Failed saving upload: {error}"))?;
+    Ok(bytes)
+}
+
+fn job_dir(job_id: &str) -> Result<PathBuf, String> {
+    let jobs = jobs();
+    let job = jobs
+        .get(job_id)
+        .ok_or_else(|| format!("Unknown job: {job_id}"))?;
+    if job.status.stage != WorkerJobStage::Receiving {
+        return Err(format!("Job {job_id} is no longer receiving files."));
+    }
+    Ok(job.dir.clone())
+}
+
+fn create_job() -> Result<WorkerJobStatus, String> {
+    let job_id = format!(
+        "job-{}-{}",
+        unix_micros(),
+        NEXT_JOB.fetch_add(1, Ordering::Relaxed)
+    );
+    let dir = jobs_dir()?.join(&job_id);
+    fs::create_dir_all(dir.join(MEDIA_DIR))
+        .map_err(|error| format!("Failed creating job dir: {error}"))?;
+    let status = WorkerJobStatus {
+        job_id: job_id.clone(),
+        stage: WorkerJobStage::Receiving,
+        error: None,
+        result: None,
+    };
+    jobs().insert(
+        job_id,
+        WorkerJob {
+            status: status.clone(),
+            dir,
+            project_id: None,
+        },
+    );
+    Ok(status)
+}
+
+/// Imports the shipped bundle, points it at the uploaded media and renders
+/// it like a local `render_video` call.
+fn render_job(job_id: &str, dir: &Path, spec: RemoteRenderSpec) -> Result<Value, String> {
+    let project = project_bundle::import_bundle(&dir.join(BUNDLE_FILE))?;
+    if let Some(job) = jobs().get_mut(job_id) {
+        job.project_id = Some(project.id.clone());
+    }
+    let project_dir = path_safety::project_dir(&project.id)?;
+    let replacements = spec
+        .media
+        .iter()
+        .map(|(from, name)| (from.clone(), dir.join(MEDIA_DIR).join(name)))
+        .chain(
+            spec.proxied
+                .iter()
+                .map(|from| (from.clone(), project_dir.join(PROXY_PATH))),
+        )
+        .collect::<Vec<_>>();
+    let mut files = Vec::new();
+    project_bundle::project_files(&project_dir, "", &mut files)?;
+    for relative in files.iter().filter(|file| file.ends_with(".json")) {
+        let path = project_dir.join(relative);
+        let raw = fs::read_to_string(&path)
+            .map_err(|error| format!("Failed reading {relative}: {error}"))?;
+        let rewritten = rewrite_paths(&raw, &replacements);
+        if rewritten != raw {
+            fs::write(&path, rewritten)
+                .map_err(|error| format!("Failed writing {relative}: {error}"))?;
+        }
+    }
+    // The LUT lives in the project record, which the bundle import keeps
+    // as it was.
+    let mut projects = read_projects()?;
+    if let Some(imported) = projects.iter_mut().find(|entry| entry.id == project.id) {
+        let lut = imported.settings.lut.as_ref().and_then(|lut| {
+            let lut = lut.to_string_lossy();
+            replacements
+                .iter()
+                .find(|(from, _)| *from == lut)
+                .map(|(_, to)| to.clone())
+        });
+        if lut.is_some() {
+            imported.settings.lut = lut;
+            write_projects(&projects)?;
+        }
+    }
+
+    let mut request = spec.request;
+    request.project_id = project.id;
+    request.worker = None;
+    request.use_proxy = None;
+    tauri::async_runtime::block_on(run_render_video(request))
+}
+
+fn start_render(job_id: &str, spec: RemoteRenderSpec) -> Result<WorkerJobStatus, String> {
+    // The spec maps project paths to upload names; anything else could
+    // point the render at files outside the job.
+    if let Some(name) = spec.media.values().find(|name| !valid_name(name)) {
+        return Err(format!("Invalid media file name: {name:?}"));
+    }
+    let dir = job_dir(job_id)?;
+    if !dir.join(BUNDLE_FILE).is_file() {
+        return Err("The project bundle has not been uploaded.".to_string());
+    }
+    if let Some(name) = spec
+        .media
+        .values()
+        .find(|name| !dir.join(MEDIA_DIR).join(name).is_file())
+    {
+        return Err(format!("Media file {name} has not been uploaded."));
+    }
+    // Checked and claimed under one lock so two renders cannot both start.
+    let status = {
+        let mut jobs = jobs();
+        if busy(&jobs) {
+            return Err("This worker is busy with another render.".to_string());
+        }
+        let job = jobs
+            .get_mut(job_id)
+            .ok_or_else(|| format!("Unknown job: {job_id}"))?;
+        if job.status.stage != WorkerJobStage::Receiving {
+            return Err(format!("Job {job_id} is no longer receiving files."));
+        }
+        job.status.stage = WorkerJobStage::Rendering;
+        job.status.clone()
+    };
+    let job_id = job_id.to_string();
+    thread::spawn(move || {
+        tracing::info!("Rendering remote job {job_id}");
+        let outcome = render_job(&job_id, &dir, spec);
+        if let Some(job) = jobs().get_mut(&job_id) {
+            match outcome {
+                Ok(result) => {
+                    job.status.stage = WorkerJobStage::Done;
+                    job.status.result = Some(result);
+                }
+                Err(error) => {
+                    tracing::warn!("Remote job {job_id} failed: {error}");
+                    job.status.stage = WorkerJobStage::Failed;
+                    job.status.error = Some(error);
+                }
+            }
+        }
+    });
+    Ok(status)
+}
+
+fn output_file(job_id: &str) -> Result<File, String> {
+    let jobs = jobs();
+    let job = jobs
+        .get(job_id)
+        .ok_or_else(|| format!("Unknown job: {job_id}"))?;
+    let path = job
+        .status
+        .result
+        .as_ref()
+        .and_then(|result| result.get("outputPath"))
+        .and_then(Value::as_str)
+        .filter(|_| job.status.stage == WorkerJobStage::Done)
+        .ok_or_else(|| format!("Job {job_id} has no output yet."))?;
+    File::open(path).map_err(|error| format!("Failed opening output: {error}"))
+}
+
+/// Removes a job's uploads and the project it was imported as.
+fn delete_job(job_id: &str) -> Result<(), String> {
+    let job = {
+        let mut jobs = jobs();
+        if jobs
+            .get(job_id)
+            .is_some_and(|job| job.status.stage == WorkerJobStage::Rendering)
+        {
+            return Err(format!("Job {job_id} is still rendering."));
+        }
+        jobs.remove(job_id)
+            .ok_or_else(|| format!("Unknown job: {job_id}"))?
+    };
+    if job.dir.is_dir() {
+        fs::remove_dir_all(&job.dir)
+            .map_err(|error| format!("Failed removing job files: {error}"))?;
+    }
+    if let Some(project_id) = job.project_id {
+        let mut projects = read_projects()?;
+        projects.retain(|project| project.id != project_id);
+        write_projects(&projects)?;
+        let dir = path_safety::project_dir(&project_id)?;
+        if dir.is_dir() {
+            fs::remove_dir_all(&dir)
+                .map_err(|error| format!("Failed removing job project: {error}"))?;
+        }
+    }
+    Ok(())
+}
+
+fn job_status(job_id: &str) -> Result<WorkerJobStatus, String> {
+    jobs()
+        .get(job_id)
+        .map(|job| job.status.clone())
+        .ok_or_else(|| format!("Unknown job: {job_id}"))
+}
+
+fn reply<S: Serialize>(request: Request, outcome: Result<S, String>) {
+    match outcome {
+        Ok(result) => respond(request, 200, ok_body(&result)),
+        Err(error) => respond(request, 400, error_body(error)),
+    }
+}
+
+fn handle(token: &str, max_upload_bytes: u64, mut request: Request) {
+    let authorized = request_token(&request).is_some_and(|given| token_matches(token, &given));
+    if !authorized {
+        respond(request, 401, error_body("Missing or invalid worker token"));
+        return;
+    }
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let Some(route) = path.strip_prefix(API_PREFIX) else {
+        respond(request, 404, error_body("Not found"));
+        return;
+    };
+    let parts = route.split('/').collect::<Vec<_>>();
+    let method = request.method().clone();
+    match (method, parts.as_slice()) {
+        (Method::Get, ["status"]) => {
+            let info = WorkerInfo {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                busy: is_busy(),
+            };
+            respond(request, 200, ok_body(&info));
+        }
+        (Method::Post, ["jobs"]) => reply(request, create_job()),
+        (Method::Get, ["jobs", job_id]) => reply(request, job_status(job_id)),
+        (Method::Delete, ["jobs", job_id]) => reply(request, delete_job(job_id)),
+        (Method::Put, ["jobs", job_id, "bundle"]) => {
+            let outcome = job_dir(job_id).and_then(|dir| {
+                receive_file(&mut request, &dir.join(BUNDLE_FILE), max_upload_bytes)
+            });
+            reply(request, outcome);
+        }
+        (Method::Put, ["jobs", job_id, "media", name]) if valid_name(name) => {
+            let outcome = job_dir(job_id).and_then(|dir| {
+                receive_file(
+                    &mut request,
+                    &dir.join(MEDIA_DIR).join(name),
+                    max_upload_bytes,
+                )
+            });
+            reply(request, outcome);
+        }
+        (Method::Post, ["jobs", job_id, "render"]) => {
+            let outcome = read_spec(&mut request).and_then(|spec| start_render(job_id, spec));
+            reply(request, outcome);
+        }
+        (Method::Get, ["jobs", job_id, "output"]) => match output_file(job_id) {
+            Ok(file) => {
+                if let Err(error) = request.respond(Response::from_file(file)) {
+                    tracing::debug!("Render worker client went away: {error}");
+                }
+            }
+            Err(error) => respond(request, 404, error_body(error)),
+        },
+        _ => respond(request, 404, error_body("Not found")),
+    }
+}
+
+/// Binds worker mode, generating and saving a token when there is none.
+/// `address`, `port` and `token` override the settings.
+pub fn start_worker(
+    address: Option<String>,
+    port: Option<u16>,
+    token: Option<String>,
+) -> Result<WorkerServer, String> {
+    let mut settings = read_app_settings()?;
+    let address = address
+        .unwrap_or_else(|| settings.render_workers.bind_address.clone())
+        .trim()
+        .to_string();
+    let port = port.unwrap_or(settings.render_workers.port);
+    let max_upload_bytes = settings
+        .render_workers
+        .max_upload_mb
+        .saturating_mul(1024 * 1024);
+    let token = match token.filter(|token| !token.trim().is_empty()) {
+        Some(token) => token.trim().to_string(),
+        None => {
+            if settings.render_workers.token.trim().is_empty() {
+                settings.render_workers.token = control_api::generate_token()?;
+                write_app_settings(&settings)?;
+            }
+            settings.render_workers.token.trim().to_string()
+        }
+    };
+    // Unlike the control API this listens on the network, so other
+    // machines can reach it; every request needs the token, which is sent
+    // in cleartext.
+    let server = Server::http((address.as_str(), port))
+        .map_err(|error| format!("Failed starting render worker on {address}:{port}: {error}"))?;
+    tracing::info!("Render worker listening on {address}:{port}");
+    Ok(WorkerServer {
+        server,
+        address,
+        port,
+        token,
+        max_upload_bytes,
+    })
+}
+
+impl WorkerServer {
+    /// Serves requests until the process exits, each on its own thread so
+    /// status polls are answered during long uploads.
+    pub fn run(self) {
+        let token = Arc::new(self.token);
+        let max_upload_bytes = self.max_upload_bytes;
+        for request in self.server.incoming_requests() {
+            let token = Arc::clone(&token);
+            thread::spawn(move || handle(&token, max_upload_bytes, request));
+        }
+    }
+}
+
+// Dispatching to a worker.
+
+fn http_client(timeout: Option<Duration>) -> Result<Client, String> {
+    Client::builder()
+        .connect_timeout(STATUS_TIMEOUT)
+        .timeout(timeout)
+        .build()
+        .map_err(|error| format!("HTTP client error: {error}"))
+}
+
+fn url(worker: &RemoteWorker, path: &str) -> String {
+    format!("http://{}{API_PREFIX}{path}", worker.address)
+}
+
+/// Sends a worker request and unwraps its `{ok, result}` body.
+fn send<T: for<'de> Deserialize<'de>>(
+    worker: &RemoteWorker,
+    request: RequestBuilder,
+) -> Result<T, String> {
+    let response = request
+        .bearer_auth(worker.token.trim())
+        .send()
+        .map_err(|error| format!("Render worker {} unreachable: {error}", worker.name))?;
+    let text = response
+        .text()
+        .map_err(|error| format!("Failed reading render worker response: {error}"))?;
+    let body = serde_json::from_str::<Value>(&text)
+        .map_err(|error| format!("Invalid response from render worker: {error}"))?;
+    if body.get("ok").and_then(Value::as_bool) != Some(true) {
+        let error = body
+            .get("error")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error");
+        return Err(format!("Render worker {}: {error}", worker.name));
+    }
+    serde_json::from_value(body.get("result").cloned().unwrap_or(Value::Null))
+        .map_err(|error| format!("Invalid response from render worker: {error}"))
+}
+
+fn worker_info(worker: &RemoteWorker) -> Result<WorkerInfo, String> {
+    let client = http_client(Some(STATUS_TIMEOUT))?;
+    send(worker, client.get(url(worker, "status")))
+}
+
+fn find_worker(worker_id: &str) -> Result<RemoteWorker, String> {
+    read_app_settings()?
+        .render_workers
+        .workers
+        .into_iter()
+        .find(|worker| worker.id == worker_id)
+        .ok_or_else(|| format!("Unknown render worker: {worker_id}"))
+}
+
+fn publish_progress(
+    project_id: &str,
+    worker_id: &str,
+    stage: RemoteRenderStage,
+    bytes_done: u64,
+    bytes_total: u64,
+) {
+    event_bus::publish(
+        project_id,
+        event_bus::REMOTE_RENDER_PROGRESS_EVENT,
+        &RemoteRenderProgress {
+            worker_id: worker_id.to_string(),
+            stage,
+            bytes_done,
+            bytes_total,
+        },
+    );
+}
+
+/// Reports bytes read through it every `step` bytes.
+struct ProgressReader<R> {
+    inner: R,
+    done: u64,
+    next_report: u64,
+    step: u64,
+    report: Box<dyn FnMut(u64) + Send>,
+}
+
+impl<R> ProgressReader<R> {
+    fn new(inner: R, total: u64, report: Box<dyn FnMut(u64) + Send>) -> Self {
+        let step = (total / 20).max(MIN_REPORT_BYTES);
+        Self {
+            inner,
+            done: 0,
+            next_report: step,
+            step,
+            report,
+        }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.done += read as u64;
+        if self.done >= self.next_report {
+            (self.report)(self.done);
+            self.next_report = self.done + self.step;
+        }
+        Ok(read)
+    }
+}
+
+/// A project ready to ship: its bundle plus the media it uses from outside
+/// the project directory.
+struct Package {
+    bundle: PathBuf,
+    /// Upload name and local file.
+    uploads: Vec<(String, PathBuf)>,
+    media: BTreeMap<String, String>,
+    proxied: Vec<String>,
+}
+
+impl Package {
+    fn total_bytes(&self) -> u64 {
+        std::iter::once(&self.bundle)
+            .chain(self.uploads.iter().map(|(_, path)| path))
+            .map(|path| fs::metadata(path).map_or(0, |metadata| metadata.len()))
+            .sum()
+    }
+}
+
+/// Absolute paths to existing files outside `project_dir` among the string
+/// values of `value`, keyed by the string as written. Media root aliases
+/// are expanded with the controller's roots.
+fn collect_external(
+    value: &Value,
+    project_dir: &Path,
+    roots: &BTreeMap<String, PathBuf>,
+    found: &mut BTreeMap<String, PathBuf>,
+) {
+    match value {
+        Value::String(text) => {
+            let path = media_roots::expand_reference(text, roots);
+            if path.is_absolute() && !path.starts_with(project_dir) && path.is_file() {
+                found.insert(text.clone(), path);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_external(item, project_dir, roots, found);
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values() {
+                collect_external(item, project_dir, roots, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn package(project_id: &str, use_proxy: bool) -> Result<Package, String> {
+    let project_dir = path_safety::project_dir(project_id)?;
+    let mut files = Vec::new();
+    project_bundle::project_files(&project_dir, "", &mut files)?;
+    let roots = media_roots::media_roots();
+    let mut external = BTreeMap::new();
+    for relative in files.iter().filter(|file| file.ends_with(".json")) {
+        let raw = fs::read_to_string(project_dir.join(relative))
+            .map_err(|error| format!("Failed reading {relative}: {error}"))?;
+        if let Ok(value) = serde_json::from_str::<Value>(&raw) {
+            collect_external(&value, &project_dir, &roots, &mut external);
+        }
+    }
+    let project = read_projects()?
+        .into_iter()
+        .find(|project| project.id == project_id)
+        .ok_or_else(|| "Project not found.".to_string())?;
+    if let Some(lut) = project.settings.lut.filter(|lut| lut.is_file()) {
+        external.insert(lut.to_string_lossy().to_string(), lut);
+    }
+
+    // The proxy stands in for the ingested source; other media still ships.
+    let proxy_source = resolve_source_path(project_id, None)
+        .ok()
+        .filter(|_| use_proxy && project_dir.join(PROXY_PATH).is_file());
+    let mut uploads = Vec::<(String, PathBuf)>::new();
+    let mut media = BTreeMap::new();
+    let mut proxied = Vec::new();
+    for (reference, path) in external {
+        if proxy_source.as_ref() == Some(&path) {
+            proxied.push(reference);
+            continue;
+        }
+        let name = match uploads.iter().find(|(_, uploaded)| *uploaded == path) {
+            Some((name, _)) => name.clone(),
+            None => {
+                let extension = path
+                    .extension()
+                    .map(|extension| format!(".{}", extension.to_string_lossy()))
+                    .unwrap_or_default();
+                let name = format!("{}{extension}", uploads.len());
+                uploads.push((name.clone(), path));
+                name
+            }
+        };
+        media.insert(reference, name);
+    }
+
+    let bundle = std::env::temp_dir().join(format!(
+        "remote-render-{project_id}-{}.{}",
+        unix_micros(),
+        project_bundle::BUNDLE_EXTENSION
+    ));
+    project_bundle::export_bundle(project_id, &bundle)?;
+    Ok(Package {
+        bundle,
+        uploads,
+        media,
+        proxied,
+    })
+}
+
+fn upload(
+    client: &Client,
+    worker: &RemoteWorker,
+    path: &str,
+    file: &Path,
+    report: Box<dyn FnMut(u64) + Send>,
+) -> Result<(), String> {
+    let length = fs::metadata(file)
+        .map_err(|error| format!("Failed reading {}: {error}", file.display()))?
+        .len();
+    let source =
+        File::open(file).map_err(|error| format!("Failed reading {}: {error}", file.display()))?;
+    let body = Body::sized(ProgressReader::new(source, length, report), length);
+    send::<u64>(worker, client.put(url(worker, path)).body(body)).map(|_| ())
+}
+
+/// Downloads the job's output into the project's renders directory.
+fn download(
+    client: &Client,
+    worker: &RemoteWorker,
+    job_id: &str,
+    target: &Path,
+    report: Box<dyn FnMut(u64, u64) + Send>,
+) -> Result<(), String> {
+    let response = client
+        .get(url(worker, &format!("jobs/{job_id}/output")))
+        .bearer_auth(worker.token.trim())
+        .send()
+        .map_err(|error| format!("Render worker {} unreachable: {error}", worker.name))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Render worker {} returned {} for the output.",
+            worker.name,
+            response.status()
+        ));
+    }
+    let total = response.content_length().unwrap_or(0);
+    let mut report = report;
+    let mut reader =
+        ProgressReader::new(response, total, Box::new(move |done| report(done, total)));
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating renders dir: {error}"))?;
+    }
+    let part = target.with_extension("part");
+    let mut file =
+        File::create(&part).map_err(|error| format!("Failed creating output file: {error}"))?;
+    io::copy(&mut reader, &mut file)
+        .map_err(|error| format!("Failed downloading output: {error}"))?;
+    fs::rename(&part, target).map_err(|error| format!("Failed saving output: {error}"))
+}
+
+/// Uploads, renders, polls and downloads one job.
+fn run_job(
+    client: &Client,
+    worker: &RemoteWorker,
+    job_id: &str,
+    package: &Package,
+    request: RenderVideoRequest,
+) -> Result<Value, String> {
+    let project_id = request.project_id.clone();
+    let total = package.total_bytes();
+    let mut sent = 0;
+    let files = std::iter::once(("bundle".to_string(), &package.bundle)).chain(
+        package
+            .uploads
+            .iter()
+            .map(|(name, path)| (format!("media/{name}"), path)),
+    );
+    for (path, file) in files {
+        let base = sent;
+        let (project, worker_id) = (project_id.clone(), worker.id.clone());
+        let report = Box::new(move |done: u64| {
+            publish_progress(
+                &project,
+                &worker_id,
+                RemoteRenderStage::Uploading,
+                base + done,
+                total,
+            );
+        });
+        upload(
+            client,
+            worker,
+            &format!("jobs/{job_id}/{path}"),
+            file,
+            report,
+        )?;
+        sent += fs::metadata(file).map_or(0, |metadata| metadata.len());
+    }
+    publish_progress(
+        &project_id,
+        &worker.id,
+        RemoteRenderStage::Uploading,
+        sent,
+        total,
+    );
+
+    let spec = RemoteRenderSpec {
+        request,
+        media: package.media.clone(),
+        proxied: package.proxied.clone(),
+    };
+    let spec = serde_json::to_string(&spec)
+        .map_err(|error| format!("Render spec serialize error: {error}"))?;
+    send::<WorkerJobStatus>(
+        worker,
+        client
+            .post(url(worker, &format!("jobs/{job_id}/render")))
+            .header("Content-Type", "application/json")
+            .body(spec),
+    )?;
+    publish_progress(&project_id, &worker.id, RemoteRenderStage::Rendering, 0, 0);
+    let status_client = http_client(Some(STATUS_TIMEOUT))?;
+    let result = loop {
+        thread::sleep(POLL_INTERVAL);
+        let status: WorkerJobStatus = send(
+            worker,
+            status_client.get(url(worker, &format!("jobs/{job_id}"))),
+        )?;
+        match status.stage {
+            WorkerJobStage::Done => break status.result.unwrap_or(Value::Null),
+            WorkerJobStage::Failed => {
+                return Err(status
+                    .error
+                    .unwrap_or_else(|| format!("Render on {} failed.", worker.name)))
+            }
+            WorkerJobStage::Receiving | WorkerJobStage::Rendering => {}
+        }
+    };
+
+    let file_name = result
+        .get("outputPath")
+        .and_then(Value::as_str)
+        .and_then(|path| Path::new(path).file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Render on {} reported no output file.", worker.name))?;
+    path_safety::validate_file_name(&file_name)?;
+    let target = path_safety::project_dir(&project_id)?
+        .join("renders")
+        .join(&file_name);
+    let (project, worker_id) = (project_id.clone(), worker.id.clone());
+    download(
+        client,
+        worker,
+        job_id,
+        &target,
+        Box::new(move |done, total| {
+            publish_progress(
+                &project,
+                &worker_id,
+                RemoteRenderStage::Downloading,
+                done,
+                total,
+            );
+        }),
+    )?;
+    Ok(localize_result(result, &target, worker))
+}
+
+/// The worker's result as a local render: output path on this machine and
+/// the worker named. Format variants stay on the worker.
+fn localize_result(mut result: Value, output: &Path, worker: &RemoteWorker) -> Value {
+    if let Some(object) = result.as_object_mut() {
+        object.insert(
+            "outputPath".to_string(),
+            Value::from(output.to_string_lossy().to_string()),
+        );
+        object.insert(
+            "worker".to_string(),
+            json!({ "id": worker.id, "name": worker.name, "address": worker.address }),
+        );
+        object.remove("formatExports");
+        object.remove("historyPath");
+    }
+    result
+}
+
+fn record_history(project_id: &str, result: &Value) -> Result<(), String> {
+    let mut entry = result.clone();
+    if let Some(object) = entry.as_object_mut() {
+        object.insert("status".to_string(), Value::from("RENDER_DONE"));
+    }
+    let mut history = read_render_history(project_id)?;
+    history.push(entry);
+    write_render_history(project_id, &history)
+}
+
+fn dispatch(worker: &RemoteWorker, request: RenderVideoRequest) -> Result<Value, String> {
+    let project_id = request.project_id.clone();
+    if worker_info(worker)?.busy {
+        return Err(format!(
+            "Render worker {} is busy with another render.",
+            worker.name
+        ));
+    }
+    let snapshot = read_timeline(&project_id)
+        .map(|timeline| timeline_snapshot(&timeline))
+        .ok();
+    publish_progress(&project_id, &worker.id, RemoteRenderStage::Packing, 0, 0);
+    let package = package(&project_id, request.use_proxy.unwrap_or(false))?;
+    let client = http_client(None)?;
+    let created = send::<WorkerJobStatus>(worker, client.post(url(worker, "jobs")));
+    let outcome = created.and_then(|job| {
+        let outcome = run_job(&client, worker, &job.job_id, &package, request);
+        let cleanup = send::<Value>(
+            worker,
+            client.delete(url(worker, &format!("jobs/{}", job.job_id))),
+        );
+        if let Err(error) = cleanup {
+            tracing::warn!("Failed cleaning up remote job {}: {error}", job.job_id);
+        }
+        outcome
+    });
+    if let Err(error) = fs::remove_file(&package.bundle) {
+        tracing::debug!("Failed removing shipped bundle: {error}");
+    }
+    let mut result = outcome?;
+    record_history(&project_id, &result)?;
+    if let (Some(render_id), Some(snapshot)) =
+        (result.get("renderId").and_then(Value::as_str), snapshot)
+    {
+        record_timeline_snapshot(&project_id, render_id, &snapshot)?;
+        if let Some(object) = result.as_object_mut() {
+            object.insert("timelineId".to_string(), Value::from(snapshot.timeline_id));
+            object.insert(
+                "timelineVersion".to_string(),
+                Value::from(snapshot.timeline_version),
+            );
+            object.insert(
+                "timelineHash".to_string(),
+                Value::from(snapshot.timeline_hash),
+            );
+        }
+    }
+    publish_progress(&project_id, &worker.id, RemoteRenderStage::Done, 0, 0);
+    Ok(result)
+}
+
+/// Renders on a remote worker: ships the project bundle and the media it
+/// uses, streams progress as `REMOTE_RENDER_PROGRESS_EVENT` and downloads
+/// the output into the project's renders, recorded in its history like a
+/// local render. Pre-render plugins and hooks run with the worker's own
+/// configuration.
+pub fn render_on_worker(worker_id: &str, request: RenderVideoRequest) -> Result<Value, String> {
+    let worker = find_worker(worker_id)?;
+    let project_id = request.project_id.clone();
+    update_project_status(&project_id, ProjectStatus::RenderInProgress)?;
+    tracing::info!("Rendering {project_id} on worker {}", worker.name);
+    let outcome = dispatch(&worker, request);
+    let status = match outcome {
+        Ok(_) => ProjectStatus::RenderDone,
+        Err(_) => ProjectStatus::RenderFailed,
+    };
+    update_project_status(&project_id, status)?;
+    outcome
+}
+
+fn list_render_workers_blocking() -> Result<Vec<RenderWorkerStatus>, String> {
+    let workers = read_app_settings()?.render_workers.workers;
+    let checks = workers
+        .into_iter()
+        .map(|worker| thread::spawn(move || (worker_info(&worker), worker)))
+        .collect::<Vec<_>>();
+    Ok(checks
+        .into_iter()
+        .filter_map(|check| check.join().ok())
+        .map(|(info, worker)| RenderWorkerStatus {
+            id: worker.id,
+            name: worker.name,
+            address: worker.address,
+            reachable: info.is_ok(),
+            error: info.as_ref().err().cloned(),
+            info: info.ok(),
+        })
+        .collect())
+}
+
+fn add_render_worker_blocking(request: AddRenderWorkerRequest) -> Result<RemoteWorker, String> {
+    let address = normalize_address(&request.address)?;
+    let worker = RemoteWorker {
+        id: format!("worker-{}", unix_micros()),
+        name: request
+            .name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| address.clone()),
+        address,
+        token: request.token.trim().to_string(),
+    };
+    // Registering checks the address and token before saving them.
+    worker_info(&worker)?;
+    let mut settings = read_app_settings()?;
+    if settings
+        .render_workers
+        .workers
+        .iter()
+        .any(|existing| existing.address == worker.address)
+    {
+        return Err(format!("A worker at {} is already added.", worker.address));
+    }
+    settings.render_workers.workers.push(worker.clone());
+    write_app_settings(&settings)?;
+    Ok(worker)
+}
+
+fn remove_render_worker_blocking(request: RemoveRenderWorkerRequest) -> Result<(), String> {
+    let mut settings = read_app_settings()?;
+    let before = settings.render_workers.workers.len();
+    settings
+        .render_workers
+        .workers
+        .retain(|worker| worker.id != request.worker_id);
+    if settings.render_workers.workers.len() == before {
+        return Err(format!("Unknown render worker: {}", request.worker_id));
+    }
+    write_app_settings(&settings)
+}
+
+/// Configured workers with whether each answers and is busy.
+#[tauri::command]
+pub async fn list_render_workers() -> Result<Vec<RenderWorkerStatus>, String> {
+    tauri::async_runtime::spawn_blocking(list_render_workers_blocking)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// Registers a worker by address and token after checking it answers.
+#[tauri::command]
+pub async fn add_render_worker(request: AddRenderWorkerRequest) -> Result<RemoteWorker, String> {
+    tauri::async_runtime::spawn_blocking(move || add_render_worker_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn remove_render_worker(request: RemoveRenderWorkerRequest) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || remove_render_worker_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn addresses_and_shipped_paths_are_normalized() {
+        assert_eq!(
+            normalize_address(" http://studio.local/ ").unwrap(),
+            format!("studio.local:{DEFAULT_PORT}")
+        );
+        assert_eq!(
+            normalize_address("192.168.1.20:9000").unwrap(),
+            "192.168.1.20:9000"
+        );
+        assert!(normalize_address("host/path").is_err());
+
+        let raw = r#"{"sourceRef":"/footage/a.mp4","other":"/footage/a.mp4.wav"}"#;
+        let rewritten = rewrite_paths(
+            raw,
+            &[(
+                "/footage/a.mp4".to_string(),
+                PathBuf::from("/jobs/job-1/media/0.mp4"),
+            )],
+        );
+        assert_eq!(
+            rewritten,
+            r#"{"sourceRef":"/jobs/job-1/media/0.mp4","other":"/footage/a.mp4.wav"}"#
+        );
+    }
+}
@@ -0,0 +1,379 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use reqwest::blocking::Client;
+use reqwest::Method;
+use sha2::{Digest, Sha256};
+
+use crate::settings::ObjectStorageSettings;
+
+const MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Minimal S3-compatible client: SigV4 signing plus the multipart upload calls.
+pub struct S3Client {
+    scheme: String,
+    host: String,
+    region: String,
+    access_key_id: String,
+    secret_access_key: String,
+    path_style: bool,
+    http: Client,
+}
+
+pub struct UploadedObject {
+    pub url: String,
+    pub etag: Option<String>,
+    pub size_bytes: u64,
+}
+
+pub struct UploadProgress {
+    pub uploaded_bytes: u64,
+    pub total_bytes: u64,
+    pub part_number: u32,
+    pub part_count: u32,
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex(&Sha256::digest(bytes))
+}
+
+fn hmac_sha256(key: &[u8], message: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(message.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// RFC 3986 encoding as SigV4 expects it; `/` is kept in object keys.
+fn uri_encode(value: &str, keep_slash: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if keep_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// `(YYYYMMDD, YYYYMMDDTHHMMSSZ)` in UTC.
+fn amz_dates(epoch_secs: u64) -> (String, String) {
+    let days = (epoch_secs / 86_400) as i64;
+    let seconds_of_day = epoch_secs % 86_400;
+    // Days-to-civil conversion (proleptic Gregorian).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let date = format!("{year:04}{month:02}{day:02}");
+    let stamp = format!(
+        "{date}T{:02}{:02}{:02}Z",
+        seconds_of_day / 3600,
+        (seconds_of_day / 60) % 60,
+        seconds_of_day % 60
+    );
+    (date, stamp)
+}
+
+fn xml_tag<'a>(body: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let start = body.find(&open)? + open.len();
+    let end = body[start..].find(&close)? + start;
+    Some(&body[start..end])
+}
+
+fn error_snippet(body: &str) -> String {
+    xml_tag(body, "Message")
+        .unwrap_or(body)
+        .trim()
+        .chars()
+        .take(300)
+        .collect()
+}
+
+impl S3Client {
+    pub fn new(settings: &ObjectStorageSettings) -> Result<Self, String> {
+        if settings.access_key_id.trim().is_empty() || settings.secret_access_key.trim().is_empty()
+        {
+            return Err(
+                "Object storage credentials are not configured in app settings.".to_string(),
+            );
+        }
+        let region = if settings.region.trim().is_empty() {
+            "us-east-1".to_string()
+        } else {
+            settings.region.trim().to_string()
+        };
+        let endpoint = settings
+            .endpoint
+            .as_deref()
+            .map(str::trim)
+            .filter(|endpoint| !endpoint.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("https://s3.{region}.amazonaws.com"));
+        let (scheme, host) = match endpoint.split_once("://") {
+            Some((scheme, host)) => (scheme.to_string(), host.trim_end_matches('/').to_string()),
+            None => (
+                "https".to_string(),
+                endpoint.trim_end_matches('/').to_string(),
+            ),
+        };
+        let http = Client::builder()
+            .timeout(Duration::from_secs(600))
+            .build()
+            .map_err(|error| format!("Failed creating HTTP client: {error}"))?;
+
+        Ok(Self {
+            scheme,
+            host,
+            region,
+            access_key_id: settings.access_key_id.trim().to_string(),
+            secret_access_key: settings.secret_access_key.trim().to_string(),
+            path_style: settings.path_style,
+            http,
+        })
+    }
+
+    fn host_and_path(&self, bucket: &str, key: &str) -> (String, String) {
+        let key = uri_encode(key.trim_start_matches('/'), true);
+        if self.path_style {
+            (self.host.clone(), format!("/{bucket}/{key}"))
+        } else {
+            (format!("{bucket}.{}", self.host), format!("/{key}"))
+        }
+    }
+
+    pub fn object_url(&self, bucket: &str, key: &str) -> String {
+        let (host, path) = self.host_and_path(bucket, key);
+        format!("{}://{host}{path}", self.scheme)
+    }
+
+    fn send(
+        &self,
+        method: Method,
+        bucket: &str,
+        key: &str,
+        query: &[(&str, String)],
+        body: Vec<u8>,
+    ) -> Result<reqwest::blocking::Response, String> {
+        let (host, path) = self.host_and_path(bucket, key);
+        let mut query = query
+            .iter()
+            .map(|(name, value)| {
+                format!("{}={}", uri_encode(name, false), uri_encode(value, false))
+            })
+            .collect::<Vec<_>>();
+        query.sort();
+        let query = query.join("&");
+
+        let epoch_secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let (date, stamp) = amz_dates(epoch_secs);
+        let payload_hash = sha256_hex(&body);
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{method}\n{path}\n{query}\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{stamp}\n\n{signed_headers}\n{payload_hash}"
+        );
+        let scope = format!("{date}/{}/s3/aws4_request", self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{stamp}\n{scope}\n{}",
+            sha256_hex(canonical_request.as_bytes())
+        );
+        let signing_key = ["s3", "aws4_request"].iter().fold(
+            hmac_sha256(
+                &hmac_sha256(format!("AWS4{}", self.secret_access_key).as_bytes(), &date),
+                &self.region,
+            ),
+            |key, part| hmac_sha256(&key, part),
+        );
+        let signature = hex(&hmac_sha256(&signing_key, &string_to_sign));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            self.access_key_id
+        );
+
+        let url = if query.is_empty() {
+            format!("{}://{host}{path}", self.scheme)
+        } else {
+            format!("{}://{host}{path}?{query}", self.scheme)
+        };
+        let response = self
+            .http
+            .request(method.clone(), url)
+            .header("x-amz-date", stamp)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("authorization", authorization)
+            .body(body)
+            .send()
+            .map_err(|error| format!("S3 {method} request failed: {error}"))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(format!(
+                "S3 {method} returned {status}: {}",
+                error_snippet(&body)
+            ));
+        }
+        Ok(response)
+    }
+
+    /// Uploads a file in parts, reporting after each one. The upload is
+    /// aborted on failure so no orphaned parts are billed.
+    pub fn upload_file_multipart(
+        &self,
+        bucket: &str,
+        key: &str,
+        file_path: &Path,
+        part_size: u64,
+        on_progress: &mut dyn FnMut(UploadProgress),
+    ) -> Result<UploadedObject, String> {
+        let part_size = part_size.max(MIN_PART_SIZE);
+        let total_bytes = file_path
+            .metadata()
+            .map_err(|error| format!("Failed reading render file: {error}"))?
+            .len();
+        let part_count = total_bytes.div_ceil(part_size).max(1) as u32;
+
+        let created = self.send(
+            Method::POST,
+            bucket,
+            key,
+            &[("uploads", String::new())],
+            Vec::new(),
+        )?;
+        let body = created
+            .text()
+            .map_err(|error| format!("Failed reading S3 response: {error}"))?;
+        let upload_id = xml_tag(&body, "UploadId")
+            .ok_or_else(|| "S3 did not return an UploadId.".to_string())?
+            .to_string();
+
+        let uploaded = self.upload_parts(
+            bucket,
+            key,
+            &upload_id,
+            file_path,
+            part_size,
+            total_bytes,
+            part_count,
+            on_progress,
+        );
+        let etags = match uploaded {
+            Ok(etags) => etags,
+            Err(error) => {
+                let _ = self.send(
+                    Method::DELETE,
+                    bucket,
+                    key,
+                    &[("uploadId", upload_id.clone())],
+                    Vec::new(),
+                );
+                return Err(error);
+            }
+        };
+
+        let parts = etags
+            .iter()
+            .enumerate()
+            .map(|(index, etag)| {
+                format!(
+                    "<Part><PartNumber>{}</PartNumber><ETag>{etag}</ETag></Part>",
+                    index + 1
+                )
+            })
+            .collect::<String>();
+        let completed = self.send(
+            Method::POST,
+            bucket,
+            key,
+            &[("uploadId", upload_id)],
+            format!("<CompleteMultipartUpload>{parts}</CompleteMultipartUpload>").into_bytes(),
+        )?;
+        let body = completed
+            .text()
+            .map_err(|error| format!("Failed reading S3 response: {error}"))?;
+        // S3 can report a failed completion with a 200 status.
+        if body.contains("<Error>") {
+            return Err(format!(
+                "S3 multipart completion failed: {}",
+                error_snippet(&body)
+            ));
+        }
+
+        Ok(UploadedObject {
+            url: self.object_url(bucket, key),
+            etag: xml_tag(&body, "ETag").map(|etag| etag.replace("&quot;", "\"")),
+            size_bytes: total_bytes,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn upload_parts(
+        &self,
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        file_path: &Path,
+        part_size: u64,
+        total_bytes: u64,
+        part_count: u32,
+        on_progress: &mut dyn FnMut(UploadProgress),
+    ) -> Result<Vec<String>, String> {
+        let mut file = File::open(file_path)
+            .map_err(|error| format!("Failed opening render file: {error}"))?;
+        let mut etags = Vec::new();
+        let mut uploaded_bytes = 0_u64;
+
+        for part_number in 1..=part_count {
+            let mut chunk = Vec::new();
+            (&mut file)
+                .take(part_size)
+                .read_to_end(&mut chunk)
+                .map_err(|error| format!("Failed reading render file: {error}"))?;
+            let chunk_len = chunk.len() as u64;
+            let response = self.send(
+                Method::PUT,
+                bucket,
+                key,
+                &[
+                    ("partNumber", part_number.to_string()),
+                    ("uploadId", upload_id.to_string()),
+                ],
+                chunk,
+            )?;
+            let etag = response
+                .headers()
+                .get("etag")
+                .and_then(|value| value.to_str().ok())
+                .ok_or_else(|| format!("S3 returned no ETag for part {part_number}."))?;
+            etags.push(etag.to_string());
+
+            uploaded_bytes += chunk_len;
+            on_progress(UploadProgress {
+                uploaded_bytes,
+                total_bytes,
+                part_number,
+                part_count,
+            });
+        }
+        Ok(etags)
+    }
+}
@@ -0,0 +1,166 @@
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+use tauri::AppHandle;
+use tracing::Instrument;
+
+use crate::project_status::ProjectStatus;
+use crate::timeline::{build_rough_cut_timeline, RoughCutOptions, TimeRange};
+use crate::{
+    generate_project_id, mark_timeline_written, now_iso, path_safety, publish_project_status,
+    read_projects, render_history, run_ingest_media, scheduler, script_path, sponsors,
+    write_projects, write_timeline, MediaIngestRequest, Project, ProjectSettings,
+};
+
+/// Short demo clip shipped with the app (see `bundle.resources`).
+const SAMPLE_CLIP: &str = "samples/test.mp4";
+const SAMPLE_NAME: &str = "Sample project";
+const SAMPLE_FPS: u32 = 30;
+/// Fractions of the clip cut from the pre-built timeline, so the sample
+/// shows a few edits rather than one untouched clip.
+const SAMPLE_CUTS: [(f64, f64); 2] = [(0.15, 0.25), (0.55, 0.65)];
+
+fn create_project_entry() -> Result<Project, String> {
+    let mut projects = read_projects()?;
+    let now = now_iso();
+    let project = Project {
+        id: generate_project_id(),
+        name: SAMPLE_NAME.to_string(),
+        settings: ProjectSettings {
+            aspect_ratio: "16:9".to_string(),
+            fps: SAMPLE_FPS,
+            resolution: "1080p".to_string(),
+            language: "en".to_string(),
+            ai_mode: "hybrid".to_string(),
+            fallback_policy: None,
+            transcription_model: None,
+            cut_planner_model: None,
+            template_planner_model: None,
+            lut: None,
+        },
+        status: "PROJECT_CREATED".to_string(),
+        parent_id: None,
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    projects.push(project.clone());
+    write_projects(&projects)?;
+    publish_project_status(&project, None);
+    Ok(project)
+}
+
+fn copy_clip(source: &Path, target: &Path) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating sample dir: {error}"))?;
+    }
+    fs::copy(source, target)
+        .map(|_| ())
+        .map_err(|error| format!("Failed copying sample clip: {error}"))
+}
+
+/// Writes a pre-cut timeline over the copied clip and a render history
+/// entry pointing at its own copy, so deleting that render from the
+/// history view leaves the source footage alone.
+fn seed_project(project: &Project, clip: &Path, ingest: &Value) -> Result<(), String> {
+    let duration_sec = ingest["media"]["durationSec"].as_f64().unwrap_or(0.0);
+    if duration_sec <= 0.0 {
+        return Err("Sample clip ingest reported no duration.".to_string());
+    }
+    let duration_us = (duration_sec * 1_000_000.0) as u64;
+    let remove_ranges = SAMPLE_CUTS
+        .iter()
+        .map(|(start, end)| TimeRange {
+            start_us: (duration_us as f64 * start) as u64,
+            end_us: (duration_us as f64 * end) as u64,
+        })
+        .collect();
+    let mut timeline = build_rough_cut_timeline(
+        project.id.clone(),
+        duration_us,
+        SAMPLE_FPS,
+        clip.to_string_lossy().to_string(),
+        remove_ranges,
+        &RoughCutOptions::default(),
+        &sponsors::protected_ranges(&project.id),
+    );
+    timeline.meta = serde_json::json!({ "sample": true });
+    write_timeline(&timeline)?;
+    mark_timeline_written(&project.id, ProjectStatus::RoughCutReady)?;
+
+    let data_dir = path_safety::project_dir(&project.id)?;
+    let render_path = data_dir.join("renders").join("sample-render.mp4");
+    copy_clip(clip, &render_path)?;
+    let snapshot = render_history::timeline_snapshot(&timeline);
+    let entry = serde_json::json!({
+        "ok": true,
+        "renderId": format!("render-sample-{}", project.id),
+        "projectId": project.id,
+        "outputPath": render_path.to_string_lossy(),
+        "quality": "preview",
+        "status": "RENDER_DONE",
+        "finishedAt": now_iso(),
+        "warnings": [],
+        "sample": true,
+        "timelineId": snapshot.timeline_id,
+        "timelineVersion": snapshot.timeline_version,
+        "timelineHash": snapshot.timeline_hash,
+    });
+    render_history::write_render_history(&project.id, &[entry])
+}
+
+/// Creates a project around the bundled demo clip: copies it into the
+/// project's media, ingests it like any import, builds a small pre-cut
+/// timeline and seeds one render, so the editor has something to show
+/// before the user brings their own footage.
+#[tauri::command]
+pub async fn create_sample_project(app: AppHandle) -> Result<Project, String> {
+    let source = script_path(SAMPLE_CLIP)?;
+    if !source.is_file() {
+        return Err(format!(
+            "Sample clip is missing from the install: {}",
+            source.display()
+        ));
+    }
+    let (project, clip) = tauri::async_runtime::spawn_blocking(move || {
+        let project = create_project_entry()?;
+        let clip = path_safety::project_dir(&project.id)?
+            .join("media")
+            .join("sample.mp4");
+        copy_clip(&source, &clip)?;
+        Ok::<_, String>((project, clip))
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))??;
+    tracing::info!("Creating sample project {}", project.id);
+
+    // The user is waiting on this one in the wizard.
+    let permit = scheduler::acquire(
+        &app,
+        scheduler::JobKind::Ingest,
+        Some(scheduler::Priority::Interactive),
+        &project.id,
+    )
+    .await?;
+    let ingest = run_ingest_media(MediaIngestRequest {
+        project_id: project.id.clone(),
+        input: clip.to_string_lossy().to_string(),
+        generate_proxy: Some(true),
+        generate_waveform: Some(true),
+        priority: None,
+    })
+    .instrument(permit.span())
+    .await?;
+    drop(permit);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        seed_project(&project, &clip, &ingest)?;
+        read_projects()?
+            .into_iter()
+            .find(|entry| entry.id == project.id)
+            .ok_or_else(|| "Project not found.".to_string())
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
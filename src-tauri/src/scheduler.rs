@@ -0,0 +1,314 @@
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::settings::{self, SchedulerSettings};
+use crate::{control_api, event_bus, power, shutdown};
+
+pub const JOB_STATE_EVENT: &str = "scheduler://job-state";
+pub const QUEUE_PAUSED_EVENT: &str = "scheduler://queue-paused";
+/// `waiting_for` of queued jobs while the queue is paused.
+const PAUSED: &str = "paused";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobKind {
+    Ingest,
+    Transcription,
+    Planning,
+    Render,
+    /// A render running on a remote worker; only the upload and download
+    /// use this machine.
+    RemoteRender,
+}
+
+impl JobKind {
+    /// What a queued job of this kind is waiting for, for the UI.
+    fn resource(self) -> &'static str {
+        match self {
+            Self::Render | Self::Transcription => "gpu",
+            Self::Ingest => "disk",
+            Self::Planning => "cpu",
+            Self::RemoteRender => "network",
+        }
+    }
+
+    fn limit(self, settings: &SchedulerSettings) -> usize {
+        let limit = match self {
+            Self::Ingest => settings.max_ingests,
+            Self::Transcription => settings.max_transcriptions,
+            Self::Planning => settings.max_plannings,
+            Self::Render => settings.max_renders,
+            Self::RemoteRender => settings.max_remote_renders,
+        };
+        limit.max(1) as usize
+    }
+}
+
+/// Higher priorities start first; equal ones in the order they were queued.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Priority {
+    Background,
+    #[default]
+    Normal,
+    Interactive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Finished,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledJob {
+    pub job_id: u64,
+    pub kind: JobKind,
+    pub priority: Priority,
+    pub project_id: String,
+    pub state: JobState,
+    /// Place in this kind's queue, 1 being next up; 0 once running.
+    pub position: usize,
+    pub waiting_for: Option<&'static str>,
+}
+
+#[derive(Default)]
+struct Queue {
+    next_id: u64,
+    /// Queued jobs don't start while set; running ones carry on.
+    paused: bool,
+    running: Vec<ScheduledJob>,
+    waiting: Vec<ScheduledJob>,
+}
+
+impl Queue {
+    /// Waiting jobs of the same kind that go before `job`.
+    fn ahead_of(&self, job: &ScheduledJob) -> usize {
+        self.waiting
+            .iter()
+            .filter(|other| {
+                other.kind == job.kind
+                    && (other.priority > job.priority
+                        || (other.priority == job.priority && other.job_id < job.job_id))
+            })
+            .count()
+    }
+
+    fn running_of(&self, kind: JobKind) -> usize {
+        self.running.iter().filter(|job| job.kind == kind).count()
+    }
+}
+
+/// Limits how many jobs of each kind run at once so an ingest, a
+/// transcription and a render don't all fight over the machine. Registered
+/// as managed state; commands hold a [`JobPermit`] while they work.
+#[derive(Default)]
+pub struct Scheduler {
+    queue: Arc<(Mutex<Queue>, Condvar)>,
+}
+
+/// Frees the job's slot when dropped.
+pub struct JobPermit {
+    queue: Arc<(Mutex<Queue>, Condvar)>,
+    app: AppHandle,
+    job_id: u64,
+    span: tracing::Span,
+}
+
+impl JobPermit {
+    pub fn job_id(&self) -> u64 {
+        self.job_id
+    }
+
+    /// Span carrying the job id; run the job's work inside it so every log
+    /// line names the job.
+    pub fn span(&self) -> tracing::Span {
+        self.span.clone()
+    }
+}
+
+fn lock(queue: &Mutex<Queue>) -> MutexGuard<'_, Queue> {
+    queue
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn emit_state(job: &ScheduledJob) {
+    event_bus::publish(&job.project_id, JOB_STATE_EVENT, job);
+}
+
+impl Drop for JobPermit {
+    fn drop(&mut self) {
+        let (queue, changed) = &*self.queue;
+        let mut queue = lock(queue);
+        if let Some(index) = queue
+            .running
+            .iter()
+            .position(|job| job.job_id == self.job_id)
+        {
+            let mut job = queue.running.remove(index);
+            job.state = JobState::Finished;
+            self.span.in_scope(|| tracing::info!("Job finished"));
+            emit_state(&job);
+        }
+        // Under the queue lock so a job starting now can't race the release.
+        if queue.running.is_empty() && queue.waiting.is_empty() {
+            power::queue_drained(&self.app);
+        }
+        changed.notify_all();
+    }
+}
+
+fn acquire_blocking(
+    queue: Arc<(Mutex<Queue>, Condvar)>,
+    app: AppHandle,
+    kind: JobKind,
+    priority: Priority,
+    project_id: String,
+) -> JobPermit {
+    // Read once per job so changed limits apply to the next one queued.
+    let limits = settings::read_app_settings()
+        .map(|settings| settings.scheduler)
+        .unwrap_or_default();
+    let limit = kind.limit(&limits);
+
+    let (lock_queue, changed) = &*queue;
+    let mut guard = lock(lock_queue);
+    guard.next_id += 1;
+    let mut job = ScheduledJob {
+        job_id: guard.next_id,
+        kind,
+        priority,
+        project_id,
+        state: JobState::Queued,
+        position: 0,
+        waiting_for: Some(kind.resource()),
+    };
+    guard.waiting.push(job.clone());
+
+    let mut reported = None;
+    loop {
+        let ahead = guard.ahead_of(&job);
+        if !guard.paused && ahead == 0 && guard.running_of(kind) < limit {
+            break;
+        }
+        let position = ahead + 1;
+        let waiting_for = if guard.paused {
+            PAUSED
+        } else {
+            kind.resource()
+        };
+        if reported != Some((position, waiting_for)) {
+            tracing::info!(
+                "Job {} ({kind:?}) queued at position {position}, waiting for {waiting_for}",
+                job.job_id
+            );
+            job.position = position;
+            job.waiting_for = Some(waiting_for);
+            emit_state(&job);
+            reported = Some((position, waiting_for));
+        }
+        guard = changed
+            .wait(guard)
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+    }
+
+    guard.waiting.retain(|other| other.job_id != job.job_id);
+    job.state = JobState::Running;
+    job.position = 0;
+    job.waiting_for = None;
+    guard.running.push(job.clone());
+    power::job_started(&app);
+    emit_state(&job);
+    let span = tracing::info_span!("job", id = job.job_id, kind = ?kind);
+    span.in_scope(|| tracing::info!("Job started for project {}", job.project_id));
+    // Another waiter of a different kind may have been blocked behind this one.
+    changed.notify_all();
+    drop(guard);
+
+    JobPermit {
+        queue: Arc::clone(&queue),
+        app,
+        job_id: job.job_id,
+        span,
+    }
+}
+
+/// Waits for a free slot of `kind` and holds it until the permit is dropped.
+pub async fn acquire(
+    app: &AppHandle,
+    kind: JobKind,
+    priority: Option<Priority>,
+    project_id: &str,
+) -> Result<JobPermit, String> {
+    if shutdown::is_shutting_down() {
+        return Err("The app is quitting; the job was not started.".to_string());
+    }
+    let queue = Arc::clone(&app.state::<Scheduler>().queue);
+    let app = app.clone();
+    let project_id = project_id.to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        acquire_blocking(queue, app, kind, priority.unwrap_or_default(), project_id)
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))
+}
+
+/// Running jobs followed by queued ones, the order the jobs list shows.
+fn snapshot(queue: &Queue) -> Vec<ScheduledJob> {
+    let mut jobs = queue.running.clone();
+    let mut waiting = queue.waiting.clone();
+    for job in waiting.iter_mut() {
+        job.position = queue.ahead_of(job) + 1;
+        if queue.paused {
+            job.waiting_for = Some(PAUSED);
+        }
+    }
+    waiting.sort_by_key(|job| (job.kind.resource(), job.position));
+    jobs.extend(waiting);
+    jobs
+}
+
+pub fn active_jobs(app: &AppHandle) -> Vec<ScheduledJob> {
+    snapshot(&lock(&app.state::<Scheduler>().queue.0))
+}
+
+pub fn is_paused(app: &AppHandle) -> bool {
+    lock(&app.state::<Scheduler>().queue.0).paused
+}
+
+/// Holds queued jobs back, or lets them start again.
+pub fn set_paused(app: &AppHandle, paused: bool) {
+    let (queue, changed) = &*app.state::<Scheduler>().queue;
+    let mut guard = lock(queue);
+    if guard.paused == paused {
+        return;
+    }
+    guard.paused = paused;
+    tracing::info!("Job queue {}", if paused { "paused" } else { "resumed" });
+    let _ = app.emit(QUEUE_PAUSED_EVENT, paused);
+    control_api::publish(QUEUE_PAUSED_EVENT, &paused);
+    // Waiters re-check the flag and report themselves as paused or start.
+    changed.notify_all();
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetQueuePausedRequest {
+    paused: bool,
+}
+
+#[tauri::command]
+pub fn list_scheduled_jobs(scheduler: State<'_, Scheduler>) -> Vec<ScheduledJob> {
+    snapshot(&lock(&scheduler.queue.0))
+}
+
+#[tauri::command]
+pub fn set_queue_paused(app: AppHandle, request: SetQueuePausedRequest) {
+    set_paused(&app, request.paused);
+}
@@ -0,0 +1,548 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::shutdown::{self, ChildGuard};
+use crate::{
+    audio_devices, event_bus, ffmpeg, now_iso, path_safety, run_ingest_media, scheduler,
+    MediaIngestRequest,
+};
+
+const DEFAULT_FRAME_RATE: u32 = 30;
+/// How long ffmpeg gets to finish the file after being asked to stop.
+const STOP_TIMEOUT: Duration = Duration::from_secs(15);
+/// A capture that fails to open its device exits within this time.
+const START_CHECK: Duration = Duration::from_millis(800);
+/// Minimum gap between two duration events.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Captures in progress, one per project.
+static CAPTURES: Mutex<BTreeMap<String, Capture>> = Mutex::new(BTreeMap::new());
+
+struct Capture {
+    child: Child,
+    _tracked: ChildGuard,
+    path: PathBuf,
+    with_audio: bool,
+}
+
+/// What gets recorded. Displays count from 0, the main one first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CaptureSource {
+    Display(u32),
+    Window(String),
+    Camera(String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartScreenCaptureRequest {
+    project_id: String,
+    /// Display index; the main display when nothing else is picked.
+    display: Option<u32>,
+    /// A window instead of a display: its title on Windows, its X11 id on
+    /// Linux. macOS captures displays only.
+    window: Option<String>,
+    /// A webcam instead of a screen: its name or index.
+    camera: Option<String>,
+    include_mic: Option<bool>,
+    /// An input id from `list_audio_devices`; the system default when unset.
+    mic_device: Option<String>,
+    frame_rate: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopScreenCaptureRequest {
+    project_id: String,
+    /// Throws the recording away instead of ingesting it.
+    discard: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenCapture {
+    pub project_id: String,
+    pub path: String,
+    pub started_at: String,
+}
+
+/// Payload of `CAPTURE_PROGRESS_EVENT`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureProgress {
+    pub path: String,
+    pub duration_us: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenCaptureResult {
+    pub path: String,
+    pub duration_us: u64,
+    /// `ingest_media` output; None for a discarded recording.
+    pub ingest: Option<Value>,
+}
+
+fn captures() -> std::sync::MutexGuard<'static, BTreeMap<String, Capture>> {
+    CAPTURES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn unix_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
+fn source_of(request: &StartScreenCaptureRequest) -> Result<CaptureSource, String> {
+    let picked = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    match (
+        request.display,
+        picked(&request.window),
+        picked(&request.camera),
+    ) {
+        (None, None, None) => Ok(CaptureSource::Display(0)),
+        (Some(display), None, None) => Ok(CaptureSource::Display(display)),
+        (None, Some(window), None) => Ok(CaptureSource::Window(window)),
+        (None, None, Some(camera)) => Ok(CaptureSource::Camera(camera)),
+        _ => Err("Pick one of a display, a window or a camera.".to_string()),
+    }
+}
+
+/// AVFoundation's video device index for `Capture screen N`, from
+/// `ffmpeg -f avfoundation -list_devices true -i ""`.
+fn avfoundation_screen(listing: &str, display: u32) -> Option<String> {
+    let wanted = format!("Capture screen {display}");
+    listing
+        .lines()
+        .take_while(|line| !line.contains("AVFoundation audio devices:"))
+        .find_map(|line| {
+            let (_, rest) = line.split_once("] [")?;
+            let (index, name) = rest.split_once("] ")?;
+            (name.trim() == wanted).then(|| index.to_string())
+        })
+}
+
+/// `(x, y, width, height)` per monitor from `xrandr --listmonitors`, whose
+/// lines read ` 0: +*DP-1 1920/530x1080/300+0+0  DP-1`.
+fn parse_xrandr_monitors(listing: &str) -> Vec<(i64, i64, u64, u64)> {
+    listing
+        .lines()
+        .filter_map(|line| {
+            let geometry = line.split_whitespace().nth(2)?;
+            let (size, offsets) = geometry.split_once('+')?;
+            let (x, y) = offsets.split_once('+')?;
+            let (width, height) = size.split_once('x')?;
+            let width = width.split('/').next()?.parse().ok()?;
+            let height = height.split('/').next()?.parse().ok()?;
+            Some((x.parse().ok()?, y.parse().ok()?, width, height))
+        })
+        .collect()
+}
+
+/// `(x, y, width, height)` per monitor from the `X,Y,W,H` lines the
+/// PowerShell screen query prints.
+fn parse_screen_bounds(listing: &str) -> Vec<(i64, i64, u64, u64)> {
+    listing
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.trim().split(',').map(str::trim);
+            Some((
+                parts.next()?.parse().ok()?,
+                parts.next()?.parse().ok()?,
+                parts.next()?.parse().ok()?,
+                parts.next()?.parse().ok()?,
+            ))
+        })
+        .collect()
+}
+
+fn command_output(program: &str, args: &[&str]) -> String {
+    Command::new(program)
+        .args(args)
+        .output()
+        .map(|output| {
+            format!(
+                "{}\n{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            )
+        })
+        .unwrap_or_default()
+}
+
+fn monitor(
+    monitors: &[(i64, i64, u64, u64)],
+    display: u32,
+) -> Result<(i64, i64, u64, u64), String> {
+    monitors
+        .get(display as usize)
+        .copied()
+        .ok_or_else(|| format!("Display {display} not found."))
+}
+
+/// ffmpeg input arguments for `source`, with the microphone muxed in when
+/// `mic` is set (`Some(None)` for the default input).
+fn capture_inputs(
+    source: &CaptureSource,
+    mic: Option<Option<&str>>,
+    frame_rate: u32,
+) -> Result<Vec<String>, String> {
+    let rate = frame_rate.to_string();
+    let mut args = Vec::<String>::new();
+    if cfg!(target_os = "macos") {
+        // AVFoundation takes picture and sound as one `video:audio` input.
+        let video = match source {
+            CaptureSource::Display(display) => {
+                let listing = command_output(
+                    &ffmpeg::ffmpeg_binary().to_string_lossy(),
+                    &[
+                        "-hide_banner",
+                        "-f",
+                        "avfoundation",
+                        "-list_devices",
+                        "true",
+                        "-i",
+                        "",
+                    ],
+                );
+                args.extend(["-capture_cursor", "1"].map(str::to_string));
+                avfoundation_screen(&listing, *display).ok_or_else(|| {
+                    format!(
+                        "Display {display} not found; screen recording may need permission in \
+                         System Settings > Privacy & Security."
+                    )
+                })?
+            }
+            CaptureSource::Camera(camera) => camera.clone(),
+            CaptureSource::Window(_) => {
+                return Err("macOS captures whole displays; pick a display instead.".to_string())
+            }
+        };
+        let audio = match mic {
+            Some(device) => device.unwrap_or(audio_devices::DEFAULT_DEVICE_ID),
+            None => "none",
+        };
+        args.extend(["-f", "avfoundation", "-framerate", &rate].map(str::to_string));
+        args.extend(["-i".to_string(), format!("{video}:{audio}")]);
+        return Ok(args);
+    }
+
+    if cfg!(windows) {
+        match source {
+            CaptureSource::Display(display) => {
+                let bounds = command_output(
+                    "powershell",
+                    &[
+                        "-NoProfile",
+                        "-NonInteractive",
+                        "-Command",
+                        "Add-Type -AssemblyName System.Windows.Forms; \
+                         [System.Windows.Forms.Screen]::AllScreens | ForEach-Object { \
+                         '{0},{1},{2},{3}' -f $_.Bounds.X,$_.Bounds.Y,$_.Bounds.Width,$_.Bounds.Height }",
+                    ],
+                );
+                let (x, y, width, height) = monitor(&parse_screen_bounds(&bounds), *display)?;
+                args.extend([
+                    "-f".to_string(),
+                    "gdigrab".to_string(),
+                    "-framerate".to_string(),
+                    rate,
+                    "-offset_x".to_string(),
+                    x.to_string(),
+                    "-offset_y".to_string(),
+                    y.to_string(),
+                    "-video_size".to_string(),
+                    format!("{width}x{height}"),
+                    "-i".to_string(),
+                    "desktop".to_string(),
+                ]);
+            }
+            CaptureSource::Window(title) => args.extend([
+                "-f".to_string(),
+                "gdigrab".to_string(),
+                "-framerate".to_string(),
+                rate,
+                "-i".to_string(),
+                format!("title={title}"),
+            ]),
+            CaptureSource::Camera(camera) => args.extend([
+                "-f".to_string(),
+                "dshow".to_string(),
+                "-framerate".to_string(),
+                rate,
+                "-i".to_string(),
+                format!("video={camera}"),
+            ]),
+        }
+    } else {
+        let x_display = std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string());
+        match source {
+            CaptureSource::Display(display) => {
+                let listing = command_output("xrandr", &["--listmonitors"]);
+                let monitors = parse_xrandr_monitors(&listing);
+                args.extend(["-f", "x11grab", "-framerate", &rate].map(str::to_string));
+                // Without xrandr the whole X screen is recorded.
+                if !monitors.is_empty() || *display > 0 {
+                    let (x, y, width, height) = monitor(&monitors, *display)?;
+                    args.extend([
+                        "-video_size".to_string(),
+                        format!("{width}x{height}"),
+                        "-i".to_string(),
+                        format!("{x_display}+{x},{y}"),
+                    ]);
+                } else {
+                    args.extend(["-i".to_string(), x_display]);
+                }
+            }
+            CaptureSource::Window(window_id) => {
+                args.extend(["-f", "x11grab", "-framerate", &rate].map(str::to_string));
+                args.extend([
+                    "-window_id".to_string(),
+                    window_id.clone(),
+                    "-i".to_string(),
+                    x_display,
+                ]);
+            }
+            CaptureSource::Camera(camera) => {
+                let device = if camera.chars().all(|c| c.is_ascii_digit()) {
+                    format!("/dev/video{camera}")
+                } else {
+                    camera.clone()
+                };
+                args.extend(["-f", "v4l2", "-framerate", &rate, "-i", &device].map(str::to_string));
+            }
+        }
+    }
+    if let Some(device) = mic {
+        args.extend(audio_devices::capture_input(device)?);
+    }
+    Ok(args)
+}
+
+/// Publishes the recorded duration from ffmpeg's `-progress` key=value
+/// stream until the capture ends.
+fn report_progress(project_id: String, path: PathBuf, stdout: ChildStdout) {
+    let mut last = None::<Instant>;
+    let mut bytes = 0;
+    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+        if let Some(size) = line.strip_prefix("total_size=") {
+            bytes = size.trim().parse().unwrap_or(bytes);
+            continue;
+        }
+        // `out_time_ms` is in microseconds too, despite its name.
+        let Some(duration_us) = line
+            .strip_prefix("out_time_us=")
+            .and_then(|value| value.trim().parse::<u64>().ok())
+        else {
+            continue;
+        };
+        if last.is_some_and(|at| at.elapsed() < PROGRESS_INTERVAL) {
+            continue;
+        }
+        last = Some(Instant::now());
+        event_bus::publish(
+            &project_id,
+            event_bus::CAPTURE_PROGRESS_EVENT,
+            &CaptureProgress {
+                path: path.to_string_lossy().to_string(),
+                duration_us,
+                bytes,
+            },
+        );
+    }
+}
+
+fn start_blocking(request: StartScreenCaptureRequest) -> Result<ScreenCapture, String> {
+    let project_dir = path_safety::project_dir(&request.project_id)?;
+    if captures().contains_key(&request.project_id) {
+        return Err("A capture is already running for this project.".to_string());
+    }
+    let source = source_of(&request)?;
+    let with_audio = request.include_mic.unwrap_or(false);
+    let mic = with_audio.then_some(request.mic_device.as_deref());
+    let frame_rate = request
+        .frame_rate
+        .unwrap_or(DEFAULT_FRAME_RATE)
+        .clamp(1, 60);
+    let inputs = capture_inputs(&source, mic, frame_rate)?;
+
+    let dir = project_dir.join("media").join("recordings");
+    fs::create_dir_all(&dir).map_err(|error| format!("Failed creating recordings dir: {error}"))?;
+    // Matroska stays readable if the app dies before ffmpeg finalizes it.
+    let path = dir.join(format!("capture-{}.mkv", unix_millis()));
+
+    let mut command = Command::new(ffmpeg::ffmpeg_binary());
+    command
+        .args(["-hide_banner", "-loglevel", "error", "-nostats", "-y"])
+        .args(["-progress", "pipe:1"])
+        .args(&inputs)
+        // x264 wants even dimensions; window captures often aren't.
+        .args(["-vf", "scale=trunc(iw/2)*2:trunc(ih/2)*2"])
+        .args(["-c:v", "libx264", "-preset", "ultrafast", "-crf", "23"])
+        .args(["-pix_fmt", "yuv420p"]);
+    if with_audio {
+        command.args(["-c:a", "aac", "-b:a", "160k"]);
+    }
+    command
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = command
+        .spawn()
+        .map_err(|error| format!("Failed starting capture: {error}"))?;
+    let tracked = shutdown::track_child(&child);
+
+    thread::sleep(START_CHECK);
+    if let Ok(Some(_)) = child.try_wait() {
+        let mut stderr = String::new();
+        if let Some(mut pipe) = child.stderr.take() {
+            let _ = pipe.read_to_string(&mut stderr);
+        }
+        let _ = fs::remove_file(&path);
+        return Err(format!("Capture could not be started: {}", stderr.trim()));
+    }
+    if let Some(stdout) = child.stdout.take() {
+        let (project_id, path) = (request.project_id.clone(), path.clone());
+        thread::spawn(move || report_progress(project_id, path, stdout));
+    }
+
+    let capture = ScreenCapture {
+        project_id: request.project_id.clone(),
+        path: path.to_string_lossy().to_string(),
+        started_at: now_iso(),
+    };
+    captures().insert(
+        request.project_id,
+        Capture {
+            child,
+            _tracked: tracked,
+            path,
+            with_audio,
+        },
+    );
+    tracing::info!("Screen capture started ({source:?}): {}", capture.path);
+    Ok(capture)
+}
+
+async fn stop(request: StopScreenCaptureRequest) -> Result<ScreenCaptureResult, String> {
+    let mut capture = captures()
+        .remove(&request.project_id)
+        .ok_or_else(|| "No capture is running for this project.".to_string())?;
+    let (path, with_audio) = (capture.path.clone(), capture.with_audio);
+    tauri::async_runtime::spawn_blocking(move || {
+        ffmpeg::stop_capture(&mut capture.child, STOP_TIMEOUT);
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?;
+
+    let bytes = fs::metadata(&path).map_or(0, |meta| meta.len());
+    if bytes == 0 || request.discard.unwrap_or(false) {
+        let _ = fs::remove_file(&path);
+        if bytes == 0 {
+            return Err("The capture is empty; check the capture source.".to_string());
+        }
+        return Ok(ScreenCaptureResult {
+            path: path.to_string_lossy().to_string(),
+            duration_us: 0,
+            ingest: None,
+        });
+    }
+
+    let ingest = run_ingest_media(MediaIngestRequest {
+        project_id: request.project_id.clone(),
+        input: path.to_string_lossy().to_string(),
+        generate_proxy: None,
+        generate_waveform: Some(with_audio),
+        priority: Some(scheduler::Priority::Interactive),
+    })
+    .await?;
+    let source_path = ingest
+        .get("sourcePath")
+        .and_then(Value::as_str)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| path.clone());
+    // The media pool keeps its own link or copy of the recording.
+    if source_path != path {
+        let _ = fs::remove_file(&path);
+    }
+    let duration_us = ingest
+        .pointer("/media/durationSec")
+        .and_then(Value::as_f64)
+        .map_or(0, |seconds| (seconds * 1_000_000.0).round() as u64);
+    Ok(ScreenCaptureResult {
+        path: source_path.to_string_lossy().to_string(),
+        duration_us,
+        ingest: Some(ingest),
+    })
+}
+
+/// Records a display, window or webcam (optionally with the microphone)
+/// through ffmpeg's platform capture devices; `capture://progress` events
+/// carry the running duration.
+#[tauri::command]
+pub async fn start_screen_capture(
+    request: StartScreenCaptureRequest,
+) -> Result<ScreenCapture, String> {
+    tauri::async_runtime::spawn_blocking(move || start_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// Stops the capture and ingests the recording, into the media pool when
+/// it is enabled.
+#[tauri::command]
+pub async fn stop_screen_capture(
+    request: StopScreenCaptureRequest,
+) -> Result<ScreenCaptureResult, String> {
+    stop(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_displays_in_platform_listings() {
+        let avfoundation = "\
+[AVFoundation indev @ 0x1] AVFoundation video devices:
+[AVFoundation indev @ 0x1] [0] FaceTime HD Camera
+[AVFoundation indev @ 0x1] [1] Capture screen 0
+[AVFoundation indev @ 0x1] [2] Capture screen 1
+[AVFoundation indev @ 0x1] AVFoundation audio devices:
+[AVFoundation indev @ 0x1] [0] MacBook Pro Microphone
+";
+        assert_eq!(avfoundation_screen(avfoundation, 1).as_deref(), Some("2"));
+        assert_eq!(avfoundation_screen(avfoundation, 2), None);
+
+        let xrandr = "Monitors: 2\n 0: +*DP-1 2560/600x1440/340+0+0  DP-1\n \
+                      1: +HDMI-1 1920/530x1080/300+2560+180  HDMI-1";
+        assert_eq!(
+            parse_xrandr_monitors(xrandr),
+            vec![(0, 0, 2560, 1440), (2560, 180, 1920, 1080)]
+        );
+        assert_eq!(
+            parse_screen_bounds("0,0,1920,1080\r\n-1280,0,1280,1024\r\n"),
+            vec![(0, 0, 1920, 1080), (-1280, 0, 1280, 1024)]
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::settings::RetrySettings;
+use crate::{append_telemetry_event, now_iso};
+
+/// Rough cause of a script failure, read from its last stderr line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorClass {
+    Network,
+    ModelLoad,
+    Timeout,
+    InvalidInput,
+    Other,
+}
+
+const INVALID_INPUT_MARKERS: &[&str] = &[
+    "missing required argument",
+    "input file not found",
+    "invalid data found",
+    "no such file",
+    "unsupported",
+    "invalid",
+];
+
+const NETWORK_MARKERS: &[&str] = &[
+    "fetch failed",
+    "socket hang up",
+    "econnreset",
+    "econnrefused",
+    "enotfound",
+    "eai_again",
+    "network",
+    "rate limit",
+    "http 429",
+    "http 502",
+    "http 503",
+    "http 504",
+    "status 429",
+    "status 502",
+    "status 503",
+    "status 504",
+];
+
+const MODEL_LOAD_MARKERS: &[&str] = &[
+    "failed to load model",
+    "error loading model",
+    "model is loading",
+    "llama runner",
+    "out of memory",
+    "cuda error",
+];
+
+const TIMEOUT_MARKERS: &[&str] = &["timed out", "timeout", "etimedout"];
+
+pub fn classify_error(error: &str) -> ErrorClass {
+    // Scripts log progress to stderr too; the failure itself is the last line.
+    let last_line = error
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("")
+        .to_lowercase();
+    let has = |markers: &[&str]| markers.iter().any(|marker| last_line.contains(marker));
+    if has(INVALID_INPUT_MARKERS) {
+        ErrorClass::InvalidInput
+    } else if has(TIMEOUT_MARKERS) {
+        ErrorClass::Timeout
+    } else if has(NETWORK_MARKERS) {
+        ErrorClass::Network
+    } else if has(MODEL_LOAD_MARKERS) {
+        ErrorClass::ModelLoad
+    } else {
+        ErrorClass::Other
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedAttempt {
+    pub attempt: u32,
+    pub class: ErrorClass,
+    pub error: String,
+    /// Wait before the next attempt; None when this was the last one.
+    pub backoff_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryReport {
+    pub attempts: u32,
+    pub failures: Vec<FailedAttempt>,
+}
+
+fn backoff_ms(settings: &RetrySettings, attempt: u32) -> u64 {
+    let factor = 1u64 << attempt.saturating_sub(1).min(16);
+    settings
+        .initial_backoff_ms
+        .saturating_mul(factor)
+        .min(settings.max_backoff_ms)
+}
+
+fn record_failure(project_id: &str, pipeline: &str, failure: &FailedAttempt) {
+    let event = serde_json::json!({
+        "timestamp": now_iso(),
+        "projectId": project_id,
+        "pipeline": format!("{pipeline}-retry"),
+        "status": "ATTEMPT_FAILED",
+        "error": failure.error.chars().take(300).collect::<String>(),
+        "totalDurationMs": 0,
+        "stageDurationsMs": {},
+        "meta": {
+            "attempt": failure.attempt,
+            "errorClass": failure.class,
+            "backoffMs": failure.backoff_ms,
+        },
+    });
+    if let Err(error) = append_telemetry_event(project_id, &event) {
+        tracing::warn!("Failed recording retry attempt: {error}");
+    }
+}
+
+/// Runs `run` until it succeeds, the error is not one of the retryable
+/// classes, or `max_attempts` is reached, sleeping with exponential backoff
+/// in between. Each failure is recorded in telemetry. Stages that write
+/// checkpoints pick up where the failed attempt stopped.
+pub fn run_with_retry<T>(
+    project_id: &str,
+    pipeline: &str,
+    settings: &RetrySettings,
+    mut run: impl FnMut() -> Result<T, String>,
+) -> Result<(T, RetryReport), String> {
+    let max_attempts = settings.max_attempts.max(1);
+    let mut report = RetryReport {
+        attempts: 0,
+        failures: Vec::new(),
+    };
+    loop {
+        report.attempts += 1;
+        let error = match run() {
+            Ok(value) => return Ok((value, report)),
+            Err(error) => error,
+        };
+        let class = classify_error(&error);
+        let retry = report.attempts < max_attempts && settings.retry_on.contains(&class);
+        let failure = FailedAttempt {
+            attempt: report.attempts,
+            class,
+            error,
+            backoff_ms: retry.then(|| backoff_ms(settings, report.attempts)),
+        };
+        record_failure(project_id, pipeline, &failure);
+        let Some(wait) = failure.backoff_ms else {
+            return Err(if report.attempts > 1 {
+                format!(
+                    "{} (failed after {} attempts)",
+                    failure.error, report.attempts
+                )
+            } else {
+                failure.error
+            });
+        };
+        tracing::warn!(
+            "{pipeline} attempt {} failed ({class:?}); retrying in {wait} ms",
+            report.attempts
+        );
+        report.failures.push(failure);
+        thread::sleep(Duration::from_millis(wait));
+    }
+}
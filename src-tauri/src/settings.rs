@@ -0,0 +1,622 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::onboarding::OnboardingStep;
+use crate::script_retry::ErrorClass;
+use crate::workspace_root;
+use crate::{control_api, logging};
+
+/// Credentials and defaults for an S3-compatible bucket (AWS, GCS interop,
+/// MinIO, R2, ...).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ObjectStorageSettings {
+    /// Defaults to the regional AWS endpoint.
+    pub endpoint: Option<String>,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub default_bucket: Option<String>,
+    pub default_prefix: Option<String>,
+    /// `https://endpoint/bucket/key` instead of `https://bucket.endpoint/key`.
+    pub path_style: bool,
+    pub part_size_mb: u32,
+}
+
+impl Default for ObjectStorageSettings {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            region: "us-east-1".to_string(),
+            access_key_id: String::new(),
+            secret_access_key: String::new(),
+            default_bucket: None,
+            default_prefix: None,
+            path_style: true,
+            part_size_mb: 8,
+        }
+    }
+}
+
+/// LLM endpoint the native planner calls for cut and template planning.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlannerSettings {
+    /// `ollama`, or `openai` for any OpenAI-compatible `/chat/completions` API.
+    pub backend: String,
+    /// Defaults to the local Ollama server or `https://api.openai.com/v1`.
+    pub base_url: Option<String>,
+    /// Falls back to `OPENAI_API_KEY` from the AI config or environment.
+    pub api_key: Option<String>,
+    pub model: String,
+    /// Requests per plan, counting retries after malformed output.
+    pub max_attempts: u32,
+    pub timeout_secs: u64,
+}
+
+impl Default for PlannerSettings {
+    fn default() -> Self {
+        Self {
+            backend: "ollama".to_string(),
+            base_url: None,
+            api_key: None,
+            model: "qwen3:1.7b".to_string(),
+            max_attempts: 3,
+            timeout_secs: 180,
+        }
+    }
+}
+
+/// How often a failed pipeline script is re-run before giving up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetrySettings {
+    /// Runs per script, counting the first.
+    pub max_attempts: u32,
+    /// Doubles after each failure, up to `max_backoff_ms`.
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Failures of any other class (bad input, unknown) are not retried.
+    pub retry_on: Vec<ErrorClass>,
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 2_000,
+            max_backoff_ms: 30_000,
+            retry_on: vec![
+                ErrorClass::Network,
+                ErrorClass::ModelLoad,
+                ErrorClass::Timeout,
+            ],
+        }
+    }
+}
+
+/// How many jobs of each kind may run at once; the rest queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SchedulerSettings {
+    pub max_ingests: u32,
+    pub max_transcriptions: u32,
+    pub max_plannings: u32,
+    pub max_renders: u32,
+    /// Renders dispatched to remote workers at once.
+    pub max_remote_renders: u32,
+}
+
+impl Default for SchedulerSettings {
+    fn default() -> Self {
+        Self {
+            max_ingests: 2,
+            max_transcriptions: 1,
+            max_plannings: 1,
+            max_renders: 1,
+            max_remote_renders: 1,
+        }
+    }
+}
+
+/// Log levels for the log file and stderr.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LoggingSettings {
+    /// `error`, `warn`, `info`, `debug` or `trace`.
+    pub level: String,
+    /// Per-module overrides, e.g. `{ "whisper": "debug" }`.
+    pub modules: BTreeMap<String, String>,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            modules: BTreeMap::new(),
+        }
+    }
+}
+
+/// Desktop notifications when long pipeline jobs finish or fail.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    pub start_editing: bool,
+    pub edit_now: bool,
+    pub render: bool,
+    /// Stay quiet while an app window has focus.
+    pub only_in_background: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            start_editing: true,
+            edit_now: true,
+            render: true,
+            only_in_background: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PowerSettings {
+    /// Keep the machine from sleeping while jobs run or wait to run.
+    pub prevent_sleep: bool,
+}
+
+impl Default for PowerSettings {
+    fn default() -> Self {
+        Self {
+            prevent_sleep: true,
+        }
+    }
+}
+
+/// Localhost HTTP/WebSocket API for external tools; see `control_api`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ControlApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Bearer token clients must send. Generated when the API is enabled
+    /// with none set; clear it to get a new one.
+    pub token: String,
+}
+
+impl Default for ControlApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 47831,
+            token: String::new(),
+        }
+    }
+}
+
+/// A user command run at a hook point with the event as JSON on stdin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HookCommand {
+    pub command: String,
+    pub args: Vec<String>,
+    /// Fail the job when the hook fails; otherwise it is only reported.
+    pub fatal: bool,
+    pub timeout_secs: u64,
+}
+
+impl Default for HookCommand {
+    fn default() -> Self {
+        Self {
+            command: String::new(),
+            args: Vec::new(),
+            fatal: false,
+            timeout_secs: 60,
+        }
+    }
+}
+
+/// Commands to run around pipeline jobs, in order, per hook point.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HookSettings {
+    pub post_ingest: Vec<HookCommand>,
+    pub pre_render: Vec<HookCommand>,
+    pub post_render: Vec<HookCommand>,
+}
+
+/// Third-party pipeline stages from `plugins/`; none run until enabled.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PluginSettings {
+    /// Plugin names, in the order their stages run.
+    pub enabled: Vec<String>,
+    /// Passed to each plugin as `options`, by plugin name.
+    pub options: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletedStep {
+    pub step: OnboardingStep,
+    pub completed_at: String,
+}
+
+/// Where the first-run wizard got to, so it resumes after a restart.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OnboardingSettings {
+    /// Data directory picked in the first step.
+    pub data_dir: Option<String>,
+    pub completed: Vec<CompletedStep>,
+    pub finished_at: Option<String>,
+}
+
+/// Native file pickers' state.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PickerSettings {
+    /// Last directory each picker opened in, by picker id.
+    pub last_directories: BTreeMap<String, String>,
+    /// Every folder the user has picked, newest last; `open_path` may
+    /// reveal files inside them.
+    pub chosen_directories: Vec<String>,
+}
+
+/// Native pipeline stages served by an HTTP backend instead of their
+/// built-in runner.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PipelineSettings {
+    /// Endpoint to POST each stage's inputs to, by stage id.
+    pub http_stages: BTreeMap<String, String>,
+    pub http_timeout_secs: u64,
+}
+
+impl Default for PipelineSettings {
+    fn default() -> Self {
+        Self {
+            http_stages: BTreeMap::new(),
+            http_timeout_secs: 600,
+        }
+    }
+}
+
+/// Backends for `enhance_audio` beyond ffmpeg's built-in denoiser.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AudioEnhanceSettings {
+    /// `arnndn` model file for the `rnnoise` method.
+    pub rnnoise_model: Option<String>,
+    /// Enhancer run by the `external` method.
+    pub external_command: Option<String>,
+    /// `{input}` and `{output}` are replaced with the source file and the
+    /// WAV file the enhancer must write.
+    pub external_args: Vec<String>,
+    pub external_timeout_secs: u64,
+}
+
+impl Default for AudioEnhanceSettings {
+    fn default() -> Self {
+        Self {
+            rnnoise_model: None,
+            external_command: None,
+            external_args: vec!["{input}".to_string(), "{output}".to_string()],
+            external_timeout_secs: 1800,
+        }
+    }
+}
+
+/// Per-project cache of rendered clip segments; see `render_cache`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RenderCacheSettings {
+    pub enabled: bool,
+    /// Least recently used segments are evicted past this size.
+    pub max_size_mb: u64,
+}
+
+impl Default for RenderCacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_size_mb: 4096,
+        }
+    }
+}
+
+/// A machine running `lapaas-ai-editor-desktop worker`, added by address.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RemoteWorker {
+    pub id: String,
+    pub name: String,
+    /// `host:port` on the local network.
+    pub address: String,
+    /// The worker's token, as printed when it starts.
+    pub token: String,
+}
+
+/// Remote render workers; see `render_workers`. Workers speak plain HTTP,
+/// so the token and uploaded media cross the network unencrypted; only run
+/// worker mode on a network you trust.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RenderWorkerSettings {
+    /// Address this machine binds in worker mode; `0.0.0.0` for every
+    /// interface.
+    pub bind_address: String,
+    /// Port this machine listens on in worker mode.
+    pub port: u16,
+    /// Largest bundle or media upload accepted in worker mode.
+    pub max_upload_mb: u64,
+    /// Token this machine requires in worker mode. Generated the first time
+    /// worker mode starts without one.
+    pub token: String,
+    /// Workers this machine can send renders to.
+    pub workers: Vec<RemoteWorker>,
+}
+
+impl Default for RenderWorkerSettings {
+    fn default() -> Self {
+        Self {
+            bind_address: "0.0.0.0".to_string(),
+            port: 47832,
+            max_upload_mb: 50 * 1024,
+            token: String::new(),
+            workers: Vec::new(),
+        }
+    }
+}
+
+/// Deduplicated media import; see `media_pool`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MediaPoolSettings {
+    /// Ingest stores files once in `desktop/data/media-pool`, keyed by
+    /// content hash, instead of reading them from where they were picked.
+    pub enabled: bool,
+}
+
+impl Default for MediaPoolSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Ingest from http(s) URLs; see `url_ingest`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UrlIngestSettings {
+    /// Fetch web pages (YouTube and other video sites) through yt-dlp.
+    /// Direct links to media files download without it.
+    pub use_yt_dlp: bool,
+    /// yt-dlp binary; looked up on `PATH` when unset.
+    pub yt_dlp_path: Option<String>,
+    /// yt-dlp `-f` format selector.
+    pub yt_dlp_format: String,
+}
+
+impl Default for UrlIngestSettings {
+    fn default() -> Self {
+        Self {
+            use_yt_dlp: true,
+            yt_dlp_path: None,
+            yt_dlp_format: "bv*[ext=mp4]+ba[ext=m4a]/b[ext=mp4]/bv*+ba/b".to_string(),
+        }
+    }
+}
+
+/// Timeline playback through mpv; see `player`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlayerSettings {
+    /// mpv binary; looked up on `PATH` when unset.
+    pub mpv_path: Option<String>,
+}
+
+/// App-wide (not per-project) settings, stored in `desktop/data/settings.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    pub object_storage: ObjectStorageSettings,
+    /// Node.js binary for the pipeline scripts; found automatically when unset.
+    pub node_path: Option<String>,
+    /// Path aliases for source refs: `${NAME}/clip.mp4` resolves under
+    /// `mediaRoots.NAME`, so a project keeps working when its media moves
+    /// to another machine or a network share.
+    pub media_roots: BTreeMap<String, PathBuf>,
+    pub planner: PlannerSettings,
+    pub script_retry: RetrySettings,
+    pub scheduler: SchedulerSettings,
+    pub logging: LoggingSettings,
+    pub onboarding: OnboardingSettings,
+    pub pickers: PickerSettings,
+    pub notifications: NotificationSettings,
+    pub power: PowerSettings,
+    pub control_api: ControlApiSettings,
+    pub plugins: PluginSettings,
+    pub hooks: HookSettings,
+    pub pipeline: PipelineSettings,
+    pub audio_enhance: AudioEnhanceSettings,
+    pub render_cache: RenderCacheSettings,
+    pub render_workers: RenderWorkerSettings,
+    pub media_pool: MediaPoolSettings,
+    pub url_ingest: UrlIngestSettings,
+    pub player: PlayerSettings,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveAppSettingsRequest {
+    settings: AppSettings,
+}
+
+/// Sent to the frontend in place of stored secrets. Saving it back keeps the
+/// stored secret; saving an empty value clears it.
+pub const SECRET_MASK: &str = "********";
+
+fn mask(secret: &mut String) {
+    if !secret.is_empty() {
+        *secret = SECRET_MASK.to_string();
+    }
+}
+
+fn keep_stored(secret: &mut String, stored: &str) {
+    if secret == SECRET_MASK {
+        *secret = stored.to_string();
+    }
+}
+
+impl AppSettings {
+    /// Copy safe to hand to the frontend: keys and tokens are masked.
+    pub fn masked(mut self) -> Self {
+        mask(&mut self.object_storage.secret_access_key);
+        if let Some(api_key) = self.planner.api_key.as_mut() {
+            mask(api_key);
+        }
+        mask(&mut self.control_api.token);
+        mask(&mut self.render_workers.token);
+        for worker in &mut self.render_workers.workers {
+            mask(&mut worker.token);
+        }
+        self
+    }
+
+    /// Puts back the secrets that came in still masked by `masked`.
+    pub fn keep_stored_secrets(&mut self, stored: &AppSettings) {
+        keep_stored(
+            &mut self.object_storage.secret_access_key,
+            &stored.object_storage.secret_access_key,
+        );
+        if let Some(api_key) = self.planner.api_key.as_mut() {
+            keep_stored(api_key, stored.planner.api_key.as_deref().unwrap_or(""));
+        }
+        self.planner.api_key = self.planner.api_key.take().filter(|key| !key.is_empty());
+        keep_stored(&mut self.control_api.token, &stored.control_api.token);
+        keep_stored(&mut self.render_workers.token, &stored.render_workers.token);
+        for worker in &mut self.render_workers.workers {
+            let previous = stored
+                .render_workers
+                .workers
+                .iter()
+                .find(|previous| previous.id == worker.id);
+            if let Some(previous) = previous {
+                keep_stored(&mut worker.token, &previous.token);
+            }
+        }
+    }
+}
+
+fn settings_file_path() -> Result<PathBuf, String> {
+    let root = workspace_root()?;
+    Ok(root.join("desktop").join("data").join("settings.json"))
+}
+
+pub fn read_app_settings() -> Result<AppSettings, String> {
+    let file_path = settings_file_path()?;
+    if !file_path.exists() {
+        return Ok(AppSettings::default());
+    }
+    let raw = fs::read_to_string(&file_path)
+        .map_err(|error| format!("Failed reading settings file: {error}"))?;
+    serde_json::from_str::<AppSettings>(&raw)
+        .map_err(|error| format!("Invalid settings JSON: {error}"))
+}
+
+pub fn write_app_settings(settings: &AppSettings) -> Result<(), String> {
+    let file_path = settings_file_path()?;
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent).map_err(|error| format!("Failed creating data dir: {error}"))?;
+    }
+    let serialized = serde_json::to_string_pretty(settings)
+        .map_err(|error| format!("Settings serialize error: {error}"))?;
+    fs::write(&file_path, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing settings file: {error}"))
+}
+
+#[tauri::command]
+pub async fn get_app_settings() -> Result<AppSettings, String> {
+    tauri::async_runtime::spawn_blocking(|| read_app_settings().map(AppSettings::masked))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn save_app_settings(
+    app: AppHandle,
+    request: SaveAppSettingsRequest,
+) -> Result<AppSettings, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut settings = request.settings;
+        settings.keep_stored_secrets(&read_app_settings()?);
+        control_api::ensure_token(&mut settings.control_api)?;
+        write_app_settings(&settings)?;
+        logging::apply_settings(&settings.logging);
+        control_api::apply(&app, &settings.control_api);
+        Ok(settings.masked())
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masked_secrets_survive_a_save_round_trip() {
+        let mut stored = AppSettings::default();
+        stored.object_storage.secret_access_key = "s3-secret".to_string();
+        stored.planner.api_key = Some("sk-planner".to_string());
+        stored.control_api.token = "control-token".to_string();
+        stored.render_workers.workers.push(RemoteWorker {
+            id: "worker-1".to_string(),
+            token: "worker-token".to_string(),
+            ..RemoteWorker::default()
+        });
+
+        let mut incoming = stored.clone().masked();
+        assert_eq!(incoming.object_storage.secret_access_key, SECRET_MASK);
+        assert_eq!(incoming.planner.api_key.as_deref(), Some(SECRET_MASK));
+        assert_eq!(incoming.render_workers.workers[0].token, SECRET_MASK);
+        assert!(incoming.render_workers.token.is_empty());
+
+        incoming.control_api.token = "rotated-token".to_string();
+        incoming.keep_stored_secrets(&stored);
+        assert_eq!(incoming.object_storage.secret_access_key, "s3-secret");
+        assert_eq!(incoming.planner.api_key.as_deref(), Some("sk-planner"));
+        assert_eq!(incoming.control_api.token, "rotated-token");
+        assert_eq!(incoming.render_workers.workers[0].token, "worker-token");
+    }
+
+    #[test]
+    fn empty_secrets_clear_the_stored_value() {
+        let mut stored = AppSettings::default();
+        stored.object_storage.secret_access_key = "s3-secret".to_string();
+        stored.planner.api_key = Some("sk-planner".to_string());
+        stored.control_api.enabled = true;
+        stored.control_api.token = "control-token".to_string();
+
+        let mut incoming = stored.clone().masked();
+        incoming.object_storage.secret_access_key.clear();
+        incoming.planner.api_key = Some(String::new());
+        incoming.control_api.token.clear();
+        incoming.keep_stored_secrets(&stored);
+        assert!(incoming.object_storage.secret_access_key.is_empty());
+        assert_eq!(incoming.planner.api_key, None);
+
+        // A cleared control API token is replaced with a fresh one on save.
+        assert!(control_api::ensure_token(&mut incoming.control_api).unwrap());
+        assert!(!incoming.control_api.token.is_empty());
+        assert_ne!(incoming.control_api.token, "control-token");
+    }
+}
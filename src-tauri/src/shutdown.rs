@@ -0,0 +1,343 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::scheduler::{self, JobKind, JobState, Priority};
+use crate::{control_api, now_iso, path_safety, windows};
+
+/// How long children get to exit after SIGTERM before they are killed.
+const TERMINATE_GRACE: Duration = Duration::from_secs(5);
+/// How long quitting waits for store writes already in progress.
+const WRITE_FLUSH_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const INTERRUPTED_JOBS_FILE: &str = "interrupted_jobs.json";
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+/// Store writes in progress, counted by [`WriteGuard`].
+static WRITES: (Mutex<usize>, Condvar) = (Mutex::new(0), Condvar::new());
+/// Pids of pipeline scripts started through [`run_tracked`].
+static CHILDREN: Mutex<BTreeSet<u32>> = Mutex::new(BTreeSet::new());
+
+/// Held for the duration of a store write; quitting waits for it.
+pub struct WriteGuard(());
+
+impl Drop for WriteGuard {
+    fn drop(&mut self) {
+        let (count, drained) = &WRITES;
+        let mut count = count
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *count = count.saturating_sub(1);
+        drained.notify_all();
+    }
+}
+
+pub fn begin_write() -> WriteGuard {
+    *WRITES
+        .0
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) += 1;
+    WriteGuard(())
+}
+
+fn wait_for_writes(timeout: Duration) -> bool {
+    let (count, drained) = &WRITES;
+    let count = count
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let (count, _) = drained
+        .wait_timeout_while(count, timeout, |count| *count > 0)
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *count == 0
+}
+
+fn children() -> std::sync::MutexGuard<'static, BTreeSet<u32>> {
+    CHILDREN
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// `Command::output`, but the child is known to the shutdown coordinator
+/// so quitting can stop it instead of orphaning it.
+pub fn run_tracked(command: &mut Command) -> io::Result<Output> {
+    let child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let pid = child.id();
+    children().insert(pid);
+    let output = child.wait_with_output();
+    children().remove(&pid);
+    output
+}
+
+/// Keeps a child spawned elsewhere (one whose output is streamed, say)
+/// known to the shutdown coordinator until dropped.
+pub struct ChildGuard(u32);
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        children().remove(&self.0);
+    }
+}
+
+pub fn track_child(child: &Child) -> ChildGuard {
+    children().insert(child.id());
+    ChildGuard(child.id())
+}
+
+/// [`run_tracked`] for untrusted programs: `input` goes to stdin and the
+/// child is stopped once `timeout` passes, returning a `TimedOut` error.
+pub fn run_tracked_with_timeout(
+    command: &mut Command,
+    input: Vec<u8>,
+    timeout: Duration,
+) -> io::Result<Output> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let pid = child.id();
+    children().insert(pid);
+    // Pipes are drained on their own threads so a chatty child can't block
+    // on a full pipe while we wait for it.
+    let stdin = child.stdin.take().map(|mut stdin| {
+        thread::spawn(move || {
+            let _ = io::Write::write_all(&mut stdin, &input);
+        })
+    });
+    let drain = |pipe: Option<Box<dyn io::Read + Send>>| {
+        thread::spawn(move || {
+            let mut bytes = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut bytes);
+            }
+            bytes
+        })
+    };
+    let stdout = drain(child.stdout.take().map(|pipe| Box::new(pipe) as Box<_>));
+    let stderr = drain(child.stderr.take().map(|pipe| Box::new(pipe) as Box<_>));
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) if Instant::now() < deadline => thread::sleep(POLL_INTERVAL),
+            Ok(None) => {
+                terminate_child(&mut child);
+                break Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("timed out after {}s", timeout.as_secs()),
+                ));
+            }
+            Err(error) => break Err(error),
+        }
+    };
+    children().remove(&pid);
+    if let Some(stdin) = stdin {
+        let _ = stdin.join();
+    }
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    Ok(Output {
+        status: status?,
+        stdout,
+        stderr,
+    })
+}
+
+fn signal(pid: u32, force: bool) {
+    #[cfg(unix)]
+    let status = Command::new("kill")
+        .arg(if force { "-KILL" } else { "-TERM" })
+        .arg(pid.to_string())
+        .status();
+    #[cfg(windows)]
+    let status = {
+        let mut command = Command::new("taskkill");
+        command.args(["/PID", &pid.to_string(), "/T"]);
+        if force {
+            command.arg("/F");
+        }
+        command.status()
+    };
+    if let Err(error) = status {
+        tracing::warn!("Failed signalling process {pid}: {error}");
+    }
+}
+
+/// SIGTERM, then SIGKILL for whatever is still running after the grace
+/// period.
+fn terminate_scripts() {
+    let pids = children().clone();
+    if pids.is_empty() {
+        return;
+    }
+    tracing::info!("Stopping {} pipeline script(s)", pids.len());
+    for pid in &pids {
+        signal(*pid, false);
+    }
+    let deadline = Instant::now() + TERMINATE_GRACE;
+    while Instant::now() < deadline && !children().is_empty() {
+        thread::sleep(POLL_INTERVAL);
+    }
+    for pid in children().iter() {
+        tracing::warn!("Pipeline script {pid} ignored SIGTERM; killing it");
+        signal(*pid, true);
+    }
+}
+
+/// Stops a child this process owns the same way: SIGTERM, a grace period,
+/// then kill.
+pub fn terminate_child(child: &mut Child) {
+    if matches!(child.try_wait(), Ok(Some(_))) {
+        return;
+    }
+    signal(child.id(), false);
+    let deadline = Instant::now() + TERMINATE_GRACE;
+    while Instant::now() < deadline {
+        if matches!(child.try_wait(), Ok(Some(_))) {
+            return;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// A job that was queued or running when the app quit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedJob {
+    pub kind: JobKind,
+    pub priority: Priority,
+    pub project_id: String,
+    pub state: JobState,
+    pub interrupted_at: String,
+}
+
+fn interrupted_jobs_path() -> Result<PathBuf, String> {
+    Ok(path_safety::data_dir()?.join(INTERRUPTED_JOBS_FILE))
+}
+
+fn persist_queue(app: &AppHandle) -> Result<(), String> {
+    let now = now_iso();
+    let jobs = scheduler::active_jobs(app)
+        .into_iter()
+        .map(|job| InterruptedJob {
+            kind: job.kind,
+            priority: job.priority,
+            project_id: job.project_id,
+            state: job.state,
+            interrupted_at: now.clone(),
+        })
+        .collect::<Vec<_>>();
+    if jobs.is_empty() {
+        return Ok(());
+    }
+    let path = interrupted_jobs_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| format!("Failed creating data dir: {error}"))?;
+    }
+    let serialized =
+        serde_json::to_string_pretty(&jobs).map_err(|error| format!("Serialize error: {error}"))?;
+    fs::write(&path, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing interrupted jobs: {error}"))?;
+    tracing::info!("Saved {} unfinished job(s) for next launch", jobs.len());
+    Ok(())
+}
+
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+/// Runs once per process: holds back queued jobs, closes the control API,
+/// records unfinished jobs for the next launch, stops pipeline scripts and
+/// waits for store writes in progress. The backend server is stopped by
+/// the caller.
+pub fn run(app: &AppHandle) {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tracing::info!("Shutting down");
+    scheduler::set_paused(app, true);
+    control_api::stop(app);
+    if let Err(error) = persist_queue(app) {
+        tracing::warn!("Failed saving job queue: {error}");
+    }
+    terminate_scripts();
+    if !wait_for_writes(WRITE_FLUSH_TIMEOUT) {
+        tracing::warn!("Quitting with store writes still in progress");
+    }
+}
+
+/// Asks whether to cancel active jobs and quit or let them finish with the
+/// window hidden. Blocks on the dialog, so call it off the main thread.
+fn confirm_quit(app: &AppHandle, active: usize) -> bool {
+    app.dialog()
+        .message(format!(
+            "{active} job(s) are still running or queued. Cancel them and quit, or let them finish in the background?"
+        ))
+        .title("Jobs still running")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Cancel jobs and quit".to_string(),
+            "Finish in background".to_string(),
+        ))
+        .blocking_show()
+}
+
+fn quit_now(app: &AppHandle) {
+    run(app);
+    app.exit(0);
+}
+
+/// Quits, first asking what to do with active jobs if there are any. When
+/// the user picks "finish in background" the windows are hidden and the
+/// tray keeps the app alive.
+pub fn request_quit(app: &AppHandle) {
+    let active = scheduler::active_jobs(app).len();
+    if active == 0 {
+        quit_now(app);
+        return;
+    }
+    let app = app.clone();
+    thread::spawn(move || {
+        if confirm_quit(&app, active) {
+            quit_now(&app);
+        } else {
+            windows::hide_all(&app);
+        }
+    });
+}
+
+/// Unfinished jobs from the last session, cleared once read so the UI
+/// offers to re-run them only once.
+#[tauri::command]
+pub async fn take_interrupted_jobs() -> Result<Vec<InterruptedJob>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let path = interrupted_jobs_path()?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let raw = fs::read_to_string(&path)
+            .map_err(|error| format!("Failed reading interrupted jobs: {error}"))?;
+        let _ = fs::remove_file(&path);
+        serde_json::from_str(&raw)
+            .map_err(|error| format!("Invalid interrupted jobs JSON: {error}"))
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
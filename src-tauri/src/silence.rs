@@ -0,0 +1,194 @@
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cuts::{self, PlannedRemoveRange};
+use crate::ffmpeg::ffmpeg_binary;
+use crate::media::resolve_source_path;
+use crate::project_status::ProjectStatus;
+use crate::timeline::{build_rough_cut_timeline, normalize_ranges, RoughCutOptions, TimeRange};
+use crate::{mark_timeline_written, sponsors, write_timeline, Timeline};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectSilenceRequest {
+    project_id: String,
+    source_ref: Option<String>,
+    threshold_db: Option<f64>,
+    min_duration_ms: Option<u32>,
+    /// Silence kept on each side of a detected range so cuts don't clip speech.
+    padding_ms: Option<u32>,
+    /// Feed the ranges straight into the rough-cut builder.
+    build_rough_cut: Option<bool>,
+    fps: Option<u32>,
+    rough_cut_options: Option<RoughCutOptions>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceDetection {
+    pub source_path: String,
+    pub duration_us: u64,
+    pub threshold_db: f64,
+    pub min_duration_ms: u32,
+    pub ranges: Vec<TimeRange>,
+    pub total_silence_us: u64,
+    pub timeline: Option<Timeline>,
+}
+
+pub fn seconds_to_us(seconds: f64) -> u64 {
+    (seconds.max(0.0) * 1_000_000.0).round() as u64
+}
+
+pub fn value_after<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let start = line.find(key)? + key.len();
+    line[start..].split_whitespace().next()
+}
+
+/// Parses the `Duration: HH:MM:SS.xx` banner ffmpeg prints for its input.
+pub fn parse_ffmpeg_duration_us(stderr: &str) -> Option<u64> {
+    let stamp = stderr
+        .lines()
+        .find_map(|line| value_after(line, "Duration:"))?
+        .trim_end_matches(',');
+    let mut parts = stamp.split(':');
+    let hours = parts.next()?.parse::<f64>().ok()?;
+    let minutes = parts.next()?.parse::<f64>().ok()?;
+    let seconds = parts.next()?.parse::<f64>().ok()?;
+    Some(seconds_to_us(hours * 3600.0 + minutes * 60.0 + seconds))
+}
+
+/// Turns `silencedetect` log lines into ranges. A trailing `silence_start`
+/// without an end runs to the end of the media.
+pub fn parse_silencedetect(stderr: &str, duration_us: u64) -> Vec<TimeRange> {
+    let mut ranges = Vec::new();
+    let mut open_start: Option<u64> = None;
+
+    for line in stderr.lines() {
+        if let Some(start) = value_after(line, "silence_start:") {
+            open_start = start.parse::<f64>().ok().map(seconds_to_us);
+        } else if let Some(end) = value_after(line, "silence_end:") {
+            if let (Some(start_us), Ok(end)) = (open_start.take(), end.parse::<f64>()) {
+                ranges.push(TimeRange {
+                    start_us,
+                    end_us: seconds_to_us(end),
+                });
+            }
+        }
+    }
+    if let Some(start_us) = open_start {
+        ranges.push(TimeRange {
+            start_us,
+            end_us: duration_us,
+        });
+    }
+
+    normalize_ranges(ranges, duration_us)
+}
+
+fn shrink_ranges(ranges: Vec<TimeRange>, padding_us: u64) -> Vec<TimeRange> {
+    ranges
+        .into_iter()
+        .filter_map(|range| {
+            let start_us = range.start_us.saturating_add(padding_us);
+            let end_us = range.end_us.saturating_sub(padding_us);
+            (end_us > start_us).then_some(TimeRange { start_us, end_us })
+        })
+        .collect()
+}
+
+fn detect_silence_blocking(request: DetectSilenceRequest) -> Result<SilenceDetection, String> {
+    let source_path = resolve_source_path(&request.project_id, request.source_ref.as_deref())?;
+    let threshold_db = request.threshold_db.unwrap_or(-35.0).min(0.0);
+    let min_duration_ms = request.min_duration_ms.unwrap_or(800).max(50);
+
+    let output = Command::new(ffmpeg_binary())
+        .args(["-hide_banner", "-nostats", "-i"])
+        .arg(&source_path)
+        .args([
+            "-vn",
+            "-af",
+            &format!(
+                "silencedetect=noise={threshold_db}dB:d={}",
+                f64::from(min_duration_ms) / 1000.0
+            ),
+            "-f",
+            "null",
+            "-",
+        ])
+        .output()
+        .map_err(|error| format!("Failed to execute ffmpeg: {error}"))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg silencedetect failed: {}",
+            stderr.trim().chars().take(300).collect::<String>()
+        ));
+    }
+
+    let duration_us = parse_ffmpeg_duration_us(&stderr)
+        .ok_or_else(|| "ffmpeg did not report a media duration.".to_string())?;
+    let padding_us = u64::from(request.padding_ms.unwrap_or(150)) * 1_000;
+    let ranges = shrink_ranges(parse_silencedetect(&stderr, duration_us), padding_us);
+    let total_silence_us = ranges
+        .iter()
+        .map(|range| range.end_us - range.start_us)
+        .sum();
+
+    let timeline = if request.build_rough_cut.unwrap_or(false) {
+        let source_ref = request
+            .source_ref
+            .clone()
+            .unwrap_or_else(|| "source-video".to_string());
+        let fps = request.fps.unwrap_or(30);
+        let planned = ranges
+            .iter()
+            .map(|range| PlannedRemoveRange {
+                start_us: range.start_us,
+                end_us: range.end_us,
+                reason: Some("silence".to_string()),
+                confidence: None,
+            })
+            .collect();
+        let options = request.rough_cut_options.clone().unwrap_or_default();
+        cuts::record_proposed_cuts(
+            &request.project_id,
+            &source_ref,
+            duration_us,
+            fps,
+            planned,
+            options.clone(),
+        )?;
+        let timeline = build_rough_cut_timeline(
+            request.project_id.clone(),
+            duration_us,
+            fps,
+            source_ref,
+            ranges.clone(),
+            &options,
+            &sponsors::protected_ranges(&request.project_id),
+        );
+        write_timeline(&timeline)?;
+        mark_timeline_written(&request.project_id, ProjectStatus::RoughCutReady)?;
+        Some(timeline)
+    } else {
+        None
+    };
+
+    Ok(SilenceDetection {
+        source_path: source_path.to_string_lossy().to_string(),
+        duration_us,
+        threshold_db,
+        min_duration_ms,
+        ranges,
+        total_silence_us,
+        timeline,
+    })
+}
+
+#[tauri::command]
+pub async fn detect_silence(request: DetectSilenceRequest) -> Result<SilenceDetection, String> {
+    tauri::async_runtime::spawn_blocking(move || detect_silence_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,15 @@
+use std::path::Path;
+
+use tauri::AppHandle;
+
+use crate::{launch, windows};
+
+/// Runs in the first instance when the app is launched again; the second
+/// process exits right after handing over. Brings the main window forward
+/// and opens whatever the launch named: project bundles, media files
+/// (ingested like a drop) or deep links.
+pub fn handle_second_launch(app: &AppHandle, argv: Vec<String>, cwd: String) {
+    tracing::info!("Second launch handed over: {argv:?}");
+    windows::show_main_window(app);
+    launch::submit(app, launch::parse_args(&argv, Path::new(&cwd)));
+}
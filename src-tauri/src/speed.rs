@@ -0,0 +1,290 @@
+use serde::{Deserialize, Serialize};
+
+use crate::clip_flags;
+use crate::timeline_stats::content_end_us;
+use crate::{now_iso, read_timeline, write_timeline, Timeline, TimelineClip};
+
+pub const MIN_SPEED: f64 = 0.1;
+pub const MAX_SPEED: f64 = 16.0;
+
+/// A playback-rate change inside a clip. The speed holds until the next
+/// keyframe; before the first one the clip's own `speed` applies.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeedKeyframe {
+    /// Offset from the clip's `source_start_us`.
+    pub source_offset_us: u64,
+    pub speed: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetClipSpeedRequest {
+    project_id: String,
+    clip_id: String,
+    speed: f64,
+    /// Replaces the clip's keyframes; omit to clear them.
+    speed_keyframes: Option<Vec<SpeedKeyframe>>,
+    /// Shift everything after the clip by the length change. Defaults to true.
+    ripple: Option<bool>,
+    /// Change the clip, and ripple past clips, even when they are locked.
+    force: Option<bool>,
+}
+
+pub fn default_speed() -> f64 {
+    1.0
+}
+
+pub fn is_default_speed(speed: &f64) -> bool {
+    *speed == 1.0
+}
+
+fn has_speed_change(clip: &TimelineClip) -> bool {
+    !is_default_speed(&clip.speed) || !clip.speed_keyframes.is_empty()
+}
+
+/// `(source offset start, source offset end, speed)` spans covering the
+/// clip's source range.
+fn speed_spans(clip: &TimelineClip) -> Vec<(u64, u64, f64)> {
+    let source_len = clip.source_end_us.saturating_sub(clip.source_start_us);
+    let mut bounds = vec![(0, clip.speed)];
+    let mut keyframes = clip
+        .speed_keyframes
+        .iter()
+        .filter(|keyframe| keyframe.source_offset_us > 0 && keyframe.source_offset_us < source_len)
+        .collect::<Vec<_>>();
+    keyframes.sort_by_key(|keyframe| keyframe.source_offset_us);
+    bounds.extend(
+        keyframes
+            .into_iter()
+            .map(|keyframe| (keyframe.source_offset_us, keyframe.speed)),
+    );
+    bounds
+        .iter()
+        .enumerate()
+        .map(|(index, (start, speed))| {
+            let end = bounds.get(index + 1).map_or(source_len, |next| next.0);
+            (*start, end, *speed)
+        })
+        .collect()
+}
+
+fn span_timeline_len(source_len: u64, speed: f64) -> u64 {
+    (source_len as f64 / speed).round() as u64
+}
+
+/// Length the clip occupies on the timeline once its speed is applied.
+pub fn timeline_length_us(clip: &TimelineClip) -> u64 {
+    if !has_speed_change(clip) {
+        return clip.source_end_us.saturating_sub(clip.source_start_us);
+    }
+    speed_spans(clip)
+        .into_iter()
+        .map(|(start, end, speed)| span_timeline_len(end - start, speed))
+        .sum()
+}
+
+pub fn source_to_timeline_offset(clip: &TimelineClip, source_offset_us: u64) -> u64 {
+    if !has_speed_change(clip) {
+        return source_offset_us;
+    }
+    let mut timeline_offset = 0;
+    for (start, end, speed) in speed_spans(clip) {
+        if source_offset_us < end {
+            return timeline_offset
+                + span_timeline_len(source_offset_us.saturating_sub(start), speed);
+        }
+        timeline_offset += span_timeline_len(end - start, speed);
+    }
+    timeline_offset
+}
+
+pub fn timeline_to_source_offset(clip: &TimelineClip, timeline_offset_us: u64) -> u64 {
+    if !has_speed_change(clip) {
+        return timeline_offset_us;
+    }
+    let mut span_timeline_start = 0;
+    let mut last_end = 0;
+    for (start, end, speed) in speed_spans(clip) {
+        let span_len = span_timeline_len(end - start, speed);
+        if timeline_offset_us < span_timeline_start + span_len {
+            let into = (timeline_offset_us - span_timeline_start) as f64 * speed;
+            return (start + into.round() as u64).min(end);
+        }
+        span_timeline_start += span_len;
+        last_end = end;
+    }
+    last_end
+}
+
+/// Splits `clip` at a source timestamp strictly inside it. Keyframes go to
+/// the half they fall in, and the right half starts at the speed in effect
+/// at the cut. Both halves keep the clip's id.
+pub fn split_at_source(
+    clip: &TimelineClip,
+    source_us: u64,
+) -> Option<(TimelineClip, TimelineClip)> {
+    if source_us <= clip.source_start_us || source_us >= clip.source_end_us {
+        return None;
+    }
+    let offset = source_us - clip.source_start_us;
+    let timeline_at =
+        (clip.start_us + source_to_timeline_offset(clip, offset)).clamp(clip.start_us, clip.end_us);
+    let speed_at = speed_spans(clip)
+        .into_iter()
+        .find(|(start, end, _)| offset >= *start && offset < *end)
+        .map_or(clip.speed, |(_, _, speed)| speed);
+
+    let mut left = clip.clone();
+    left.source_end_us = source_us;
+    left.end_us = timeline_at;
+    left.speed_keyframes
+        .retain(|keyframe| keyframe.source_offset_us < offset);
+
+    let mut right = clip.clone();
+    right.source_start_us = source_us;
+    right.start_us = timeline_at;
+    right.speed = speed_at;
+    right.speed_keyframes = clip
+        .speed_keyframes
+        .iter()
+        .filter(|keyframe| keyframe.source_offset_us > offset)
+        .map(|keyframe| SpeedKeyframe {
+            source_offset_us: keyframe.source_offset_us - offset,
+            speed: keyframe.speed,
+        })
+        .collect();
+    Some((left, right))
+}
+
+/// Rejects rates outside `MIN_SPEED..=MAX_SPEED`, unordered keyframes and
+/// spans shorter than one frame at `fps`.
+pub fn validate_clip_speed(clip: &TimelineClip, fps: u32) -> Result<(), String> {
+    let in_range = |speed: f64| speed.is_finite() && (MIN_SPEED..=MAX_SPEED).contains(&speed);
+    if !in_range(clip.speed) {
+        return Err(format!(
+            "Clip {} speed must be between {MIN_SPEED} and {MAX_SPEED}, got {}.",
+            clip.clip_id, clip.speed
+        ));
+    }
+    let source_len = clip.source_end_us.saturating_sub(clip.source_start_us);
+    let mut previous = 0;
+    for keyframe in &clip.speed_keyframes {
+        if !in_range(keyframe.speed) {
+            return Err(format!(
+                "Clip {} keyframe speed must be between {MIN_SPEED} and {MAX_SPEED}, got {}.",
+                clip.clip_id, keyframe.speed
+            ));
+        }
+        if keyframe.source_offset_us <= previous || keyframe.source_offset_us >= source_len {
+            return Err(format!(
+                "Clip {} speed keyframes must be increasing and inside the clip's source range.",
+                clip.clip_id
+            ));
+        }
+        previous = keyframe.source_offset_us;
+    }
+    let frame_us = 1_000_000 / u64::from(fps.max(1));
+    let too_short = speed_spans(clip)
+        .into_iter()
+        .any(|(start, end, speed)| span_timeline_len(end - start, speed) < frame_us);
+    if too_short {
+        return Err(format!(
+            "Clip {} has a speed span shorter than one frame at {fps} fps.",
+            clip.clip_id
+        ));
+    }
+    Ok(())
+}
+
+/// Validates sped-up/slowed clips and sets their `end_us` from the source
+/// range. Clips at normal speed are left untouched.
+pub fn apply_clip_speeds(timeline: &mut Timeline) -> Result<(), String> {
+    let fps = timeline.fps;
+    for clip in timeline
+        .clips
+        .iter_mut()
+        .filter(|clip| has_speed_change(clip))
+    {
+        validate_clip_speed(clip, fps)?;
+        clip.end_us = clip.start_us + timeline_length_us(clip);
+    }
+    Ok(())
+}
+
+fn set_clip_speed_blocking(request: SetClipSpeedRequest) -> Result<Timeline, String> {
+    let mut timeline = read_timeline(&request.project_id)?;
+    let index = timeline
+        .clips
+        .iter()
+        .position(|clip| clip.clip_id == request.clip_id)
+        .ok_or_else(|| format!("Clip not found: {}", request.clip_id))?;
+    let locked_tracks = timeline
+        .tracks
+        .iter()
+        .filter(|track| track.locked)
+        .map(|track| track.id.clone())
+        .collect::<Vec<_>>();
+    if locked_tracks.contains(&timeline.clips[index].track_id) {
+        return Err(format!(
+            "Track {} is locked.",
+            timeline.clips[index].track_id
+        ));
+    }
+
+    let force = request.force.unwrap_or(false);
+    let clip = &mut timeline.clips[index];
+    if clip.clip_type != "source_clip" {
+        return Err(format!("Clip {} is not a source clip.", clip.clip_id));
+    }
+    clip_flags::ensure_unlocked(clip, force)?;
+    clip.speed = request.speed;
+    clip.speed_keyframes = request.speed_keyframes.unwrap_or_default();
+    validate_clip_speed(clip, timeline.fps)?;
+    let old_end = clip.end_us;
+    let new_end = clip.start_us + timeline_length_us(clip);
+    clip.end_us = new_end;
+
+    if request.ripple.unwrap_or(true) && new_end != old_end {
+        let moved =
+            |clip: &TimelineClip| clip.clip_id != request.clip_id && clip.start_us >= old_end;
+        if let Some(clip) = timeline
+            .clips
+            .iter()
+            .find(|clip| moved(clip) && locked_tracks.contains(&clip.track_id))
+        {
+            return Err(format!(
+                "Rippling would move clip {} on locked track {}.",
+                clip.clip_id, clip.track_id
+            ));
+        }
+        if let Some(clip) = timeline
+            .clips
+            .iter()
+            .find(|clip| moved(clip) && clip.locked && !force)
+        {
+            return Err(format!(
+                "Rippling would move locked clip {}; force it to move anyway.",
+                clip.clip_id
+            ));
+        }
+        for clip in timeline.clips.iter_mut().filter(|clip| moved(clip)) {
+            let length = clip.end_us - clip.start_us;
+            clip.start_us = (clip.start_us + new_end).saturating_sub(old_end);
+            clip.end_us = clip.start_us + length;
+        }
+    }
+
+    timeline.duration_us = content_end_us(&timeline);
+    timeline.version = timeline.version.saturating_add(1);
+    timeline.updated_at = now_iso();
+    write_timeline(&timeline)?;
+    Ok(timeline)
+}
+
+#[tauri::command]
+pub async fn set_clip_speed(request: SetClipSpeedRequest) -> Result<Timeline, String> {
+    tauri::async_runtime::spawn_blocking(move || set_clip_speed_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,431 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::clip_flags::ProtectedRange;
+use crate::timeline::TimeRange;
+use crate::transcript::{read_transcript, Transcript};
+use crate::{now_iso, path_safety, speed, Timeline};
+
+const SPONSORS_FILE: &str = "sponsors.json";
+const DEFAULT_SOURCE_REF: &str = "source-video";
+/// Ad reads longer than this are cut short by the detector.
+const MAX_AD_READ_US: u64 = 180_000_000;
+/// A read ends once this long passes without another ad phrase.
+const AD_READ_GAP_US: u64 = 20_000_000;
+/// Phrases that open an ad read.
+const INTRO_PHRASES: &[&str] = &[
+    "sponsored by",
+    "brought to you by",
+    "today's sponsor",
+    "this video's sponsor",
+    "sponsor of this video",
+    "sponsor of today's video",
+    "for sponsoring",
+    "partnered with",
+    "word from our sponsor",
+];
+/// Phrases typical of the rest of an ad read.
+const AD_PHRASES: &[&str] = &[
+    "promo code",
+    "use code",
+    "my code",
+    "discount",
+    "% off",
+    "percent off",
+    "free trial",
+    "link in the description",
+    "link below",
+    "sign up",
+    "first month",
+    "limited time",
+    "check them out",
+    "dot com",
+    ".com",
+];
+/// Phrases that hand back to the video.
+const OUTRO_PHRASES: &[&str] = &[
+    "back to the video",
+    "now back to",
+    "let's get back",
+    "back to today's",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SponsorOrigin {
+    Manual,
+    Detected,
+}
+
+/// A sponsor read in source time. AI cuts never remove it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsorSegment {
+    pub id: String,
+    pub source_ref: String,
+    pub start_us: u64,
+    pub end_us: u64,
+    pub label: String,
+    pub origin: SponsorOrigin,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkSponsorSegmentRequest {
+    project_id: String,
+    /// Source time.
+    range: TimeRange,
+    label: Option<String>,
+    source_ref: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSponsorSegmentsRequest {
+    project_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveSponsorSegmentRequest {
+    project_id: String,
+    segment_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectSponsorSegmentsRequest {
+    project_id: String,
+    /// Mark what was found, skipping reads that overlap a marked segment.
+    apply: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsorDetection {
+    pub candidates: Vec<SponsorSegment>,
+    pub marked: Vec<SponsorSegment>,
+}
+
+fn sponsors_path(project_id: &str) -> Result<PathBuf, String> {
+    Ok(path_safety::project_dir(project_id)?.join(SPONSORS_FILE))
+}
+
+pub fn read_segments(project_id: &str) -> Result<Vec<SponsorSegment>, String> {
+    let path = sponsors_path(project_id)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = fs::read_to_string(&path)
+        .map_err(|error| format!("Failed reading sponsor segments: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("Invalid sponsor segments JSON: {error}"))
+}
+
+fn write_segments(project_id: &str, segments: &[SponsorSegment]) -> Result<(), String> {
+    let path = sponsors_path(project_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating project dir: {error}"))?;
+    }
+    let serialized = serde_json::to_string_pretty(segments)
+        .map_err(|error| format!("Sponsor segments serialize error: {error}"))?;
+    fs::write(&path, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing sponsor segments: {error}"))
+}
+
+/// Marked sponsor reads as ranges the rough cut must keep. Unreadable
+/// markings are logged and ignored so a rebuild still goes through.
+pub fn protected_ranges(project_id: &str) -> Vec<ProtectedRange> {
+    let segments = match read_segments(project_id) {
+        Ok(segments) => segments,
+        Err(error) => {
+            tracing::warn!("Ignoring sponsor segments: {error}");
+            return Vec::new();
+        }
+    };
+    segments
+        .into_iter()
+        .map(|segment| ProtectedRange {
+            source_ref: segment.source_ref,
+            start_us: segment.start_us,
+            end_us: segment.end_us,
+            locked: false,
+            protected_from_ai: true,
+        })
+        .collect()
+}
+
+/// Timeline spans showing marked sponsor reads, through the source clips
+/// that use them.
+pub fn timeline_spans(timeline: &Timeline, segments: &[SponsorSegment]) -> Vec<TimeRange> {
+    let mut spans = Vec::new();
+    for segment in segments {
+        for clip in timeline.clips.iter().filter(|clip| {
+            clip.clip_type == "source_clip"
+                && clip.source_ref == segment.source_ref
+                && clip.source_start_us < segment.end_us
+                && clip.source_end_us > segment.start_us
+        }) {
+            let start = segment.start_us.max(clip.source_start_us) - clip.source_start_us;
+            let end = segment.end_us.min(clip.source_end_us) - clip.source_start_us;
+            spans.push(TimeRange {
+                start_us: clip.start_us + speed::source_to_timeline_offset(clip, start),
+                end_us: clip.start_us + speed::source_to_timeline_offset(clip, end),
+            });
+        }
+    }
+    spans.sort_by_key(|span| span.start_us);
+    spans
+}
+
+fn contains_any(text: &str, phrases: &[&str]) -> usize {
+    phrases
+        .iter()
+        .filter(|phrase| text.contains(*phrase))
+        .count()
+}
+
+/// The sponsor's name from the words after "sponsored by" and the like.
+fn sponsor_name(text: &str) -> Option<String> {
+    let lower = text.to_ascii_lowercase();
+    let (phrase, at) = ["sponsored by", "brought to you by", "partnered with"]
+        .iter()
+        .find_map(|phrase| Some((*phrase, lower.find(phrase)?)))?;
+    let mut words = Vec::new();
+    for word in text[at + phrase.len()..].split_whitespace().take(3) {
+        let trimmed = word.trim_end_matches(|c: char| c.is_ascii_punctuation());
+        if !trimmed.is_empty() {
+            words.push(trimmed);
+        }
+        if trimmed.len() != word.len() {
+            break;
+        }
+    }
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// Finds ad reads in the transcript: a read opens with a sponsor phrase,
+/// runs on while ad phrases keep coming and ends at a hand-back phrase.
+pub fn detect_ad_reads(transcript: &Transcript) -> Vec<SponsorSegment> {
+    let source_ref = transcript
+        .source_ref
+        .clone()
+        .unwrap_or_else(|| DEFAULT_SOURCE_REF.to_string());
+    let segments = &transcript.segments;
+    let mut found = Vec::new();
+    let mut index = 0;
+    while index < segments.len() {
+        let opening = &segments[index];
+        let text = opening.text.to_lowercase();
+        if contains_any(&text, INTRO_PHRASES) == 0 {
+            index += 1;
+            continue;
+        }
+        let mut hits = contains_any(&text, AD_PHRASES);
+        let mut end_us = opening.end_us;
+        let mut next = index + 1;
+        while let Some(segment) = segments.get(next) {
+            let text = segment.text.to_lowercase();
+            if contains_any(&text, OUTRO_PHRASES) > 0
+                || segment.end_us.saturating_sub(opening.start_us) > MAX_AD_READ_US
+                || segment.start_us.saturating_sub(end_us) > AD_READ_GAP_US
+            {
+                break;
+            }
+            let segment_hits = contains_any(&text, AD_PHRASES);
+            if segment_hits > 0 {
+                hits += segment_hits;
+                end_us = segment.end_us;
+            }
+            next += 1;
+        }
+        let label = sponsor_name(&opening.text)
+            .map(|name| format!("Sponsor: {name}"))
+            .unwrap_or_else(|| "Sponsor".to_string());
+        found.push(SponsorSegment {
+            id: format!("sponsor-detected-{}", found.len() + 1),
+            source_ref: source_ref.clone(),
+            start_us: opening.start_us,
+            end_us,
+            label,
+            origin: SponsorOrigin::Detected,
+            confidence: Some((0.5 + 0.1 * hits as f64).min(0.95)),
+            created_at: now_iso(),
+        });
+        index = next.max(index + 1);
+    }
+    found
+}
+
+fn segment_id() -> String {
+    format!("sponsor-{}", now_iso().replace([':', '.'], "-"))
+}
+
+fn mark_sponsor_segment_blocking(
+    request: MarkSponsorSegmentRequest,
+) -> Result<SponsorSegment, String> {
+    if request.range.end_us <= request.range.start_us {
+        return Err("Sponsor segment must end after it starts.".to_string());
+    }
+    let mut segments = read_segments(&request.project_id)?;
+    let segment = SponsorSegment {
+        id: segment_id(),
+        source_ref: request
+            .source_ref
+            .map(|source| source.trim().to_string())
+            .filter(|source| !source.is_empty())
+            .unwrap_or_else(|| DEFAULT_SOURCE_REF.to_string()),
+        start_us: request.range.start_us,
+        end_us: request.range.end_us,
+        label: request
+            .label
+            .map(|label| label.trim().to_string())
+            .filter(|label| !label.is_empty())
+            .unwrap_or_else(|| "Sponsor".to_string()),
+        origin: SponsorOrigin::Manual,
+        confidence: None,
+        created_at: now_iso(),
+    };
+    segments.push(segment.clone());
+    segments.sort_by_key(|segment| segment.start_us);
+    write_segments(&request.project_id, &segments)?;
+    Ok(segment)
+}
+
+fn detect_sponsor_segments_blocking(
+    request: DetectSponsorSegmentsRequest,
+) -> Result<SponsorDetection, String> {
+    let transcript = read_transcript(&request.project_id)?;
+    let candidates = detect_ad_reads(&transcript);
+    let mut marked = Vec::new();
+    if request.apply.unwrap_or(false) {
+        let mut segments = read_segments(&request.project_id)?;
+        for (index, candidate) in candidates.iter().enumerate() {
+            let overlaps = segments.iter().any(|segment| {
+                segment.source_ref == candidate.source_ref
+                    && segment.start_us < candidate.end_us
+                    && candidate.start_us < segment.end_us
+            });
+            if overlaps {
+                continue;
+            }
+            let segment = SponsorSegment {
+                id: format!("{}-{}", segment_id(), index + 1),
+                ..candidate.clone()
+            };
+            segments.push(segment.clone());
+            marked.push(segment);
+        }
+        segments.sort_by_key(|segment| segment.start_us);
+        write_segments(&request.project_id, &segments)?;
+    }
+    Ok(SponsorDetection { candidates, marked })
+}
+
+/// Marks a source range as a sponsor read. Rough cuts built afterwards keep
+/// it and chapters over it are flagged.
+#[tauri::command]
+pub async fn mark_sponsor_segment(
+    request: MarkSponsorSegmentRequest,
+) -> Result<SponsorSegment, String> {
+    tauri::async_runtime::spawn_blocking(move || mark_sponsor_segment_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn list_sponsor_segments(
+    request: ListSponsorSegmentsRequest,
+) -> Result<Vec<SponsorSegment>, String> {
+    tauri::async_runtime::spawn_blocking(move || read_segments(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn remove_sponsor_segment(
+    request: RemoveSponsorSegmentRequest,
+) -> Result<Vec<SponsorSegment>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut segments = read_segments(&request.project_id)?;
+        let before = segments.len();
+        segments.retain(|segment| segment.id != request.segment_id);
+        if segments.len() == before {
+            return Err(format!("Sponsor segment not found: {}", request.segment_id));
+        }
+        write_segments(&request.project_id, &segments)?;
+        Ok(segments)
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// Looks for ad reads in the transcript. Nothing is marked unless `apply`
+/// is set.
+#[tauri::command]
+pub async fn detect_sponsor_segments(
+    request: DetectSponsorSegmentsRequest,
+) -> Result<SponsorDetection, String> {
+    tauri::async_runtime::spawn_blocking(move || detect_sponsor_segments_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::TranscriptSegment;
+    use serde_json::Map;
+
+    fn segment(start_s: u64, end_s: u64, text: &str) -> TranscriptSegment {
+        TranscriptSegment {
+            id: format!("seg-{start_s}"),
+            start_us: start_s * 1_000_000,
+            end_us: end_s * 1_000_000,
+            text: text.to_string(),
+            confidence: None,
+            speaker: None,
+            words: Vec::new(),
+            extra: Map::new(),
+        }
+    }
+
+    #[test]
+    fn finds_an_ad_read_between_intro_and_hand_back() {
+        let transcript = Transcript {
+            project_id: "project-test".to_string(),
+            language: None,
+            source_ref: None,
+            segments: vec![
+                segment(0, 30, "Today we're building a bookshelf."),
+                segment(
+                    30,
+                    40,
+                    "This video is sponsored by Acme Tools, the best kit around.",
+                ),
+                segment(40, 55, "Use code BUILD for 20% off your first order."),
+                segment(55, 60, "The link is in the description below, link below."),
+                segment(60, 70, "Now back to the video."),
+                segment(70, 90, "First, measure the boards."),
+            ],
+            speaker_labels: Default::default(),
+            word_count: 0,
+            updated_at: String::new(),
+            extra: Map::new(),
+        };
+        let found = detect_ad_reads(&transcript);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].label, "Sponsor: Acme Tools");
+        assert_eq!(
+            (found[0].start_us, found[0].end_us),
+            (30_000_000, 60_000_000)
+        );
+        assert_eq!(found[0].source_ref, "source-video");
+        assert!(found[0].confidence.unwrap() > 0.5);
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::SystemTime;
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::{Project, Timeline};
+
+/// Set once the app is up; before that (and in CLI mode) every read goes to
+/// disk.
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// What a cached value was parsed from. Anything else touching the file
+/// (the Node backend, `repair_store`, a restore) changes it and the next
+/// read goes back to disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Stamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+struct Cached<T> {
+    stamp: Stamp,
+    value: T,
+}
+
+/// Parsed copies of `projects.json` and each project's `timeline.json`.
+#[derive(Default)]
+pub struct StoreCache {
+    projects: Mutex<Option<Cached<Vec<Project>>>>,
+    timelines: Mutex<HashMap<String, Cached<Timeline>>>,
+}
+
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+fn cache() -> Option<State<'static, StoreCache>> {
+    APP.get()?.try_state::<StoreCache>()
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let meta = fs::metadata(path).ok()?;
+    Some(Stamp {
+        modified: meta.modified().ok()?,
+        len: meta.len(),
+    })
+}
+
+/// The cached project list if `path` hasn't changed since it was stored.
+pub fn projects(path: &Path) -> Option<Vec<Project>> {
+    let cache = cache()?;
+    let current = stamp(path)?;
+    let cached = lock(&cache.projects);
+    cached
+        .as_ref()
+        .filter(|cached| cached.stamp == current)
+        .map(|cached| cached.value.clone())
+}
+
+/// Records the project list just read from or written to `path`.
+pub fn store_projects(path: &Path, projects: &[Project]) {
+    let (Some(cache), Some(stamp)) = (cache(), stamp(path)) else {
+        return;
+    };
+    *lock(&cache.projects) = Some(Cached {
+        stamp,
+        value: projects.to_vec(),
+    });
+}
+
+/// The cached timeline if `path` hasn't changed since it was stored.
+pub fn timeline(project_id: &str, path: &Path) -> Option<Timeline> {
+    let cache = cache()?;
+    let mut timelines = lock(&cache.timelines);
+    let Some(current) = stamp(path) else {
+        // Deleted; drop the entry rather than keep it around.
+        timelines.remove(project_id);
+        return None;
+    };
+    timelines
+        .get(project_id)
+        .filter(|cached| cached.stamp == current)
+        .map(|cached| cached.value.clone())
+}
+
+/// Records the timeline just read from or written to `path`.
+pub fn store_timeline(project_id: &str, path: &Path, timeline: &Timeline) {
+    let (Some(cache), Some(stamp)) = (cache(), stamp(path)) else {
+        return;
+    };
+    lock(&cache.timelines).insert(
+        project_id.to_string(),
+        Cached {
+            stamp,
+            value: timeline.clone(),
+        },
+    );
+}
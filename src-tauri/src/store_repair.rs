@@ -0,0 +1,314 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    backups, now_iso, projects_file_path, timeline_file_path, timeline_stats, workspace_root,
+    write_projects, write_timeline, Project, Timeline, TimelineClip, TimelineTrack,
+};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairStoreRequest {
+    /// Limit the timeline check to one project; all projects when omitted.
+    project_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RepairOutcome {
+    /// Rewritten from what could be salvaged.
+    Repaired,
+    /// Nothing usable; the file was moved aside and not replaced.
+    Unrecoverable,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreFileRepair {
+    pub path: String,
+    pub project_id: Option<String>,
+    pub outcome: RepairOutcome,
+    /// Where the corrupt original was moved.
+    pub moved_to: String,
+    pub recovered: Vec<String>,
+    pub lost: Vec<String>,
+    /// Next step for the user, e.g. a backup to restore.
+    pub hint: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreRepairReport {
+    pub files_checked: usize,
+    /// Only files that failed to parse are listed.
+    pub repairs: Vec<StoreFileRepair>,
+}
+
+/// Renames a corrupt file to `<name>.corrupt-<timestamp>` so it's kept for
+/// inspection but no longer read.
+fn move_aside(file_path: &Path) -> Result<PathBuf, String> {
+    let file_name = file_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stamp = now_iso();
+    let mut target = file_path.with_file_name(format!("{file_name}.corrupt-{stamp}"));
+    let mut suffix = 1;
+    while target.exists() {
+        target = file_path.with_file_name(format!("{file_name}.corrupt-{stamp}-{suffix}"));
+        suffix += 1;
+    }
+    fs::rename(file_path, &target)
+        .map_err(|error| format!("Failed moving corrupt file aside: {error}"))?;
+    Ok(target)
+}
+
+/// Pulls the complete values out of a JSON array, stopping at the first one
+/// that doesn't parse. Handles files truncated by a crash mid-write.
+fn salvage_array_values(raw: &str) -> Vec<Value> {
+    let Some(start) = raw.find('[') else {
+        return Vec::new();
+    };
+    let mut rest = &raw[start + 1..];
+    let mut values = Vec::new();
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        if rest.is_empty() || rest.starts_with(']') {
+            return values;
+        }
+        let mut stream = serde_json::Deserializer::from_str(rest).into_iter::<Value>();
+        match stream.next() {
+            Some(Ok(value)) => {
+                values.push(value);
+                rest = &rest[stream.byte_offset()..];
+            }
+            _ => return values,
+        }
+    }
+}
+
+fn entry_label(value: &Value, key: &str, index: usize) -> String {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("entry {index}"))
+}
+
+fn repair_projects() -> Result<Option<StoreFileRepair>, String> {
+    let file_path = projects_file_path()?;
+    if !file_path.exists() {
+        return Ok(None);
+    }
+    let raw = fs::read_to_string(&file_path)
+        .map_err(|error| format!("Failed reading projects store: {error}"))?;
+    if serde_json::from_str::<Vec<Project>>(&raw).is_ok() {
+        return Ok(None);
+    }
+
+    let entries = match serde_json::from_str::<Vec<Value>>(&raw) {
+        Ok(entries) => entries,
+        Err(_) => salvage_array_values(&raw),
+    };
+    let mut recovered = Vec::new();
+    let mut projects = Vec::new();
+    let mut lost = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        match serde_json::from_value::<Project>(entry.clone()) {
+            Ok(project) => {
+                recovered.push(project.id.clone());
+                projects.push(project);
+            }
+            Err(error) => lost.push(format!("{}: {error}", entry_label(entry, "id", index))),
+        }
+    }
+    // Directories of projects that didn't make it are still on disk.
+    let hint = (projects.is_empty() || !lost.is_empty()).then(|| {
+        "Project folders are untouched; re-create lost projects to adopt them.".to_string()
+    });
+
+    let moved_to = move_aside(&file_path)?;
+    write_projects(&projects)?;
+    tracing::info!(
+        "Repaired projects store: {} recovered, {} lost",
+        recovered.len(),
+        lost.len()
+    );
+    Ok(Some(StoreFileRepair {
+        path: file_path.to_string_lossy().to_string(),
+        project_id: None,
+        outcome: RepairOutcome::Repaired,
+        moved_to: moved_to.to_string_lossy().to_string(),
+        recovered,
+        lost,
+        hint,
+    }))
+}
+
+fn salvage_items<T: for<'de> Deserialize<'de>>(
+    value: &Value,
+    key: &str,
+    id_key: &str,
+    kind: &str,
+    recovered: &mut Vec<String>,
+    lost: &mut Vec<String>,
+) -> Vec<T> {
+    let mut items = Vec::new();
+    let entries = value
+        .get(key)
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    for (index, entry) in entries.iter().enumerate() {
+        let label = entry_label(entry, id_key, index);
+        match serde_json::from_value::<T>(entry.clone()) {
+            Ok(item) => {
+                items.push(item);
+                recovered.push(format!("{kind} {label}"));
+            }
+            Err(error) => lost.push(format!("{kind} {label}: {error}")),
+        }
+    }
+    items
+}
+
+/// Rebuilds a timeline from whichever tracks and clips still parse.
+fn salvage_timeline(
+    project_id: &str,
+    value: &Value,
+    recovered: &mut Vec<String>,
+    lost: &mut Vec<String>,
+) -> Timeline {
+    let text = |key: &str, fallback: String| {
+        value
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or(fallback)
+    };
+    let tracks = salvage_items::<TimelineTrack>(value, "tracks", "id", "track", recovered, lost);
+    let clips = salvage_items::<TimelineClip>(value, "clips", "clipId", "clip", recovered, lost);
+    let now = now_iso();
+    let mut timeline = Timeline {
+        id: text("id", format!("timeline-{project_id}")),
+        project_id: project_id.to_string(),
+        version: value
+            .get("version")
+            .and_then(Value::as_u64)
+            .and_then(|version| u32::try_from(version).ok())
+            .unwrap_or(0)
+            .saturating_add(1),
+        status: text("status", "REPAIRED".to_string()),
+        fps: value
+            .get("fps")
+            .and_then(Value::as_u64)
+            .and_then(|fps| u32::try_from(fps).ok())
+            .filter(|fps| *fps > 0)
+            .unwrap_or(30),
+        duration_us: 0,
+        created_at: text("createdAt", now.clone()),
+        updated_at: now,
+        tracks,
+        clips,
+        meta: value.get("meta").cloned().unwrap_or(Value::Null),
+    };
+    timeline.duration_us = timeline_stats::content_end_us(&timeline);
+    timeline
+}
+
+fn repair_timeline(project_id: &str) -> Result<Option<StoreFileRepair>, String> {
+    let file_path = timeline_file_path(project_id)?;
+    if !file_path.exists() {
+        return Ok(None);
+    }
+    let raw = fs::read_to_string(&file_path)
+        .map_err(|error| format!("Failed reading timeline file: {error}"))?;
+    if serde_json::from_str::<Timeline>(&raw).is_ok() {
+        return Ok(None);
+    }
+
+    let mut recovered = Vec::new();
+    let mut lost = Vec::new();
+    let salvaged = serde_json::from_str::<Value>(&raw)
+        .ok()
+        .filter(Value::is_object)
+        .map(|value| salvage_timeline(project_id, &value, &mut recovered, &mut lost));
+    let moved_to = move_aside(&file_path)?;
+
+    let (outcome, hint) = match salvaged {
+        Some(timeline) => {
+            write_timeline(&timeline)?;
+            (RepairOutcome::Repaired, None)
+        }
+        None => {
+            lost.push("timeline (not valid JSON)".to_string());
+            let latest_backup = backups::list_backups_for(project_id)
+                .ok()
+                .and_then(|backups| {
+                    backups
+                        .into_iter()
+                        .find(|backup| backup.timeline_version.is_some())
+                });
+            let hint = match latest_backup {
+                Some(backup) => format!("Restore backup {} to get the timeline back.", backup.id),
+                None => "No backup available; rebuild the timeline with start_editing.".to_string(),
+            };
+            (RepairOutcome::Unrecoverable, Some(hint))
+        }
+    };
+    tracing::info!("Repaired timeline for {project_id}: {outcome:?}");
+    Ok(Some(StoreFileRepair {
+        path: file_path.to_string_lossy().to_string(),
+        project_id: Some(project_id.to_string()),
+        outcome,
+        moved_to: moved_to.to_string_lossy().to_string(),
+        recovered,
+        lost,
+        hint,
+    }))
+}
+
+/// Project ids with a `timeline.json` in their data dir.
+fn project_ids_with_timelines() -> Result<Vec<String>, String> {
+    let data_dir = workspace_root()?.join("desktop").join("data");
+    if !data_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        fs::read_dir(&data_dir).map_err(|error| format!("Failed reading data dir: {error}"))?;
+    let mut ids = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().join("timeline.json").is_file())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    ids.sort();
+    Ok(ids)
+}
+
+fn repair_store_blocking(request: RepairStoreRequest) -> Result<StoreRepairReport, String> {
+    let project_ids = match request.project_id {
+        Some(project_id) => vec![project_id],
+        None => project_ids_with_timelines()?,
+    };
+    let mut repairs = Vec::new();
+    repairs.extend(repair_projects()?);
+    for project_id in &project_ids {
+        repairs.extend(repair_timeline(project_id)?);
+    }
+    Ok(StoreRepairReport {
+        files_checked: 1 + project_ids.len(),
+        repairs,
+    })
+}
+
+#[tauri::command]
+pub async fn repair_store(
+    request: Option<RepairStoreRequest>,
+) -> Result<StoreRepairReport, String> {
+    tauri::async_runtime::spawn_blocking(move || repair_store_blocking(request.unwrap_or_default()))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
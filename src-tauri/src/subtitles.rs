@@ -0,0 +1,905 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::captions::CAPTION_CLIP_TYPE;
+use crate::ffmpeg::ffmpeg_binary;
+use crate::media::{probe_video_dimensions, resolve_source_path};
+use crate::timeline::{source_to_timeline_us, timeline_to_source_us};
+use crate::transcript::{read_transcript, Transcript};
+use crate::{path_safety, read_timeline, Timeline, TimelineClip};
+
+/// Words further apart than this on the timeline start a new caption.
+pub const CUE_GAP_US: u64 = 1_000_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitlePosition {
+    Top,
+    Middle,
+    #[default]
+    Bottom,
+}
+
+/// How burned-in subtitles look. Sizes are in pixels at a 1080-pixel short
+/// side and scale with the output frame.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SubtitleStyle {
+    pub font_family: String,
+    pub font_size: u32,
+    pub bold: bool,
+    /// `#RRGGBB` or `#RRGGBBAA`.
+    pub primary_color: String,
+    pub outline_color: String,
+    pub outline_width: f64,
+    pub shadow: f64,
+    pub position: SubtitlePosition,
+    /// Distance from the top/bottom edge.
+    pub margin_v: u32,
+    pub max_lines: u32,
+    pub max_line_chars: u32,
+    /// Highlight each word as it is spoken.
+    pub karaoke: bool,
+    pub highlight_color: String,
+}
+
+impl Default for SubtitleStyle {
+    fn default() -> Self {
+        Self {
+            font_family: "Arial".to_string(),
+            font_size: 54,
+            bold: true,
+            primary_color: "#FFFFFF".to_string(),
+            outline_color: "#000000".to_string(),
+            outline_width: 3.0,
+            shadow: 0.0,
+            position: SubtitlePosition::Bottom,
+            margin_v: 80,
+            max_lines: 2,
+            max_line_chars: 42,
+            karaoke: false,
+            highlight_color: "#FFD400".to_string(),
+        }
+    }
+}
+
+/// Changes to the project style for one caption, stored as `meta.style` on a
+/// clip on a caption track and applied to the captions that start within it.
+/// Unset fields keep the project style.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CaptionStyleOverride {
+    pub font_size: Option<u32>,
+    pub bold: Option<bool>,
+    pub primary_color: Option<String>,
+    pub outline_color: Option<String>,
+    pub highlight_color: Option<String>,
+    pub position: Option<SubtitlePosition>,
+    pub margin_v: Option<u32>,
+    pub karaoke: Option<bool>,
+    /// Words drawn in `emphasis_color`, matched ignoring case and punctuation.
+    pub emphasis_words: Vec<String>,
+    /// Defaults to the highlight color.
+    pub emphasis_color: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSubtitleStyleRequest {
+    project_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveSubtitleStyleRequest {
+    project_id: String,
+    style: SubtitleStyle,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewSubtitleStyleRequest {
+    project_id: String,
+    /// Unsaved style to try out; defaults to the project's saved style.
+    style: Option<SubtitleStyle>,
+    /// Timeline position to grab; defaults to the first caption.
+    at_us: Option<u64>,
+    /// Overrides the transcript text shown at `at_us`.
+    text: Option<String>,
+    /// Defaults to `frames/subtitle-preview.png` in the project directory.
+    path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitlePreview {
+    pub path: String,
+    pub timeline_us: u64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CueWord {
+    pub start_us: u64,
+    pub end_us: u64,
+    pub text: String,
+    pub speaker: Option<String>,
+}
+
+/// One on-screen caption, in timeline time.
+#[derive(Debug, Clone)]
+struct Cue {
+    words: Vec<CueWord>,
+    /// Word index each line starts at.
+    line_starts: Vec<usize>,
+    /// Index into the document's styles; 0 is the project style.
+    style: usize,
+}
+
+/// A named ASS style cues can refer to.
+#[derive(Debug, Clone)]
+struct CueStyle {
+    name: String,
+    style: SubtitleStyle,
+    /// Normalized words to color, and the color.
+    emphasis: Option<(Vec<String>, String)>,
+}
+
+impl CueStyle {
+    fn project(style: SubtitleStyle) -> Self {
+        Self {
+            name: "Default".to_string(),
+            style,
+            emphasis: None,
+        }
+    }
+}
+
+impl Cue {
+    fn start_us(&self) -> u64 {
+        self.words.first().map_or(0, |word| word.start_us)
+    }
+
+    fn end_us(&self) -> u64 {
+        self.words.last().map_or(0, |word| word.end_us)
+    }
+
+    fn plain_text(&self) -> String {
+        self.words
+            .iter()
+            .map(|word| word.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+pub fn parse_color(value: &str, field: &str) -> Result<(u8, u8, u8, u8), String> {
+    let hex = value.trim().trim_start_matches('#');
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16);
+    let parsed = match hex.len() {
+        6 if hex.is_ascii() => (channel(0), channel(2), channel(4), Ok(255)),
+        8 if hex.is_ascii() => (channel(0), channel(2), channel(4), channel(6)),
+        _ => {
+            return Err(format!(
+                "{field} must be #RRGGBB or #RRGGBBAA, got {value:?}."
+            ))
+        }
+    };
+    match parsed {
+        (Ok(r), Ok(g), Ok(b), Ok(a)) => Ok((r, g, b, a)),
+        _ => Err(format!(
+            "{field} must be #RRGGBB or #RRGGBBAA, got {value:?}."
+        )),
+    }
+}
+
+/// ASS colours are `&HAABBGGRR` with inverted alpha (00 is opaque).
+fn ass_color(value: &str) -> String {
+    let (r, g, b, a) = parse_color(value, "color").unwrap_or((255, 255, 255, 255));
+    format!("&H{:02X}{b:02X}{g:02X}{r:02X}", 255 - a)
+}
+
+/// The `&HBBGGRR&` form override tags take; alpha is left to the style.
+fn ass_inline_color(value: &str) -> String {
+    let (r, g, b, _) = parse_color(value, "color").unwrap_or((255, 255, 255, 255));
+    format!("&H{b:02X}{g:02X}{r:02X}&")
+}
+
+fn emphasis_key(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+impl SubtitleStyle {
+    pub fn validate(&self) -> Result<(), String> {
+        let font = self.font_family.trim();
+        // Commas separate fields in an ASS style line.
+        if font.is_empty() || font.contains(',') {
+            return Err("Font family must be non-empty and contain no commas.".to_string());
+        }
+        if !(8..=200).contains(&self.font_size) {
+            return Err(format!("Font size must be 8-200, got {}.", self.font_size));
+        }
+        parse_color(&self.primary_color, "Primary color")?;
+        parse_color(&self.outline_color, "Outline color")?;
+        parse_color(&self.highlight_color, "Highlight color")?;
+        if !(0.0..=20.0).contains(&self.outline_width) || !(0.0..=20.0).contains(&self.shadow) {
+            return Err("Outline width and shadow must be between 0 and 20.".to_string());
+        }
+        if self.margin_v > 540 {
+            return Err(format!(
+                "Vertical margin must be at most 540, got {}.",
+                self.margin_v
+            ));
+        }
+        if !(1..=4).contains(&self.max_lines) {
+            return Err(format!("Max lines must be 1-4, got {}.", self.max_lines));
+        }
+        if !(10..=120).contains(&self.max_line_chars) {
+            return Err(format!(
+                "Max line length must be 10-120 characters, got {}.",
+                self.max_line_chars
+            ));
+        }
+        Ok(())
+    }
+
+    /// This style with a caption's overrides applied.
+    pub fn with_override(&self, changes: &CaptionStyleOverride) -> SubtitleStyle {
+        let mut style = self.clone();
+        if let Some(font_size) = changes.font_size {
+            style.font_size = font_size;
+        }
+        if let Some(bold) = changes.bold {
+            style.bold = bold;
+        }
+        if let Some(color) = &changes.primary_color {
+            style.primary_color = color.clone();
+        }
+        if let Some(color) = &changes.outline_color {
+            style.outline_color = color.clone();
+        }
+        if let Some(color) = &changes.highlight_color {
+            style.highlight_color = color.clone();
+        }
+        if let Some(position) = changes.position {
+            style.position = position;
+        }
+        if let Some(margin_v) = changes.margin_v {
+            style.margin_v = margin_v;
+        }
+        if let Some(karaoke) = changes.karaoke {
+            style.karaoke = karaoke;
+        }
+        style
+    }
+
+    /// One `[V4+ Styles]` line named `name`, for a `width`x`height` output.
+    fn ass_style_line(&self, name: &str, width: u32, height: u32) -> String {
+        let scale = f64::from(width.min(height).max(1)) / 1080.0;
+        let scaled = |value: f64| (value * scale * 100.0).round() / 100.0;
+        let alignment = match self.position {
+            SubtitlePosition::Bottom => 2,
+            SubtitlePosition::Middle => 5,
+            SubtitlePosition::Top => 8,
+        };
+        // With karaoke, words start in SecondaryColour and switch to
+        // PrimaryColour once reached.
+        let (primary, secondary) = if self.karaoke {
+            (
+                ass_color(&self.highlight_color),
+                ass_color(&self.primary_color),
+            )
+        } else {
+            (
+                ass_color(&self.primary_color),
+                ass_color(&self.primary_color),
+            )
+        };
+        format!(
+            "Style: {name},{font},{size},{primary},{secondary},{outline},{back},{bold},0,0,0,100,100,0,0,1,{outline_width},{shadow},{alignment},{margin_h},{margin_h},{margin_v},1",
+            font = self.font_family.trim(),
+            size = scaled(f64::from(self.font_size)),
+            outline = ass_color(&self.outline_color),
+            back = ass_color(&self.outline_color),
+            bold = if self.bold { -1 } else { 0 },
+            outline_width = scaled(self.outline_width),
+            shadow = scaled(self.shadow),
+            margin_h = scaled(60.0).round(),
+            margin_v = scaled(f64::from(self.margin_v)).round(),
+        )
+    }
+}
+
+fn project_dir(project_id: &str) -> Result<PathBuf, String> {
+    path_safety::project_dir(project_id)
+}
+
+fn style_file_path(project_id: &str) -> Result<PathBuf, String> {
+    Ok(project_dir(project_id)?
+        .join("subtitles")
+        .join("style.json"))
+}
+
+pub fn read_subtitle_style(project_id: &str) -> Result<SubtitleStyle, String> {
+    let file_path = style_file_path(project_id)?;
+    if !file_path.exists() {
+        return Ok(SubtitleStyle::default());
+    }
+    let raw = fs::read_to_string(&file_path)
+        .map_err(|error| format!("Failed reading subtitle style file: {error}"))?;
+    serde_json::from_str::<SubtitleStyle>(&raw)
+        .map_err(|error| format!("Invalid subtitle style JSON: {error}"))
+}
+
+fn write_subtitle_style(project_id: &str, style: &SubtitleStyle) -> Result<(), String> {
+    let file_path = style_file_path(project_id)?;
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating subtitles dir: {error}"))?;
+    }
+    let serialized = serde_json::to_string_pretty(style)
+        .map_err(|error| format!("Subtitle style serialize error: {error}"))?;
+    fs::write(&file_path, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing subtitle style file: {error}"))
+}
+
+/// ASS has no escape for override braces, so they are swapped out.
+fn ass_text(text: &str) -> String {
+    text.trim()
+        .replace('{', "(")
+        .replace('}', ")")
+        .replace('\\', "/")
+}
+
+/// `H:MM:SS.cc`, the centisecond clock ASS uses.
+fn ass_time(us: u64) -> String {
+    let cs = us / 10_000;
+    format!(
+        "{}:{:02}:{:02}.{:02}",
+        cs / 360_000,
+        (cs / 6_000) % 60,
+        (cs / 100) % 60,
+        cs % 100
+    )
+}
+
+/// Transcript words moved onto the timeline; words in removed ranges drop out.
+pub fn timeline_words(transcript: &Transcript, timeline: &Timeline) -> Vec<Vec<CueWord>> {
+    let source_ref = transcript.source_ref.as_deref();
+    transcript
+        .segments
+        .iter()
+        .map(|segment| {
+            // Segments without word timings caption as a single unit.
+            let spans = if segment.words.is_empty() {
+                vec![(
+                    segment.start_us,
+                    segment.end_us,
+                    segment.text.as_str(),
+                    segment.speaker.as_ref(),
+                )]
+            } else {
+                segment
+                    .words
+                    .iter()
+                    .map(|word| {
+                        (
+                            word.start_us,
+                            word.end_us,
+                            word.text.as_str(),
+                            word.speaker.as_ref().or(segment.speaker.as_ref()),
+                        )
+                    })
+                    .collect()
+            };
+            spans
+                .into_iter()
+                .filter(|(_, _, text, _)| !text.trim().is_empty())
+                .filter_map(|(start_us, end_us, text, speaker)| {
+                    let start = source_to_timeline_us(timeline, source_ref, start_us)?;
+                    Some(CueWord {
+                        start_us: start,
+                        end_us: start + end_us.saturating_sub(start_us).max(10_000),
+                        text: ass_text(text),
+                        speaker: speaker.cloned(),
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Packs words into cues of at most `max_lines` lines of `max_line_chars`.
+fn build_cues(segments: Vec<Vec<CueWord>>, style: &SubtitleStyle) -> Vec<Cue> {
+    let max_chars = style.max_line_chars as usize;
+    let mut cues = Vec::new();
+    for words in segments {
+        let mut cue = Cue {
+            words: Vec::new(),
+            line_starts: vec![0],
+            style: 0,
+        };
+        let mut line_chars = 0;
+        for word in words {
+            let word_chars = word.text.chars().count();
+            if let Some(previous) = cue.words.last() {
+                let gap = word.start_us.saturating_sub(previous.end_us);
+                // Reordered clips can put a later word before an earlier one.
+                let jumped = word.start_us < previous.start_us;
+                if gap > CUE_GAP_US || jumped {
+                    cues.push(cue);
+                    cue = Cue {
+                        words: Vec::new(),
+                        line_starts: vec![0],
+                        style: 0,
+                    };
+                    line_chars = 0;
+                } else if line_chars + 1 + word_chars > max_chars {
+                    if cue.line_starts.len() as u32 >= style.max_lines {
+                        cues.push(cue);
+                        cue = Cue {
+                            words: Vec::new(),
+                            line_starts: vec![0],
+                            style: 0,
+                        };
+                    } else {
+                        cue.line_starts.push(cue.words.len());
+                    }
+                    line_chars = 0;
+                }
+            }
+            line_chars += if line_chars == 0 {
+                word_chars
+            } else {
+                word_chars + 1
+            };
+            cue.words.push(word);
+        }
+        if !cue.words.is_empty() {
+            cues.push(cue);
+        }
+    }
+    // Keep cues from overlapping the next one.
+    for index in 1..cues.len() {
+        let next_start = cues[index].start_us();
+        if let Some(last) = cues[index - 1].words.last_mut() {
+            if last.end_us > next_start {
+                last.end_us = next_start.max(last.start_us);
+            }
+        }
+    }
+    cues
+}
+
+/// Cues from the caption clips `generate_captions` (or the editor) put on
+/// the timeline. Clips without word timings show their text for the whole
+/// clip.
+fn caption_clip_cues(timeline: &Timeline) -> Vec<Cue> {
+    let mut clips = timeline
+        .clips
+        .iter()
+        .filter(|clip| clip.clip_type == CAPTION_CLIP_TYPE && clip.end_us > clip.start_us)
+        .collect::<Vec<_>>();
+    clips.sort_by_key(|clip| clip.start_us);
+    clips
+        .into_iter()
+        .filter_map(|clip| {
+            let timed = clip
+                .meta
+                .get("words")
+                .and_then(Value::as_array)
+                .map(|words| {
+                    words
+                        .iter()
+                        .filter_map(|word| {
+                            Some(CueWord {
+                                start_us: word.get("startUs")?.as_u64()?,
+                                end_us: word.get("endUs")?.as_u64()?,
+                                text: ass_text(word.get("text")?.as_str()?),
+                                speaker: None,
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .filter(|words| !words.is_empty());
+            let (words, line_starts) = match timed {
+                Some(words) => {
+                    let line_starts = clip
+                        .meta
+                        .get("lineStarts")
+                        .and_then(Value::as_array)
+                        .map(|starts| {
+                            starts
+                                .iter()
+                                .filter_map(Value::as_u64)
+                                .map(|start| start as usize)
+                                .filter(|&start| start < words.len())
+                                .collect::<Vec<_>>()
+                        })
+                        .filter(|starts| starts.first() == Some(&0))
+                        .unwrap_or_else(|| vec![0]);
+                    (words, line_starts)
+                }
+                None => {
+                    let text = clip.meta.get("text").and_then(Value::as_str)?;
+                    let mut words = Vec::new();
+                    let mut line_starts = Vec::new();
+                    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+                        line_starts.push(words.len());
+                        words.extend(line.split_whitespace().map(|word| CueWord {
+                            start_us: clip.start_us,
+                            end_us: clip.end_us,
+                            text: ass_text(word),
+                            speaker: None,
+                        }));
+                    }
+                    (words, line_starts)
+                }
+            };
+            if words.is_empty() {
+                return None;
+            }
+            let mut cue = Cue {
+                words,
+                line_starts,
+                style: 0,
+            };
+            // The clip's span wins over the stored word times.
+            if let Some(first) = cue.words.first_mut() {
+                first.start_us = first.start_us.max(clip.start_us);
+            }
+            for word in &mut cue.words {
+                word.start_us = word.start_us.clamp(clip.start_us, clip.end_us);
+                word.end_us = word.end_us.clamp(word.start_us, clip.end_us);
+            }
+            if let Some(last) = cue.words.last_mut() {
+                last.end_us = clip.end_us;
+            }
+            Some(cue)
+        })
+        .collect()
+}
+
+/// Caption clips when the timeline has any, otherwise cues built from the
+/// transcript. None when there is nothing to caption from.
+fn timeline_cues(project_id: &str, timeline: &Timeline, style: &SubtitleStyle) -> Option<Vec<Cue>> {
+    let cues = caption_clip_cues(timeline);
+    if !cues.is_empty() {
+        return Some(cues);
+    }
+    let transcript = read_transcript(project_id).ok()?;
+    Some(build_cues(timeline_words(&transcript, timeline), style))
+}
+
+/// A caption clip's override, if it has one. Checked against the default
+/// style so a bad color or size is reported where it was set.
+pub fn caption_override(clip: &TimelineClip) -> Result<Option<CaptionStyleOverride>, String> {
+    let Some(raw) = clip.meta.get("style").filter(|raw| !raw.is_null()) else {
+        return Ok(None);
+    };
+    let changes = serde_json::from_value::<CaptionStyleOverride>(raw.clone())
+        .map_err(|error| format!("Invalid caption style: {error}."))?;
+    SubtitleStyle::default()
+        .with_override(&changes)
+        .validate()?;
+    if let Some(color) = &changes.emphasis_color {
+        parse_color(color, "Emphasis color")?;
+    }
+    Ok(Some(changes))
+}
+
+/// The project style followed by one style per caption clip with overrides,
+/// and each cue pointed at the override covering its start. Invalid
+/// overrides are skipped so one bad caption can't fail a render.
+fn apply_caption_styles(
+    timeline: &Timeline,
+    style: &SubtitleStyle,
+    cues: &mut [Cue],
+) -> Vec<CueStyle> {
+    let mut styles = vec![CueStyle::project(style.clone())];
+    let mut spans = Vec::new();
+    let caption_tracks = timeline
+        .tracks
+        .iter()
+        .filter(|track| track.kind == "caption")
+        .map(|track| track.id.as_str())
+        .collect::<Vec<_>>();
+    for clip in timeline
+        .clips
+        .iter()
+        .filter(|clip| caption_tracks.contains(&clip.track_id.as_str()))
+    {
+        let changes = match caption_override(clip) {
+            Ok(Some(changes)) => changes,
+            Ok(None) => continue,
+            Err(error) => {
+                tracing::warn!("Ignoring style of caption {}: {error}", clip.clip_id);
+                continue;
+            }
+        };
+        let merged = style.with_override(&changes);
+        if let Err(error) = merged.validate() {
+            tracing::warn!("Ignoring style of caption {}: {error}", clip.clip_id);
+            continue;
+        }
+        let words = changes
+            .emphasis_words
+            .iter()
+            .map(|word| emphasis_key(word))
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>();
+        let emphasis = (!words.is_empty()).then(|| {
+            let color = changes
+                .emphasis_color
+                .clone()
+                .unwrap_or_else(|| merged.highlight_color.clone());
+            (words, color)
+        });
+        spans.push((clip.start_us, clip.end_us, styles.len()));
+        styles.push(CueStyle {
+            name: format!("Caption{}", styles.len()),
+            style: merged,
+            emphasis,
+        });
+    }
+    for cue in cues.iter_mut() {
+        let start = cue.start_us();
+        if let Some((_, _, index)) = spans
+            .iter()
+            .find(|(start_us, end_us, _)| (*start_us..*end_us).contains(&start))
+        {
+            cue.style = *index;
+        }
+    }
+    styles
+}
+
+fn dialogue_line(cue: &Cue, cue_style: &CueStyle) -> String {
+    let style = &cue_style.style;
+    let mut text = String::new();
+    for (index, word) in cue.words.iter().enumerate() {
+        if index > 0 {
+            text.push_str(if cue.line_starts.contains(&index) {
+                "\\N"
+            } else {
+                " "
+            });
+        }
+        if style.karaoke {
+            let until = cue
+                .words
+                .get(index + 1)
+                .map_or(word.end_us, |next| next.start_us);
+            text.push_str(&format!(
+                "{{\\k{}}}",
+                until.saturating_sub(word.start_us) / 10_000
+            ));
+        }
+        match &cue_style.emphasis {
+            Some((words, color)) if words.contains(&emphasis_key(&word.text)) => {
+                text.push_str(&format!("{{\\1c{}}}", ass_inline_color(color)));
+                text.push_str(&word.text);
+                text.push_str("{\\r}");
+            }
+            _ => text.push_str(&word.text),
+        }
+    }
+    format!(
+        "Dialogue: 0,{},{},{},,0,0,0,,{text}",
+        ass_time(cue.start_us()),
+        ass_time(cue.end_us()),
+        cue_style.name
+    )
+}
+
+fn ass_document(styles: &[CueStyle], width: u32, height: u32, cues: &[Cue]) -> String {
+    let style_lines = styles
+        .iter()
+        .map(|cue_style| {
+            cue_style
+                .style
+                .ass_style_line(&cue_style.name, width, height)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut document = format!(
+        "[Script Info]\n\
+         ScriptType: v4.00+\n\
+         PlayResX: {width}\n\
+         PlayResY: {height}\n\
+         WrapStyle: 2\n\
+         ScaledBorderAndShadow: yes\n\n\
+         [V4+ Styles]\n\
+         Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+         {style_lines}\n\n\
+         [Events]\n\
+         Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+    );
+    for cue in cues {
+        document.push_str(&dialogue_line(
+            cue,
+            &styles[cue.style.min(styles.len() - 1)],
+        ));
+        document.push('\n');
+    }
+    document
+}
+
+/// Size of the first source on the timeline, falling back to 1080p.
+pub fn timeline_frame_size(project_id: &str, timeline: &Timeline) -> (u32, u32) {
+    timeline
+        .clips
+        .iter()
+        .filter(|clip| clip.clip_type == "source_clip")
+        .find_map(|clip| {
+            let path = resolve_source_path(project_id, Some(&clip.source_ref)).ok()?;
+            probe_video_dimensions(&path)
+        })
+        .unwrap_or((1920, 1080))
+}
+
+/// Writes the project's styled subtitles for `timeline` as ASS. Returns
+/// `None` when there is nothing to caption from.
+pub fn write_styled_subtitles(
+    project_id: &str,
+    timeline: &Timeline,
+    frame_size: (u32, u32),
+    output: &Path,
+) -> Result<Option<PathBuf>, String> {
+    let style = read_subtitle_style(project_id)?;
+    style.validate()?;
+    let Some(mut cues) = timeline_cues(project_id, timeline, &style) else {
+        return Ok(None);
+    };
+    let styles = apply_caption_styles(timeline, &style, &mut cues);
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating subtitles dir: {error}"))?;
+    }
+    fs::write(
+        output,
+        ass_document(&styles, frame_size.0, frame_size.1, &cues),
+    )
+    .map_err(|error| format!("Failed writing styled subtitles: {error}"))?;
+    Ok(Some(output.to_path_buf()))
+}
+
+/// Default location for the styled subtitles of the main render.
+pub fn styled_subtitles_path(project_id: &str) -> Result<PathBuf, String> {
+    Ok(project_dir(project_id)?
+        .join("subtitles")
+        .join("subtitles.ass"))
+}
+
+/// Path quoting for ffmpeg filter arguments.
+fn escape_filter_path(path: &Path) -> String {
+    path.to_string_lossy()
+        .replace('\\', "/")
+        .replace(':', "\\:")
+        .replace(',', "\\,")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace('\'', "\\'")
+}
+
+fn preview_blocking(request: PreviewSubtitleStyleRequest) -> Result<SubtitlePreview, String> {
+    let style = match request.style {
+        Some(style) => style,
+        None => read_subtitle_style(&request.project_id)?,
+    };
+    style.validate()?;
+
+    let timeline = read_timeline(&request.project_id)?;
+    let mut cues = timeline_cues(&request.project_id, &timeline, &style).unwrap_or_default();
+    let styles = apply_caption_styles(&timeline, &style, &mut cues);
+    let at_us = request
+        .at_us
+        .or_else(|| cues.first().map(|cue| cue.start_us()))
+        .unwrap_or(0);
+
+    // The grabbed frame sits at t=0 of the preview, so the cue is shifted to
+    // show the karaoke highlight as it would be at `at_us`.
+    let cue = match request.text.as_deref().map(str::trim) {
+        Some(text) if !text.is_empty() => {
+            let words = text
+                .split_whitespace()
+                .map(|word| CueWord {
+                    start_us: 0,
+                    end_us: 0,
+                    text: ass_text(word),
+                    speaker: None,
+                })
+                .collect();
+            build_cues(vec![words], &style).into_iter().next()
+        }
+        _ => cues
+            .into_iter()
+            .find(|cue| at_us >= cue.start_us() && at_us < cue.end_us()),
+    }
+    .map(|mut cue| {
+        for word in &mut cue.words {
+            word.start_us = word.start_us.saturating_sub(at_us);
+            word.end_us = word.end_us.saturating_sub(at_us).max(1_000_000);
+        }
+        cue
+    })
+    .ok_or_else(|| format!("No caption at {at_us}us; pass preview text instead."))?;
+
+    let (clip, source_us) = timeline_to_source_us(&timeline, at_us)
+        .ok_or_else(|| format!("No video clip at {at_us}us on the timeline."))?;
+    let source_path = resolve_source_path(&request.project_id, Some(&clip.source_ref))?;
+    let (width, height) = probe_video_dimensions(&source_path).unwrap_or((1920, 1080));
+
+    let dir = project_dir(&request.project_id)?.join("frames");
+    fs::create_dir_all(&dir).map_err(|error| format!("Failed creating frame dir: {error}"))?;
+    let ass_path = dir.join("subtitle-preview.ass");
+    fs::write(
+        &ass_path,
+        ass_document(&styles, width, height, std::slice::from_ref(&cue)),
+    )
+    .map_err(|error| format!("Failed writing preview subtitles: {error}"))?;
+    let output = match request.path.filter(|path| !path.trim().is_empty()) {
+        Some(path) => PathBuf::from(path),
+        None => dir.join("subtitle-preview.png"),
+    };
+
+    let result = Command::new(ffmpeg_binary())
+        .args(["-y", "-hide_banner", "-loglevel", "error", "-ss"])
+        .arg(format!("{:.6}", source_us as f64 / 1_000_000.0))
+        .arg("-i")
+        .arg(&source_path)
+        .arg("-vf")
+        .arg(format!("ass=filename={}", escape_filter_path(&ass_path)))
+        .args(["-frames:v", "1", "-an"])
+        .arg(&output)
+        .output()
+        .map_err(|error| format!("Failed to execute ffmpeg: {error}"))?;
+    let _ = fs::remove_file(&ass_path);
+    if !result.status.success() || !output.is_file() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(format!(
+            "ffmpeg subtitle preview failed: {}",
+            stderr.trim().chars().take(300).collect::<String>()
+        ));
+    }
+
+    Ok(SubtitlePreview {
+        path: output.to_string_lossy().to_string(),
+        timeline_us: at_us,
+        text: cue.plain_text(),
+    })
+}
+
+#[tauri::command]
+pub async fn get_subtitle_style(request: GetSubtitleStyleRequest) -> Result<SubtitleStyle, String> {
+    tauri::async_runtime::spawn_blocking(move || read_subtitle_style(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn save_subtitle_style(
+    request: SaveSubtitleStyleRequest,
+) -> Result<SubtitleStyle, String> {
+    request.style.validate()?;
+    tauri::async_runtime::spawn_blocking(move || {
+        write_subtitle_style(&request.project_id, &request.style)?;
+        Ok(request.style)
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn preview_subtitle_style(
+    request: PreviewSubtitleStyleRequest,
+) -> Result<SubtitlePreview, String> {
+    tauri::async_runtime::spawn_blocking(move || preview_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
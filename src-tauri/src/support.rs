@@ -0,0 +1,412 @@
+use std::backtrace::Backtrace;
+use std::fs;
+use std::io::Write;
+use std::panic;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::logging::logs_dir;
+use crate::{
+    app_metadata, hardware, now_iso, projects_file_path, read_projects, settings,
+    timeline_file_path, workspace_root,
+};
+
+const LAST_FAILURE_FILE: &str = "last-failure.json";
+const CRASH_PREFIX: &str = "crash-";
+/// Newest log files and crash reports copied into a bundle.
+const BUNDLED_LOG_FILES: usize = 2;
+const BUNDLED_CRASHES: usize = 5;
+/// Tail of each log file kept, so bundles stay small enough to attach.
+const MAX_LOG_LINES: usize = 5_000;
+
+/// Prefixes of well-known API token formats.
+const TOKEN_PREFIXES: &[&str] = &[
+    "sk-",
+    "hf_",
+    "ghp_",
+    "gho_",
+    "github_pat_",
+    "glpat-",
+    "xoxb-",
+    "xoxp-",
+    "AKIA",
+];
+/// Keys whose values are redacted in `key=value` and `"key": "value"` text.
+const SECRET_KEYS: &[&str] = &[
+    "api_key",
+    "apikey",
+    "api-key",
+    "access_key",
+    "accesskey",
+    "secret",
+    "token",
+    "password",
+    "authorization",
+];
+const REDACTED: &str = "<redacted>";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportBundle {
+    pub path: String,
+    pub entries: Vec<String>,
+    pub size_bytes: u64,
+}
+
+/// Keeps the stderr of the most recent failed script, so a bundle made after
+/// the fact still has it.
+pub fn record_script_failure(script: &Path, args: &[String], stderr: &str) {
+    let write = || -> Result<(), String> {
+        let dir = logs_dir()?;
+        fs::create_dir_all(&dir).map_err(|error| format!("Failed creating logs dir: {error}"))?;
+        let failure = serde_json::json!({
+            "failedAt": now_iso(),
+            "script": script.file_name().map(|name| name.to_string_lossy().to_string()),
+            "args": args,
+            "stderr": stderr,
+        });
+        let raw = serde_json::to_string_pretty(&failure)
+            .map_err(|error| format!("Failure serialize error: {error}"))?;
+        fs::write(dir.join(LAST_FAILURE_FILE), format!("{raw}\n"))
+            .map_err(|error| format!("Failed writing last failure: {error}"))
+    };
+    if let Err(error) = write() {
+        tracing::warn!("Failed recording script failure: {error}");
+    }
+}
+
+/// Logs panics and writes each one to `logs/crash-<time>.txt` before the
+/// default hook runs.
+pub fn install_panic_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current();
+        let report = format!(
+            "{info}\nthread: {}\nversion: {}\nos: {} {}\n\n{}\n",
+            thread.name().unwrap_or("unnamed"),
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            Backtrace::force_capture()
+        );
+        tracing::error!("Panic: {info}");
+        if let Ok(dir) = logs_dir() {
+            let _ = fs::create_dir_all(&dir);
+            let _ = fs::write(dir.join(format!("{CRASH_PREFIX}{}.txt", now_iso())), report);
+        }
+        previous(info);
+    }));
+}
+
+/// Values from the settings and AI config that must never leave the machine.
+fn known_secrets() -> Vec<String> {
+    let mut secrets = Vec::new();
+    if let Ok(settings) = settings::read_app_settings() {
+        secrets.push(settings.object_storage.secret_access_key);
+        secrets.push(settings.object_storage.access_key_id);
+        secrets.extend(settings.planner.api_key);
+    }
+    let ai_config = workspace_root()
+        .ok()
+        .and_then(|root| fs::read_to_string(root.join("desktop/data/ai_config.json")).ok())
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok());
+    if let Some(Value::Object(config)) = ai_config {
+        secrets.extend(
+            config
+                .values()
+                .filter_map(Value::as_str)
+                .map(str::to_string),
+        );
+    }
+    // Short values (provider names, flags) would redact ordinary words.
+    secrets.retain(|secret| secret.len() >= 8);
+    secrets
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')
+}
+
+/// Replaces each run of token characters after `start` with the marker.
+fn redact_value(text: &str, start: usize) -> (String, usize) {
+    let rest = &text[start..];
+    let value_len = rest
+        .find(|c: char| !is_token_char(c) && c != '/' && c != '+' && c != '=')
+        .unwrap_or(rest.len());
+    (REDACTED.to_string(), start + value_len)
+}
+
+fn redact_tokens(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut index = 0;
+    while index < text.len() {
+        let rest = &text[index..];
+        let at_word_start =
+            index == 0 || !text[..index].chars().next_back().is_some_and(is_token_char);
+        if at_word_start {
+            let token_len = rest.find(|c: char| !is_token_char(c)).unwrap_or(rest.len());
+            if token_len >= 16 && TOKEN_PREFIXES.iter().any(|prefix| rest.starts_with(prefix)) {
+                out.push_str(REDACTED);
+                index += token_len;
+                continue;
+            }
+            if rest.starts_with("Bearer ") {
+                out.push_str("Bearer ");
+                let (marker, end) = redact_value(text, index + "Bearer ".len());
+                out.push_str(&marker);
+                index = end;
+                continue;
+            }
+        }
+        let c = rest.chars().next().unwrap_or_default();
+        out.push(c);
+        index += c.len_utf8();
+    }
+    out
+}
+
+/// Redacts the value after any `SECRET_KEYS` name followed by `:` or `=`.
+fn redact_key_values(line: &str) -> String {
+    let lower = line.to_ascii_lowercase();
+    let mut out = String::with_capacity(line.len());
+    let mut index = 0;
+    while index < line.len() {
+        let hit = SECRET_KEYS
+            .iter()
+            .filter_map(|key| lower[index..].find(key).map(|at| (index + at, key.len())))
+            .min();
+        let Some((at, key_len)) = hit else {
+            break;
+        };
+        let mut value_start = at + key_len;
+        // Skip the rest of the key (`api_key_id`), quotes, and the separator.
+        while value_start < line.len()
+            && line[value_start..].starts_with(|c: char| is_token_char(c) || c == '"')
+        {
+            value_start += 1;
+        }
+        let separator = line[value_start..].trim_start();
+        if !(separator.starts_with(':') || separator.starts_with('=')) {
+            out.push_str(&line[index..at + key_len]);
+            index = at + key_len;
+            continue;
+        }
+        value_start = line.len() - separator.len() + 1;
+        while value_start < line.len() && line[value_start..].starts_with([' ', '"', '\'']) {
+            value_start += 1;
+        }
+        out.push_str(&line[index..value_start]);
+        let (marker, end) = redact_value(line, value_start);
+        if end > value_start {
+            out.push_str(&marker);
+        }
+        index = end;
+    }
+    out.push_str(&line[index.min(line.len())..]);
+    out
+}
+
+/// Strips user names from paths under home directories.
+fn redact_user_dirs(text: &str) -> String {
+    let mut text = text.to_string();
+    for marker in ["/Users/", "/home/", "\\Users\\"] {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text.as_str();
+        while let Some(at) = rest.find(marker) {
+            let after = &rest[at + marker.len()..];
+            let name_len = after.find(['/', '\\', '"', ' ']).unwrap_or(after.len());
+            out.push_str(&rest[..at + marker.len()]);
+            out.push_str("<user>");
+            rest = &after[name_len..];
+        }
+        out.push_str(rest);
+        text = out;
+    }
+    text
+}
+
+struct Redactor {
+    secrets: Vec<String>,
+    workspace: Option<String>,
+}
+
+impl Redactor {
+    fn new() -> Self {
+        Self {
+            secrets: known_secrets(),
+            workspace: workspace_root()
+                .ok()
+                .map(|root| root.to_string_lossy().to_string())
+                .filter(|root| root.len() > 1),
+        }
+    }
+
+    fn redact(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for secret in &self.secrets {
+            text = text.replace(secret.as_str(), REDACTED);
+        }
+        if let Some(workspace) = &self.workspace {
+            text = text.replace(workspace.as_str(), "<workspace>");
+        }
+        let text = text
+            .lines()
+            .map(redact_key_values)
+            .collect::<Vec<_>>()
+            .join("\n");
+        redact_user_dirs(&redact_tokens(&text))
+    }
+}
+
+fn newest_files(dir: &Path, matches: impl Fn(&str) -> bool, count: usize) -> Vec<PathBuf> {
+    let mut files = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(&matches)
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    // Log and crash names embed their date, so name order is age order.
+    files.sort();
+    files.into_iter().rev().take(count).collect()
+}
+
+fn tail_lines(raw: &str, count: usize) -> String {
+    let lines = raw.lines().collect::<Vec<_>>();
+    lines[lines.len().saturating_sub(count)..].join("\n")
+}
+
+/// Size, readability and version counter of each JSON store.
+fn store_summary() -> Value {
+    let describe = |path: Result<PathBuf, String>| {
+        let Ok(path) = path else {
+            return Value::Null;
+        };
+        let raw = fs::read_to_string(&path).ok();
+        let parsed = raw
+            .as_deref()
+            .and_then(|raw| serde_json::from_str::<Value>(raw).ok());
+        serde_json::json!({
+            "path": path.to_string_lossy(),
+            "exists": raw.is_some(),
+            "sizeBytes": raw.as_ref().map(|raw| raw.len()),
+            "valid": parsed.is_some(),
+            "version": parsed.as_ref().and_then(|value| value.get("version")).cloned(),
+            "schemaVersion": parsed.as_ref().and_then(|value| value.get("schemaVersion")).cloned(),
+        })
+    };
+    let timelines = read_projects()
+        .unwrap_or_default()
+        .iter()
+        .map(|project| {
+            serde_json::json!({
+                "projectId": project.id,
+                "status": project.status,
+                "timeline": describe(timeline_file_path(&project.id)),
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::json!({
+        "projects": describe(projects_file_path()),
+        "timelines": timelines,
+    })
+}
+
+fn create_bundle() -> Result<SupportBundle, String> {
+    let redactor = Redactor::new();
+    let mut files: Vec<(String, String)> = Vec::new();
+    let mut push_json = |name: &str, value: &Value| {
+        let raw = serde_json::to_string_pretty(value).unwrap_or_default();
+        files.push((name.to_string(), redactor.redact(&raw)));
+    };
+
+    let mut metadata = app_metadata();
+    if let Some(object) = metadata.as_object_mut() {
+        object.insert("generatedAt".to_string(), Value::String(now_iso()));
+        object.insert("os".to_string(), Value::String(std::env::consts::OS.into()));
+        object.insert(
+            "arch".to_string(),
+            Value::String(std::env::consts::ARCH.into()),
+        );
+    }
+    push_json("metadata.json", &metadata);
+    push_json(
+        "hardware.json",
+        &serde_json::to_value(hardware::hardware_info()).unwrap_or(Value::Null),
+    );
+    push_json("stores.json", &store_summary());
+
+    let logs = logs_dir()?;
+    if let Ok(raw) = fs::read_to_string(logs.join(LAST_FAILURE_FILE)) {
+        files.push((LAST_FAILURE_FILE.to_string(), redactor.redact(&raw)));
+    }
+    for path in newest_files(
+        &logs,
+        |name| name.starts_with("editor") && name.ends_with(".log"),
+        BUNDLED_LOG_FILES,
+    ) {
+        if let Ok(raw) = fs::read_to_string(&path) {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            files.push((
+                format!("logs/{name}"),
+                redactor.redact(&tail_lines(&raw, MAX_LOG_LINES)),
+            ));
+        }
+    }
+    for path in newest_files(
+        &logs,
+        |name| name.starts_with(CRASH_PREFIX),
+        BUNDLED_CRASHES,
+    ) {
+        if let Ok(raw) = fs::read_to_string(&path) {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            files.push((format!("crashes/{name}"), redactor.redact(&raw)));
+        }
+    }
+
+    let dir = workspace_root()?
+        .join("desktop")
+        .join("data")
+        .join("support");
+    fs::create_dir_all(&dir).map_err(|error| format!("Failed creating support dir: {error}"))?;
+    let path = dir.join(format!("support-bundle-{}.zip", now_iso()));
+    let file = fs::File::create(&path)
+        .map_err(|error| format!("Failed creating support bundle: {error}"))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, contents) in &files {
+        zip.start_file(name.as_str(), options)
+            .map_err(|error| format!("Failed writing support bundle: {error}"))?;
+        zip.write_all(contents.as_bytes())
+            .map_err(|error| format!("Failed writing support bundle: {error}"))?;
+    }
+    zip.finish()
+        .map_err(|error| format!("Failed writing support bundle: {error}"))?;
+
+    let size_bytes = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+    tracing::info!(
+        "Created support bundle with {} files ({size_bytes} bytes)",
+        files.len()
+    );
+    Ok(SupportBundle {
+        path: path.to_string_lossy().to_string(),
+        entries: files.into_iter().map(|(name, _)| name).collect(),
+        size_bytes,
+    })
+}
+
+#[tauri::command]
+pub async fn create_support_bundle() -> Result<SupportBundle, String> {
+    tauri::async_runtime::spawn_blocking(create_bundle)
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
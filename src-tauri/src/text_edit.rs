@@ -0,0 +1,293 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cuts::{self, ProposedCutSet};
+use crate::project_status::ProjectStatus;
+use crate::timeline::{normalize_ranges, TimeRange};
+use crate::transcript::{normalize_word, read_transcript, Transcript};
+use crate::{update_project_status, write_timeline, Timeline};
+
+const DEFAULT_FILLER_WORDS: &[&str] = &["um", "uh", "erm", "er", "ah", "hmm", "you know"];
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyTextEditRequest {
+    project_id: String,
+    removed_word_ids: Vec<String>,
+    /// Extra source time removed on each side of a deleted run, clamped so it
+    /// never eats into a kept word.
+    padding_ms: Option<u32>,
+    /// Audio crossfade recorded on every clip that follows a cut.
+    crossfade_ms: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveFillerWordsRequest {
+    project_id: String,
+    /// Filler words or short phrases; defaults to `DEFAULT_FILLER_WORDS`.
+    words: Option<Vec<String>>,
+    padding_ms: Option<u32>,
+    /// Preview only unless set.
+    apply: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FillerOccurrence {
+    pub filler: String,
+    pub word_ids: Vec<String>,
+    pub start_us: u64,
+    pub end_us: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FillerWordReport {
+    pub counts: BTreeMap<String, usize>,
+    pub occurrences: Vec<FillerOccurrence>,
+    pub removed_ranges: Vec<TimeRange>,
+    pub total_seconds_saved: f64,
+    pub applied: bool,
+    pub timeline: Option<Timeline>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextEditResult {
+    pub removed_ranges: Vec<TimeRange>,
+    pub unknown_word_ids: Vec<String>,
+    pub timeline: Timeline,
+}
+
+/// Converts deleted words into source ranges. Consecutive deleted words are
+/// joined into one range so the pauses between them are removed as well.
+pub fn word_removal_ranges(
+    transcript: &Transcript,
+    removed: &HashSet<&str>,
+    padding_us: u64,
+    duration_us: u64,
+) -> Vec<TimeRange> {
+    let words = transcript.words().collect::<Vec<_>>();
+    let mut ranges = Vec::new();
+    let mut index = 0;
+
+    while index < words.len() {
+        if !removed.contains(words[index].id.as_str()) {
+            index += 1;
+            continue;
+        }
+        let run_start = index;
+        while index < words.len() && removed.contains(words[index].id.as_str()) {
+            index += 1;
+        }
+        let run_end = index - 1;
+
+        let lower_bound = if run_start > 0 {
+            words[run_start - 1].end_us
+        } else {
+            0
+        };
+        let upper_bound = words
+            .get(index)
+            .map(|word| word.start_us)
+            .unwrap_or(duration_us);
+
+        let start_us = words[run_start]
+            .start_us
+            .saturating_sub(padding_us)
+            .max(lower_bound);
+        let end_us = words[run_end]
+            .end_us
+            .saturating_add(padding_us)
+            .min(upper_bound.max(words[run_end].end_us));
+        ranges.push(TimeRange { start_us, end_us });
+    }
+
+    normalize_ranges(ranges, duration_us)
+}
+
+fn apply_crossfades(timeline: &mut Timeline, crossfade_ms: u32) {
+    let mut video_clips = timeline
+        .clips
+        .iter_mut()
+        .filter(|clip| clip.clip_type == "source_clip")
+        .collect::<Vec<_>>();
+    video_clips.sort_by_key(|clip| clip.start_us);
+    for clip in video_clips.into_iter().skip(1) {
+        if let Some(effects) = clip.effects.as_object_mut() {
+            effects.insert(
+                "audioCrossfadeMs".to_string(),
+                serde_json::json!(crossfade_ms),
+            );
+        }
+    }
+}
+
+/// Records the ranges as accepted cuts and rebuilds the rough cut from them.
+fn apply_removals(
+    set: &mut ProposedCutSet,
+    ranges: &[TimeRange],
+    reason: &str,
+    crossfade_ms: Option<u32>,
+) -> Result<Timeline, String> {
+    cuts::append_cuts(set, ranges, reason);
+    cuts::write_proposed_cuts(set)?;
+
+    let mut timeline = cuts::build_timeline_from_decisions(set);
+    if let Some(crossfade_ms) = crossfade_ms.filter(|ms| *ms > 0) {
+        apply_crossfades(&mut timeline, crossfade_ms);
+    }
+    write_timeline(&timeline)?;
+    update_project_status(&set.project_id, ProjectStatus::RoughCutReady)?;
+    Ok(timeline)
+}
+
+fn apply_text_edit_blocking(request: ApplyTextEditRequest) -> Result<TextEditResult, String> {
+    let transcript = read_transcript(&request.project_id)?;
+    let mut set: ProposedCutSet = cuts::load_or_derive_cut_set(&request.project_id)?;
+
+    let known = transcript
+        .words()
+        .map(|word| word.id.as_str())
+        .collect::<HashSet<_>>();
+    let unknown_word_ids = request
+        .removed_word_ids
+        .iter()
+        .filter(|id| !known.contains(id.as_str()))
+        .cloned()
+        .collect::<Vec<_>>();
+    let removed = request
+        .removed_word_ids
+        .iter()
+        .map(String::as_str)
+        .filter(|id| known.contains(id))
+        .collect::<HashSet<_>>();
+    if removed.is_empty() {
+        return Err("None of the removed word IDs exist in the transcript.".to_string());
+    }
+
+    let padding_us = u64::from(request.padding_ms.unwrap_or(0)) * 1_000;
+    let removed_ranges = word_removal_ranges(&transcript, &removed, padding_us, set.duration_us);
+    let timeline = apply_removals(&mut set, &removed_ranges, "text-edit", request.crossfade_ms)?;
+
+    Ok(TextEditResult {
+        removed_ranges,
+        unknown_word_ids,
+        timeline,
+    })
+}
+
+/// Finds every occurrence of each filler phrase in transcript word order.
+fn find_filler_occurrences(transcript: &Transcript, fillers: &[String]) -> Vec<FillerOccurrence> {
+    let words = transcript.words().collect::<Vec<_>>();
+    let phrases = fillers
+        .iter()
+        .map(|filler| {
+            let tokens = filler
+                .split_whitespace()
+                .map(normalize_word)
+                .filter(|token| !token.is_empty())
+                .collect::<Vec<_>>();
+            (filler.trim().to_lowercase(), tokens)
+        })
+        .filter(|(_, tokens)| !tokens.is_empty())
+        .collect::<Vec<_>>();
+
+    let mut occurrences = Vec::new();
+    let mut index = 0;
+    while index < words.len() {
+        let hit = phrases.iter().find(|(_, tokens)| {
+            words.len() - index >= tokens.len()
+                && tokens
+                    .iter()
+                    .zip(&words[index..])
+                    .all(|(token, word)| word.normalized == *token)
+        });
+        match hit {
+            Some((filler, tokens)) => {
+                let matched = &words[index..index + tokens.len()];
+                occurrences.push(FillerOccurrence {
+                    filler: filler.clone(),
+                    word_ids: matched.iter().map(|word| word.id.clone()).collect(),
+                    start_us: matched[0].start_us,
+                    end_us: matched[matched.len() - 1].end_us,
+                });
+                index += tokens.len();
+            }
+            None => index += 1,
+        }
+    }
+    occurrences
+}
+
+fn remove_filler_words_blocking(
+    request: RemoveFillerWordsRequest,
+) -> Result<FillerWordReport, String> {
+    let transcript = read_transcript(&request.project_id)?;
+    let mut set = cuts::load_or_derive_cut_set(&request.project_id)?;
+    let fillers = request
+        .words
+        .filter(|words| !words.is_empty())
+        .unwrap_or_else(|| {
+            DEFAULT_FILLER_WORDS
+                .iter()
+                .map(|word| word.to_string())
+                .collect()
+        });
+
+    let occurrences = find_filler_occurrences(&transcript, &fillers);
+    let mut counts = BTreeMap::new();
+    for occurrence in &occurrences {
+        *counts.entry(occurrence.filler.clone()).or_insert(0) += 1;
+    }
+    let removed = occurrences
+        .iter()
+        .flat_map(|occurrence| occurrence.word_ids.iter().map(String::as_str))
+        .collect::<HashSet<_>>();
+    let padding_us = u64::from(request.padding_ms.unwrap_or(0)) * 1_000;
+    let removed_ranges = word_removal_ranges(&transcript, &removed, padding_us, set.duration_us);
+    let total_seconds_saved = removed_ranges
+        .iter()
+        .map(|range| range.end_us - range.start_us)
+        .sum::<u64>() as f64
+        / 1_000_000.0;
+
+    let apply = request.apply.unwrap_or(false) && !removed_ranges.is_empty();
+    let timeline = if apply {
+        Some(apply_removals(
+            &mut set,
+            &removed_ranges,
+            "filler-word",
+            None,
+        )?)
+    } else {
+        None
+    };
+
+    Ok(FillerWordReport {
+        counts,
+        occurrences,
+        removed_ranges,
+        total_seconds_saved,
+        applied: apply,
+        timeline,
+    })
+}
+
+#[tauri::command]
+pub async fn remove_filler_words(
+    request: RemoveFillerWordsRequest,
+) -> Result<FillerWordReport, String> {
+    tauri::async_runtime::spawn_blocking(move || remove_filler_words_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn apply_text_edit(request: ApplyTextEditRequest) -> Result<TextEditResult, String> {
+    tauri::async_runtime::spawn_blocking(move || apply_text_edit_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,612 @@
+use serde::{Deserialize, Serialize};
+
+use crate::clip_flags::{self, ProtectedRange};
+use crate::{generate_project_id, now_iso, speed, Timeline, TimelineClip, TimelineTrack};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeRange {
+    pub start_us: u64,
+    pub end_us: u64,
+}
+
+/// How cut points are moved onto the project frame grid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameSnapPolicy {
+    Off,
+    Floor,
+    Ceil,
+    #[default]
+    Nearest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RoughCutOptions {
+    pub frame_snap: FrameSnapPolicy,
+    /// Kept segments shorter than this are cut as well.
+    pub min_clip_duration_us: u64,
+    /// Remove ranges shorter than this are ignored and the footage kept.
+    pub min_gap_us: u64,
+}
+
+impl Default for RoughCutOptions {
+    fn default() -> Self {
+        Self {
+            frame_snap: FrameSnapPolicy::default(),
+            min_clip_duration_us: 100_000,
+            min_gap_us: 40_000,
+        }
+    }
+}
+
+/// Clamps ranges to `[0, duration_us]`, drops empty ones and merges
+/// overlapping or touching ranges. The result is sorted.
+pub fn normalize_ranges(ranges: Vec<TimeRange>, duration_us: u64) -> Vec<TimeRange> {
+    let mut normalized = ranges
+        .into_iter()
+        .map(|range| TimeRange {
+            start_us: range.start_us.min(duration_us),
+            end_us: range.end_us.min(duration_us),
+        })
+        .filter(|range| range.end_us > range.start_us)
+        .collect::<Vec<_>>();
+
+    normalized.sort_by_key(|range| range.start_us);
+    let mut merged: Vec<TimeRange> = Vec::new();
+
+    for range in normalized {
+        if let Some(last) = merged.last_mut() {
+            if range.start_us <= last.end_us {
+                if range.end_us > last.end_us {
+                    last.end_us = range.end_us;
+                }
+                continue;
+            }
+        }
+        merged.push(range);
+    }
+
+    merged
+}
+
+/// The parts of `[0, duration_us]` not covered by `remove_ranges`, which
+/// must already be normalized.
+pub fn invert_ranges(remove_ranges: &[TimeRange], duration_us: u64) -> Vec<TimeRange> {
+    if duration_us == 0 {
+        return Vec::new();
+    }
+
+    let mut keep_ranges = Vec::new();
+    let mut cursor = 0_u64;
+
+    for range in remove_ranges {
+        if range.start_us > cursor {
+            keep_ranges.push(TimeRange {
+                start_us: cursor,
+                end_us: range.start_us,
+            });
+        }
+        cursor = range.end_us.max(cursor);
+    }
+
+    if cursor < duration_us {
+        keep_ranges.push(TimeRange {
+            start_us: cursor,
+            end_us: duration_us,
+        });
+    }
+
+    keep_ranges
+}
+
+/// Moves a timestamp onto the nearest frame boundary allowed by `policy`.
+pub fn snap_to_frame(time_us: u64, fps: u32, policy: FrameSnapPolicy) -> u64 {
+    let fps = u128::from(fps.max(1));
+    let scaled = u128::from(time_us) * fps;
+    let frame = match policy {
+        FrameSnapPolicy::Off => return time_us,
+        FrameSnapPolicy::Floor => scaled / 1_000_000,
+        FrameSnapPolicy::Ceil => scaled.div_ceil(1_000_000),
+        FrameSnapPolicy::Nearest => (scaled + 500_000) / 1_000_000,
+    };
+    (frame * 1_000_000 / fps) as u64
+}
+
+/// Snaps both ends of every range to the frame grid and reports the largest
+/// shift applied. Ranges never grow past the media end; ranges that collapse
+/// to zero frames are dropped.
+pub fn quantize_ranges(
+    ranges: Vec<TimeRange>,
+    fps: u32,
+    policy: FrameSnapPolicy,
+    duration_us: u64,
+) -> (Vec<TimeRange>, u64) {
+    let mut max_shift_us = 0_u64;
+    let snapped = ranges
+        .into_iter()
+        .map(|range| {
+            let start_us = snap_to_frame(range.start_us, fps, policy);
+            let end_us = snap_to_frame(range.end_us, fps, policy);
+            max_shift_us = max_shift_us
+                .max(start_us.abs_diff(range.start_us))
+                .max(end_us.abs_diff(range.end_us));
+            TimeRange { start_us, end_us }
+        })
+        .collect();
+    (normalize_ranges(snapped, duration_us), max_shift_us)
+}
+
+/// Drops cuts shorter than `min_gap_us` (keeping that footage) and then cuts
+/// any kept segment shorter than `min_clip_duration_us`. Returns the final
+/// remove ranges plus the dropped cuts and dropped clips for reporting.
+fn drop_degenerate_segments(
+    remove_ranges: Vec<TimeRange>,
+    duration_us: u64,
+    min_gap_us: u64,
+    min_clip_duration_us: u64,
+) -> (Vec<TimeRange>, Vec<TimeRange>, Vec<TimeRange>) {
+    let (remove_ranges, dropped_cuts): (Vec<_>, Vec<_>) = remove_ranges
+        .into_iter()
+        .partition(|range| range.end_us - range.start_us >= min_gap_us);
+
+    let dropped_clips = invert_ranges(&remove_ranges, duration_us)
+        .into_iter()
+        .filter(|keep| keep.end_us - keep.start_us < min_clip_duration_us)
+        .collect::<Vec<_>>();
+    let mut merged = remove_ranges;
+    merged.extend(dropped_clips.iter().cloned());
+
+    (
+        normalize_ranges(merged, duration_us),
+        dropped_cuts,
+        dropped_clips,
+    )
+}
+
+/// One input of a rough cut: a source asset and what to remove from it.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoughCutSource {
+    pub source_ref: String,
+    pub duration_us: u64,
+    #[serde(default)]
+    pub remove_ranges: Vec<TimeRange>,
+}
+
+/// How kept segments from several sources are arranged on the timeline.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceArrangement {
+    /// Every kept segment of source 1, then source 2, ... (multi-take).
+    #[default]
+    Sequential,
+    /// Round-robin: segment 1 of each source, then segment 2, ... (multicam).
+    Interleave,
+    /// First source on the main track, each further source on its own track
+    /// starting at zero (A-roll + B-roll).
+    Layered,
+}
+
+/// Kept segments of a single source after range cleanup.
+struct PlannedSource {
+    source_ref: String,
+    duration_us: u64,
+    remove_ranges: Vec<TimeRange>,
+    keep_ranges: Vec<TimeRange>,
+    dropped_cuts: Vec<TimeRange>,
+    dropped_clips: Vec<TimeRange>,
+    max_shift_us: u64,
+}
+
+fn plan_source(source: RoughCutSource, fps: u32, options: &RoughCutOptions) -> PlannedSource {
+    let duration_us = source.duration_us;
+    let remove_ranges = normalize_ranges(source.remove_ranges, duration_us);
+    let (remove_ranges, max_shift_us) =
+        quantize_ranges(remove_ranges, fps, options.frame_snap, duration_us);
+    let (remove_ranges, dropped_cuts, dropped_clips) = drop_degenerate_segments(
+        remove_ranges,
+        duration_us,
+        options.min_gap_us,
+        options.min_clip_duration_us,
+    );
+    let keep_ranges = invert_ranges(&remove_ranges, duration_us);
+    PlannedSource {
+        source_ref: source.source_ref,
+        duration_us,
+        remove_ranges,
+        keep_ranges,
+        dropped_cuts,
+        dropped_clips,
+        max_shift_us,
+    }
+}
+
+/// A single-source rough cut: `duration_us` of `source_ref` with
+/// `remove_ranges` taken out, cleaned up according to `options`. Ranges in
+/// `protected` (marked sponsor reads) are kept whole and their clips
+/// protected from AI.
+pub fn build_rough_cut_timeline(
+    project_id: String,
+    duration_us: u64,
+    fps: u32,
+    source_ref: String,
+    remove_ranges: Vec<TimeRange>,
+    options: &RoughCutOptions,
+    protected: &[ProtectedRange],
+) -> Timeline {
+    build_multi_source_timeline(
+        project_id,
+        fps,
+        vec![RoughCutSource {
+            source_ref,
+            duration_us,
+            remove_ranges,
+        }],
+        SourceArrangement::Sequential,
+        options,
+        protected,
+    )
+}
+
+/// Cuts every source like [`build_rough_cut_timeline`] and lays the kept
+/// segments out as `arrangement` says, butted together per track.
+pub fn build_multi_source_timeline(
+    project_id: String,
+    fps: u32,
+    sources: Vec<RoughCutSource>,
+    arrangement: SourceArrangement,
+    options: &RoughCutOptions,
+    protected: &[ProtectedRange],
+) -> Timeline {
+    let planned = sources
+        .into_iter()
+        .map(|mut source| {
+            source.remove_ranges =
+                clip_flags::spare_protected(source.remove_ranges, &source.source_ref, protected);
+            plan_source(source, fps, options)
+        })
+        .collect::<Vec<_>>();
+
+    let video_track = TimelineTrack {
+        id: "track-video-main".to_string(),
+        name: "Main Video".to_string(),
+        kind: "video".to_string(),
+        order: 0,
+        locked: false,
+    };
+    let mut tracks = vec![video_track];
+    if arrangement == SourceArrangement::Layered {
+        for index in 1..planned.len() {
+            tracks.push(TimelineTrack {
+                id: format!("track-video-{}", index + 1),
+                name: format!("Video {}", index + 1),
+                kind: "video".to_string(),
+                order: index as u32,
+                locked: false,
+            });
+        }
+    }
+    tracks.push(TimelineTrack {
+        id: "track-captions".to_string(),
+        name: "Captions".to_string(),
+        kind: "caption".to_string(),
+        order: tracks.len() as u32,
+        locked: false,
+    });
+
+    // (source index, keep index, track index) in placement order.
+    let mut placements = Vec::new();
+    match arrangement {
+        SourceArrangement::Sequential => {
+            for (source_index, source) in planned.iter().enumerate() {
+                for keep_index in 0..source.keep_ranges.len() {
+                    placements.push((source_index, keep_index, 0));
+                }
+            }
+        }
+        SourceArrangement::Interleave => {
+            let rounds = planned
+                .iter()
+                .map(|source| source.keep_ranges.len())
+                .max()
+                .unwrap_or(0);
+            for keep_index in 0..rounds {
+                for (source_index, source) in planned.iter().enumerate() {
+                    if keep_index < source.keep_ranges.len() {
+                        placements.push((source_index, keep_index, 0));
+                    }
+                }
+            }
+        }
+        SourceArrangement::Layered => {
+            for (source_index, source) in planned.iter().enumerate() {
+                for keep_index in 0..source.keep_ranges.len() {
+                    placements.push((source_index, keep_index, source_index));
+                }
+            }
+        }
+    }
+
+    let mut clips = Vec::new();
+    let mut track_cursors = vec![0_u64; tracks.len()];
+
+    for (index, (source_index, keep_index, track_index)) in placements.into_iter().enumerate() {
+        let source = &planned[source_index];
+        let keep = &source.keep_ranges[keep_index];
+        let clip_duration = keep.end_us - keep.start_us;
+        let timeline_start = track_cursors[track_index];
+        let timeline_end = timeline_start + clip_duration;
+
+        clips.push(TimelineClip {
+            clip_id: format!("clip-{}", index + 1),
+            track_id: tracks[track_index].id.clone(),
+            clip_type: "source_clip".to_string(),
+            start_us: timeline_start,
+            end_us: timeline_end,
+            source_start_us: keep.start_us,
+            source_end_us: keep.end_us,
+            speed: 1.0,
+            speed_keyframes: Vec::new(),
+            locked: false,
+            protected_from_ai: false,
+            source_ref: source.source_ref.clone(),
+            effects: serde_json::json!({}),
+            transform: serde_json::json!({}),
+            meta: serde_json::json!({
+                "generatedBy": "ai-rough-cut",
+                "removeRangesApplied": source.remove_ranges,
+                "frameQuantization": {
+                    "policy": options.frame_snap,
+                    "fps": fps.max(1),
+                    "maxShiftUs": source.max_shift_us
+                }
+            }),
+        });
+
+        track_cursors[track_index] = timeline_end;
+    }
+
+    let source_reports = planned
+        .iter()
+        .map(|source| {
+            serde_json::json!({
+                "sourceRef": source.source_ref,
+                "durationUs": source.duration_us,
+                "droppedCuts": source.dropped_cuts,
+                "droppedClips": source.dropped_clips
+            })
+        })
+        .collect::<Vec<_>>();
+    let dropped_cuts = planned
+        .iter()
+        .flat_map(|source| source.dropped_cuts.iter().cloned())
+        .collect::<Vec<_>>();
+    let dropped_clips = planned
+        .iter()
+        .flat_map(|source| source.dropped_clips.iter().cloned())
+        .collect::<Vec<_>>();
+
+    let now = now_iso();
+    let mut timeline = Timeline {
+        id: format!("timeline-{}", generate_project_id()),
+        project_id,
+        version: 1,
+        status: "ROUGH_CUT_READY".to_string(),
+        fps: fps.max(1),
+        duration_us: track_cursors.into_iter().max().unwrap_or(0),
+        created_at: now.clone(),
+        updated_at: now,
+        tracks,
+        clips,
+        meta: serde_json::json!({
+            "roughCut": {
+                "arrangement": arrangement,
+                "minClipDurationUs": options.min_clip_duration_us,
+                "minGapUs": options.min_gap_us,
+                "droppedCuts": dropped_cuts,
+                "droppedClips": dropped_clips,
+                "sources": source_reports
+            }
+        }),
+    };
+    clip_flags::carry_over(&mut timeline, protected);
+    timeline
+}
+
+/// Source clips of the edit's main (lowest) video track, in timeline order.
+pub fn main_track_clips(timeline: &Timeline) -> Vec<&TimelineClip> {
+    let main_track = timeline
+        .tracks
+        .iter()
+        .filter(|track| track.kind == "video")
+        .min_by_key(|track| track.order)
+        .map(|track| track.id.as_str());
+    let mut clips = timeline
+        .clips
+        .iter()
+        .filter(|clip| clip.clip_type == "source_clip")
+        .filter(|clip| main_track.map_or(true, |track| clip.track_id == track))
+        .collect::<Vec<_>>();
+    clips.sort_by_key(|clip| clip.start_us);
+    clips
+}
+
+/// Maps a source timestamp onto the timeline through the source clips that
+/// reference it. Returns `None` when that part of the source was cut.
+pub fn source_to_timeline_us(
+    timeline: &Timeline,
+    source_ref: Option<&str>,
+    source_us: u64,
+) -> Option<u64> {
+    timeline
+        .clips
+        .iter()
+        .filter(|clip| clip.clip_type == "source_clip")
+        .filter(|clip| source_ref.map_or(true, |source| clip.source_ref == source))
+        .find(|clip| source_us >= clip.source_start_us && source_us < clip.source_end_us)
+        .map(|clip| {
+            clip.start_us + speed::source_to_timeline_offset(clip, source_us - clip.source_start_us)
+        })
+}
+
+/// Inverse of `source_to_timeline_us`: finds the source clip visible at a
+/// timeline position (the highest-ordered video track wins) and the source
+/// timestamp shown there.
+pub fn timeline_to_source_us(
+    timeline: &Timeline,
+    timeline_us: u64,
+) -> Option<(&TimelineClip, u64)> {
+    let track_order = |track_id: &str| {
+        timeline
+            .tracks
+            .iter()
+            .find(|track| track.id == track_id && track.kind == "video")
+            .map(|track| track.order)
+    };
+    timeline
+        .clips
+        .iter()
+        .filter(|clip| clip.clip_type == "source_clip")
+        .filter(|clip| timeline_us >= clip.start_us && timeline_us < clip.end_us)
+        .filter_map(|clip| track_order(&clip.track_id).map(|order| (order, clip)))
+        .max_by_key(|(order, _)| *order)
+        .map(|(_, clip)| {
+            let offset = speed::timeline_to_source_offset(clip, timeline_us - clip.start_us);
+            (clip, clip.source_start_us + offset)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const MAX_US: u64 = 10_000_000;
+
+    fn ranges() -> impl Strategy<Value = Vec<TimeRange>> {
+        prop::collection::vec((0..MAX_US, 0..MAX_US), 0..24).prop_map(|pairs| {
+            pairs
+                .into_iter()
+                .map(|(start_us, end_us)| TimeRange { start_us, end_us })
+                .collect()
+        })
+    }
+
+    fn pairs(ranges: &[TimeRange]) -> Vec<(u64, u64)> {
+        ranges
+            .iter()
+            .map(|range| (range.start_us, range.end_us))
+            .collect()
+    }
+
+    fn covers(ranges: &[TimeRange], time_us: u64) -> bool {
+        ranges
+            .iter()
+            .any(|range| range.start_us <= time_us && time_us < range.end_us)
+    }
+
+    /// Every range boundary and its neighbours, which is where coverage can
+    /// change.
+    fn probes(ranges: &[TimeRange], duration_us: u64) -> Vec<u64> {
+        ranges
+            .iter()
+            .flat_map(|range| [range.start_us, range.end_us])
+            .chain([0, duration_us])
+            .flat_map(|time| [time.saturating_sub(1), time, time + 1])
+            .collect()
+    }
+
+    fn assert_normalized(ranges: &[TimeRange], duration_us: u64) {
+        for range in ranges {
+            assert!(range.start_us < range.end_us && range.end_us <= duration_us);
+        }
+        for pair in ranges.windows(2) {
+            assert!(pair[0].end_us < pair[1].start_us, "{:?}", pairs(ranges));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn normalize_merges_without_changing_coverage(
+            input in ranges(),
+            duration_us in 0..MAX_US,
+        ) {
+            let normalized = normalize_ranges(input.clone(), duration_us);
+            assert_normalized(&normalized, duration_us);
+            for time in probes(&input, duration_us) {
+                let expected = time < duration_us && covers(&input, time);
+                prop_assert_eq!(covers(&normalized, time), expected, "at {}", time);
+            }
+            let again = normalize_ranges(normalized.clone(), duration_us);
+            prop_assert_eq!(pairs(&again), pairs(&normalized));
+        }
+
+        #[test]
+        fn inversion_partitions_the_media(input in ranges(), duration_us in 1..MAX_US) {
+            let removed = normalize_ranges(input, duration_us);
+            let kept = invert_ranges(&removed, duration_us);
+            assert_normalized(&kept, duration_us);
+            for time in probes(&removed, duration_us) {
+                if time < duration_us {
+                    prop_assert!(covers(&kept, time) != covers(&removed, time), "at {}", time);
+                }
+            }
+            let restored = invert_ranges(&kept, duration_us);
+            prop_assert_eq!(pairs(&restored), pairs(&removed));
+        }
+
+        #[test]
+        fn snapping_stays_within_a_frame(time_us in 0..MAX_US, fps in 1_u32..120) {
+            let frame_us = 1_000_000 / u64::from(fps) + 1;
+            let floor = snap_to_frame(time_us, fps, FrameSnapPolicy::Floor);
+            let ceil = snap_to_frame(time_us, fps, FrameSnapPolicy::Ceil);
+            let nearest = snap_to_frame(time_us, fps, FrameSnapPolicy::Nearest);
+            prop_assert!(floor <= time_us && time_us - floor < frame_us);
+            prop_assert!(ceil >= time_us && ceil - time_us < frame_us);
+            prop_assert!(nearest.abs_diff(time_us) <= frame_us / 2 + 1);
+            prop_assert_eq!(snap_to_frame(time_us, fps, FrameSnapPolicy::Off), time_us);
+        }
+
+        #[test]
+        fn quantized_ranges_stay_normalized(
+            input in ranges(),
+            duration_us in 1..MAX_US,
+            fps in 1_u32..120,
+        ) {
+            let normalized = normalize_ranges(input, duration_us);
+            let (quantized, max_shift_us) =
+                quantize_ranges(normalized, fps, FrameSnapPolicy::Nearest, duration_us);
+            assert_normalized(&quantized, duration_us);
+            prop_assert!(max_shift_us <= 1_000_000 / u64::from(fps) / 2 + 1);
+        }
+
+        #[test]
+        fn rough_cut_clips_are_contiguous(input in ranges(), duration_us in 1..MAX_US) {
+            let timeline = build_rough_cut_timeline(
+                "project-test".to_string(),
+                duration_us,
+                30,
+                "source-video".to_string(),
+                input,
+                &RoughCutOptions::default(),
+                &[],
+            );
+            let mut cursor = 0;
+            let mut source_cursor = 0;
+            for clip in &timeline.clips {
+                prop_assert_eq!(clip.start_us, cursor);
+                prop_assert_eq!(
+                    clip.end_us - clip.start_us,
+                    clip.source_end_us - clip.source_start_us
+                );
+                prop_assert!(clip.source_start_us >= source_cursor);
+                prop_assert!(clip.source_end_us <= duration_us);
+                cursor = clip.end_us;
+                source_cursor = clip.source_end_us;
+            }
+            prop_assert_eq!(timeline.duration_us, cursor);
+        }
+    }
+}
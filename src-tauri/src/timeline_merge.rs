@@ -0,0 +1,499 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::clip_flags;
+use crate::{now_iso, read_timeline, timeline_file_path, timeline_stats, Timeline, TimelineClip};
+
+/// Start of the error `save_timeline` returns when the stored timeline moved
+/// on since the editor loaded it.
+pub const CONFLICT_PREFIX: &str = "CONFLICT";
+
+/// Clip `meta` flag `save_timeline` sets on clips the editor changed.
+pub const USER_EDIT_FLAG: &str = "editedByUser";
+
+/// Held from the version check until the write so two saves can't both pass
+/// the check against the same stored version.
+static SAVE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeTimelineRequest {
+    /// The timeline as the editor loaded it.
+    base: Timeline,
+    /// The editor's edited copy of `base`.
+    timeline: Timeline,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineMerge {
+    /// The stored timeline with the editor's clip changes applied; carries
+    /// the stored version so it can be saved straight away.
+    pub timeline: Timeline,
+    /// Clips changed on both sides, or changed on one and deleted on the
+    /// other. The stored side is kept for these.
+    pub conflicts: Vec<String>,
+}
+
+/// A hand-edited clip whose source range the re-run's plan no longer keeps
+/// in full. The hand edit is kept.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RerunConflict {
+    pub clip_id: String,
+    pub source_ref: String,
+    pub source_start_us: u64,
+    pub source_end_us: u64,
+    /// How much of the clip's source range the new plan keeps.
+    pub planned_keep_us: u64,
+}
+
+/// How a pipeline re-run was merged with the hand-edited timeline.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RerunMerge {
+    /// Hand-edited clips carried over from the previous timeline.
+    pub preserved: Vec<String>,
+    /// Generated clips placed around them.
+    pub regenerated: usize,
+    pub conflicts: Vec<RerunConflict>,
+}
+
+pub fn lock_saves() -> MutexGuard<'static, ()> {
+    SAVE_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub fn conflict_error(server_version: u32, based_on: u32) -> String {
+    format!(
+        "{CONFLICT_PREFIX}: timeline is at version {server_version}, this save was based on version {based_on}. Reload, merge or save with force."
+    )
+}
+
+/// The stored timeline, if any; errors other than a missing file are
+/// returned so a corrupt store isn't silently overwritten.
+pub fn stored_timeline(project_id: &str) -> Result<Option<Timeline>, String> {
+    if !timeline_file_path(project_id)?.exists() {
+        return Ok(None);
+    }
+    read_timeline(project_id).map(Some)
+}
+
+/// Version the next save should get. Fails with [`conflict_error`] when the
+/// incoming timeline wasn't based on the stored one, unless `force` is set.
+pub fn next_version(stored: Option<&Timeline>, incoming: u32, force: bool) -> Result<u32, String> {
+    match stored {
+        Some(stored) if stored.version != incoming && !force => {
+            Err(conflict_error(stored.version, incoming))
+        }
+        Some(stored) => Ok(stored.version.max(incoming).saturating_add(1)),
+        None => Ok(incoming.saturating_add(1)),
+    }
+}
+
+fn same(left: &TimelineClip, right: &TimelineClip) -> bool {
+    serde_json::to_value(left).ok() == serde_json::to_value(right).ok()
+}
+
+pub fn is_user_edited(clip: &TimelineClip) -> bool {
+    clip.meta
+        .get(USER_EDIT_FLAG)
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Flags clips that are new or changed relative to the stored timeline, so
+/// a pipeline re-run knows to leave them alone.
+pub fn mark_user_edits(stored: Option<&Timeline>, timeline: &mut Timeline) {
+    let Some(stored) = stored else {
+        return;
+    };
+    let stored_clips = by_id(stored);
+    for clip in &mut timeline.clips {
+        let changed = stored_clips
+            .get(clip.clip_id.as_str())
+            .map_or(true, |stored| !same(stored, clip));
+        if !changed || is_user_edited(clip) {
+            continue;
+        }
+        if !clip.meta.is_object() {
+            clip.meta = json!({});
+        }
+        if let Some(meta) = clip.meta.as_object_mut() {
+            meta.insert(USER_EDIT_FLAG.to_string(), Value::Bool(true));
+        }
+    }
+}
+
+/// `start..end` minus every range in `holes`, which must be sorted.
+pub fn subtract_ranges(start: u64, end: u64, holes: &[(u64, u64)]) -> Vec<(u64, u64)> {
+    let mut pieces = Vec::new();
+    let mut cursor = start;
+    for &(hole_start, hole_end) in holes {
+        if hole_end <= cursor || hole_start >= end {
+            continue;
+        }
+        if hole_start > cursor {
+            pieces.push((cursor, hole_start));
+        }
+        cursor = cursor.max(hole_end);
+    }
+    if cursor < end {
+        pieces.push((cursor, end));
+    }
+    pieces
+}
+
+/// Folds a regenerated timeline around the previous timeline's hand-edited,
+/// locked and AI-protected source clips. Per track, generated clips lose any
+/// source range a kept clip on that track covers; the rest are slotted
+/// between the kept clips in source order and the track is laid out end to
+/// end. Other kept clips, and tracks the pipeline doesn't generate, are left
+/// as they were. The regenerated timeline takes over the previous one's
+/// identity.
+pub fn preserve_user_edits(previous: &Timeline, regenerated: &mut Timeline) -> RerunMerge {
+    regenerated.id = previous.id.clone();
+    regenerated.created_at = previous.created_at.clone();
+    regenerated.version = previous.version.saturating_add(1);
+    regenerated.updated_at = now_iso();
+
+    let edited = previous
+        .clips
+        .iter()
+        .filter(|clip| is_user_edited(clip) || clip_flags::is_ai_protected(clip))
+        .collect::<Vec<_>>();
+    let mut report = RerunMerge {
+        preserved: edited.iter().map(|clip| clip.clip_id.clone()).collect(),
+        ..RerunMerge::default()
+    };
+    if edited.is_empty() {
+        report.regenerated = regenerated.clips.len();
+        return report;
+    }
+
+    let mut source_order = Vec::<&str>::new();
+    for clip in &regenerated.clips {
+        if !source_order.contains(&clip.source_ref.as_str()) {
+            source_order.push(&clip.source_ref);
+        }
+    }
+    let source_key = |clip: &TimelineClip| {
+        let index = source_order
+            .iter()
+            .position(|source| *source == clip.source_ref)
+            .unwrap_or(source_order.len());
+        (index, clip.source_start_us)
+    };
+
+    for clip in edited.iter().filter(|clip| clip.clip_type == "source_clip") {
+        let planned_keep_us = regenerated
+            .clips
+            .iter()
+            .filter(|generated| {
+                generated.clip_type == "source_clip" && generated.source_ref == clip.source_ref
+            })
+            .map(|generated| {
+                generated
+                    .source_end_us
+                    .min(clip.source_end_us)
+                    .saturating_sub(generated.source_start_us.max(clip.source_start_us))
+            })
+            .sum::<u64>();
+        if planned_keep_us < clip.source_end_us.saturating_sub(clip.source_start_us) {
+            report.conflicts.push(RerunConflict {
+                clip_id: clip.clip_id.clone(),
+                source_ref: clip.source_ref.clone(),
+                source_start_us: clip.source_start_us,
+                source_end_us: clip.source_end_us,
+                planned_keep_us,
+            });
+        }
+    }
+
+    let generated_tracks = regenerated
+        .clips
+        .iter()
+        .map(|clip| clip.track_id.clone())
+        .collect::<BTreeSet<_>>();
+    let mut used_ids = edited
+        .iter()
+        .map(|clip| clip.clip_id.clone())
+        .collect::<BTreeSet<_>>();
+    let mut next_id = 1_usize;
+    let mut clips = edited
+        .iter()
+        .filter(|clip| {
+            clip.clip_type != "source_clip" || !generated_tracks.contains(&clip.track_id)
+        })
+        .map(|clip| (*clip).clone())
+        .collect::<Vec<_>>();
+
+    for track_id in &generated_tracks {
+        let mut kept = edited
+            .iter()
+            .filter(|clip| clip.clip_type == "source_clip" && &clip.track_id == track_id)
+            .collect::<Vec<_>>();
+        kept.sort_by_key(|clip| clip.start_us);
+
+        let mut pieces = Vec::new();
+        for generated in regenerated
+            .clips
+            .iter()
+            .filter(|clip| &clip.track_id == track_id)
+        {
+            let mut holes = kept
+                .iter()
+                .filter(|clip| clip.source_ref == generated.source_ref)
+                .map(|clip| (clip.source_start_us, clip.source_end_us))
+                .collect::<Vec<_>>();
+            holes.sort_unstable();
+            for (start, end) in
+                subtract_ranges(generated.source_start_us, generated.source_end_us, &holes)
+            {
+                while used_ids.contains(&format!("clip-{next_id}")) {
+                    next_id += 1;
+                }
+                let clip_id = format!("clip-{next_id}");
+                used_ids.insert(clip_id.clone());
+                let mut piece = generated.clone();
+                piece.clip_id = clip_id;
+                piece.source_start_us = start;
+                piece.source_end_us = end;
+                piece.end_us = piece.start_us + (end - start);
+                pieces.push(piece);
+            }
+        }
+        report.regenerated += pieces.len();
+
+        let mut cursor = 0_u64;
+        let mut place = |mut clip: TimelineClip| {
+            let duration = clip.end_us.saturating_sub(clip.start_us);
+            clip.start_us = cursor;
+            clip.end_us = cursor + duration;
+            cursor = clip.end_us;
+            clips.push(clip);
+        };
+        let mut pieces = pieces.into_iter().peekable();
+        for clip in kept {
+            while let Some(piece) = pieces.next_if(|piece| source_key(piece) < source_key(clip)) {
+                place(piece);
+            }
+            place(TimelineClip::clone(clip));
+        }
+        pieces.for_each(place);
+    }
+
+    for track in &previous.tracks {
+        match regenerated
+            .tracks
+            .iter_mut()
+            .find(|existing| existing.id == track.id)
+        {
+            Some(existing) => *existing = track.clone(),
+            None => regenerated.tracks.push(track.clone()),
+        }
+    }
+    clips.sort_by_key(|clip| clip.start_us);
+    regenerated.clips = clips;
+    regenerated.duration_us = timeline_stats::content_end_us(regenerated);
+    report
+}
+
+fn by_id(timeline: &Timeline) -> BTreeMap<&str, &TimelineClip> {
+    timeline
+        .clips
+        .iter()
+        .map(|clip| (clip.clip_id.as_str(), clip))
+        .collect()
+}
+
+/// Three-way merge by clip id. Edits, additions and deletions made on only
+/// one side are kept; anything both sides touched differently keeps the
+/// stored (`theirs`) clip and is reported. Tracks added by the editor are
+/// carried over; everything else comes from `theirs`.
+pub fn merge_clips(base: &Timeline, ours: &Timeline, theirs: &Timeline) -> TimelineMerge {
+    let base_clips = by_id(base);
+    let our_clips = by_id(ours);
+    let mut conflicts = BTreeSet::new();
+    let mut clips = Vec::new();
+
+    for their in &theirs.clips {
+        let id = their.clip_id.as_str();
+        let clip = match (base_clips.get(id), our_clips.get(id)) {
+            // Theirs added it, or both added it.
+            (None, None) => Some(their),
+            (None, Some(our)) => {
+                if !same(our, their) {
+                    conflicts.insert(id.to_string());
+                }
+                Some(their)
+            }
+            // We deleted it.
+            (Some(base), None) => {
+                if same(base, their) {
+                    None
+                } else {
+                    conflicts.insert(id.to_string());
+                    Some(their)
+                }
+            }
+            (Some(base), Some(our)) => {
+                if same(base, our) || same(our, their) {
+                    Some(their)
+                } else if same(base, their) {
+                    Some(*our)
+                } else {
+                    conflicts.insert(id.to_string());
+                    Some(their)
+                }
+            }
+        };
+        clips.extend(clip.cloned());
+    }
+
+    let their_ids = by_id(theirs);
+    for our in &ours.clips {
+        let id = our.clip_id.as_str();
+        if their_ids.contains_key(id) {
+            continue;
+        }
+        match base_clips.get(id) {
+            None => clips.push(our.clone()),
+            // They deleted it; keep the deletion unless we edited the clip.
+            Some(base) => {
+                if !same(base, our) {
+                    conflicts.insert(id.to_string());
+                }
+            }
+        }
+    }
+
+    let mut timeline = theirs.clone();
+    for track in &ours.tracks {
+        if !timeline
+            .tracks
+            .iter()
+            .any(|existing| existing.id == track.id)
+        {
+            timeline.tracks.push(track.clone());
+        }
+    }
+    timeline.clips = clips;
+    timeline.duration_us = timeline_stats::content_end_us(&timeline);
+    TimelineMerge {
+        timeline,
+        conflicts: conflicts.into_iter().collect(),
+    }
+}
+
+/// Merges the editor's changes onto the stored timeline after a save
+/// conflict. Nothing is written; save the result to keep it.
+#[tauri::command]
+pub async fn merge_timeline(request: MergeTimelineRequest) -> Result<TimelineMerge, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let project_id = &request.timeline.project_id;
+        if request.base.project_id != *project_id {
+            return Err("Base and edited timelines belong to different projects.".to_string());
+        }
+        let theirs = read_timeline(project_id)?;
+        Ok(merge_clips(&request.base, &request.timeline, &theirs))
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+
+    fn clip(id: &str, start_us: u64) -> TimelineClip {
+        test_fixtures::clip(id, "video-main", start_us, start_us + 1_000_000)
+    }
+
+    fn timeline(version: u32, clips: Vec<TimelineClip>) -> Timeline {
+        let mut timeline = test_fixtures::timeline(30, Vec::new(), clips);
+        timeline.version = version;
+        timeline
+    }
+
+    #[test]
+    fn stale_saves_conflict_unless_forced() {
+        let stored = timeline(4, Vec::new());
+        assert_eq!(next_version(Some(&stored), 4, false), Ok(5));
+        let error = next_version(Some(&stored), 3, false).unwrap_err();
+        assert!(error.starts_with(CONFLICT_PREFIX) && error.contains("version 4"));
+        assert_eq!(next_version(Some(&stored), 3, true), Ok(5));
+        assert_eq!(next_version(None, 0, false), Ok(1));
+    }
+
+    #[test]
+    fn merge_keeps_one_sided_changes() {
+        let base = timeline(
+            1,
+            vec![clip("a", 0), clip("b", 1_000_000), clip("c", 2_000_000)],
+        );
+        // Ours moves a and deletes b; theirs moves c and adds d.
+        let ours = timeline(1, vec![clip("a", 500_000), clip("c", 2_000_000)]);
+        let theirs = timeline(
+            2,
+            vec![
+                clip("a", 0),
+                clip("b", 1_000_000),
+                clip("c", 3_000_000),
+                clip("d", 5_000_000),
+            ],
+        );
+        let merge = merge_clips(&base, &ours, &theirs);
+        let starts = merge
+            .timeline
+            .clips
+            .iter()
+            .map(|clip| (clip.clip_id.as_str(), clip.start_us))
+            .collect::<Vec<_>>();
+        assert_eq!(starts, [("a", 500_000), ("c", 3_000_000), ("d", 5_000_000)]);
+        assert!(merge.conflicts.is_empty());
+        assert_eq!(merge.timeline.version, 2);
+
+        let clash = timeline(2, vec![clip("a", 700_000)]);
+        assert_eq!(merge_clips(&base, &ours, &clash).conflicts, ["a"]);
+    }
+
+    #[test]
+    fn rerun_keeps_hand_edited_regions() {
+        let mut edited = clip("b", 2_000_000);
+        edited.meta = json!({ USER_EDIT_FLAG: true });
+        let mut dropped = clip("e", 6_000_000);
+        dropped.meta = json!({ USER_EDIT_FLAG: true });
+        let previous = timeline(3, vec![clip("a", 0), edited, dropped]);
+        let mut whole = clip("clip-1", 0);
+        whole.end_us = 4_000_000;
+        whole.source_end_us = 4_000_000;
+        let mut regenerated = timeline(1, vec![whole]);
+
+        let merge = preserve_user_edits(&previous, &mut regenerated);
+        let layout = regenerated
+            .clips
+            .iter()
+            .map(|clip| (clip.clip_id.as_str(), clip.start_us, clip.source_start_us))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            layout,
+            [
+                ("clip-1", 0, 0),
+                ("b", 2_000_000, 2_000_000),
+                ("clip-2", 3_000_000, 3_000_000),
+                ("e", 4_000_000, 6_000_000),
+            ]
+        );
+        assert_eq!(merge.preserved, ["b", "e"]);
+        assert_eq!(merge.regenerated, 2);
+        assert_eq!(merge.conflicts.len(), 1);
+        assert_eq!(merge.conflicts[0].clip_id, "e");
+        assert_eq!(regenerated.version, 4);
+    }
+}
@@ -0,0 +1,34 @@
+use tauri::ipc::{InvokeBody, Request, Response};
+
+use crate::{read_timeline, save_timeline_blocking, GetTimelineRequest, SaveTimelineRequest};
+
+/// MessagePack with field names, so the decoded value has the same shape as
+/// the JSON `get_timeline` returns.
+fn pack<T: serde::Serialize>(value: &T) -> Result<Response, String> {
+    rmp_serde::to_vec_named(value)
+        .map(Response::new)
+        .map_err(|error| format!("Timeline pack error: {error}"))
+}
+
+/// `get_timeline` as a MessagePack byte response, which the bridge passes
+/// through without a JSON round-trip.
+#[tauri::command]
+pub async fn get_timeline_packed(request: GetTimelineRequest) -> Result<Response, String> {
+    tauri::async_runtime::spawn_blocking(move || pack(&read_timeline(&request.project_id)?))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// `save_timeline` taking the same request MessagePack-encoded as the raw
+/// invoke body, and answering with the saved timeline packed the same way.
+#[tauri::command]
+pub async fn save_timeline_packed(request: Request<'_>) -> Result<Response, String> {
+    let InvokeBody::Raw(bytes) = request.body() else {
+        return Err("save_timeline_packed expects a MessagePack body.".to_string());
+    };
+    let request = rmp_serde::from_slice::<SaveTimelineRequest>(bytes)
+        .map_err(|error| format!("Invalid packed timeline: {error}"))?;
+    tauri::async_runtime::spawn_blocking(move || pack(&save_timeline_blocking(request)?))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{read_timeline, Timeline};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineStatsRequest {
+    project_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackStats {
+    pub track_id: String,
+    pub kind: String,
+    pub clip_count: usize,
+    /// End of the track's last clip.
+    pub duration_us: u64,
+    /// Time actually covered by clips, with overlaps counted once.
+    pub covered_us: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineStats {
+    pub duration_us: u64,
+    /// What the saved timeline says, which may be stale.
+    pub stored_duration_us: u64,
+    pub clip_count: usize,
+    pub tracks: Vec<TrackStats>,
+}
+
+/// End of the last clip across all tracks.
+pub fn content_end_us(timeline: &Timeline) -> u64 {
+    timeline
+        .clips
+        .iter()
+        .map(|clip| clip.end_us)
+        .max()
+        .unwrap_or(0)
+}
+
+fn track_stats(timeline: &Timeline, track_id: &str, kind: &str) -> TrackStats {
+    let mut ranges = timeline
+        .clips
+        .iter()
+        .filter(|clip| clip.track_id == track_id)
+        .map(|clip| (clip.start_us, clip.end_us.max(clip.start_us)))
+        .collect::<Vec<_>>();
+    ranges.sort_unstable();
+    let clip_count = ranges.len();
+    let duration_us = ranges.iter().map(|(_, end)| *end).max().unwrap_or(0);
+    let mut covered_us = 0;
+    let mut covered_until = 0;
+    for (start, end) in ranges {
+        let start = start.max(covered_until);
+        if end > start {
+            covered_us += end - start;
+            covered_until = end;
+        }
+    }
+    TrackStats {
+        track_id: track_id.to_string(),
+        kind: kind.to_string(),
+        clip_count,
+        duration_us,
+        covered_us,
+    }
+}
+
+pub fn timeline_stats(timeline: &Timeline) -> TimelineStats {
+    let mut tracks = timeline.tracks.iter().collect::<Vec<_>>();
+    tracks.sort_by_key(|track| track.order);
+    TimelineStats {
+        duration_us: content_end_us(timeline),
+        stored_duration_us: timeline.duration_us,
+        clip_count: timeline.clips.len(),
+        tracks: tracks
+            .into_iter()
+            .map(|track| track_stats(timeline, &track.id, &track.kind))
+            .collect(),
+    }
+}
+
+#[tauri::command]
+pub async fn recompute_timeline_stats(
+    request: TimelineStatsRequest,
+) -> Result<TimelineStats, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let timeline = read_timeline(&request.project_id)?;
+        Ok(timeline_stats(&timeline))
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,308 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::media::{resolve_source_path, MediaKind};
+use crate::{media_roots, read_timeline, speed, subtitles, Timeline, TimelineClip};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineDiagnostic {
+    pub severity: DiagnosticSeverity,
+    /// Unset for problems with a track rather than a clip.
+    pub clip_id: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateTimelineRequest {
+    project_id: String,
+}
+
+fn error(clip: &TimelineClip, message: String) -> TimelineDiagnostic {
+    TimelineDiagnostic {
+        severity: DiagnosticSeverity::Error,
+        clip_id: Some(clip.clip_id.clone()),
+        message,
+    }
+}
+
+fn warning(clip: &TimelineClip, message: String) -> TimelineDiagnostic {
+    TimelineDiagnostic {
+        severity: DiagnosticSeverity::Warning,
+        clip_id: Some(clip.clip_id.clone()),
+        message,
+    }
+}
+
+/// Only path-like overlay refs point at files; `template:` and `stock:` refs
+/// are placeholders the render fills in.
+fn overlay_file(source_ref: &str) -> Option<PathBuf> {
+    if let Some(path) = source_ref.strip_prefix("file://") {
+        return Some(PathBuf::from(path));
+    }
+    (source_ref.starts_with('/') || source_ref.starts_with("./")).then(|| PathBuf::from(source_ref))
+}
+
+/// What a clip plays: the `meta.kind` ingest or the planner recorded,
+/// otherwise the file's extension.
+fn clip_media_kind(clip: &TimelineClip, path: Option<&Path>) -> Option<MediaKind> {
+    clip.meta
+        .get("kind")
+        .and_then(Value::as_str)
+        .and_then(MediaKind::parse)
+        .or_else(|| path.and_then(MediaKind::of_path))
+}
+
+/// Flags media on a track that can't carry it. Audio-only sources stay
+/// allowed on video tracks, where audio-only edits render from.
+fn check_placement(
+    clip: &TimelineClip,
+    track_kind: &str,
+    media: MediaKind,
+    diagnostics: &mut Vec<TimelineDiagnostic>,
+) {
+    let track = &clip.track_id;
+    match (track_kind, media) {
+        ("caption", _) => diagnostics.push(error(
+            clip,
+            format!("Media can't go on caption track {track}."),
+        )),
+        ("audio", MediaKind::Image) => diagnostics.push(error(
+            clip,
+            format!("Image on audio track {track}; move it to a video or overlay track."),
+        )),
+        ("audio", MediaKind::Video) => diagnostics.push(warning(
+            clip,
+            format!("Video on audio track {track}; its picture belongs on a video track."),
+        )),
+        (_, MediaKind::Audio) if track_kind != "audio" && clip.clip_type == "asset_clip" => {
+            diagnostics.push(error(
+                clip,
+                format!(
+                    "Audio asset on {track_kind} track {track} has no picture to overlay; \
+                     move it to an audio track."
+                ),
+            ))
+        }
+        _ => {}
+    }
+}
+
+fn check_ranges(
+    clip: &TimelineClip,
+    timeline: &Timeline,
+    diagnostics: &mut Vec<TimelineDiagnostic>,
+) {
+    if clip.end_us < clip.start_us {
+        diagnostics.push(error(
+            clip,
+            format!(
+                "Clip ends ({}us) before it starts ({}us).",
+                clip.end_us, clip.start_us
+            ),
+        ));
+    } else if clip.end_us == clip.start_us {
+        diagnostics.push(error(clip, "Clip has zero length.".to_string()));
+    }
+
+    if clip.clip_type == "source_clip" {
+        if clip.source_end_us < clip.source_start_us {
+            diagnostics.push(error(
+                clip,
+                format!(
+                    "Source range ends ({}us) before it starts ({}us).",
+                    clip.source_end_us, clip.source_start_us
+                ),
+            ));
+        } else if clip.source_end_us == clip.source_start_us {
+            diagnostics.push(error(clip, "Clip has an empty source range.".to_string()));
+        }
+        if let Err(message) = speed::validate_clip_speed(clip, timeline.fps) {
+            diagnostics.push(error(clip, message));
+        }
+    }
+
+    if clip.end_us > timeline.duration_us {
+        diagnostics.push(error(
+            clip,
+            format!(
+                "Clip ends at {}us, past the timeline duration ({}us).",
+                clip.end_us, timeline.duration_us
+            ),
+        ));
+    }
+}
+
+/// Checks a timeline for problems that would break playback or rendering.
+/// Errors mean the render will fail or drop content; warnings are worth a look.
+pub fn validate(project_id: &str, timeline: &Timeline) -> Vec<TimelineDiagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let mut track_ids = HashSet::new();
+    let track_kinds = timeline
+        .tracks
+        .iter()
+        .map(|track| (track.id.as_str(), track.kind.as_str()))
+        .collect::<HashMap<_, _>>();
+    for track in &timeline.tracks {
+        if !track_ids.insert(track.id.as_str()) {
+            diagnostics.push(TimelineDiagnostic {
+                severity: DiagnosticSeverity::Error,
+                clip_id: None,
+                message: format!("Duplicate track id {}.", track.id),
+            });
+        }
+    }
+
+    let mut clip_ids = HashSet::new();
+    let mut resolved_sources = HashMap::new();
+    for clip in &timeline.clips {
+        if !clip_ids.insert(clip.clip_id.as_str()) {
+            diagnostics.push(error(clip, format!("Duplicate clip id {}.", clip.clip_id)));
+        }
+        if !track_ids.contains(clip.track_id.as_str()) {
+            diagnostics.push(error(
+                clip,
+                format!("Clip is on missing track {}.", clip.track_id),
+            ));
+        }
+
+        check_ranges(clip, timeline, &mut diagnostics);
+
+        let media_path = match clip.clip_type.as_str() {
+            "source_clip" => {
+                let resolved = resolved_sources
+                    .entry(clip.source_ref.clone())
+                    .or_insert_with(|| resolve_source_path(project_id, Some(&clip.source_ref)));
+                if let Err(message) = resolved {
+                    diagnostics.push(error(clip, message.clone()));
+                }
+                resolved.as_ref().ok().cloned()
+            }
+            "asset_clip" | "template_clip" => {
+                let file = overlay_file(&clip.source_ref);
+                match &file {
+                    Some(path) if !path.is_file() => diagnostics.push(error(
+                        clip,
+                        format!("Overlay file not found: {}", path.display()),
+                    )),
+                    Some(_) => {}
+                    None if clip.clip_type == "asset_clip" => diagnostics.push(warning(
+                        clip,
+                        format!(
+                            "Asset {} has not been downloaded and will be skipped at render.",
+                            clip.source_ref
+                        ),
+                    )),
+                    None => {}
+                }
+                file
+            }
+            "audio_clip" => {
+                let path = media_roots::reference_path(&clip.source_ref);
+                if !path.is_file() {
+                    diagnostics.push(error(
+                        clip,
+                        format!("Audio file not found: {}", path.display()),
+                    ));
+                }
+                if track_kinds.get(clip.track_id.as_str()) != Some(&"audio") {
+                    diagnostics.push(error(
+                        clip,
+                        format!("Audio clip on non-audio track {}.", clip.track_id),
+                    ));
+                }
+                Some(path)
+            }
+            _ => None,
+        };
+        if let (Some(track_kind), Some(media)) = (
+            track_kinds.get(clip.track_id.as_str()),
+            clip_media_kind(clip, media_path.as_deref()),
+        ) {
+            check_placement(clip, track_kind, media, &mut diagnostics);
+        }
+        if let Err(message) = subtitles::caption_override(clip) {
+            diagnostics.push(warning(
+                clip,
+                format!("{message} The caption will use the project style."),
+            ));
+        }
+    }
+
+    let mut by_track = HashMap::<&str, Vec<&TimelineClip>>::new();
+    for clip in timeline
+        .clips
+        .iter()
+        .filter(|clip| clip.end_us > clip.start_us)
+    {
+        by_track
+            .entry(clip.track_id.as_str())
+            .or_default()
+            .push(clip);
+    }
+    for track in &timeline.tracks {
+        let Some(clips) = by_track.get_mut(track.id.as_str()) else {
+            continue;
+        };
+        clips.sort_by_key(|clip| (clip.start_us, clip.end_us));
+        for pair in clips.windows(2) {
+            if pair[1].start_us < pair[0].end_us {
+                diagnostics.push(error(
+                    pair[1],
+                    format!(
+                        "Clip overlaps {} on track {} ({}us-{}us).",
+                        pair[0].clip_id,
+                        track.id,
+                        pair[1].start_us,
+                        pair[0].end_us.min(pair[1].end_us)
+                    ),
+                ));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+pub fn has_errors(diagnostics: &[TimelineDiagnostic]) -> bool {
+    diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+}
+
+/// One-line summary for refusing a save, e.g. "3 timeline error(s): ...".
+pub fn error_summary(diagnostics: &[TimelineDiagnostic]) -> String {
+    let errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+        .map(|diagnostic| match &diagnostic.clip_id {
+            Some(clip_id) => format!("{clip_id}: {}", diagnostic.message),
+            None => diagnostic.message.clone(),
+        })
+        .collect::<Vec<_>>();
+    format!("{} timeline error(s): {}", errors.len(), errors.join("; "))
+}
+
+#[tauri::command]
+pub async fn validate_timeline(
+    request: ValidateTimelineRequest,
+) -> Result<Vec<TimelineDiagnostic>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let timeline = read_timeline(&request.project_id)?;
+        Ok(validate(&request.project_id, &timeline))
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+
+use crate::timeline::TimeRange;
+use crate::{read_timeline, Timeline, TimelineClip, TimelineTrack};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTimelineWindowRequest {
+    project_id: String,
+    range: TimeRange,
+    /// Only clips on tracks of these kinds; every track when absent.
+    track_kinds: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackClipCount {
+    pub track_id: String,
+    pub kind: String,
+    /// Clips on the whole track, so the UI can size its scroll area.
+    pub clip_count: usize,
+    pub window_count: usize,
+}
+
+/// The timeline without most of its clips: everything the editor needs to
+/// lay out the tracks, plus the clips it is about to draw.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineWindow {
+    pub id: String,
+    pub project_id: String,
+    pub version: u32,
+    pub fps: u32,
+    pub duration_us: u64,
+    pub updated_at: String,
+    pub tracks: Vec<TimelineTrack>,
+    pub range: TimeRange,
+    /// Clips overlapping `range`, in stored order.
+    pub clips: Vec<TimelineClip>,
+    pub track_counts: Vec<TrackClipCount>,
+}
+
+fn overlaps(clip: &TimelineClip, range: &TimeRange) -> bool {
+    clip.start_us < range.end_us && clip.end_us > range.start_us
+}
+
+pub fn timeline_window(
+    timeline: &Timeline,
+    range: TimeRange,
+    track_kinds: Option<&[String]>,
+) -> TimelineWindow {
+    let wanted = |kind: &str| track_kinds.map_or(true, |kinds| kinds.iter().any(|k| k == kind));
+    let mut tracks = timeline.tracks.clone();
+    tracks.sort_by_key(|track| track.order);
+
+    let track_counts = tracks
+        .iter()
+        .filter(|track| wanted(&track.kind))
+        .map(|track| {
+            let on_track = timeline
+                .clips
+                .iter()
+                .filter(|clip| clip.track_id == track.id);
+            let (clip_count, window_count) = on_track.fold((0, 0), |(all, window), clip| {
+                (all + 1, window + usize::from(overlaps(clip, &range)))
+            });
+            TrackClipCount {
+                track_id: track.id.clone(),
+                kind: track.kind.clone(),
+                clip_count,
+                window_count,
+            }
+        })
+        .collect::<Vec<_>>();
+    let clips = timeline
+        .clips
+        .iter()
+        .filter(|clip| overlaps(clip, &range))
+        .filter(|clip| {
+            track_counts
+                .iter()
+                .any(|track| track.track_id == clip.track_id)
+        })
+        .cloned()
+        .collect();
+
+    TimelineWindow {
+        id: timeline.id.clone(),
+        project_id: timeline.project_id.clone(),
+        version: timeline.version,
+        fps: timeline.fps,
+        duration_us: timeline.duration_us,
+        updated_at: timeline.updated_at.clone(),
+        tracks,
+        range,
+        clips,
+        track_counts,
+    }
+}
+
+/// Clips intersecting `range` instead of the whole timeline, for editors
+/// that only draw what is on screen.
+#[tauri::command]
+pub async fn get_timeline_window(
+    request: GetTimelineWindowRequest,
+) -> Result<TimelineWindow, String> {
+    if request.range.end_us <= request.range.start_us {
+        return Err("Window range must end after it starts.".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let timeline = read_timeline(&request.project_id)?;
+        Ok(timeline_window(
+            &timeline,
+            request.range,
+            request.track_kinds.as_deref(),
+        ))
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{clip, timeline, track};
+
+    #[test]
+    fn window_keeps_overlapping_clips_and_counts_all() {
+        let timeline = timeline(
+            30,
+            vec![track("video", "video", 0), track("captions", "caption", 1)],
+            vec![
+                clip("v1", "video", 0, 20),
+                clip("v2", "video", 20, 40),
+                clip("c1", "captions", 5, 10),
+                clip("c2", "captions", 30, 35),
+            ],
+        );
+        let range = TimeRange {
+            start_us: 10,
+            end_us: 20,
+        };
+        let window = timeline_window(&timeline, range.clone(), None);
+        let ids = window
+            .clips
+            .iter()
+            .map(|clip| clip.clip_id.as_str())
+            .collect::<Vec<_>>();
+        // Clips touching the window edges are left out.
+        assert_eq!(ids, ["v1"]);
+        assert_eq!(window.track_counts[1].clip_count, 2);
+        assert_eq!(window.track_counts[1].window_count, 0);
+
+        let captions = timeline_window(&timeline, range, Some(&["caption".to_string()]));
+        assert!(captions.clips.is_empty());
+        assert_eq!(captions.track_counts.len(), 1);
+    }
+}
@@ -0,0 +1,297 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::clip_flags;
+use crate::subtitles::parse_color;
+use crate::{now_iso, read_timeline, write_timeline, Timeline, TimelineClip, TimelineTrack};
+
+pub const TITLE_CLIP_TYPE: &str = "title";
+pub const TITLE_TRACK_ID: &str = "track-titles";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TitlePosition {
+    Top,
+    #[default]
+    Center,
+    Bottom,
+    LowerThird,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TitleAnimation {
+    None,
+    #[default]
+    Fade,
+    SlideUp,
+}
+
+/// What a title clip shows. Stored under `meta.title` on the clip, which is
+/// where the render pipeline reads it from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TitlePayload {
+    pub text: String,
+    #[serde(default = "default_font_family")]
+    pub font_family: String,
+    /// Pixels at a 1080-pixel short side.
+    #[serde(default = "default_font_size")]
+    pub font_size: u32,
+    #[serde(default = "default_color")]
+    pub color: String,
+    /// Draws a box behind the text when set.
+    #[serde(default)]
+    pub background_color: Option<String>,
+    #[serde(default)]
+    pub position: TitlePosition,
+    #[serde(default)]
+    pub animation: TitleAnimation,
+    pub duration_us: u64,
+}
+
+fn default_font_family() -> String {
+    "Arial".to_string()
+}
+
+fn default_font_size() -> u32 {
+    72
+}
+
+fn default_color() -> String {
+    "#FFFFFF".to_string()
+}
+
+impl TitlePayload {
+    /// Default styling with no text yet.
+    pub fn new(duration_us: u64) -> Self {
+        Self {
+            text: String::new(),
+            font_family: default_font_family(),
+            font_size: default_font_size(),
+            color: default_color(),
+            background_color: None,
+            position: TitlePosition::default(),
+            animation: TitleAnimation::default(),
+            duration_us,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.text.trim().is_empty() {
+            return Err("Title text is empty.".to_string());
+        }
+        if self.font_family.trim().is_empty() {
+            return Err("Title font family is empty.".to_string());
+        }
+        if !(8..=400).contains(&self.font_size) {
+            return Err(format!(
+                "Title font size must be 8-400, got {}.",
+                self.font_size
+            ));
+        }
+        parse_color(&self.color, "Title color")?;
+        if let Some(background) = &self.background_color {
+            parse_color(background, "Title background color")?;
+        }
+        if self.duration_us < 100_000 {
+            return Err("Title duration must be at least 100ms.".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn from_clip(clip: &TimelineClip) -> Option<Self> {
+        if clip.clip_type != TITLE_CLIP_TYPE {
+            return None;
+        }
+        serde_json::from_value(clip.meta.get("title")?.clone()).ok()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddTitleClipRequest {
+    project_id: String,
+    start_us: u64,
+    title: TitlePayload,
+    /// Defaults to the titles track, which is created when missing.
+    track_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTitleClipRequest {
+    project_id: String,
+    clip_id: String,
+    /// Replaces the whole payload; the clip length follows its duration.
+    title: Option<TitlePayload>,
+    start_us: Option<u64>,
+    /// Change the clip even when it is locked.
+    force: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TitleClipResult {
+    pub clip_id: String,
+    pub timeline: Timeline,
+}
+
+fn ensure_title_track(timeline: &mut Timeline) -> String {
+    if !timeline
+        .tracks
+        .iter()
+        .any(|track| track.id == TITLE_TRACK_ID)
+    {
+        let order = timeline
+            .tracks
+            .iter()
+            .map(|track| track.order + 1)
+            .max()
+            .unwrap_or(0);
+        timeline.tracks.push(TimelineTrack {
+            id: TITLE_TRACK_ID.to_string(),
+            name: "Titles".to_string(),
+            kind: "title".to_string(),
+            order,
+            locked: false,
+        });
+    }
+    TITLE_TRACK_ID.to_string()
+}
+
+fn check_track_unlocked(timeline: &Timeline, track_id: &str) -> Result<(), String> {
+    let track = timeline
+        .tracks
+        .iter()
+        .find(|track| track.id == track_id)
+        .ok_or_else(|| format!("Track not found: {track_id}"))?;
+    if track.locked {
+        return Err(format!("Track {track_id} is locked."));
+    }
+    Ok(())
+}
+
+fn check_start(timeline: &Timeline, start_us: u64) -> Result<(), String> {
+    if start_us >= timeline.duration_us {
+        return Err(format!(
+            "Title starts at {start_us}us, past the end of the timeline ({}us).",
+            timeline.duration_us
+        ));
+    }
+    Ok(())
+}
+
+fn title_meta(title: &TitlePayload) -> Result<Value, String> {
+    let title =
+        serde_json::to_value(title).map_err(|error| format!("Title serialize error: {error}"))?;
+    Ok(serde_json::json!({ "title": title }))
+}
+
+fn next_title_clip_id(timeline: &Timeline) -> String {
+    let micros = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros();
+    let mut suffix = 0;
+    loop {
+        let candidate = if suffix == 0 {
+            format!("title-{micros}")
+        } else {
+            format!("title-{micros}-{suffix}")
+        };
+        if !timeline.clips.iter().any(|clip| clip.clip_id == candidate) {
+            return candidate;
+        }
+        suffix += 1;
+    }
+}
+
+fn save(mut timeline: Timeline, clip_id: String) -> Result<TitleClipResult, String> {
+    timeline.version = timeline.version.saturating_add(1);
+    timeline.updated_at = now_iso();
+    write_timeline(&timeline)?;
+    Ok(TitleClipResult { clip_id, timeline })
+}
+
+fn add_title_clip_blocking(request: AddTitleClipRequest) -> Result<TitleClipResult, String> {
+    request.title.validate()?;
+    let mut timeline = read_timeline(&request.project_id)?;
+    check_start(&timeline, request.start_us)?;
+    let track_id = match request.track_id {
+        Some(track_id) => track_id,
+        None => ensure_title_track(&mut timeline),
+    };
+    check_track_unlocked(&timeline, &track_id)?;
+
+    let clip_id = next_title_clip_id(&timeline);
+    timeline.clips.push(TimelineClip {
+        clip_id: clip_id.clone(),
+        track_id,
+        clip_type: TITLE_CLIP_TYPE.to_string(),
+        start_us: request.start_us,
+        end_us: request.start_us + request.title.duration_us,
+        source_start_us: 0,
+        source_end_us: request.title.duration_us,
+        speed: 1.0,
+        speed_keyframes: Vec::new(),
+        locked: false,
+        protected_from_ai: false,
+        source_ref: TITLE_CLIP_TYPE.to_string(),
+        effects: serde_json::json!({}),
+        transform: serde_json::json!({}),
+        meta: title_meta(&request.title)?,
+    });
+    save(timeline, clip_id)
+}
+
+fn update_title_clip_blocking(request: UpdateTitleClipRequest) -> Result<TitleClipResult, String> {
+    let mut timeline = read_timeline(&request.project_id)?;
+    if let Some(start_us) = request.start_us {
+        check_start(&timeline, start_us)?;
+    }
+    let clip = timeline
+        .clips
+        .iter()
+        .find(|clip| clip.clip_id == request.clip_id)
+        .ok_or_else(|| format!("Clip not found: {}", request.clip_id))?;
+    let current = TitlePayload::from_clip(clip)
+        .ok_or_else(|| format!("Clip {} is not a title clip.", request.clip_id))?;
+    check_track_unlocked(&timeline, &clip.track_id)?;
+    clip_flags::ensure_unlocked(clip, request.force.unwrap_or(false))?;
+
+    let title = request.title.unwrap_or(current);
+    title.validate()?;
+    let meta = title_meta(&title)?;
+    let clip = timeline
+        .clips
+        .iter_mut()
+        .find(|clip| clip.clip_id == request.clip_id)
+        .ok_or_else(|| format!("Clip not found: {}", request.clip_id))?;
+    let start_us = request.start_us.unwrap_or(clip.start_us);
+    clip.start_us = start_us;
+    clip.end_us = start_us + title.duration_us;
+    clip.source_end_us = title.duration_us;
+    if let Some(meta_object) = clip.meta.as_object_mut() {
+        meta_object.insert("title".to_string(), meta["title"].clone());
+    } else {
+        clip.meta = meta;
+    }
+    save(timeline, request.clip_id)
+}
+
+#[tauri::command]
+pub async fn add_title_clip(request: AddTitleClipRequest) -> Result<TitleClipResult, String> {
+    tauri::async_runtime::spawn_blocking(move || add_title_clip_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn update_title_clip(request: UpdateTitleClipRequest) -> Result<TitleClipResult, String> {
+    tauri::async_runtime::spawn_blocking(move || update_title_clip_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
@@ -0,0 +1,605 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::subtitles::timeline_words;
+use crate::timeline::source_to_timeline_us;
+use crate::{
+    captions, now_iso, path_safety, read_timeline, timeline_merge, write_timeline, Timeline,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptWord {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub normalized: String,
+    pub start_us: u64,
+    pub end_us: u64,
+    #[serde(default)]
+    pub confidence: Option<f64>,
+    #[serde(default)]
+    pub speaker: Option<String>,
+    /// Fields other tools add (diarization, filler flags), kept on rewrite.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSegment {
+    pub id: String,
+    pub start_us: u64,
+    pub end_us: u64,
+    pub text: String,
+    #[serde(default)]
+    pub confidence: Option<f64>,
+    #[serde(default)]
+    pub speaker: Option<String>,
+    #[serde(default)]
+    pub words: Vec<TranscriptWord>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transcript {
+    #[serde(default)]
+    pub project_id: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub source_ref: Option<String>,
+    #[serde(default)]
+    pub segments: Vec<TranscriptSegment>,
+    /// Display names for diarization ids, e.g. `SPEAKER_1` -> `Host`. The
+    /// ids on segments and words stay as the diarizer wrote them.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub speaker_labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub word_count: usize,
+    #[serde(default)]
+    pub updated_at: String,
+    /// Pipeline fields the editor does not use (`transcriptId`, `source`,
+    /// `adapter`, ...), kept so a rewrite does not drop them.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Transcript {
+    pub fn words(&self) -> impl Iterator<Item = &TranscriptWord> {
+        self.segments
+            .iter()
+            .flat_map(|segment| segment.words.iter())
+    }
+
+    /// The speaker's label, or its id when it has none.
+    pub fn speaker_label<'a>(&'a self, speaker: &'a str) -> &'a str {
+        self.speaker_labels
+            .get(speaker)
+            .map_or(speaker, String::as_str)
+    }
+
+    /// Speaker ids in the order they first speak.
+    pub fn speakers(&self) -> Vec<&str> {
+        let mut speakers = Vec::new();
+        for segment in &self.segments {
+            let ids = segment.speaker.iter().chain(
+                segment
+                    .words
+                    .iter()
+                    .filter_map(|word| word.speaker.as_ref()),
+            );
+            for id in ids {
+                if !speakers.contains(&id.as_str()) {
+                    speakers.push(id.as_str());
+                }
+            }
+        }
+        speakers
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveTranscriptRequest {
+    project_id: String,
+    transcript: Transcript,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTranscriptRequest {
+    project_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchTranscriptRequest {
+    project_id: String,
+    query: String,
+    limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelSpeakersRequest {
+    project_id: String,
+    /// Speaker id to label; an empty label clears it.
+    mapping: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakerStats {
+    pub speaker: String,
+    pub label: String,
+    pub word_count: usize,
+    /// Talk time in the source recording.
+    pub source_talk_us: u64,
+    /// Talk time left on the timeline after cuts.
+    pub timeline_talk_us: u64,
+    /// Share of the timeline's attributed talk time, 0-1.
+    pub timeline_share: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptMatch {
+    pub segment_id: String,
+    pub word_ids: Vec<String>,
+    pub text: String,
+    pub source_start_us: u64,
+    pub source_end_us: u64,
+    /// `None` when the matched words fall inside a removed range.
+    pub timeline_start_us: Option<u64>,
+    pub timeline_end_us: Option<u64>,
+}
+
+fn transcript_file_path(project_id: &str) -> Result<PathBuf, String> {
+    Ok(path_safety::project_dir(project_id)?.join("transcript.json"))
+}
+
+/// Reads both the pipeline's canonical shape, where words sit at the top
+/// level and segments point at them through `wordIds`, and segments that
+/// carry their own `words`.
+fn parse_transcript(raw: &str) -> Result<Transcript, String> {
+    let mut value = serde_json::from_str::<Value>(raw)
+        .map_err(|error| format!("Invalid transcript JSON: {error}"))?;
+    nest_words(&mut value);
+    serde_json::from_value::<Transcript>(value)
+        .map_err(|error| format!("Invalid transcript JSON: {error}"))
+}
+
+/// Moves top-level `words` into the segments that list them in `wordIds`,
+/// or, without ids, into the segment they start in.
+fn nest_words(value: &mut Value) {
+    let Some(Value::Array(words)) = value
+        .as_object_mut()
+        .and_then(|object| object.remove("words"))
+    else {
+        return;
+    };
+    let Some(segments) = value.get_mut("segments").and_then(Value::as_array_mut) else {
+        return;
+    };
+    let by_id = words
+        .iter()
+        .filter_map(|word| Some((word.get("id")?.as_str()?.to_string(), word)))
+        .collect::<HashMap<_, _>>();
+    let word_start = |word: &Value| word.get("startUs").and_then(Value::as_u64);
+    for segment in segments {
+        let nested = segment
+            .get("words")
+            .and_then(Value::as_array)
+            .is_some_and(|words| !words.is_empty());
+        if nested {
+            continue;
+        }
+        let resolved = match segment.get("wordIds").and_then(Value::as_array) {
+            Some(ids) => ids
+                .iter()
+                .filter_map(|id| by_id.get(id.as_str()?))
+                .map(|word| (*word).clone())
+                .collect::<Vec<_>>(),
+            None => {
+                let start = segment.get("startUs").and_then(Value::as_u64).unwrap_or(0);
+                let end = segment.get("endUs").and_then(Value::as_u64).unwrap_or(0);
+                words
+                    .iter()
+                    .filter(|word| word_start(word).is_some_and(|at| at >= start && at < end))
+                    .cloned()
+                    .collect()
+            }
+        };
+        segment["words"] = Value::Array(resolved);
+    }
+}
+
+/// The canonical shape the pipeline scripts read: every word once at the
+/// top level, and segments listing theirs in `wordIds`.
+fn canonical_json(transcript: &Transcript) -> Result<Value, String> {
+    let mut value = serde_json::to_value(transcript)
+        .map_err(|error| format!("Transcript serialize error: {error}"))?;
+    if let Some(segments) = value.get_mut("segments").and_then(Value::as_array_mut) {
+        for (segment, typed) in segments.iter_mut().zip(&transcript.segments) {
+            if let Some(segment) = segment.as_object_mut() {
+                segment.remove("words");
+                segment.insert(
+                    "wordIds".to_string(),
+                    json!(typed.words.iter().map(|word| &word.id).collect::<Vec<_>>()),
+                );
+            }
+        }
+    }
+    value["words"] = serde_json::to_value(transcript.words().collect::<Vec<_>>())
+        .map_err(|error| format!("Transcript serialize error: {error}"))?;
+    Ok(value)
+}
+
+/// Sorts segments/words, fills derived fields and rejects inverted ranges.
+fn normalize_transcript(mut transcript: Transcript) -> Result<Transcript, String> {
+    for segment in &mut transcript.segments {
+        if segment.end_us < segment.start_us {
+            return Err(format!(
+                "Transcript segment {} ends before it starts.",
+                segment.id
+            ));
+        }
+        for word in &mut segment.words {
+            if word.end_us < word.start_us {
+                return Err(format!(
+                    "Transcript word {} ends before it starts.",
+                    word.id
+                ));
+            }
+            if word.normalized.is_empty() {
+                word.normalized = normalize_word(&word.text);
+            }
+            if word.speaker.is_none() {
+                word.speaker = segment.speaker.clone();
+            }
+        }
+        segment.words.sort_by_key(|word| word.start_us);
+    }
+    transcript.segments.sort_by_key(|segment| segment.start_us);
+    transcript.word_count = transcript.words().count();
+    Ok(transcript)
+}
+
+/// Lowercases and strips punctuation, matching the pipeline's `normalized` field.
+pub fn normalize_word(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '\'')
+        .collect()
+}
+
+pub fn read_transcript(project_id: &str) -> Result<Transcript, String> {
+    let file_path = transcript_file_path(project_id)?;
+    if !file_path.exists() {
+        return Err("Transcript not found.".to_string());
+    }
+    let raw = fs::read_to_string(&file_path)
+        .map_err(|error| format!("Failed reading transcript file: {error}"))?;
+    let mut transcript = parse_transcript(&raw)?;
+    transcript.project_id = project_id.to_string();
+    Ok(transcript)
+}
+
+pub fn write_transcript(project_id: &str, transcript: Transcript) -> Result<Transcript, String> {
+    let mut transcript = normalize_transcript(transcript)?;
+    transcript.project_id = project_id.to_string();
+    transcript.updated_at = now_iso();
+
+    let file_path = transcript_file_path(project_id)?;
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed creating project dir: {error}"))?;
+    }
+    let serialized = serde_json::to_string_pretty(&canonical_json(&transcript)?)
+        .map_err(|error| format!("Transcript serialize error: {error}"))?;
+    fs::write(&file_path, format!("{serialized}\n"))
+        .map_err(|error| format!("Failed writing transcript file: {error}"))?;
+    Ok(transcript)
+}
+
+/// Pulls the transcript a pipeline script wrote (wherever it put it) into the
+/// project store in its typed form.
+pub fn import_pipeline_transcript(
+    project_id: &str,
+    transcript_path: &Path,
+    source_ref: &str,
+) -> Result<Transcript, String> {
+    let raw = fs::read_to_string(transcript_path)
+        .map_err(|error| format!("Failed reading pipeline transcript: {error}"))?;
+    let mut transcript = parse_transcript(&raw)?;
+    if transcript.source_ref.is_none() {
+        transcript.source_ref = Some(source_ref.to_string());
+    }
+    write_transcript(project_id, transcript)
+}
+
+fn locate_match(
+    timeline: Option<&Timeline>,
+    source_ref: Option<&str>,
+    segment: &TranscriptSegment,
+    words: &[&TranscriptWord],
+    start_us: u64,
+    end_us: u64,
+) -> TranscriptMatch {
+    let timeline_start_us =
+        timeline.and_then(|timeline| source_to_timeline_us(timeline, source_ref, start_us));
+    // End timestamps are exclusive, so map the last microsecond inside the match.
+    let timeline_end_us = timeline
+        .and_then(|timeline| {
+            source_to_timeline_us(timeline, source_ref, end_us.saturating_sub(1).max(start_us))
+        })
+        .map(|end| end + 1);
+
+    TranscriptMatch {
+        segment_id: segment.id.clone(),
+        word_ids: words.iter().map(|word| word.id.clone()).collect(),
+        text: segment.text.clone(),
+        source_start_us: start_us,
+        source_end_us: end_us,
+        timeline_start_us,
+        timeline_end_us,
+    }
+}
+
+/// Phrase search over normalized words, falling back to a plain substring
+/// search for segments that carry no word timings.
+pub fn search(
+    transcript: &Transcript,
+    timeline: Option<&Timeline>,
+    query: &str,
+    limit: usize,
+) -> Vec<TranscriptMatch> {
+    let needle = query
+        .split_whitespace()
+        .map(normalize_word)
+        .filter(|token| !token.is_empty())
+        .collect::<Vec<_>>();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let source_ref = transcript.source_ref.as_deref();
+    let lowered_query = query.trim().to_lowercase();
+    let mut matches = Vec::new();
+
+    for segment in &transcript.segments {
+        if segment.words.is_empty() {
+            if segment.text.to_lowercase().contains(&lowered_query) {
+                matches.push(locate_match(
+                    timeline,
+                    source_ref,
+                    segment,
+                    &[],
+                    segment.start_us,
+                    segment.end_us,
+                ));
+            }
+            continue;
+        }
+
+        let words = segment.words.iter().collect::<Vec<_>>();
+        if words.len() < needle.len() {
+            continue;
+        }
+        for start in 0..=(words.len() - needle.len()) {
+            let window = &words[start..start + needle.len()];
+            let hit = window
+                .iter()
+                .zip(&needle)
+                .all(|(word, token)| word.normalized == *token);
+            if hit {
+                let start_us = window[0].start_us;
+                let end_us = window[window.len() - 1].end_us;
+                matches.push(locate_match(
+                    timeline, source_ref, segment, window, start_us, end_us,
+                ));
+            }
+        }
+        if matches.len() >= limit {
+            break;
+        }
+    }
+
+    matches.truncate(limit);
+    matches
+}
+
+/// Applies `mapping` to the transcript's speaker labels. Ids the transcript
+/// doesn't have are rejected so a typo doesn't silently do nothing.
+pub fn apply_speaker_labels(
+    transcript: &mut Transcript,
+    mapping: BTreeMap<String, String>,
+) -> Result<(), String> {
+    let speakers = transcript.speakers();
+    if let Some(unknown) = mapping
+        .keys()
+        .find(|speaker| !speakers.contains(&speaker.as_str()))
+    {
+        return Err(format!("Unknown speaker {unknown}."));
+    }
+    for (speaker, label) in mapping {
+        let label = label.trim();
+        if label.is_empty() {
+            transcript.speaker_labels.remove(&speaker);
+        } else {
+            transcript.speaker_labels.insert(speaker, label.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Talk time per speaker, in first-spoken order. Segments without word
+/// timings count whole; speech with no speaker isn't counted.
+pub fn speaker_stats(transcript: &Transcript, timeline: Option<&Timeline>) -> Vec<SpeakerStats> {
+    let mut stats = transcript
+        .speakers()
+        .into_iter()
+        .map(|speaker| SpeakerStats {
+            speaker: speaker.to_string(),
+            label: transcript.speaker_label(speaker).to_string(),
+            word_count: 0,
+            source_talk_us: 0,
+            timeline_talk_us: 0,
+            timeline_share: 0.0,
+        })
+        .collect::<Vec<_>>();
+    let mut add = |speaker: Option<&String>, words: usize, source_us: u64, timeline_us: u64| {
+        let Some(speaker) = speaker else {
+            return;
+        };
+        if let Some(entry) = stats.iter_mut().find(|entry| &entry.speaker == speaker) {
+            entry.word_count += words;
+            entry.source_talk_us += source_us;
+            entry.timeline_talk_us += timeline_us;
+        }
+    };
+
+    for segment in &transcript.segments {
+        if segment.words.is_empty() {
+            add(
+                segment.speaker.as_ref(),
+                segment.text.split_whitespace().count(),
+                segment.end_us.saturating_sub(segment.start_us),
+                0,
+            );
+        }
+        for word in &segment.words {
+            add(
+                word.speaker.as_ref(),
+                1,
+                word.end_us.saturating_sub(word.start_us),
+                0,
+            );
+        }
+    }
+    if let Some(timeline) = timeline {
+        for word in timeline_words(transcript, timeline).iter().flatten() {
+            add(word.speaker.as_ref(), 0, 0, word.end_us - word.start_us);
+        }
+    }
+
+    let total_us = stats
+        .iter()
+        .map(|entry| entry.timeline_talk_us)
+        .sum::<u64>();
+    if total_us > 0 {
+        for entry in &mut stats {
+            entry.timeline_share = entry.timeline_talk_us as f64 / total_us as f64;
+        }
+    }
+    stats
+}
+
+/// Renames speakers in the transcript and on generated caption clips.
+#[tauri::command]
+pub async fn label_speakers(request: LabelSpeakersRequest) -> Result<Transcript, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut transcript = read_transcript(&request.project_id)?;
+        apply_speaker_labels(&mut transcript, request.mapping)?;
+        let transcript = write_transcript(&request.project_id, transcript)?;
+
+        let _save = timeline_merge::lock_saves();
+        if let Ok(mut timeline) = read_timeline(&request.project_id) {
+            if captions::relabel_speakers(&mut timeline, &transcript) > 0 {
+                timeline.version = timeline.version.saturating_add(1);
+                timeline.updated_at = now_iso();
+                write_timeline(&timeline)?;
+            }
+        }
+        Ok(transcript)
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn get_speaker_stats(request: GetTranscriptRequest) -> Result<Vec<SpeakerStats>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let transcript = read_transcript(&request.project_id)?;
+        let timeline = read_timeline(&request.project_id).ok();
+        Ok(speaker_stats(&transcript, timeline.as_ref()))
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn search_transcript(
+    request: SearchTranscriptRequest,
+) -> Result<Vec<TranscriptMatch>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let transcript = read_transcript(&request.project_id)?;
+        let timeline = read_timeline(&request.project_id).ok();
+        let limit = request.limit.unwrap_or(100).clamp(1, 1000) as usize;
+        Ok(search(
+            &transcript,
+            timeline.as_ref(),
+            &request.query,
+            limit,
+        ))
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn save_transcript(request: SaveTranscriptRequest) -> Result<Transcript, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        write_transcript(&request.project_id, request.transcript)
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[tauri::command]
+pub async fn get_transcript(request: GetTranscriptRequest) -> Result<Transcript, String> {
+    tauri::async_runtime::spawn_blocking(move || read_transcript(&request.project_id))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_transcripts_round_trip() {
+        let raw = json!({
+            "transcriptId": "tx-1",
+            "adapter": { "engine": "local:whisper.cpp:v1" },
+            "source": { "ref": "source-video", "durationUs": 2_000_000 },
+            "words": [
+                { "id": "w1", "text": "Hello", "startUs": 0, "endUs": 400_000 },
+                { "id": "w2", "text": "there", "startUs": 500_000, "endUs": 900_000, "isFiller": false },
+            ],
+            "segments": [
+                { "id": "s1", "startUs": 0, "endUs": 1_000_000, "text": "Hello there", "wordIds": ["w1", "w2"] },
+            ],
+            "wordCount": 2,
+        });
+        let transcript = parse_transcript(&raw.to_string()).unwrap();
+        let ids = transcript
+            .words()
+            .map(|word| word.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["w1", "w2"]);
+
+        let written = canonical_json(&transcript).unwrap();
+        assert_eq!(written["adapter"], raw["adapter"]);
+        assert_eq!(written["source"], raw["source"]);
+        assert_eq!(written["words"][1]["isFiller"], json!(false));
+        assert_eq!(written["segments"][0]["wordIds"], json!(["w1", "w2"]));
+        assert!(written["segments"][0].get("words").is_none());
+    }
+}
@@ -0,0 +1,173 @@
+use std::fs;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+use serde_json::Value;
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager};
+
+use crate::scheduler::{self, JobKind, JobState, ScheduledJob};
+use crate::windows;
+use crate::{path_safety, shutdown};
+
+const TRAY_ID: &str = "main";
+const APP_NAME: &str = "Lapaas AI Editor";
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+const STATUS_ITEM: &str = "status";
+const SHOW_ITEM: &str = "show";
+const PAUSE_ITEM: &str = "pause";
+const DATA_FOLDER_ITEM: &str = "data-folder";
+const QUIT_ITEM: &str = "quit";
+
+/// Menu items whose text follows the job queue.
+struct TrayMenu {
+    status: MenuItem,
+    pause: MenuItem,
+}
+
+/// Percent written by the render script to `render_progress.json`.
+fn render_percent(project_id: &str) -> Option<f64> {
+    let path = path_safety::project_dir(project_id)
+        .ok()?
+        .join("render_progress.json");
+    let progress: Value = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    progress["percent"].as_f64()
+}
+
+/// Status line for the menu, plus the percent of the least-done render
+/// for the tray title.
+fn summarize(jobs: &[ScheduledJob], paused: bool) -> (String, Option<f64>) {
+    let running = jobs
+        .iter()
+        .filter(|job| job.state == JobState::Running)
+        .collect::<Vec<_>>();
+    let queued = jobs.len() - running.len();
+    let render = running
+        .iter()
+        .filter(|job| job.kind == JobKind::Render)
+        .filter_map(|job| render_percent(&job.project_id))
+        .min_by(f64::total_cmp);
+
+    let mut status = match (running.len(), queued) {
+        (0, 0) => "No jobs running".to_string(),
+        (running, 0) => format!("{running} job(s) running"),
+        (running, queued) => format!("{running} job(s) running, {queued} queued"),
+    };
+    if let Some(percent) = render {
+        status.push_str(&format!(" · rendering {percent:.0}%"));
+    }
+    if paused {
+        status.push_str(" · queue paused");
+    }
+    (status, render)
+}
+
+fn refresh(app: &AppHandle) {
+    let jobs = scheduler::active_jobs(app);
+    let paused = scheduler::is_paused(app);
+    let (status, render) = summarize(&jobs, paused);
+    if let Some(menu) = app.try_state::<TrayMenu>() {
+        let _ = menu.status.set_text(&status);
+        let _ = menu.pause.set_text(if paused {
+            "Resume queue"
+        } else {
+            "Pause queue"
+        });
+    }
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(format!("{APP_NAME} — {status}")));
+        // Shown next to the icon on macOS.
+        let _ = tray.set_title(render.map(|percent| format!("{percent:.0}%")));
+    }
+}
+
+fn open_data_folder() -> Result<(), String> {
+    let dir = path_safety::data_dir()?;
+    fs::create_dir_all(&dir).map_err(|error| format!("Failed creating data dir: {error}"))?;
+    let status = Command::new("open")
+        .arg(&dir)
+        .status()
+        .map_err(|error| format!("Failed to execute open command: {error}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err("open command exited with non-zero status".to_string())
+    }
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        SHOW_ITEM => windows::show_main_window(app),
+        PAUSE_ITEM => {
+            scheduler::set_paused(app, !scheduler::is_paused(app));
+            refresh(app);
+        }
+        DATA_FOLDER_ITEM => {
+            if let Err(error) = open_data_folder() {
+                tracing::warn!("Failed opening data folder from tray: {error}");
+            }
+        }
+        QUIT_ITEM => shutdown::request_quit(app),
+        _ => {}
+    }
+}
+
+/// Adds the tray icon and keeps its status line and tooltip in step with
+/// the job queue.
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let status = MenuItem::with_id(app, STATUS_ITEM, "No jobs running", false, None::<&str>)?;
+    let show = MenuItem::with_id(
+        app,
+        SHOW_ITEM,
+        format!("Show {APP_NAME}"),
+        true,
+        None::<&str>,
+    )?;
+    let pause = MenuItem::with_id(app, PAUSE_ITEM, "Pause queue", true, None::<&str>)?;
+    let data_folder = MenuItem::with_id(
+        app,
+        DATA_FOLDER_ITEM,
+        "Open data folder",
+        true,
+        None::<&str>,
+    )?;
+    let quit = MenuItem::with_id(
+        app,
+        QUIT_ITEM,
+        format!("Quit {APP_NAME}"),
+        true,
+        None::<&str>,
+    )?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &status,
+            &PredefinedMenuItem::separator(app)?,
+            &show,
+            &pause,
+            &data_folder,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )?;
+
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(APP_NAME)
+        .menu(&menu)
+        .on_menu_event(on_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+    app.manage(TrayMenu { status, pause });
+
+    let app = app.clone();
+    thread::spawn(move || loop {
+        refresh(&app);
+        thread::sleep(REFRESH_INTERVAL);
+    });
+    Ok(())
+}
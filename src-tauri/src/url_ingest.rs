@@ -0,0 +1,438 @@
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use reqwest::blocking::Client;
+use reqwest::header::CONTENT_TYPE;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::model_downloads::{download_resumable, part_path, RangeDownload};
+use crate::settings::{read_app_settings, UrlIngestSettings};
+use crate::{event_bus, ffmpeg, path_safety, shutdown};
+
+const DOWNLOADS_DIR: &str = "downloads";
+
+/// Progress events are throttled to this interval; each one is journaled.
+const EMIT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// URL extensions downloaded directly without asking the server first.
+const MEDIA_EXTENSIONS: &[&str] = &[
+    "mp4", "mov", "m4v", "mkv", "webm", "avi", "mts", "m2ts", "mxf", "mp3", "wav", "m4a", "aac",
+    "flac", "ogg", "opus",
+];
+
+/// URLs downloading right now, so two ingests can't append to the same
+/// part file.
+static ACTIVE: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DownloadStage {
+    Downloading,
+    Verifying,
+    Done,
+}
+
+/// Payload of `INGEST_DOWNLOAD_EVENT`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProgress {
+    pub url: String,
+    pub stage: DownloadStage,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    /// 0–100. yt-dlp reports only this, without byte counts.
+    pub percent: Option<f64>,
+}
+
+pub fn is_url(input: &str) -> bool {
+    let input = input.trim().to_ascii_lowercase();
+    input.starts_with("http://") || input.starts_with("https://")
+}
+
+struct Reporter<'a> {
+    project_id: &'a str,
+    url: &'a str,
+    last_emit: Option<Instant>,
+}
+
+impl Reporter<'_> {
+    fn emit(
+        &mut self,
+        stage: DownloadStage,
+        downloaded: u64,
+        total: Option<u64>,
+        percent: Option<f64>,
+    ) {
+        let now = Instant::now();
+        let throttled = stage == DownloadStage::Downloading
+            && self
+                .last_emit
+                .is_some_and(|last| now.duration_since(last) < EMIT_INTERVAL);
+        if throttled {
+            return;
+        }
+        self.last_emit = Some(now);
+        let percent = percent.or_else(|| {
+            total
+                .filter(|total| *total > 0)
+                .map(|total| downloaded as f64 * 100.0 / total as f64)
+        });
+        event_bus::publish(
+            self.project_id,
+            event_bus::INGEST_DOWNLOAD_EVENT,
+            &DownloadProgress {
+                url: self.url.to_string(),
+                stage,
+                downloaded_bytes: downloaded,
+                total_bytes: total,
+                percent,
+            },
+        );
+    }
+}
+
+/// Removes the URL from [`ACTIVE`] when a download stops, however it stops.
+struct ActiveGuard(String);
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        if let Ok(mut active) = ACTIVE.lock() {
+            active.remove(&self.0);
+        }
+    }
+}
+
+fn register(url: &str) -> Result<ActiveGuard, String> {
+    let mut active = ACTIVE
+        .lock()
+        .map_err(|_| "Download registry lock poisoned".to_string())?;
+    if !active.insert(url.to_string()) {
+        return Err(format!("{url} is already downloading."));
+    }
+    Ok(ActiveGuard(url.to_string()))
+}
+
+pub fn downloads_dir() -> Result<PathBuf, String> {
+    Ok(path_safety::data_dir()?.join(DOWNLOADS_DIR))
+}
+
+/// Downloads are named after their URL, so a retry finds the part file.
+fn download_key(url: &str) -> String {
+    Sha256::digest(url.as_bytes())
+        .iter()
+        .take(8)
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Extension of the last path segment, if it names a media file.
+fn url_extension(url: &str) -> Option<String> {
+    let url = url.split(['?', '#']).next()?;
+    let (_, path) = url.split_once("://")?.1.split_once('/')?;
+    let (_, extension) = path.rsplit('/').next()?.rsplit_once('.')?;
+    let extension = extension.to_ascii_lowercase();
+    MEDIA_EXTENSIONS
+        .contains(&extension.as_str())
+        .then_some(extension)
+}
+
+/// Extension for a media `Content-Type`; None for pages and anything else
+/// that needs yt-dlp. ffprobe checks the contents afterwards, so generic
+/// binary responses are given the benefit of the doubt.
+fn content_type_extension(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next()?.trim().to_ascii_lowercase();
+    match mime.as_str() {
+        "video/quicktime" => Some("mov"),
+        "video/webm" => Some("webm"),
+        "video/x-matroska" => Some("mkv"),
+        "audio/mpeg" => Some("mp3"),
+        "audio/wav" | "audio/x-wav" | "audio/wave" => Some("wav"),
+        "audio/ogg" => Some("ogg"),
+        "audio/flac" => Some("flac"),
+        "application/octet-stream" => Some("mp4"),
+        mime if mime.starts_with("video/") => Some("mp4"),
+        mime if mime.starts_with("audio/") => Some("m4a"),
+        _ => None,
+    }
+}
+
+fn http_client() -> Result<Client, String> {
+    // No overall timeout: long recordings take as long as they take.
+    Client::builder()
+        .connect_timeout(Duration::from_secs(30))
+        .timeout(None)
+        .build()
+        .map_err(|error| format!("HTTP client error: {error}"))
+}
+
+/// Extension to save a direct download under, or None when `url` is a page.
+fn direct_extension(client: &Client, url: &str) -> Option<String> {
+    if let Some(extension) = url_extension(url) {
+        return Some(extension);
+    }
+    let response = client.head(url).send().ok()?;
+    let content_type = response.headers().get(CONTENT_TYPE)?.to_str().ok()?;
+    content_type_extension(content_type).map(str::to_string)
+}
+
+/// A completed download from an earlier ingest of the same URL.
+fn finished_download(dir: &Path, key: &str) -> Option<PathBuf> {
+    MEDIA_EXTENSIONS
+        .iter()
+        .map(|extension| dir.join(format!("{key}.{extension}")))
+        .find(|path| path.is_file())
+}
+
+/// Downloads `url` to `dest`, appending to `<dest>.part` from an earlier
+/// interrupted attempt.
+fn download_direct(
+    client: &Client,
+    url: &str,
+    dest: &Path,
+    reporter: &mut Reporter,
+) -> Result<(), String> {
+    let part = part_path(dest);
+    let outcome = download_resumable(
+        client,
+        url,
+        &part,
+        &shutdown::is_shutting_down,
+        &mut |_, downloaded, total| {
+            reporter.emit(DownloadStage::Downloading, downloaded, total, None)
+        },
+    )?;
+    if let RangeDownload::Stopped { .. } = outcome {
+        return Err("Download stopped; ingest again to resume.".to_string());
+    }
+    fs::rename(&part, dest).map_err(|error| format!("Failed saving download: {error}"))
+}
+
+fn yt_dlp_binary(settings: &UrlIngestSettings) -> Option<PathBuf> {
+    if let Some(path) = settings
+        .yt_dlp_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    {
+        return Some(PathBuf::from(path));
+    }
+    let name = if cfg!(windows) {
+        "yt-dlp.exe"
+    } else {
+        "yt-dlp"
+    };
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// `42.5` from `[download]  42.5% of ~ 120.00MiB at 3.10MiB/s ETA 00:25`.
+fn parse_percent(line: &str) -> Option<f64> {
+    let rest = line.strip_prefix("[download]")?.trim_start();
+    let (percent, _) = rest.split_once('%')?;
+    percent.trim().parse().ok()
+}
+
+/// Lets yt-dlp pick and merge the streams into `<key>.<ext>`; it resumes
+/// its own part files.
+fn download_yt_dlp(
+    binary: &Path,
+    format: &str,
+    url: &str,
+    dir: &Path,
+    key: &str,
+    reporter: &mut Reporter,
+) -> Result<PathBuf, String> {
+    let mut command = Command::new(binary);
+    command
+        .args([
+            "--newline",
+            "--no-playlist",
+            "--continue",
+            "--progress",
+            "--no-simulate",
+            "--merge-output-format",
+            "mp4",
+            "--print",
+            "after_move:filepath",
+            "-f",
+            format,
+            "-o",
+        ])
+        .arg(dir.join(format!("{key}.%(ext)s")))
+        .arg("--")
+        .arg(url);
+    // yt-dlp merges the audio and video streams with ffmpeg.
+    if let Some(path) = ffmpeg::child_path() {
+        command.env("PATH", path);
+    }
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("Failed starting yt-dlp: {error}"))?;
+    let _tracked = shutdown::track_child(&child);
+    // Drained on its own thread so a chatty yt-dlp can't block on a full pipe.
+    let stderr = child.stderr.take().map(|mut pipe| {
+        thread::spawn(move || {
+            let mut text = String::new();
+            let _ = pipe.read_to_string(&mut text);
+            text
+        })
+    });
+
+    let mut output = None;
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if let Some(percent) = parse_percent(&line) {
+                reporter.emit(DownloadStage::Downloading, 0, None, Some(percent));
+            } else if !line.starts_with('[') && !line.trim().is_empty() {
+                output = Some(PathBuf::from(line.trim()));
+            }
+        }
+    }
+    let status = child
+        .wait()
+        .map_err(|error| format!("Failed waiting for yt-dlp: {error}"))?;
+    let stderr = stderr
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+    if !status.success() {
+        let detail = stderr
+            .lines()
+            .rev()
+            .find(|line| line.contains("ERROR"))
+            .or_else(|| stderr.lines().last())
+            .unwrap_or("no output")
+            .trim();
+        return Err(format!("yt-dlp failed for {url}: {detail}"));
+    }
+    output
+        .filter(|path| path.is_file())
+        .ok_or_else(|| format!("yt-dlp did not report a downloaded file for {url}."))
+}
+
+/// Rejects downloads ffprobe can't open or that hold neither audio nor
+/// video, like an HTML error page saved under a media name.
+fn verify_container(path: &Path) -> Result<(), String> {
+    let output = Command::new(ffmpeg::ffprobe_binary())
+        .args([
+            "-v",
+            "error",
+            "-show_entries",
+            "stream=codec_type",
+            "-of",
+            "csv=p=0",
+        ])
+        .arg(path)
+        .output()
+        .map_err(|error| format!("Failed running ffprobe: {error}"))?;
+    let streams = String::from_utf8_lossy(&output.stdout);
+    let playable = output.status.success()
+        && streams
+            .lines()
+            .any(|line| matches!(line.trim(), "video" | "audio"));
+    if playable {
+        return Ok(());
+    }
+    Err(format!(
+        "Downloaded file is not playable media: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    ))
+}
+
+/// Downloads `url` into `desktop/data/downloads`, resuming an earlier
+/// attempt, and checks that ffprobe can read the result. Direct media links
+/// are fetched over HTTP; pages go through yt-dlp when it's enabled.
+/// Progress is published as `INGEST_DOWNLOAD_EVENT`.
+pub fn fetch(project_id: &str, url: &str) -> Result<PathBuf, String> {
+    let url = url.trim();
+    let _active = register(url)?;
+    let settings = read_app_settings()?.url_ingest;
+    let dir = downloads_dir()?;
+    fs::create_dir_all(&dir).map_err(|error| format!("Failed creating downloads dir: {error}"))?;
+    let key = download_key(url);
+    let mut reporter = Reporter {
+        project_id,
+        url,
+        last_emit: None,
+    };
+
+    let path = match finished_download(&dir, &key) {
+        Some(path) => path,
+        None => {
+            let client = http_client()?;
+            match direct_extension(&client, url) {
+                Some(extension) => {
+                    let dest = dir.join(format!("{key}.{extension}"));
+                    download_direct(&client, url, &dest, &mut reporter)?;
+                    dest
+                }
+                None if settings.use_yt_dlp => {
+                    let binary = yt_dlp_binary(&settings).ok_or_else(|| {
+                        format!("{url} needs yt-dlp; install it or set its path in settings.")
+                    })?;
+                    tracing::info!("Downloading {url} with yt-dlp");
+                    download_yt_dlp(
+                        &binary,
+                        &settings.yt_dlp_format,
+                        url,
+                        &dir,
+                        &key,
+                        &mut reporter,
+                    )?
+                }
+                None => {
+                    return Err(format!(
+                    "{url} is not a direct media link; enable yt-dlp to import from video sites."
+                ))
+                }
+            }
+        }
+    };
+
+    let size = fs::metadata(&path).map_or(0, |meta| meta.len());
+    reporter.emit(DownloadStage::Verifying, size, Some(size), None);
+    if let Err(error) = verify_container(&path) {
+        let _ = fs::remove_file(&path);
+        return Err(error);
+    }
+    reporter.emit(DownloadStage::Done, size, Some(size), None);
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_direct_links_and_yt_dlp_progress() {
+        assert!(is_url(" HTTPS://example.com/a.mp4"));
+        assert!(!is_url("/Users/me/a.mp4"));
+        assert_eq!(
+            url_extension("https://cdn.example.com/v/Clip.MOV?sig=1#t=3").as_deref(),
+            Some("mov")
+        );
+        assert_eq!(url_extension("https://www.youtube.com/watch?v=abc"), None);
+        assert_eq!(url_extension("https://example.mp4"), None);
+        assert_eq!(
+            content_type_extension("video/mp4; codecs=avc1"),
+            Some("mp4")
+        );
+        assert_eq!(content_type_extension("text/html; charset=utf-8"), None);
+        assert_eq!(
+            parse_percent("[download]  42.5% of ~ 120.00MiB at 3.10MiB/s ETA 00:25"),
+            Some(42.5)
+        );
+        assert_eq!(parse_percent("[youtube] abc: Downloading webpage"), None);
+    }
+}
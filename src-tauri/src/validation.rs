@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{luts, path_safety, ProjectSettings};
+
+const MAX_NAME_LEN: usize = 120;
+const FPS_RANGE: std::ops::RangeInclusive<u32> = 1..=240;
+const ASPECT_RATIOS: &[&str] = &["16:9", "9:16", "1:1", "4:5", "4:3", "21:9"];
+/// Named presets; `<width>x<height>` is accepted as well.
+const RESOLUTIONS: &[&str] = &["480p", "720p", "1080p", "1440p", "2160p", "4K"];
+const MAX_DIMENSION: u32 = 8192;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    /// camelCase path as the UI sends it, e.g. `settings.fps`.
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateProjectRequest {
+    name: Option<String>,
+    settings: ProjectSettings,
+}
+
+/// Collects every problem in a request so the UI can mark all bad fields
+/// at once rather than one per submit.
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn fail(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            message: message.into(),
+        });
+    }
+
+    pub fn name(&mut self, field: &str, value: &str) {
+        let length = value.trim().chars().count();
+        if length == 0 {
+            self.fail(field, "must not be empty");
+        } else if length > MAX_NAME_LEN {
+            self.fail(field, format!("must be at most {MAX_NAME_LEN} characters"));
+        }
+    }
+
+    pub fn project_id(&mut self, field: &str, value: &str) {
+        if let Err(error) = path_safety::validate_project_id(value) {
+            self.fail(field, error);
+        }
+    }
+
+    pub fn fps(&mut self, field: &str, value: u32) {
+        if !FPS_RANGE.contains(&value) {
+            self.fail(
+                field,
+                format!(
+                    "must be between {} and {}",
+                    FPS_RANGE.start(),
+                    FPS_RANGE.end()
+                ),
+            );
+        }
+    }
+
+    pub fn aspect_ratio(&mut self, field: &str, value: &str) {
+        if !ASPECT_RATIOS.contains(&value) {
+            self.fail(
+                field,
+                format!("must be one of {}", ASPECT_RATIOS.join(", ")),
+            );
+        }
+    }
+
+    pub fn resolution(&mut self, field: &str, value: &str) {
+        if RESOLUTIONS.contains(&value) {
+            return;
+        }
+        let dimensions = value.split_once('x').and_then(|(width, height)| {
+            Some((width.parse::<u32>().ok()?, height.parse::<u32>().ok()?))
+        });
+        match dimensions {
+            Some((width, height))
+                if (2..=MAX_DIMENSION).contains(&width)
+                    && (2..=MAX_DIMENSION).contains(&height)
+                    && width % 2 == 0
+                    && height % 2 == 0 => {}
+            Some(_) => self.fail(
+                field,
+                format!("width and height must be even and at most {MAX_DIMENSION}"),
+            ),
+            None => self.fail(
+                field,
+                format!(
+                    "must be one of {} or <width>x<height>",
+                    RESOLUTIONS.join(", ")
+                ),
+            ),
+        }
+    }
+
+    /// `auto`, or a BCP-47 tag such as `en`, `pt-BR` or `zh-Hant-TW`.
+    pub fn language(&mut self, field: &str, value: &str) {
+        if value != "auto" && !is_language_tag(value) {
+            self.fail(
+                field,
+                "must be a BCP-47 language tag like en or pt-BR, or auto",
+            );
+        }
+    }
+
+    pub fn project_settings(&mut self, prefix: &str, settings: &ProjectSettings) {
+        let field = |name: &str| format!("{prefix}.{name}");
+        self.fps(&field("fps"), settings.fps);
+        self.aspect_ratio(&field("aspectRatio"), &settings.aspect_ratio);
+        self.resolution(&field("resolution"), &settings.resolution);
+        self.language(&field("language"), &settings.language);
+        if let Some(lut) = &settings.lut {
+            if let Err(error) = luts::read_cube_file(lut) {
+                self.fail(&field("lut"), error);
+            }
+        }
+    }
+
+    pub fn into_errors(self) -> Vec<FieldError> {
+        self.errors
+    }
+
+    /// One error string naming every bad field, in the style commands
+    /// already return.
+    pub fn finish(self) -> Result<(), String> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+        let details = self
+            .errors
+            .iter()
+            .map(|error| format!("{}: {}", error.field, error.message))
+            .collect::<Vec<_>>()
+            .join("; ");
+        Err(format!("Invalid request: {details}"))
+    }
+}
+
+/// Language subtag (2-3 letters, or 5-8 for registered ones) followed by
+/// script, region and variant subtags. Extensions and private use are
+/// rejected since no transcription model understands them.
+fn is_language_tag(value: &str) -> bool {
+    let mut subtags = value.split(['-', '_']);
+    let Some(language) = subtags.next() else {
+        return false;
+    };
+    let alpha = |subtag: &str| subtag.chars().all(|c| c.is_ascii_alphabetic());
+    if !alpha(language) || !matches!(language.len(), 2 | 3 | 5..=8) {
+        return false;
+    }
+    let alphanumeric = |subtag: &str| subtag.chars().all(|c| c.is_ascii_alphanumeric());
+    subtags.all(|subtag| match subtag.len() {
+        // Region (BR) or UN M.49 area (419).
+        2 => alpha(subtag),
+        3 => subtag.chars().all(|c| c.is_ascii_digit()),
+        // Script (Hant), or a variant starting with a digit (1996).
+        4 => {
+            alpha(subtag)
+                || (subtag.starts_with(|c: char| c.is_ascii_digit()) && alphanumeric(subtag))
+        }
+        5..=8 => alphanumeric(subtag),
+        _ => false,
+    })
+}
+
+/// Checks a project form without saving it, for inline errors.
+#[tauri::command]
+pub fn validate_project_settings(request: ValidateProjectRequest) -> Vec<FieldError> {
+    let mut validator = Validator::new();
+    if let Some(name) = &request.name {
+        validator.name("name", name);
+    }
+    validator.project_settings("settings", &request.settings);
+    validator.into_errors()
+}
@@ -0,0 +1,358 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::shutdown::{self, ChildGuard};
+use crate::{
+    audio_devices, ffmpeg, now_iso, path_safety, read_timeline, run_ingest_media, scheduler,
+    timeline_merge, write_timeline, MediaIngestRequest, Timeline, TimelineClip, TimelineTrack,
+};
+
+pub const VOICEOVER_CLIP_TYPE: &str = "audio_clip";
+const VOICEOVER_TRACK_ID: &str = "track-audio-voiceover";
+/// How long ffmpeg gets to finish the WAV after being asked to stop.
+const STOP_TIMEOUT: Duration = Duration::from_secs(10);
+/// A capture that fails to open its device exits within this time.
+const START_CHECK: Duration = Duration::from_millis(400);
+const WAV_HEADER_BYTES: u64 = 44;
+
+/// Recordings in progress, one per project.
+static RECORDINGS: Mutex<BTreeMap<String, Recording>> = Mutex::new(BTreeMap::new());
+
+struct Recording {
+    child: Child,
+    _tracked: ChildGuard,
+    path: PathBuf,
+    started_at: String,
+    insert_at_us: Option<u64>,
+    track_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartVoiceoverRequest {
+    project_id: String,
+    /// An input id from `list_audio_devices`; the system default when unset.
+    device: Option<String>,
+    /// Playhead position; the take is placed here once recording stops.
+    insert_at_us: Option<u64>,
+    /// Audio track for the take; the first one with room when unset.
+    track_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopVoiceoverRequest {
+    project_id: String,
+    /// Throws the take away instead of ingesting it.
+    discard: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceoverRecording {
+    pub project_id: String,
+    pub path: String,
+    pub started_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceoverResult {
+    pub path: String,
+    pub duration_us: u64,
+    /// `ingest_media` output; None for a discarded take.
+    pub ingest: Option<Value>,
+    /// Set when the take was placed on the timeline.
+    pub clip_id: Option<String>,
+    pub timeline_version: Option<u32>,
+}
+
+fn recordings() -> std::sync::MutexGuard<'static, BTreeMap<String, Recording>> {
+    RECORDINGS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn unix_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
+fn start_blocking(request: StartVoiceoverRequest) -> Result<VoiceoverRecording, String> {
+    let project_dir = path_safety::project_dir(&request.project_id)?;
+    if recordings().contains_key(&request.project_id) {
+        return Err("A voiceover is already recording for this project.".to_string());
+    }
+    let dir = project_dir.join("media").join("recordings");
+    fs::create_dir_all(&dir).map_err(|error| format!("Failed creating recordings dir: {error}"))?;
+    let path = dir.join(format!("voiceover-{}.wav", unix_millis()));
+
+    let mut command = Command::new(ffmpeg::ffmpeg_binary());
+    command
+        .args(["-hide_banner", "-loglevel", "error", "-y"])
+        .args(audio_devices::capture_input(request.device.as_deref())?)
+        .args(["-ac", "1", "-ar", "48000", "-c:a", "pcm_s16le"])
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    let mut child = command
+        .spawn()
+        .map_err(|error| format!("Failed starting recording: {error}"))?;
+    let tracked = shutdown::track_child(&child);
+
+    thread::sleep(START_CHECK);
+    if let Ok(Some(_)) = child.try_wait() {
+        let mut stderr = String::new();
+        if let Some(mut pipe) = child.stderr.take() {
+            let _ = pipe.read_to_string(&mut stderr);
+        }
+        let _ = fs::remove_file(&path);
+        return Err(format!(
+            "Recording device could not be opened: {}",
+            stderr.trim()
+        ));
+    }
+
+    let recording = VoiceoverRecording {
+        project_id: request.project_id.clone(),
+        path: path.to_string_lossy().to_string(),
+        started_at: now_iso(),
+    };
+    recordings().insert(
+        request.project_id,
+        Recording {
+            child,
+            _tracked: tracked,
+            path,
+            started_at: recording.started_at.clone(),
+            insert_at_us: request.insert_at_us,
+            track_id: request.track_id,
+        },
+    );
+    tracing::info!("Voiceover recording started: {}", recording.path);
+    Ok(recording)
+}
+
+/// Asks ffmpeg to stop (`q` on stdin) so it finalizes the WAV header.
+fn finish_capture(recording: &mut Recording) -> Result<(), String> {
+    ffmpeg::stop_capture(&mut recording.child, STOP_TIMEOUT);
+    let bytes = fs::metadata(&recording.path).map_or(0, |meta| meta.len());
+    if bytes <= WAV_HEADER_BYTES {
+        let _ = fs::remove_file(&recording.path);
+        return Err("The recording is empty; check the input device.".to_string());
+    }
+    Ok(())
+}
+
+/// An audio track with nothing between `start_us` and `end_us`: the
+/// requested one, else the first free audio track, else a new one.
+fn voiceover_track(
+    timeline: &mut Timeline,
+    wanted: Option<&str>,
+    start_us: u64,
+    end_us: u64,
+) -> Result<String, String> {
+    let is_free = |track_id: &str| {
+        !timeline.clips.iter().any(|clip| {
+            clip.track_id == track_id && clip.start_us < end_us && start_us < clip.end_us
+        })
+    };
+    if let Some(track_id) = wanted {
+        let track = timeline
+            .tracks
+            .iter()
+            .find(|track| track.id == track_id)
+            .ok_or_else(|| format!("Track {track_id} not found."))?;
+        if track.kind != "audio" {
+            return Err(format!("Track {track_id} is not an audio track."));
+        }
+        if track.locked {
+            return Err(format!("Track {track_id} is locked."));
+        }
+        if !is_free(track_id) {
+            return Err(format!(
+                "Track {track_id} already has audio at the playhead."
+            ));
+        }
+        return Ok(track_id.to_string());
+    }
+    if let Some(track) = timeline
+        .tracks
+        .iter()
+        .find(|track| track.kind == "audio" && !track.locked && is_free(&track.id))
+    {
+        return Ok(track.id.clone());
+    }
+    let count = timeline
+        .tracks
+        .iter()
+        .filter(|track| track.id.starts_with(VOICEOVER_TRACK_ID))
+        .count();
+    let (id, name) = match count {
+        0 => (VOICEOVER_TRACK_ID.to_string(), "Voiceover".to_string()),
+        n => (
+            format!("{VOICEOVER_TRACK_ID}-{}", n + 1),
+            format!("Voiceover {}", n + 1),
+        ),
+    };
+    timeline.tracks.push(TimelineTrack {
+        id: id.clone(),
+        name,
+        kind: "audio".to_string(),
+        order: timeline.tracks.len() as u32,
+        locked: false,
+    });
+    Ok(id)
+}
+
+/// Places the take at `at_us`, cut at the end of the timeline.
+fn insert_take(
+    project_id: &str,
+    source_ref: &str,
+    duration_us: u64,
+    at_us: u64,
+    track_id: Option<&str>,
+) -> Result<(String, u32), String> {
+    let _save = timeline_merge::lock_saves();
+    let mut timeline = read_timeline(project_id)?;
+    if at_us >= timeline.duration_us {
+        return Err(format!(
+            "The playhead ({at_us}us) is past the end of the timeline."
+        ));
+    }
+    let end_us = at_us.saturating_add(duration_us).min(timeline.duration_us);
+    let track_id = voiceover_track(&mut timeline, track_id, at_us, end_us)?;
+    let clip_id = format!("clip-voiceover-{}", unix_millis());
+    timeline.clips.push(TimelineClip {
+        clip_id: clip_id.clone(),
+        track_id,
+        clip_type: VOICEOVER_CLIP_TYPE.to_string(),
+        start_us: at_us,
+        end_us,
+        source_start_us: 0,
+        source_end_us: end_us - at_us,
+        speed: 1.0,
+        speed_keyframes: Vec::new(),
+        locked: false,
+        protected_from_ai: false,
+        source_ref: source_ref.to_string(),
+        effects: serde_json::json!({}),
+        transform: serde_json::json!({}),
+        meta: serde_json::json!({ "kind": "audio", "generatedBy": "voiceover" }),
+    });
+    timeline.version = timeline.version.saturating_add(1);
+    timeline.updated_at = now_iso();
+    write_timeline(&timeline)?;
+    Ok((clip_id, timeline.version))
+}
+
+async fn stop(request: StopVoiceoverRequest) -> Result<VoiceoverResult, String> {
+    let mut recording = recordings()
+        .remove(&request.project_id)
+        .ok_or_else(|| "No voiceover is recording for this project.".to_string())?;
+    let (path, insert_at_us, track_id) = (
+        recording.path.clone(),
+        recording.insert_at_us,
+        recording.track_id.clone(),
+    );
+    tracing::info!(
+        "Stopping voiceover recording started {}",
+        recording.started_at
+    );
+    tauri::async_runtime::spawn_blocking(move || finish_capture(&mut recording))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))??;
+
+    if request.discard.unwrap_or(false) {
+        let _ = fs::remove_file(&path);
+        return Ok(VoiceoverResult {
+            path: path.to_string_lossy().to_string(),
+            duration_us: 0,
+            ingest: None,
+            clip_id: None,
+            timeline_version: None,
+        });
+    }
+
+    let ingest = run_ingest_media(MediaIngestRequest {
+        project_id: request.project_id.clone(),
+        input: path.to_string_lossy().to_string(),
+        generate_proxy: Some(false),
+        generate_waveform: Some(true),
+        priority: Some(scheduler::Priority::Interactive),
+    })
+    .await?;
+    let source_path = ingest
+        .get("sourcePath")
+        .and_then(Value::as_str)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| path.clone());
+    // The media pool keeps its own link or copy of the take.
+    if source_path != path {
+        let _ = fs::remove_file(&path);
+    }
+    let duration_us = ingest
+        .pointer("/media/durationSec")
+        .and_then(Value::as_f64)
+        .map_or(0, |seconds| (seconds * 1_000_000.0).round() as u64);
+
+    let placed = match insert_at_us.filter(|_| duration_us > 0) {
+        Some(at_us) => {
+            let project_id = request.project_id.clone();
+            let source_ref = source_path.to_string_lossy().to_string();
+            let placed = tauri::async_runtime::spawn_blocking(move || {
+                insert_take(
+                    &project_id,
+                    &source_ref,
+                    duration_us,
+                    at_us,
+                    track_id.as_deref(),
+                )
+            })
+            .await
+            .map_err(|error| format!("Task join error: {error}"))??;
+            Some(placed)
+        }
+        None => None,
+    };
+    Ok(VoiceoverResult {
+        path: source_path.to_string_lossy().to_string(),
+        duration_us,
+        ingest: Some(ingest),
+        clip_id: placed.as_ref().map(|(clip_id, _)| clip_id.clone()),
+        timeline_version: placed.map(|(_, version)| version),
+    })
+}
+
+/// Starts capturing the input device to a WAV in the project's media
+/// folder, through ffmpeg's platform capture (AVFoundation, DirectShow or
+/// PulseAudio).
+#[tauri::command]
+pub async fn start_voiceover_recording(
+    request: StartVoiceoverRequest,
+) -> Result<VoiceoverRecording, String> {
+    tauri::async_runtime::spawn_blocking(move || start_blocking(request))
+        .await
+        .map_err(|error| format!("Task join error: {error}"))?
+}
+
+/// Stops the capture, ingests the take (into the media pool when enabled)
+/// and places it on an audio track when recording began at a playhead.
+#[tauri::command]
+pub async fn stop_voiceover_recording(
+    request: StopVoiceoverRequest,
+) -> Result<VoiceoverResult, String> {
+    stop(request).await
+}
@@ -0,0 +1,436 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::AppHandle;
+
+use crate::ffmpeg::ffmpeg_binary;
+use crate::media::resolve_source_path;
+use crate::model_downloads::models_dir;
+use crate::scheduler::{self, JobKind, Priority};
+use crate::transcript::{self, Transcript, TranscriptSegment, TranscriptWord};
+use crate::{model_registry, now_iso, path_safety, windows, workspace_root};
+
+pub const PROGRESS_EVENT: &str = "transcription://progress";
+
+/// Names of the whisper.cpp CLI across releases (`main` was renamed).
+const BINARY_NAMES: &[&str] = &["whisper-cli", "whisper-cpp"];
+
+/// Tried in order when no model is requested.
+const AUTO_MODELS: &[&str] = &["large-v3-turbo", "large-v3", "medium", "small", "base"];
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscribeMediaRequest {
+    project_id: String,
+    /// Source ref of the media to transcribe; defaults to the ingested source.
+    asset_id: Option<String>,
+    /// `small`, `large-v3-turbo`, ... or a path to a ggml `.bin`; `auto` picks
+    /// the largest one installed.
+    model: Option<String>,
+    /// ISO code, or `auto` to let whisper detect it.
+    language: Option<String>,
+    priority: Option<Priority>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum TranscriptionStage {
+    ExtractingAudio,
+    Transcribing,
+    Saving,
+    Done,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TranscriptionProgressEvent {
+    project_id: String,
+    asset_id: String,
+    stage: TranscriptionStage,
+    percent: u32,
+}
+
+fn executable(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{name}.exe")
+    } else {
+        name.to_string()
+    }
+}
+
+/// `WHISPER_CPP_BIN`, then a binary bundled next to the app, then the tools
+/// dir, then PATH.
+fn whisper_binary() -> Result<PathBuf, String> {
+    if let Some(path) = env::var_os("WHISPER_CPP_BIN") {
+        return Ok(PathBuf::from(path));
+    }
+    let mut dirs = Vec::new();
+    if let Some(dir) = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    {
+        dirs.push(dir);
+    }
+    if let Ok(root) = workspace_root() {
+        dirs.push(
+            root.join("desktop")
+                .join("data")
+                .join("tools")
+                .join("whisper.cpp")
+                .join("bin"),
+        );
+    }
+    if let Some(path) = env::var_os("PATH") {
+        dirs.extend(env::split_paths(&path));
+    }
+    dirs.iter()
+        .flat_map(|dir| {
+            BINARY_NAMES
+                .iter()
+                .map(move |name| dir.join(executable(name)))
+        })
+        .find(|path| path.is_file())
+        .ok_or_else(|| "whisper.cpp was not found. Install it, or set WHISPER_CPP_BIN.".to_string())
+}
+
+fn model_file(name: &str) -> Result<PathBuf, String> {
+    Ok(models_dir()?
+        .join("whisper_cpp")
+        .join(format!("ggml-{name}.bin")))
+}
+
+/// Path to the ggml model and the name recorded as last used.
+fn resolve_model(requested: Option<&str>) -> Result<(PathBuf, String), String> {
+    let requested = requested.map(str::trim).filter(|model| !model.is_empty());
+    match requested {
+        None | Some("auto") => {
+            for name in AUTO_MODELS {
+                let path = model_file(name)?;
+                if path.is_file() {
+                    return Ok((path, name.to_string()));
+                }
+            }
+            Err("No whisper.cpp model is installed; install one with install_model.".to_string())
+        }
+        Some(model) if model.ends_with(".bin") => {
+            let path = PathBuf::from(model);
+            if !path.is_file() {
+                return Err(format!("Whisper model not found: {model}"));
+            }
+            Ok((path, model.to_string()))
+        }
+        Some(model) => {
+            let path = model_file(model)?;
+            if !path.is_file() {
+                return Err(format!(
+                    "Whisper model {model} is not installed; install it with install_model."
+                ));
+            }
+            Ok((path, model.to_string()))
+        }
+    }
+}
+
+fn extract_audio(input: &Path, output: &Path) -> Result<(), String> {
+    let result = Command::new(ffmpeg_binary())
+        .args(["-y", "-hide_banner", "-loglevel", "error", "-i"])
+        .arg(input)
+        .args(["-vn", "-ar", "16000", "-ac", "1", "-c:a", "pcm_s16le"])
+        .arg(output)
+        .output()
+        .map_err(|error| format!("Failed to execute ffmpeg: {error}"))?;
+    if result.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    Err(format!(
+        "Audio extraction failed: {}",
+        stderr.trim().chars().take(300).collect::<String>()
+    ))
+}
+
+/// `... progress =  45%` from whisper.cpp's `--print-progress`.
+fn parse_progress(line: &str) -> Option<u32> {
+    let (_, rest) = line.split_once("progress =")?;
+    rest.trim().trim_end_matches('%').trim().parse().ok()
+}
+
+/// Runs whisper.cpp, reporting progress from stderr, and returns the path of
+/// the full JSON output.
+fn run_whisper(
+    binary: &Path,
+    model: &Path,
+    wav: &Path,
+    language: &str,
+    on_progress: &mut dyn FnMut(u32),
+) -> Result<PathBuf, String> {
+    let output_base = wav.with_extension("");
+    let threads = std::thread::available_parallelism()
+        .map(|cores| cores.get().min(8))
+        .unwrap_or(4);
+    let mut child = Command::new(binary)
+        .arg("-m")
+        .arg(model)
+        .arg("-f")
+        .arg(wav)
+        .args(["-l", language, "-t", &threads.to_string()])
+        .args(["--output-json-full", "--print-progress", "-of"])
+        .arg(&output_base)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("Failed to execute whisper.cpp: {error}"))?;
+
+    let mut tail = Vec::new();
+    if let Some(stderr) = child.stderr.take() {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            match parse_progress(&line) {
+                Some(percent) => on_progress(percent),
+                None => {
+                    tail.push(line);
+                    if tail.len() > 5 {
+                        tail.remove(0);
+                    }
+                }
+            }
+        }
+    }
+    let status = child
+        .wait()
+        .map_err(|error| format!("Failed waiting for whisper.cpp: {error}"))?;
+    if !status.success() {
+        return Err(format!(
+            "whisper.cpp failed: {}",
+            tail.join(" ").chars().take(300).collect::<String>()
+        ));
+    }
+    Ok(output_base.with_extension("json"))
+}
+
+fn offset_us(node: &Value, key: &str) -> u64 {
+    node.get("offsets")
+        .and_then(|offsets| offsets.get(key))
+        .and_then(Value::as_u64)
+        .unwrap_or(0)
+        * 1000
+}
+
+/// Joins whisper tokens into words: a token starting with a space opens a new
+/// word. Special tokens (`[_BEG_]`, `[_TT_150]`) are skipped.
+fn segment_words(segment_index: usize, tokens: &[Value]) -> Vec<TranscriptWord> {
+    let mut words: Vec<(String, u64, u64, Vec<f64>)> = Vec::new();
+    for token in tokens {
+        let text = token
+            .get("text")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if text.trim().is_empty() || text.starts_with("[_") {
+            continue;
+        }
+        let start = offset_us(token, "from");
+        let end = offset_us(token, "to");
+        let probability = token.get("p").and_then(Value::as_f64);
+        match words.last_mut() {
+            Some(word) if !text.starts_with(' ') => {
+                word.0.push_str(text);
+                word.2 = end.max(word.2);
+                word.3.extend(probability);
+            }
+            _ => words.push((
+                text.trim_start().to_string(),
+                start,
+                end,
+                probability.into_iter().collect(),
+            )),
+        }
+    }
+    words
+        .into_iter()
+        .enumerate()
+        .map(
+            |(index, (text, start_us, end_us, probabilities))| TranscriptWord {
+                id: format!("word-{segment_index}-{index}"),
+                normalized: transcript::normalize_word(&text),
+                text,
+                start_us,
+                end_us: end_us.max(start_us),
+                confidence: (!probabilities.is_empty())
+                    .then(|| probabilities.iter().sum::<f64>() / probabilities.len() as f64),
+                speaker: None,
+                extra: Map::new(),
+            },
+        )
+        .collect()
+}
+
+fn parse_whisper_json(raw: &str) -> Result<(Vec<TranscriptSegment>, Option<String>), String> {
+    let output = serde_json::from_str::<Value>(raw)
+        .map_err(|error| format!("Invalid whisper.cpp JSON: {error}"))?;
+    let language = output
+        .get("result")
+        .and_then(|result| result.get("language"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let segments = output
+        .get("transcription")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, segment)| {
+            let text = segment.get("text")?.as_str()?.trim().to_string();
+            if text.is_empty() {
+                return None;
+            }
+            let words = segment
+                .get("tokens")
+                .and_then(Value::as_array)
+                .map(|tokens| segment_words(index, tokens))
+                .unwrap_or_default();
+            let scored = words
+                .iter()
+                .filter_map(|word| word.confidence)
+                .collect::<Vec<_>>();
+            let start_us = offset_us(segment, "from");
+            Some(TranscriptSegment {
+                id: format!("seg-{index}"),
+                start_us,
+                end_us: offset_us(segment, "to").max(start_us),
+                text,
+                confidence: (!scored.is_empty())
+                    .then(|| scored.iter().sum::<f64>() / scored.len() as f64),
+                speaker: None,
+                words,
+                extra: Map::new(),
+            })
+        })
+        .collect();
+    Ok((segments, language))
+}
+
+fn transcribe_blocking(
+    app: AppHandle,
+    request: TranscribeMediaRequest,
+) -> Result<Transcript, String> {
+    let asset_id = request
+        .asset_id
+        .map(|asset_id| asset_id.trim().to_string())
+        .filter(|asset_id| !asset_id.is_empty())
+        .unwrap_or_else(|| "source-video".to_string());
+    let emit = |stage: TranscriptionStage, percent: u32| {
+        windows::emit_for_project(
+            &app,
+            &request.project_id,
+            PROGRESS_EVENT,
+            TranscriptionProgressEvent {
+                project_id: request.project_id.clone(),
+                asset_id: asset_id.clone(),
+                stage,
+                percent,
+            },
+        );
+    };
+
+    let binary = whisper_binary()?;
+    let (model_path, model_name) = resolve_model(request.model.as_deref())?;
+    let input = resolve_source_path(&request.project_id, Some(&asset_id))?;
+    let language = request
+        .language
+        .map(|language| language.trim().to_string())
+        .filter(|language| !language.is_empty())
+        .unwrap_or_else(|| "auto".to_string());
+
+    let work_dir = path_safety::project_dir(&request.project_id)?.join("media");
+    fs::create_dir_all(&work_dir).map_err(|error| format!("Failed creating media dir: {error}"))?;
+    let wav = work_dir.join("whisper-16k.wav");
+
+    emit(TranscriptionStage::ExtractingAudio, 0);
+    tracing::info!(
+        "Transcribing {} with whisper.cpp model {model_name}",
+        input.display()
+    );
+    extract_audio(&input, &wav)?;
+    emit(TranscriptionStage::Transcribing, 0);
+    let result = run_whisper(&binary, &model_path, &wav, &language, &mut |percent| {
+        emit(TranscriptionStage::Transcribing, percent)
+    });
+    let _ = fs::remove_file(&wav);
+    let json_path = result?;
+    let raw = fs::read_to_string(&json_path)
+        .map_err(|error| format!("Failed reading whisper.cpp output: {error}"));
+    let _ = fs::remove_file(&json_path);
+    let (segments, detected_language) = parse_whisper_json(&raw?)?;
+
+    emit(TranscriptionStage::Saving, 100);
+    let duration_us = segments
+        .iter()
+        .map(|segment| segment.end_us)
+        .max()
+        .unwrap_or(0);
+    // The pipeline scripts read the source and adapter of a stored transcript.
+    let extra = json!({
+        "transcriptId": format!("tx-{}", now_iso().replace([':', '.'], "-")),
+        "createdAt": now_iso(),
+        "mode": "local",
+        "source": {
+            "path": input.to_string_lossy(),
+            "ref": asset_id,
+            "durationUs": duration_us,
+        },
+        "adapter": {
+            "kind": "local",
+            "runtime": "whisper.cpp",
+            "binary": binary.to_string_lossy(),
+            "model": model_name,
+            "engine": "local:whisper.cpp:v1",
+        },
+    });
+    let transcript = transcript::write_transcript(
+        &request.project_id,
+        Transcript {
+            project_id: request.project_id.clone(),
+            language: detected_language.or(Some(language).filter(|language| language != "auto")),
+            source_ref: Some(asset_id.clone()),
+            segments,
+            speaker_labels: BTreeMap::new(),
+            word_count: 0,
+            updated_at: String::new(),
+            extra: extra.as_object().cloned().unwrap_or_default(),
+        },
+    )?;
+    if let Err(error) = model_registry::touch_models(&[model_name]) {
+        tracing::warn!("Failed updating model last-used time: {error}");
+    }
+    emit(TranscriptionStage::Done, 100);
+    Ok(transcript)
+}
+
+/// Transcribes project media with a local whisper.cpp binary and stores the
+/// result as the project transcript. Progress arrives as
+/// `transcription://progress` events.
+#[tauri::command]
+pub async fn transcribe_media(
+    app: AppHandle,
+    request: TranscribeMediaRequest,
+) -> Result<Transcript, String> {
+    let permit = scheduler::acquire(
+        &app,
+        JobKind::Transcription,
+        request.priority,
+        &request.project_id,
+    )
+    .await?;
+    let span = permit.span();
+    tauri::async_runtime::spawn_blocking(move || {
+        span.in_scope(|| transcribe_blocking(app, request))
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))?
+}
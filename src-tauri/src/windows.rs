@@ -0,0 +1,243 @@
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder, Window};
+
+use crate::{control_api, path_safety, read_projects};
+
+pub const MAIN_WINDOW: &str = "main";
+/// Tells the main window to switch to a project.
+pub const OPEN_PROJECT_EVENT: &str = "app://open-project";
+const PROJECT_WINDOW_PREFIX: &str = "project-";
+const WINDOW_WIDTH: f64 = 1280.0;
+const WINDOW_HEIGHT: f64 = 820.0;
+
+/// Which project each window has open, keyed by window label. The main
+/// window reports its project through `set_active_project`; project
+/// windows are registered when `open_project_window` creates them.
+#[derive(Default)]
+pub struct ProjectWindows(Mutex<BTreeMap<String, String>>);
+
+impl ProjectWindows {
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, String>> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn project_for(&self, label: &str) -> Option<String> {
+        self.lock().get(label).cloned()
+    }
+
+    fn labels_for(&self, project_id: &str) -> Vec<String> {
+        self.lock()
+            .iter()
+            .filter(|(_, owned)| *owned == project_id)
+            .map(|(label, _)| label.clone())
+            .collect()
+    }
+
+    /// A window that has `project_id` open, if any.
+    pub fn window_for(&self, project_id: &str) -> Option<String> {
+        self.labels_for(project_id).into_iter().next()
+    }
+
+    fn set(&self, label: &str, project_id: Option<String>) {
+        let mut windows = self.lock();
+        match project_id {
+            Some(project_id) => windows.insert(label.to_string(), project_id),
+            None => windows.remove(label),
+        };
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetActiveProjectRequest {
+    /// None when no project is open; drops are then rejected.
+    project_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenProjectWindowRequest {
+    project_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectWindow {
+    pub label: String,
+    pub project_id: String,
+}
+
+/// Sends a project's event only to the windows that have it open, so two
+/// open projects don't see each other's progress. Falls back to every
+/// window when none has claimed the project yet. Control API event
+/// streams get every project's events.
+pub fn emit_for_project<S: Serialize + Clone>(
+    app: &AppHandle,
+    project_id: &str,
+    event: &str,
+    payload: S,
+) {
+    control_api::publish(event, &payload);
+    let labels = app.state::<ProjectWindows>().labels_for(project_id);
+    if labels.is_empty() {
+        let _ = app.emit(event, payload);
+        return;
+    }
+    for label in labels {
+        let _ = app.emit_to(label.as_str(), event, payload.clone());
+    }
+}
+
+/// Drops a closed window's claim on its project.
+pub fn forget_window(app: &AppHandle, label: &str) {
+    app.state::<ProjectWindows>().set(label, None);
+}
+
+/// Brings the window that has `project_id` open to the front. False when
+/// no window has it.
+pub fn focus_project_window(app: &AppHandle, project_id: &str) -> bool {
+    let Some(window) = app
+        .state::<ProjectWindows>()
+        .labels_for(project_id)
+        .iter()
+        .find_map(|label| app.get_webview_window(label))
+    else {
+        return false;
+    };
+    let _ = window.show();
+    let _ = window.unminimize();
+    if let Err(error) = window.set_focus() {
+        tracing::warn!("Failed focusing window {}: {error}", window.label());
+    }
+    true
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Hides every window; the tray icon keeps the app reachable.
+pub fn hide_all(app: &AppHandle) {
+    for window in app.webview_windows().values() {
+        let _ = window.hide();
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OpenProjectEvent {
+    project_id: String,
+}
+
+/// Brings `project_id` up: the window that has it open if there is one,
+/// otherwise the main window switches to it.
+pub fn open_in_ui(app: &AppHandle, project_id: &str) {
+    if focus_project_window(app, project_id) {
+        return;
+    }
+    show_main_window(app);
+    let _ = app.emit_to(
+        MAIN_WINDOW,
+        OPEN_PROJECT_EVENT,
+        OpenProjectEvent {
+            project_id: project_id.to_string(),
+        },
+    );
+}
+
+/// Whether `label` is the only window left, i.e. closing it ends the app.
+pub fn is_last_window(app: &AppHandle, label: &str) -> bool {
+    app.webview_windows().keys().all(|open| open == label)
+}
+
+pub fn project_name(project_id: &str) -> Result<String, String> {
+    read_projects()?
+        .into_iter()
+        .find(|project| project.id == project_id)
+        .map(|project| project.name)
+        .ok_or_else(|| format!("Project not found: {project_id}"))
+}
+
+/// Opens `project_id` in a window of its own, or focuses the window that
+/// already has it open. Returns the window's label.
+#[tauri::command]
+pub async fn open_project_window(
+    app: AppHandle,
+    windows: State<'_, ProjectWindows>,
+    request: OpenProjectWindowRequest,
+) -> Result<String, String> {
+    let project_id = request.project_id;
+    path_safety::validate_project_id(&project_id)?;
+    let name = tauri::async_runtime::spawn_blocking({
+        let project_id = project_id.clone();
+        move || project_name(&project_id)
+    })
+    .await
+    .map_err(|error| format!("Task join error: {error}"))??;
+
+    if focus_project_window(&app, &project_id) {
+        if let Some(label) = windows.window_for(&project_id) {
+            return Ok(label);
+        }
+    }
+
+    let label = format!("{PROJECT_WINDOW_PREFIX}{project_id}");
+    windows.set(&label, Some(project_id.clone()));
+    let url = WebviewUrl::App(format!("index.html?projectId={project_id}").into());
+    let built = WebviewWindowBuilder::new(&app, label.clone(), url)
+        .title(format!("{name} — Lapaas AI Editor"))
+        .inner_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+        .build();
+    if let Err(error) = built {
+        windows.set(&label, None);
+        return Err(format!("Failed opening project window: {error}"));
+    }
+    tracing::info!("Opened window {label} for project {project_id}");
+    Ok(label)
+}
+
+/// Records which project the calling window has open.
+#[tauri::command]
+pub async fn set_active_project(
+    window: Window,
+    windows: State<'_, ProjectWindows>,
+    request: SetActiveProjectRequest,
+) -> Result<(), String> {
+    if let Some(project_id) = &request.project_id {
+        let exists = tauri::async_runtime::spawn_blocking({
+            let project_id = project_id.clone();
+            move || {
+                read_projects()
+                    .map(|projects| projects.iter().any(|project| project.id == project_id))
+            }
+        })
+        .await
+        .map_err(|error| format!("Task join error: {error}"))??;
+        if !exists {
+            return Err(format!("Project not found: {project_id}"));
+        }
+    }
+    windows.set(window.label(), request.project_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_project_windows(windows: State<'_, ProjectWindows>) -> Vec<ProjectWindow> {
+    windows
+        .lock()
+        .iter()
+        .map(|(label, project_id)| ProjectWindow {
+            label: label.clone(),
+            project_id: project_id.clone(),
+        })
+        .collect()
+}
@@ -568,7 +568,7 @@ export const TimelinePanel: React.FC = () => {
                                     const width = clip.duration * pxPerSec;
                                     const isSelected = selectedClipId === clip.id;
                                     const baseColor = AI_TRACK_COLORS[track.id] || TRACK_COLORS[track.type] || '#666';
-                                    const isLocked = !!track.isLocked;
+                                    const isLocked = !!track.isLocked || !!clip.locked;
 
                                     const clipEndSec = clip.start + clip.duration;
                                     const tooltipText = `${clip.name || 'Clip'}\n${formatTime(clip.start)} → ${formatTime(clipEndSec)} (${clip.duration.toFixed(1)}s)${isLocked ? '\n(locked)' : ''}`;
@@ -1,5 +1,6 @@
 
 import React, { createContext, useContext, useState, useEffect, useCallback } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import { useTauri } from '../hooks/useTauri';
 import { logger } from '../utils/logger';
 import { getTemplateById } from '../templates/registry';
@@ -50,6 +51,9 @@ export interface Clip {
     sourceRef?: string;
     templateId?: string;
     content?: { headline?: string; subline?: string;[key: string]: any };
+    // Locked clips can't be split, trimmed or rippled; protected ones are kept out of AI cuts
+    locked?: boolean;
+    protectedFromAi?: boolean;
 }
 
 export interface Track {
@@ -300,7 +304,11 @@ export const EditorProvider: React.FC<{ children: React.ReactNode }> = ({ childr
                 }
             }
             if (projects.length > 0) {
-                setCurrentProject(normalizeProject(projects[projects.length - 1]));
+                // Project windows are opened with ?projectId=; the main window
+                // falls back to the most recent project.
+                const windowProjectId = new URLSearchParams(window.location.search).get('projectId');
+                const owned = windowProjectId ? projects.find((p: any) => p.id === windowProjectId) : undefined;
+                setCurrentProject(normalizeProject(owned ?? projects[projects.length - 1]));
             }
         } catch (err) {
             const msg = err instanceof Error ? err.message : String(err);
@@ -753,6 +761,8 @@ export const EditorProvider: React.FC<{ children: React.ReactNode }> = ({ childr
                                     offset: Number(c.sourceStartUs || c.sourceOffsetUs || 0) / 1_000_000,
                                     type: 'video' as const,
                                     name: c.label || c.clipId || `Clip ${idx + 1}`,
+                                    locked: Boolean(c.locked),
+                                    protectedFromAi: Boolean(c.protectedFromAi),
                                 }));
                             return { ...track, clips: videoClips };
                         }
@@ -1828,6 +1838,7 @@ export const EditorProvider: React.FC<{ children: React.ReactNode }> = ({ childr
             if (clipIndex === -1) return track;
 
             const clip = track.clips[clipIndex];
+            if (clip.locked) return track;
             const clipEnd = clip.start + clip.duration;
 
             // splitTime must be within clip range (with small margin)
@@ -1866,7 +1877,7 @@ export const EditorProvider: React.FC<{ children: React.ReactNode }> = ({ childr
             return {
                 ...track,
                 clips: track.clips.map(clip => {
-                    if (clip.id !== clipId) return clip;
+                    if (clip.id !== clipId || clip.locked) return clip;
 
                     if (side === 'start') {
                         const oldStart = clip.start;
@@ -1899,7 +1910,9 @@ export const EditorProvider: React.FC<{ children: React.ReactNode }> = ({ childr
             if (track.id !== trackId) return track;
 
             const clip = track.clips.find(c => c.id === clipId);
-            if (!clip) return track;
+            if (!clip || clip.locked) return track;
+            // Rippling would move a locked clip
+            if (ripple && track.clips.some(c => c.locked && c.start > clip.start)) return track;
 
             let newClips = track.clips.filter(c => c.id !== clipId);
 
@@ -1971,6 +1984,37 @@ export const EditorProvider: React.FC<{ children: React.ReactNode }> = ({ childr
         return () => clearInterval(interval);
     }, []);
 
+    // ── Native file drop ──────────────────────────────────────────────────────
+    // Files dropped on the window are ingested natively into the active project.
+
+    useEffect(() => {
+        if (!isTauri) return;
+        invokeCommand('set_active_project', { request: { projectId: currentProject?.id ?? null } })
+            .catch(() => { /* logged by invokeCommand */ });
+    }, [isTauri, invokeCommand, currentProject?.id]);
+
+    // ── Open project requests ─────────────────────────────────────────────────
+    // Clicking a job notification or opening a project bundle asks the main
+    // window to switch to that project.
+
+    useEffect(() => {
+        if (!isTauri) return;
+        let unlisten: (() => void) | undefined;
+        listen<{ projectId: string }>('app://open-project', async ({ payload }) => {
+            try {
+                const projects: any[] = await invokeCommand('list_projects', {});
+                const project = projects.find((p: any) => p.id === payload.projectId);
+                if (project) setCurrentProject(normalizeProject(project));
+            } catch { /* logged by invokeCommand */ }
+        }).then(fn => {
+            unlisten = fn;
+            // Now that open requests are heard, route the files and links
+            // the app was launched with.
+            return invokeCommand('launch_ready', {});
+        }).catch(() => { /* logged by invokeCommand */ });
+        return () => unlisten?.();
+    }, [isTauri, invokeCommand]);
+
     // ── Initial load ──────────────────────────────────────────────────────────
 
     useEffect(() => {
@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
 import { useCallback } from 'react';
+import { decode, encode } from '../utils/msgpack';
 
 // Helper to check if running in Tauri
 const isTauri = () => !!(window as any).__TAURI__;
@@ -13,9 +14,97 @@ export type TauriCommand =
     | 'edit_now'
     | 'render'
     | 'model_health'
+    | 'system_health'
+    | 'get_onboarding_state'
+    | 'complete_onboarding_step'
+    | 'create_sample_project'
+    | 'set_active_project'
+    | 'pick_media_files'
+    | 'pick_output_directory'
+    | 'validate_project_settings'
+    | 'open_project_window'
+    | 'list_project_windows'
+    | 'set_queue_paused'
+    | 'take_interrupted_jobs'
+    | 'export_project_bundle'
+    | 'import_project_bundle'
+    | 'launch_ready'
+    | 'control_api_status'
+    | 'reveal_control_api_token'
+    | 'list_plugins'
+    | 'get_event_journal'
+    | 'get_project_status_history'
+    | 'merge_timeline'
+    | 'get_timeline_window'
+    | 'get_timeline_packed'
+    | 'save_timeline_packed'
+    | 'run_pipeline'
+    | 'get_project_config'
+    | 'generate_captions'
+    | 'label_speakers'
+    | 'get_speaker_stats'
+    | 'plan_multicam_cut'
+    | 'extract_highlights'
+    | 'list_markers'
+    | 'save_marker'
+    | 'delete_marker'
+    | 'generate_chapters'
+    | 'get_chapters'
+    | 'detect_profanity'
+    | 'apply_censor'
+    | 'measure_loudness'
+    | 'enhance_audio'
+    | 'set_clip_audio_enhance'
+    | 'list_enhanced_audio'
+    | 'import_lut'
+    | 'list_luts'
+    | 'set_clip_lut'
+    | 'get_branding'
+    | 'set_branding'
+    | 'set_clip_flags'
+    | 'mark_sponsor_segment'
+    | 'list_sponsor_segments'
+    | 'remove_sponsor_segment'
+    | 'detect_sponsor_segments'
+    | 'get_edit_analytics'
+    | 'estimate_render'
+    | 'verify_render_output'
+    | 'build_preview'
+    | 'get_render_cache'
+    | 'clear_render_cache'
+    | 'list_render_workers'
+    | 'add_render_worker'
+    | 'remove_render_worker'
+    | 'remap_media_root'
+    | 'get_media_pool'
+    | 'prune_media_pool'
+    | 'start_voiceover_recording'
+    | 'stop_voiceover_recording'
+    | 'list_audio_devices'
+    | 'start_screen_capture'
+    | 'stop_screen_capture'
+    | 'get_preview_frame'
+    | 'player_load'
+    | 'player_seek'
+    | 'player_play'
+    | 'player_pause'
+    | 'player_stop'
+    | 'list_actions'
+    | 'run_action'
     | 'install_model'
     | 'save_project';
 
+// Timeline round-trips as MessagePack instead of JSON; desktop only.
+export const getTimelinePacked = async <T,>(projectId: string): Promise<T> => {
+    const bytes = await invoke<ArrayBuffer>('get_timeline_packed', { request: { projectId } });
+    return decode<T>(bytes);
+};
+
+export const saveTimelinePacked = async <T,>(request: Record<string, unknown>): Promise<T> => {
+    const bytes = await invoke<ArrayBuffer>('save_timeline_packed', encode(request));
+    return decode<T>(bytes);
+};
+
 export const useTauri = () => {
     const _isTauri = isTauri();
 
@@ -0,0 +1,235 @@
+// Minimal MessagePack codec for the packed timeline commands
+// (get_timeline_packed / save_timeline_packed). Covers what serde produces
+// for timelines: nil, booleans, numbers, strings, binary, arrays and maps.
+// 64-bit integers decode to plain numbers, which is exact up to 2^53 µs
+// (about 285 years of timeline).
+
+const textEncoder = new TextEncoder();
+const textDecoder = new TextDecoder();
+
+class Writer {
+    private buffer = new Uint8Array(1024);
+    private view = new DataView(this.buffer.buffer);
+    private length = 0;
+
+    private reserve(size: number) {
+        if (this.length + size <= this.buffer.length) return;
+        let capacity = this.buffer.length * 2;
+        while (capacity < this.length + size) capacity *= 2;
+        const next = new Uint8Array(capacity);
+        next.set(this.buffer.subarray(0, this.length));
+        this.buffer = next;
+        this.view = new DataView(next.buffer);
+    }
+
+    u8(value: number) {
+        this.reserve(1);
+        this.view.setUint8(this.length, value);
+        this.length += 1;
+    }
+
+    u16(value: number) {
+        this.reserve(2);
+        this.view.setUint16(this.length, value);
+        this.length += 2;
+    }
+
+    u32(value: number) {
+        this.reserve(4);
+        this.view.setUint32(this.length, value);
+        this.length += 4;
+    }
+
+    f64(value: number) {
+        this.reserve(8);
+        this.view.setFloat64(this.length, value);
+        this.length += 8;
+    }
+
+    bytes(value: Uint8Array) {
+        this.reserve(value.length);
+        this.buffer.set(value, this.length);
+        this.length += value.length;
+    }
+
+    finish(): Uint8Array {
+        return this.buffer.slice(0, this.length);
+    }
+}
+
+const writeLength = (writer: Writer, length: number, fix: number | null, tags: [number, number, number]) => {
+    if (fix !== null && length < 16) {
+        writer.u8(fix | length);
+    } else if (length < 0x100 && tags[0] !== 0) {
+        writer.u8(tags[0]);
+        writer.u8(length);
+    } else if (length < 0x10000) {
+        writer.u8(tags[1]);
+        writer.u16(length);
+    } else {
+        writer.u8(tags[2]);
+        writer.u32(length);
+    }
+};
+
+const writeInteger = (writer: Writer, value: number) => {
+    if (value >= 0 && value < 0x80) {
+        writer.u8(value);
+    } else if (value >= 0 && value < 0x100) {
+        writer.u8(0xcc);
+        writer.u8(value);
+    } else if (value >= 0 && value < 0x10000) {
+        writer.u8(0xcd);
+        writer.u16(value);
+    } else if (value >= 0 && value < 0x100000000) {
+        writer.u8(0xce);
+        writer.u32(value);
+    } else if (value >= -32 && value < 0) {
+        writer.u8(value & 0xff);
+    } else if (value >= -0x80000000 && value < 0) {
+        writer.u8(0xd2);
+        writer.u32(value >>> 0);
+    } else {
+        // 64-bit, as two's complement across the two 32-bit halves.
+        const high = Math.floor(value / 0x100000000);
+        writer.u8(value >= 0 ? 0xcf : 0xd3);
+        writer.u32(high >>> 0);
+        writer.u32((value - high * 0x100000000) >>> 0);
+    }
+};
+
+const writeValue = (writer: Writer, value: unknown): void => {
+    if (value === null || value === undefined) {
+        writer.u8(0xc0);
+    } else if (typeof value === 'boolean') {
+        writer.u8(value ? 0xc3 : 0xc2);
+    } else if (typeof value === 'number') {
+        if (Number.isSafeInteger(value)) {
+            writeInteger(writer, value);
+        } else {
+            writer.u8(0xcb);
+            writer.f64(value);
+        }
+    } else if (typeof value === 'string') {
+        const encoded = textEncoder.encode(value);
+        if (encoded.length < 32) {
+            writer.u8(0xa0 | encoded.length);
+        } else {
+            writeLength(writer, encoded.length, null, [0xd9, 0xda, 0xdb]);
+        }
+        writer.bytes(encoded);
+    } else if (value instanceof Uint8Array) {
+        writeLength(writer, value.length, null, [0xc4, 0xc5, 0xc6]);
+        writer.bytes(value);
+    } else if (Array.isArray(value)) {
+        writeLength(writer, value.length, 0x90, [0, 0xdc, 0xdd]);
+        value.forEach(item => writeValue(writer, item));
+    } else if (typeof value === 'object') {
+        // Like JSON.stringify, fields set to undefined are left out.
+        const entries = Object.keys(value as object)
+            .map(key => [key, (value as Record<string, unknown>)[key]] as const)
+            .filter(([, item]) => item !== undefined);
+        writeLength(writer, entries.length, 0x80, [0, 0xde, 0xdf]);
+        entries.forEach(([key, item]) => {
+            writeValue(writer, key);
+            writeValue(writer, item);
+        });
+    } else {
+        throw new Error(`Cannot encode ${typeof value} as MessagePack`);
+    }
+};
+
+export const encode = (value: unknown): Uint8Array => {
+    const writer = new Writer();
+    writeValue(writer, value);
+    return writer.finish();
+};
+
+class Reader {
+    private offset = 0;
+    private view: DataView;
+
+    constructor(private bytes: Uint8Array) {
+        this.view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+    }
+
+    private advance(size: number): number {
+        const at = this.offset;
+        if (at + size > this.bytes.length) throw new Error('Truncated MessagePack data');
+        this.offset += size;
+        return at;
+    }
+
+    u8() { return this.view.getUint8(this.advance(1)); }
+    i8() { return this.view.getInt8(this.advance(1)); }
+    u16() { return this.view.getUint16(this.advance(2)); }
+    i16() { return this.view.getInt16(this.advance(2)); }
+    u32() { return this.view.getUint32(this.advance(4)); }
+    i32() { return this.view.getInt32(this.advance(4)); }
+    f32() { return this.view.getFloat32(this.advance(4)); }
+    f64() { return this.view.getFloat64(this.advance(8)); }
+    u64() { return this.u32() * 0x100000000 + this.u32(); }
+    i64() { return this.i32() * 0x100000000 + this.u32(); }
+
+    raw(length: number): Uint8Array {
+        const at = this.advance(length);
+        return this.bytes.subarray(at, at + length);
+    }
+
+    str(length: number) { return textDecoder.decode(this.raw(length)); }
+
+    array(length: number): unknown[] {
+        const items = new Array(length);
+        for (let i = 0; i < length; i++) items[i] = this.value();
+        return items;
+    }
+
+    map(length: number): Record<string, unknown> {
+        const object: Record<string, unknown> = {};
+        for (let i = 0; i < length; i++) {
+            const key = String(this.value());
+            object[key] = this.value();
+        }
+        return object;
+    }
+
+    value(): unknown {
+        const tag = this.u8();
+        if (tag < 0x80) return tag;
+        if (tag < 0x90) return this.map(tag & 0x0f);
+        if (tag < 0xa0) return this.array(tag & 0x0f);
+        if (tag < 0xc0) return this.str(tag & 0x1f);
+        if (tag >= 0xe0) return tag - 0x100;
+        switch (tag) {
+            case 0xc0: return null;
+            case 0xc2: return false;
+            case 0xc3: return true;
+            case 0xc4: return this.raw(this.u8()).slice();
+            case 0xc5: return this.raw(this.u16()).slice();
+            case 0xc6: return this.raw(this.u32()).slice();
+            case 0xca: return this.f32();
+            case 0xcb: return this.f64();
+            case 0xcc: return this.u8();
+            case 0xcd: return this.u16();
+            case 0xce: return this.u32();
+            case 0xcf: return this.u64();
+            case 0xd0: return this.i8();
+            case 0xd1: return this.i16();
+            case 0xd2: return this.i32();
+            case 0xd3: return this.i64();
+            case 0xd9: return this.str(this.u8());
+            case 0xda: return this.str(this.u16());
+            case 0xdb: return this.str(this.u32());
+            case 0xdc: return this.array(this.u16());
+            case 0xdd: return this.array(this.u32());
+            case 0xde: return this.map(this.u16());
+            case 0xdf: return this.map(this.u32());
+            default: throw new Error(`Unsupported MessagePack tag 0x${tag.toString(16)}`);
+        }
+    }
+}
+
+export const decode = <T = unknown>(data: ArrayBuffer | Uint8Array): T => {
+    const bytes = data instanceof Uint8Array ? data : new Uint8Array(data);
+    return new Reader(bytes).value() as T;
+};